@@ -0,0 +1,56 @@
+use std::env;
+use std::process::Command;
+
+/**
+ * Record what the binary was built from, shown by `--version` and the About screen of the TUI.
+ */
+fn main() {
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/index");
+
+    println!("cargo:rustc-env=PDFCUTTER_GIT_HASH={}", git_hash());
+
+    // Cargo exposes each enabled feature as CARGO_FEATURE_<NAME>
+    let mut features: Vec<String> = env::vars()
+        .filter_map(|(key, _)| {
+            key.strip_prefix("CARGO_FEATURE_")
+                .map(|name| name.to_lowercase().replace('_', "-"))
+        })
+        .collect();
+    features.sort();
+    println!("cargo:rustc-env=PDFCUTTER_FEATURES={}", features.join(","));
+
+    println!(
+        "cargo:rustc-env=PDFCUTTER_BUILD_PROFILE={}",
+        env::var("PROFILE").unwrap_or_default()
+    );
+    println!(
+        "cargo:rustc-env=PDFCUTTER_BUILD_TARGET={}",
+        env::var("TARGET").unwrap_or_default()
+    );
+}
+
+/**
+ * Short hash of the commit being built.
+ * @returns The hash, with "-dirty" when tracked files were modified, or "unknown" outside
+ * of a git checkout (e.g. a source archive).
+ */
+fn git_hash() -> String {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+
+    match git(&["rev-parse", "--short", "HEAD"]) {
+        Some(hash) if !hash.is_empty() => {
+            let dirty = git(&["status", "--porcelain", "--untracked-files=no"])
+                .is_some_and(|status| !status.is_empty());
+            if dirty { format!("{hash}-dirty") } else { hash }
+        }
+        _ => "unknown".to_string(),
+    }
+}
@@ -0,0 +1,129 @@
+//! Feeds arbitrary text to the page and segment parsers, which see every keystroke typed in the
+//! TUI and every argument of the CLI. The first byte picks the page count of the document the
+//! pages are resolved against (none for 0), so `last` and complements are reached too. Run with
+//! `cargo fuzz run page_spec` from the repository root.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+/// `tr!` without catalogs: messages stay in English.
+mod i18n {
+    use std::fmt::Display;
+
+    pub fn translate(message: &'static str) -> &'static str {
+        message
+    }
+
+    pub fn fill(message: &str, args: &[(&str, &dyn Display)]) -> String {
+        args.iter()
+            .fold(message.to_string(), |text, (name, value)| {
+                text.replace(&format!("{{{}}}", name), &value.to_string())
+            })
+    }
+
+    macro_rules! tr {
+        ($message:literal) => {
+            $crate::i18n::translate($message)
+        };
+        ($message:literal, $($name:ident = $value:expr),+ $(,)?) => {
+            $crate::i18n::fill(
+                $crate::i18n::translate($message),
+                &[$((stringify!($name), &$value as &dyn std::fmt::Display)),+],
+            )
+        };
+    }
+    pub(crate) use tr;
+}
+
+// File names and labels of segments are only used by split
+#[allow(dead_code)]
+#[path = "../../src/pdf/page_spec.rs"]
+mod page_spec;
+
+use page_spec::{
+    MAX_PAGES, MAX_SPEC_LENGTH, PageSegment, SpecError, parse_named_segments,
+    parse_page_segments, parse_page_sequence, parse_page_set, parse_pages_to_delete,
+};
+
+/// Check what an error says about where the specification goes wrong.
+fn check_error(error: SpecError, input: &str) {
+    match error {
+        SpecError::TooLong { length } => assert!(length > MAX_SPEC_LENGTH),
+        SpecError::Unexpected { column, .. }
+        | SpecError::MissingNumber { column }
+        | SpecError::NumberTooLarge { column }
+        | SpecError::ZeroPage { column }
+        | SpecError::Reversed { column, .. }
+        | SpecError::StepWithoutRange { column }
+        | SpecError::ZeroStep { column }
+        | SpecError::PageCountNeeded { column, .. }
+        | SpecError::BeforeFirstPage { column, .. }
+        | SpecError::PageOutOfRange { column, .. }
+        | SpecError::MissingName { column } => {
+            assert!(column >= 1 && column <= input.chars().count() + 1)
+        }
+        error => {
+            error.to_string();
+        }
+    }
+}
+
+fn check_pages(pages: &[u32], page_count: Option<usize>) {
+    assert!(!pages.is_empty() && pages.len() <= MAX_PAGES as usize);
+    assert!(pages.iter().all(|&page| page > 0));
+    if let Some(page_count) = page_count {
+        assert!(pages.iter().all(|&page| page as usize <= page_count));
+    }
+}
+
+fn check_segments(segments: &[PageSegment]) {
+    assert!(!segments.is_empty());
+    assert!(
+        segments
+            .iter()
+            .all(|segment| segment.start > 0 && segment.end.is_none_or(|end| end >= segment.start))
+    );
+    assert!(segments.iter().map(PageSegment::page_count).sum::<u64>() <= MAX_PAGES as u64);
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&count, text)) = data.split_first() else {
+        return;
+    };
+    let Ok(input) = std::str::from_utf8(text) else {
+        return;
+    };
+    let page_count = (count > 0).then_some(count as usize);
+
+    match parse_page_set(input, page_count) {
+        Ok(pages) => {
+            check_pages(&pages, page_count);
+            assert!(pages.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+        Err(error) => check_error(error, input),
+    }
+    match parse_page_sequence(input, page_count) {
+        Ok(pages) => {
+            check_pages(&pages, page_count);
+            let mut unique = pages.clone();
+            unique.sort_unstable();
+            unique.dedup();
+            assert_eq!(unique.len(), pages.len());
+        }
+        Err(error) => check_error(error, input),
+    }
+    match parse_pages_to_delete(input, page_count) {
+        Ok(pages) => {
+            check_pages(&pages, page_count);
+            assert!(page_count.is_none_or(|count| pages.len() < count));
+        }
+        Err(error) => check_error(error, input),
+    }
+    for segments in [parse_page_segments(input), parse_named_segments(input)] {
+        match segments {
+            Ok(segments) => check_segments(&segments),
+            Err(error) => check_error(error, input),
+        }
+    }
+});
@@ -0,0 +1,88 @@
+use crate::pdf::output::contract_home;
+use std::env::consts::{ARCH, OS};
+use std::path::Path;
+
+/// Version of the package, e.g. "0.1.0".
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Short hash of the commit the binary was built from, or "unknown".
+pub const GIT_HASH: &str = env!("PDFCUTTER_GIT_HASH");
+
+/// Version with the commit, as printed by `--version`.
+pub const LONG_VERSION: &str = concat!(
+    env!("CARGO_PKG_VERSION"),
+    " (",
+    env!("PDFCUTTER_GIT_HASH"),
+    ")"
+);
+
+/// Cargo profile of the build: "debug" or "release".
+pub const PROFILE: &str = env!("PDFCUTTER_BUILD_PROFILE");
+
+/// Target triple the binary was built for.
+pub const TARGET: &str = env!("PDFCUTTER_BUILD_TARGET");
+
+/// Comma separated cargo features enabled in the build.
+const FEATURES: &str = env!("PDFCUTTER_FEATURES");
+
+/**
+ * Cargo features enabled in the build (e.g. an optional rasterizer, OCR or server).
+ * @returns The feature names, sorted; empty for a default build.
+ */
+pub fn features() -> Vec<&'static str> {
+    FEATURES
+        .split(',')
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/**
+ * Describe the build and its environment as plain text, to paste in a bug report.
+ * @param config_path The configuration file location, if there is one.
+ * @returns One "name: value" line per detail.
+ */
+pub fn report(config_path: Option<&Path>) -> String {
+    let features = features();
+    let config = match config_path {
+        Some(path) if path.exists() => contract_home(path),
+        Some(path) => format!("{} (not created)", contract_home(path)),
+        None => "none (no home directory)".to_string(),
+    };
+    [
+        format!("pdf-cutter {}", LONG_VERSION),
+        format!("Build: {} ({})", PROFILE, TARGET),
+        format!(
+            "Features: {}",
+            if features.is_empty() {
+                "none".to_string()
+            } else {
+                features.join(", ")
+            }
+        ),
+        format!("System: {} {}", OS, ARCH),
+        format!("Config file: {}", config),
+    ]
+    .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        assert!(LONG_VERSION.starts_with(VERSION));
+        assert!(!GIT_HASH.is_empty());
+        assert!(features().iter().all(|name| !name.is_empty()));
+
+        let text = report(Some(Path::new("/nonexistent/pdf-cutter/config.toml")));
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(lines[0], format!("pdf-cutter {VERSION} ({GIT_HASH})"));
+        assert!(lines[1].contains(TARGET));
+        assert_eq!(
+            lines.last(),
+            Some(&"Config file: /nonexistent/pdf-cutter/config.toml (not created)")
+        );
+        assert!(report(None).ends_with("Config file: none (no home directory)"));
+    }
+}
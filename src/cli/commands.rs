@@ -1,28 +1,212 @@
-use clap::Parser;
+use clap::{Args, Parser, ValueEnum};
 
 /// PDF Cutter - A CLI tool for merging and deleting pages from PDF files
 #[derive(Parser, Debug)]
 #[command(
     author,
     version,
+    long_version = crate::build_info::LONG_VERSION,
     override_usage = "cargo run -- -<COMMAND> -<COMMAND_ARGS>"
 )]
 pub struct Cli {
+    /// Only print errors: no progress, success or warning messages
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
+    /// Report the time spent loading, copying and saving, and the peak memory, on stderr
+    #[arg(long, global = true)]
+    pub timings: bool,
+
+    /// Try the passwords listed in FILE, one per line, on inputs locked by a password, and
+    /// report which line opened each
+    #[arg(long, global = true, value_name = "FILE")]
+    pub password_file: Option<String>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
 
+/// Order the inputs of a merge are put in before merging.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputSort {
+    /// Keep the order given on the command line.
+    None,
+    /// Compare the paths character by character, like a shell glob.
+    Name,
+    /// Compare the paths with numbers by value, so `p2` comes before `p10`.
+    Natural,
+    /// Oldest modification time first.
+    Mtime,
+}
+
+/// What a merge does with a named destination or form field name used by two inputs.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NameConflictPolicy {
+    /// Prefix the name from the later input with its position in the merge (2_intro).
+    Prefix,
+    /// Stop with an error naming both inputs.
+    Error,
+    /// Keep the first input's, dropping the later one.
+    KeepFirst,
+}
+
+/// Where `annotate` puts its note, as seen in a viewer.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NotePosition {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    Center,
+}
+
+/// How `info` and `links` print what they find.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// Lines to read in a terminal.
+    Table,
+    /// One JSON value on one line.
+    Json,
+    /// A header row then one row per page or link, to paste into a spreadsheet.
+    Csv,
+}
+
+/// Document information written into the outputs of a command.
+#[derive(Args, Debug)]
+pub struct MetadataArgs {
+    /// Title of the output (an empty value removes it)
+    #[arg(long)]
+    pub title: Option<String>,
+
+    /// Author of the output (an empty value removes it)
+    #[arg(long)]
+    pub author: Option<String>,
+
+    /// Subject of the output (an empty value removes it)
+    #[arg(long)]
+    pub subject: Option<String>,
+
+    /// Keywords of the output, e.g. "scan, invoices" (an empty value removes them)
+    #[arg(long)]
+    pub keywords: Option<String>,
+
+    /// File identifier of the output: keep (the one of the first input), new, or the
+    /// hexadecimal digits to write
+    #[arg(long, value_name = "keep|new|HEX", conflicts_with = "deterministic")]
+    pub id: Option<String>,
+
+    /// Creation and modification dates of the output: keep (those of the first input), now, or
+    /// a date such as 2025-01-31 or 2025-01-31T09:30:00+01:00
+    #[arg(long, value_name = "keep|now|DATE", conflicts_with = "deterministic")]
+    pub dates: Option<String>,
+
+    /// Write byte-identical outputs for identical inputs: no file identifier, and dates taken
+    /// from SOURCE_DATE_EPOCH or left out
+    #[arg(long)]
+    pub deterministic: bool,
+
+    /// ICC profile of the printing condition (e.g., FOGRA39.icc), attached as the output intent
+    /// in place of those of the inputs
+    #[arg(long, value_name = "FILE")]
+    pub icc_profile: Option<String>,
+
+    /// Leave out the provenance receipt otherwise recorded in the metadata of the output: the
+    /// pdf-cutter version, the command, the name and SHA-256 hash of each input and the date
+    #[arg(long)]
+    pub no_provenance: bool,
+}
+
+/// How the outputs of a command are written to disk.
+#[derive(Args, Debug)]
+pub struct OutputArgs {
+    /// Flush every output and its directory to disk before finishing, so a power loss cannot lose them
+    #[arg(long)]
+    pub durable: bool,
+
+    /// Write every object on its own instead of packing small ones into object streams, for readers older than PDF 1.5
+    #[arg(long)]
+    pub no_object_streams: bool,
+}
+
 #[derive(Parser, Debug)]
 pub enum Commands {
     /// Merge multiple PDFs into one
     Merge {
-        /// Output PDF file
+        /// Output PDF file (asked for on a terminal; default: output_merged.pdf, kept apart from
+        /// existing files)
         #[arg(short, long)]
-        output: String,
+        output: Option<String>,
 
         /// Input PDF files (at least 2)
         #[arg(required = true)]
         inputs: Vec<String>,
+
+        /// Open the result in the system PDF viewer
+        #[arg(long)]
+        open: bool,
+
+        /// Allow writing over an input file (output path equal to an input)
+        #[arg(long)]
+        in_place: bool,
+
+        /// Keep the files the output replaces, renamed name.pdf.SUFFIX (default: bak)
+        #[arg(
+            long,
+            value_name = "SUFFIX",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "bak"
+        )]
+        backup: Option<String>,
+
+        /// Read the output back once written and fail unless it has the expected pages, each
+        /// with content that can be decoded
+        #[arg(long)]
+        verify: bool,
+
+        /// Print the progress as JSON lines on stdout (start, file, done and error events)
+        /// instead of the usual messages, for programs wrapping the CLI
+        #[arg(long)]
+        json: bool,
+
+        /// Order of the input files: as given, by name, by name with numbers compared by
+        /// value (p2 before p10), or by modification time (oldest first)
+        #[arg(long, value_enum, default_value_t = InputSort::None)]
+        sort: InputSort,
+
+        /// Rotate every page of one input clockwise, as INPUT:ANGLE where INPUT is a path or a
+        /// position in the merge order (e.g., 2:90); repeat for several inputs
+        #[arg(long, value_name = "INPUT:ANGLE", allow_hyphen_values = true)]
+        rotate: Vec<String>,
+
+        /// Take only some pages of one input, in the order typed, as INPUT:PAGES
+        /// (e.g., b.pdf:1-5); repeat for several inputs
+        #[arg(long, value_name = "INPUT:PAGES")]
+        pages: Vec<String>,
+
+        /// Put a page between the inputs: `blank`, `title` (the file name of the next input) or
+        /// every page of a divider PDF given by its path
+        #[arg(long, value_name = "blank|title|FILE")]
+        separator: Option<String>,
+
+        /// Stamp every page with the file name and page number it comes from, in a small footer
+        #[arg(long)]
+        source_footer: bool,
+
+        /// Keep the accessibility tags (structure tree) of tagged inputs
+        #[arg(long)]
+        keep_tags: bool,
+
+        /// When inputs use the same name for a named destination or a form field: prefix the
+        /// later one with its input's position, stop with an error, or keep the first one
+        #[arg(long, value_enum, default_value_t = NameConflictPolicy::Prefix)]
+        name_conflicts: NameConflictPolicy,
+
+        #[command(flatten)]
+        metadata: MetadataArgs,
+
+        #[command(flatten)]
+        output_settings: OutputArgs,
     },
     /// Delete pages from a PDF
     Delete {
@@ -30,13 +214,48 @@ pub enum Commands {
         #[arg(short, long)]
         input: String,
 
-        /// Output PDF file
+        /// Output PDF file (default: output_deleted_pages.pdf, kept apart from existing files)
         #[arg(short, long)]
-        output: String,
+        output: Option<String>,
 
-        /// Pages to delete (e.g., "3", "3-5", "1,3,5-7")
+        /// Pages to delete (e.g., "3", "3-5", "1,3,5-7", "last-1-last", "2-20:2", "!1-4"), asked
+        /// for on a terminal when missing
         #[arg(short = 'p', long)]
-        pages: String,
+        pages: Option<String>,
+
+        /// Open the result in the system PDF viewer
+        #[arg(long)]
+        open: bool,
+
+        /// Allow writing over an input file (output path equal to an input)
+        #[arg(long)]
+        in_place: bool,
+
+        /// Keep the files the output replaces, renamed name.pdf.SUFFIX (default: bak)
+        #[arg(
+            long,
+            value_name = "SUFFIX",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "bak"
+        )]
+        backup: Option<String>,
+
+        /// Read the output back once written and fail unless it has the expected pages, each
+        /// with content that can be decoded
+        #[arg(long)]
+        verify: bool,
+
+        /// Keep the accessibility tags (structure tree) of a tagged input, without the parts on
+        /// deleted pages
+        #[arg(long)]
+        keep_tags: bool,
+
+        #[command(flatten)]
+        metadata: MetadataArgs,
+
+        #[command(flatten)]
+        output_settings: OutputArgs,
     },
 
     /// Split a PDF into multiple smaller PDFs
@@ -49,15 +268,215 @@ pub enum Commands {
         #[arg(short = 'p', long)]
         pages: String,
 
-        // Output file prefix, e.g., "output_" will create files like "output_1.pdf", "output_2.pdf", etc.
+        // Output file prefix, e.g., "output" will create files like "output_001.pdf", "output_002.pdf", etc.
+        // Missing folders are created, e.g., "out/2024/part" writes "out/2024/part_001.pdf"
+        // Defaults to "split_output", kept apart from existing files
         #[arg(short = 'o', long = "output-prefix")]
-        output_prefix: String,
+        output_prefix: Option<String>,
 
         /// Use named segments format (name:pages)
         #[arg(long)]
         named: bool,
+
+        /// Allow segments that share pages, which are then written to several files
+        /// (refused by default, as they are usually typos)
+        #[arg(long)]
+        allow_overlap: bool,
+
+        /// Open every produced file in the system PDF viewer
+        #[arg(long)]
+        open: bool,
+
+        /// Allow writing over an input file (output path equal to an input)
+        #[arg(long)]
+        in_place: bool,
+
+        /// Keep the files the output replaces, renamed name.pdf.SUFFIX (default: bak)
+        #[arg(
+            long,
+            value_name = "SUFFIX",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "bak"
+        )]
+        backup: Option<String>,
+
+        /// Read the output back once written and fail unless it has the expected pages, each
+        /// with content that can be decoded
+        #[arg(long)]
+        verify: bool,
+
+        /// Print the progress as JSON lines on stdout (start, segment, done and error events)
+        /// instead of the usual messages, for programs wrapping the CLI
+        #[arg(long)]
+        json: bool,
+
+        #[command(flatten)]
+        metadata: MetadataArgs,
+
+        #[command(flatten)]
+        output_settings: OutputArgs,
+    },
+
+    /// Rotate pages of a PDF clockwise by 90, 180 or 270 degrees
+    Rotate {
+        /// Input PDF file
+        #[arg(short, long)]
+        input: String,
+
+        /// Output PDF file (default: output_rotated.pdf, kept apart from existing files)
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Pages to rotate (e.g., "3", "2,5-7", "last", "2-20:2", "!1"), every page when left out
+        #[arg(short = 'p', long)]
+        pages: Option<String>,
+
+        /// Clockwise angle in degrees: 90, 180 or 270, added to the current rotation of each
+        /// page (-90 turns counterclockwise)
+        #[arg(short = 'a', long, allow_hyphen_values = true)]
+        angle: i64,
+
+        /// Open the result in the system PDF viewer
+        #[arg(long)]
+        open: bool,
+
+        /// Allow writing over an input file (output path equal to an input)
+        #[arg(long)]
+        in_place: bool,
+
+        /// Keep the files the output replaces, renamed name.pdf.SUFFIX (default: bak)
+        #[arg(
+            long,
+            value_name = "SUFFIX",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "bak"
+        )]
+        backup: Option<String>,
+
+        /// Read the output back once written and fail unless it has the expected pages, each
+        /// with content that can be decoded
+        #[arg(long)]
+        verify: bool,
+
+        #[command(flatten)]
+        metadata: MetadataArgs,
+
+        #[command(flatten)]
+        output_settings: OutputArgs,
+    },
+    /// Put a text note on a page of a PDF, e.g. a review stamp, as a FreeText annotation
+    Annotate {
+        /// Input PDF file
+        #[arg(short, long)]
+        input: String,
+
+        /// Output PDF file (default: output_annotated.pdf, kept apart from existing files)
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Page to put the note on
+        #[arg(long, default_value_t = 1)]
+        page: u32,
+
+        /// Text of the note, on one line (e.g., "Reviewed 2024-05-01")
+        #[arg(long)]
+        text: String,
+
+        /// Where the note goes on the page, as seen in a viewer
+        #[arg(long, value_enum, default_value_t = NotePosition::TopRight)]
+        pos: NotePosition,
+
+        /// Open the result in the system PDF viewer
+        #[arg(long)]
+        open: bool,
+
+        /// Allow writing over an input file (output path equal to an input)
+        #[arg(long)]
+        in_place: bool,
+
+        /// Keep the files the output replaces, renamed name.pdf.SUFFIX (default: bak)
+        #[arg(
+            long,
+            value_name = "SUFFIX",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "bak"
+        )]
+        backup: Option<String>,
+
+        /// Read the output back once written and fail unless it has the pages of the input,
+        /// each with content that can be decoded
+        #[arg(long)]
+        verify: bool,
+
+        #[command(flatten)]
+        metadata: MetadataArgs,
+
+        #[command(flatten)]
+        output_settings: OutputArgs,
+    },
+
+    /// List the links of a PDF: pages of the document, named destinations and web addresses
+    Links {
+        /// Input PDF file
+        input: String,
+
+        /// Only list the links leading to no page of the document, and fail if there are any
+        #[arg(long)]
+        check: bool,
+
+        /// Print the links as lines, as JSON or as CSV
+        #[arg(long, value_enum, default_value_t = ReportFormat::Table)]
+        format: ReportFormat,
+    },
+
+    /// Show the pages, size and page sizes of a PDF, listing the pages whose size or
+    /// orientation differs from the rest
+    Info {
+        /// Input PDF file
+        input: String,
+
+        /// Print the summary as lines, as JSON, or as CSV with one row per page
+        #[arg(long, value_enum, default_value_t = ReportFormat::Table)]
+        format: ReportFormat,
     },
 
     /// Launch Terminal User Interface
-    Tui,
+    Tui {
+        /// Draw plain ASCII instead of emoji and symbols, for terminals or fonts lacking them
+        #[arg(long)]
+        ascii: bool,
+
+        #[command(flatten)]
+        output_settings: OutputArgs,
+    },
+}
+
+impl Commands {
+    /// How the command writes its outputs, or None for the commands writing no PDF file.
+    pub fn output_args(&self) -> Option<&OutputArgs> {
+        match self {
+            Commands::Merge {
+                output_settings, ..
+            }
+            | Commands::Delete {
+                output_settings, ..
+            }
+            | Commands::Split {
+                output_settings, ..
+            }
+            | Commands::Rotate {
+                output_settings, ..
+            }
+            | Commands::Annotate {
+                output_settings, ..
+            }
+            | Commands::Tui {
+                output_settings, ..
+            } => Some(output_settings),
+            Commands::Links { .. } | Commands::Info { .. } => None,
+        }
+    }
 }
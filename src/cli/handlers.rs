@@ -1,116 +1,1516 @@
-use super::commands::Commands;
+use super::commands::{
+    Cli, Commands, InputSort, MetadataArgs, NameConflictPolicy, NotePosition, ReportFormat,
+};
+use super::prompt::{self, complete_pdf_path};
+use super::report::{info_csv, info_json, links_csv, links_json};
+use crate::config::Config;
+use crate::i18n::tr;
 use crate::pdf;
+use crate::pdf::annotations::TextNote;
+use crate::pdf::delete::delete_pages_with_tags;
+use crate::pdf::inspect::inspect_document;
+use crate::pdf::intents::read_icc_profile;
+use crate::pdf::links::{Link, LinkTarget, list_links};
+use crate::pdf::metadata::{
+    DocumentDates, DocumentMetadata, FileId, document_dates, epoch_date, file_id, parse_file_id,
+    pdf_date, pdf_date_from_epoch, pdf_date_from_text, set_output_metadata,
+};
+use crate::pdf::names::NameConflicts;
+use crate::pdf::operation::{Operation, PreparedOutputs};
+use crate::pdf::output::{
+    DEFAULT_ANNOTATE_OUTPUT, DEFAULT_BACKUP_SUFFIX, DEFAULT_DELETE_OUTPUT, DEFAULT_MERGE_OUTPUT,
+    DEFAULT_ROTATE_OUTPUT, DEFAULT_SPLIT_PREFIX, OverwritePolicy, backup_file,
+    backup_in_place_from_config, backup_suffix_from_config, expand_home, parse_backup_suffix,
+    same_file,
+};
+use crate::pdf::page_spec::{
+    SpecError, parse_named_segments, parse_page_segments, parse_page_sequence, parse_page_set,
+    parse_pages_to_delete,
+};
+use crate::pdf::passwords::take_unlocked;
+use crate::pdf::progress::{Progress, report, watch_progress};
+use crate::pdf::provenance::receipt;
+use crate::pdf::rotate::check_rules;
+use crate::pdf::space::{SpaceCheck, SpaceWarning};
+use crate::pdf::split::{find_overlap, segment_filenames};
+use crate::pdf::structure::TagWarning;
+use crate::pdf::timings::{format_elapsed, peak_memory, take_timings};
+use crate::pdf::utils::load_document;
+use crate::pdf::verify::verify_output;
+use crate::pdf::watermark::WatermarkPosition;
+use crate::pdf::{MergeInput, MergeOptions, RotationRule, Separator};
 use crate::tui;
-use anyhow::{Result, bail};
+use crate::tui::system::open_in_viewer;
+use crate::tui::utils::{
+    describe_page_sizes, describe_space_warning, format_page_ranges, format_size, natural_cmp,
+};
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::{DateTime, FixedOffset, Local};
+use clap::CommandFactory;
+use clap::error::ErrorKind;
+use std::fs;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+/// Set by `--quiet`: only errors are printed.
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Print only errors from now on, for scripts and cron jobs.
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+/// Set by `--json` of merge and split: stdout only gets their progress events, as JSON lines.
+static JSON: AtomicBool = AtomicBool::new(false);
+
+/// Print the progress of merge or split as JSON lines on stdout from now on, one event per
+/// line as it happens, instead of the usual messages.
+fn set_json(json: bool) {
+    JSON.store(json, Ordering::Relaxed);
+    if json {
+        watch_progress(|event| println!("{}", event.to_json()));
+    }
+}
+
+/// End the `--json` events of a failed command with an error event. The error is still
+/// printed on stderr.
+pub fn report_error(error: &anyhow::Error) {
+    if JSON.load(Ordering::Relaxed) {
+        report(Progress::Error {
+            message: format!("{:#}", error),
+        });
+    }
+}
+
+/// Print a progress or success message on stdout, unless `--quiet` or `--json` is given.
+fn inform(message: &str) {
+    if !QUIET.load(Ordering::Relaxed) && !JSON.load(Ordering::Relaxed) {
+        println!("{}", message);
+    }
+}
+
+/// Set by `--timings`: report where the time of a command went.
+static SHOW_TIMINGS: AtomicBool = AtomicBool::new(false);
+
+/// Report the time spent loading, copying and saving after each command.
+pub fn set_timings(timings: bool) {
+    SHOW_TIMINGS.store(timings, Ordering::Relaxed);
+}
+
+/// Print a warning on stderr, unless `--quiet` is given. Errors are always printed.
+pub fn warn(message: &str) {
+    if !QUIET.load(Ordering::Relaxed) {
+        eprintln!("{}", message);
+    }
+}
 
 pub fn handle_command(command: Option<Commands>) -> Result<()> {
     match command {
-        Some(Commands::Tui) => return tui::run(),
-        Some(Commands::Merge { inputs, output }) => handle_merge(inputs, output),
+        // The output settings are applied by main before any command runs
+        Some(Commands::Tui {
+            ascii,
+            output_settings: _,
+        }) => tui::run(ascii),
+        Some(Commands::Merge {
+            inputs,
+            output,
+            open,
+            in_place,
+            backup,
+            verify,
+            json,
+            sort,
+            rotate,
+            pages,
+            separator,
+            source_footer,
+            keep_tags,
+            name_conflicts,
+            metadata,
+            output_settings: _,
+        }) => {
+            set_json(json);
+            // A program reading the events answers no prompt
+            let output = match output {
+                None if prompt::is_interactive() && !json => Some(ask_merge_output()?),
+                output => output,
+            };
+            let order = MergeOrder {
+                sort,
+                rotate,
+                pages,
+                separator,
+                source_footer,
+                keep_tags,
+                name_conflicts: match name_conflicts {
+                    NameConflictPolicy::Prefix => NameConflicts::Prefix,
+                    NameConflictPolicy::Error => NameConflicts::Error,
+                    NameConflictPolicy::KeepFirst => NameConflicts::KeepFirst,
+                },
+            };
+            let flags = OutputFlags {
+                in_place,
+                backup,
+                verify,
+                metadata: output_metadata(metadata)?,
+            };
+            handle_merge(inputs, output, open, order, flags)
+        }
         Some(Commands::Delete {
             input,
             output,
             pages,
-        }) => handle_delete(input, output, pages),
+            open,
+            in_place,
+            backup,
+            verify,
+            keep_tags,
+            metadata,
+            output_settings: _,
+        }) => {
+            let pages = match pages {
+                Some(pages) => pages,
+                None if prompt::is_interactive() => ask_delete_pages(&input)?,
+                None => missing_argument("delete", "--pages <PAGES>"),
+            };
+            let flags = OutputFlags {
+                in_place,
+                backup,
+                verify,
+                metadata: output_metadata(metadata)?,
+            };
+            handle_delete(input, output, pages, open, keep_tags, flags)
+        }
         Some(Commands::Split {
             input,
             pages,
             output_prefix,
             named,
-        }) => handle_split(input, output_prefix, pages, named),
+            allow_overlap,
+            open,
+            in_place,
+            backup,
+            verify,
+            json,
+            metadata,
+            output_settings: _,
+        }) => {
+            set_json(json);
+            handle_split(
+                input,
+                output_prefix,
+                pages,
+                named,
+                allow_overlap,
+                open,
+                OutputFlags {
+                    in_place,
+                    backup,
+                    verify,
+                    metadata: output_metadata(metadata)?,
+                },
+            )
+        }
+        Some(Commands::Rotate {
+            input,
+            output,
+            pages,
+            angle,
+            open,
+            in_place,
+            backup,
+            verify,
+            metadata,
+            output_settings: _,
+        }) => {
+            let flags = OutputFlags {
+                in_place,
+                backup,
+                verify,
+                metadata: output_metadata(metadata)?,
+            };
+            handle_rotate(input, output, pages, angle, open, flags)
+        }
+        Some(Commands::Annotate {
+            input,
+            output,
+            page,
+            text,
+            pos,
+            open,
+            in_place,
+            backup,
+            verify,
+            metadata,
+            output_settings: _,
+        }) => {
+            let position = match pos {
+                NotePosition::TopLeft => WatermarkPosition::TopLeft,
+                NotePosition::TopRight => WatermarkPosition::TopRight,
+                NotePosition::BottomLeft => WatermarkPosition::BottomLeft,
+                NotePosition::BottomRight => WatermarkPosition::BottomRight,
+                NotePosition::Center => WatermarkPosition::Center,
+            };
+            let note = TextNote {
+                page,
+                text,
+                position,
+            };
+            let flags = OutputFlags {
+                in_place,
+                backup,
+                verify,
+                metadata: output_metadata(metadata)?,
+            };
+            handle_annotate(input, output, note, open, flags)
+        }
+        Some(Commands::Links {
+            input,
+            check,
+            format,
+        }) => handle_links(&input, check, format),
+        Some(Commands::Info { input, format }) => handle_info(&input, format),
         None => {
-            bail!("No command provided. Use --help for usage or --tui for interactive mode.");
+            bail!(tr!(
+                "No command provided. Use --help for usage or --tui for interactive mode."
+            ));
+        }
+    }
+}
+
+/**
+ * Stop with the usage error clap gives for a missing required argument, when it cannot be
+ * asked for because the command does not run on a terminal.
+ * @param subcommand The subcommand name, e.g. "delete".
+ * @param argument The argument as shown in the usage, e.g. "--pages <PAGES>".
+ */
+fn missing_argument(subcommand: &str, argument: &str) -> ! {
+    let mut command = Cli::command();
+    command.build();
+    let message = tr!(
+        "the following required arguments were not provided:\n  {argument}",
+        argument = argument
+    );
+    match command.find_subcommand_mut(subcommand) {
+        Some(subcommand) => subcommand.error(ErrorKind::MissingRequiredArgument, message),
+        None => command.error(ErrorKind::MissingRequiredArgument, message),
+    }
+    .exit()
+}
+
+/**
+ * Ask for the output of a merge, offering the default name.
+ * @returns The output path, with `~` expanded.
+ * @throws anyhow::Error if the prompt is cancelled or the config file is invalid.
+ */
+fn ask_merge_output() -> Result<String> {
+    let default = output_or_default(None, DEFAULT_MERGE_OUTPUT, |candidate| {
+        Path::new(candidate).exists()
+    })?;
+    prompt::ask(
+        tr!("Output file"),
+        Some(&default),
+        Some(complete_pdf_path),
+        |answer| {
+            let output = expand_home(answer);
+            if let Some(parent) = output.parent()
+                && let Some(existing) = parent.ancestors().find(|dir| dir.exists())
+                && !existing.is_dir()
+            {
+                bail!(tr!(
+                    "{path} is a file, not a folder",
+                    path = existing.display()
+                ));
+            }
+            Ok(output.to_string_lossy().into_owned())
+        },
+    )
+}
+
+/**
+ * Ask for the pages to delete, checked against the page count of the input.
+ * @param input The input PDF file.
+ * @returns The pages as typed.
+ * @throws anyhow::Error if the input does not exist or the prompt is cancelled.
+ */
+fn ask_delete_pages(input: &str) -> Result<String> {
+    if !Path::new(input).exists() {
+        bail!(tr!("Input file does not exist: {path}", path = input));
+    }
+    let page_count = inspect_document(input).ok().map(|info| info.page_count());
+    let question = match page_count {
+        Some(count) => tr!("Pages to delete (1-{count})", count = count),
+        None => tr!("Pages to delete").to_string(),
+    };
+    prompt::ask(&question, None, None, |answer| {
+        parse_pages_to_delete(answer, page_count).map_err(invalid_pages)?;
+        Ok(answer.to_string())
+    })
+}
+
+/// Report pages or segments that cannot be parsed, with the column where they go wrong.
+fn invalid_pages(error: SpecError) -> anyhow::Error {
+    anyhow!(tr!("Invalid page range: {input}", input = error))
+}
+
+/// Flags deciding how a command treats the files its outputs replace, and what it writes in them.
+struct OutputFlags {
+    /// `--in-place`: an output may replace an input.
+    in_place: bool,
+    /// `--backup[=SUFFIX]`: keep the replaced files under this suffix.
+    backup: Option<String>,
+    /// `--verify`: read the outputs back and check their pages.
+    verify: bool,
+    /// `--title`, `--author`, `--subject`, `--keywords`, `--id`, `--dates`, `--deterministic`
+    /// and `--no-provenance`: applied to every output.
+    metadata: OutputMetadata,
+}
+
+/// What the metadata options ask to write into the outputs of a command.
+struct OutputMetadata {
+    /// The entries given on the command line, with the identifier and dates when they do not
+    /// come from the source.
+    entries: DocumentMetadata,
+    /// `--id keep`: copy the identifier of the source.
+    keep_id: bool,
+    /// `--dates keep`: copy the dates of the source.
+    keep_dates: bool,
+    /// Record how the outputs were made, unless `--no-provenance` is given.
+    provenance: bool,
+    /// When the command ran, for the provenance receipt; None with `--deterministic` and no
+    /// SOURCE_DATE_EPOCH.
+    receipt_date: Option<DateTime<FixedOffset>>,
+}
+
+/// How the inputs of a merge are ordered and changed before merging, and what goes between them.
+struct MergeOrder {
+    /// `--sort`: the order of the inputs.
+    sort: InputSort,
+    /// `--rotate INPUT:ANGLE`, each time it is given.
+    rotate: Vec<String>,
+    /// `--pages INPUT:PAGES`, each time it is given.
+    pages: Vec<String>,
+    /// `--separator`: `blank`, `title` or a divider PDF.
+    separator: Option<String>,
+    /// `--source-footer`: stamp the source of every page.
+    source_footer: bool,
+    /// `--keep-tags`: copy the structure trees of tagged inputs.
+    keep_tags: bool,
+    /// `--name-conflicts`: how names used by two inputs are settled.
+    name_conflicts: NameConflicts,
+}
+
+/**
+ * Put the inputs of a merge in the order asked with `--sort`.
+ * The sort is stable, so paths that compare equal keep their command-line order.
+ * @param inputs The input paths, as given on the command line.
+ * @param sort The order to put them in.
+ * @throws anyhow::Error if a modification time cannot be read.
+ */
+fn sort_inputs(inputs: &mut [String], sort: InputSort) -> Result<()> {
+    match sort {
+        InputSort::None => {}
+        InputSort::Name => inputs.sort(),
+        InputSort::Natural => inputs.sort_by(|a, b| natural_cmp(a, b)),
+        InputSort::Mtime => {
+            let mut timed = inputs
+                .iter()
+                .map(|path| {
+                    let modified =
+                        fs::metadata(path)
+                            .and_then(|m| m.modified())
+                            .with_context(|| {
+                                tr!("Cannot read the modification time of '{path}'", path = path)
+                            })?;
+                    Ok((modified, path.clone()))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            timed.sort_by_key(|(modified, _)| *modified);
+            for (slot, (_, path)) in inputs.iter_mut().zip(timed) {
+                *slot = path;
+            }
         }
     }
+    Ok(())
+}
+
+/**
+ * Split an `INPUT:VALUE` argument at the first colon followed by nothing but a page list or an
+ * angle, so that both paths with colons and steps such as "1-20:2" work. Without such a colon,
+ * it is split at the last one and the value is reported when it is checked.
+ * @param spec The argument, e.g. "b.pdf:1-5".
+ * @param flag The option it was given to, for the error message.
+ * @returns The input and the value.
+ * @throws anyhow::Error if there is no colon or either side is empty.
+ */
+fn split_input_spec<'a>(spec: &'a str, flag: &str) -> Result<(&'a str, &'a str)> {
+    let is_value = |value: &str| {
+        value
+            .to_ascii_lowercase()
+            .replace("last", "")
+            .chars()
+            .all(|c| c.is_ascii_digit() || c.is_whitespace() || ",-:!^".contains(c))
+    };
+    let split = spec
+        .match_indices(':')
+        .map(|(index, _)| (&spec[..index], &spec[index + 1..]))
+        .find(|(input, value)| !input.is_empty() && is_value(value))
+        .or_else(|| spec.rsplit_once(':'));
+    match split {
+        Some((input, value)) if !input.is_empty() && !value.trim().is_empty() => {
+            Ok((input, value.trim()))
+        }
+        _ => bail!(tr!(
+            "Invalid {flag} value '{spec}': expected INPUT:VALUE",
+            flag = flag,
+            spec = spec
+        )),
+    }
+}
+
+/**
+ * Find the inputs an `INPUT:` prefix names: every input that is this file, else every input
+ * with this file name (so `b.pdf` names `scans/b.pdf`), else the input at this position in the
+ * merge order (1-based).
+ * @param inputs The inputs, in merge order.
+ * @param target The path or position typed.
+ * @returns The indices of the inputs named.
+ * @throws anyhow::Error if no input matches.
+ */
+fn find_inputs(inputs: &[String], target: &str) -> Result<Vec<usize>> {
+    let matching: Vec<usize> = (0..inputs.len())
+        .filter(|&i| inputs[i] == target || same_file(&inputs[i], target))
+        .collect();
+    if !matching.is_empty() {
+        return Ok(matching);
+    }
+    let same_name: Vec<usize> = (0..inputs.len())
+        .filter(|&i| Path::new(&inputs[i]).file_name() == Some(target.as_ref()))
+        .collect();
+    if !same_name.is_empty() {
+        return Ok(same_name);
+    }
+    match target.parse::<usize>() {
+        Ok(position) if (1..=inputs.len()).contains(&position) => Ok(vec![position - 1]),
+        _ => bail!(tr!(
+            "'{input}' is neither an input of the merge nor a position from 1 to {count}",
+            input = target,
+            count = inputs.len()
+        )),
+    }
+}
+
+/**
+ * List the links of a PDF, or with `--check` only those leading to no page of it.
+ * @param input The input PDF file.
+ * @param check Whether to check the internal links instead of listing every link.
+ * @param format How the links are printed.
+ * @throws anyhow::Error if the input cannot be loaded, or with `--check` if a link is broken.
+ */
+fn handle_links(input: &str, check: bool, format: ReportFormat) -> Result<()> {
+    if !Path::new(input).exists() {
+        bail!(tr!("Input file does not exist: {path}", path = input));
+    }
+    let links = list_links(input)?;
+    let shown: Vec<&Link> = links
+        .iter()
+        .filter(|link| !check || link.is_broken())
+        .collect();
+    match format {
+        ReportFormat::Table => {
+            let mark = if check { "✗ " } else { "" };
+            for link in &shown {
+                println!("{}{}", mark, describe_link(link));
+            }
+        }
+        ReportFormat::Json => println!("{}", links_json(&shown)),
+        ReportFormat::Csv => print!("{}", links_csv(&shown)),
+    }
+    // JSON and CSV fill stdout on their own, the count is in them
+    let summary = format == ReportFormat::Table;
+    if !check {
+        if summary {
+            inform(&tr!(
+                "{count} links in '{input}'",
+                count = links.len(),
+                input = input
+            ));
+        }
+        return Ok(());
+    }
+
+    if !shown.is_empty() {
+        bail!(tr!(
+            "{count} of {total} links lead to no page of '{input}'",
+            count = shown.len(),
+            total = links.len(),
+            input = input
+        ));
+    }
+    if summary {
+        inform(&tr!(
+            "✅ Every internal link of '{input}' leads to a page",
+            input = input
+        ));
+    }
+    Ok(())
+}
+
+/**
+ * Print a summary of a PDF: its page count, file size, title, provenance and encryption, then
+ * its page sizes, the most common first, with the pages of the other sizes to rotate or check
+ * before printing. JSON and CSV list the size of every page instead.
+ * @param input The input PDF file.
+ * @param format How the summary is printed.
+ * @throws anyhow::Error if the input cannot be loaded.
+ */
+fn handle_info(input: &str, format: ReportFormat) -> Result<()> {
+    if !Path::new(input).exists() {
+        bail!(tr!("Input file does not exist: {path}", path = input));
+    }
+    let info = inspect_document(input)?;
+    match format {
+        ReportFormat::Table => {}
+        ReportFormat::Json => {
+            println!("{}", info_json(&info));
+            return Ok(());
+        }
+        ReportFormat::Csv => {
+            print!("{}", info_csv(&info));
+            return Ok(());
+        }
+    }
+    println!("{} {}", tr!("Pages:"), info.page_count());
+    println!("{} {}", tr!("Size:"), format_size(info.file_size));
+    if let Some(title) = &info.title {
+        println!("{} {}", tr!("Title:"), title);
+    }
+    if let Some(provenance) = &info.provenance {
+        println!("{} {}", tr!("Provenance:"), provenance);
+    }
+    let encrypted = if info.encrypted {
+        tr!("yes")
+    } else {
+        tr!("no")
+    };
+    println!("{} {}", tr!("Encrypted:"), encrypted);
+    println!("{}", tr!("Page sizes:"));
+    for line in describe_page_sizes(&info.pages) {
+        println!("  {}", line);
+    }
+    Ok(())
+}
+
+/// One line of `links`: the page a link is on and where it leads.
+fn describe_link(link: &Link) -> String {
+    let target = match &link.target {
+        LinkTarget::Page(Some(page)) => tr!("page {page}", page = page),
+        LinkTarget::Page(None) => tr!("no page").to_string(),
+        LinkTarget::Named {
+            name,
+            page: Some(page),
+        } => tr!("'{name}' (page {page})", name = name, page = page),
+        LinkTarget::Named { name, page: None } => {
+            tr!("'{name}' (no page)", name = name)
+        }
+        LinkTarget::Uri(uri) => uri.clone(),
+        LinkTarget::File(path) => tr!("file {path}", path = path),
+        LinkTarget::Other(action) => tr!("{action} action", action = action),
+    };
+    tr!("page {page} → {target}", page = link.page, target = target)
+}
+
+/**
+ * Turn the `--rotate` and `--pages` options into the changes made to each input.
+ * Rotations given several times for an input add up; pages may only be given once per input.
+ * @param inputs The inputs, in merge order.
+ * @param order The options of the merge.
+ * @returns One merge input per path, in the same order.
+ * @throws anyhow::Error if an option is malformed, names no input or lists missing pages.
+ */
+fn merge_inputs_from_options(inputs: &[String], order: &MergeOrder) -> Result<Vec<MergeInput>> {
+    let mut merge_inputs: Vec<MergeInput> =
+        inputs.iter().map(|path| MergeInput::new(path)).collect();
+
+    for spec in &order.rotate {
+        let (target, angle) = split_input_spec(spec, "--rotate")?;
+        let angle = angle
+            .parse::<i64>()
+            .ok()
+            .filter(|angle| angle % 90 == 0)
+            .ok_or_else(|| {
+                anyhow!(tr!(
+                    "Invalid rotation: {angle} (must be a multiple of 90)",
+                    angle = angle
+                ))
+            })?;
+        for i in find_inputs(inputs, target)? {
+            merge_inputs[i].rotation += angle;
+        }
+    }
+
+    for spec in &order.pages {
+        let (target, pages) = split_input_spec(spec, "--pages")?;
+        for i in find_inputs(inputs, target)? {
+            let input = &mut merge_inputs[i];
+            if input.pages.is_some() {
+                bail!(tr!(
+                    "--pages is given twice for '{path}'",
+                    path = input.path
+                ));
+            }
+            let page_count = inspect_document(&input.path)
+                .ok()
+                .map(|info| info.page_count());
+            let selected = parse_page_sequence(pages, page_count)
+                .with_context(|| tr!("Invalid --pages for '{path}'", path = input.path))?;
+            input.pages = Some(selected);
+        }
+    }
+
+    Ok(merge_inputs)
 }
 
-fn handle_merge(inputs: Vec<String>, output: String) -> Result<()> {
+fn handle_merge(
+    mut inputs: Vec<String>,
+    output: Option<String>,
+    open: bool,
+    order: MergeOrder,
+    flags: OutputFlags,
+) -> Result<()> {
+    let started = Instant::now();
     if inputs.len() < 2 {
-        bail!("You must provide at least two input PDF files");
+        bail!(tr!("You must provide at least two input PDF files"));
     }
 
     for p in &inputs {
         if !Path::new(p).exists() {
-            bail!("Input file does not exist: {}", p);
+            bail!(tr!("Input file does not exist: {path}", path = p));
         }
     }
+    let options = MergeOptions {
+        separator: order.separator.as_deref().map(Separator::parse),
+        source_footer: order.source_footer,
+        keep_tags: order.keep_tags,
+        name_conflicts: order.name_conflicts,
+    };
+    if let Some(Separator::File(path)) = &options.separator
+        && !Path::new(path).exists()
+    {
+        bail!(tr!("Separator file does not exist: {path}", path = path));
+    }
+    sort_inputs(&mut inputs, order.sort)?;
+    let merge_inputs = merge_inputs_from_options(&inputs, &order)?;
+    let output = output_or_default(output, DEFAULT_MERGE_OUTPUT, |candidate| {
+        Path::new(candidate).exists()
+    })?;
+
+    let operation = Operation::Merge {
+        inputs: inputs.clone(),
+        output: output.clone(),
+    };
+    check_space(&operation)?;
+    set_output_metadata(source_metadata(&flags.metadata, &operation)?);
+    // Counted before writing, as the output may replace an input
+    let expected_pages = flags
+        .verify
+        .then(|| merged_page_count(&merge_inputs, options.separator.as_ref()))
+        .transpose()?;
+    let prepared = prepare_outputs(&operation, &flags)?;
+    report(Progress::Start {
+        command: operation.command(),
+        inputs: inputs.clone(),
+        total: merge_inputs.len(),
+    });
+    let tag_warnings =
+        prepared.write_or_roll_back(|| pdf::merge_inputs(&merge_inputs, &options, &output))?;
+    warn_tags(&tag_warnings);
+    if let Some(pages) = expected_pages {
+        verify_outputs(&[(output.clone(), pages)])?;
+    }
+    report(Progress::Done {
+        command: operation.command(),
+        outputs: vec![output.clone()],
+        elapsed: started.elapsed(),
+    });
+    inform(&tr!(
+        "✅ Merged {count} files into '{output}'",
+        count = inputs.len(),
+        output = output
+    ));
+    report_timings(started);
+    if open {
+        open_outputs(&[output]);
+    }
+    Ok(())
+}
+
+/**
+ * Count the pages a merge writes: the pages taken from each input, and the separators put
+ * between them.
+ * @param inputs The inputs, in merge order.
+ * @param separator What is put between two inputs, if anything.
+ * @throws anyhow::Error if an input or the divider cannot be read.
+ */
+fn merged_page_count(inputs: &[MergeInput], separator: Option<&Separator>) -> Result<usize> {
+    let mut count = 0;
+    for input in inputs {
+        count += match &input.pages {
+            Some(pages) => pages.len(),
+            None => inspect_document(&input.path)?.page_count(),
+        };
+    }
+    let between = match separator {
+        None => 0,
+        Some(Separator::Blank | Separator::Title) => 1,
+        Some(Separator::File(path)) => inspect_document(path)?.page_count(),
+    };
+    Ok(count + between * inputs.len().saturating_sub(1))
+}
 
-    pdf::merge_pdfs(&inputs, &output)?;
-    println!("✅ Merged {} files into '{}'", inputs.len(), output);
+/**
+ * Read the outputs of a command back, for `--verify`, and check they have the pages written
+ * into them, each with content that can be decoded.
+ * @param outputs The files written, with the number of pages each should have.
+ * @throws anyhow::Error naming the first output that does not hold up; the outputs are left in
+ * place for inspection.
+ */
+fn verify_outputs(outputs: &[(String, usize)]) -> Result<()> {
+    for (output, pages) in outputs {
+        verify_output(output, *pages).context(tr!("Verification failed"))?;
+    }
+    match outputs {
+        [(output, pages)] => inform(&tr!(
+            "✅ Verified '{output}': {count} pages, each with its content",
+            output = output,
+            count = pages
+        )),
+        _ => inform(&tr!(
+            "✅ Verified {count} outputs: every page is there with its content",
+            count = outputs.len()
+        )),
+    }
     Ok(())
 }
 
-fn handle_delete(input: String, output: String, pages: String) -> Result<()> {
+/// Warn about the accessibility tags an operation could not keep.
+fn warn_tags(warnings: &[TagWarning]) {
+    for warning in warnings {
+        let message = match warning {
+            TagWarning::Dropped { path } => tr!(
+                "'{path}' is tagged for accessibility, but the tags are left out (pass --keep-tags to keep them)",
+                path = path
+            ),
+            TagWarning::Untagged { path } => tr!(
+                "'{path}' has no accessibility tags: its pages are untagged in the output",
+                path = path
+            ),
+            TagWarning::Partial { path } => tr!(
+                "Some accessibility tags of '{path}' could not be kept",
+                path = path
+            ),
+        };
+        warn(&format!("⚠ {}", message));
+    }
+}
+
+fn handle_delete(
+    input: String,
+    output: Option<String>,
+    pages: String,
+    open: bool,
+    keep_tags: bool,
+    flags: OutputFlags,
+) -> Result<()> {
+    let started = Instant::now();
     if !Path::new(&input).exists() {
-        bail!("Input file does not exist: {}", input);
+        bail!(tr!("Input file does not exist: {path}", path = input));
     }
 
-    let pages_to_delete = parse_page_ranges(&pages)?;
-    pdf::delete_pages(&input, &output, &pages_to_delete)?;
-    println!(
-        "✅ Deleted pages {} from '{}' and saved to '{}'",
-        pages, input, output
-    );
+    // A complement ("!2-5") is resolved against the page count; other pages are checked again
+    // when the document is loaded
+    let page_count = inspect_document(&input).ok().map(|info| info.page_count());
+    let pages_to_delete = parse_pages_to_delete(&pages, page_count).map_err(invalid_pages)?;
+    let output = output_or_default(output, DEFAULT_DELETE_OUTPUT, |candidate| {
+        Path::new(candidate).exists()
+    })?;
+    let operation = Operation::Delete {
+        input: input.clone(),
+        output: output.clone(),
+        pages: pages_to_delete.clone(),
+    };
+    check_space(&operation)?;
+    set_output_metadata(source_metadata(&flags.metadata, &operation)?);
+    let expected_pages = match (flags.verify, page_count) {
+        (false, _) => None,
+        (true, Some(count)) => Some(count),
+        (true, None) => Some(inspect_document(&input)?.page_count()),
+    }
+    .map(|count| count.saturating_sub(pages_to_delete.len()));
+    let prepared = prepare_outputs(&operation, &flags)?;
+    let tag_warnings = prepared.write_or_roll_back(|| {
+        delete_pages_with_tags(&input, &output, &pages_to_delete, keep_tags)
+    })?;
+    warn_tags(&tag_warnings);
+    if let Some(pages) = expected_pages {
+        verify_outputs(&[(output.clone(), pages)])?;
+    }
+    inform(&tr!(
+        "✅ Deleted pages {pages} from '{input}' and saved to '{output}'",
+        pages = format_page_ranges(&pages_to_delete),
+        input = input,
+        output = output
+    ));
+    report_timings(started);
+    if open {
+        open_outputs(&[output]);
+    }
     Ok(())
 }
 
-fn handle_split(input: String, output_prefix: String, pages: String, named: bool) -> Result<()> {
+fn handle_split(
+    input: String,
+    output_prefix: Option<String>,
+    pages: String,
+    named: bool,
+    allow_overlap: bool,
+    open: bool,
+    flags: OutputFlags,
+) -> Result<()> {
+    let started = Instant::now();
     if !Path::new(&input).exists() {
-        bail!("Input file does not exist: {}", input);
+        bail!(tr!("Input file does not exist: {path}", path = input));
     }
 
-    let output_files = if named {
-        pdf::split_pdfs_named(&input, &output_prefix, &pages)?
+    // Overlaps are usually typos, found before a long split rather than in its outputs
+    let segments = if named {
+        parse_named_segments(&pages)
     } else {
-        pdf::split_pdfs(&input, &output_prefix, &pages)?
+        parse_page_segments(&pages)
+    }
+    .map_err(invalid_pages)?;
+    // Checked whether overlaps are allowed or not: the second file would replace the first
+    segment_filenames(
+        &segments,
+        output_prefix.as_deref().unwrap_or(DEFAULT_SPLIT_PREFIX),
+    )?;
+    if !allow_overlap && let Some(overlap) = find_overlap(&segments) {
+        let first = segments[overlap.first].label();
+        let second = segments[overlap.second].label();
+        if let [page] = overlap.pages[..] {
+            bail!(tr!(
+                "Segments '{first}' and '{second}' both include page {page}: pass --allow-overlap to write it to both files",
+                first = first,
+                second = second,
+                page = page
+            ));
+        }
+        bail!(tr!(
+            "Segments '{first}' and '{second}' both include pages {pages}: pass --allow-overlap to write them to both files",
+            first = first,
+            second = second,
+            pages = format_page_ranges(&overlap.pages)
+        ));
+    }
+
+    let output_prefix = output_or_default(output_prefix, DEFAULT_SPLIT_PREFIX, |candidate| {
+        Operation::Split {
+            input: input.clone(),
+            output_prefix: candidate.to_string(),
+            segments: pages.clone(),
+            named,
+        }
+        .outputs()
+        .is_ok_and(|outputs| outputs.iter().any(|output| Path::new(output).exists()))
+    })?;
+    let operation = Operation::Split {
+        input: input.clone(),
+        output_prefix: output_prefix.clone(),
+        segments: pages.clone(),
+        named,
+    };
+    check_space(&operation)?;
+    set_output_metadata(source_metadata(&flags.metadata, &operation)?);
+    let prepared = prepare_outputs(&operation, &flags)?;
+    report(Progress::Start {
+        command: operation.command(),
+        inputs: vec![input.clone()],
+        total: segments.len(),
+    });
+    let output_files = prepared.write_or_roll_back(|| {
+        if named {
+            pdf::split_pdfs_named(&input, &output_prefix, &pages)
+        } else {
+            pdf::split_pdfs(&input, &output_prefix, &pages)
+        }
+    })?;
+    if flags.verify {
+        let expected: Vec<(String, usize)> = output_files
+            .iter()
+            .zip(&segments)
+            .map(|(output, segment)| (output.clone(), segment.get_pages().len()))
+            .collect();
+        verify_outputs(&expected)?;
+    }
+    report(Progress::Done {
+        command: operation.command(),
+        outputs: output_files.clone(),
+        elapsed: started.elapsed(),
+    });
+
+    inform(&tr!(
+        "✅ Split '{input}' into {count} files:",
+        input = input,
+        count = output_files.len()
+    ));
+    for file in &output_files {
+        inform(&format!("  - {}", file));
+    }
+    report_timings(started);
+    if open {
+        open_outputs(&output_files);
+    }
+    Ok(())
+}
+
+/**
+ * Rotate pages clockwise and save the result, checking the pages and angle before anything is
+ * written.
+ * @param input The input PDF file.
+ * @param output The output given on the command line, if any.
+ * @param pages The pages to rotate, every page when None.
+ * @param angle The clockwise angle, a multiple of 90.
+ * @param open Whether to open the result in the system PDF viewer.
+ * @param flags The output flags of the command.
+ * @throws anyhow::Error if the input cannot be read, a page does not exist, the angle is not a
+ * multiple of 90 or the output cannot be written.
+ */
+fn handle_rotate(
+    input: String,
+    output: Option<String>,
+    pages: Option<String>,
+    angle: i64,
+    open: bool,
+    flags: OutputFlags,
+) -> Result<()> {
+    let started = Instant::now();
+    if !Path::new(&input).exists() {
+        bail!(tr!("Input file does not exist: {path}", path = input));
+    }
+    let page_count = inspect_document(&input)?.page_count();
+    let pages = match pages {
+        Some(pages) => parse_page_set(&pages, Some(page_count)).map_err(invalid_pages)?,
+        None => (1..=page_count as u32).collect(),
     };
+    let rules = vec![RotationRule {
+        pages: pages.clone(),
+        angle,
+    }];
+    check_rules(&rules)?;
 
-    println!("✅ Split '{}' into {} files:", input, output_files.len());
-    for file in output_files {
-        println!("  - {}", file);
+    let output = output_or_default(output, DEFAULT_ROTATE_OUTPUT, |candidate| {
+        Path::new(candidate).exists()
+    })?;
+    let operation = Operation::Rotate {
+        input: input.clone(),
+        output: output.clone(),
+        rules: rules.clone(),
+    };
+    check_space(&operation)?;
+    set_output_metadata(source_metadata(&flags.metadata, &operation)?);
+    let prepared = prepare_outputs(&operation, &flags)?;
+    prepared.write_or_roll_back(|| pdf::rotate_pages(&input, &output, &rules))?;
+    if flags.verify {
+        verify_outputs(&[(output.clone(), page_count)])?;
+    }
+    inform(&tr!(
+        "✅ Rotated pages {pages} of '{input}' by {angle}° and saved to '{output}'",
+        pages = format_page_ranges(&pages),
+        angle = angle,
+        input = input,
+        output = output
+    ));
+    report_timings(started);
+    if open {
+        open_outputs(&[output]);
     }
     Ok(())
 }
 
-/// Parse page ranges like "3", "3-5", "1,3,5-7" into a Vec of page numbers
-fn parse_page_ranges(pages_str: &str) -> Result<Vec<u32>> {
-    let mut pages = Vec::new();
+/**
+ * Put a text note on a page and save the result, checking the page before anything is written.
+ * @param input The input PDF file.
+ * @param output The output given on the command line, if any.
+ * @param note The page, text and position of the note.
+ * @param open Whether to open the result in the system PDF viewer.
+ * @param flags The output flags of the command.
+ * @throws anyhow::Error if the input cannot be read, the page does not exist or the output cannot be written.
+ */
+fn handle_annotate(
+    input: String,
+    output: Option<String>,
+    note: TextNote,
+    open: bool,
+    flags: OutputFlags,
+) -> Result<()> {
+    let started = Instant::now();
+    if !Path::new(&input).exists() {
+        bail!(tr!("Input file does not exist: {path}", path = input));
+    }
+    if note.text.trim().is_empty() {
+        bail!(tr!("Note text cannot be empty"));
+    }
+    let page_count = inspect_document(&input)?.page_count();
+    if note.page == 0 || note.page as usize > page_count {
+        bail!(tr!(
+            "Invalid page number: {page}. PDF has {count} pages (1-{count})",
+            page = note.page,
+            count = page_count
+        ));
+    }
 
-    for part in pages_str.split(',') {
-        let part = part.trim();
-        if part.contains('-') {
-            let range_parts: Vec<&str> = part.split('-').collect();
-            if range_parts.len() != 2 {
-                bail!("Invalid page range format: {}", part);
-            }
-            let start: u32 = range_parts[0]
-                .parse()
-                .map_err(|_| anyhow::anyhow!("Invalid page number: {}", range_parts[0]))?;
-            let end: u32 = range_parts[1]
-                .parse()
-                .map_err(|_| anyhow::anyhow!("Invalid page number: {}", range_parts[1]))?;
-
-            if start > end {
-                bail!(
-                    "Invalid range: start page {} is greater than end page {}",
-                    start,
-                    end
-                );
-            }
+    let output = output_or_default(output, DEFAULT_ANNOTATE_OUTPUT, |candidate| {
+        Path::new(candidate).exists()
+    })?;
+    let operation = Operation::Annotate {
+        input: input.clone(),
+        output: output.clone(),
+        note: note.clone(),
+    };
+    check_space(&operation)?;
+    set_output_metadata(source_metadata(&flags.metadata, &operation)?);
+    let prepared = prepare_outputs(&operation, &flags)?;
+    prepared.write_or_roll_back(|| pdf::annotate_page(&input, &output, &note))?;
+    if flags.verify {
+        verify_outputs(&[(output.clone(), page_count)])?;
+    }
+    inform(&tr!(
+        "✅ Added a note on page {page} of '{input}' and saved to '{output}'",
+        page = note.page,
+        input = input,
+        output = output
+    ));
+    report_timings(started);
+    if open {
+        open_outputs(&[output]);
+    }
+    Ok(())
+}
+
+/**
+ * Use the output given on the command line, or the default one when none is given.
+ * The default name follows the overwrite policy from the config file, an explicit one is used as is.
+ * @param output The output given on the command line, if any.
+ * @param default The default output name or prefix of the command.
+ * @param taken Whether writing to a candidate name would replace an existing file.
+ * @returns The output name or prefix to write to.
+ * @throws anyhow::Error if the config file is invalid.
+ */
+fn output_or_default(
+    output: Option<String>,
+    default: &str,
+    taken: impl Fn(&str) -> bool,
+) -> Result<String> {
+    if let Some(output) = output {
+        return Ok(output);
+    }
+    let policy = OverwritePolicy::from_config(&Config::load()?)
+        .map_err(|e| anyhow!(e))
+        .context(tr!("Invalid overwrite policy in config file"))?;
+    Ok(policy.free_name(default, taken))
+}
 
-            for page in start..=end {
-                pages.push(page);
+/**
+ * Check the outputs of an operation before writing them, so that it does not fail halfway through saving.
+ * An output larger than the configured size is only reported, outputs that would not fit stop the command.
+ * @param operation The operation about to run.
+ * @throws anyhow::Error if the config file is invalid or there is not enough free space.
+ */
+fn check_space(operation: &Operation) -> Result<()> {
+    let check = SpaceCheck::from_config(&Config::load()?)
+        .map_err(|e| anyhow!(e))
+        .context(tr!("Invalid output size warning in config file"))?;
+    // Unreadable inputs are reported by the operation itself
+    let Ok(warnings) = check.warnings(operation) else {
+        return Ok(());
+    };
+    for warning in &warnings {
+        match warning {
+            SpaceWarning::NotEnoughSpace { .. } => bail!(describe_space_warning(warning)),
+            SpaceWarning::LargeOutput { .. } => {
+                warn(&format!("⚠ {}", describe_space_warning(warning)))
             }
-        } else {
-            let page: u32 = part
-                .parse()
-                .map_err(|_| anyhow::anyhow!("Invalid page number: {}", part))?;
-            pages.push(page);
         }
     }
+    Ok(())
+}
+
+/**
+ * Get the output paths ready before writing: refuse to write over an input unless `--in-place` is
+ * given, keep the files the outputs replace when a backup is asked for, and create the missing
+ * folders of the outputs. PreparedOutputs::write_or_roll_back undoes this when writing fails.
+ * `--backup` or the `backup` setting renames the replaced outputs and copies the inputs written over;
+ * `backup_in_place` in the config file copies the inputs only.
+ * @param operation The operation about to run.
+ * @param flags The output flags of the command.
+ * @returns What was changed, to undo if writing fails.
+ * @throws anyhow::Error if an output is an input without `--in-place`, the suffix or the config
+ * file is invalid, a backup cannot be made or a folder cannot be created.
+ */
+fn prepare_outputs(operation: &Operation, flags: &OutputFlags) -> Result<PreparedOutputs> {
+    let overwritten = operation.overwritten_inputs();
+    if !overwritten.is_empty() && !flags.in_place {
+        bail!(tr!(
+            "'{path}' is an input file: pass --in-place to write over it",
+            path = overwritten[0]
+        ));
+    }
+
+    let config = Config::load()?;
+    let suffix = match &flags.backup {
+        Some(suffix) => Some(parse_backup_suffix(suffix).map_err(|e| anyhow!(e))?),
+        None => backup_suffix_from_config(&config)
+            .map_err(|e| anyhow!(e))
+            .context(tr!("Invalid backup setting in config file"))?,
+    };
+    let backup_in_place = backup_in_place_from_config(&config)
+        .map_err(|e| anyhow!(e))
+        .context(tr!("Invalid backup setting in config file"))?;
+
+    let in_place_suffix = match &suffix {
+        Some(suffix) => Some(suffix.as_str()),
+        None => backup_in_place.then_some(DEFAULT_BACKUP_SUFFIX),
+    };
+    if let Some(in_place_suffix) = in_place_suffix {
+        for input in &overwritten {
+            let copy = backup_file(input, in_place_suffix)
+                .with_context(|| tr!("Could not back up '{path}'", path = input))?;
+            inform(&tr!(
+                "Backed up '{path}' to '{backup}'",
+                path = input,
+                backup = copy
+            ));
+        }
+    }
+    let prepared = operation.prepare_outputs(suffix.as_deref())?;
+    for (path, backup) in &prepared.backups {
+        inform(&tr!(
+            "Moved '{path}' to '{backup}'",
+            path = path,
+            backup = backup
+        ));
+    }
+    Ok(prepared)
+}
+
+/**
+ * Gather what the metadata options ask to write into the outputs.
+ * With `--deterministic`, the identifier is left out and the dates, those of the provenance
+ * receipt included, come from SOURCE_DATE_EPOCH when it is set.
+ * @param args The metadata options of the command.
+ * @returns The entries to set.
+ * @throws anyhow::Error if an identifier, a date, SOURCE_DATE_EPOCH or the ICC profile is invalid.
+ */
+fn output_metadata(args: MetadataArgs) -> Result<OutputMetadata> {
+    let mut id = match args.id.as_deref() {
+        None | Some("keep") => None,
+        Some("new") => Some(FileId::New),
+        Some(hex) => Some(parse_file_id(hex)?),
+    };
+    let mut dates = match args.dates.as_deref() {
+        None | Some("keep") => None,
+        Some("now") => Some(DocumentDates::at(&pdf_date(Local::now().fixed_offset()))),
+        Some(date) => Some(DocumentDates::at(&pdf_date_from_text(date)?)),
+    };
+    let mut receipt_date = Some(Local::now().fixed_offset());
+    if args.deterministic {
+        id = Some(FileId::Remove);
+        let epoch = std::env::var("SOURCE_DATE_EPOCH").ok();
+        dates = Some(match &epoch {
+            Some(epoch) => DocumentDates::at(&pdf_date_from_epoch(epoch)?),
+            None => DocumentDates::default(),
+        });
+        receipt_date = epoch.as_deref().map(epoch_date).transpose()?;
+    }
+    Ok(OutputMetadata {
+        entries: DocumentMetadata {
+            title: args.title,
+            author: args.author,
+            subject: args.subject,
+            keywords: args.keywords,
+            provenance: None,
+            id,
+            dates,
+            output_intent: args
+                .icc_profile
+                .as_deref()
+                .map(read_icc_profile)
+                .transpose()?,
+        },
+        keep_id: args.id.as_deref() == Some("keep"),
+        keep_dates: args.dates.as_deref() == Some("keep"),
+        provenance: !args.no_provenance,
+        receipt_date,
+    })
+}
+
+/**
+ * Read what `--id keep` and `--dates keep` copy from the first input, and hash the inputs for
+ * the provenance receipt, before the outputs are written and may replace them.
+ * @param metadata The metadata options of the command.
+ * @param operation The operation about to run.
+ * @returns The entries to write into the outputs.
+ * @throws anyhow::Error if an input cannot be loaded or read.
+ */
+fn source_metadata(metadata: &OutputMetadata, operation: &Operation) -> Result<DocumentMetadata> {
+    let sources: Vec<String> = operation.inputs().into_iter().map(String::from).collect();
+    let mut entries = metadata.entries.clone();
+    if metadata.provenance {
+        entries.provenance = Some(receipt(
+            operation.command(),
+            &sources,
+            metadata.receipt_date,
+        )?);
+    }
+    if metadata.keep_id || metadata.keep_dates {
+        let doc = load_document(&sources[0])?;
+        if metadata.keep_id {
+            entries.id = Some(file_id(&doc));
+        }
+        if metadata.keep_dates {
+            entries.dates = Some(document_dates(&doc));
+        }
+    }
+    Ok(entries)
+}
+
+/**
+ * Print the time spent loading, copying and saving, and the peak memory, when `--timings` is
+ * given. The report goes to stderr, so that it is not mixed with the list of outputs.
+ * @param started When the command started.
+ */
+fn report_timings(started: Instant) {
+    if !SHOW_TIMINGS.load(Ordering::Relaxed) {
+        return;
+    }
+    let timings = take_timings();
+    let memory = peak_memory().map_or_else(|| "?".to_string(), format_size);
+    eprintln!(
+        "{}",
+        tr!(
+            "⏱ load {load} • copy {copy} • save {save} • total {total} • peak memory {memory}",
+            load = format_elapsed(timings.load),
+            copy = format_elapsed(timings.copy),
+            save = format_elapsed(timings.save),
+            total = format_elapsed(started.elapsed()),
+            memory = memory
+        )
+    );
+}
+
+/**
+ * Print which locked inputs were opened with `--password-file`, and the line of the password
+ * that opened each, including those of a command that failed afterwards.
+ */
+pub fn report_unlocked() {
+    for unlocked in take_unlocked() {
+        inform(&tr!(
+            "🔓 Opened '{path}' with the password on line {line} of the password file",
+            path = unlocked.path,
+            line = unlocked.line
+        ));
+    }
+}
+
+/// Open the produced files in the system viewer. The operation already succeeded,
+/// so a viewer that fails to start is only reported as a warning.
+fn open_outputs(files: &[String]) {
+    for file in files {
+        if let Err(e) = open_in_viewer(file) {
+            warn(&tr!(
+                "⚠ Could not open '{path}': {error}",
+                path = file,
+                error = e
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::inspect::page_infos;
+    use clap::Parser;
+    use lopdf::Document;
+
+    fn run(args: &[&str]) -> Result<()> {
+        let cli = Cli::try_parse_from(["pdf-cutter"].iter().chain(args))?;
+        handle_command(Some(cli.command))
+    }
+
+    fn rotations(path: &str) -> Vec<i64> {
+        page_infos(&Document::load(path).unwrap())
+            .iter()
+            .map(|page| page.rotation)
+            .collect()
+    }
+
+    #[test]
+    fn test_json_is_an_option_of_merge_and_split() {
+        let parse = |args: &[&str]| Cli::try_parse_from(["pdf-cutter"].iter().chain(args));
+        assert!(parse(&["merge", "--json", "a.pdf", "b.pdf"]).is_ok());
+        assert!(parse(&["split", "--json", "-i", "a.pdf", "-p", "1"]).is_ok());
+        // Other commands print no events, so they do not take the option
+        assert!(parse(&["delete", "--json", "-i", "a.pdf", "-p", "1"]).is_err());
+        assert!(parse(&["rotate", "--json", "-i", "a.pdf", "-a", "90"]).is_err());
+        assert!(parse(&["info", "--json", "a.pdf"]).is_err());
+        assert!(parse(&["--json", "merge", "a.pdf", "b.pdf"]).is_err());
+    }
+
+    #[test]
+    fn test_report_formats() {
+        let parse = |args: &[&str]| Cli::try_parse_from(["pdf-cutter"].iter().chain(args));
+        for format in ["table", "json", "csv"] {
+            assert!(run(&["info", "--format", format, "tests/tests_pdf/c.pdf"]).is_ok());
+            assert!(run(&["links", "--format", format, "tests/tests_pdf/c.pdf"]).is_ok());
+        }
+        assert!(parse(&["info", "--format", "xml", "a.pdf"]).is_err());
+        // Commands writing PDF files print messages, not reports
+        assert!(parse(&["merge", "--format", "json", "a.pdf", "b.pdf"]).is_err());
+    }
+
+    #[test]
+    fn test_reporting_commands_write_no_output() {
+        let writes = |args: &[&str]| {
+            Cli::try_parse_from(["pdf-cutter"].iter().chain(args))
+                .unwrap()
+                .command
+                .output_args()
+                .is_some()
+        };
+        assert!(writes(&["merge", "a.pdf", "b.pdf"]));
+        assert!(writes(&["split", "-i", "a.pdf", "-p", "1"]));
+        assert!(writes(&["tui"]));
+        // Settings of the outputs in the config file are not checked for these
+        assert!(!writes(&["info", "a.pdf"]));
+        assert!(!writes(&["links", "a.pdf"]));
+
+        // The output settings are options of the commands writing files only
+        let parse = |args: &[&str]| Cli::try_parse_from(["pdf-cutter"].iter().chain(args));
+        let settings = parse(&["rotate", "--durable", "-i", "a.pdf", "-a", "90"]).unwrap();
+        assert!(settings.command.output_args().is_some_and(|s| s.durable));
+        let settings = parse(&["tui", "--no-object-streams"]).unwrap();
+        assert!(
+            settings
+                .command
+                .output_args()
+                .is_some_and(|s| s.no_object_streams)
+        );
+        assert!(parse(&["info", "--durable", "a.pdf"]).is_err());
+        assert!(parse(&["links", "--no-object-streams", "a.pdf"]).is_err());
+        assert!(parse(&["--durable", "merge", "a.pdf", "b.pdf"]).is_err());
+        // Passwords open the inputs of every command, so the option stays before any of them
+        assert!(parse(&["--password-file", "pw.txt", "info", "a.pdf"]).is_ok());
+    }
 
-    pages.sort();
-    pages.dedup();
-    Ok(pages)
+    #[test]
+    fn test_rotate_arguments() {
+        let output = std::env::temp_dir().join("pdfcutter_cli_rotate_args.pdf");
+        let output = output.to_string_lossy();
+        let input = "tests/tests_pdf/c.pdf";
+
+        // The angle is required and must be a number
+        assert!(Cli::try_parse_from(["pdf-cutter", "rotate", "-i", input]).is_err());
+        assert!(Cli::try_parse_from(["pdf-cutter", "rotate", "-i", input, "-a", "right"]).is_err());
+        // Counterclockwise angles are not taken for flags
+        assert!(Cli::try_parse_from(["pdf-cutter", "rotate", "-i", input, "-a", "-90"]).is_ok());
+
+        // Angles other than 90, 180 and 270, unknown pages and missing inputs write nothing
+        for args in [
+            ["-a", "45", "-p", "1"],
+            ["-a", "90", "-p", "99"],
+            ["-a", "90", "-p", "2-1"],
+        ] {
+            assert!(run(&[&["rotate", "-i", input, "-o", &output], &args[..]].concat()).is_err());
+            assert!(!Path::new(&*output).exists());
+        }
+        assert!(run(&["rotate", "-i", "missing.pdf", "-o", &output, "-a", "90"]).is_err());
+        assert!(!Path::new(&*output).exists());
+
+        let before = rotations(input);
+        run(&["rotate", "-i", input, "-o", &output, "-a", "-90", "-p", "2"]).unwrap();
+        let after = rotations(&output);
+        assert_eq!(after[0], before[0]);
+        assert_eq!(after[1], (before[1] + 270) % 360);
+        fs::remove_file(&*output).unwrap();
+    }
+
+    #[test]
+    fn test_failed_split_removes_created_folders() {
+        let directory = std::env::temp_dir().join("pdfcutter_cli_split_folders");
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        let prefix = directory.join("out").join("2024").join("part");
+
+        // The first segment is written before page 99 is found missing
+        let result = run(&[
+            "split",
+            "-i",
+            "tests/tests_pdf/c.pdf",
+            "-p",
+            "1,99",
+            "-o",
+            &prefix.to_string_lossy(),
+        ]);
+        assert!(result.is_err());
+        assert!(!directory.join("out").exists());
+
+        run(&[
+            "split",
+            "-i",
+            "tests/tests_pdf/c.pdf",
+            "-p",
+            "1,2",
+            "-o",
+            &prefix.to_string_lossy(),
+        ])
+        .unwrap();
+        assert!(directory.join("out").join("2024").is_dir());
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_rotate_in_place_with_backup() {
+        let directory = std::env::temp_dir().join("pdfcutter_cli_rotate_in_place");
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        let input = directory.join("scan.pdf");
+        fs::copy("tests/tests_pdf/c.pdf", &input).unwrap();
+        let input = input.to_string_lossy();
+        let before = rotations(&input);
+
+        // Writing over the input needs --in-place
+        assert!(run(&["rotate", "-i", &input, "-o", &input, "-a", "180"]).is_err());
+        assert_eq!(rotations(&input), before);
+
+        run(&[
+            "rotate",
+            "-i",
+            &input,
+            "-o",
+            &input,
+            "-a",
+            "180",
+            "--in-place",
+            "--backup=orig",
+        ])
+        .unwrap();
+        assert_eq!(rotations(&format!("{}.orig", input)), before);
+        let after = rotations(&input);
+        for (after, before) in after.iter().zip(&before) {
+            assert_eq!(*after, (before + 180) % 360);
+        }
+        fs::remove_dir_all(&directory).unwrap();
+    }
 }
@@ -1,6 +1,8 @@
 pub mod commands;
 pub mod handlers;
+pub mod prompt;
+pub mod report;
 
 // pub use commands::{Cli, Commands};
 pub use commands::Cli;
-pub use handlers::handle_command;
+pub use handlers::{handle_command, report_error, report_unlocked, set_quiet, set_timings, warn};
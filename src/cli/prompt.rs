@@ -0,0 +1,189 @@
+use crate::i18n::tr;
+use crate::pdf::output::expand_home;
+use anyhow::{Result, bail};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+
+/// Whether missing arguments can be asked for: both the input and the messages are on a
+/// terminal. Scripts and pipes keep the strict usage error.
+pub fn is_interactive() -> bool {
+    io::stdin().is_terminal() && io::stderr().is_terminal()
+}
+
+/// Turns raw mode off when the prompt ends, however it ends.
+struct RawMode;
+
+impl RawMode {
+    fn enable() -> io::Result<Self> {
+        enable_raw_mode()?;
+        Ok(Self)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+    }
+}
+
+/**
+ * Ask for a value on the terminal until it is valid.
+ * Enter with an empty line takes the default, Tab completes paths when a completion function
+ * is given, Esc or Ctrl+C cancels. The question and errors are written to stderr, so the
+ * output of the command stays clean.
+ * @param question The question, e.g. "Output file".
+ * @param default The value used for an empty answer, shown in brackets.
+ * @param complete Completions of the typed text, or None when Tab does nothing.
+ * @param validate Checks the answer and turns it into the value to use.
+ * @returns The validated value.
+ * @throws anyhow::Error if the prompt is cancelled or the terminal cannot be read.
+ */
+pub fn ask<T>(
+    question: &str,
+    default: Option<&str>,
+    complete: Option<fn(&str) -> Vec<String>>,
+    validate: impl Fn(&str) -> Result<T>,
+) -> Result<T> {
+    let label = match default {
+        Some(default) => format!("{} [{}]: ", question, default),
+        None => format!("{}: ", question),
+    };
+    loop {
+        let line = read_line(&label, complete)?;
+        let answer = match (line.trim(), default) {
+            ("", Some(default)) => default,
+            (answer, _) => answer,
+        };
+        match validate(answer) {
+            Ok(value) => return Ok(value),
+            Err(e) => eprintln!("❌ {:#}", e),
+        }
+    }
+}
+
+/**
+ * Read one line in raw mode, echoing it on stderr.
+ * @param label The text shown before the typed line.
+ * @param complete Completions of the typed text, for Tab.
+ * @returns The typed line.
+ * @throws anyhow::Error if the prompt is cancelled or the terminal cannot be read.
+ */
+fn read_line(label: &str, complete: Option<fn(&str) -> Vec<String>>) -> Result<String> {
+    let mut stderr = io::stderr();
+    let mut line = String::new();
+    let _raw = RawMode::enable()?;
+    write!(stderr, "{}", label)?;
+    stderr.flush()?;
+
+    loop {
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Enter => {
+                write!(stderr, "\r\n")?;
+                return Ok(line);
+            }
+            KeyCode::Esc => {
+                write!(stderr, "\r\n")?;
+                bail!(tr!("Cancelled"));
+            }
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                write!(stderr, "\r\n")?;
+                bail!(tr!("Cancelled"));
+            }
+            KeyCode::Char(c) => {
+                line.push(c);
+                write!(stderr, "{}", c)?;
+            }
+            KeyCode::Backspace if !line.is_empty() => {
+                line.pop();
+                write!(stderr, "\u{8} \u{8}")?;
+            }
+            KeyCode::Tab => {
+                let Some(complete) = complete else {
+                    continue;
+                };
+                let completions = complete(&line);
+                let prefix = common_prefix(&completions);
+                if prefix.len() > line.len() {
+                    write!(stderr, "{}", &prefix[line.len()..])?;
+                    line = prefix;
+                } else if completions.len() > 1 {
+                    // Nothing more in common: list the choices and type on
+                    write!(
+                        stderr,
+                        "\r\n{}\r\n{}{}",
+                        completions.join("  "),
+                        label,
+                        line
+                    )?;
+                }
+            }
+            _ => {}
+        }
+        stderr.flush()?;
+    }
+}
+
+/// Longest text every completion starts with.
+fn common_prefix(completions: &[String]) -> String {
+    let Some(first) = completions.first() else {
+        return String::new();
+    };
+    let mut prefix = first.as_str();
+    for completion in &completions[1..] {
+        while !completion.starts_with(prefix) {
+            let mut chars = prefix.chars();
+            chars.next_back();
+            prefix = chars.as_str();
+        }
+    }
+    prefix.to_string()
+}
+
+/**
+ * Complete a partially typed path with the folders and PDF files it may name.
+ * Hidden entries are only offered when the typed name starts with a dot.
+ * @param input The partial path (e.g., "out" or "~/Doc").
+ * @returns The sorted completions; folders end with a slash.
+ */
+pub fn complete_pdf_path(input: &str) -> Vec<String> {
+    let (parent, partial) = match input.rfind('/') {
+        Some(i) => input.split_at(i + 1),
+        None => ("", input),
+    };
+    let search_dir = if parent.is_empty() {
+        Path::new(".").to_path_buf()
+    } else {
+        expand_home(parent)
+    };
+    let Ok(entries) = fs::read_dir(search_dir) else {
+        return Vec::new();
+    };
+
+    let mut completions: Vec<String> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            if !name.starts_with(partial) || (name.starts_with('.') && !partial.starts_with('.')) {
+                return None;
+            }
+            if entry.path().is_dir() {
+                Some(format!("{}{}/", parent, name))
+            } else if name.to_lowercase().ends_with(".pdf") {
+                Some(format!("{}{}", parent, name))
+            } else {
+                None
+            }
+        })
+        .collect();
+    completions.sort();
+    completions
+}
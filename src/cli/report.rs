@@ -0,0 +1,250 @@
+use crate::pdf::inspect::{DocumentInfo, POINTS_PER_MM, PageInfo};
+use crate::pdf::links::{Link, LinkTarget};
+use crate::pdf::progress::json_string;
+
+/// Header row of `info --format csv`, one row per page follows.
+const INFO_CSV_HEADER: &str = "page,paper,orientation,width_mm,height_mm,rotation";
+
+/// Header row of `links --format csv`, one row per link follows.
+const LINKS_CSV_HEADER: &str = "page,type,target,target_page,broken";
+
+/// Fields of a page shared by the JSON and CSV reports, with the size as displayed.
+fn page_fields(page: &PageInfo) -> (Option<&'static str>, &'static str, String, String) {
+    let (width, height) = page.displayed_size();
+    let orientation = if page.is_landscape() {
+        "landscape"
+    } else {
+        "portrait"
+    };
+    (
+        page.paper_name(),
+        orientation,
+        format!("{:.1}", width / POINTS_PER_MM),
+        format!("{:.1}", height / POINTS_PER_MM),
+    )
+}
+
+/// Kind of a link target and the name, address, file or action it holds, for the reports.
+fn link_fields(link: &Link) -> (&'static str, Option<&str>, Option<u32>) {
+    match &link.target {
+        LinkTarget::Page(page) => ("page", None, *page),
+        LinkTarget::Named { name, page } => ("named", Some(name.as_str()), *page),
+        LinkTarget::Uri(uri) => ("uri", Some(uri.as_str()), None),
+        LinkTarget::File(path) => ("file", Some(path.as_str()), None),
+        LinkTarget::Other(action) => ("other", Some(action.as_str()), None),
+    }
+}
+
+fn json_or_null(value: Option<&str>) -> String {
+    value.map_or_else(|| "null".to_string(), json_string)
+}
+
+/**
+ * Quote a CSV field when it holds a comma, a quote or a line break, doubling its quotes.
+ * @param value The field.
+ * @returns The field as written in a CSV row.
+ */
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/**
+ * Describe a document as a JSON object on one line, for `info --format json`.
+ * e.g. {"page_count":1,"file_size":1024,"title":null,"provenance":null,"encrypted":false,
+ * "pages":[{"page":1,"paper":"A4","orientation":"portrait","width_mm":210.0,"height_mm":297.0,"rotation":0}]}
+ * @param info The summary of the document.
+ * @returns The JSON object, without a line break.
+ */
+pub fn info_json(info: &DocumentInfo) -> String {
+    let pages: Vec<String> = info
+        .pages
+        .iter()
+        .enumerate()
+        .map(|(index, page)| {
+            let (paper, orientation, width, height) = page_fields(page);
+            format!(
+                "{{\"page\":{},\"paper\":{},\"orientation\":{},\"width_mm\":{},\"height_mm\":{},\"rotation\":{}}}",
+                index + 1,
+                json_or_null(paper),
+                json_string(orientation),
+                width,
+                height,
+                page.rotation
+            )
+        })
+        .collect();
+    format!(
+        "{{\"page_count\":{},\"file_size\":{},\"title\":{},\"provenance\":{},\"encrypted\":{},\"pages\":[{}]}}",
+        info.page_count(),
+        info.file_size,
+        json_or_null(info.title.as_deref()),
+        json_or_null(info.provenance.as_deref()),
+        info.encrypted,
+        pages.join(",")
+    )
+}
+
+/**
+ * Describe the pages of a document as CSV, one row per page after a header row, for
+ * `info --format csv`. Unknown paper sizes are left empty.
+ * @param info The summary of the document.
+ * @returns The rows, each ending with a line break.
+ */
+pub fn info_csv(info: &DocumentInfo) -> String {
+    let mut csv = format!("{}\n", INFO_CSV_HEADER);
+    for (index, page) in info.pages.iter().enumerate() {
+        let (paper, orientation, width, height) = page_fields(page);
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            index + 1,
+            paper.unwrap_or_default(),
+            orientation,
+            width,
+            height,
+            page.rotation
+        ));
+    }
+    csv
+}
+
+/**
+ * Describe links as a JSON array on one line, for `links --format json`.
+ * e.g. [{"page":2,"type":"named","target":"chapter3","target_page":21,"broken":false}]
+ * @param links The links to list.
+ * @returns The JSON array, without a line break.
+ */
+pub fn links_json(links: &[&Link]) -> String {
+    let links: Vec<String> = links
+        .iter()
+        .map(|link| {
+            let (kind, target, target_page) = link_fields(link);
+            format!(
+                "{{\"page\":{},\"type\":{},\"target\":{},\"target_page\":{},\"broken\":{}}}",
+                link.page,
+                json_string(kind),
+                json_or_null(target),
+                target_page.map_or_else(|| "null".to_string(), |page| page.to_string()),
+                link.is_broken()
+            )
+        })
+        .collect();
+    format!("[{}]", links.join(","))
+}
+
+/**
+ * Describe links as CSV, one row per link after a header row, for `links --format csv`.
+ * @param links The links to list.
+ * @returns The rows, each ending with a line break.
+ */
+pub fn links_csv(links: &[&Link]) -> String {
+    let mut csv = format!("{}\n", LINKS_CSV_HEADER);
+    for link in links {
+        let (kind, target, target_page) = link_fields(link);
+        csv.push_str(&format!(
+            "{},{},{},{},{}\n",
+            link.page,
+            kind,
+            csv_field(target.unwrap_or_default()),
+            target_page.map(|page| page.to_string()).unwrap_or_default(),
+            link.is_broken()
+        ));
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn a4(rotation: i64) -> PageInfo {
+        PageInfo {
+            width: 595.3,
+            height: 841.9,
+            rotation,
+        }
+    }
+
+    #[test]
+    fn test_info_reports() {
+        let info = DocumentInfo {
+            pages: vec![a4(0), a4(90)],
+            file_size: 2048,
+            title: Some("Q3 \"final\"".to_string()),
+            provenance: None,
+            encrypted: false,
+        };
+        assert_eq!(
+            info_json(&info),
+            "{\"page_count\":2,\"file_size\":2048,\"title\":\"Q3 \\\"final\\\"\",\
+             \"provenance\":null,\"encrypted\":false,\"pages\":[\
+             {\"page\":1,\"paper\":\"A4\",\"orientation\":\"portrait\",\"width_mm\":210.0,\"height_mm\":297.0,\"rotation\":0},\
+             {\"page\":2,\"paper\":\"A4\",\"orientation\":\"landscape\",\"width_mm\":297.0,\"height_mm\":210.0,\"rotation\":90}]}"
+        );
+        assert_eq!(
+            info_csv(&info),
+            "page,paper,orientation,width_mm,height_mm,rotation\n\
+             1,A4,portrait,210.0,297.0,0\n\
+             2,A4,landscape,297.0,210.0,90\n"
+        );
+
+        // A size matching no paper is left out
+        let custom = DocumentInfo {
+            pages: vec![PageInfo {
+                width: 100.0,
+                height: 100.0,
+                rotation: 0,
+            }],
+            ..info
+        };
+        assert!(info_json(&custom).contains("\"paper\":null"));
+        assert!(info_csv(&custom).ends_with("\n1,,portrait,35.3,35.3,0\n"));
+    }
+
+    #[test]
+    fn test_links_reports() {
+        let links = [
+            Link {
+                page: 2,
+                target: LinkTarget::Named {
+                    name: "chapter3".to_string(),
+                    page: Some(21),
+                },
+            },
+            Link {
+                page: 3,
+                target: LinkTarget::Page(None),
+            },
+            Link {
+                page: 5,
+                target: LinkTarget::Uri("https://example.com/?a=1,2".to_string()),
+            },
+        ];
+        let links: Vec<&Link> = links.iter().collect();
+        assert_eq!(
+            links_json(&links),
+            "[{\"page\":2,\"type\":\"named\",\"target\":\"chapter3\",\"target_page\":21,\"broken\":false},\
+             {\"page\":3,\"type\":\"page\",\"target\":null,\"target_page\":null,\"broken\":true},\
+             {\"page\":5,\"type\":\"uri\",\"target\":\"https://example.com/?a=1,2\",\"target_page\":null,\"broken\":false}]"
+        );
+        assert_eq!(
+            links_csv(&links),
+            "page,type,target,target_page,broken\n\
+             2,named,chapter3,21,false\n\
+             3,page,,,true\n\
+             5,uri,\"https://example.com/?a=1,2\",,false\n"
+        );
+        assert_eq!(links_json(&[]), "[]");
+    }
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+}
@@ -0,0 +1,373 @@
+use crate::i18n::tr;
+use anyhow::{Context, Result, bail};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Environment variable overriding the location of the configuration file.
+pub const CONFIG_ENV: &str = "PDFCUTTER_CONFIG";
+
+/// Configuration file written on the first run of the TUI: every setting, commented out
+/// with its default value.
+pub const DEFAULT_CONFIG: &str = r#"# pdf-cutter settings: remove the # in front of a setting to change it.
+
+# Built-in presets: dark (default), light, high-contrast, color-blind, mono
+# theme = "dark"
+# Put "ERROR:" and "OK:" before messages (default: on with high-contrast, color-blind and mono)
+# message_prefixes = false
+
+# Available: en, fr (default: from LC_ALL, LC_MESSAGES or LANG)
+# locale = "en"
+
+# When a default output name is taken: counter (default), timestamp, replace
+# overwrite = "counter"
+
+# Warn before writing outputs larger than this size (B, KB, MB, GB or TB)
+# output_size_warning = "500 MB"
+
+# Keep files replaced by an output: true for the .bak suffix, or the suffix to use
+# backup = "bak"
+
+# Enable the backup when confirming to write over an input file
+# backup_in_place = false
+
+# Draw plain ASCII instead of emoji and symbols (default: only when the terminal needs it)
+# ascii = false
+
+# Stop a queue run at the first failing step, instead of running the steps that do not need its outputs
+# queue_stop_on_error = false
+
+# Ask before dropping selected files or typed pages, segments and output names (Ctrl+H, Esc)
+# confirm_discard = true
+
+# Style overrides, see the "Themes" section of the documentation
+# [colors]
+# error = "bold #d70000"
+
+# Folders pinned in the file browser
+# [favorites]
+# downloads = "~/Downloads"
+"#;
+
+/// User settings read from `config.toml`.
+///
+/// The file uses a small TOML subset: `key = value` lines, `[section]` headers
+/// and `#` comments. Keys inside a section are stored as `section.key`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    values: BTreeMap<String, String>,
+}
+
+impl Config {
+    /**
+     * Load the configuration file, if there is one.
+     * @returns The configuration, empty when the file does not exist.
+     * @throws anyhow::Error if the file exists but cannot be read or parsed.
+     */
+    pub fn load() -> Result<Self> {
+        let Some(path) = config_path() else {
+            return Ok(Self::default());
+        };
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let text = fs::read_to_string(&path)
+            .with_context(|| tr!("Failed to read config file '{path}'", path = path.display()))?;
+        Self::parse(&text)
+            .with_context(|| tr!("Invalid config file '{path}'", path = path.display()))
+    }
+
+    /**
+     * Parse the content of a configuration file.
+     * @param text The file content.
+     * @returns The configuration.
+     * @throws anyhow::Error naming the first line that is not a section, a comment or a `key = value` pair.
+     */
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut values = BTreeMap::new();
+        let mut section = String::new();
+
+        for (number, line) in text.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = name.trim().to_string();
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                bail!(tr!(
+                    "line {number}: expected `key = value`, got '{line}'",
+                    number = number + 1,
+                    line = line
+                ));
+            };
+            let key = key.trim();
+            if key.is_empty() {
+                bail!(tr!(
+                    "line {number}: missing key before '='",
+                    number = number + 1
+                ));
+            }
+
+            let key = if section.is_empty() {
+                key.to_string()
+            } else {
+                format!("{}.{}", section, key)
+            };
+            values.insert(key, unquote(value.trim()).to_string());
+        }
+
+        Ok(Self { values })
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    /**
+     * Read a `true` or `false` setting.
+     * @param key The setting.
+     * @returns The value, None when the setting is not set.
+     * @throws String if the value is neither true nor false.
+     */
+    pub fn get_bool(&self, key: &str) -> Result<Option<bool>, String> {
+        let Some(value) = self.get(key) else {
+            return Ok(None);
+        };
+        match value.trim().to_ascii_lowercase().as_str() {
+            "true" => Ok(Some(true)),
+            "false" => Ok(Some(false)),
+            _ => Err(tr!(
+                "Invalid value '{value}' for {key} (expected true or false)",
+                value = value,
+                key = key
+            )),
+        }
+    }
+
+    /// Every `key = value` pair of a section, with the section prefix removed.
+    pub fn section(&self, name: &str) -> Vec<(&str, &str)> {
+        let prefix = format!("{}.", name);
+        self.values
+            .iter()
+            .filter_map(|(key, value)| Some((key.strip_prefix(&prefix)?, value.as_str())))
+            .collect()
+    }
+}
+
+/**
+ * Replace a section of the configuration file, keeping the rest of the file as it was written.
+ * @param path The configuration file, created with its directory when missing.
+ * @param name The section name.
+ * @param entries The `key = value` pairs of the section; none removes the section.
+ * @throws anyhow::Error if the file cannot be read or written.
+ */
+pub fn save_section(path: &Path, name: &str, entries: &[(String, String)]) -> Result<()> {
+    let text = if path.exists() {
+        fs::read_to_string(path)
+            .with_context(|| tr!("Failed to read config file '{path}'", path = path.display()))?
+    } else {
+        String::new()
+    };
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)
+            .with_context(|| tr!("Failed to create '{path}'", path = directory.display()))?;
+    }
+    fs::write(path, replace_section(&text, name, entries)).with_context(|| {
+        tr!(
+            "Failed to write config file '{path}'",
+            path = path.display()
+        )
+    })
+}
+
+/**
+ * Write the default configuration file, with every setting commented out.
+ * @param path The configuration file path; its directory is created if needed.
+ * @throws anyhow::Error if the file already exists or cannot be written.
+ */
+pub fn create_default(path: &Path) -> Result<()> {
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)
+            .with_context(|| tr!("Failed to create '{path}'", path = directory.display()))?;
+    }
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(DEFAULT_CONFIG.as_bytes()))
+        .with_context(|| {
+            tr!(
+                "Failed to write config file '{path}'",
+                path = path.display()
+            )
+        })
+}
+
+/**
+ * Rewrite the content of a configuration file with new entries for one section.
+ * The section is removed from where it was and written again at the end of the file.
+ * @param text The current file content.
+ * @param name The section name.
+ * @param entries The `key = value` pairs of the section; none removes the section.
+ * @returns The new file content.
+ */
+fn replace_section(text: &str, name: &str, entries: &[(String, String)]) -> String {
+    let mut lines = Vec::new();
+    let mut in_section = false;
+    for line in text.lines() {
+        if let Some(header) = strip_comment(line)
+            .trim()
+            .strip_prefix('[')
+            .and_then(|l| l.strip_suffix(']'))
+        {
+            in_section = header.trim() == name;
+        }
+        if !in_section {
+            lines.push(line);
+        }
+    }
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+
+    let mut result = lines.join("\n");
+    if !entries.is_empty() {
+        if !result.is_empty() {
+            result.push_str("\n\n");
+        }
+        result.push_str(&format!("[{}]", name));
+        for (key, value) in entries {
+            result.push_str(&format!("\n{} = \"{}\"", key, value));
+        }
+    }
+    if !result.is_empty() {
+        result.push('\n');
+    }
+    result
+}
+
+/**
+ * Location of the configuration file: `$PDFCUTTER_CONFIG`, or `config.toml` in
+ * `$XDG_CONFIG_HOME/pdf-cutter` (defaulting to `~/.config/pdf-cutter`).
+ * @returns The path, or None when no home directory is known.
+ */
+pub fn config_path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os(CONFIG_ENV) {
+        return Some(PathBuf::from(path));
+    }
+
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("pdf-cutter").join("config.toml"))
+}
+
+/// Remove a `#` comment, unless the `#` is inside a quoted value (e.g. a hex color).
+fn strip_comment(line: &str) -> &str {
+    let mut in_quotes = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '#' if !in_quotes => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config = Config::parse(
+            r##"
+# Appearance
+theme = "light"
+backup_in_place = True
+queue_stop_on_error = maybe
+
+[colors]
+error = "#ff0000"   # a hex color
+title = bold blue
+"##,
+        )
+        .unwrap();
+
+        assert_eq!(config.get("theme"), Some("light"));
+        assert_eq!(config.get("colors.error"), Some("#ff0000"));
+        assert_eq!(
+            config.section("colors"),
+            vec![("error", "#ff0000"), ("title", "bold blue")]
+        );
+        assert_eq!(config.get("missing"), None);
+        assert_eq!(config.get_bool("backup_in_place"), Ok(Some(true)));
+        assert_eq!(config.get_bool("missing"), Ok(None));
+        assert!(config.get_bool("queue_stop_on_error").is_err());
+    }
+
+    #[test]
+    fn test_parse_config_errors() {
+        let error = Config::parse("theme = dark\nnot a pair").unwrap_err();
+        assert!(error.to_string().contains("line 2"));
+        assert!(Config::parse("= value").is_err());
+    }
+
+    #[test]
+    fn test_replace_section() {
+        let text = "theme = dark\n\n[favorites]\nold = \"/old\"\n\n[colors]\ntitle = blue\n";
+        let entries = vec![("Scans".to_string(), "~/Scans".to_string())];
+        let replaced = replace_section(text, "favorites", &entries);
+        assert_eq!(
+            replaced,
+            "theme = dark\n\n[colors]\ntitle = blue\n\n[favorites]\nScans = \"~/Scans\"\n"
+        );
+        let config = Config::parse(&replaced).unwrap();
+        assert_eq!(config.section("favorites"), vec![("Scans", "~/Scans")]);
+        assert_eq!(config.get("colors.title"), Some("blue"));
+
+        assert_eq!(
+            replace_section(&replaced, "favorites", &[]),
+            "theme = dark\n\n[colors]\ntitle = blue\n"
+        );
+        assert_eq!(
+            replace_section("", "favorites", &entries),
+            "[favorites]\nScans = \"~/Scans\"\n"
+        );
+    }
+
+    #[test]
+    fn test_create_default() {
+        // Every setting is commented out, so the defaults apply until one is edited
+        assert_eq!(Config::parse(DEFAULT_CONFIG).unwrap(), Config::default());
+
+        let directory = env::temp_dir().join("pdfcutter_default_config");
+        let _ = fs::remove_dir_all(&directory);
+        let path = directory.join("pdf-cutter").join("config.toml");
+        create_default(&path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), DEFAULT_CONFIG);
+
+        // An existing file is never replaced
+        fs::write(&path, "theme = \"light\"\n").unwrap();
+        assert!(create_default(&path).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "theme = \"light\"\n");
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
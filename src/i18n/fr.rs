@@ -0,0 +1,1961 @@
+//! French translations, keyed by the English message.
+
+pub const MESSAGES: &[(&str, &str)] = &[
+    ("File not found: {path}", "Fichier introuvable : {path}"),
+    ("Invalid PDF file: {path}", "Fichier PDF invalide : {path}"),
+    (
+        "No PDF files found in {path}",
+        "Aucun fichier PDF trouvé dans {path}",
+    ),
+    (
+        "Cannot read folder {path}: {reason}",
+        "Impossible de lire le dossier {path} : {reason}",
+    ),
+    (
+        "Still checking {path}, please wait",
+        "Vérification de {path} en cours, veuillez patienter",
+    ),
+    (
+        "Invalid output name '{name}': {reason}",
+        "Nom de sortie invalide '{name}' : {reason}",
+    ),
+    (
+        "Invalid page range: {input}",
+        "Plage de pages invalide : {input}",
+    ),
+    (
+        "Page {page} is out of range (document has {page_count} pages)",
+        "La page {page} est hors limites (le document a {page_count} pages)",
+    ),
+    (
+        "Cannot delete all {page_count} pages of the document",
+        "Impossible de supprimer les {page_count} pages du document",
+    ),
+    (
+        "Not enough files for merge (need at least 2, got {count})",
+        "Pas assez de fichiers pour la fusion (au moins 2 requis, {count} fournis)",
+    ),
+    (
+        "No file selected (this operation needs exactly 1)",
+        "Aucun fichier sélectionné (cette opération en demande exactement 1)",
+    ),
+    (
+        "Too many files (this operation needs exactly 1, got {count})",
+        "Trop de fichiers (cette opération en demande exactement 1, {count} fournis)",
+    ),
+    (
+        "PDF operation failed: {source}",
+        "Échec de l'opération PDF : {source}",
+    ),
+    ("empty path", "chemin vide"),
+    (
+        "use the output directory for folders",
+        "utilisez le dossier de sortie pour les dossiers",
+    ),
+    (
+        "control characters are not allowed",
+        "les caractères de contrôle sont interdits",
+    ),
+    ("not a file name", "ce n'est pas un nom de fichier"),
+    ("the name is empty", "le nom est vide"),
+    ("the name is too long", "le nom est trop long"),
+    ("Merge {inputs} → {output}", "Fusionner {inputs} → {output}"),
+    (
+        "Delete pages {pages} from {input} → {output}",
+        "Supprimer les pages {pages} de {input} → {output}",
+    ),
+    (
+        "Split {input} into {segments} → {prefix}_*.pdf",
+        "Découper {input} en {segments} → {prefix}_*.pdf",
+    ),
+    (
+        "Rotate {input} {rules} → {output}",
+        "Pivoter {input} : {rules} → {output}",
+    ),
+    ("{pages} by {angle}°", "{pages} de {angle}°"),
+    ("all pages", "toutes les pages"),
+    ("pages {pages}", "pages {pages}"),
+    (
+        "Extract pages {pages} from {input} → {output}",
+        "Extraire les pages {pages} de {input} → {output}",
+    ),
+    ("?: Keys", "?: Touches"),
+    ("caused by:", "causé par :"),
+    ("No file selected", "Aucun fichier sélectionné"),
+    (
+        "Cannot read the page count of the document",
+        "Impossible de lire le nombre de pages du document",
+    ),
+    (
+        "Please specify pages to extract",
+        "Indiquez les pages à extraire",
+    ),
+    (
+        "Please specify pages to delete",
+        "Indiquez les pages à supprimer",
+    ),
+    (
+        "Please specify page segments",
+        "Indiquez les segments de pages",
+    ),
+    (
+        "Failed to merge PDFs: {error}",
+        "Échec de la fusion des PDF : {error}",
+    ),
+    (
+        "Failed to rotate pages: {error}",
+        "Échec de la rotation des pages : {error}",
+    ),
+    (
+        "Failed to extract pages: {error}",
+        "Échec de l'extraction des pages : {error}",
+    ),
+    (
+        "Failed to delete pages: {error}",
+        "Échec de la suppression des pages : {error}",
+    ),
+    (
+        "Failed to split PDF: {error}",
+        "Échec du découpage du PDF : {error}",
+    ),
+    (
+        "✅ Successfully merged {count} files into '{output}'",
+        "✅ {count} fichiers fusionnés dans '{output}'",
+    ),
+    (
+        "✅ Successfully rotated {count} pages of '{input}' by {angle}° and saved to '{output}'",
+        "✅ {count} pages de '{input}' pivotées de {angle}° et enregistrées dans '{output}'",
+    ),
+    (
+        "✅ Successfully extracted pages {pages} from '{input}' and saved to '{output}'",
+        "✅ Pages {pages} extraites de '{input}' et enregistrées dans '{output}'",
+    ),
+    (
+        "✅ Successfully deleted pages {pages} from '{input}' and saved to '{output}'",
+        "✅ Pages {pages} supprimées de '{input}', résultat enregistré dans '{output}'",
+    ),
+    (
+        "✅ Successfully split PDF into {count} files",
+        "✅ PDF découpé en {count} fichiers",
+    ),
+    (
+        "The queue is empty: press 'a' in a configuration screen to add an operation",
+        "La file est vide : appuyez sur 'a' dans un écran de configuration pour ajouter une opération",
+    ),
+    (
+        "Failed to read pages: {error}",
+        "Impossible de lire les pages : {error}",
+    ),
+    (
+        "Copied path to clipboard",
+        "Chemin copié dans le presse-papiers",
+    ),
+    (
+        "Could not copy path: {error}",
+        "Impossible de copier le chemin : {error}",
+    ),
+    ("Start: Merge PDFs", "Démarrer : Fusionner des PDF"),
+    ("Start: Delete pages", "Démarrer : Supprimer des pages"),
+    ("Start: Split pages", "Démarrer : Découper des pages"),
+    ("Start: Rotate pages", "Démarrer : Pivoter des pages"),
+    ("Start: Extract pages", "Démarrer : Extraire des pages"),
+    ("Start: Compress PDF", "Démarrer : Compresser un PDF"),
+    ("Start", "Démarrer"),
+    ("Add a file", "Ajouter un fichier"),
+    (
+        "Toggle named segments",
+        "Activer/désactiver les segments nommés",
+    ),
+    ("Change output directory", "Changer le dossier de sortie"),
+    (
+        "Pick pages in the page grid",
+        "Choisir les pages dans la grille",
+    ),
+    ("Show operation queue", "Afficher la file d'opérations"),
+    ("Run operation queue", "Lancer la file d'opérations"),
+    (
+        "Switch theme (dark, light, high contrast, color-blind, mono)",
+        "Changer de thème (sombre, clair, contraste élevé, daltonien, mono)",
+    ),
+    ("Show logs", "Afficher les journaux"),
+    ("Help", "Aide"),
+    ("Back to main menu", "Retour au menu principal"),
+    ("Quit", "Quitter"),
+    ("Theme: {name}", "Thème : {name}"),
+    ("Opened '{path}'", "'{path}' ouvert"),
+    (
+        "Could not open '{path}': {error}",
+        "Impossible d'ouvrir '{path}' : {error}",
+    ),
+    ("Command palette", "Palette de commandes"),
+    ("Keys of the current screen", "Touches de l'écran actuel"),
+    ("Log viewer", "Journaux"),
+    (
+        "Quit (asks for confirmation)",
+        "Quitter (demande confirmation)",
+    ),
+    ("Type / Backspace", "Saisie / Retour arrière"),
+    ("Edit the field", "Modifier le champ"),
+    ("Enter / Tab", "Entrée / Tab"),
+    ("Confirm", "Valider"),
+    ("Stop editing", "Arrêter la saisie"),
+    (
+        "Edit the directory (~ is expanded)",
+        "Modifier le dossier (~ est développé)",
+    ),
+    (
+        "Complete, press again to cycle",
+        "Compléter, appuyer à nouveau pour parcourir",
+    ),
+    ("Enter", "Entrée"),
+    (
+        "Save the directory (it must exist)",
+        "Enregistrer le dossier (il doit exister)",
+    ),
+    ("Cancel", "Annuler"),
+    ("Navigate the menu", "Parcourir le menu"),
+    ("Select the entry", "Choisir l'entrée"),
+    ("Select an entry directly", "Choisir une entrée directement"),
+    ("Select a file", "Sélectionner un fichier"),
+    (
+        "Type the path of a file to add",
+        "Saisir le chemin d'un fichier à ajouter",
+    ),
+    ("Backspace", "Retour arrière"),
+    ("Tab + folder", "Tab + dossier"),
+    (
+        "Add all the PDFs of a folder (merge)",
+        "Ajouter tous les PDF d'un dossier (fusion)",
+    ),
+    (
+        "Edit the path of the selected file",
+        "Modifier le chemin du fichier sélectionné",
+    ),
+    ("Remove the selected file", "Retirer le fichier sélectionné"),
+    ("Reorder files", "Réordonner les fichiers"),
+    ("Enter / →", "Entrée / →"),
+    ("Continue to the configuration", "Passer à la configuration"),
+    ("Back to the main menu", "Retour au menu principal"),
+    (
+        "Move the selected file up or down",
+        "Monter ou descendre le fichier sélectionné",
+    ),
+    (
+        "Edit the output filename",
+        "Modifier le nom du fichier de sortie",
+    ),
+    ("Edit the output directory", "Modifier le dossier de sortie"),
+    ("Merge", "Fusionner"),
+    (
+        "Add to the operation queue",
+        "Ajouter à la file d'opérations",
+    ),
+    (
+        "Back to the file selection",
+        "Retour à la sélection des fichiers",
+    ),
+    ("Edit the pages to delete", "Modifier les pages à supprimer"),
+    ("Delete the pages", "Supprimer les pages"),
+    ("Edit the page segments", "Modifier les segments de pages"),
+    ("Space", "Espace"),
+    ("Edit the output prefix", "Modifier le préfixe de sortie"),
+    ("Split", "Découper"),
+    ("Select a rule", "Sélectionner une règle"),
+    (
+        "Edit the pages of the rule (empty: every page)",
+        "Modifier les pages de la règle (vide : toutes les pages)",
+    ),
+    (
+        "Change the angle of the rule (90°, 180°, 270°)",
+        "Changer l'angle de la règle (90°, 180°, 270°)",
+    ),
+    ("R / Space", "R / Espace"),
+    (
+        "Cycle the angle of the rule",
+        "Faire tourner l'angle de la règle",
+    ),
+    ("Add a rule", "Ajouter une règle"),
+    ("Remove the selected rule", "Retirer la règle sélectionnée"),
+    (
+        "Pick the pages of the rule in the page grid",
+        "Choisir les pages de la règle dans la grille",
+    ),
+    ("Rotate", "Pivoter"),
+    (
+        "Edit the pages to extract (written in the order typed)",
+        "Modifier les pages à extraire (écrites dans l'ordre saisi)",
+    ),
+    ("Extract", "Extraire"),
+    ("Arrows", "Flèches"),
+    ("Move between pages", "Se déplacer entre les pages"),
+    ("Home / End", "Début / Fin"),
+    ("Alt+Home / Alt+End", "Alt+Début / Alt+Fin"),
+    ("First / last page", "Première / dernière page"),
+    (
+        "Toggle the current page",
+        "Cocher/décocher la page actuelle",
+    ),
+    ("Shift+Arrows", "Maj+Flèches"),
+    (
+        "Select a range from the last toggled page",
+        "Sélectionner une plage depuis la dernière page cochée",
+    ),
+    (
+        "Select or clear every page",
+        "Tout sélectionner ou tout désélectionner",
+    ),
+    ("Apply the selection", "Appliquer la sélection"),
+    ("Select a step", "Sélectionner une étape"),
+    ("X / Del", "X / Suppr"),
+    ("Remove the selected step", "Retirer l'étape sélectionnée"),
+    (
+        "Clear the queue, or cancel a run",
+        "Vider la file, ou annuler une exécution",
+    ),
+    ("Enter / R", "Entrée / R"),
+    ("Run every step", "Lancer toutes les étapes"),
+    (
+        "Cancel a run, or back to the main menu",
+        "Annuler l'exécution, ou retour au menu principal",
+    ),
+    ("Select an output file", "Sélectionner un fichier de sortie"),
+    (
+        "Open it in the system viewer",
+        "Ouvrir dans la visionneuse du système",
+    ),
+    (
+        "Copy its path, or the details of a failure, to the clipboard",
+        "Copier son chemin, ou les détails d'un échec, dans le presse-papiers",
+    ),
+    (
+        "Run the same operation on it",
+        "Lui appliquer la même opération",
+    ),
+    (
+        "Merge, delete, split, rotate, extract, compress or watermark it",
+        "Le fusionner, supprimer, découper, pivoter, extraire, compresser ou filigraner",
+    ),
+    ("Enter / Esc", "Entrée / Échap"),
+    ("Scroll back / forward", "Remonter / descendre"),
+    (
+        "Oldest / newest line",
+        "Ligne la plus ancienne / la plus récente",
+    ),
+    (
+        "Cycle the level filter (error, warn, info, debug)",
+        "Changer le niveau affiché (error, warn, info, debug)",
+    ),
+    ("Clear the log", "Vider le journal"),
+    ("L / Esc", "L / Échap"),
+    ("Back to the previous screen", "Retour à l'écran précédent"),
+    ("Scroll", "Défiler"),
+    ("PgUp / PgDn", "PgPréc / PgSuiv"),
+    ("Scroll by a page", "Défiler d'une page"),
+    ("Next / previous section", "Section suivante / précédente"),
+    ("Top / bottom", "Haut / bas"),
+    ("N / Esc", "N / Échap"),
+    ("Stay", "Rester"),
+    ("q / Esc", "q / Échap"),
+    ("Esc", "Échap"),
+    ("Main menu", "Menu principal"),
+    ("File selection", "Sélection des fichiers"),
+    ("Merge configuration", "Configuration de la fusion"),
+    ("Delete configuration", "Configuration de la suppression"),
+    ("Split configuration", "Configuration du découpage"),
+    ("Rotate configuration", "Configuration de la rotation"),
+    ("Extract configuration", "Configuration de l'extraction"),
+    ("Compress configuration", "Configuration de la compression"),
+    ("Page grid", "Grille de pages"),
+    ("Operation queue", "File d'opérations"),
+    ("Result", "Résultat"),
+    ("Exit confirmation", "Confirmation de sortie"),
+    ("🧭 Getting started", "🧭 Pour commencer"),
+    (
+        "Pick an operation, add its input files, then configure and run it.",
+        "Choisissez une opération, ajoutez ses fichiers, puis configurez-la et lancez-la.",
+    ),
+    ("🔗 Merge PDFs", "🔗 Fusionner des PDF"),
+    (
+        "Combine several PDF files into one, in the order of the list.",
+        "Réunit plusieurs fichiers PDF en un seul, dans l'ordre de la liste.",
+    ),
+    ("✂️  Delete pages", "✂️  Supprimer des pages"),
+    (
+        "Remove pages from a PDF and save the rest as a new file.",
+        "Retire des pages d'un PDF et enregistre le reste dans un nouveau fichier.",
+    ),
+    ("🔪 Split pages", "🔪 Découper des pages"),
+    (
+        "Cut a PDF into several files, one per page segment (optionally named).",
+        "Découpe un PDF en plusieurs fichiers, un par segment (éventuellement nommé).",
+    ),
+    ("🔄 Rotate pages", "🔄 Pivoter des pages"),
+    (
+        "Turn some or all pages by 90°, 180° or 270°.",
+        "Fait pivoter tout ou partie des pages de 90°, 180° ou 270°.",
+    ),
+    ("📑 Extract pages", "📑 Extraire des pages"),
+    (
+        "Copy selected pages into a new PDF.",
+        "Copie les pages choisies dans un nouveau PDF.",
+    ),
+    ("🔢 Page grid", "🔢 Grille de pages"),
+    (
+        "Pick the pages to delete, rotate, extract or watermark instead of typing them.",
+        "Choisissez les pages à supprimer, pivoter, extraire ou filigraner sans les saisir.",
+    ),
+    ("📋 Operation queue", "📋 File d'opérations"),
+    (
+        "Queue configured operations with A, then run them one after the other.",
+        "Ajoutez des opérations configurées avec A, puis lancez-les à la suite.",
+    ),
+    ("✅ Results", "✅ Résultats"),
+    (
+        "Open, copy or keep working on the files an operation wrote.",
+        "Ouvrez, copiez ou retravaillez les fichiers écrits par une opération.",
+    ),
+    ("📜 Logs", "📜 Journaux"),
+    (
+        "See what was loaded and saved, and the full cause of a failure.",
+        "Ce qui a été chargé et enregistré, et la cause complète d'un échec.",
+    ),
+    ("Configuration", "Configuration"),
+    ("Run", "Exécution"),
+    ("Input", "Saisie"),
+    ("ERROR: {error}", "ERREUR : {error}"),
+    ("(current directory)", "(dossier courant)"),
+    (
+        "Output Directory (Tab: complete)",
+        "Dossier de sortie (Tab : compléter)",
+    ),
+    ("Output Directory", "Dossier de sortie"),
+    (
+        "Output Directory (match {index}/{count})",
+        "Dossier de sortie (choix {index}/{count})",
+    ),
+    ("⚠ {path} already exists", "⚠ {path} existe déjà"),
+    (
+        "⚠ {path} • {count} files already exist",
+        "⚠ {path} • {count} fichiers existent déjà",
+    ),
+    ("Directory: {directory}", "Dossier : {directory}"),
+    (
+        "Terminal too small: {width}×{height}\nNeed at least {min_width}×{min_height}",
+        "Terminal trop petit : {width}×{height}\nIl faut au moins {min_width}×{min_height}",
+    ),
+    ("1. 🔗 Merge PDFs", "1. 🔗 Fusionner des PDF"),
+    ("2. ✂️  Delete Pages", "2. ✂️  Supprimer des pages"),
+    ("3. 🔪  Split Pages", "3. 🔪  Découper des pages"),
+    ("4. 🔄 Rotate Pages", "4. 🔄 Pivoter des pages"),
+    ("5. 📑 Extract Pages", "5. 📑 Extraire des pages"),
+    ("6. 🗜️  Compress PDF", "6. 🗜️  Compresser un PDF"),
+    ("9. ❓ Help", "9. ❓ Aide"),
+    ("q. 🚪 Exit", "q. 🚪 Quitter"),
+    (
+        "8. 📋 Operation Queue ({count})",
+        "8. 📋 File d'opérations ({count})",
+    ),
+    ("Select Operation", "Choisir une opération"),
+    (
+        "↑↓: Navigate • Enter: Select • 1-9: Direct select • Ctrl+K: Commands • q: Quit",
+        "↑↓ : Parcourir • Entrée : Choisir • 1-9 : Choix direct • Ctrl+K : Commandes • q : Quitter",
+    ),
+    ("📄 Select PDFs to Merge", "📄 Choisir les PDF à fusionner"),
+    (
+        "📄 Select PDF for Page Deletion",
+        "📄 Choisir le PDF dont supprimer des pages",
+    ),
+    ("📄 Select PDF to Rotate", "📄 Choisir le PDF à pivoter"),
+    (
+        "📄 Select PDF to Extract Pages From",
+        "📄 Choisir le PDF dont extraire des pages",
+    ),
+    ("📄 File Selection", "📄 Sélection des fichiers"),
+    ("Selected Files", "Fichiers sélectionnés"),
+    ("Added 1 PDF file", "1 fichier PDF ajouté"),
+    ("Title", "Titre"),
+    ("Author", "Auteur"),
+    ("Subject", "Sujet"),
+    ("Keywords", "Mots-clés"),
+    ("Creator", "Créateur"),
+    ("Producer", "Producteur"),
+    ("Created", "Créé le"),
+    ("Modified", "Modifié le"),
+    ("Other", "Autre"),
+    ("Pages:", "Pages :"),
+    ("Size:", "Taille :"),
+    ("Encrypted:", "Chiffré :"),
+    ("yes", "oui"),
+    ("no", "non"),
+    ("Page sizes:", "Formats de page :"),
+    ("No metadata", "Aucune métadonnée"),
+    ("First page:", "Première page :"),
+    ("(no text found)", "(aucun texte trouvé)"),
+    ("Preview the selected file", "Aperçu du fichier sélectionné"),
+    (
+        "Move the selected file to the top or bottom",
+        "Placer le fichier sélectionné en premier ou en dernier",
+    ),
+    (
+        "Move file to the top of the merge order",
+        "Placer le fichier en tête de la fusion",
+    ),
+    (
+        "Move file to the bottom of the merge order",
+        "Placer le fichier en fin de fusion",
+    ),
+    ("Added {count} PDF files", "{count} fichiers PDF ajoutés"),
+    (
+        "📁 Add PDFs from a folder",
+        "📁 Ajouter les PDF d'un dossier",
+    ),
+    (
+        "No PDF files in {directory}",
+        "Aucun fichier PDF dans {directory}",
+    ),
+    (
+        "1 PDF file in {directory}",
+        "1 fichier PDF dans {directory}",
+    ),
+    (
+        "{count} PDF files in {directory}",
+        "{count} fichiers PDF dans {directory}",
+    ),
+    ("(subfolders included)", "(sous-dossiers compris)"),
+    ("… and {count} more", "… et {count} de plus"),
+    (
+        "Enter: Add all • R: Include subfolders • Esc: Cancel",
+        "Entrée : Tout ajouter • R : Inclure les sous-dossiers • Échap : Annuler",
+    ),
+    (
+        "Enter: Add all • R: Leave out subfolders • Esc: Cancel",
+        "Entrée : Tout ajouter • R : Exclure les sous-dossiers • Échap : Annuler",
+    ),
+    (
+        "{spinner} {file} — validating…",
+        "{spinner} {file} — vérification…",
+    ),
+    ("✗ {file} — not a valid PDF", "✗ {file} — PDF invalide"),
+    (
+        "Enter file path (Enter to add, Esc to cancel)",
+        "Chemin du fichier (Entrée pour ajouter, Échap pour annuler)",
+    ),
+    (
+        "File path (Tab add file)",
+        "Chemin du fichier (Tab : ajouter un fichier)",
+    ),
+    (
+        "Enter: Add file | Esc: Cancel",
+        "Entrée : Ajouter le fichier | Échap : Annuler",
+    ),
+    (
+        "Edit file path (Enter to save, Esc to cancel)",
+        "Modifier le chemin (Entrée pour enregistrer, Échap pour annuler)",
+    ),
+    (
+        "Enter: Save file | Esc: Cancel",
+        "Entrée : Enregistrer le fichier | Échap : Annuler",
+    ),
+    (
+        "↑/↓: Navigate • Tab: Add file • b: Browse • e: Edit • p: Preview • Backspace: Remove • Alt+↑/↓: Reorder • Enter: Next • ?: Keys",
+        "↑/↓ : Parcourir • Tab : Ajouter • b : Explorer • e : Modifier • p : Aperçu • Retour arrière : Retirer • Alt+↑/↓ : Réordonner • Entrée : Suivant • ?: Touches",
+    ),
+    (
+        "↑/↓: Navigate • Tab: Add file • b: Browse • e: Edit • p: Preview • Backspace: Remove • Enter: Next • Esc: Back • ?: Keys",
+        "↑/↓ : Parcourir • Tab : Ajouter • b : Explorer • e : Modifier • p : Aperçu • Retour arrière : Retirer • Entrée : Suivant • Échap : Retour • ?: Touches",
+    ),
+    ("🔗 Merge Configuration", "🔗 Configuration de la fusion"),
+    (
+        "✂️ Delete Configuration",
+        "✂️ Configuration de la suppression",
+    ),
+    ("🔄 Rotate Configuration", "🔄 Configuration de la rotation"),
+    (
+        "📑 Extract Configuration",
+        "📑 Configuration de l'extraction",
+    ),
+    ("🔪 Split Configuration", "🔪 Configuration du découpage"),
+    ("📋 Operation Result", "📋 Résultat de l'opération"),
+    (
+        "{file} — {pages} pages, {size}",
+        "{file} — {pages} pages, {size}",
+    ),
+    ("{file} — unreadable", "{file} — illisible"),
+    ("Files to Merge", "Fichiers à fusionner"),
+    (
+        "Files to Merge ({files} files, {pages} pages total, est. {size})",
+        "Fichiers à fusionner ({files} fichiers, {pages} pages au total, env. {size})",
+    ),
+    ("Output Filename", "Fichier de sortie"),
+    (
+        "Tab: Output name • D: Directory • Enter: Merge • A: Queue • Esc: Back • ?: Keys",
+        "Tab : Nom de sortie • D : Dossier • Entrée : Fusionner • A : File • Échap : Retour • ?: Touches",
+    ),
+    (
+        "{files} files • {pages} pages • estimated size {size}",
+        "{files} fichiers • {pages} pages • taille estimée {size}",
+    ),
+    (" • {count} unreadable", " • {count} illisibles"),
+    ("Output Summary", "Résumé de la sortie"),
+    (
+        "File to Delete Pages From",
+        "Fichier dont supprimer des pages",
+    ),
+    (
+        "Pages to Delete (e.g., 1,3-5)",
+        "Pages à supprimer (ex. 1,3-5)",
+    ),
+    (
+        "Pages to Delete (e.g., 1,3-5) • document has {count} pages",
+        "Pages à supprimer (ex. 1,3-5) • le document a {count} pages",
+    ),
+    (
+        "Pages to Rotate (e.g., 1,3-5, empty for all)",
+        "Pages à pivoter (ex. 1,3-5, vide pour toutes)",
+    ),
+    (
+        "Pages to Rotate (e.g., 1,3-5, empty for all) • document has {count} pages",
+        "Pages à pivoter (ex. 1,3-5, vide pour toutes) • le document a {count} pages",
+    ),
+    (
+        "Pages to Extract (e.g., 1,3-5)",
+        "Pages à extraire (ex. 1,3-5)",
+    ),
+    (
+        "Pages to Extract (e.g., 1,3-5) • document has {count} pages",
+        "Pages à extraire (ex. 1,3-5) • le document a {count} pages",
+    ),
+    (
+        "p: Pages • g: Grid • Tab: Output name • d: Directory • Enter: Delete • a: Queue • ?: Keys",
+        "p : Pages • g : Grille • Tab : Nom de sortie • d : Dossier • Entrée : Supprimer • a : File • ?: Touches",
+    ),
+    (
+        "Rotation Rules • {file} (N: Add • X: Remove)",
+        "Règles de rotation • {file} (N : Ajouter • X : Retirer)",
+    ),
+    ("{angle}° clockwise", "{angle}° dans le sens horaire"),
+    (
+        "Rotation (←/→: 90° / 180° / 270°)",
+        "Rotation (←/→ : 90° / 180° / 270°)",
+    ),
+    (
+        "↑/↓: Rule • p: Pages • ←/→: Angle • g: Grid • Tab: Output name • d: Directory • Enter: Rotate • ?: Keys",
+        "↑/↓ : Règle • p : Pages • ←/→ : Angle • g : Grille • Tab : Nom de sortie • d : Dossier • Entrée : Pivoter • ?: Touches",
+    ),
+    (
+        "File to Extract Pages From",
+        "Fichier dont extraire des pages",
+    ),
+    (
+        "p: Pages • g: Grid • o: Order • n: Annotations • Tab: Output name • d: Directory • Enter: Extract • a: Queue • ?: Keys",
+        "p : Pages • g : Grille • o : Ordre • n : Annotations • Tab : Nom de sortie • d : Dossier • Entrée : Extraire • a : File • ?: Touches",
+    ),
+    ("File to Split", "Fichier à découper"),
+    (
+        "Named Segments (e.g., intro:1-3,chapter1:4-10,conclusion:11)",
+        "Segments nommés (ex. intro:1-3,chapitre1:4-10,conclusion:11)",
+    ),
+    (
+        "Page Segments (e.g., 1-3,5,7-9)",
+        "Segments de pages (ex. 1-3,5,7-9)",
+    ),
+    ("Output Prefix", "Préfixe de sortie"),
+    (
+        "Enter: Save segments • Esc: Cancel",
+        "Entrée : Enregistrer les segments • Échap : Annuler",
+    ),
+    (
+        "Enter: Save prefix • Esc: Cancel",
+        "Entrée : Enregistrer le préfixe • Échap : Annuler",
+    ),
+    (
+        "Tab: Complete directory • Enter: Save directory • Esc: Cancel",
+        "Tab : Compléter le dossier • Entrée : Enregistrer le dossier • Échap : Annuler",
+    ),
+    (
+        "S: Segments • Space: Named • O: Overlaps • P: Prefix • D: Directory • Enter: Split • A: Queue • ?: Keys",
+        "S : Segments • Espace : Nommés • O : Chevauchements • P : Préfixe • D : Dossier • Entrée : Découper • A : File • ?: Touches",
+    ),
+    (
+        "🔢 Select Pages to Delete",
+        "🔢 Choisir les pages à supprimer",
+    ),
+    (
+        "🔢 Select Pages to Rotate",
+        "🔢 Choisir les pages à pivoter",
+    ),
+    (
+        "🔢 Select Pages to Extract",
+        "🔢 Choisir les pages à extraire",
+    ),
+    ("🔢 Select Pages", "🔢 Choisir des pages"),
+    (
+        "Document has {pages} pages ({selected} selected)",
+        "Le document a {pages} pages ({selected} sélectionnées)",
+    ),
+    ("Pages", "Pages"),
+    (
+        "←↑↓→: Move • Space: Toggle • Shift+Move: Select range • a: All • Enter: Apply • Esc: Cancel",
+        "←↑↓→ : Déplacer • Espace : Cocher • Maj+Déplacer : Plage • a : Tout • Entrée : Appliquer • Échap : Annuler",
+    ),
+    ("Page {page}", "Page {page}"),
+    ("Rotation: {angle}°", "Rotation : {angle}°"),
+    ("selected", "sélectionnée"),
+    (
+        "Type segments to preview the generated files",
+        "Saisissez des segments pour prévisualiser les fichiers générés",
+    ),
+    ("Output Preview", "Aperçu de la sortie"),
+    (
+        "{file} — page {page} is past the end ({count} pages)",
+        "{file} — la page {page} dépasse la fin ({count} pages)",
+    ),
+    ("{file} — {pages} • ⚠ exists", "{file} — {pages} • ⚠ existe"),
+    (
+        "Output Preview ({count} files)",
+        "Aperçu de la sortie ({count} fichiers)",
+    ),
+    ("No result available", "Aucun résultat disponible"),
+    ("No files were written", "Aucun fichier n'a été écrit"),
+    (
+        "{file} — could not be read back",
+        "{file} — relecture impossible",
+    ),
+    ("Output Files", "Fichiers de sortie"),
+    (
+        "Use as input → {again}1 Merge • 2 Delete • 3 Split • 4 Rotate • 5 Extract • 6 Compress • 7 Watermark",
+        "Utiliser en entrée → {again}1 Fusionner • 2 Supprimer • 3 Découper • 4 Pivoter • 5 Extraire • 6 Compresser • 7 Filigrane",
+    ),
+    ("n: {operation} again • ", "n : {operation} à nouveau • "),
+    (
+        "↑↓: Select • o: Open • f: Open folder • c: Copy path • Enter/Esc: Main menu",
+        "↑↓ : Choisir • o : Ouvrir • f : Ouvrir le dossier • c : Copier le chemin • Entrée/Échap : Menu principal",
+    ),
+    (
+        "d: Details • c: Copy details • L: Logs • Enter/Esc: Main menu",
+        "d : Détails • c : Copier les détails • L : Journaux • Entrée/Échap : Menu principal",
+    ),
+    ("Enter/Esc: Main menu", "Entrée/Échap : Menu principal"),
+    ("Delete", "Supprimer"),
+    ("1 page", "1 page"),
+    ("{count} pages", "{count} pages"),
+    ("📋 Operation Queue", "📋 File d'opérations"),
+    (
+        "No operation queued: press 'a' in a configuration screen to add one",
+        "Aucune opération en file : appuyez sur 'a' dans un écran de configuration pour en ajouter une",
+    ),
+    ("wrote {files}", "a écrit {files}"),
+    ("skipped", "ignorée"),
+    ("Steps", "Étapes"),
+    ("Progress", "Progression"),
+    ("{finished}/{total} steps", "{finished}/{total} étapes"),
+    (
+        "Running… • Esc/c: Cancel remaining steps",
+        "Exécution… • Échap/c : Annuler les étapes restantes",
+    ),
+    (
+        "↑↓: Select • Enter/r: Run all • x/Del: Remove • c: Clear • Esc: Main menu",
+        "↑↓ : Choisir • Entrée/r : Tout lancer • x/Suppr : Retirer • c : Vider • Échap : Menu principal",
+    ),
+    (
+        "Level: {level} and above • {count} lines{scrolled}",
+        "Niveau : {level} et plus • {count} lignes{scrolled}",
+    ),
+    (" • scrolled", " • défilé"),
+    ("Nothing logged yet", "Rien n'a encore été journalisé"),
+    (
+        "↑↓: Scroll • F: Level • C: Clear • L/Esc: Back • ?: Keys",
+        "↑↓ : Défiler • F : Niveau • C : Vider • L/Échap : Retour • ?: Touches",
+    ),
+    ("❓ Help", "❓ Aide"),
+    (
+        "↑↓/PgUp/PgDn: Scroll • Tab/Shift+Tab: Sections • Esc: Return to main menu",
+        "↑↓/PgPréc/PgSuiv : Défiler • Tab/Maj+Tab : Sections • Échap : Retour au menu principal",
+    ),
+    ("⌨️  Everywhere", "⌨️  Partout"),
+    ("Any screen", "Tous les écrans"),
+    ("While typing in a field", "Pendant la saisie dans un champ"),
+    (
+        "While editing the output directory",
+        "Pendant la modification du dossier de sortie",
+    ),
+    ("Keys: {screen}", "Touches : {screen}"),
+    (
+        "Press any key to close",
+        "Appuyez sur une touche pour fermer",
+    ),
+    ("Exit Confirmation", "Confirmation de sortie"),
+    (
+        "Are you sure you want to exit PDF Cutter? (y/n)",
+        "Voulez-vous vraiment quitter PDF Cutter ? (y/n)",
+    ),
+    ("No operation", "Aucune opération"),
+    ("1 file", "1 fichier"),
+    ("{count} files", "{count} fichiers"),
+    ("Error", "Erreur"),
+    ("Info", "Info"),
+    ("Command Palette", "Palette de commandes"),
+    (
+        "↑↓: Select • Enter: Run • Esc: Close",
+        "↑↓ : Choisir • Entrée : Lancer • Échap : Fermer",
+    ),
+    ("No matching action", "Aucune action correspondante"),
+    (
+        "Failed to load PDF '{path}'",
+        "Impossible de charger le PDF '{path}'",
+    ),
+    (
+        "Failed to save PDF '{path}'",
+        "Impossible d'enregistrer le PDF '{path}'",
+    ),
+    (
+        "Invalid page number: {page}. PDF has {count} pages (1-{count})",
+        "Numéro de page invalide : {page}. Le PDF a {count} pages (1-{count})",
+    ),
+    (
+        "Cannot delete all pages from PDF",
+        "Impossible de supprimer toutes les pages du PDF",
+    ),
+    ("No pages to extract", "Aucune page à extraire"),
+    ("landscape", "paysage"),
+    ("portrait", "portrait"),
+    ("Custom", "Personnalisé"),
+    ("Failed to read '{path}'", "Impossible de lire '{path}'"),
+    (
+        "Invalid rotation: {angle} (must be a multiple of 90)",
+        "Rotation invalide : {angle} (doit être un multiple de 90)",
+    ),
+    ("No pages to rotate", "Aucune page à pivoter"),
+    (
+        "Page {page} is not a dictionary",
+        "La page {page} n'est pas un dictionnaire",
+    ),
+    (
+        "No pages to include in PDF",
+        "Aucune page à inclure dans le PDF",
+    ),
+    ("PDF has no pages", "Le PDF n'a aucune page"),
+    (
+        "No command provided. Use --help for usage or --tui for interactive mode.",
+        "Aucune commande indiquée. Utilisez --help pour l'aide ou --tui pour le mode interactif.",
+    ),
+    (
+        "You must provide at least two input PDF files",
+        "Il faut au moins deux fichiers PDF en entrée",
+    ),
+    (
+        "Input file does not exist: {path}",
+        "Le fichier d'entrée n'existe pas : {path}",
+    ),
+    (
+        "✅ Merged {count} files into '{output}'",
+        "✅ {count} fichiers fusionnés dans '{output}'",
+    ),
+    (
+        "✅ Deleted pages {pages} from '{input}' and saved to '{output}'",
+        "✅ Pages {pages} supprimées de '{input}', résultat enregistré dans '{output}'",
+    ),
+    (
+        "✅ Split '{input}' into {count} files:",
+        "✅ '{input}' découpé en {count} fichiers :",
+    ),
+    (
+        "⚠ Could not open '{path}': {error}",
+        "⚠ Impossible d'ouvrir '{path}' : {error}",
+    ),
+    (
+        "Invalid page range format: {range}",
+        "Format de plage de pages invalide : {range}",
+    ),
+    (
+        "Invalid range: start page {start} is greater than end page {end}",
+        "Plage invalide : la page de début {start} est après la page de fin {end}",
+    ),
+    (
+        "Invalid theme in config file",
+        "Thème invalide dans le fichier de configuration",
+    ),
+    (
+        "Invalid locale in config file",
+        "Langue invalide dans le fichier de configuration",
+    ),
+    (
+        "Compress {input} → {output}",
+        "Compresser {input} → {output}",
+    ),
+    (
+        "✅ Successfully compressed '{input}' from {before} to {after} and saved to '{output}'",
+        "✅ '{input}' compressé de {before} à {after}, résultat enregistré dans '{output}'",
+    ),
+    (
+        "Failed to compress PDF: {error}",
+        "Échec de la compression du PDF : {error}",
+    ),
+    (
+        "Toggle stream recompression",
+        "Activer ou non la recompression des flux",
+    ),
+    (
+        "Cycle the image resolution",
+        "Changer la résolution des images",
+    ),
+    (
+        "Toggle metadata stripping",
+        "Activer ou non la suppression des métadonnées",
+    ),
+    ("Compress", "Compresser"),
+    ("🗜️  Compress PDF", "🗜️  Compresser un PDF"),
+    (
+        "Make a PDF smaller: recompress its streams, downsample its images and strip its metadata.",
+        "Réduire la taille d'un PDF : recompresser ses flux, sous-échantillonner ses images et supprimer ses métadonnées.",
+    ),
+    (
+        "📄 Select PDF to Compress",
+        "📄 Choisir le PDF à compresser",
+    ),
+    (
+        "🗜️  Compress Configuration",
+        "🗜️  Configuration de la compression",
+    ),
+    ("File to Compress", "Fichier à compresser"),
+    (
+        "recompressed at the best level",
+        "recompressés au meilleur niveau",
+    ),
+    ("left unchanged", "laissés tels quels"),
+    ("Streams (S: recompress)", "Flux (S : recompresser)"),
+    (
+        "downsampled above {dpi} dpi",
+        "sous-échantillonnées au-delà de {dpi} ppp",
+    ),
+    ("kept as they are", "conservées telles quelles"),
+    (
+        "Images (I / ← →: resolution)",
+        "Images (I / ← → : résolution)",
+    ),
+    ("stripped", "supprimées"),
+    ("kept", "conservées"),
+    ("Metadata (M: strip)", "Métadonnées (M : supprimer)"),
+    (
+        "s: Streams • i: Images • m: Metadata • f: Annotations • Tab: Output name • d: Directory • Enter: Compress • a: Queue • ?: Keys",
+        "s : Flux • i : Images • m : Métadonnées • f : Annotations • Tab : Nom de sortie • d : Dossier • Entrée : Compresser • a : File • ?: Touches",
+    ),
+    (
+        "{file} — {pages} pages, {before} → {after} ({saving}% saved)",
+        "{file} — {pages} pages, {before} → {after} ({saving} % gagnés)",
+    ),
+    ("Add the watermark", "Ajouter le filigrane"),
+    ("Cycle the position", "Changer de position"),
+    ("Edit the pages to stamp", "Modifier les pages à tamponner"),
+    (
+        "Edit the path of a JPEG or PNG image to stamp",
+        "Modifier le chemin d'une image JPEG ou PNG à tamponner",
+    ),
+    ("Edit the watermark text", "Modifier le texte du filigrane"),
+    ("Lower / raise the opacity", "Baisser / augmenter l'opacité"),
+    (
+        "Pages to Stamp (e.g., 1,3-5, empty for all) • document has {count} pages",
+        "Pages à tamponner (ex. 1,3-5, vide pour toutes) • le document a {count} pages",
+    ),
+    (
+        "Pages to Stamp (e.g., 1,3-5, empty for all)",
+        "Pages à tamponner (ex. 1,3-5, vide pour toutes)",
+    ),
+    (
+        "Stamp a text or an image on some or all pages, with a position and an opacity.",
+        "Tamponner un texte ou une image sur tout ou partie des pages, avec une position et une opacité.",
+    ),
+    ("Start: Add a watermark", "Démarrer : Ajouter un filigrane"),
+    ("Watermark configuration", "Configuration du filigrane"),
+    (
+        "Watermark {input} {pages} → {output}",
+        "Filigrane {input} {pages} → {output}",
+    ),
+    ("Watermark", "Filigrane"),
+    ("bottom left corner", "coin inférieur gauche"),
+    ("bottom right corner", "coin inférieur droit"),
+    ("top left corner", "coin supérieur gauche"),
+    ("top right corner", "coin supérieur droit"),
+    ("centered", "centré"),
+    (
+        "diagonal, across the page",
+        "en diagonale, sur toute la page",
+    ),
+    (
+        "{position} • {opacity}% opaque • {pages}",
+        "{position} • opaque à {opacity} % • {pages}",
+    ),
+    ("💧 Watermark", "💧 Filigrane"),
+    ("7. 💧 Add a Watermark", "7. 💧 Ajouter un filigrane"),
+    ("File to Watermark", "Fichier à filigraner"),
+    (
+        "Image (I: JPEG or PNG path • T: text instead)",
+        "Image (I : chemin JPEG ou PNG • T : texte à la place)",
+    ),
+    ("Opacity (← →)", "Opacité (← →)"),
+    (
+        "Position (O: next preset)",
+        "Position (O : préréglage suivant)",
+    ),
+    ("Preview", "Aperçu"),
+    (
+        "Text (T: edit • I: image instead)",
+        "Texte (T : modifier • I : image à la place)",
+    ),
+    (
+        "Unsupported image (use a JPEG or PNG file): {path}",
+        "Image non prise en charge (utilisez un fichier JPEG ou PNG) : {path}",
+    ),
+    ("all {count} pages", "les {count} pages"),
+    ("image {name}", "image {name}"),
+    ("no image chosen yet", "aucune image choisie"),
+    ("no text typed yet", "aucun texte saisi"),
+    (
+        "t: Text • i: Image • o: Position • ←→: Opacity • p: Pages • g: Grid • Tab: Output name • d: Directory • Enter: Stamp • a: Queue • ?: Keys",
+        "t : Texte • i : Image • o : Position • ←→ : Opacité • p : Pages • g : Grille • Tab : Nom de sortie • d : Dossier • Entrée : Tamponner • a : File • ?: Touches",
+    ),
+    (
+        "💧 Watermark Configuration",
+        "💧 Configuration du filigrane",
+    ),
+    (
+        "📄 Select PDF to Watermark",
+        "📄 Choisir le PDF à filigraner",
+    ),
+    (
+        "🔢 Select Pages to Watermark",
+        "🔢 Choisir les pages à filigraner",
+    ),
+    (
+        "Failed to add the watermark: {error}",
+        "Échec de l'ajout du filigrane : {error}",
+    ),
+    (
+        "Failed to read image '{path}'",
+        "Impossible de lire l'image '{path}'",
+    ),
+    ("Invalid JPEG data", "Données JPEG invalides"),
+    ("Invalid PNG data", "Données PNG invalides"),
+    (
+        "Invalid opacity: {opacity} (must be between 1 and 100)",
+        "Opacité invalide : {opacity} (doit être comprise entre 1 et 100)",
+    ),
+    ("No pages to watermark", "Aucune page à filigraner"),
+    (
+        "Only 8-bit, non-interlaced PNG images are supported",
+        "Seules les images PNG 8 bits non entrelacées sont prises en charge",
+    ),
+    (
+        "Please type the watermark text",
+        "Veuillez saisir le texte du filigrane",
+    ),
+    (
+        "Unsupported image format: {path}",
+        "Format d'image non pris en charge : {path}",
+    ),
+    (
+        "Watermark text cannot be empty",
+        "Le texte du filigrane ne peut pas être vide",
+    ),
+    (
+        "✅ Successfully stamped {pages} of '{input}' and saved to '{output}'",
+        "✅ {pages} de '{input}' tamponnées et enregistrées dans '{output}'",
+    ),
+    (
+        "Not enough space in {directory}: about {needed} needed, {available} free",
+        "Pas assez de place dans {directory} : environ {needed} nécessaires, {available} libres",
+    ),
+    (
+        "{output} will be about {size}, over the {limit} warning size",
+        "{output} fera environ {size}, au-delà du seuil d'alerte de {limit}",
+    ),
+    (
+        "Invalid output size warning in config file",
+        "Seuil d'alerte de taille invalide dans le fichier de configuration",
+    ),
+    (
+        "Invalid overwrite policy in config file",
+        "Politique d'écrasement invalide dans le fichier de configuration",
+    ),
+    (
+        "Sizes are estimated from the input files before anything is written.",
+        "Les tailles sont estimées à partir des fichiers d'entrée, avant toute écriture.",
+    ),
+    (
+        "💾 Check the output size",
+        "💾 Vérifier la taille de sortie",
+    ),
+    (
+        "Enter: Run anyway • Esc: Back",
+        "Entrée : Lancer quand même • Échap : Retour",
+    ),
+    (
+        "Browse folders to pick a file",
+        "Parcourir les dossiers pour choisir un fichier",
+    ),
+    ("Select an entry", "Sélectionner une entrée"),
+    (
+        "Open the folder, or add the PDF",
+        "Ouvrir le dossier, ou ajouter le PDF",
+    ),
+    ("Go up to the parent folder", "Remonter au dossier parent"),
+    (
+        "Pin or unpin the current folder",
+        "Épingler ou désépingler le dossier actuel",
+    ),
+    ("Go to a pinned folder", "Aller à un dossier épinglé"),
+    (
+        "Switch between the files and the pinned folders",
+        "Passer des fichiers aux dossiers épinglés",
+    ),
+    (
+        "Unpin the selected folder (pinned folders)",
+        "Désépingler le dossier sélectionné (dossiers épinglés)",
+    ),
+    ("📂 File browser", "📂 Explorateur de fichiers"),
+    (
+        "Browse folders to pick PDFs, and pin the folders you use often (saved in the config file).",
+        "Parcourez les dossiers pour choisir des PDF, et épinglez ceux que vous utilisez souvent (enregistrés dans le fichier de configuration).",
+    ),
+    ("File browser", "Explorateur de fichiers"),
+    ("📂 Browse Files", "📂 Parcourir les fichiers"),
+    ("F: pin this folder", "F : épingler ce dossier"),
+    ("📌 Pinned", "📌 Épinglés"),
+    (
+        "No folders or PDF files here",
+        "Aucun dossier ni fichier PDF ici",
+    ),
+    (
+        "↑↓: Select • Enter: Go • X: Unpin • Tab/Esc: Files • ?: Keys",
+        "↑↓ : Sélectionner • Entrée : Aller • X : Désépingler • Tab/Échap : Fichiers • ?: Touches",
+    ),
+    (
+        "↑↓: Select • Enter: Open/Add • Backspace: Up • S: Sort • H: Hidden • A: All files • F: Pin • W: Work here • 1-9: Pinned • Tab: Pinned list • Esc: Back • ?: Keys",
+        "↑↓ : Sélectionner • Entrée : Ouvrir/Ajouter • Retour arrière : Remonter • S : Trier • H : Cachés • A : Tous les fichiers • F : Épingler • W : Travailler ici • 1-9 : Épinglés • Tab : Liste épinglée • Échap : Retour • ?: Touches",
+    ),
+    (
+        "No pinned folders yet: press F to pin this one",
+        "Aucun dossier épinglé : appuyez sur F pour épingler celui-ci",
+    ),
+    ("Added {file}", "{file} ajouté"),
+    ("Pinned {directory}", "{directory} épinglé"),
+    ("Unpinned {directory}", "{directory} désépinglé"),
+    (
+        "Could not save the pinned folders: {error}",
+        "Impossible d'enregistrer les dossiers épinglés : {error}",
+    ),
+    ("name", "nom"),
+    ("date modified", "date de modification"),
+    ("size", "taille"),
+    ("Sorted by {sort}", "Tri par {sort}"),
+    ("Showing hidden entries", "Éléments cachés affichés"),
+    ("Hiding hidden entries", "Éléments cachés masqués"),
+    ("Showing all files", "Tous les fichiers affichés"),
+    ("Showing PDF files only", "Fichiers PDF uniquement"),
+    (" • Sort: {sort}", " • Tri : {sort}"),
+    (" • All files", " • Tous les fichiers"),
+    (" • Hidden shown", " • Cachés affichés"),
+    ("No files here", "Aucun fichier ici"),
+    (
+        "Sort by name, date modified or size",
+        "Trier par nom, date de modification ou taille",
+    ),
+    (
+        "Show or hide hidden entries",
+        "Afficher ou masquer les éléments cachés",
+    ),
+    (
+        "List all files, or PDFs only",
+        "Lister tous les fichiers, ou seulement les PDF",
+    ),
+    ("Move by ten items", "Avancer de dix éléments"),
+    ("First / last item", "Premier / dernier élément"),
+    ("overwrite", "ecraser"),
+    ("Type '{word}' to confirm", "Tapez '{word}' pour confirmer"),
+    (
+        "Backed up '{path}' to '{backup}'",
+        "Copie de sauvegarde de '{path}' dans '{backup}'",
+    ),
+    (
+        "Could not back up '{path}': {error}",
+        "Impossible de sauvegarder '{path}' : {error}",
+    ),
+    (
+        "Could not back up '{path}'",
+        "Impossible de sauvegarder '{path}'",
+    ),
+    (
+        "⚠ This writes over the original file:",
+        "⚠ Le fichier d'origine sera écrasé :",
+    ),
+    (
+        "[x] Keep a .bak copy of the original (Tab)",
+        "[x] Garder une copie .bak de l'original (Tab)",
+    ),
+    (
+        "[ ] Keep a .bak copy of the original (Tab)",
+        "[ ] Garder une copie .bak de l'original (Tab)",
+    ),
+    (
+        "Type '{word}' and press Enter to confirm:",
+        "Tapez '{word}' puis Entrée pour confirmer :",
+    ),
+    ("⚠ Overwrite the input?", "⚠ Écraser le fichier d'entrée ?"),
+    (
+        "Enter: Confirm • Tab: Backup • Esc: Back",
+        "Entrée : Confirmer • Tab : Sauvegarde • Échap : Retour",
+    ),
+    (
+        "Invalid backup setting in config file",
+        "Réglage de sauvegarde invalide dans le fichier de configuration",
+    ),
+    (
+        "'{path}' is an input file: pass --in-place to write over it",
+        "'{path}' est un fichier d'entrée : ajoutez --in-place pour l'écraser",
+    ),
+    (
+        "⚠ {path} is an input file: it will be written over",
+        "⚠ {path} est un fichier d'entrée : il sera écrasé",
+    ),
+    (
+        "Moved '{path}' to '{backup}'",
+        "'{path}' déplacé vers '{backup}'",
+    ),
+    (
+        "⚠ {path} already exists • kept as .{suffix}",
+        "⚠ {path} existe déjà • gardé en .{suffix}",
+    ),
+    (
+        "⚠ {path} • {count} files already exist • kept as .{suffix}",
+        "⚠ {path} • {count} fichiers existent déjà • gardés en .{suffix}",
+    ),
+    ("Next page", "Page suivante"),
+    ("Previous page", "Page précédente"),
+    ("Skip the tour", "Passer la visite"),
+    ("Welcome tour", "Visite guidée"),
+    ("Show the welcome tour", "Afficher la visite guidée"),
+    (
+        "Settings can be changed in {path}",
+        "Les réglages se modifient dans {path}",
+    ),
+    (
+        "Could not create the config file: {error}",
+        "Impossible de créer le fichier de configuration : {error}",
+    ),
+    (
+        "PDF Cutter merges, splits and edits PDF files without leaving the terminal.",
+        "PDF Cutter fusionne, découpe et modifie des fichiers PDF sans quitter le terminal.",
+    ),
+    (
+        "Everything is done with the keyboard, and the mouse works too.",
+        "Tout se fait au clavier, et la souris fonctionne aussi.",
+    ),
+    (
+        "Merge, Delete and Split are wizards that go through the same steps:",
+        "Fusionner, Supprimer et Découper sont des assistants qui suivent les mêmes étapes :",
+    ),
+    (
+        "Add the files in the order they should appear, then name the output.",
+        "Ajoutez les fichiers dans l'ordre voulu, puis nommez la sortie.",
+    ),
+    (
+        "Add one file, then type the pages to remove (e.g. 1,3,5-7) or pick them with G.",
+        "Ajoutez un fichier, puis tapez les pages à supprimer (ex. 1,3,5-7) ou choisissez-les avec G.",
+    ),
+    (
+        "Add one file, then type where to cut (e.g. 1-3,4-10): each segment becomes a file.",
+        "Ajoutez un fichier, puis tapez où couper (ex. 1-3,4-10) : chaque segment devient un fichier.",
+    ),
+    (
+        "Rotate, Extract, Compress and Watermark work the same way.",
+        "Pivoter, Extraire, Compresser et Filigrane fonctionnent de la même façon.",
+    ),
+    (
+        "Enter goes to the next step and Esc goes back, on every screen.",
+        "Sur chaque écran, Entrée passe à l'étape suivante et Échap revient en arrière.",
+    ),
+    ("Files", "Fichiers"),
+    (
+        "Tab types the path of a file, b browses folders, Backspace removes the selected file.",
+        "Tab permet de taper le chemin d'un fichier, b de parcourir les dossiers, Retour arrière retire le fichier sélectionné.",
+    ),
+    (
+        "Tab edits the output name (P when splitting), D the output directory.",
+        "Tab modifie le nom de sortie (P pour un découpage), D le dossier de sortie.",
+    ),
+    (
+        "A adds the operation to the queue instead of running it, to run several in a row.",
+        "A ajoute l'opération à la file au lieu de la lancer, pour en enchaîner plusieurs.",
+    ),
+    ("Lost?", "Perdu ?"),
+    (
+        "? lists the keys of the current screen, Ctrl+K searches its actions, and Help (9 in the main menu) explains everything.",
+        "? liste les touches de l'écran courant, Ctrl+K recherche ses actions, et l'Aide (9 dans le menu principal) explique tout.",
+    ),
+    (
+        "Outputs are written to the current directory, {directory}, unless you press D on a configuration screen to pick another one.",
+        "Les sorties sont écrites dans le dossier courant, {directory}, sauf si vous en choisissez un autre avec D sur un écran de configuration.",
+    ),
+    (
+        "Leaving the output name empty uses a default name such as output_merged.pdf, with a number added when that file already exists.",
+        "Un nom de sortie vide utilise un nom par défaut comme output_merged.pdf, avec un numéro ajouté si ce fichier existe déjà.",
+    ),
+    (
+        "Writing over one of the input files always asks for a confirmation first.",
+        "Écrire par-dessus un fichier d'entrée demande toujours une confirmation.",
+    ),
+    (
+        "Settings (theme, language, backups...) are in {path}.",
+        "Les réglages (thème, langue, sauvegardes...) sont dans {path}.",
+    ),
+    (
+        "Settings (theme, language, backups...) will be in {path}, created with every setting commented out when this tour ends.",
+        "Les réglages (thème, langue, sauvegardes...) seront dans {path}, créé avec tous les réglages en commentaire à la fin de cette visite.",
+    ),
+    ("👋 Welcome to PDF Cutter", "👋 Bienvenue dans PDF Cutter"),
+    ("🧭 The operation wizards", "🧭 Les assistants d'opération"),
+    ("⌨️  Keys worth knowing", "⌨️  Les touches utiles"),
+    (
+        "📁 Where outputs are written",
+        "📁 Où sont écrites les sorties",
+    ),
+    (
+        "Welcome tour ({page}/{count})",
+        "Visite guidée ({page}/{count})",
+    ),
+    (
+        "→/Enter: Next • ←: Previous • Esc: Skip the tour",
+        "→/Entrée : Suivant • ← : Précédent • Échap : Passer la visite",
+    ),
+    (
+        "Enter: Start • ←: Previous • Esc: Skip the tour",
+        "Entrée : Commencer • ← : Précédent • Échap : Passer la visite",
+    ),
+    ("a. ℹ️  About", "a. ℹ️  À propos"),
+    ("ℹ️  About PDF Cutter", "ℹ️  À propos de PDF Cutter"),
+    ("{path} (not created yet)", "{path} (pas encore créé)"),
+    (
+        "none (no home directory)",
+        "aucun (pas de dossier personnel)",
+    ),
+    ("Version", "Version"),
+    ("Commit", "Commit"),
+    ("Build", "Compilation"),
+    ("Features", "Fonctionnalités"),
+    ("System", "Système"),
+    ("Config file", "Fichier de configuration"),
+    ("Theme", "Thème"),
+    ("Graphics", "Graphismes"),
+    ("none", "aucune"),
+    (
+        "Reporting a bug? Press C to copy these details, then paste them in your report.",
+        "Vous signalez un bogue ? Appuyez sur C pour copier ces informations, puis collez-les dans votre rapport.",
+    ),
+    (
+        "C: Copy for a bug report • Esc: Back • ?: Keys",
+        "C : Copier pour un rapport de bogue • Échap : Retour • ? : Touches",
+    ),
+    (
+        "Copied the build details to the clipboard",
+        "Informations de compilation copiées dans le presse-papiers",
+    ),
+    (
+        "Could not copy the build details: {error}",
+        "Impossible de copier les informations de compilation : {error}",
+    ),
+    (
+        "Copy the details for a bug report",
+        "Copier les informations pour un rapport de bogue",
+    ),
+    (
+        "About: version and build details",
+        "À propos : version et compilation",
+    ),
+    ("About", "À propos"),
+    ("ℹ️  About", "ℹ️  À propos"),
+    (
+        "Version, build and configuration file details, to include when reporting a bug.",
+        "Version, compilation et fichier de configuration, à joindre à un rapport de bogue.",
+    ),
+    (
+        "'{segment}' has a name: press Space to use named segments",
+        "'{segment}' a un nom : appuyez sur Espace pour utiliser les segments nommés",
+    ),
+    (
+        "'{segment}' needs a name before ':'",
+        "'{segment}' doit avoir un nom avant ':'",
+    ),
+    ("Page segments", "Segments de pages"),
+    ("Named segments", "Segments nommés"),
+    (
+        "Segment mode (Space to switch)",
+        "Type de segments (Espace pour changer)",
+    ),
+    (
+        "'{first}' and '{second}' both include page {page} (O allows overlaps)",
+        "'{first}' et '{second}' contiennent tous deux la page {page} (O autorise les chevauchements)",
+    ),
+    (
+        "Allow or forbid pages in several segments",
+        "Autoriser ou non une page dans plusieurs segments",
+    ),
+    (
+        "☑ Overlapping segments allowed (O)",
+        "☑ Segments qui se chevauchent autorisés (O)",
+    ),
+    (
+        "☐ Overlapping segments allowed (O)",
+        "☐ Segments qui se chevauchent autorisés (O)",
+    ),
+    (
+        "⚠ Keeping only {pages} — {kept} of {count} pages",
+        "⚠ Seules les pages {pages} restent — {kept} sur {count}",
+    ),
+    (
+        "Keeping {pages} — {kept}",
+        "Pages conservées : {pages} — {kept}",
+    ),
+    ("Rule {rule}: {error}", "Règle {rule} : {error}"),
+    (
+        "✅ Successfully rotated {count} pages of '{input}' with {rules} rules and saved to '{output}'",
+        "✅ {count} pages de '{input}' pivotées selon {rules} règles et enregistrées dans '{output}'",
+    ),
+    (
+        "Reorder or remove pages in the page list",
+        "Réordonner ou retirer des pages dans la liste des pages",
+    ),
+    ("Select a page", "Sélectionner une page"),
+    ("Move the page up or down", "Monter ou descendre la page"),
+    (
+        "Move the page to the top or bottom",
+        "Placer la page en haut ou en bas",
+    ),
+    ("D / Del", "D / Suppr"),
+    ("Remove or restore the page", "Retirer ou rétablir la page"),
+    ("Apply the order", "Appliquer l'ordre"),
+    ("🔀 Page order", "🔀 Ordre des pages"),
+    (
+        "Reorder the pages to extract and leave some out, seeing each page's size.",
+        "Réordonner les pages à extraire et en écarter certaines, en voyant la taille de chaque page.",
+    ),
+    ("Page order", "Ordre des pages"),
+    ("Keep at least one page", "Gardez au moins une page"),
+    ("🔀 Order Pages to Extract", "🔀 Ordre des pages à extraire"),
+    (
+        "Document has {pages} pages ({kept} kept)",
+        "Le document a {pages} pages ({kept} gardées)",
+    ),
+    (
+        "↑/↓: Select • Alt+↑/↓: Move • d: Remove/restore • Enter: Apply • Esc: Cancel",
+        "↑/↓ : Sélectionner • Alt+↑/↓ : Déplacer • d : Retirer/rétablir • Entrée : Appliquer • Échap : Annuler",
+    ),
+    ("Elapsed {time}", "Écoulé {time}"),
+    ("{rate} pages/s", "{rate} pages/s"),
+    ("ETA {time}", "Reste {time}"),
+    ("Writing {files}", "Écriture de {files}"),
+    ("Processing", "Traitement"),
+    ("⏳ Processing", "⏳ Traitement"),
+    ("Esc/c: Cancel", "Échap/c : Annuler"),
+    ("Esc / C", "Échap / C"),
+    (
+        "Cancelling… the files being written will be deleted",
+        "Annulation… les fichiers en cours d'écriture seront supprimés",
+    ),
+    (
+        "Cancelled: the files the operation had started writing were deleted",
+        "Annulé : les fichiers que l'opération avait commencé à écrire ont été supprimés",
+    ),
+    (
+        "Cancel the operation, deleting the files it had started writing",
+        "Annuler l'opération, en supprimant les fichiers qu'elle avait commencé à écrire",
+    ),
+    (
+        "The operation stopped unexpectedly",
+        "L'opération s'est arrêtée de façon inattendue",
+    ),
+    (
+        "Invalid queue setting in config file",
+        "Réglage de file invalide dans le fichier de configuration",
+    ),
+    ("No failed step to retry", "Aucune étape échouée à relancer"),
+    (
+        "{done} done • {failed} failed • {skipped} skipped",
+        "{done} terminées • {failed} échouées • {skipped} ignorées",
+    ),
+    (
+        "↑↓: Select • f: Retry failed • Enter/r: Run all • x/Del: Remove • c: Clear • Esc: Main menu",
+        "↑↓ : Choisir • f : Relancer les échecs • Entrée/r : Tout lancer • x/Suppr : Retirer • c : Vider • Échap : Menu principal",
+    ),
+    (
+        "Run again the failed and skipped steps",
+        "Relancer les étapes échouées et ignorées",
+    ),
+    (
+        "Show or hide the details of a failure",
+        "Afficher ou masquer les détails d'un échec",
+    ),
+    (
+        "Copied the error details to the clipboard",
+        "Détails de l'erreur copiés dans le presse-papiers",
+    ),
+    (
+        "Could not copy the error details: {error}",
+        "Impossible de copier les détails de l'erreur : {error}",
+    ),
+    ("Recent log:", "Journal récent :"),
+    ("Details", "Détails"),
+    (
+        "c: Copy for a bug report • d/Esc: Hide details • L: Logs",
+        "c : Copier pour un rapport de bug • d/Échap : Masquer les détails • L : Journaux",
+    ),
+    (
+        "Open its folder in the file manager",
+        "Ouvrir son dossier dans le gestionnaire de fichiers",
+    ),
+    (
+        "Invalid ascii setting in config file",
+        "Réglage ascii invalide dans le fichier de configuration",
+    ),
+    ("ERROR: {message}", "ERREUR : {message}"),
+    ("OK: {message}", "OK : {message}"),
+    (
+        "Cannot work in {path}: {reason}",
+        "Impossible de travailler dans {path} : {reason}",
+    ),
+    (
+        "Make the current folder the working directory",
+        "Faire du dossier courant le répertoire de travail",
+    ),
+    (
+        "Working directory: {path}",
+        "Répertoire de travail : {path}",
+    ),
+    (
+        "Change working directory",
+        "Changer de répertoire de travail",
+    ),
+    (
+        "Go to a folder and press W to work in it",
+        "Allez dans un dossier et appuyez sur W pour y travailler",
+    ),
+    (
+        "❌ {file} — moved or deleted",
+        "❌ {file} — déplacé ou supprimé",
+    ),
+    (
+        "Encrypted PDF, remove its password first: {path}",
+        "PDF chiffré, retirez d'abord son mot de passe : {path}",
+    ),
+    (
+        "PDF without any page: {path}",
+        "PDF sans aucune page : {path}",
+    ),
+    (
+        "🔒 {file} — encrypted, remove its password first",
+        "🔒 {file} — chiffré, retirez d'abord son mot de passe",
+    ),
+    ("⚠️ {file} — no pages", "⚠️ {file} — aucune page"),
+    (
+        "Start over: reset the current operation",
+        "Recommencer : réinitialiser l'opération en cours",
+    ),
+    ("Go back to the main menu?", "Revenir au menu principal ?"),
+    (
+        "Start this operation over?",
+        "Recommencer cette opération ?",
+    ),
+    (
+        "⚠ The selected files ({count}) and the settings of this operation will be lost.",
+        "⚠ Les fichiers sélectionnés ({count}) et les réglages de cette opération seront perdus.",
+    ),
+    (
+        "Queued steps are kept.",
+        "Les étapes de la file d'attente sont conservées.",
+    ),
+    (
+        "⚠ Discard the current operation?",
+        "⚠ Abandonner l'opération en cours ?",
+    ),
+    (
+        "Enter/Y: Discard • Any other key: Stay",
+        "Entrée/Y : Abandonner • Autre touche : Rester",
+    ),
+    ("Added {count} files", "{count} fichiers ajoutés"),
+    (
+        "Added {added} of {total} files. Not added: {errors}",
+        "{added} fichiers ajoutés sur {total}. Non ajoutés : {errors}",
+    ),
+    (
+        "⚠ Discard the configuration?",
+        "⚠ Abandonner la configuration ?",
+    ),
+    (
+        "Go back to the file list?",
+        "Revenir à la liste des fichiers ?",
+    ),
+    (
+        "⚠ The pages, segments and output names typed on this screen will be lost.",
+        "⚠ Les pages, segments et noms de sortie saisis sur cet écran seront perdus.",
+    ),
+    (
+        "Invalid confirm_discard setting in config file",
+        "Réglage confirm_discard invalide dans le fichier de configuration",
+    ),
+    ("Cancelled", "Annulé"),
+    ("Output file", "Fichier de sortie"),
+    (
+        "Pages to delete (1-{count})",
+        "Pages à supprimer (1-{count})",
+    ),
+    ("Pages to delete", "Pages à supprimer"),
+    (
+        "the following required arguments were not provided:\n  {argument}",
+        "les arguments obligatoires suivants n'ont pas été fournis :\n  {argument}",
+    ),
+    (
+        "Cannot read the modification time of '{path}'",
+        "Impossible de lire la date de modification de '{path}'",
+    ),
+    (
+        "Invalid {flag} value '{spec}': expected INPUT:VALUE",
+        "Valeur de {flag} invalide '{spec}' : ENTRÉE:VALEUR attendu",
+    ),
+    (
+        "'{input}' is neither an input of the merge nor a position from 1 to {count}",
+        "'{input}' n'est ni un fichier de la fusion ni une position de 1 à {count}",
+    ),
+    (
+        "--pages is given twice for '{path}'",
+        "--pages est donné deux fois pour '{path}'",
+    ),
+    (
+        "Invalid --pages for '{path}'",
+        "--pages invalide pour '{path}'",
+    ),
+    (
+        "Failed to rotate '{path}'",
+        "Impossible de faire pivoter '{path}'",
+    ),
+    (
+        "Failed to take pages of '{path}'",
+        "Impossible de prendre les pages de '{path}'",
+    ),
+    (
+        "Invalid SOURCE_DATE_EPOCH: {value} (expected seconds since 1970)",
+        "SOURCE_DATE_EPOCH invalide : {value} (secondes depuis 1970 attendues)",
+    ),
+    (
+        "Invalid date: {date} (expected e.g. 2025-01-31 or 2025-01-31T09:30:00+01:00)",
+        "Date invalide : {date} (attendu par ex. 2025-01-31 ou 2025-01-31T09:30:00+01:00)",
+    ),
+    (
+        "Invalid file identifier: {id} (expected hexadecimal digits, e.g. 32 of them)",
+        "Identifiant de fichier invalide : {id} (chiffres hexadécimaux attendus, par ex. 32)",
+    ),
+    (
+        "Cannot create folder '{path}': permission denied",
+        "Impossible de créer le dossier '{path}' : permission refusée",
+    ),
+    (
+        "Cannot create folder '{path}'",
+        "Impossible de créer le dossier '{path}'",
+    ),
+    (
+        "Cannot write into '{path}': it is a file, not a folder",
+        "Impossible d'écrire dans '{path}' : c'est un fichier, pas un dossier",
+    ),
+    (
+        "{path} is a file, not a folder",
+        "{path} est un fichier, pas un dossier",
+    ),
+    ("→ {path} • new folder", "→ {path} • nouveau dossier"),
+    (
+        "⏱ load {load} • copy {copy} • save {save} • total {total} • peak memory {memory}",
+        "⏱ chargement {load} • copie {copy} • enregistrement {save} • total {total} • mémoire maximale {memory}",
+    ),
+    (
+        "'{input}' needs the page count of the document",
+        "'{input}' nécessite le nombre de pages du document",
+    ),
+    (
+        "Segments '{first}' and '{second}' both include page {page}: pass --allow-overlap to write it to both files",
+        "Les segments '{first}' et '{second}' contiennent tous deux la page {page} : passez --allow-overlap pour l'écrire dans les deux fichiers",
+    ),
+    (
+        "Segments '{first}' and '{second}' both include pages {pages}: pass --allow-overlap to write them to both files",
+        "Les segments '{first}' et '{second}' contiennent tous deux les pages {pages} : passez --allow-overlap pour les écrire dans les deux fichiers",
+    ),
+    ("{index} of {count}", "{index} sur {count}"),
+    (
+        "Failed to draw a separator page",
+        "Impossible de dessiner une page de séparation",
+    ),
+    (
+        "Separator file does not exist: {path}",
+        "Le fichier de séparation n'existe pas : {path}",
+    ),
+    ("{file}, page {page}", "{file}, page {page}"),
+    ("{count} links in '{input}'", "{count} liens dans '{input}'"),
+    (
+        "{count} of {total} links lead to no page of '{input}'",
+        "{count} liens sur {total} ne mènent à aucune page de '{input}'",
+    ),
+    (
+        "✅ Every internal link of '{input}' leads to a page",
+        "✅ Tous les liens internes de '{input}' mènent à une page",
+    ),
+    ("page {page}", "page {page}"),
+    ("no page", "aucune page"),
+    ("'{name}' (page {page})", "'{name}' (page {page})"),
+    ("'{name}' (no page)", "'{name}' (aucune page)"),
+    ("file {path}", "fichier {path}"),
+    ("{action} action", "action {action}"),
+    ("page {page} → {target}", "page {page} → {target}"),
+    (
+        "'{path}' is tagged for accessibility, but the tags are left out (pass --keep-tags to keep them)",
+        "'{path}' est balisé pour l'accessibilité, mais les balises sont omises (passez --keep-tags pour les garder)",
+    ),
+    (
+        "'{path}' has no accessibility tags: its pages are untagged in the output",
+        "'{path}' n'a pas de balises d'accessibilité : ses pages ne sont pas balisées dans le résultat",
+    ),
+    (
+        "Some accessibility tags of '{path}' could not be kept",
+        "Certaines balises d'accessibilité de '{path}' n'ont pas pu être gardées",
+    ),
+    (
+        "'{path}' is not an ICC profile",
+        "'{path}' n'est pas un profil ICC",
+    ),
+    (
+        "'{path}' is not a grey, RGB or CMYK ICC profile",
+        "'{path}' n'est pas un profil ICC gris, RVB ou CMJN",
+    ),
+    (
+        "'{path}' is larger than 4 GB, the most a PDF can be read with",
+        "'{path}' dépasse 4 Go, la taille maximale d'un PDF pouvant être lu",
+    ),
+    (
+        "'{path}' would be larger than 4 GB, the most a PDF can be written with; split the input first",
+        "'{path}' dépasserait 4 Go, la taille maximale d'un PDF pouvant être écrit ; découpez d'abord l'entrée",
+    ),
+    (
+        "Invalid durable setting in config file",
+        "Réglage durable invalide dans le fichier de configuration",
+    ),
+    (
+        "Invalid object_streams setting in config file",
+        "Réglage object_streams invalide dans le fichier de configuration",
+    ),
+    (
+        "too long ({length} characters, at most {max})",
+        "trop long ({length} caractères, au plus {max})",
+    ),
+    (
+        "unexpected '{character}' at column {column}",
+        "'{character}' inattendu à la colonne {column}",
+    ),
+    (
+        "missing page number at column {column}",
+        "numéro de page manquant à la colonne {column}",
+    ),
+    (
+        "number too large at column {column} (at most {max})",
+        "nombre trop grand à la colonne {column} (au plus {max})",
+    ),
+    (
+        "too many parts ({count}, at most {max})",
+        "trop de parties ({count}, au plus {max})",
+    ),
+    (
+        "too many pages ({count}, at most {max})",
+        "trop de pages ({count}, au plus {max})",
+    ),
+    (
+        "'{path}' cannot be read back",
+        "'{path}' ne peut pas être relu",
+    ),
+    (
+        "'{path}' has {found} pages instead of {expected}",
+        "'{path}' a {found} pages au lieu de {expected}",
+    ),
+    (
+        "Page {page} of '{path}' has no readable content",
+        "La page {page} de '{path}' n'a pas de contenu lisible",
+    ),
+    (
+        "The content of page {page} of '{path}' cannot be decoded",
+        "Le contenu de la page {page} de '{path}' ne peut pas être décodé",
+    ),
+    (
+        "the content is not a stream",
+        "le contenu n'est pas un flux",
+    ),
+    ("no content stream", "aucun flux de contenu"),
+    ("Verification failed", "La vérification a échoué"),
+    (
+        "✅ Verified '{output}': {count} pages, each with its content",
+        "✅ '{output}' vérifié : {count} pages, chacune avec son contenu",
+    ),
+    (
+        "✅ Verified {count} outputs: every page is there with its content",
+        "✅ {count} fichiers vérifiés : chaque page est présente avec son contenu",
+    ),
+    ("Title:", "Titre :"),
+    ("1 page {size}", "1 page {size}"),
+    ("All {count} pages {size}", "Les {count} pages {size}"),
+    ("{count} pages {size}", "{count} pages {size}"),
+    ("1 page {size}: page {page}", "1 page {size} : page {page}"),
+    (
+        "{count} pages {size}: pages {pages}",
+        "{count} pages {size} : pages {pages}",
+    ),
+    (
+        "Failed to draw the annotations of page {page}",
+        "Impossible de dessiner les annotations de la page {page}",
+    ),
+    (
+        "drawn into the pages, then removed",
+        "dessinées dans les pages, puis supprimées",
+    ),
+    ("Annotations (F: flatten)", "Annotations (F : aplatir)"),
+    (
+        "Toggle annotation flattening",
+        "Activer ou non l'aplatissement des annotations",
+    ),
+    (
+        "Note text cannot be empty",
+        "Le texte de la note ne peut pas être vide",
+    ),
+    (
+        "Failed to draw the note on page {page}",
+        "Impossible de dessiner la note de la page {page}",
+    ),
+    (
+        "Annotate page {page} of {input} → {output}",
+        "Annoter la page {page} de {input} → {output}",
+    ),
+    (
+        "✅ Added a note on page {page} of '{input}' and saved to '{output}'",
+        "✅ Note ajoutée à la page {page} de '{input}', résultat enregistré dans '{output}'",
+    ),
+    (
+        "kept, with the links between extracted pages",
+        "conservées, avec les liens entre les pages extraites",
+    ),
+    ("left out", "retirées"),
+    ("Annotations (N: keep)", "Annotations (N : conserver)"),
+    (
+        "Keep or leave out the annotations",
+        "Conserver ou retirer les annotations",
+    ),
+    (
+        "'{first}' and '{second}' both define the named destination '{name}'",
+        "'{first}' et '{second}' définissent tous deux la destination nommée '{name}'",
+    ),
+    (
+        "'{first}' and '{second}' both define the form field '{name}'",
+        "'{first}' et '{second}' définissent tous deux le champ de formulaire '{name}'",
+    ),
+    (
+        "Failed to read the password file '{path}'",
+        "Impossible de lire le fichier de mots de passe '{path}'",
+    ),
+    (
+        "The password file '{path}' has no password",
+        "Le fichier de mots de passe '{path}' ne contient aucun mot de passe",
+    ),
+    (
+        "No password of the password file opens '{path}' ({count} tried)",
+        "Aucun mot de passe du fichier de mots de passe n'ouvre '{path}' ({count} essayés)",
+    ),
+    (
+        "🔓 Opened '{path}' with the password on line {line} of the password file",
+        "🔓 '{path}' ouvert avec le mot de passe de la ligne {line} du fichier de mots de passe",
+    ),
+    ("Provenance", "Provenance"),
+    ("Provenance:", "Provenance :"),
+    (
+        "✅ Rotated pages {pages} of '{input}' by {angle}° and saved to '{output}'",
+        "✅ Pages {pages} de '{input}' pivotées de {angle}° et enregistrées dans '{output}'",
+    ),
+    (
+        "Segments '{first}' and '{second}' would both be written to '{file}'",
+        "Les segments '{first}' et '{second}' seraient tous deux écrits dans '{file}'",
+    ),
+    (
+        "'{first}' and '{second}' would both be written to '{file}'",
+        "'{first}' et '{second}' seraient tous deux écrits dans '{file}'",
+    ),
+    (
+        "Failed to read config file '{path}'",
+        "Impossible de lire le fichier de configuration '{path}'",
+    ),
+    (
+        "Invalid config file '{path}'",
+        "Fichier de configuration invalide '{path}'",
+    ),
+    (
+        "line {number}: expected `key = value`, got '{line}'",
+        "ligne {number} : `clé = valeur` attendu, '{line}' trouvé",
+    ),
+    (
+        "line {number}: missing key before '='",
+        "ligne {number} : clé manquante avant '='",
+    ),
+    (
+        "Invalid value '{value}' for {key} (expected true or false)",
+        "Valeur invalide '{value}' pour {key} (true ou false attendu)",
+    ),
+    ("Failed to create '{path}'", "Impossible de créer '{path}'"),
+    (
+        "Failed to write config file '{path}'",
+        "Impossible d'écrire le fichier de configuration '{path}'",
+    ),
+    (
+        "page 0 at column {column} (pages start at 1)",
+        "page 0 à la colonne {column} (les pages commencent à 1)",
+    ),
+    (
+        "range {start}-{end} at column {column} goes backwards (start must be <= end)",
+        "la plage {start}-{end} à la colonne {column} est inversée (le début doit être <= la fin)",
+    ),
+    (
+        "step at column {column} without a range (e.g. 1-20:2)",
+        "pas à la colonne {column} sans plage (par ex. 1-20:2)",
+    ),
+    (
+        "step 0 at column {column} (at least 1)",
+        "pas de 0 à la colonne {column} (au moins 1)",
+    ),
+    (
+        "'{reference}' at column {column} needs the page count of the document",
+        "'{reference}' à la colonne {column} demande le nombre de pages du document",
+    ),
+    (
+        "'{reference}' at column {column} is before the first page (document has {page_count} pages)",
+        "'{reference}' à la colonne {column} est avant la première page (le document a {page_count} pages)",
+    ),
+    (
+        "page {page} at column {column} is past the end (document has {page_count} pages)",
+        "la page {page} à la colonne {column} est après la fin (le document a {page_count} pages)",
+    ),
+    (
+        "missing segment name before ':' at column {column}",
+        "nom de segment manquant avant ':' à la colonne {column}",
+    ),
+    (
+        "segment name '{name}' at column {column} cannot be a file name (no '/', '\\' or '..', and not only dots)",
+        "le nom de segment '{name}' à la colonne {column} ne peut pas servir de nom de fichier (ni '/', ni '\\', ni '..', et pas uniquement des points)",
+    ),
+    ("no pages listed", "aucune page indiquée"),
+    ("no segments listed", "aucun segment indiqué"),
+    (
+        "no page is left outside {pages}",
+        "aucune page ne reste en dehors de {pages}",
+    ),
+    (
+        "cannot delete all {page_count} pages of the document",
+        "impossible de supprimer les {page_count} pages du document",
+    ),
+];
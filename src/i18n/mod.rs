@@ -0,0 +1,243 @@
+mod fr;
+
+use crate::config::Config;
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Display;
+use std::sync::{LazyLock, RwLock};
+
+/// Languages the user-facing messages are available in, selected with `locale = "..."`
+/// in the config file or from the `LC_ALL`, `LC_MESSAGES` and `LANG` variables.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Locale {
+    English,
+    French,
+}
+
+/// Locale names accepted in the config file.
+pub const LOCALES: &[&str] = &["en", "fr"];
+
+static CURRENT_LOCALE: RwLock<Locale> = RwLock::new(Locale::English);
+
+static FRENCH: LazyLock<HashMap<&'static str, &'static str>> =
+    LazyLock::new(|| fr::MESSAGES.iter().copied().collect());
+
+/// Locale used for the next messages.
+pub fn current_locale() -> Locale {
+    *CURRENT_LOCALE.read().unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn set_locale(locale: Locale) {
+    *CURRENT_LOCALE.write().unwrap_or_else(|e| e.into_inner()) = locale;
+}
+
+impl Locale {
+    /**
+     * Parse a locale name, either as written in the config file ("fr") or as found in
+     * the environment ("fr_FR.UTF-8").
+     * @param name The locale name.
+     * @returns The locale, or None when the language has no translation.
+     */
+    pub fn from_name(name: &str) -> Option<Self> {
+        let language = name
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        match language.as_str() {
+            "en" | "c" | "posix" => Some(Self::English),
+            "fr" => Some(Self::French),
+            _ => None,
+        }
+    }
+
+    /**
+     * Pick the locale: `locale` in the config file, or else the first of `LC_ALL`,
+     * `LC_MESSAGES` and `LANG` that is set, falling back to English.
+     * @param config The user configuration.
+     * @returns The locale.
+     * @throws String naming an unknown locale in the config file.
+     */
+    pub fn from_config(config: &Config) -> Result<Self, String> {
+        Self::resolve(config, environment_locale().as_deref())
+    }
+
+    /// Locale of the `LC_ALL`, `LC_MESSAGES` and `LANG` variables, used for the errors
+    /// of the config file itself, before its `locale` setting is known.
+    pub fn from_environment() -> Self {
+        Self::resolve(&Config::default(), environment_locale().as_deref()).unwrap_or(Self::English)
+    }
+
+    fn resolve(config: &Config, environment: Option<&str>) -> Result<Self, String> {
+        if let Some(name) = config.get("locale") {
+            return Self::from_name(name).ok_or_else(|| {
+                format!(
+                    "Unknown locale '{}' (expected one of: {})",
+                    name,
+                    LOCALES.join(", ")
+                )
+            });
+        }
+        // An untranslated system language is not an error, messages stay in English
+        Ok(environment
+            .and_then(Self::from_name)
+            .unwrap_or(Self::English))
+    }
+}
+
+fn environment_locale() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+}
+
+/**
+ * Translate a message into the current locale.
+ * Messages are identified by their English text, which is also the fallback when a
+ * translation is missing.
+ * @param message The English message.
+ * @returns The translated message.
+ */
+pub fn translate(message: &'static str) -> &'static str {
+    catalog(current_locale())
+        .and_then(|messages| messages.get(message).copied())
+        .unwrap_or(message)
+}
+
+/// Whether a message has a translation in a locale (English messages always do).
+#[cfg(test)]
+pub fn has_translation(locale: Locale, message: &str) -> bool {
+    catalog(locale).is_none_or(|messages| messages.contains_key(message))
+}
+
+fn catalog(locale: Locale) -> Option<&'static HashMap<&'static str, &'static str>> {
+    match locale {
+        Locale::English => None,
+        Locale::French => Some(&FRENCH),
+    }
+}
+
+/**
+ * Replace the `{name}` placeholders of a translated message, in a single pass so a value
+ * containing braces (a file named `{backup}.pdf`) is never taken for a placeholder.
+ * @param message The message, with placeholders.
+ * @param args The value of each placeholder.
+ * @returns The message with every known placeholder replaced.
+ */
+pub fn fill(message: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut text = String::with_capacity(message.len());
+    let mut rest = message;
+    while let Some(start) = rest.find('{') {
+        text.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let placeholder = after.find('}').and_then(|end| {
+            let value = args.iter().find(|(name, _)| *name == &after[..end])?.1;
+            Some((end, value))
+        });
+        match placeholder {
+            Some((end, value)) => {
+                text.push_str(&value.to_string());
+                rest = &after[end + 1..];
+            }
+            None => {
+                text.push('{');
+                rest = after;
+            }
+        }
+    }
+    text.push_str(rest);
+    text
+}
+
+/// Translate a message, e.g. `tr!("Quit")` or `tr!("Saved '{file}'", file = path)`.
+macro_rules! tr {
+    ($message:literal) => {
+        $crate::i18n::translate($message)
+    };
+    ($message:literal, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::fill(
+            $crate::i18n::translate($message),
+            &[$((stringify!($name), &$value as &dyn std::fmt::Display)),+],
+        )
+    };
+}
+pub(crate) use tr;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_from_name() {
+        assert_eq!(Locale::from_name("fr_FR.UTF-8"), Some(Locale::French));
+        assert_eq!(Locale::from_name("fr"), Some(Locale::French));
+        assert_eq!(Locale::from_name("en_GB"), Some(Locale::English));
+        assert_eq!(Locale::from_name("C.UTF-8"), Some(Locale::English));
+        assert_eq!(Locale::from_name("de_DE"), None);
+    }
+
+    #[test]
+    fn test_locale_from_config() {
+        let empty = Config::default();
+        assert_eq!(Locale::resolve(&empty, None).unwrap(), Locale::English);
+        assert_eq!(
+            Locale::resolve(&empty, Some("fr_CA.UTF-8")).unwrap(),
+            Locale::French
+        );
+        assert_eq!(
+            Locale::resolve(&empty, Some("de_DE")).unwrap(),
+            Locale::English
+        );
+
+        // The config file wins over the environment
+        let english = Config::parse("locale = en").unwrap();
+        assert_eq!(
+            Locale::resolve(&english, Some("fr_FR")).unwrap(),
+            Locale::English
+        );
+        assert!(Locale::resolve(&Config::parse("locale = klingon").unwrap(), None).is_err());
+    }
+
+    #[test]
+    fn test_fill() {
+        assert_eq!(
+            fill(
+                "Saved '{file}' ({pages} pages)",
+                &[("file", &"a.pdf"), ("pages", &3)]
+            ),
+            "Saved 'a.pdf' (3 pages)"
+        );
+        // Values are never read as placeholders, unknown placeholders are kept
+        assert_eq!(
+            fill(
+                "Moved '{path}' to '{backup}' {unknown",
+                &[("path", &"{backup}.pdf"), ("backup", &"old.pdf")]
+            ),
+            "Moved '{backup}.pdf' to 'old.pdf' {unknown"
+        );
+        assert_eq!(fill("{missing} {", &[]), "{missing} {");
+    }
+
+    #[test]
+    fn test_french_catalog() {
+        // Every placeholder of a message must be kept by its translation
+        for (english, french) in fr::MESSAGES {
+            let placeholders = |text: &str| {
+                let mut names: Vec<String> = text
+                    .split('{')
+                    .skip(1)
+                    .filter_map(|rest| Some(rest.split_once('}')?.0.to_string()))
+                    .collect();
+                names.sort();
+                names
+            };
+            assert_eq!(placeholders(english), placeholders(french), "{}", english);
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        for (english, _) in fr::MESSAGES {
+            assert!(seen.insert(english), "duplicate message: {}", english);
+        }
+    }
+}
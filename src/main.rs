@@ -1,11 +1,51 @@
+mod build_info;
 mod cli;
+mod config;
+mod i18n;
 mod pdf;
 mod tui;
 
-use anyhow::Result;
+use anyhow::{Context, Result, anyhow};
 use clap::Parser;
 
 fn main() -> Result<()> {
     let cli = cli::Cli::parse();
-    cli::handle_command(Some(cli.command))
+
+    // Messages of both the CLI and the TUI follow the configured (or system) language; errors
+    // of the config file itself can only follow the system one
+    i18n::set_locale(i18n::Locale::from_environment());
+    let config = config::Config::load()?;
+    cli::set_quiet(cli.quiet);
+    cli::set_timings(cli.timings);
+    // A mistyped locale only costs the translation, every command still runs
+    match i18n::Locale::from_config(&config) {
+        Ok(locale) => i18n::set_locale(locale),
+        Err(e) => cli::warn(&format!(
+            "{:#}",
+            anyhow!(e).context(i18n::tr!("Invalid locale in config file"))
+        )),
+    }
+    // Output settings are only checked by the commands writing files, so a typo in them does
+    // not keep `info`, `links` or the others from running
+    if let Some(settings) = cli.command.output_args() {
+        let durable = pdf::output::durable_from_config(&config, settings.durable)
+            .map_err(|e| anyhow!(e))
+            .context(i18n::tr!("Invalid durable setting in config file"))?;
+        pdf::output::set_durable(durable);
+        let object_streams =
+            pdf::output::object_streams_from_config(&config, settings.no_object_streams)
+                .map_err(|e| anyhow!(e))
+                .context(i18n::tr!("Invalid object_streams setting in config file"))?;
+        pdf::output::set_object_streams(object_streams);
+    }
+    if let Some(path) = &cli.password_file {
+        pdf::passwords::set_passwords(pdf::passwords::read_password_file(path)?);
+    }
+
+    let result = cli::handle_command(Some(cli.command));
+    cli::report_unlocked();
+    if let Err(error) = &result {
+        cli::report_error(error);
+    }
+    result
 }
@@ -0,0 +1,597 @@
+use super::inspect::{media_box, page_infos};
+use super::utils::{load_document, save_document};
+use super::watermark::{
+    WatermarkPosition, add_page_resource, append_page_content, display_matrix, placement, reals,
+    text_width, unused_resource_name, win_ansi,
+};
+use crate::i18n::tr;
+use anyhow::{Context, Result, bail};
+use lopdf::content::{Content, Operation};
+use lopdf::{
+    Dictionary, Document, Object, ObjectId, Stream, StringFormat, dictionary, text_string,
+};
+use std::collections::HashSet;
+
+/// Annotation flags (PDF 32000-1, table 165) of annotations that are never displayed.
+const HIDDEN: i64 = 1 << 1;
+const NO_VIEW: i64 = 1 << 5;
+/// Annotation flag of annotations that are printed with the page.
+const PRINT: i64 = 1 << 2;
+
+/// Font size of text notes, in points.
+const NOTE_FONT_SIZE: f32 = 12.0;
+
+/// Space between the text of a note and its border, in points.
+const NOTE_PADDING: f32 = 4.0;
+
+/// Depth of the Helvetica descenders below the baseline, per 1000 units of font size.
+const DESCENT: f32 = 207.0;
+
+/// A line of text put on a page as a FreeText annotation, such as a review stamp.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextNote {
+    /// The page the note goes on, from 1.
+    pub page: u32,
+    pub text: String,
+    /// Where the note goes, as seen in a viewer. A diagonal note is put in the center.
+    pub position: WatermarkPosition,
+}
+
+/// What becomes of an annotation when the annotations of a page are flattened.
+enum Flattening {
+    /// Drawn into the page content with this appearance stream, then removed.
+    Draw {
+        appearance: ObjectId,
+        matrix: [f32; 6],
+    },
+    /// Removed without being drawn, as it is never displayed.
+    Remove,
+    /// Left on the page: links, form fields, and annotations without an appearance to draw.
+    Keep,
+}
+
+/**
+ * Burn the appearance of the annotations (stamps, highlights, ink signatures, comments...) into
+ * the content of their page, then remove them, so that the markings stay when the annotations
+ * are stripped or ignored by a viewer or a printer. Links and form fields are kept, as are
+ * annotations without an appearance stream, which nothing would show once removed. Hidden
+ * annotations are removed without being drawn, and popups go with their annotation.
+ * The removed annotations stay in the document until its unused objects are pruned.
+ * @param doc The document to change.
+ * @returns The number of annotations drawn into the pages.
+ * @throws anyhow::Error if a page is not a dictionary.
+ */
+pub fn flatten_annotations(doc: &mut Document) -> Result<usize> {
+    let mut flattened = 0;
+    let mut save_state = None;
+    for (page_num, page_id) in doc.get_pages() {
+        let Ok(annotations) = doc
+            .get_dictionary(page_id)
+            .and_then(|page| page.get(b"Annots"))
+            .and_then(|annots| doc.dereference(annots))
+            .and_then(|(_, annots)| annots.as_array())
+            .cloned()
+        else {
+            continue;
+        };
+
+        let mut kept = Vec::new();
+        let mut removed = HashSet::new();
+        let mut operations = Vec::new();
+        for annotation in &annotations {
+            let Ok((id, Object::Dictionary(dict))) = doc.dereference(annotation) else {
+                continue;
+            };
+            match flattening(doc, dict) {
+                Flattening::Draw { appearance, matrix } => {
+                    let name = unused_resource_name(doc, page_id, "Annot");
+                    make_form(doc, appearance);
+                    add_page_resource(doc, page_id, b"XObject", &name, appearance)
+                        .with_context(|| tr!("Page {page} is not a dictionary", page = page_num))?;
+                    operations.extend([
+                        Operation::new("q", vec![]),
+                        Operation::new("cm", reals(&matrix)),
+                        Operation::new("Do", vec![Object::Name(name.into_bytes())]),
+                        Operation::new("Q", vec![]),
+                    ]);
+                    flattened += 1;
+                    removed.extend(id);
+                }
+                Flattening::Remove => removed.extend(id),
+                Flattening::Keep => kept.push(annotation.clone()),
+            }
+        }
+        if removed.is_empty() {
+            continue;
+        }
+        // A popup only shows the text of its annotation
+        kept.retain(|annotation| {
+            !doc.dereference(annotation)
+                .ok()
+                .and_then(|(_, popup)| popup.as_dict().ok())
+                .and_then(|popup| popup.get(b"Parent").and_then(Object::as_reference).ok())
+                .is_some_and(|parent| removed.contains(&parent))
+        });
+
+        let page = doc
+            .get_dictionary_mut(page_id)
+            .with_context(|| tr!("Page {page} is not a dictionary", page = page_num))?;
+        if kept.is_empty() {
+            page.remove(b"Annots");
+        } else {
+            page.set("Annots", kept);
+        }
+        if operations.is_empty() {
+            continue;
+        }
+        operations.insert(0, Operation::new("Q", vec![]));
+        let content = Content { operations }.encode().with_context(|| {
+            tr!(
+                "Failed to draw the annotations of page {page}",
+                page = page_num
+            )
+        })?;
+        let save_state = *save_state
+            .get_or_insert_with(|| doc.add_object(Stream::new(Dictionary::new(), b"q\n".to_vec())));
+        append_page_content(doc, page_id, save_state, content)
+            .with_context(|| tr!("Page {page} is not a dictionary", page = page_num))?;
+    }
+    Ok(flattened)
+}
+
+/**
+ * Put a text note on a page of a PDF and save the result.
+ * @param input The input PDF file path.
+ * @param output The output PDF file path.
+ * @param note The page, text and position of the note.
+ * @throws anyhow::Error if the text is empty, the page does not exist or the PDF cannot be written.
+ */
+pub fn annotate_page(input: &str, output: &str, note: &TextNote) -> Result<()> {
+    log::debug!("Loading '{}'", input);
+    let mut doc = load_document(input)?;
+    add_text_note(&mut doc, note)?;
+    save_document(&mut doc, output)?;
+    Ok(())
+}
+
+/**
+ * Add a FreeText annotation showing a line of text in a pale yellow box with a thin border.
+ * The annotation carries its own appearance, set in Helvetica like the watermarks, so it looks
+ * the same in every viewer, prints with the page and can be flattened. Characters outside
+ * Latin-1 are drawn as '?', but the annotation keeps the whole text as its contents.
+ * @param doc The document to change.
+ * @param note The page, text and position of the note.
+ * @returns The id of the annotation.
+ * @throws anyhow::Error if the text is empty, the page does not exist or is not a dictionary.
+ */
+pub fn add_text_note(doc: &mut Document, note: &TextNote) -> Result<ObjectId> {
+    if note.text.trim().is_empty() {
+        bail!(tr!("Note text cannot be empty"));
+    }
+    let pages = doc.get_pages();
+    let Some(&page_id) = pages.get(&note.page) else {
+        bail!(tr!(
+            "Invalid page number: {page}. PDF has {count} pages (1-{count})",
+            page = note.page,
+            count = pages.len()
+        ));
+    };
+    let bounds = media_box(doc, page_id).unwrap_or([0.0, 0.0, 595.28, 841.89]);
+    let rotation = page_infos(doc)[note.page as usize - 1].rotation;
+
+    let text = win_ansi(&note.text);
+    let width = text_width(&text) * NOTE_FONT_SIZE / 1000.0 + 2.0 * NOTE_PADDING;
+    let height = NOTE_FONT_SIZE + 2.0 * NOTE_PADDING;
+    let (page_width, page_height) = (bounds[2] - bounds[0], bounds[3] - bounds[1]);
+    let shown = if rotation % 180 == 0 {
+        (page_width, page_height)
+    } else {
+        (page_height, page_width)
+    };
+    let position = match note.position {
+        WatermarkPosition::Diagonal => WatermarkPosition::Center,
+        position => position,
+    };
+    let [.., x, y] = placement(position, shown, (width, height));
+
+    // The rectangle is in user space, the box is turned with the page so it reads upright
+    let display = display_matrix(rotation, bounds);
+    let corners = [(x, y), (x + width, y + height)].map(|(x, y)| {
+        (
+            display[0] * x + display[2] * y + display[4],
+            display[1] * x + display[3] * y + display[5],
+        )
+    });
+    let rect = [
+        corners[0].0.min(corners[1].0),
+        corners[0].1.min(corners[1].1),
+        corners[0].0.max(corners[1].0),
+        corners[0].1.max(corners[1].1),
+    ];
+
+    let font = doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Helvetica",
+        "Encoding" => "WinAnsiEncoding",
+    });
+    let operations = vec![
+        Operation::new("rg", reals(&[1.0, 1.0, 0.8])),
+        Operation::new("re", reals(&[0.0, 0.0, width, height])),
+        Operation::new("f", vec![]),
+        Operation::new("w", vec![0.5.into()]),
+        Operation::new("G", vec![0.into()]),
+        Operation::new("re", reals(&[0.25, 0.25, width - 0.5, height - 0.5])),
+        Operation::new("S", vec![]),
+        Operation::new("BT", vec![]),
+        Operation::new("Tf", vec!["Helv".into(), NOTE_FONT_SIZE.into()]),
+        Operation::new("g", vec![0.into()]),
+        Operation::new(
+            "Td",
+            reals(&[
+                NOTE_PADDING,
+                NOTE_PADDING + DESCENT * NOTE_FONT_SIZE / 1000.0,
+            ]),
+        ),
+        Operation::new("Tj", vec![Object::String(text, StringFormat::Literal)]),
+        Operation::new("ET", vec![]),
+    ];
+    let content = Content { operations }
+        .encode()
+        .with_context(|| tr!("Failed to draw the note on page {page}", page = note.page))?;
+    let appearance = doc.add_object(Stream::new(
+        dictionary! {
+            "Type" => "XObject",
+            "Subtype" => "Form",
+            "BBox" => reals(&[0.0, 0.0, width, height]),
+            "Matrix" => reals(&[display[0], display[1], display[2], display[3], 0.0, 0.0]),
+            "Resources" => dictionary! { "Font" => dictionary! { "Helv" => font } },
+        },
+        content,
+    ));
+    let annotation = doc.add_object(dictionary! {
+        "Type" => "Annot",
+        "Subtype" => "FreeText",
+        "Rect" => reals(&rect),
+        "Contents" => text_string(&note.text),
+        "DA" => Object::string_literal(format!("/Helv {NOTE_FONT_SIZE} Tf 0 g")),
+        "F" => PRINT,
+        "P" => page_id,
+        "AP" => dictionary! { "N" => appearance },
+    });
+
+    let mut annotations = doc
+        .get_dictionary(page_id)
+        .and_then(|page| page.get(b"Annots"))
+        .and_then(|annots| doc.dereference(annots))
+        .and_then(|(_, annots)| annots.as_array())
+        .cloned()
+        .unwrap_or_default();
+    annotations.push(annotation.into());
+    doc.get_dictionary_mut(page_id)
+        .with_context(|| tr!("Page {page} is not a dictionary", page = note.page))?
+        .set("Annots", annotations);
+    Ok(annotation)
+}
+
+/// Decide what flattening does to an annotation.
+fn flattening(doc: &Document, annotation: &Dictionary) -> Flattening {
+    let subtype = annotation.get(b"Subtype").and_then(Object::as_name);
+    if matches!(subtype, Ok(b"Link" | b"Widget" | b"Popup")) {
+        return Flattening::Keep;
+    }
+    let flags = annotation.get(b"F").and_then(Object::as_i64).unwrap_or(0);
+    if flags & (HIDDEN | NO_VIEW) != 0 {
+        return Flattening::Remove;
+    }
+    let Some(appearance) = normal_appearance(doc, annotation) else {
+        return Flattening::Keep;
+    };
+    let Ok(stream) = doc.get_object(appearance).and_then(Object::as_stream) else {
+        return Flattening::Keep;
+    };
+    let rect = numbers(doc, annotation.get(b"Rect").ok());
+    let bbox = numbers(doc, stream.dict.get(b"BBox").ok());
+    let matrix =
+        numbers(doc, stream.dict.get(b"Matrix").ok()).unwrap_or([1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+    match (rect, bbox) {
+        (Some(rect), Some(bbox)) => match appearance_matrix(rect, bbox, matrix) {
+            Some(matrix) => Flattening::Draw { appearance, matrix },
+            None => Flattening::Keep,
+        },
+        _ => Flattening::Keep,
+    }
+}
+
+/**
+ * The appearance stream shown for an annotation: its normal appearance, or for an annotation
+ * with several states (a check box, a toggled stamp) the one of its current state.
+ */
+fn normal_appearance(doc: &Document, annotation: &Dictionary) -> Option<ObjectId> {
+    let appearances = doc
+        .dereference(annotation.get(b"AP").ok()?)
+        .ok()?
+        .1
+        .as_dict()
+        .ok()?;
+    let normal = appearances.get(b"N").ok()?;
+    if let Ok(id) = normal.as_reference()
+        && doc.get_object(id).and_then(Object::as_stream).is_ok()
+    {
+        return Some(id);
+    }
+    let states = doc.dereference(normal).ok()?.1.as_dict().ok()?;
+    let state = annotation.get(b"AS").and_then(Object::as_name).ok()?;
+    states.get(state).and_then(Object::as_reference).ok()
+}
+
+/**
+ * Matrix drawing an appearance stream in the rectangle of its annotation (PDF 32000-1, 12.5.5):
+ * the bounding box of the appearance, transformed by its own matrix, is scaled and moved onto
+ * the rectangle.
+ * @param rect The rectangle of the annotation on the page.
+ * @param bbox The bounding box of the appearance stream.
+ * @param matrix The matrix of the appearance stream.
+ * @returns None when the transformed bounding box or the rectangle is empty.
+ */
+fn appearance_matrix(rect: [f32; 4], bbox: [f32; 4], matrix: [f32; 6]) -> Option<[f32; 6]> {
+    let corners = [
+        (bbox[0], bbox[1]),
+        (bbox[2], bbox[1]),
+        (bbox[0], bbox[3]),
+        (bbox[2], bbox[3]),
+    ]
+    .map(|(x, y)| {
+        (
+            matrix[0] * x + matrix[2] * y + matrix[4],
+            matrix[1] * x + matrix[3] * y + matrix[5],
+        )
+    });
+    let min_x = corners.iter().map(|c| c.0).fold(f32::INFINITY, f32::min);
+    let max_x = corners
+        .iter()
+        .map(|c| c.0)
+        .fold(f32::NEG_INFINITY, f32::max);
+    let min_y = corners.iter().map(|c| c.1).fold(f32::INFINITY, f32::min);
+    let max_y = corners
+        .iter()
+        .map(|c| c.1)
+        .fold(f32::NEG_INFINITY, f32::max);
+    let (left, right) = (rect[0].min(rect[2]), rect[0].max(rect[2]));
+    let (bottom, top) = (rect[1].min(rect[3]), rect[1].max(rect[3]));
+    if max_x - min_x <= 0.0 || max_y - min_y <= 0.0 || right - left <= 0.0 || top - bottom <= 0.0 {
+        return None;
+    }
+    let scale_x = (right - left) / (max_x - min_x);
+    let scale_y = (top - bottom) / (max_y - min_y);
+    Some([
+        scale_x,
+        0.0,
+        0.0,
+        scale_y,
+        left - min_x * scale_x,
+        bottom - min_y * scale_y,
+    ])
+}
+
+/// Make sure an appearance stream can be drawn with `Do`, which needs a form XObject.
+fn make_form(doc: &mut Document, appearance: ObjectId) {
+    if let Ok(Object::Stream(stream)) = doc.get_object_mut(appearance) {
+        stream.dict.set("Type", "XObject");
+        stream.dict.set("Subtype", "Form");
+    }
+}
+
+/// Read an array of N numbers, such as a rectangle or a matrix.
+fn numbers<const N: usize>(doc: &Document, array: Option<&Object>) -> Option<[f32; N]> {
+    let values = doc.dereference(array?).ok()?.1.as_array().ok()?;
+    let numbers: Vec<f32> = values
+        .iter()
+        .filter_map(|v| doc.dereference(v).ok()?.1.as_float().ok())
+        .collect();
+    numbers.try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_appearance_matrix() {
+        // The appearance fills the rectangle, scaled from its bounding box
+        assert_eq!(
+            appearance_matrix(
+                [100.0, 200.0, 300.0, 250.0],
+                [0.0, 0.0, 100.0, 50.0],
+                [1.0, 0.0, 0.0, 1.0, 0.0, 0.0]
+            ),
+            Some([2.0, 0.0, 0.0, 1.0, 100.0, 200.0])
+        );
+        // A bounding box turned by the matrix of the appearance is measured once turned
+        assert_eq!(
+            appearance_matrix(
+                [10.0, 10.0, 60.0, 110.0],
+                [0.0, 0.0, 100.0, 50.0],
+                [0.0, 1.0, -1.0, 0.0, 0.0, 0.0]
+            ),
+            Some([1.0, 0.0, 0.0, 1.0, 60.0, 10.0])
+        );
+        assert_eq!(
+            appearance_matrix(
+                [0.0, 0.0, 10.0, 10.0],
+                [0.0, 0.0, 0.0, 50.0],
+                [1.0, 0.0, 0.0, 1.0, 0.0, 0.0]
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_flatten_annotations() {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let content_id = doc.add_object(Stream::new(dictionary! {}, b"0 0 m 10 10 l S".to_vec()));
+        let stamp = doc.add_object(Stream::new(
+            dictionary! { "BBox" => vec![0.into(), 0.into(), 100.into(), 50.into()] },
+            b"1 0 0 rg 0 0 100 50 re f".to_vec(),
+        ));
+        let checked = doc.add_object(Stream::new(
+            dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Form",
+                "BBox" => vec![0.into(), 0.into(), 10.into(), 10.into()],
+            },
+            b"0 0 10 10 re f".to_vec(),
+        ));
+        let unchecked = doc.add_object(Stream::new(
+            dictionary! { "BBox" => vec![0.into(), 0.into(), 10.into(), 10.into()] },
+            vec![],
+        ));
+        let rect = |values: [i64; 4]| values.map(Object::Integer).to_vec();
+
+        let stamp_annotation = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Stamp",
+            "Rect" => rect([100, 200, 300, 250]),
+            "AP" => dictionary! { "N" => stamp },
+        });
+        let popup = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Popup",
+            "Rect" => rect([300, 200, 400, 300]),
+            "Parent" => stamp_annotation,
+        });
+        let toggled = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Square",
+            "Rect" => rect([0, 0, 20, 20]),
+            "AP" => dictionary! { "N" => dictionary! { "On" => checked, "Off" => unchecked } },
+            "AS" => "On",
+        });
+        let hidden = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Stamp",
+            "F" => HIDDEN,
+            "Rect" => rect([0, 0, 20, 20]),
+            "AP" => dictionary! { "N" => stamp },
+        });
+        let note = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Text",
+            "Rect" => rect([0, 700, 20, 720]),
+            "Contents" => Object::string_literal("No appearance"),
+        });
+        let link = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Link",
+            "Rect" => rect([0, 0, 50, 50]),
+            "AP" => dictionary! { "N" => stamp },
+        });
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "Contents" => content_id,
+            "MediaBox" => rect([0, 0, 612, 792]),
+            "Annots" => vec![
+                stamp_annotation.into(),
+                popup.into(),
+                toggled.into(),
+                hidden.into(),
+                note.into(),
+                link.into(),
+            ],
+        });
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => vec![page_id.into()],
+                "Count" => 1,
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! { "Type" => "Catalog", "Pages" => pages_id });
+        doc.trailer.set("Root", catalog_id);
+
+        assert_eq!(flatten_annotations(&mut doc).unwrap(), 2);
+
+        let page = doc.get_dictionary(page_id).unwrap();
+        assert_eq!(
+            page.get(b"Annots").unwrap().as_array().unwrap(),
+            &vec![Object::Reference(note), Object::Reference(link)]
+        );
+        let xobjects = page
+            .get(b"Resources")
+            .and_then(Object::as_dict)
+            .and_then(|resources| resources.get(b"XObject"))
+            .and_then(Object::as_dict)
+            .unwrap();
+        assert_eq!(xobjects.get(b"Annot1").unwrap(), &Object::Reference(stamp));
+        assert_eq!(
+            xobjects.get(b"Annot2").unwrap(),
+            &Object::Reference(checked)
+        );
+        let form = doc.get_object(stamp).and_then(Object::as_stream).unwrap();
+        assert_eq!(
+            form.dict.get(b"Subtype").unwrap(),
+            &Object::Name(b"Form".to_vec())
+        );
+
+        let content = String::from_utf8(doc.get_page_content(page_id).unwrap()).unwrap();
+        assert!(content.starts_with("q\n0 0 m 10 10 l S"), "{content}");
+        assert!(
+            content.contains("q\n2 0 0 1 100 200 cm\n/Annot1 Do\nQ"),
+            "{content}"
+        );
+        assert!(content.contains("/Annot2 Do"), "{content}");
+
+        // Once flattened, a page has nothing left to draw
+        assert_eq!(flatten_annotations(&mut doc).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_add_text_note() {
+        let mut doc = load_document("tests/tests_pdf/c.pdf").unwrap();
+        let pages = doc.get_pages();
+        let [_, _, width, height] = media_box(&doc, pages[&1]).unwrap();
+        doc.get_dictionary_mut(pages[&2]).unwrap().set("Rotate", 90);
+        let note = |page: u32, text: &str| TextNote {
+            page,
+            text: text.to_string(),
+            position: WatermarkPosition::TopRight,
+        };
+        let rect = |doc: &Document, id: ObjectId| {
+            numbers::<4>(doc, doc.get_dictionary(id).unwrap().get(b"Rect").ok()).unwrap()
+        };
+
+        let upright = add_text_note(&mut doc, &note(1, "Reviewed 2024-05-01")).unwrap();
+        let [left, bottom, right, top] = rect(&doc, upright);
+        assert_eq!((right, top), (width - 28.0, height - 28.0));
+        assert_eq!(top - bottom, NOTE_FONT_SIZE + 2.0 * NOTE_PADDING);
+        assert!(left > width / 2.0);
+        let annotation = doc.get_dictionary(upright).unwrap();
+        assert_eq!(
+            annotation.get(b"Subtype").unwrap(),
+            &Object::Name(b"FreeText".to_vec())
+        );
+        assert_eq!(
+            lopdf::decode_text_string(annotation.get(b"Contents").unwrap()).unwrap(),
+            "Reviewed 2024-05-01"
+        );
+
+        // The top-right corner of a page turned clockwise is its top-left corner unturned
+        let turned = add_text_note(&mut doc, &note(2, "Relu ✓")).unwrap();
+        let [left, bottom, right, top] = rect(&doc, turned);
+        assert_eq!((left, top), (28.0, height - 28.0));
+        assert_eq!(right - left, NOTE_FONT_SIZE + 2.0 * NOTE_PADDING);
+        assert!(bottom > height / 2.0);
+
+        assert!(add_text_note(&mut doc, &note(8, "Late")).is_err());
+        assert!(add_text_note(&mut doc, &note(1, " ")).is_err());
+
+        // Notes carry their appearance, so they can be flattened like any other markup
+        assert_eq!(flatten_annotations(&mut doc).unwrap(), 2);
+        let content =
+            String::from_utf8_lossy(&doc.get_page_content(pages[&2]).unwrap()).into_owned();
+        assert!(content.contains("/Annot1 Do"), "{content}");
+    }
+}
@@ -0,0 +1,73 @@
+use crate::i18n::tr;
+use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use thiserror::Error;
+
+/// Asks the operation run on another thread to stop, shared between that thread and the one
+/// cancelling it.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The error an operation stops with once its token is cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("{}", tr!("Cancelled"))]
+pub struct Cancelled;
+
+thread_local! {
+    // Each thread runs its own operation, so cancelling one does not stop another
+    static TOKEN: RefCell<Option<CancelToken>> = const { RefCell::new(None) };
+}
+
+/// Stop the operations run on the current thread from now on once the token is cancelled.
+pub fn watch_cancellation(token: CancelToken) {
+    TOKEN.with(|slot| *slot.borrow_mut() = Some(token));
+}
+
+/**
+ * Check whether the operation run on the current thread was cancelled, between two pages or
+ * before its output is moved into place.
+ * @throws Cancelled once the token watched by the current thread is cancelled.
+ */
+pub fn check_cancelled() -> Result<(), Cancelled> {
+    TOKEN.with(|slot| match slot.borrow().as_ref() {
+        Some(token) if token.is_cancelled() => Err(Cancelled),
+        _ => Ok(()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancellation_is_per_thread() {
+        assert_eq!(check_cancelled(), Ok(()));
+        let token = CancelToken::new();
+        let worker = token.clone();
+        let handle = std::thread::spawn(move || {
+            watch_cancellation(worker);
+            while check_cancelled().is_ok() {
+                std::thread::yield_now();
+            }
+        });
+        token.cancel();
+        handle.join().unwrap();
+        // The token was only watched by the worker
+        assert_eq!(check_cancelled(), Ok(()));
+    }
+}
@@ -0,0 +1,512 @@
+use super::annotations::flatten_annotations;
+use super::output::object_streams;
+use super::utils::{load_document, save_document, save_document_with};
+use crate::i18n::tr;
+use anyhow::{Context, Result};
+use lopdf::content::Content;
+use lopdf::{Dictionary, Document, Object, ObjectId, Stream};
+use std::collections::HashMap;
+use std::fs;
+use std::ops::Range;
+
+/// Images are only downsampled when their resolution exceeds the target by this factor,
+/// so that re-encoding a nearly matching image does not lose quality for nothing.
+const DOWNSAMPLE_THRESHOLD: f32 = 1.1;
+
+/// Flate level of the object streams of a compressed output.
+const BEST_COMPRESSION: u32 = 9;
+
+/// What `compress_pdf` does to shrink a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressOptions {
+    /// Recompress streams, and the object streams of the output, at the best Flate level.
+    pub recompress_streams: bool,
+    /// Downsample images displayed above this resolution (in dots per inch).
+    pub max_image_dpi: Option<u32>,
+    /// Remove the document information dictionary and the XMP metadata.
+    pub strip_metadata: bool,
+    /// Draw the appearance of the annotations into the pages, then remove the annotations.
+    pub flatten_annotations: bool,
+}
+
+impl Default for CompressOptions {
+    fn default() -> Self {
+        Self {
+            recompress_streams: true,
+            max_image_dpi: None,
+            strip_metadata: false,
+            flatten_annotations: false,
+        }
+    }
+}
+
+/// Outcome of a compression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressReport {
+    /// Size of the input file, in bytes.
+    pub before: u64,
+    /// Size of the output file, in bytes.
+    pub after: u64,
+    /// Number of images that were downsampled.
+    pub downsampled_images: usize,
+    /// Number of annotations drawn into the pages.
+    pub flattened_annotations: usize,
+}
+
+impl CompressReport {
+    /**
+     * Share of the input size saved by the compression.
+     * @returns The saving in percent, negative when the output grew.
+     */
+    pub fn saving_percent(&self) -> f64 {
+        if self.before == 0 {
+            return 0.0;
+        }
+        (1.0 - self.after as f64 / self.before as f64) * 100.0
+    }
+}
+
+/**
+ * Shrink a PDF and save the result.
+ * Only 8-bit RGB, grayscale and CMYK images that lopdf can decode are downsampled;
+ * JPEG images and images drawn inside form XObjects are left as they are.
+ * @param input The input PDF file path.
+ * @param output The output PDF file path.
+ * @param options What to do to shrink the document.
+ * @returns The sizes before and after, and the number of downsampled images.
+ * @throws anyhow::Error if the PDF cannot be loaded or written.
+ */
+pub fn compress_pdf(
+    input: &str,
+    output: &str,
+    options: &CompressOptions,
+) -> Result<CompressReport> {
+    let before = fs::metadata(input)
+        .with_context(|| tr!("Failed to load PDF '{path}'", path = input))?
+        .len();
+
+    log::debug!("Loading '{}'", input);
+    let mut doc = load_document(input)?;
+
+    let downsampled_images = match options.max_image_dpi {
+        Some(dpi) => downsample_images(&mut doc, dpi),
+        None => 0,
+    };
+    // The annotations taken out are pruned, so that no comment is left in the file
+    let flattened_annotations = if options.flatten_annotations {
+        let flattened = flatten_annotations(&mut doc)?;
+        doc.prune_objects();
+        flattened
+    } else {
+        0
+    };
+    if options.strip_metadata {
+        strip_metadata(&mut doc);
+    }
+
+    if options.recompress_streams {
+        recompress_streams(&mut doc);
+        save_document_with(&mut doc, output, object_streams(), BEST_COMPRESSION)?;
+    } else {
+        save_document(&mut doc, output)?;
+    }
+
+    let after = fs::metadata(output)
+        .with_context(|| tr!("Failed to save PDF '{path}'", path = output))?
+        .len();
+    log::info!(
+        "Compressed '{}' from {} to {} bytes ({} images downsampled, {} annotations flattened)",
+        input,
+        before,
+        after,
+        downsampled_images,
+        flattened_annotations
+    );
+
+    Ok(CompressReport {
+        before,
+        after,
+        downsampled_images,
+        flattened_annotations,
+    })
+}
+
+/**
+ * Recompress every stream that lopdf can decode at the best Flate level,
+ * keeping the original encoding when it is already smaller.
+ */
+fn recompress_streams(doc: &mut Document) {
+    for object in doc.objects.values_mut() {
+        let Object::Stream(stream) = object else {
+            continue;
+        };
+        if !stream.allows_compression || stream.dict.has(b"DecodeParms") {
+            continue;
+        }
+        let plain = match stream.filters() {
+            Ok(filters) if !filters.is_empty() => match stream.decompressed_content() {
+                Ok(plain) => plain,
+                // Unsupported filters (e.g. DCTDecode) are kept as they are
+                Err(_) => continue,
+            },
+            _ => stream.content.clone(),
+        };
+
+        let mut recompressed = stream.clone();
+        recompressed.set_plain_content(plain);
+        if recompressed.compress().is_ok() && recompressed.content.len() < stream.content.len() {
+            *stream = recompressed;
+        }
+    }
+}
+
+/**
+ * Remove the document information dictionary and the XMP metadata of the catalog and pages.
+ */
+fn strip_metadata(doc: &mut Document) {
+    doc.trailer.remove(b"Info");
+    if let Ok(catalog) = doc.catalog_mut() {
+        catalog.remove(b"Metadata");
+    }
+    let page_ids: Vec<ObjectId> = doc.get_pages().into_values().collect();
+    for page_id in page_ids {
+        if let Ok(page) = doc.get_dictionary_mut(page_id) {
+            page.remove(b"Metadata");
+        }
+    }
+    doc.prune_objects();
+}
+
+/**
+ * Downsample the images whose displayed resolution exceeds the target.
+ * @returns The number of downsampled images.
+ */
+fn downsample_images(doc: &mut Document, max_dpi: u32) -> usize {
+    let mut downsampled = 0;
+    for (image_id, dpi) in image_resolutions(doc) {
+        if dpi <= max_dpi as f32 * DOWNSAMPLE_THRESHOLD {
+            continue;
+        }
+        let Ok(Object::Stream(stream)) = doc.get_object_mut(image_id) else {
+            continue;
+        };
+        if downsample_image(stream, max_dpi as f32 / dpi) {
+            log::debug!(
+                "Downsampled image {:?} from {:.0} to {} dpi",
+                image_id,
+                dpi,
+                max_dpi
+            );
+            downsampled += 1;
+        }
+    }
+    downsampled
+}
+
+/**
+ * Find the effective resolution of the images drawn directly by the pages.
+ * An image drawn several times keeps the resolution of its largest use.
+ * @returns The lowest resolution (in dots per inch) of each image.
+ */
+fn image_resolutions(doc: &Document) -> HashMap<ObjectId, f32> {
+    let mut resolutions: HashMap<ObjectId, f32> = HashMap::new();
+    for page_id in doc.get_pages().into_values() {
+        let images = page_images(doc, page_id);
+        if images.is_empty() {
+            continue;
+        }
+        let Ok(content) = doc
+            .get_page_content(page_id)
+            .and_then(|content| Content::decode(&content))
+        else {
+            continue;
+        };
+
+        let mut ctm = IDENTITY;
+        let mut saved = Vec::new();
+        for operation in content.operations {
+            match operation.operator.as_str() {
+                "q" => saved.push(ctm),
+                "Q" => ctm = saved.pop().unwrap_or(IDENTITY),
+                "cm" => {
+                    let values: Vec<f32> = operation
+                        .operands
+                        .iter()
+                        .filter_map(|operand| operand.as_float().ok())
+                        .collect();
+                    if let Ok(matrix) = <[f32; 6]>::try_from(values) {
+                        ctm = multiply(&matrix, &ctm);
+                    }
+                }
+                "Do" => {
+                    let Some(&(image_id, width, height)) = operation
+                        .operands
+                        .first()
+                        .and_then(|name| name.as_name().ok())
+                        .and_then(|name| images.get(name))
+                    else {
+                        continue;
+                    };
+                    // The image fills the unit square, scaled by the CTM (in points)
+                    let shown_width = ctm[0].hypot(ctm[1]) / 72.0;
+                    let shown_height = ctm[2].hypot(ctm[3]) / 72.0;
+                    if shown_width <= 0.0 || shown_height <= 0.0 {
+                        continue;
+                    }
+                    let dpi = (width as f32 / shown_width).min(height as f32 / shown_height);
+                    resolutions
+                        .entry(image_id)
+                        .and_modify(|lowest| *lowest = lowest.min(dpi))
+                        .or_insert(dpi);
+                }
+                _ => {}
+            }
+        }
+    }
+    resolutions
+}
+
+/// The identity transformation matrix.
+const IDENTITY: [f32; 6] = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+/**
+ * Concatenate two transformation matrices, as the `cm` operator does.
+ * @returns The matrix applying `m` then `ctm`.
+ */
+fn multiply(m: &[f32; 6], ctm: &[f32; 6]) -> [f32; 6] {
+    [
+        m[0] * ctm[0] + m[1] * ctm[2],
+        m[0] * ctm[1] + m[1] * ctm[3],
+        m[2] * ctm[0] + m[3] * ctm[2],
+        m[2] * ctm[1] + m[3] * ctm[3],
+        m[4] * ctm[0] + m[5] * ctm[2] + ctm[4],
+        m[4] * ctm[1] + m[5] * ctm[3] + ctm[5],
+    ]
+}
+
+/**
+ * Image XObjects available to a page, by resource name.
+ * @returns The object id and pixel size of each image.
+ */
+fn page_images(doc: &Document, page_id: ObjectId) -> HashMap<Vec<u8>, (ObjectId, i64, i64)> {
+    let mut images = HashMap::new();
+    let Ok((inline, resource_ids)) = doc.get_page_resources(page_id) else {
+        return images;
+    };
+    let resources = inline.into_iter().chain(
+        resource_ids
+            .iter()
+            .filter_map(|&id| doc.get_dictionary(id).ok()),
+    );
+    for resource in resources {
+        let Ok(xobjects) = resource
+            .get(b"XObject")
+            .and_then(|xobjects| doc.dereference(xobjects))
+            .and_then(|(_, xobjects)| xobjects.as_dict())
+        else {
+            continue;
+        };
+        for (name, object) in xobjects.iter() {
+            let Ok(image_id) = object.as_reference() else {
+                continue;
+            };
+            let Ok(stream) = doc.get_object(image_id).and_then(Object::as_stream) else {
+                continue;
+            };
+            if !is_image(&stream.dict) {
+                continue;
+            }
+            let size = (
+                stream.dict.get(b"Width").and_then(Object::as_i64),
+                stream.dict.get(b"Height").and_then(Object::as_i64),
+            );
+            if let (Ok(width), Ok(height)) = size {
+                images
+                    .entry(name.clone())
+                    .or_insert((image_id, width, height));
+            }
+        }
+    }
+    images
+}
+
+fn is_image(dict: &Dictionary) -> bool {
+    dict.get(b"Subtype")
+        .and_then(Object::as_name)
+        .is_ok_and(|subtype| subtype == b"Image")
+}
+
+/**
+ * Number of color components of an image, for the color spaces that can be resampled.
+ * @returns None for indexed, separation and other color spaces.
+ */
+fn color_components(dict: &Dictionary) -> Option<usize> {
+    match dict.get(b"ColorSpace").ok()? {
+        Object::Name(name) => match name.as_slice() {
+            b"DeviceGray" => Some(1),
+            b"DeviceRGB" => Some(3),
+            b"DeviceCMYK" => Some(4),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/**
+ * Resample an image by averaging the pixels covered by each new pixel.
+ * @param scale The ratio of the new size to the current one (below 1).
+ * @returns Whether the image was downsampled.
+ */
+fn downsample_image(stream: &mut Stream, scale: f32) -> bool {
+    let dict = &stream.dict;
+    if dict.has(b"Mask")
+        || dict.has(b"DecodeParms")
+        || dict.get(b"BitsPerComponent").and_then(Object::as_i64).ok() != Some(8)
+        || dict
+            .get(b"ImageMask")
+            .and_then(Object::as_bool)
+            .unwrap_or(false)
+    {
+        return false;
+    }
+    let Some(components) = color_components(dict) else {
+        return false;
+    };
+    let (Ok(width), Ok(height)) = (
+        dict.get(b"Width").and_then(Object::as_i64),
+        dict.get(b"Height").and_then(Object::as_i64),
+    ) else {
+        return false;
+    };
+    let (width, height) = (width as usize, height as usize);
+    let Ok(pixels) = stream.get_plain_content() else {
+        return false;
+    };
+    if width == 0 || height == 0 || pixels.len() < width * height * components {
+        return false;
+    }
+
+    let new_width = ((width as f32 * scale).round() as usize).max(1);
+    let new_height = ((height as f32 * scale).round() as usize).max(1);
+    if new_width >= width && new_height >= height {
+        return false;
+    }
+
+    let mut resampled = Vec::with_capacity(new_width * new_height * components);
+    for y in 0..new_height {
+        let rows = covered(y, height, new_height);
+        for x in 0..new_width {
+            let columns = covered(x, width, new_width);
+            let count = (rows.len() * columns.len()) as u32;
+            for component in 0..components {
+                let mut sum = 0u32;
+                for row in rows.clone() {
+                    for column in columns.clone() {
+                        sum += pixels[(row * width + column) * components + component] as u32;
+                    }
+                }
+                resampled.push(((sum + count / 2) / count) as u8);
+            }
+        }
+    }
+
+    stream.set_plain_content(resampled);
+    stream.dict.set("Width", new_width as i64);
+    stream.dict.set("Height", new_height as i64);
+    let _ = stream.compress();
+    true
+}
+
+/**
+ * Source pixels covered by a pixel of the resampled image, along one axis.
+ * @param index The position of the new pixel.
+ * @param size The number of source pixels.
+ * @param new_size The number of new pixels (at most `size`).
+ * @returns The range of source positions, never empty.
+ */
+fn covered(index: usize, size: usize, new_size: usize) -> Range<usize> {
+    let start = index * size / new_size;
+    let end = ((index + 1) * size / new_size).max(start + 1);
+    start..end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::dictionary;
+
+    #[test]
+    fn test_compress_pdf() {
+        let input = "tests/tests_pdf/c.pdf";
+        let output = std::env::temp_dir().join("pdfcutter_compress_output.pdf");
+        let output = output.to_string_lossy();
+        let options = CompressOptions {
+            recompress_streams: true,
+            max_image_dpi: None,
+            strip_metadata: true,
+            flatten_annotations: true,
+        };
+
+        let report = compress_pdf(input, &output, &options).unwrap();
+        assert_eq!(report.before, fs::metadata(input).unwrap().len());
+        assert_eq!(report.after, fs::metadata(&*output).unwrap().len());
+        assert!(report.after < report.before);
+        assert!(report.saving_percent() > 0.0);
+
+        let before = Document::load(input).unwrap();
+        let after = Document::load(&*output).unwrap();
+        assert_eq!(after.get_pages().len(), before.get_pages().len());
+        assert!(after.trailer.get(b"Info").is_err());
+        fs::remove_file(&*output).unwrap();
+    }
+
+    #[test]
+    fn test_downsample_images() {
+        // A 300x200 gray image drawn on 1x0.5 inch, i.e. at 300 dpi horizontally and 400 vertically
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let pixels: Vec<u8> = (0..300 * 200).map(|i| (i % 256) as u8).collect();
+        let image_id = doc.add_object(Stream::new(
+            dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Image",
+                "Width" => 300,
+                "Height" => 200,
+                "ColorSpace" => "DeviceGray",
+                "BitsPerComponent" => 8,
+            },
+            pixels,
+        ));
+        let content_id = doc.add_object(Stream::new(
+            dictionary! {},
+            b"q 72 0 0 36 0 0 cm /Im0 Do Q".to_vec(),
+        ));
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "Contents" => content_id,
+            "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+            "Resources" => dictionary! { "XObject" => dictionary! { "Im0" => image_id } },
+        });
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => vec![page_id.into()],
+                "Count" => 1,
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! { "Type" => "Catalog", "Pages" => pages_id });
+        doc.trailer.set("Root", catalog_id);
+
+        assert_eq!(image_resolutions(&doc)[&image_id], 300.0);
+        // Already below the target
+        assert_eq!(downsample_images(&mut doc, 300), 0);
+
+        assert_eq!(downsample_images(&mut doc, 150), 1);
+        let image = doc.get_object(image_id).unwrap().as_stream().unwrap();
+        assert_eq!(image.dict.get(b"Width").unwrap().as_i64().unwrap(), 150);
+        assert_eq!(image.dict.get(b"Height").unwrap().as_i64().unwrap(), 100);
+        assert_eq!(image.get_plain_content().unwrap().len(), 150 * 100);
+    }
+}
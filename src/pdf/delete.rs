@@ -1,10 +1,35 @@
-use super::utils::{copy_page_with_resources, create_pages_structure, finalize_document};
-use anyhow::{Context, Result};
+use super::intents::{add_output_intents, copy_output_intents};
+use super::structure::{StructureBuilder, TagWarning};
+use super::utils::{PageCopier, create_pages_structure, finalize_document, load_document};
+use crate::i18n::tr;
+use anyhow::Result;
 use lopdf::{Document, ObjectId};
 
 /// Delete specified pages from a PDF and save the result
 pub fn delete_pages(input: &str, output: &str, pages_to_delete: &[u32]) -> Result<()> {
-    let doc = Document::load(input).with_context(|| format!("Failed to load PDF '{}'", input))?;
+    delete_pages_with_tags(input, output, pages_to_delete, false)?;
+    Ok(())
+}
+
+/**
+ * Delete pages from a PDF and save the result, keeping its accessibility tags when asked.
+ * The structure elements whose content was only on deleted pages are left out.
+ * @param input The input PDF file path.
+ * @param output The output PDF file path.
+ * @param pages_to_delete The pages to delete (1-based).
+ * @param keep_tags Whether to copy the structure tree of a tagged input.
+ * @returns Why the tags of the input are not, or not all, in the output.
+ * @throws anyhow::Error if a page does not exist, every page would be deleted or the PDF cannot
+ * be written.
+ */
+pub fn delete_pages_with_tags(
+    input: &str,
+    output: &str,
+    pages_to_delete: &[u32],
+    keep_tags: bool,
+) -> Result<Vec<TagWarning>> {
+    log::debug!("Loading '{}'", input);
+    let doc = load_document(input)?;
 
     let all_pages = doc.get_pages();
     let total_pages = all_pages.len();
@@ -12,12 +37,11 @@ pub fn delete_pages(input: &str, output: &str, pages_to_delete: &[u32]) -> Resul
     // Validate page numbers
     for &page_num in pages_to_delete {
         if page_num == 0 || page_num > total_pages as u32 {
-            return Err(anyhow::anyhow!(
-                "Invalid page number: {}. PDF has {} pages (1-{})",
-                page_num,
-                total_pages,
-                total_pages
-            ));
+            return Err(anyhow::anyhow!(tr!(
+                "Invalid page number: {page}. PDF has {count} pages (1-{count})",
+                page = page_num,
+                count = total_pages
+            )));
         }
     }
 
@@ -34,24 +58,29 @@ pub fn delete_pages(input: &str, output: &str, pages_to_delete: &[u32]) -> Resul
     }
 
     if pages_to_keep.is_empty() {
-        return Err(anyhow::anyhow!("Cannot delete all pages from PDF"));
+        return Err(anyhow::anyhow!(tr!("Cannot delete all pages from PDF")));
     }
 
     // Create a new document with only the pages we want to keep
-    let mut target = Document::with_version("1.5");
-    let mut page_objects: Vec<ObjectId> = Vec::new();
-
-    // Copy each page we want to keep
-    for page_id in pages_to_keep {
-        let new_page_id = copy_page_with_resources(&doc, page_id, &mut target)?;
-        page_objects.push(new_page_id);
-    }
+    let mut copier = PageCopier::new(Document::with_version("1.5"));
+    let page_objects = copier.copy_pages(&doc, input, &pages_to_keep)?;
+    let output_intents = copy_output_intents(&doc, input, &mut copier);
+    let mut target = copier.into_target();
+
+    let copies: Vec<(ObjectId, ObjectId)> = pages_to_keep
+        .into_iter()
+        .zip(page_objects.iter().copied())
+        .collect();
+    let mut structure = StructureBuilder::new(keep_tags);
+    structure.add(&doc, input, &copies, &mut target);
 
     // Create the document structure and save
     create_pages_structure(&mut target, &page_objects)?;
+    add_output_intents(&mut target, output_intents);
+    let warnings = structure.finish(&mut target);
     finalize_document(&mut target, output)?;
 
-    Ok(())
+    Ok(warnings)
 }
 
 #[cfg(test)]
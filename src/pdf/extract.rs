@@ -0,0 +1,227 @@
+use super::links::remove_broken_links;
+use super::names::{NameConflicts, NameMerger};
+use super::utils::{PageCopier, create_pages_structure, finalize_document, load_document};
+use crate::i18n::tr;
+use anyhow::{Context, Result, bail};
+use lopdf::{Document, ObjectId};
+
+/// What an extract keeps besides the pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtractOptions {
+    /// Keep the annotations of the pages (comments, highlights, links...), with the named
+    /// destinations leading to extracted pages and the form fields of their widgets. Links to
+    /// pages left out are removed.
+    pub keep_annotations: bool,
+}
+
+impl Default for ExtractOptions {
+    fn default() -> Self {
+        Self {
+            keep_annotations: true,
+        }
+    }
+}
+
+/**
+ * Copy the given pages of a PDF into a new document, in the order given.
+ * @param input The input PDF file path.
+ * @param output The output PDF file path.
+ * @param pages The pages to extract (1-based).
+ * @param options Whether the annotations of the pages are kept.
+ * @throws anyhow::Error if no pages are given, a page does not exist or the PDF cannot be written.
+ */
+pub fn extract_pages(
+    input: &str,
+    output: &str,
+    pages: &[u32],
+    options: &ExtractOptions,
+) -> Result<()> {
+    if pages.is_empty() {
+        bail!(tr!("No pages to extract"));
+    }
+
+    log::debug!("Loading '{}'", input);
+    let mut doc = load_document(input)?;
+    let page_ids = page_ids(&doc, pages)?;
+    if !options.keep_annotations {
+        // Left out of the source, so that nothing they refer to is copied
+        for &page_id in &page_ids {
+            if let Ok(page) = doc.get_dictionary_mut(page_id) {
+                page.remove(b"Annots");
+            }
+        }
+    }
+
+    let mut copier = PageCopier::new(Document::with_version("1.5"));
+    let page_objects = copier.copy_pages(&doc, input, &page_ids)?;
+    let mut names = NameMerger::new(NameConflicts::default());
+    if options.keep_annotations {
+        let copies: Vec<(ObjectId, ObjectId)> = page_ids
+            .iter()
+            .copied()
+            .zip(page_objects.iter().copied())
+            .collect();
+        names.add(&mut copier, &doc, input, &copies)?;
+    }
+    let mut target = copier.into_target();
+
+    create_pages_structure(&mut target, &page_objects)?;
+    if options.keep_annotations {
+        names.finish(&mut target)?;
+        let removed = remove_broken_links(&mut target);
+        if removed > 0 {
+            log::info!(
+                "Removed {} links to pages left out of '{}'",
+                removed,
+                output
+            );
+        }
+    }
+    finalize_document(&mut target, output)
+        .with_context(|| tr!("Failed to save PDF '{path}'", path = output))?;
+
+    Ok(())
+}
+
+/**
+ * Find the objects of pages of a document.
+ * @param doc The document.
+ * @param pages The page numbers (1-based).
+ * @returns The id of each page, in the order given.
+ * @throws anyhow::Error if a page does not exist.
+ */
+pub fn page_ids(doc: &Document, pages: &[u32]) -> Result<Vec<ObjectId>> {
+    let all_pages = doc.get_pages();
+    let total_pages = all_pages.len();
+    pages
+        .iter()
+        .map(|page_num| match all_pages.get(page_num) {
+            Some(&page_id) => Ok(page_id),
+            None => bail!(tr!(
+                "Invalid page number: {page}. PDF has {count} pages (1-{count})",
+                page = page_num,
+                count = total_pages
+            )),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::links::{LinkTarget, catalog_destinations, find_links, name_tree_destinations};
+    use lopdf::{Object, StringFormat, dictionary};
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn test_extract_pages() {
+        let input = "tests/tests_pdf/c.pdf";
+        let output = "test_extract_output.pdf";
+
+        extract_pages(input, output, &[2, 4, 5], &ExtractOptions::default()).unwrap();
+        let result = Document::load(output).unwrap();
+        assert_eq!(result.get_pages().len(), 3);
+
+        std::fs::remove_file(output).unwrap();
+    }
+
+    #[test]
+    fn test_extract_invalid_pages() {
+        let input = "tests/tests_pdf/c.pdf";
+        let output = "test_extract_invalid.pdf";
+
+        let options = ExtractOptions::default();
+        assert!(extract_pages(input, output, &[8], &options).is_err());
+        assert!(extract_pages(input, output, &[], &options).is_err());
+        assert!(!Path::new(output).exists());
+    }
+
+    #[test]
+    fn test_extract_keeps_annotations_and_links() {
+        let dir = std::env::temp_dir().join("pdfcutter_extract_links");
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("linked.pdf").to_string_lossy().into_owned();
+        let output = dir.join("chapter.pdf").to_string_lossy().into_owned();
+
+        let mut doc = load_document("tests/tests_pdf/c.pdf").unwrap();
+        let pages = doc.get_pages();
+        let string = |text: &str| Object::String(text.as_bytes().to_vec(), StringFormat::Literal);
+        let link = |dest: Object| {
+            Object::Dictionary(dictionary! {
+                "Type" => "Annot",
+                "Subtype" => "Link",
+                "Rect" => vec![0.into(), 0.into(), 10.into(), 10.into()],
+                "Dest" => dest,
+            })
+        };
+        let fit = |page: u32| Object::Array(vec![pages[&page].into(), "Fit".into()]);
+        let comment = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Text",
+            "Rect" => vec![0.into(), 0.into(), 20.into(), 20.into()],
+            "Contents" => string("Check this figure"),
+        });
+        let annotations = vec![
+            link(fit(3)),
+            link(fit(5)),
+            link(string("chapter2")),
+            link(string("annex")),
+            link("intro".into()),
+            comment.into(),
+        ];
+        doc.get_dictionary_mut(pages[&1])
+            .unwrap()
+            .set("Annots", annotations);
+        let tree = doc.add_object(dictionary! {
+            "Names" => vec![string("annex"), fit(6), string("chapter2"), fit(2)],
+        });
+        let catalog = doc.catalog_mut().unwrap();
+        catalog.set("Names", dictionary! { "Dests" => tree });
+        catalog.set("Dests", dictionary! { "intro" => fit(1), "end" => fit(7) });
+        doc.save(&input).unwrap();
+
+        extract_pages(&input, &output, &[1, 2, 3], &ExtractOptions::default()).unwrap();
+        let extracted = load_document(&output).unwrap();
+        let targets: Vec<LinkTarget> = find_links(&extracted)
+            .into_iter()
+            .map(|link| link.target)
+            .collect();
+        assert_eq!(
+            targets,
+            vec![
+                LinkTarget::Page(Some(3)),
+                LinkTarget::Named {
+                    name: "chapter2".to_string(),
+                    page: Some(2)
+                },
+                LinkTarget::Named {
+                    name: "intro".to_string(),
+                    page: Some(1)
+                },
+            ]
+        );
+        let first = extracted.get_pages()[&1];
+        let annots = extracted.get_dictionary(first).unwrap().get(b"Annots");
+        assert_eq!(annots.unwrap().as_array().unwrap().len(), 4);
+        assert_eq!(name_tree_destinations(&extracted).len(), 1);
+        assert_eq!(catalog_destinations(&extracted).len(), 1);
+
+        let options = ExtractOptions {
+            keep_annotations: false,
+        };
+        extract_pages(&input, &output, &[1, 2, 3], &options).unwrap();
+        let extracted = load_document(&output).unwrap();
+        let first = extracted.get_pages()[&1];
+        assert!(!extracted.get_dictionary(first).unwrap().has(b"Annots"));
+        assert!(name_tree_destinations(&extracted).is_empty());
+        let annotation_objects = extracted
+            .objects
+            .values()
+            .filter(|object| object.type_name().is_ok_and(|name| name == b"Annot"))
+            .count();
+        assert_eq!(annotation_objects, 0);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
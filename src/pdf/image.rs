@@ -0,0 +1,392 @@
+use crate::i18n::tr;
+use anyhow::{Context, Result, bail};
+use flate2::read::ZlibDecoder;
+use lopdf::{Document, ObjectId, Stream, dictionary};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+/// Signature at the start of every PNG file.
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// An image added to a document, ready to be drawn with the `Do` operator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageXObject {
+    pub id: ObjectId,
+    pub width: u32,
+    pub height: u32,
+}
+
+/**
+ * Whether a path names an image that `add_image` can load, judging by its extension.
+ */
+pub fn has_image_extension(path: &str) -> bool {
+    Path::new(path).extension().is_some_and(|extension| {
+        ["jpg", "jpeg", "png"]
+            .iter()
+            .any(|known| extension.eq_ignore_ascii_case(known))
+    })
+}
+
+/**
+ * Load a JPEG or PNG file and add it to a document as an image XObject.
+ * JPEG data is embedded as is; PNG images are decoded, and their transparency is kept as a soft mask.
+ * Only 8-bit, non-interlaced PNG files are supported.
+ * @param doc The document to add the image to.
+ * @param path The image file path.
+ * @returns The image object and its size in pixels.
+ * @throws anyhow::Error if the file cannot be read or is not a supported image.
+ */
+pub fn add_image(doc: &mut Document, path: &str) -> Result<ImageXObject> {
+    let data = fs::read(path).with_context(|| tr!("Failed to read image '{path}'", path = path))?;
+    let image = if data.starts_with(PNG_SIGNATURE) {
+        decode_png(&data)
+    } else if data.starts_with(&[0xFF, 0xD8]) {
+        jpeg_image(data)
+    } else {
+        bail!(tr!("Unsupported image format: {path}", path = path));
+    }
+    .with_context(|| tr!("Failed to read image '{path}'", path = path))?;
+
+    let mut dict = dictionary! {
+        "Type" => "XObject",
+        "Subtype" => "Image",
+        "Width" => image.width as i64,
+        "Height" => image.height as i64,
+        "ColorSpace" => image.color_space,
+        "BitsPerComponent" => 8,
+    };
+    if let Some(alpha) = image.alpha {
+        let mut mask = Stream::new(
+            dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Image",
+                "Width" => image.width as i64,
+                "Height" => image.height as i64,
+                "ColorSpace" => "DeviceGray",
+                "BitsPerComponent" => 8,
+            },
+            alpha,
+        );
+        let _ = mask.compress();
+        dict.set("SMask", doc.add_object(mask));
+    }
+    let stream = match image.filter {
+        Some(filter) => {
+            dict.set("Filter", filter);
+            Stream::new(dict, image.data).with_compression(false)
+        }
+        None => {
+            let mut stream = Stream::new(dict, image.data);
+            let _ = stream.compress();
+            stream
+        }
+    };
+
+    Ok(ImageXObject {
+        id: doc.add_object(stream),
+        width: image.width,
+        height: image.height,
+    })
+}
+
+/// Pixels of a decoded (or passed-through) image file.
+struct RawImage {
+    width: u32,
+    height: u32,
+    color_space: &'static str,
+    /// Filter the data is already encoded with, None for raw samples.
+    filter: Option<&'static str>,
+    data: Vec<u8>,
+    /// One 8-bit opacity sample per pixel, if the image has transparency.
+    alpha: Option<Vec<u8>>,
+}
+
+/**
+ * Read the size and color space of a JPEG file from its start-of-frame segment.
+ * The data itself is embedded with the DCTDecode filter.
+ */
+fn jpeg_image(data: Vec<u8>) -> Result<RawImage> {
+    let mut position = 2;
+    while position + 4 <= data.len() {
+        if data[position] != 0xFF {
+            bail!(tr!("Invalid JPEG data"));
+        }
+        let marker = data[position + 1];
+        let length = u16::from_be_bytes([data[position + 2], data[position + 3]]) as usize;
+        // SOF0 to SOF15, except DHT (C4), JPG (C8) and DAC (CC)
+        let is_frame = (0xC0..=0xCF).contains(&marker) && ![0xC4, 0xC8, 0xCC].contains(&marker);
+        if is_frame && position + 10 <= data.len() {
+            let height = u16::from_be_bytes([data[position + 5], data[position + 6]]) as u32;
+            let width = u16::from_be_bytes([data[position + 7], data[position + 8]]) as u32;
+            let color_space = match data[position + 9] {
+                1 => "DeviceGray",
+                3 => "DeviceRGB",
+                4 => "DeviceCMYK",
+                _ => bail!(tr!("Invalid JPEG data")),
+            };
+            return Ok(RawImage {
+                width,
+                height,
+                color_space,
+                filter: Some("DCTDecode"),
+                data,
+                alpha: None,
+            });
+        }
+        position += 2 + length;
+    }
+    bail!(tr!("Invalid JPEG data"))
+}
+
+/**
+ * Decode an 8-bit, non-interlaced PNG file into raw samples and an optional alpha channel.
+ */
+fn decode_png(data: &[u8]) -> Result<RawImage> {
+    let mut header = None;
+    let mut palette: &[u8] = &[];
+    let mut transparency: &[u8] = &[];
+    let mut compressed = Vec::new();
+
+    let mut position = PNG_SIGNATURE.len();
+    while position + 8 <= data.len() {
+        let length = u32::from_be_bytes(data[position..position + 4].try_into()?) as usize;
+        let kind = &data[position + 4..position + 8];
+        let Some(chunk) = data.get(position + 8..position + 8 + length) else {
+            bail!(tr!("Invalid PNG data"));
+        };
+        match kind {
+            b"IHDR" if chunk.len() >= 13 => header = Some(chunk),
+            b"PLTE" => palette = chunk,
+            b"tRNS" => transparency = chunk,
+            b"IDAT" => compressed.extend_from_slice(chunk),
+            b"IEND" => break,
+            _ => {}
+        }
+        // Chunk data is followed by a 4-byte CRC
+        position += 12 + length;
+    }
+
+    let Some(header) = header else {
+        bail!(tr!("Invalid PNG data"));
+    };
+    let width = u32::from_be_bytes(header[0..4].try_into()?);
+    let height = u32::from_be_bytes(header[4..8].try_into()?);
+    let (bit_depth, color_type, interlace) = (header[8], header[9], header[12]);
+    if bit_depth != 8 || interlace != 0 {
+        bail!(tr!("Only 8-bit, non-interlaced PNG images are supported"));
+    }
+    let channels = match color_type {
+        0 | 3 => 1,
+        2 => 3,
+        4 => 2,
+        6 => 4,
+        _ => bail!(tr!("Invalid PNG data")),
+    };
+
+    let mut filtered = Vec::new();
+    ZlibDecoder::new(compressed.as_slice()).read_to_end(&mut filtered)?;
+    let pixels = unfilter(&filtered, width as usize, height as usize, channels)?;
+
+    let (color_space, data, alpha) = match color_type {
+        0 => ("DeviceGray", pixels, None),
+        2 => ("DeviceRGB", pixels, None),
+        4 => split_alpha(&pixels, 1, "DeviceGray"),
+        6 => split_alpha(&pixels, 3, "DeviceRGB"),
+        _ => {
+            // Palette entries are expanded to RGB, with their tRNS opacity if any
+            let mut rgb = Vec::with_capacity(pixels.len() * 3);
+            let mut alpha = Vec::with_capacity(pixels.len());
+            for &index in &pixels {
+                let index = index as usize;
+                let Some(color) = palette.get(index * 3..index * 3 + 3) else {
+                    bail!(tr!("Invalid PNG data"));
+                };
+                rgb.extend_from_slice(color);
+                alpha.push(transparency.get(index).copied().unwrap_or(255));
+            }
+            let alpha = (!transparency.is_empty()).then_some(alpha);
+            ("DeviceRGB", rgb, alpha)
+        }
+    };
+
+    Ok(RawImage {
+        width,
+        height,
+        color_space,
+        filter: None,
+        data,
+        alpha,
+    })
+}
+
+/**
+ * Split interleaved samples into color samples and an alpha channel.
+ */
+fn split_alpha(
+    pixels: &[u8],
+    colors: usize,
+    color_space: &'static str,
+) -> (&'static str, Vec<u8>, Option<Vec<u8>>) {
+    let mut color = Vec::with_capacity(pixels.len() / (colors + 1) * colors);
+    let mut alpha = Vec::with_capacity(pixels.len() / (colors + 1));
+    for pixel in pixels.chunks_exact(colors + 1) {
+        color.extend_from_slice(&pixel[..colors]);
+        alpha.push(pixel[colors]);
+    }
+    (color_space, color, Some(alpha))
+}
+
+/**
+ * Undo the per-row filters of PNG image data.
+ * @returns The samples, row after row, without the filter bytes.
+ */
+fn unfilter(data: &[u8], width: usize, height: usize, channels: usize) -> Result<Vec<u8>> {
+    let stride = width * channels;
+    if data.len() < height * (stride + 1) {
+        bail!(tr!("Invalid PNG data"));
+    }
+
+    let mut pixels = vec![0u8; height * stride];
+    for y in 0..height {
+        let filter = data[y * (stride + 1)];
+        let row = &data[y * (stride + 1) + 1..(y + 1) * (stride + 1)];
+        let (done, current) = pixels.split_at_mut(y * stride);
+        let previous = if y > 0 {
+            &done[(y - 1) * stride..]
+        } else {
+            &[][..]
+        };
+        let current = &mut current[..stride];
+        for x in 0..stride {
+            let left = if x >= channels {
+                current[x - channels]
+            } else {
+                0
+            };
+            let up = previous.get(x).copied().unwrap_or(0);
+            let up_left = if x >= channels {
+                previous.get(x - channels).copied().unwrap_or(0)
+            } else {
+                0
+            };
+            current[x] = row[x].wrapping_add(match filter {
+                0 => 0,
+                1 => left,
+                2 => up,
+                3 => ((left as u16 + up as u16) / 2) as u8,
+                4 => paeth(left, up, up_left),
+                _ => bail!(tr!("Invalid PNG data")),
+            });
+        }
+    }
+    Ok(pixels)
+}
+
+fn paeth(left: u8, up: u8, up_left: u8) -> u8 {
+    let estimate = left as i16 + up as i16 - up_left as i16;
+    let distance = |value: u8| (estimate - value as i16).abs();
+    if distance(left) <= distance(up) && distance(left) <= distance(up_left) {
+        left
+    } else if distance(up) <= distance(up_left) {
+        up
+    } else {
+        up_left
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::ZlibEncoder;
+    use std::io::Write;
+
+    /// Build a PNG file from raw rows (each starting with its filter byte).
+    fn png(
+        width: u32,
+        height: u32,
+        color_type: u8,
+        rows: &[u8],
+        extra: &[(&[u8], &[u8])],
+    ) -> Vec<u8> {
+        let mut chunks: Vec<(&[u8], Vec<u8>)> = Vec::new();
+        let mut header = Vec::new();
+        header.extend_from_slice(&width.to_be_bytes());
+        header.extend_from_slice(&height.to_be_bytes());
+        header.extend_from_slice(&[8, color_type, 0, 0, 0]);
+        chunks.push((b"IHDR", header));
+        for (kind, data) in extra {
+            chunks.push((kind, data.to_vec()));
+        }
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(rows).unwrap();
+        chunks.push((b"IDAT", encoder.finish().unwrap()));
+        chunks.push((b"IEND", Vec::new()));
+
+        let mut file = PNG_SIGNATURE.to_vec();
+        for (kind, data) in chunks {
+            file.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            file.extend_from_slice(kind);
+            file.extend_from_slice(&data);
+            // The CRC is not checked
+            file.extend_from_slice(&[0; 4]);
+        }
+        file
+    }
+
+    #[test]
+    fn test_decode_png() {
+        // 2x2 RGBA: the first row unfiltered, the second with the "up" filter
+        let rows = [
+            0, 10, 20, 30, 255, 40, 50, 60, 128, //
+            2, 1, 1, 1, 0, 1, 1, 1, 0,
+        ];
+        let image = decode_png(&png(2, 2, 6, &rows, &[])).unwrap();
+        assert_eq!((image.width, image.height), (2, 2));
+        assert_eq!(image.color_space, "DeviceRGB");
+        assert_eq!(
+            image.data,
+            vec![10, 20, 30, 40, 50, 60, 11, 21, 31, 41, 51, 61]
+        );
+        assert_eq!(image.alpha, Some(vec![255, 128, 255, 128]));
+
+        // Palette with transparency
+        let image = decode_png(&png(
+            2,
+            1,
+            3,
+            &[0, 1, 0],
+            &[(b"PLTE", &[0, 0, 0, 255, 0, 0]), (b"tRNS", &[0])],
+        ))
+        .unwrap();
+        assert_eq!(image.data, vec![255, 0, 0, 0, 0, 0]);
+        assert_eq!(image.alpha, Some(vec![255, 0]));
+
+        // Unsupported bit depth
+        let mut sixteen_bits = png(1, 1, 0, &[0, 0, 0], &[]);
+        sixteen_bits[PNG_SIGNATURE.len() + 16] = 16;
+        assert!(decode_png(&sixteen_bits).is_err());
+    }
+
+    #[test]
+    fn test_add_image() {
+        let path = std::env::temp_dir().join("pdfcutter_test_image.png");
+        fs::write(&path, png(1, 1, 4, &[0, 200, 100], &[])).unwrap();
+
+        let mut doc = Document::with_version("1.5");
+        let image = add_image(&mut doc, &path.to_string_lossy()).unwrap();
+        assert_eq!((image.width, image.height), (1, 1));
+        let dict = &doc.get_object(image.id).unwrap().as_stream().unwrap().dict;
+        assert_eq!(
+            dict.get(b"ColorSpace").unwrap().as_name().unwrap(),
+            b"DeviceGray"
+        );
+        assert!(dict.get(b"SMask").unwrap().as_reference().is_ok());
+        fs::remove_file(&path).unwrap();
+
+        assert!(add_image(&mut doc, "tests/tests_pdf/a.pdf").is_err());
+        assert!(has_image_extension("logo.PNG"));
+        assert!(!has_image_extension("logo.gif"));
+    }
+}
@@ -0,0 +1,491 @@
+use super::text::page_text;
+use super::utils::load_document;
+use crate::i18n::tr;
+use anyhow::{Context, Result};
+use lopdf::{Document, Object, ObjectId, decode_text_string};
+
+/// Points per millimetre (1 pt = 1/72 inch).
+pub const POINTS_PER_MM: f32 = 72.0 / 25.4;
+
+/// Common paper sizes in millimetres (portrait).
+const PAPER_SIZES: &[(&str, f32, f32)] = &[
+    ("A3", 297.0, 420.0),
+    ("A4", 210.0, 297.0),
+    ("A5", 148.0, 210.0),
+    ("Letter", 215.9, 279.4),
+    ("Legal", 215.9, 355.6),
+];
+
+/// Geometry of a single page, in PDF points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageInfo {
+    pub width: f32,
+    pub height: f32,
+    pub rotation: i64,
+}
+
+impl PageInfo {
+    /// Width and height as displayed by a viewer, taking /Rotate into account.
+    pub fn displayed_size(&self) -> (f32, f32) {
+        if self.rotation % 180 == 0 {
+            (self.width, self.height)
+        } else {
+            (self.height, self.width)
+        }
+    }
+
+    pub fn is_landscape(&self) -> bool {
+        let (width, height) = self.displayed_size();
+        width > height
+    }
+
+    /// Name of the standard paper size matching this page, if any (2 mm tolerance).
+    pub fn paper_name(&self) -> Option<&'static str> {
+        let (width, height) = self.displayed_size();
+        let (short, long) = if width <= height {
+            (width / POINTS_PER_MM, height / POINTS_PER_MM)
+        } else {
+            (height / POINTS_PER_MM, width / POINTS_PER_MM)
+        };
+
+        PAPER_SIZES
+            .iter()
+            .find(|(_, w, h)| (short - w).abs() <= 2.0 && (long - h).abs() <= 2.0)
+            .map(|(name, _, _)| *name)
+    }
+
+    /// Human readable description, e.g. "A4 portrait (210 × 297 mm)".
+    pub fn describe(&self) -> String {
+        let (width, height) = self.displayed_size();
+        let orientation = if self.is_landscape() {
+            tr!("landscape")
+        } else {
+            tr!("portrait")
+        };
+        format!(
+            "{} {} ({:.0} × {:.0} mm)",
+            self.paper_name().unwrap_or(tr!("Custom")),
+            orientation,
+            width / POINTS_PER_MM,
+            height / POINTS_PER_MM
+        )
+    }
+}
+
+/// Pages of a document that share a size and orientation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageSizeGroup {
+    /// The first of these pages, standing for all of them.
+    pub page: PageInfo,
+    /// The page numbers (1-based), in order.
+    pub pages: Vec<u32>,
+}
+
+/**
+ * Group the pages of a document by the size and orientation they are displayed with, as
+ * described by `PageInfo::describe`, to tell at a glance which pages stand out before printing.
+ * @param pages The pages, in document order.
+ * @returns The groups, largest first; groups of the same size keep the order of their first page.
+ */
+pub fn page_size_groups(pages: &[PageInfo]) -> Vec<PageSizeGroup> {
+    let mut groups: Vec<(String, PageSizeGroup)> = Vec::new();
+    for (index, page) in pages.iter().enumerate() {
+        let size = page.describe();
+        let number = index as u32 + 1;
+        match groups.iter_mut().find(|(known, _)| *known == size) {
+            Some((_, group)) => group.pages.push(number),
+            None => groups.push((
+                size,
+                PageSizeGroup {
+                    page: *page,
+                    pages: vec![number],
+                },
+            )),
+        }
+    }
+    let mut groups: Vec<PageSizeGroup> = groups.into_iter().map(|(_, group)| group).collect();
+    groups.sort_by_key(|group| std::cmp::Reverse(group.pages.len()));
+    groups
+}
+
+/// Summary of a document, as needed to validate and preview operations on it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentInfo {
+    pub pages: Vec<PageInfo>,
+    pub file_size: u64,
+    /// Title from the document information dictionary, when set and not blank.
+    pub title: Option<String>,
+    /// Provenance receipt recorded by pdf-cutter, see provenance::receipt.
+    pub provenance: Option<String>,
+    /// Protected by a password, even an empty one: its pages cannot be rewritten.
+    pub encrypted: bool,
+}
+
+impl DocumentInfo {
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+}
+
+/**
+ * Load a PDF and summarize it.
+ * @param path The PDF file path.
+ * @returns The document information.
+ * @throws anyhow::Error if the file cannot be read or loaded.
+ */
+pub fn inspect_document(path: &str) -> Result<DocumentInfo> {
+    let file_size = std::fs::metadata(path)
+        .with_context(|| tr!("Failed to read '{path}'", path = path))?
+        .len();
+    log::debug!("Loading '{}'", path);
+    let doc = load_document(path)?;
+
+    Ok(DocumentInfo {
+        pages: page_infos(&doc),
+        file_size,
+        title: document_title(&doc),
+        provenance: info_entry(&doc, "Provenance"),
+        encrypted: is_encrypted(&doc),
+    })
+}
+
+/// Whether the document has an encryption dictionary, opened with the empty password or not.
+fn is_encrypted(doc: &Document) -> bool {
+    doc.is_encrypted() || doc.encryption_state.is_some()
+}
+
+/**
+ * Read the /Title entry of the document information dictionary.
+ * @param doc The loaded document.
+ * @returns The decoded title, or None when it is missing, blank or cannot be decoded.
+ */
+pub fn document_title(doc: &Document) -> Option<String> {
+    info_entry(doc, "Title")
+}
+
+/**
+ * Read a text entry of the document information dictionary.
+ * @param doc The loaded document.
+ * @param key The entry name (e.g., "Author").
+ * @returns The decoded text, or None when it is missing, blank or cannot be decoded.
+ */
+fn info_entry(doc: &Document, key: &str) -> Option<String> {
+    let info = doc.trailer.get(b"Info").ok()?;
+    let info = doc.dereference(info).ok()?.1.as_dict().ok()?;
+    let value = doc.dereference(info.get(key.as_bytes()).ok()?).ok()?.1;
+    let value = decode_text_string(value).ok()?;
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Entries of the document information dictionary shown in a preview, in display order.
+pub const METADATA_KEYS: &[&str] = &[
+    "Title",
+    "Author",
+    "Subject",
+    "Keywords",
+    "Creator",
+    "Producer",
+    "CreationDate",
+    "ModDate",
+    "Provenance",
+];
+
+/// Everything shown in the quick preview of a file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentPreview {
+    pub info: DocumentInfo,
+    /// Metadata entries that are set, as (key, value) in METADATA_KEYS order.
+    pub metadata: Vec<(&'static str, String)>,
+    pub first_page_text: Option<String>,
+}
+
+/**
+ * Load a PDF and gather its summary, metadata, encryption status and first page text.
+ * @param path The PDF file path.
+ * @returns The preview.
+ * @throws anyhow::Error if the file cannot be read or loaded.
+ */
+pub fn preview_document(path: &str) -> Result<DocumentPreview> {
+    let file_size = std::fs::metadata(path)
+        .with_context(|| tr!("Failed to read '{path}'", path = path))?
+        .len();
+    log::debug!("Loading '{}' for preview", path);
+    let doc = load_document(path)?;
+
+    let metadata = METADATA_KEYS
+        .iter()
+        .filter_map(|&key| {
+            let value = info_entry(&doc, key)?;
+            Some(match key {
+                "CreationDate" | "ModDate" => (key, format_pdf_date(&value)),
+                _ => (key, value),
+            })
+        })
+        .collect();
+
+    Ok(DocumentPreview {
+        info: DocumentInfo {
+            pages: page_infos(&doc),
+            file_size,
+            title: document_title(&doc),
+            provenance: info_entry(&doc, "Provenance"),
+            encrypted: is_encrypted(&doc),
+        },
+        metadata,
+        first_page_text: page_text(&doc, 1),
+    })
+}
+
+/**
+ * Format a PDF date ("D:20240131093000+01'00'") as "2024-01-31 09:30".
+ * @param raw The date as stored in the document.
+ * @returns The readable date, or the raw text when it does not follow the PDF format.
+ */
+pub fn format_pdf_date(raw: &str) -> String {
+    let digits: String = raw
+        .trim_start_matches("D:")
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    if digits.len() < 8 {
+        return raw.to_string();
+    }
+    let date = format!("{}-{}-{}", &digits[0..4], &digits[4..6], &digits[6..8]);
+    if digits.len() >= 12 {
+        format!("{} {}:{}", date, &digits[8..10], &digits[10..12])
+    } else {
+        date
+    }
+}
+
+/**
+ * Return the geometry of each page of a loaded document, in page order.
+ * Pages without a usable MediaBox default to A4.
+ */
+pub fn page_infos(doc: &Document) -> Vec<PageInfo> {
+    doc.get_pages()
+        .values()
+        .map(|&page_id| page_info(doc, page_id))
+        .collect()
+}
+
+fn page_info(doc: &Document, page_id: ObjectId) -> PageInfo {
+    let (width, height) = match media_box(doc, page_id) {
+        Some(b) => ((b[2] - b[0]).abs(), (b[3] - b[1]).abs()),
+        None => (210.0 * POINTS_PER_MM, 297.0 * POINTS_PER_MM),
+    };
+
+    let rotation = inherited_attribute(doc, page_id, b"Rotate")
+        .and_then(|obj| obj.as_i64().ok())
+        .unwrap_or(0)
+        .rem_euclid(360);
+
+    PageInfo {
+        width,
+        height,
+        rotation,
+    }
+}
+
+/**
+ * The MediaBox of a page, as [left, bottom, right, top] in points.
+ * @returns None when the page has no usable MediaBox.
+ */
+pub(super) fn media_box(doc: &Document, page_id: ObjectId) -> Option<[f32; 4]> {
+    let values = inherited_attribute(doc, page_id, b"MediaBox")?
+        .as_array()
+        .ok()?;
+    let numbers: Vec<f32> = values
+        .iter()
+        .filter_map(|v| doc.dereference(v).ok()?.1.as_float().ok())
+        .collect();
+    numbers.try_into().ok()
+}
+
+/**
+ * Look up a page attribute, following the /Parent chain for inheritable entries
+ * (MediaBox, Rotate, Resources, CropBox).
+ */
+pub(super) fn inherited_attribute<'a>(
+    doc: &'a Document,
+    page_id: ObjectId,
+    key: &[u8],
+) -> Option<&'a Object> {
+    let mut current = doc.get_dictionary(page_id).ok();
+    let mut depth = 0;
+
+    while let Some(dict) = current {
+        if let Ok(value) = dict.get(key) {
+            return doc.dereference(value).ok().map(|(_, obj)| obj);
+        }
+        // Guard against malformed, cyclic page trees
+        depth += 1;
+        if depth > 64 {
+            return None;
+        }
+        current = dict
+            .get(b"Parent")
+            .and_then(Object::as_reference)
+            .and_then(|id| doc.get_dictionary(id))
+            .ok();
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::{dictionary, text_string};
+
+    #[test]
+    fn test_inspect_document() {
+        let info = inspect_document("tests/tests_pdf/c.pdf").unwrap();
+        assert_eq!(info.page_count(), 7);
+        assert!(info.file_size > 0);
+        assert_eq!(info.title.as_deref(), Some("Document sans titre"));
+
+        let infos = info.pages;
+        assert!(
+            infos
+                .iter()
+                .all(|info| info.width > 0.0 && info.height > 0.0)
+        );
+
+        assert!(inspect_document("nonexistent.pdf").is_err());
+    }
+
+    #[test]
+    fn test_page_size_groups() {
+        let a4 = PageInfo {
+            width: 595.0,
+            height: 842.0,
+            rotation: 0,
+        };
+        let a4_turned = PageInfo { rotation: 90, ..a4 };
+        let a3_landscape = PageInfo {
+            width: 1191.0,
+            height: 842.0,
+            rotation: 0,
+        };
+        let pages = [a3_landscape, a4, a4, a4_turned, a4, a3_landscape, a4];
+
+        let groups = page_size_groups(&pages);
+        let summary: Vec<(String, Vec<u32>)> = groups
+            .iter()
+            .map(|group| (group.page.describe(), group.pages.clone()))
+            .collect();
+        // An A4 page turned by its rotation is displayed like an A4 landscape page
+        assert_eq!(
+            summary,
+            vec![
+                ("A4 portrait (210 × 297 mm)".to_string(), vec![2, 3, 5, 7]),
+                ("A3 landscape (420 × 297 mm)".to_string(), vec![1, 6]),
+                ("A4 landscape (297 × 210 mm)".to_string(), vec![4]),
+            ]
+        );
+        assert!(page_size_groups(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_inspect_encrypted_and_empty_documents() {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.add_object(dictionary! {
+            "Type" => "Pages",
+            "Kids" => vec![],
+            "Count" => 0,
+        });
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+        let empty = std::env::temp_dir().join("pdf_cutter_inspect_empty.pdf");
+        doc.save(&empty).unwrap();
+        let info = inspect_document(&empty.to_string_lossy()).unwrap();
+        assert_eq!(info.page_count(), 0);
+        assert!(!info.encrypted);
+
+        let encrypt_id = doc.add_object(dictionary! {
+            "Filter" => "Standard",
+            "V" => 1,
+            "R" => 2,
+            "O" => lopdf::Object::string_literal(vec![0u8; 32]),
+            "U" => lopdf::Object::string_literal(vec![0u8; 32]),
+            "P" => -4,
+        });
+        doc.trailer.set("Encrypt", encrypt_id);
+        let encrypted = std::env::temp_dir().join("pdf_cutter_inspect_encrypted.pdf");
+        doc.save(&encrypted).unwrap();
+        let info = inspect_document(&encrypted.to_string_lossy()).unwrap();
+        assert!(info.encrypted);
+
+        std::fs::remove_file(empty).unwrap();
+        std::fs::remove_file(encrypted).unwrap();
+    }
+
+    #[test]
+    fn test_document_title() {
+        let mut doc = Document::with_version("1.5");
+        assert_eq!(document_title(&doc), None);
+
+        let info_id = doc.add_object(dictionary! {
+            "Title" => text_string("Relevé de compte"),
+        });
+        doc.trailer.set("Info", info_id);
+        assert_eq!(document_title(&doc).as_deref(), Some("Relevé de compte"));
+
+        let blank_id = doc.add_object(dictionary! {
+            "Title" => text_string("  "),
+        });
+        doc.trailer.set("Info", blank_id);
+        assert_eq!(document_title(&doc), None);
+    }
+
+    #[test]
+    fn test_preview_document() {
+        let preview = preview_document("tests/tests_pdf/c.pdf").unwrap();
+        assert_eq!(preview.info.page_count(), 7);
+        assert!(!preview.info.encrypted);
+        assert!(
+            preview
+                .metadata
+                .contains(&("Title", "Document sans titre".to_string()))
+        );
+        assert!(preview_document("nonexistent.pdf").is_err());
+    }
+
+    #[test]
+    fn test_format_pdf_date() {
+        assert_eq!(
+            format_pdf_date("D:20240131093000+01'00'"),
+            "2024-01-31 09:30"
+        );
+        assert_eq!(format_pdf_date("D:20240131"), "2024-01-31");
+        assert_eq!(format_pdf_date("yesterday"), "yesterday");
+    }
+
+    #[test]
+    fn test_page_info_describe() {
+        let a4 = PageInfo {
+            width: 595.0,
+            height: 842.0,
+            rotation: 0,
+        };
+        assert_eq!(a4.paper_name(), Some("A4"));
+        assert!(!a4.is_landscape());
+        assert_eq!(a4.describe(), "A4 portrait (210 × 297 mm)");
+
+        let rotated = PageInfo { rotation: 90, ..a4 };
+        assert!(rotated.is_landscape());
+        assert_eq!(rotated.paper_name(), Some("A4"));
+
+        let custom = PageInfo {
+            width: 100.0,
+            height: 100.0,
+            rotation: 0,
+        };
+        assert_eq!(custom.paper_name(), None);
+    }
+}
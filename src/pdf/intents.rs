@@ -0,0 +1,191 @@
+use super::utils::PageCopier;
+use crate::i18n::tr;
+use anyhow::{Context, Result, bail};
+use lopdf::{Document, Object, Stream, dictionary};
+use std::path::Path;
+
+/// Length of the header every ICC profile starts with.
+const ICC_HEADER_LENGTH: usize = 128;
+
+/// An ICC profile describing the printing condition the colors of a document are meant for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IccProfile {
+    /// Name of the printing condition, written as the identifier of the output intent.
+    pub name: String,
+    /// Color components of the profile: 1 (grey), 3 (RGB) or 4 (CMYK).
+    pub components: u8,
+    pub data: Vec<u8>,
+}
+
+/**
+ * Read an ICC profile to attach to outputs, named after its file (e.g., "FOGRA39" for
+ * FOGRA39.icc).
+ * @param path The profile file path.
+ * @returns The profile.
+ * @throws anyhow::Error if the file cannot be read, is not an ICC profile or is not a grey, RGB or
+ * CMYK one.
+ */
+pub fn read_icc_profile(path: &str) -> Result<IccProfile> {
+    let data = std::fs::read(path).with_context(|| tr!("Failed to read '{path}'", path = path))?;
+    if data.len() < ICC_HEADER_LENGTH || &data[36..40] != b"acsp" {
+        bail!(tr!("'{path}' is not an ICC profile", path = path));
+    }
+    let components = match &data[16..20] {
+        b"GRAY" => 1,
+        b"RGB " => 3,
+        b"CMYK" => 4,
+        _ => bail!(tr!(
+            "'{path}' is not a grey, RGB or CMYK ICC profile",
+            path = path
+        )),
+    };
+    let name = Path::new(path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string());
+    Ok(IccProfile {
+        name,
+        components,
+        data,
+    })
+}
+
+/**
+ * Make an ICC profile the output intent of a document, in place of those it has.
+ * @param doc The document to change.
+ * @param profile The profile of the printing condition.
+ */
+pub fn set_output_intent(doc: &mut Document, profile: &IccProfile) {
+    let stream = Stream::new(
+        dictionary! { "N" => profile.components as i64 },
+        profile.data.clone(),
+    );
+    let profile_id = doc.add_object(stream);
+    let intent = doc.add_object(dictionary! {
+        "Type" => "OutputIntent",
+        "S" => "GTS_PDFX",
+        "OutputConditionIdentifier" => Object::string_literal(profile.name.as_str()),
+        "Info" => Object::string_literal(profile.name.as_str()),
+        "DestOutputProfile" => profile_id,
+    });
+    if let Ok(catalog) = doc.catalog_mut() {
+        catalog.set("OutputIntents", vec![intent.into()]);
+    }
+}
+
+/**
+ * Copy the output intents of a source into a document built from its pages, so a print-ready
+ * file keeps the description of its colors.
+ * @param source The document the pages come from.
+ * @param name The name the source is known by in the copier.
+ * @param copier The copier building the document.
+ * @returns The copied intents, to put in the catalog of the target once it has one, or None
+ * when the source has none.
+ */
+pub fn copy_output_intents(
+    source: &Document,
+    name: &str,
+    copier: &mut PageCopier,
+) -> Option<Object> {
+    let intents = source.catalog().ok()?.get(b"OutputIntents").ok()?;
+    Some(copier.copy_object(source, name, intents))
+}
+
+/**
+ * Put copied output intents in the catalog of a document.
+ * @param target The document, once it has a catalog.
+ * @param intents The intents returned by copy_output_intents.
+ */
+pub fn add_output_intents(target: &mut Document, intents: Option<Object>) {
+    if let Some(intents) = intents
+        && let Ok(catalog) = target.catalog_mut()
+    {
+        catalog.set("OutputIntents", intents);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::merge::merge_pdfs;
+    use crate::pdf::split::split_pdfs;
+
+    fn icc_profile(color_space: &[u8; 4]) -> Vec<u8> {
+        let mut data = vec![0u8; ICC_HEADER_LENGTH];
+        data[16..20].copy_from_slice(color_space);
+        data[36..40].copy_from_slice(b"acsp");
+        data
+    }
+
+    fn output_intents(path: &str) -> Option<Vec<(String, i64)>> {
+        let doc = Document::load(path).unwrap();
+        let intents = doc.catalog().ok()?.get(b"OutputIntents").ok()?;
+        let intents = doc.dereference(intents).unwrap().1.as_array().unwrap();
+        let described = intents
+            .iter()
+            .map(|intent| {
+                let intent = doc.dereference(intent).unwrap().1.as_dict().unwrap();
+                let name = intent.get(b"OutputConditionIdentifier").unwrap();
+                let profile = intent.get(b"DestOutputProfile").unwrap();
+                let profile = doc.get_object(profile.as_reference().unwrap()).unwrap();
+                let components = profile.as_stream().unwrap().dict.get(b"N").unwrap();
+                (
+                    String::from_utf8_lossy(name.as_str().unwrap()).into_owned(),
+                    components.as_i64().unwrap(),
+                )
+            })
+            .collect();
+        Some(described)
+    }
+
+    #[test]
+    fn test_read_icc_profile() {
+        let dir = std::env::temp_dir();
+        let path = dir.join("pdfcutter_intents_FOGRA39.icc");
+        std::fs::write(&path, icc_profile(b"CMYK")).unwrap();
+        let profile = read_icc_profile(&path.to_string_lossy()).unwrap();
+        assert_eq!(profile.name, "pdfcutter_intents_FOGRA39");
+        assert_eq!(profile.components, 4);
+
+        std::fs::write(&path, icc_profile(b"Lab ")).unwrap();
+        assert!(read_icc_profile(&path.to_string_lossy()).is_err());
+        std::fs::write(&path, b"not a profile").unwrap();
+        assert!(read_icc_profile(&path.to_string_lossy()).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_output_intents_are_kept() {
+        let dir = std::env::temp_dir();
+        let input = dir.join("pdfcutter_intents_input.pdf");
+        let input = input.to_string_lossy().into_owned();
+        let mut doc = Document::load("tests/tests_pdf/c.pdf").unwrap();
+        let profile = IccProfile {
+            name: "FOGRA39".to_string(),
+            components: 4,
+            data: icc_profile(b"CMYK"),
+        };
+        set_output_intent(&mut doc, &profile);
+        doc.save(&input).unwrap();
+        let expected = Some(vec![("FOGRA39".to_string(), 4)]);
+
+        let prefix = dir.join("pdfcutter_intents_part");
+        let outputs = split_pdfs(&input, &prefix.to_string_lossy(), "1-2,3").unwrap();
+        for output in &outputs {
+            assert_eq!(output_intents(output), expected);
+            std::fs::remove_file(output).unwrap();
+        }
+
+        // The first input with output intents gives them to the merge
+        let merged = dir.join("pdfcutter_intents_merged.pdf");
+        let merged = merged.to_string_lossy();
+        let inputs = ["tests/tests_pdf/a.pdf".to_string(), input.clone()];
+        merge_pdfs(&inputs, &merged).unwrap();
+        assert_eq!(output_intents(&merged), expected);
+        merge_pdfs(&inputs[..1], &merged).unwrap();
+        assert_eq!(output_intents(&merged), None);
+
+        std::fs::remove_file(&input).unwrap();
+        std::fs::remove_file(&*merged).unwrap();
+    }
+}
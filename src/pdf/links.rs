@@ -0,0 +1,408 @@
+use super::utils::load_document;
+use anyhow::Result;
+use lopdf::{Dictionary, Document, Object, ObjectId, decode_text_string};
+use std::collections::{HashMap, HashSet};
+
+/// Deepest name tree followed when looking up named destinations, against cycles.
+const MAX_NAME_TREE_DEPTH: usize = 32;
+
+/// Where a link annotation leads.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkTarget {
+    /// A page of the document; None when the destination is no page of it.
+    Page(Option<u32>),
+    /// A named destination and the page it leads to; None when the name is not defined or
+    /// leads to no page of the document.
+    Named { name: String, page: Option<u32> },
+    /// An address opened outside the document (web page, mail...).
+    Uri(String),
+    /// Another PDF file.
+    File(String),
+    /// An action that is not followed, by its type (e.g., "JavaScript", "Launch").
+    Other(String),
+}
+
+/// A link annotation of the document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Link {
+    /// The page the link is on (1-based).
+    pub page: u32,
+    pub target: LinkTarget,
+}
+
+impl LinkTarget {
+    /// Whether the target is inside the document, but no page of it.
+    pub fn is_broken(&self) -> bool {
+        matches!(
+            self,
+            LinkTarget::Page(None) | LinkTarget::Named { page: None, .. }
+        )
+    }
+}
+
+impl Link {
+    /// Whether the link leads inside the document, but to no page of it.
+    pub fn is_broken(&self) -> bool {
+        self.target.is_broken()
+    }
+}
+
+/**
+ * Load a PDF and list its link annotations.
+ * @param path The PDF file path.
+ * @returns The links, page by page in the order of their annotations.
+ * @throws anyhow::Error if the file cannot be loaded.
+ */
+pub fn list_links(path: &str) -> Result<Vec<Link>> {
+    log::debug!("Loading '{}'", path);
+    let doc = load_document(path)?;
+    Ok(find_links(&doc))
+}
+
+/**
+ * List the link annotations of a loaded document, resolving internal destinations to pages.
+ * Annotations with neither a destination nor an action are left out.
+ * @param doc The document.
+ * @returns The links, page by page in the order of their annotations.
+ */
+pub fn find_links(doc: &Document) -> Vec<Link> {
+    let pages = doc.get_pages();
+    let page_numbers: HashMap<ObjectId, u32> =
+        pages.iter().map(|(&number, &id)| (id, number)).collect();
+    let names = named_destinations(doc);
+    let resolver = Resolver {
+        doc,
+        page_numbers: &page_numbers,
+        page_count: pages.len(),
+        names: &names,
+    };
+
+    let mut links = Vec::new();
+    for (&page, &page_id) in &pages {
+        let Ok(annotations) = doc
+            .get_dictionary(page_id)
+            .and_then(|page| page.get(b"Annots"))
+            .and_then(|annots| doc.dereference(annots))
+            .and_then(|(_, annots)| annots.as_array())
+        else {
+            continue;
+        };
+        for annotation in annotations {
+            let Ok((_, annotation)) = doc.dereference(annotation) else {
+                continue;
+            };
+            let Ok(annotation) = annotation.as_dict() else {
+                continue;
+            };
+            if !matches!(annotation.get(b"Subtype"), Ok(Object::Name(name)) if name == b"Link") {
+                continue;
+            }
+            if let Some(target) = resolver.link_target(annotation) {
+                links.push(Link { page, target });
+            }
+        }
+    }
+    links
+}
+
+/**
+ * Remove the link annotations that lead inside the document but to no page of it, such as the
+ * links of an extract to the pages left out, which would do nothing when clicked.
+ * @param doc The document to change.
+ * @returns The number of links removed.
+ */
+pub fn remove_broken_links(doc: &mut Document) -> usize {
+    let pages = doc.get_pages();
+    let page_numbers: HashMap<ObjectId, u32> =
+        pages.iter().map(|(&number, &id)| (id, number)).collect();
+    let names = named_destinations(doc);
+    let resolver = Resolver {
+        doc,
+        page_numbers: &page_numbers,
+        page_count: pages.len(),
+        names: &names,
+    };
+
+    let mut changes = Vec::new();
+    for &page_id in pages.values() {
+        let Ok(annotations) = doc
+            .get_dictionary(page_id)
+            .and_then(|page| page.get(b"Annots"))
+            .and_then(|annots| doc.dereference(annots))
+            .and_then(|(_, annots)| annots.as_array())
+        else {
+            continue;
+        };
+        let (broken, kept): (Vec<&Object>, Vec<&Object>) =
+            annotations.iter().partition(|annotation| {
+                doc.dereference(annotation)
+                    .ok()
+                    .and_then(|(_, annotation)| annotation.as_dict().ok())
+                    .filter(|annotation| {
+                        matches!(annotation.get(b"Subtype"), Ok(Object::Name(name)) if name == b"Link")
+                    })
+                    .and_then(|annotation| resolver.link_target(annotation))
+                    .is_some_and(|target| target.is_broken())
+            });
+        if !broken.is_empty() {
+            let removed: Vec<ObjectId> = broken
+                .iter()
+                .filter_map(|annotation| annotation.as_reference().ok())
+                .collect();
+            let kept: Vec<Object> = kept.into_iter().cloned().collect();
+            changes.push((page_id, kept, broken.len(), removed));
+        }
+    }
+
+    let mut count = 0;
+    for (page_id, kept, broken, removed) in changes {
+        if let Ok(page) = doc.get_dictionary_mut(page_id) {
+            if kept.is_empty() {
+                page.remove(b"Annots");
+            } else {
+                page.set("Annots", kept);
+            }
+        }
+        for id in removed {
+            doc.objects.remove(&id);
+        }
+        count += broken;
+    }
+    count
+}
+
+/// What is needed to find the page a destination leads to.
+struct Resolver<'a> {
+    doc: &'a Document,
+    page_numbers: &'a HashMap<ObjectId, u32>,
+    page_count: usize,
+    names: &'a HashMap<Vec<u8>, Object>,
+}
+
+impl Resolver<'_> {
+    /// Where a link annotation leads: its /Dest, else its /A action.
+    fn link_target(&self, annotation: &Dictionary) -> Option<LinkTarget> {
+        if let Ok(dest) = annotation.get(b"Dest") {
+            return Some(self.destination(dest));
+        }
+        let (_, action) = self.doc.dereference(annotation.get(b"A").ok()?).ok()?;
+        let action = action.as_dict().ok()?;
+        let kind = action.get(b"S").and_then(Object::as_name).ok()?;
+        Some(match kind {
+            b"GoTo" => match action.get(b"D") {
+                Ok(dest) => self.destination(dest),
+                Err(_) => LinkTarget::Page(None),
+            },
+            b"URI" => LinkTarget::Uri(self.text(action.get(b"URI").ok()).unwrap_or_default()),
+            b"GoToR" => LinkTarget::File(self.file_name(action.get(b"F").ok()).unwrap_or_default()),
+            other => LinkTarget::Other(String::from_utf8_lossy(other).into_owned()),
+        })
+    }
+
+    /// Where a destination leads: an explicit one names its page, a named one is looked up.
+    fn destination(&self, dest: &Object) -> LinkTarget {
+        match dest {
+            Object::Name(name) | Object::String(name, _) => LinkTarget::Named {
+                name: String::from_utf8_lossy(name).into_owned(),
+                page: self
+                    .names
+                    .get(name)
+                    .and_then(|dest| self.explicit_page(dest, 0)),
+            },
+            _ => LinkTarget::Page(self.explicit_page(dest, 0)),
+        }
+    }
+
+    /// The page of an explicit destination: [page /XYZ ...], or a dictionary holding it in /D.
+    fn explicit_page(&self, dest: &Object, depth: usize) -> Option<u32> {
+        if depth > MAX_NAME_TREE_DEPTH {
+            return None;
+        }
+        match dest {
+            Object::Reference(id) => self.explicit_page(self.doc.get_object(*id).ok()?, depth + 1),
+            Object::Dictionary(dict) => self.explicit_page(dict.get(b"D").ok()?, depth + 1),
+            Object::Array(items) => match items.first()? {
+                Object::Reference(id) => self.page_numbers.get(id).copied(),
+                // Some producers number pages from 0 instead of referencing them
+                Object::Integer(index) => u32::try_from(*index + 1)
+                    .ok()
+                    .filter(|&page| page as usize <= self.page_count),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// A text string of an action, such as its URI.
+    fn text(&self, value: Option<&Object>) -> Option<String> {
+        let (_, value) = self.doc.dereference(value?).ok()?;
+        match value {
+            Object::String(bytes, _) => decode_text_string(value)
+                .ok()
+                .or_else(|| Some(String::from_utf8_lossy(bytes).into_owned())),
+            _ => None,
+        }
+    }
+
+    /// The path of a file specification, a string or a dictionary holding it in /UF or /F.
+    fn file_name(&self, spec: Option<&Object>) -> Option<String> {
+        let (_, spec) = self.doc.dereference(spec?).ok()?;
+        match spec {
+            Object::Dictionary(dict) => self
+                .text(dict.get(b"UF").ok())
+                .or_else(|| self.text(dict.get(b"F").ok())),
+            _ => self.text(Some(spec)),
+        }
+    }
+}
+
+/**
+ * Gather the named destinations of a document: the /Dests dictionary of the catalog (PDF 1.1)
+ * and the /Dests name tree of its /Names dictionary.
+ * @param doc The document.
+ * @returns The destination of each name.
+ */
+fn named_destinations(doc: &Document) -> HashMap<Vec<u8>, Object> {
+    let mut names = catalog_destinations(doc);
+    names.extend(name_tree_destinations(doc));
+    names
+}
+
+/// The destinations of the /Dests dictionary of the catalog, which links name with a name object.
+pub fn catalog_destinations(doc: &Document) -> HashMap<Vec<u8>, Object> {
+    let mut names = HashMap::new();
+    if let Ok(catalog) = doc.catalog()
+        && let Ok((_, Object::Dictionary(dests))) =
+            catalog.get(b"Dests").and_then(|d| doc.dereference(d))
+    {
+        for (name, dest) in dests.iter() {
+            names.insert(name.clone(), dest.clone());
+        }
+    }
+    names
+}
+
+/// The destinations of the /Dests name tree, which links name with a string.
+pub fn name_tree_destinations(doc: &Document) -> HashMap<Vec<u8>, Object> {
+    let mut names = HashMap::new();
+    if let Ok(catalog) = doc.catalog()
+        && let Ok((_, Object::Dictionary(name_dict))) =
+            catalog.get(b"Names").and_then(|n| doc.dereference(n))
+        && let Ok(tree) = name_dict.get(b"Dests")
+    {
+        collect_name_tree(doc, tree, &mut names, &mut HashSet::new(), 0);
+    }
+    names
+}
+
+/// Add the entries of a name tree node and of its kids.
+fn collect_name_tree(
+    doc: &Document,
+    node: &Object,
+    names: &mut HashMap<Vec<u8>, Object>,
+    visited: &mut HashSet<ObjectId>,
+    depth: usize,
+) {
+    if depth > MAX_NAME_TREE_DEPTH {
+        return;
+    }
+    if let Object::Reference(id) = node
+        && !visited.insert(*id)
+    {
+        return;
+    }
+    let Ok((_, Object::Dictionary(node))) = doc.dereference(node) else {
+        return;
+    };
+    if let Ok(Object::Array(entries)) = node.get(b"Names") {
+        for pair in entries.chunks_exact(2) {
+            if let Object::String(name, _) = &pair[0] {
+                names.insert(name.clone(), pair[1].clone());
+            }
+        }
+    }
+    if let Ok(Object::Array(kids)) = node.get(b"Kids") {
+        for kid in kids {
+            collect_name_tree(doc, kid, names, visited, depth + 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::{StringFormat, dictionary};
+
+    fn link(dest_or_action: (&str, Object)) -> Object {
+        let mut annotation = dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Link",
+            "Rect" => vec![0.into(), 0.into(), 10.into(), 10.into()],
+        };
+        annotation.set(dest_or_action.0, dest_or_action.1);
+        Object::Dictionary(annotation)
+    }
+
+    #[test]
+    fn test_find_links() {
+        let mut doc = Document::load("tests/tests_pdf/c.pdf").unwrap();
+        let pages = doc.get_pages();
+        let missing_page = doc.add_object(dictionary! { "Type" => "Page" });
+        let string = |text: &str| Object::String(text.as_bytes().to_vec(), StringFormat::Literal);
+
+        let annotations = vec![
+            link(("Dest", vec![pages[&3].into(), "Fit".into()].into())),
+            link(("Dest", vec![missing_page.into(), "Fit".into()].into())),
+            link((
+                "A",
+                dictionary! { "S" => "URI", "URI" => string("https://example.com") }.into(),
+            )),
+            link(("Dest", string("chapter2"))),
+            link(("Dest", string("nowhere"))),
+            link((
+                "A",
+                dictionary! { "S" => "GoTo", "D" => vec![6.into(), "Fit".into()] }.into(),
+            )),
+            link((
+                "A",
+                dictionary! { "S" => "GoToR", "F" => string("annex.pdf") }.into(),
+            )),
+            link(("A", dictionary! { "S" => "JavaScript" }.into())),
+            Object::Dictionary(dictionary! { "Subtype" => "Text" }),
+        ];
+        doc.get_dictionary_mut(pages[&1])
+            .unwrap()
+            .set("Annots", annotations);
+        let tree = doc.add_object(dictionary! {
+            "Names" => vec![string("chapter2"), vec![pages[&5].into(), "Fit".into()].into()],
+        });
+        let catalog_id = doc.trailer.get(b"Root").unwrap().as_reference().unwrap();
+        doc.get_dictionary_mut(catalog_id)
+            .unwrap()
+            .set("Names", dictionary! { "Dests" => tree });
+
+        let targets: Vec<LinkTarget> = find_links(&doc).into_iter().map(|l| l.target).collect();
+        assert_eq!(
+            targets,
+            vec![
+                LinkTarget::Page(Some(3)),
+                LinkTarget::Page(None),
+                LinkTarget::Uri("https://example.com".to_string()),
+                LinkTarget::Named {
+                    name: "chapter2".to_string(),
+                    page: Some(5)
+                },
+                LinkTarget::Named {
+                    name: "nowhere".to_string(),
+                    page: None
+                },
+                LinkTarget::Page(Some(7)),
+                LinkTarget::File("annex.pdf".to_string()),
+                LinkTarget::Other("JavaScript".to_string()),
+            ]
+        );
+        let broken = find_links(&doc).iter().filter(|l| l.is_broken()).count();
+        assert_eq!(broken, 2);
+        assert!(list_links("tests/tests_pdf/a.pdf").unwrap().is_empty());
+    }
+}
@@ -1,6 +1,93 @@
-use super::utils::{copy_page_with_resources, create_pages_structure, finalize_document};
+use super::inspect::{media_box, page_infos};
+use super::intents::{add_output_intents, copy_output_intents};
+use super::names::{NameConflicts, NameMerger};
+use super::progress::{Progress, report};
+use super::rotate::{RotationRule, rotate_document};
+use super::streaming::StreamingOutput;
+use super::structure::{StructureBuilder, TagWarning};
+use super::utils::{PageCopier, create_pages_structure, load_document};
+use super::watermark::{
+    add_page_resource, append_page_content, display_matrix, reals, text_width,
+    unused_resource_name, win_ansi,
+};
+use crate::i18n::tr;
 use anyhow::{Context, Result};
-use lopdf::{Document, ObjectId};
+use lopdf::content::{Content, Operation};
+use lopdf::{Dictionary, Document, Object, ObjectId, Stream, StringFormat, dictionary};
+use std::path::Path;
+
+/// Size of separator pages when the input before them has no pages: A4 portrait, in points.
+const DEFAULT_PAGE_SIZE: (f32, f32) = (595.28, 841.89);
+
+/// Largest font size of the file name on a title separator, in points.
+const TITLE_FONT_SIZE: f32 = 32.0;
+
+/// Share of the page width the file name on a title separator may span.
+const TITLE_SPAN: f32 = 0.8;
+
+/// Font size of the source footer, in points.
+const FOOTER_FONT_SIZE: f32 = 7.0;
+
+/// Distance between the baseline of the source footer and the bottom of the page, in points.
+const FOOTER_MARGIN: f32 = 12.0;
+
+/// One input of a merge, with the changes made to it on the way in.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeInput {
+    pub path: String,
+    /// The pages taken, in this order (1-based); None takes every page.
+    pub pages: Option<Vec<u32>>,
+    /// Clockwise angle added to the rotation of every page taken, a multiple of 90.
+    pub rotation: i64,
+}
+
+impl MergeInput {
+    /// Every page of the file, unchanged.
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            ..Self::default()
+        }
+    }
+}
+
+/// What is put between two inputs of a merge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Separator {
+    /// An empty page, the size of the last page before it.
+    Blank,
+    /// Every page of a divider PDF.
+    File(String),
+    /// A page showing the file name of the next input, the size of the last page before it.
+    Title,
+}
+
+/// Changes made to the whole merge rather than to one input.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeOptions {
+    /// What is put between two inputs, if anything.
+    pub separator: Option<Separator>,
+    /// Stamp every page taken from an input with the file name and page number it comes from.
+    pub source_footer: bool,
+    /// Copy the structure trees of tagged inputs, so the output stays accessible.
+    pub keep_tags: bool,
+    /// What happens when inputs use the same name for a named destination or a form field.
+    pub name_conflicts: NameConflicts,
+}
+
+impl Separator {
+    /**
+     * Read a separator given on the command line: `blank`, `title` or the path of a divider PDF.
+     * A divider file named like a keyword is given with its folder (e.g., `./blank`).
+     */
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "blank" => Separator::Blank,
+            "title" => Separator::Title,
+            path => Separator::File(path.to_string()),
+        }
+    }
+}
 
 /**
  * Merge a list of PDFs into a single output file
@@ -13,27 +100,283 @@ use lopdf::{Document, ObjectId};
  * @param output Output PDF file path
  */
 pub fn merge_pdfs(inputs: &[String], output: &str) -> Result<()> {
-    let mut target = Document::with_version("1.5");
-    let mut page_objects: Vec<ObjectId> = Vec::new();
+    let inputs: Vec<MergeInput> = inputs.iter().map(|path| MergeInput::new(path)).collect();
+    merge_inputs(&inputs, &MergeOptions::default(), output)?;
+    Ok(())
+}
 
-    for path in inputs {
-        let doc = Document::load(path).with_context(|| format!("Failed to load PDF '{}'", path))?;
+/**
+ * Merge a list of PDFs into a single output file, taking only some pages of an input or
+ * rotating them when asked. The inputs are changed in memory, the files are left untouched.
+ * The streams of each input are written out as its pages are copied, so only one input is in
+ * memory at a time with the small objects of the output.
+ * @param inputs The inputs, in merge order, with their pages and rotation.
+ * @param options The separator, footer, tags and name conflicts of the merge.
+ * @param output Output PDF file path
+ * @returns Why the accessibility tags of some inputs are not in the output.
+ * @throws anyhow::Error if an input or the divider cannot be loaded, a page does not exist, a
+ * rotation is not a multiple of 90, two inputs use the same name while name conflicts are errors
+ * or the output cannot be written.
+ */
+pub fn merge_inputs(
+    inputs: &[MergeInput],
+    options: &MergeOptions,
+    output: &str,
+) -> Result<Vec<TagWarning>> {
+    let separator = options.separator.as_ref();
+    let mut copier = PageCopier::new(Document::with_version("1.5"));
+    let mut writer = StreamingOutput::create(output, &copier.target().version)?;
+    let mut page_objects: Vec<ObjectId> = Vec::new();
 
-        // Get pages from this document
-        let pages = doc.get_pages();
+    let divider = match separator {
+        Some(Separator::File(path)) => {
+            log::debug!("Loading '{}'", path);
+            let doc = load_document(path)?;
+            Some((path, doc))
+        }
+        _ => None,
+    };
+    // Blank and title separators take the size of the last page before them
+    let mut last_page_size = DEFAULT_PAGE_SIZE;
+    let mut title_font = None;
+    let mut footer = None;
+    let mut structure = StructureBuilder::new(options.keep_tags);
+    let mut names = NameMerger::new(options.name_conflicts);
+    let mut output_intents = None;
+
+    for (index, input) in inputs.iter().enumerate() {
+        if index > 0 {
+            match (separator, &divider) {
+                (Some(Separator::Blank), _) => {
+                    let page = add_separator_page(
+                        copier.target_mut(),
+                        last_page_size,
+                        vec![],
+                        Dictionary::new(),
+                    )?;
+                    page_objects.push(page);
+                }
+                (Some(Separator::Title), _) => {
+                    let font =
+                        *title_font.get_or_insert_with(|| add_helvetica(copier.target_mut()));
+                    let page = add_separator_page(
+                        copier.target_mut(),
+                        last_page_size,
+                        title_operations(&file_name(&input.path), last_page_size),
+                        dictionary! { "Font" => dictionary! { "F1" => font } },
+                    )?;
+                    page_objects.push(page);
+                }
+                // Every copy of the divider shares its fonts, images and other objects
+                (_, Some((path, divider))) => {
+                    let pages: Vec<u32> = divider.get_pages().into_keys().collect();
+                    page_objects.extend(writer.copy_pages(&mut copier, divider, path, &pages)?);
+                }
+                _ => {}
+            }
+        }
 
-        // For each page, copy it and all its referenced objects
-        for (_page_no, page_id) in pages {
-            let new_page_id = copy_page_with_resources(&doc, page_id, &mut target)?;
-            page_objects.push(new_page_id);
+        log::debug!("Loading '{}'", input.path);
+        let mut doc = load_document(&input.path)?;
+
+        let pages = match &input.pages {
+            Some(pages) => pages.clone(),
+            None => doc.get_pages().into_keys().collect(),
+        };
+        if input.rotation != 0 && !pages.is_empty() {
+            let rule = RotationRule {
+                pages: pages.clone(),
+                angle: input.rotation,
+            };
+            rotate_document(&mut doc, &[rule])
+                .with_context(|| tr!("Failed to rotate '{path}'", path = input.path))?;
+        }
+        let copied = writer
+            .copy_pages(&mut copier, &doc, &input.path, &pages)
+            .with_context(|| tr!("Failed to take pages of '{path}'", path = input.path))?;
+        let infos = page_infos(&doc);
+        let all_pages = doc.get_pages();
+        let copies: Vec<(ObjectId, ObjectId)> = pages
+            .iter()
+            .map(|page_num| all_pages[page_num])
+            .zip(copied.iter().copied())
+            .collect();
+        structure.add(&doc, &input.path, &copies, copier.target_mut());
+        names.add(&mut copier, &doc, &input.path, &copies)?;
+        // The output intents describe the colors of the whole output: the first ones found win
+        if output_intents.is_none() {
+            output_intents = copy_output_intents(&doc, &input.path, &mut copier);
+        }
+        if options.source_footer {
+            let (font, save_state) = *footer.get_or_insert_with(|| {
+                let save_state = Stream::new(Dictionary::new(), b"q\n".to_vec());
+                (
+                    add_helvetica(copier.target_mut()),
+                    copier.target_mut().add_object(save_state),
+                )
+            });
+            let name = file_name(&input.path);
+            for (&page_num, &page_id) in pages.iter().zip(&copied) {
+                let footer = SourceFooter {
+                    text: tr!("{file}, page {page}", file = name, page = page_num),
+                    bounds: media_box(&doc, all_pages[&page_num]).unwrap_or([
+                        0.0,
+                        0.0,
+                        DEFAULT_PAGE_SIZE.0,
+                        DEFAULT_PAGE_SIZE.1,
+                    ]),
+                    rotation: infos[page_num as usize - 1].rotation,
+                };
+                add_source_footer(copier.target_mut(), page_id, &footer, font, save_state)
+                    .with_context(|| tr!("Failed to take pages of '{path}'", path = input.path))?;
+            }
+        }
+        page_objects.extend(copied);
+        report(Progress::File {
+            index: index + 1,
+            total: inputs.len(),
+            path: input.path.clone(),
+            pages: pages.len(),
+        });
+        if let Some(&last) = pages.last()
+            && let Some(info) = infos.get(last as usize - 1)
+        {
+            last_page_size = info.displayed_size();
         }
     }
 
-    // Create the document structure and save
-    create_pages_structure(&mut target, &page_objects)?;
-    finalize_document(&mut target, output)?;
+    create_pages_structure(copier.target_mut(), &page_objects)?;
+    names.finish(copier.target_mut())?;
+    add_output_intents(copier.target_mut(), output_intents);
+    let warnings = structure.finish(copier.target_mut());
+    writer.finish(copier.target_mut())?;
+    log::info!("Saved '{}' ({} pages)", output, page_objects.len());
 
-    Ok(())
+    Ok(warnings)
+}
+
+/// File name of a path, for showing on a title page or footer.
+fn file_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// Add the Helvetica font, shared by the pages PDF Cutter draws text on.
+fn add_helvetica(target: &mut Document) -> ObjectId {
+    target.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Helvetica",
+        "Encoding" => "WinAnsiEncoding",
+    })
+}
+
+/// Where a page of the merge comes from, stamped at its bottom.
+struct SourceFooter {
+    text: String,
+    /// The MediaBox of the page.
+    bounds: [f32; 4],
+    /// The clockwise rotation of the page, in degrees.
+    rotation: i64,
+}
+
+/**
+ * Stamp the source of a copied page in small grey text, centered at its bottom as seen in a viewer.
+ * @param target The document the page was copied into.
+ * @param page_id The copied page.
+ * @param footer The text and the geometry of the page.
+ * @param font The Helvetica font of the target.
+ * @param save_state A stream holding `q`, shared by every stamped page.
+ * @throws anyhow::Error if the page is not a dictionary or the footer cannot be encoded.
+ */
+fn add_source_footer(
+    target: &mut Document,
+    page_id: ObjectId,
+    footer: &SourceFooter,
+    font: ObjectId,
+    save_state: ObjectId,
+) -> Result<()> {
+    let name = unused_resource_name(target, page_id, "PdfCutterFooter");
+    add_page_resource(target, page_id, b"Font", &name, font)?;
+
+    let [left, bottom, right, top] = footer.bounds;
+    let shown_width = if footer.rotation % 180 == 0 {
+        right - left
+    } else {
+        top - bottom
+    };
+    let text = win_ansi(&footer.text);
+    let x = (shown_width - text_width(&text) * FOOTER_FONT_SIZE / 1000.0) / 2.0;
+    let operations = vec![
+        Operation::new("Q", vec![]),
+        // Marked as an artifact, so that screen readers of tagged outputs skip it
+        Operation::new("BMC", vec!["Artifact".into()]),
+        Operation::new("q", vec![]),
+        Operation::new("cm", reals(&display_matrix(footer.rotation, footer.bounds))),
+        Operation::new("BT", vec![]),
+        Operation::new(
+            "Tf",
+            vec![Object::Name(name.into_bytes()), FOOTER_FONT_SIZE.into()],
+        ),
+        Operation::new("g", vec![0.4.into()]),
+        Operation::new("Td", vec![x.max(0.0).into(), FOOTER_MARGIN.into()]),
+        Operation::new("Tj", vec![Object::String(text, StringFormat::Literal)]),
+        Operation::new("ET", vec![]),
+        Operation::new("Q", vec![]),
+        Operation::new("EMC", vec![]),
+    ];
+    let content = Content { operations }.encode()?;
+    append_page_content(target, page_id, save_state, content)
+}
+
+/**
+ * Add a separator page to a document, outside of its page tree.
+ * @param target The document to add the page to.
+ * @param size The width and height of the page, in points.
+ * @param operations What is drawn on the page; none leaves it blank.
+ * @param resources The fonts and other resources the operations use.
+ * @returns The id of the page, to put in the page tree.
+ * @throws anyhow::Error if the drawing operations cannot be encoded.
+ */
+fn add_separator_page(
+    target: &mut Document,
+    size: (f32, f32),
+    operations: Vec<Operation>,
+    resources: Dictionary,
+) -> Result<ObjectId> {
+    let content = Content { operations }
+        .encode()
+        .context(tr!("Failed to draw a separator page"))?;
+    let content_id = target.add_object(Stream::new(Dictionary::new(), content));
+    Ok(target.add_object(dictionary! {
+        "Type" => "Page",
+        "MediaBox" => vec![0.into(), 0.into(), size.0.into(), size.1.into()],
+        "Contents" => content_id,
+        "Resources" => resources,
+    }))
+}
+
+/**
+ * Drawing operations of a title separator: the file name, centered, in the font named F1.
+ * Long names get a smaller font so that they fit the width of the page.
+ * @param name The file name shown.
+ * @param size The width and height of the page, in points.
+ */
+fn title_operations(name: &str, size: (f32, f32)) -> Vec<Operation> {
+    let text = win_ansi(name);
+    let width = text_width(&text).max(1.0);
+    let font_size = TITLE_FONT_SIZE.min(size.0 * TITLE_SPAN * 1000.0 / width);
+    let x = (size.0 - width * font_size / 1000.0) / 2.0;
+    let y = (size.1 - font_size) / 2.0;
+    vec![
+        Operation::new("BT", vec![]),
+        Operation::new("Tf", vec!["F1".into(), font_size.into()]),
+        Operation::new("Td", vec![x.into(), y.into()]),
+        Operation::new("Tj", vec![Object::String(text, StringFormat::Literal)]),
+        Operation::new("ET", vec![]),
+    ]
 }
 
 #[cfg(test)]
@@ -136,6 +479,183 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_merge_inputs_with_pages_and_rotation() {
+        use crate::pdf::inspect::page_infos;
+
+        let input_a = "tests/tests_pdf/a.pdf";
+        let input_c = "tests/tests_pdf/c.pdf";
+        let output = "test_merged_inputs.pdf";
+
+        let a = page_infos(&Document::load(input_a).unwrap());
+        let c = page_infos(&Document::load(input_c).unwrap());
+        let inputs = [
+            MergeInput {
+                rotation: 90,
+                ..MergeInput::new(input_a)
+            },
+            MergeInput {
+                pages: Some(vec![3, 1]),
+                rotation: -90,
+                ..MergeInput::new(input_c)
+            },
+        ];
+        merge_inputs(&inputs, &MergeOptions::default(), output).unwrap();
+
+        let merged = page_infos(&Document::load(output).unwrap());
+        assert_eq!(merged.len(), a.len() + 2);
+        for (page, original) in merged.iter().zip(&a) {
+            assert_eq!(page.rotation, (original.rotation + 90) % 360);
+        }
+        assert_eq!(merged[a.len()].rotation, (c[2].rotation + 270) % 360);
+        assert_eq!(merged[a.len() + 1].rotation, (c[0].rotation + 270) % 360);
+        std::fs::remove_file(output).unwrap();
+
+        // Pages past the end and odd angles are refused without writing anything
+        let past_end = MergeInput {
+            pages: Some(vec![99]),
+            ..MergeInput::new(input_c)
+        };
+        assert!(merge_inputs(&[past_end], &MergeOptions::default(), output).is_err());
+        let odd_angle = MergeInput {
+            rotation: 45,
+            ..MergeInput::new(input_c)
+        };
+        assert!(merge_inputs(&[odd_angle], &MergeOptions::default(), output).is_err());
+        assert!(!Path::new(output).exists());
+    }
+
+    #[test]
+    fn test_merge_with_separators() {
+        use crate::pdf::text::page_text;
+
+        let input_a = "tests/tests_pdf/a.pdf";
+        let input_b = "tests/tests_pdf/b.pdf";
+        let output = "test_merged_separators.pdf";
+        let count = |path: &str| Document::load(path).unwrap().get_pages().len();
+        let (a, b) = (count(input_a), count(input_b));
+        let inputs = [
+            MergeInput::new(input_a),
+            MergeInput::new(input_b),
+            MergeInput::new(input_a),
+        ];
+
+        let with = |separator: Separator| MergeOptions {
+            separator: Some(separator),
+            ..MergeOptions::default()
+        };
+        merge_inputs(&inputs, &with(Separator::Blank), output).unwrap();
+        let merged = Document::load(output).unwrap();
+        assert_eq!(merged.get_pages().len(), 2 * a + b + 2);
+        let last_of_a = page_infos(&Document::load(input_a).unwrap())[a - 1];
+        let blank = page_infos(&merged)[a];
+        assert_eq!(blank.displayed_size(), last_of_a.displayed_size());
+
+        merge_inputs(&inputs, &with(Separator::Title), output).unwrap();
+        assert_eq!(count(output), 2 * a + b + 2);
+        let title = page_text(&Document::load(output).unwrap(), a as u32 + 1).unwrap();
+        assert!(title.contains("b.pdf"), "{title}");
+
+        let divider = Separator::File("tests/tests_pdf/c.pdf".to_string());
+        merge_inputs(&inputs, &with(divider), output).unwrap();
+        assert_eq!(
+            count(output),
+            2 * a + b + 2 * count("tests/tests_pdf/c.pdf")
+        );
+        std::fs::remove_file(output).unwrap();
+
+        // A missing divider is refused before anything is written
+        let missing = Separator::File("nonexistent.pdf".to_string());
+        assert!(merge_inputs(&inputs, &with(missing), output).is_err());
+        assert!(!Path::new(output).exists());
+
+        assert_eq!(Separator::parse("blank"), Separator::Blank);
+        assert_eq!(Separator::parse("title"), Separator::Title);
+        assert_eq!(
+            Separator::parse("./blank"),
+            Separator::File("./blank".to_string())
+        );
+    }
+
+    #[test]
+    fn test_merge_with_source_footer() {
+        use crate::pdf::text::page_text;
+
+        let input_a = "tests/tests_pdf/a.pdf";
+        let input_c = "tests/tests_pdf/c.pdf";
+        let output = "test_merged_footer.pdf";
+        let a = Document::load(input_a).unwrap().get_pages().len() as u32;
+        let inputs = [
+            MergeInput::new(input_a),
+            MergeInput {
+                pages: Some(vec![3, 1]),
+                rotation: 90,
+                ..MergeInput::new(input_c)
+            },
+        ];
+        let options = MergeOptions {
+            source_footer: true,
+            ..MergeOptions::default()
+        };
+        merge_inputs(&inputs, &options, output).unwrap();
+
+        let merged = Document::load(output).unwrap();
+        assert_eq!(merged.get_pages().len() as u32, a + 2);
+        let text = |page: u32| page_text(&merged, page).unwrap_or_default();
+        assert!(text(1).contains("a.pdf, page 1"), "{}", text(1));
+        assert!(text(a).contains(&format!("a.pdf, page {a}")));
+        assert!(text(a + 1).contains("c.pdf, page 3"), "{}", text(a + 1));
+        assert!(text(a + 2).contains("c.pdf, page 1"));
+        std::fs::remove_file(output).unwrap();
+    }
+
+    #[test]
+    fn test_merge_is_reproducible() {
+        let inputs = vec![
+            "tests/tests_pdf/a.pdf".to_string(),
+            "tests/tests_pdf/c.pdf".to_string(),
+            "tests/tests_pdf/b.pdf".to_string(),
+        ];
+        let first = "test_merged_reproducible_1.pdf";
+        let second = "test_merged_reproducible_2.pdf";
+
+        merge_pdfs(&inputs, first).unwrap();
+        merge_pdfs(&inputs, second).unwrap();
+        assert_eq!(
+            std::fs::read(first).unwrap(),
+            std::fs::read(second).unwrap()
+        );
+
+        std::fs::remove_file(first).unwrap();
+        std::fs::remove_file(second).unwrap();
+    }
+
+    #[test]
+    fn test_merge_reports_each_file() {
+        use crate::pdf::progress::watch_progress;
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let seen = Rc::clone(&events);
+        watch_progress(move |event| seen.borrow_mut().push(event.clone()));
+        let inputs = vec![
+            "tests/tests_pdf/a.pdf".to_string(),
+            "tests/tests_pdf/c.pdf".to_string(),
+        ];
+        let output = "test_merged_progress.pdf";
+        merge_pdfs(&inputs, output).unwrap();
+
+        let file = |index: usize, pages: usize| Progress::File {
+            index,
+            total: 2,
+            path: inputs[index - 1].clone(),
+            pages,
+        };
+        assert_eq!(*events.borrow(), vec![file(1, 2), file(2, 7)]);
+        std::fs::remove_file(output).unwrap();
+    }
+
     #[test]
     fn test_merge_nonexistent_file() {
         let inputs = vec!["nonexistent.pdf".to_string()];
@@ -0,0 +1,510 @@
+use super::intents::{IccProfile, set_output_intent};
+use crate::i18n::tr;
+use anyhow::{Result, anyhow, bail};
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use lopdf::{Dictionary, Document, Object, StringFormat, text_string};
+use std::cell::RefCell;
+use std::hash::{BuildHasher, Hasher, RandomState};
+use std::time::SystemTime;
+
+/// Entries of the document information dictionary to set on an output.
+/// None leaves an entry as it is, an empty value removes it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocumentMetadata {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub subject: Option<String>,
+    pub keywords: Option<String>,
+    /// How the output was made, see provenance::receipt.
+    pub provenance: Option<String>,
+    /// The file identifier written in the trailer, None leaves it as written.
+    pub id: Option<FileId>,
+    /// The creation and modification dates, None leaves them as written.
+    pub dates: Option<DocumentDates>,
+    /// The ICC profile made the output intent, None keeps the intents of the inputs.
+    pub output_intent: Option<IccProfile>,
+}
+
+/// The `/ID` entry of the trailer, which tools use to tell files apart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileId {
+    /// No identifier.
+    Remove,
+    /// A fresh random identifier, different for every output.
+    New,
+    /// These two identifier strings: the one given when the file was created, then the one
+    /// of its last change.
+    Set(Vec<u8>, Vec<u8>),
+}
+
+/// Creation and modification dates as PDF dates (e.g., "D:20250101000000Z"); None removes one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocumentDates {
+    pub created: Option<String>,
+    pub modified: Option<String>,
+}
+
+impl DocumentDates {
+    /// Both dates set to the same moment.
+    pub fn at(date: &str) -> Self {
+        Self {
+            created: Some(date.to_string()),
+            modified: Some(date.to_string()),
+        }
+    }
+}
+
+impl DocumentMetadata {
+    /// Whether nothing is to be changed, so outputs can be left as written.
+    pub fn is_empty(&self) -> bool {
+        self.id.is_none()
+            && self.dates.is_none()
+            && self.output_intent.is_none()
+            && self.entries().iter().all(|(_, value)| value.is_none())
+    }
+
+    fn entries(&self) -> [(&'static str, Option<&String>); 5] {
+        [
+            ("Title", self.title.as_ref()),
+            ("Author", self.author.as_ref()),
+            ("Subject", self.subject.as_ref()),
+            ("Keywords", self.keywords.as_ref()),
+            ("Provenance", self.provenance.as_ref()),
+        ]
+    }
+
+    fn update(&self, info: &mut Dictionary) {
+        for (key, value) in self.entries() {
+            match value {
+                Some(value) if value.trim().is_empty() => {
+                    info.remove(key.as_bytes());
+                }
+                Some(value) => info.set(key, text_string(value)),
+                None => {}
+            }
+        }
+        if let Some(dates) = &self.dates {
+            for (key, date) in [
+                ("CreationDate", &dates.created),
+                ("ModDate", &dates.modified),
+            ] {
+                match date {
+                    Some(date) => info.set(key, Object::string_literal(date.as_str())),
+                    None => {
+                        info.remove(key.as_bytes());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/**
+ * Write a moment as a PDF date, with its offset from UTC.
+ * @param date The moment.
+ * @returns The PDF date (e.g., "D:20250131093000+01'00'", or "D:20250131083000Z" in UTC).
+ */
+pub fn pdf_date(date: DateTime<FixedOffset>) -> String {
+    let offset = date.offset().local_minus_utc();
+    let zone = if offset == 0 {
+        "Z".to_string()
+    } else {
+        let sign = if offset < 0 { '-' } else { '+' };
+        let minutes = offset.abs() / 60;
+        format!("{}{:02}'{:02}'", sign, minutes / 60, minutes % 60)
+    };
+    format!("D:{}{}", date.format("%Y%m%d%H%M%S"), zone)
+}
+
+/**
+ * Turn a SOURCE_DATE_EPOCH value into a PDF date, the convention reproducible builds use to
+ * pin timestamps.
+ * @param epoch Seconds since 1970-01-01 UTC (e.g., "1735689600").
+ * @returns The PDF date (e.g., "D:20250101000000Z").
+ * @throws anyhow::Error if the value is not a number of seconds.
+ */
+pub fn pdf_date_from_epoch(epoch: &str) -> Result<String> {
+    Ok(pdf_date(epoch_date(epoch)?))
+}
+
+/**
+ * Read a SOURCE_DATE_EPOCH value.
+ * @param epoch Seconds since 1970-01-01 UTC (e.g., "1735689600").
+ * @returns The moment, in UTC.
+ * @throws anyhow::Error if the value is not a number of seconds.
+ */
+pub fn epoch_date(epoch: &str) -> Result<DateTime<FixedOffset>> {
+    epoch
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+        .map(|date| date.fixed_offset())
+        .ok_or_else(|| {
+            anyhow!(tr!(
+                "Invalid SOURCE_DATE_EPOCH: {value} (expected seconds since 1970)",
+                value = epoch
+            ))
+        })
+}
+
+/**
+ * Read a date typed on the command line.
+ * @param text A day (e.g., "2025-01-31", midnight UTC) or a date and time with its offset
+ * (e.g., "2025-01-31T09:30:00+01:00" or "2025-01-31T08:30:00Z").
+ * @returns The PDF date.
+ * @throws anyhow::Error if the text is neither.
+ */
+pub fn pdf_date_from_text(text: &str) -> Result<String> {
+    let text = text.trim();
+    if let Ok(date) = DateTime::parse_from_rfc3339(text) {
+        return Ok(pdf_date(date));
+    }
+    match NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+        Ok(day) => Ok(pdf_date(
+            day.and_time(Default::default()).and_utc().fixed_offset(),
+        )),
+        Err(_) => bail!(tr!(
+            "Invalid date: {date} (expected e.g. 2025-01-31 or 2025-01-31T09:30:00+01:00)",
+            date = text
+        )),
+    }
+}
+
+/**
+ * Read a file identifier typed as hexadecimal digits.
+ * @param text The digits, e.g. 32 of them for the usual 16 bytes.
+ * @returns The identifier, used for both the creation and last change strings.
+ * @throws anyhow::Error if the text is empty, odd-sized or not hexadecimal.
+ */
+pub fn parse_file_id(text: &str) -> Result<FileId> {
+    let text = text.trim();
+    let invalid = || {
+        anyhow!(tr!(
+            "Invalid file identifier: {id} (expected hexadecimal digits, e.g. 32 of them)",
+            id = text
+        ))
+    };
+    if text.is_empty() || !text.len().is_multiple_of(2) {
+        return Err(invalid());
+    }
+    let bytes = (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()
+        .ok_or_else(invalid)?;
+    Ok(FileId::Set(bytes.clone(), bytes))
+}
+
+/// 16 random bytes for a new file identifier.
+fn new_file_id() -> Vec<u8> {
+    let now = SystemTime::now();
+    [RandomState::new(), RandomState::new()]
+        .iter()
+        .flat_map(|state| {
+            let mut hasher = state.build_hasher();
+            hasher.write(format!("{:?}", now).as_bytes());
+            hasher.finish().to_be_bytes()
+        })
+        .collect()
+}
+
+/**
+ * Read the file identifier of a loaded document.
+ * @param doc The document.
+ * @returns The identifier as set, or `FileId::Remove` when it has none.
+ */
+pub fn file_id(doc: &Document) -> FileId {
+    let ids = doc.trailer.get(b"ID").and_then(Object::as_array);
+    match ids.map(|ids| ids.iter().map(Object::as_str).collect::<Vec<_>>()) {
+        Ok(ids) if ids.len() == 2 => match (&ids[0], &ids[1]) {
+            (Ok(created), Ok(changed)) => FileId::Set(created.to_vec(), changed.to_vec()),
+            _ => FileId::Remove,
+        },
+        _ => FileId::Remove,
+    }
+}
+
+/**
+ * Read the creation and modification dates of a loaded document, as written.
+ * @param doc The document.
+ * @returns The dates; a missing one is None.
+ */
+pub fn document_dates(doc: &Document) -> DocumentDates {
+    let info = doc
+        .trailer
+        .get(b"Info")
+        .ok()
+        .and_then(|info| doc.dereference(info).ok())
+        .and_then(|(_, info)| info.as_dict().ok());
+    let date = |key: &[u8]| {
+        let value = doc.dereference(info?.get(key).ok()?).ok()?.1;
+        String::from_utf8(value.as_str().ok()?.to_vec()).ok()
+    };
+    DocumentDates {
+        created: date(b"CreationDate"),
+        modified: date(b"ModDate"),
+    }
+}
+
+/**
+ * Set the metadata entries of a loaded document, in memory.
+ * An information dictionary the document refers to is updated in place, otherwise a new one
+ * is added when there is something to write in it.
+ * @param doc The document to change.
+ * @param metadata The entries to set or remove.
+ */
+pub fn apply_metadata(doc: &mut Document, metadata: &DocumentMetadata) {
+    let id_string = |bytes: &[u8]| Object::String(bytes.to_vec(), StringFormat::Hexadecimal);
+    match &metadata.id {
+        Some(FileId::Remove) => {
+            doc.trailer.remove(b"ID");
+        }
+        Some(FileId::New) => {
+            let id = new_file_id();
+            doc.trailer.set("ID", vec![id_string(&id), id_string(&id)]);
+        }
+        Some(FileId::Set(created, changed)) => {
+            doc.trailer
+                .set("ID", vec![id_string(created), id_string(changed)]);
+        }
+        None => {}
+    }
+    if let Some(profile) = &metadata.output_intent {
+        set_output_intent(doc, profile);
+    }
+
+    if let Ok(id) = doc.trailer.get(b"Info").and_then(Object::as_reference)
+        && let Ok(info) = doc.get_dictionary_mut(id)
+    {
+        metadata.update(info);
+        return;
+    }
+
+    let mut info = doc
+        .trailer
+        .get(b"Info")
+        .and_then(Object::as_dict)
+        .cloned()
+        .unwrap_or_default();
+    metadata.update(&mut info);
+    if info.is_empty() {
+        doc.trailer.remove(b"Info");
+        return;
+    }
+    let id = doc.add_object(info);
+    doc.trailer.set("Info", id);
+}
+
+thread_local! {
+    // Each thread runs its own command, so the entries of a CLI command never reach the TUI
+    static OUTPUT_METADATA: RefCell<Option<DocumentMetadata>> = const { RefCell::new(None) };
+}
+
+/**
+ * Write metadata entries into every output saved on the current thread from now on, as it is
+ * saved, so an output is never loaded and saved again to set them.
+ * @param metadata The entries to set or remove, nothing is written when there are none.
+ */
+pub fn set_output_metadata(metadata: DocumentMetadata) {
+    let metadata = (!metadata.is_empty()).then_some(metadata);
+    OUTPUT_METADATA.with(|slot| *slot.borrow_mut() = metadata);
+}
+
+/**
+ * Set the entries given to set_output_metadata on the current thread in an output about to be
+ * saved, after what the operation itself wrote into it.
+ * @param doc The output.
+ */
+pub fn apply_output_metadata(doc: &mut Document) {
+    OUTPUT_METADATA.with(|slot| {
+        if let Some(metadata) = slot.borrow().as_ref() {
+            apply_metadata(doc, metadata);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::inspect::preview_document;
+    use crate::pdf::utils::{load_document, save_document};
+
+    /// Save a copy of a document, as operations save their outputs.
+    fn save_copy(input: &str, output: &str) {
+        let mut doc = load_document(input).unwrap();
+        save_document(&mut doc, output).unwrap();
+    }
+
+    #[test]
+    fn test_output_metadata() {
+        let output = "test_metadata_output.pdf";
+
+        let metadata = DocumentMetadata {
+            title: Some("Rapport annuel — 2025".to_string()),
+            author: Some("Jane Doe".to_string()),
+            keywords: Some("scan, invoices".to_string()),
+            provenance: Some("pdf-cutter 0.1.0; merge; a.pdf sha256:25eb".to_string()),
+            ..DocumentMetadata::default()
+        };
+        assert!(!metadata.is_empty());
+        set_output_metadata(metadata);
+        save_copy("tests/tests_pdf/a.pdf", output);
+
+        let preview = preview_document(output).unwrap();
+        assert!(
+            preview
+                .metadata
+                .contains(&("Title", "Rapport annuel — 2025".to_string()))
+        );
+        assert!(
+            preview
+                .metadata
+                .contains(&("Author", "Jane Doe".to_string()))
+        );
+        assert!(
+            preview
+                .metadata
+                .contains(&("Keywords", "scan, invoices".to_string()))
+        );
+        assert_eq!(
+            preview.info.provenance.as_deref(),
+            Some("pdf-cutter 0.1.0; merge; a.pdf sha256:25eb")
+        );
+
+        // Entries left out are kept, an empty value removes one
+        let metadata = DocumentMetadata {
+            author: Some(String::new()),
+            subject: Some("Finance".to_string()),
+            ..DocumentMetadata::default()
+        };
+        set_output_metadata(metadata);
+        save_copy(output, output);
+        let preview = preview_document(output).unwrap();
+        assert!(
+            preview
+                .metadata
+                .contains(&("Title", "Rapport annuel — 2025".to_string()))
+        );
+        assert!(preview.metadata.iter().all(|(key, _)| *key != "Author"));
+        assert!(
+            preview
+                .metadata
+                .contains(&("Subject", "Finance".to_string()))
+        );
+
+        // Without entries, outputs are saved as the operation left them
+        assert!(DocumentMetadata::default().is_empty());
+        set_output_metadata(DocumentMetadata::default());
+        save_copy("tests/tests_pdf/a.pdf", output);
+        let preview = preview_document(output).unwrap();
+        assert!(preview.metadata.iter().all(|(key, _)| *key != "Subject"));
+
+        // Other threads, such as the workers of the TUI, write none
+        set_output_metadata(DocumentMetadata {
+            subject: Some("Finance".to_string()),
+            ..DocumentMetadata::default()
+        });
+        std::thread::spawn(move || save_copy("tests/tests_pdf/a.pdf", output))
+            .join()
+            .unwrap();
+        let preview = preview_document(output).unwrap();
+        assert!(preview.metadata.iter().all(|(key, _)| *key != "Subject"));
+        set_output_metadata(DocumentMetadata::default());
+        std::fs::remove_file(output).unwrap();
+    }
+
+    #[test]
+    fn test_file_id_and_dates() {
+        use lopdf::dictionary;
+
+        let mut doc = Document::with_version("1.5");
+        let info = doc.add_object(dictionary! {
+            "Title" => Object::string_literal("Scan"),
+            "CreationDate" => Object::string_literal("D:20240305101010+01'00'"),
+            "ModDate" => Object::string_literal("D:20240306101010+01'00'"),
+        });
+        doc.trailer.set("Info", info);
+        doc.trailer.set(
+            "ID",
+            vec![Object::string_literal("abc"), Object::string_literal("def")],
+        );
+        assert_eq!(file_id(&doc), FileId::Set(b"abc".to_vec(), b"def".to_vec()));
+        assert_eq!(
+            document_dates(&doc).modified.as_deref(),
+            Some("D:20240306101010+01'00'")
+        );
+
+        // Reproducible: no identifier, both dates pinned
+        let pinned = DocumentMetadata {
+            id: Some(FileId::Remove),
+            dates: Some(DocumentDates::at(
+                &pdf_date_from_epoch("1735689600").unwrap(),
+            )),
+            ..DocumentMetadata::default()
+        };
+        assert!(!pinned.is_empty());
+        apply_metadata(&mut doc, &pinned);
+        assert_eq!(file_id(&doc), FileId::Remove);
+        let dates = document_dates(&doc);
+        assert_eq!(dates, DocumentDates::at("D:20250101000000Z"));
+        assert!(doc.get_dictionary(info).unwrap().get(b"Title").is_ok());
+
+        // Dates can be left out, identifiers regenerated or set
+        let regenerated = DocumentMetadata {
+            id: Some(FileId::New),
+            dates: Some(DocumentDates::default()),
+            ..DocumentMetadata::default()
+        };
+        apply_metadata(&mut doc, &regenerated);
+        assert_eq!(document_dates(&doc), DocumentDates::default());
+        let FileId::Set(first, _) = file_id(&doc) else {
+            panic!("a new identifier should be set");
+        };
+        assert_eq!(first.len(), 16);
+        apply_metadata(&mut doc, &regenerated);
+        assert!(!matches!(file_id(&doc), FileId::Set(again, _) if again == first));
+
+        let id = parse_file_id("00112233445566778899aabbccddeeff").unwrap();
+        apply_metadata(
+            &mut doc,
+            &DocumentMetadata {
+                id: Some(id.clone()),
+                ..DocumentMetadata::default()
+            },
+        );
+        assert_eq!(file_id(&doc), id);
+
+        // No information dictionary is added just to hold nothing
+        let mut bare = Document::with_version("1.5");
+        apply_metadata(&mut bare, &regenerated);
+        assert!(bare.trailer.get(b"Info").is_err());
+    }
+
+    #[test]
+    fn test_parse_dates_and_ids() {
+        assert_eq!(
+            pdf_date_from_text("2025-01-31").unwrap(),
+            "D:20250131000000Z"
+        );
+        assert_eq!(
+            pdf_date_from_text("2025-01-31T09:30:00+01:00").unwrap(),
+            "D:20250131093000+01'00'"
+        );
+        assert_eq!(
+            pdf_date_from_text("2025-01-31T09:30:00-05:30").unwrap(),
+            "D:20250131093000-05'30'"
+        );
+        assert!(pdf_date_from_text("31/01/2025").is_err());
+        assert!(pdf_date_from_epoch("yesterday").is_err());
+
+        assert_eq!(
+            parse_file_id("0aFF").unwrap(),
+            FileId::Set(vec![0x0a, 0xff], vec![0x0a, 0xff])
+        );
+        assert!(parse_file_id("").is_err());
+        assert!(parse_file_id("abc").is_err());
+        assert!(parse_file_id("zz").is_err());
+        assert!(parse_file_id("é0").is_err());
+    }
+}
@@ -1,9 +1,39 @@
+pub mod annotations;
+pub mod cancel;
+pub mod compress;
 pub mod delete;
+pub mod extract;
+pub mod image;
+pub mod inspect;
+pub mod intents;
+pub mod links;
 pub mod merge;
+pub mod metadata;
+pub mod names;
+pub mod operation;
+pub mod output;
+pub mod page_spec;
+pub mod passwords;
+pub mod progress;
+pub mod provenance;
+pub mod rotate;
+pub mod space;
 pub mod split;
+pub mod streaming;
+pub mod structure;
+pub mod text;
+pub mod thumbnail;
+pub mod timings;
 pub mod utils;
+pub mod verify;
+pub mod watermark;
 
+pub use annotations::annotate_page;
+pub use compress::compress_pdf;
 pub use delete::delete_pages;
-pub use merge::merge_pdfs;
+pub use extract::extract_pages;
+pub use merge::{MergeInput, MergeOptions, Separator, merge_inputs, merge_pdfs};
+pub use rotate::{RotationRule, rotate_pages};
 pub use split::split_pdfs;
 pub use split::split_pdfs_named;
+pub use watermark::watermark_pages;
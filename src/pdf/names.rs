@@ -0,0 +1,578 @@
+use super::links::{catalog_destinations, name_tree_destinations};
+use super::utils::PageCopier;
+use crate::i18n::tr;
+use anyhow::{Result, bail};
+use lopdf::{Dictionary, Document, Object, ObjectId, StringFormat, dictionary};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Deepest chain of references followed to find the page of a destination, against cycles.
+const MAX_DESTINATION_DEPTH: usize = 8;
+
+/// Deepest form field followed when gathering the widgets of a field, against cycles.
+const MAX_FIELD_DEPTH: usize = 32;
+
+/// What a merge does when two inputs use the same name for a named destination or a form field.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NameConflicts {
+    /// Rename the one of the later input, prefixed with its position in the merge ("2_intro"),
+    /// along with the links of that input naming it.
+    #[default]
+    Prefix,
+    /// Stop, naming the two inputs.
+    Error,
+    /// Keep the one of the first input: links of later inputs naming the destination lead to
+    /// it, and a later form field of the same name is left out with its widgets.
+    KeepFirst,
+}
+
+/**
+ * Gathers the named destinations and form fields of the inputs of an operation into the
+ * output, keeping those that lead to or sit on copied pages. Inputs are added once their pages
+ * are copied, then the entries are written into the catalog of the output. Without this, the
+ * links naming a destination lead nowhere, and the widgets of form fields cannot be filled.
+ */
+#[derive(Debug, Default)]
+pub struct NameMerger {
+    conflicts: NameConflicts,
+    /// The inputs added so far, for the messages.
+    paths: Vec<String>,
+    /// Destinations of the /Dests dictionary, with the input defining them.
+    dests: BTreeMap<Vec<u8>, (Object, usize)>,
+    /// Destinations of the /Dests name tree, with the input defining them.
+    tree: BTreeMap<Vec<u8>, (Object, usize)>,
+    /// The top-level form fields, from every input in order.
+    fields: Vec<ObjectId>,
+    /// The names of the top-level form fields, with the input defining them.
+    field_names: HashMap<Vec<u8>, usize>,
+    need_appearances: bool,
+    default_appearance: Option<Object>,
+    /// The default resources of the forms, by category; the first input defining a name wins.
+    resources: BTreeMap<Vec<u8>, Dictionary>,
+}
+
+impl NameMerger {
+    /// A merger that settles name conflicts as given.
+    pub fn new(conflicts: NameConflicts) -> Self {
+        Self {
+            conflicts,
+            ..Self::default()
+        }
+    }
+
+    /**
+     * Copy the named destinations leading to copied pages of an input, and the form fields with
+     * a widget on them, settling the names already used by earlier inputs.
+     * @param copier The copier the pages were copied with.
+     * @param source The input document.
+     * @param path The name the input is known by in the copier, its path.
+     * @param pages Each page copied, as (page in the input, its copy in the output), in order.
+     * @throws anyhow::Error naming the two inputs when a name is used twice and conflicts are
+     * errors.
+     */
+    pub fn add(
+        &mut self,
+        copier: &mut PageCopier,
+        source: &Document,
+        path: &str,
+        pages: &[(ObjectId, ObjectId)],
+    ) -> Result<()> {
+        self.paths.push(path.to_string());
+        // A page taken twice is led to from its first copy
+        let mut taken: HashMap<ObjectId, ObjectId> = HashMap::new();
+        for &(page, copy) in pages {
+            taken.entry(page).or_insert(copy);
+        }
+        let mut copies: Vec<ObjectId> = Vec::new();
+        for &(_, copy) in pages {
+            if !copies.contains(&copy) {
+                copies.push(copy);
+            }
+        }
+
+        let renamed_names =
+            self.add_destinations(false, catalog_destinations(source), copier, source, &taken)?;
+        let renamed_strings =
+            self.add_destinations(true, name_tree_destinations(source), copier, source, &taken)?;
+        if !renamed_names.is_empty() || !renamed_strings.is_empty() {
+            let target = copier.target_mut();
+            for &copy in &copies {
+                rename_link_destinations(target, copy, &renamed_names, &renamed_strings);
+            }
+        }
+
+        self.add_fields(copier, source, path, &copies)
+    }
+
+    /**
+     * Copy the destinations of one kind, /Dests dictionary or name tree, that lead to copied
+     * pages.
+     * @returns The destinations renamed, from their name in the input to their name in the output.
+     */
+    fn add_destinations(
+        &mut self,
+        in_tree: bool,
+        destinations: HashMap<Vec<u8>, Object>,
+        copier: &mut PageCopier,
+        source: &Document,
+        taken: &HashMap<ObjectId, ObjectId>,
+    ) -> Result<HashMap<Vec<u8>, Vec<u8>>> {
+        let index = self.paths.len() - 1;
+        // In the order of their names, so the outputs are the same on every run
+        let destinations: BTreeMap<Vec<u8>, (&Vec<Object>, ObjectId)> = destinations
+            .iter()
+            .filter_map(|(name, dest)| {
+                let dest = explicit_destination(source, dest, 0)?;
+                let copy = taken.get(&dest.first()?.as_reference().ok()?)?;
+                Some((name.clone(), (dest, *copy)))
+            })
+            .collect();
+        let kept = if in_tree {
+            &mut self.tree
+        } else {
+            &mut self.dests
+        };
+        let mut renamed = HashMap::new();
+        for (name, (dest, page)) in destinations {
+            let name = match kept.get(&name) {
+                None => name,
+                Some(&(_, first)) => match self.conflicts {
+                    NameConflicts::Error => bail!(tr!(
+                        "'{first}' and '{second}' both define the named destination '{name}'",
+                        first = self.paths[first],
+                        second = self.paths[index],
+                        name = String::from_utf8_lossy(&name)
+                    )),
+                    NameConflicts::KeepFirst => continue,
+                    NameConflicts::Prefix => {
+                        let prefixed = prefixed_name(&name, index, |n| kept.contains_key(n));
+                        renamed.insert(name, prefixed.clone());
+                        prefixed
+                    }
+                },
+            };
+            // Written out, leading to the copy of the page taken from this input
+            let mut copy = copier.copy_object(
+                source,
+                &self.paths[index],
+                &Object::Array(dest[1..].to_vec()),
+            );
+            if let Object::Array(items) = &mut copy {
+                items.insert(0, Object::Reference(page));
+            }
+            kept.insert(name, (copy, index));
+        }
+        Ok(renamed)
+    }
+
+    /// Copy the top-level form fields with a widget on a copied page, and the form defaults.
+    fn add_fields(
+        &mut self,
+        copier: &mut PageCopier,
+        source: &Document,
+        path: &str,
+        copies: &[ObjectId],
+    ) -> Result<()> {
+        let Some(form) = acro_form(source) else {
+            return Ok(());
+        };
+        let index = self.paths.len() - 1;
+        let fields = form
+            .get(b"Fields")
+            .and_then(|fields| source.dereference(fields))
+            .and_then(|(_, fields)| fields.as_array())
+            .map(|fields| {
+                fields
+                    .iter()
+                    .filter_map(|field| field.as_reference().ok())
+                    .collect()
+            })
+            .unwrap_or_else(|_| Vec::new());
+
+        let mut left_out = Vec::new();
+        for field in fields {
+            // Fields with no widget on a copied page were not copied with the pages
+            let Some(copy) = copier.copy_of(path, field) else {
+                continue;
+            };
+            if self.fields.contains(&copy) {
+                continue;
+            }
+            let name = source
+                .get_dictionary(field)
+                .and_then(|field| field.get(b"T"))
+                .and_then(Object::as_str)
+                .map(<[u8]>::to_vec);
+            if let Ok(name) = name {
+                match self.field_names.get(&name) {
+                    None => {
+                        self.field_names.insert(name, index);
+                    }
+                    // Fields named alike in one input are left as the input has them
+                    Some(&first) if first == index => {}
+                    Some(&first) => match self.conflicts {
+                        NameConflicts::Error => bail!(tr!(
+                            "'{first}' and '{second}' both define the form field '{name}'",
+                            first = self.paths[first],
+                            second = self.paths[index],
+                            name = String::from_utf8_lossy(&name)
+                        )),
+                        NameConflicts::KeepFirst => {
+                            left_out.push(copy);
+                            continue;
+                        }
+                        NameConflicts::Prefix => {
+                            let prefixed =
+                                prefixed_name(&name, index, |n| self.field_names.contains_key(n));
+                            if let Ok(field) = copier.target_mut().get_dictionary_mut(copy) {
+                                field.set(
+                                    "T",
+                                    Object::String(prefixed.clone(), StringFormat::Literal),
+                                );
+                            }
+                            self.field_names.insert(prefixed, index);
+                        }
+                    },
+                }
+            }
+            self.fields.push(copy);
+        }
+        if !left_out.is_empty() {
+            remove_widgets(copier.target_mut(), &left_out, copies);
+        }
+
+        if form
+            .get(b"NeedAppearances")
+            .and_then(Object::as_bool)
+            .unwrap_or(false)
+        {
+            self.need_appearances = true;
+        }
+        if self.default_appearance.is_none() {
+            self.default_appearance = form.get(b"DA").ok().cloned();
+        }
+        if let Ok((_, Object::Dictionary(resources))) =
+            form.get(b"DR").and_then(|r| source.dereference(r))
+        {
+            for (category, entries) in resources.iter() {
+                let Ok((_, Object::Dictionary(entries))) = source.dereference(entries) else {
+                    continue;
+                };
+                for (key, value) in entries.iter() {
+                    let merged = self.resources.entry(category.clone()).or_default();
+                    if !merged.has(key) {
+                        merged.set(key.clone(), copier.copy_object(source, path, value));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /**
+     * Write the named destinations and the form into the catalog of the output.
+     * @param target The output document, with its catalog.
+     * @throws anyhow::Error if the output has no catalog.
+     */
+    pub fn finish(self, target: &mut Document) -> Result<()> {
+        let catalog = target.catalog_mut()?;
+        if !self.dests.is_empty() {
+            let dests = self.dests.into_iter().map(|(name, (dest, _))| (name, dest));
+            catalog.set("Dests", Dictionary::from_iter(dests));
+        }
+        if !self.tree.is_empty() {
+            // A single node, its names sorted as name trees require
+            let names: Vec<Object> = self
+                .tree
+                .into_iter()
+                .flat_map(|(name, (dest, _))| [Object::String(name, StringFormat::Literal), dest])
+                .collect();
+            catalog.set(
+                "Names",
+                dictionary! { "Dests" => dictionary! { "Names" => names } },
+            );
+        }
+        if !self.fields.is_empty() {
+            let fields: Vec<Object> = self.fields.into_iter().map(Object::Reference).collect();
+            let mut form = dictionary! { "Fields" => fields };
+            if self.need_appearances {
+                form.set("NeedAppearances", true);
+            }
+            if let Some(default_appearance) = self.default_appearance {
+                form.set("DA", default_appearance);
+            }
+            if !self.resources.is_empty() {
+                let resources = self
+                    .resources
+                    .into_iter()
+                    .map(|(category, entries)| (category, Object::Dictionary(entries)));
+                form.set("DR", Dictionary::from_iter(resources));
+            }
+            catalog.set("AcroForm", form);
+        }
+        Ok(())
+    }
+}
+
+/// The interactive form of a document, if it has one.
+fn acro_form(doc: &Document) -> Option<&Dictionary> {
+    let form = doc.catalog().ok()?.get(b"AcroForm").ok()?;
+    doc.dereference(form).ok()?.1.as_dict().ok()
+}
+
+/// The explicit destination [page /XYZ ...] a named destination holds, directly or in /D.
+fn explicit_destination<'a>(
+    doc: &'a Document,
+    dest: &'a Object,
+    depth: usize,
+) -> Option<&'a Vec<Object>> {
+    if depth > MAX_DESTINATION_DEPTH {
+        return None;
+    }
+    match dest {
+        Object::Reference(id) => explicit_destination(doc, doc.get_object(*id).ok()?, depth + 1),
+        Object::Dictionary(dict) => explicit_destination(doc, dict.get(b"D").ok()?, depth + 1),
+        Object::Array(items) => Some(items),
+        _ => None,
+    }
+}
+
+/// A name prefixed with the position of its input in the merge, from 1, and again until it is free.
+fn prefixed_name(name: &[u8], index: usize, taken: impl Fn(&[u8]) -> bool) -> Vec<u8> {
+    let prefix = format!("{}_", index + 1);
+    let mut prefixed = name.to_vec();
+    loop {
+        prefixed.splice(0..0, prefix.bytes());
+        if !taken(&prefixed) {
+            return prefixed;
+        }
+    }
+}
+
+/**
+ * Lead the links of a copied page to the new names of renamed destinations: a name object is
+ * looked up in the /Dests dictionary, a string in the name tree.
+ */
+fn rename_link_destinations(
+    target: &mut Document,
+    page: ObjectId,
+    renamed_names: &HashMap<Vec<u8>, Vec<u8>>,
+    renamed_strings: &HashMap<Vec<u8>, Vec<u8>>,
+) {
+    let rename = |dest: &mut Object| match dest {
+        Object::Name(name) => {
+            if let Some(new_name) = renamed_names.get(name) {
+                *name = new_name.clone();
+            }
+        }
+        Object::String(name, _) => {
+            if let Some(new_name) = renamed_strings.get(name) {
+                *name = new_name.clone();
+            }
+        }
+        _ => {}
+    };
+    let Ok(Object::Array(annotations)) = target
+        .get_dictionary(page)
+        .and_then(|page| page.get(b"Annots"))
+        .cloned()
+    else {
+        return;
+    };
+    for annotation in annotations {
+        let Ok(id) = annotation.as_reference() else {
+            continue;
+        };
+        let Ok(link) = target.get_dictionary_mut(id) else {
+            continue;
+        };
+        if let Ok(dest) = link.get_mut(b"Dest") {
+            rename(dest);
+            continue;
+        }
+        let action = match link.get_mut(b"A") {
+            Ok(Object::Dictionary(action)) => Some(action),
+            Ok(Object::Reference(action)) => {
+                let action = *action;
+                target.get_dictionary_mut(action).ok()
+            }
+            _ => None,
+        };
+        if let Some(action) = action
+            && action.get(b"S").and_then(Object::as_name).ok() == Some(b"GoTo".as_slice())
+            && let Ok(dest) = action.get_mut(b"D")
+        {
+            rename(dest);
+        }
+    }
+}
+
+/**
+ * Take the widgets of form fields off the copied pages of an input.
+ * @param target The output document.
+ * @param fields The copies of the top-level fields left out.
+ * @param pages The copied pages of the input.
+ */
+fn remove_widgets(target: &mut Document, fields: &[ObjectId], pages: &[ObjectId]) {
+    let mut widgets = HashSet::new();
+    let mut to_visit: Vec<(ObjectId, usize)> = fields.iter().map(|&id| (id, 0)).collect();
+    while let Some((id, depth)) = to_visit.pop() {
+        if depth > MAX_FIELD_DEPTH || !widgets.insert(id) {
+            continue;
+        }
+        if let Ok(Object::Array(kids)) = target.get_dictionary(id).and_then(|f| f.get(b"Kids")) {
+            to_visit.extend(
+                kids.iter()
+                    .filter_map(|kid| kid.as_reference().ok())
+                    .map(|kid| (kid, depth + 1)),
+            );
+        }
+    }
+    for &page in pages {
+        let Ok(page) = target.get_dictionary_mut(page) else {
+            continue;
+        };
+        if let Ok(Object::Array(annotations)) = page.get_mut(b"Annots") {
+            annotations.retain(|annotation| {
+                annotation
+                    .as_reference()
+                    .map_or(true, |id| !widgets.contains(&id))
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::links::{LinkTarget, find_links};
+    use crate::pdf::merge::{MergeInput, MergeOptions, merge_inputs};
+    use crate::pdf::utils::load_document;
+    use std::fs;
+
+    /// The top-level field names of the form of a document.
+    fn field_names(doc: &Document) -> Vec<String> {
+        let Some(form) = acro_form(doc) else {
+            return Vec::new();
+        };
+        form.get(b"Fields")
+            .and_then(Object::as_array)
+            .unwrap()
+            .iter()
+            .map(|field| {
+                let field = doc.get_dictionary(field.as_reference().unwrap()).unwrap();
+                String::from_utf8_lossy(field.get(b"T").unwrap().as_str().unwrap()).into_owned()
+            })
+            .collect()
+    }
+
+    /// The number of annotations on a page of a document.
+    fn annotation_count(doc: &Document, page: u32) -> usize {
+        let page = doc.get_dictionary(doc.get_pages()[&page]).unwrap();
+        page.get(b"Annots")
+            .and_then(Object::as_array)
+            .map_or(0, Vec::len)
+    }
+
+    #[test]
+    fn test_merge_settles_name_conflicts() {
+        let dir = std::env::temp_dir().join("pdfcutter_name_conflicts");
+        fs::create_dir_all(&dir).unwrap();
+        let first = dir.join("first.pdf").to_string_lossy().into_owned();
+        let second = dir.join("second.pdf").to_string_lossy().into_owned();
+        let output = dir.join("merged.pdf").to_string_lossy().into_owned();
+
+        // A link on page 2 to the named destination "intro" (page 1), and a text field "name"
+        // with its widget on page 1
+        let mut doc = load_document("tests/tests_pdf/c.pdf").unwrap();
+        let pages = doc.get_pages();
+        let link = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Link",
+            "Rect" => vec![0.into(), 0.into(), 10.into(), 10.into()],
+            "Dest" => "intro",
+        });
+        let field = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Widget",
+            "FT" => "Tx",
+            "T" => Object::String(b"name".to_vec(), StringFormat::Literal),
+            "Rect" => vec![50.into(), 50.into(), 150.into(), 70.into()],
+            "P" => pages[&1],
+        });
+        doc.get_dictionary_mut(pages[&1])
+            .unwrap()
+            .set("Annots", vec![field.into()]);
+        doc.get_dictionary_mut(pages[&2])
+            .unwrap()
+            .set("Annots", vec![link.into()]);
+        let intro = Object::Array(vec![pages[&1].into(), "Fit".into()]);
+        let catalog = doc.catalog_mut().unwrap();
+        catalog.set("Dests", dictionary! { "intro" => intro });
+        catalog.set(
+            "AcroForm",
+            dictionary! { "Fields" => vec![field.into()], "NeedAppearances" => true },
+        );
+        doc.save(&first).unwrap();
+        doc.save(&second).unwrap();
+
+        let inputs = [MergeInput::new(&first), MergeInput::new(&second)];
+        let merge = |name_conflicts: NameConflicts| {
+            let options = MergeOptions {
+                name_conflicts,
+                ..MergeOptions::default()
+            };
+            merge_inputs(&inputs, &options, &output).map(|_| load_document(&output).unwrap())
+        };
+        let link_target = |doc: &Document, page: u32| {
+            let links = find_links(doc);
+            links
+                .into_iter()
+                .find(|link| link.page == page)
+                .unwrap()
+                .target
+        };
+
+        let merged = merge(NameConflicts::Prefix).unwrap();
+        assert_eq!(
+            link_target(&merged, 9),
+            LinkTarget::Named {
+                name: "2_intro".to_string(),
+                page: Some(8)
+            }
+        );
+        assert_eq!(
+            link_target(&merged, 2),
+            LinkTarget::Named {
+                name: "intro".to_string(),
+                page: Some(1)
+            }
+        );
+        assert_eq!(field_names(&merged), vec!["name", "2_name"]);
+        assert_eq!(annotation_count(&merged, 8), 1);
+        let form = acro_form(&merged).unwrap();
+        assert!(form.get(b"NeedAppearances").unwrap().as_bool().unwrap());
+
+        let error = merge(NameConflicts::Error).unwrap_err().to_string();
+        assert!(error.contains(&first) && error.contains(&second), "{error}");
+
+        let merged = merge(NameConflicts::KeepFirst).unwrap();
+        assert_eq!(
+            link_target(&merged, 9),
+            LinkTarget::Named {
+                name: "intro".to_string(),
+                page: Some(1)
+            }
+        );
+        assert_eq!(field_names(&merged), vec!["name"]);
+        assert_eq!(annotation_count(&merged, 8), 0);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_prefixed_name() {
+        assert_eq!(prefixed_name(b"intro", 1, |_| false), b"2_intro");
+        let taken = |name: &[u8]| name == b"3_intro";
+        assert_eq!(prefixed_name(b"intro", 2, taken), b"3_3_intro");
+    }
+}
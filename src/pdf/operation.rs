@@ -0,0 +1,696 @@
+use super::annotations::TextNote;
+use super::compress::CompressOptions;
+use super::extract::ExtractOptions;
+use super::inspect::inspect_document;
+use super::output::{create_output_dirs, move_to_backup, remove_created_dirs, same_file};
+use super::page_spec::{PageSegment, parse_named_segments, parse_page_segments};
+use super::rotate::RotationRule;
+use super::split::segment_filenames;
+use super::watermark::{WatermarkOptions, WatermarkSource};
+use crate::i18n::tr;
+use anyhow::{Context, Result, anyhow};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A fully configured PDF operation, ready to run later (e.g. from a queue).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operation {
+    Merge {
+        inputs: Vec<String>,
+        output: String,
+    },
+    Delete {
+        input: String,
+        output: String,
+        pages: Vec<u32>,
+    },
+    Split {
+        input: String,
+        output_prefix: String,
+        segments: String,
+        named: bool,
+    },
+    /// A rule with an empty page list rotates every page of the input.
+    Rotate {
+        input: String,
+        output: String,
+        rules: Vec<RotationRule>,
+    },
+    Extract {
+        input: String,
+        output: String,
+        pages: Vec<u32>,
+        options: ExtractOptions,
+    },
+    Compress {
+        input: String,
+        output: String,
+        options: CompressOptions,
+    },
+    /// An empty page list stamps every page of the input.
+    Watermark {
+        input: String,
+        output: String,
+        pages: Vec<u32>,
+        options: WatermarkOptions,
+    },
+    Annotate {
+        input: String,
+        output: String,
+        note: TextNote,
+    },
+}
+
+impl Operation {
+    /**
+     * Files the operation will write, known before running it.
+     * @returns The output paths, or an error if the split segments cannot be parsed.
+     */
+    pub fn outputs(&self) -> Result<Vec<String>> {
+        match self {
+            Operation::Split {
+                output_prefix,
+                segments,
+                named,
+                ..
+            } => segment_filenames(&split_segments(segments, *named)?, output_prefix),
+            Operation::Merge { output, .. }
+            | Operation::Delete { output, .. }
+            | Operation::Rotate { output, .. }
+            | Operation::Extract { output, .. }
+            | Operation::Compress { output, .. }
+            | Operation::Watermark { output, .. }
+            | Operation::Annotate { output, .. } => Ok(vec![output.clone()]),
+        }
+    }
+
+    /// Name of the operation as a command, untranslated, e.g. "merge".
+    pub fn command(&self) -> &'static str {
+        match self {
+            Operation::Merge { .. } => "merge",
+            Operation::Delete { .. } => "delete",
+            Operation::Split { .. } => "split",
+            Operation::Rotate { .. } => "rotate",
+            Operation::Extract { .. } => "extract",
+            Operation::Compress { .. } => "compress",
+            Operation::Watermark { .. } => "watermark",
+            Operation::Annotate { .. } => "annotate",
+        }
+    }
+
+    /// Files the operation reads, the watermark image aside.
+    pub fn inputs(&self) -> Vec<&str> {
+        match self {
+            Operation::Merge { inputs, .. } => inputs.iter().map(String::as_str).collect(),
+            Operation::Delete { input, .. }
+            | Operation::Split { input, .. }
+            | Operation::Rotate { input, .. }
+            | Operation::Extract { input, .. }
+            | Operation::Compress { input, .. }
+            | Operation::Watermark { input, .. }
+            | Operation::Annotate { input, .. } => vec![input],
+        }
+    }
+
+    /// Make every path of the operation absolute, so it reads and writes the same files after
+    /// the working directory changes.
+    pub fn make_paths_absolute(&mut self) {
+        fn absolute(path: &mut String) {
+            if let Ok(absolute) = std::path::absolute(&*path) {
+                *path = absolute.to_string_lossy().into_owned();
+            }
+        }
+
+        match self {
+            Operation::Merge { inputs, output } => {
+                inputs.iter_mut().for_each(absolute);
+                absolute(output);
+            }
+            Operation::Split {
+                input,
+                output_prefix,
+                ..
+            } => {
+                absolute(input);
+                absolute(output_prefix);
+            }
+            Operation::Watermark {
+                input,
+                output,
+                options,
+                ..
+            } => {
+                absolute(input);
+                absolute(output);
+                if let WatermarkSource::Image(path) = &mut options.source {
+                    absolute(path);
+                }
+            }
+            Operation::Delete { input, output, .. }
+            | Operation::Rotate { input, output, .. }
+            | Operation::Extract { input, output, .. }
+            | Operation::Compress { input, output, .. }
+            | Operation::Annotate { input, output, .. } => {
+                absolute(input);
+                absolute(output);
+            }
+        }
+    }
+
+    /**
+     * Inputs the operation would write over, when an output is one of its own inputs.
+     * @returns The inputs replaced by an output, empty when the operation writes new files only.
+     */
+    pub fn overwritten_inputs(&self) -> Vec<String> {
+        // Unreadable split segments are reported when the operation runs
+        let outputs = self.outputs().unwrap_or_default();
+        self.inputs()
+            .into_iter()
+            .filter(|input| outputs.iter().any(|output| same_file(input, output)))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /**
+     * Move the existing files the outputs would replace out of the way, appending the backup suffix.
+     * Inputs written over are left alone: the operation still reads them, and they are copied
+     * when writing over them is confirmed.
+     * @param suffix The backup suffix.
+     * @returns The replaced files with the path each was moved to.
+     * @throws anyhow::Error if the split segments cannot be parsed or a file cannot be renamed.
+     */
+    pub fn back_up_outputs(&self, suffix: &str) -> Result<Vec<(String, String)>> {
+        let overwritten = self.overwritten_inputs();
+        let mut backups = Vec::new();
+        for output in self.outputs()? {
+            if !Path::new(&output).exists() || overwritten.iter().any(|i| same_file(i, &output)) {
+                continue;
+            }
+            match move_to_backup(&output, suffix) {
+                Ok(backup) => backups.push((output, backup)),
+                Err(e) => {
+                    // The files already moved go back, so a failed backup changes nothing
+                    restore_backups(&backups);
+                    return Err(
+                        anyhow!(e).context(tr!("Could not back up '{path}'", path = output))
+                    );
+                }
+            }
+        }
+        Ok(backups)
+    }
+
+    /**
+     * Get the outputs ready to be written: note those that do not exist yet, move the files the
+     * others replace to their backup name when a suffix is given, then create the missing
+     * folders. When a step fails, the earlier ones are undone.
+     * @param backup_suffix The backup suffix, or None to write over the replaced files.
+     * @returns What was changed, for write_or_roll_back to undo if writing fails.
+     * @throws anyhow::Error if a file cannot be backed up or a folder cannot be created.
+     */
+    pub fn prepare_outputs(&self, backup_suffix: Option<&str>) -> Result<PreparedOutputs> {
+        // Unreadable split segments are reported when writing
+        let outputs = self.outputs().unwrap_or_default();
+        // Taken before the backups, which leave the replaced outputs missing too
+        let new_outputs = outputs
+            .iter()
+            .filter(|output| !Path::new(output).exists())
+            .cloned()
+            .collect();
+        let backups = match backup_suffix {
+            Some(suffix) => self.back_up_outputs(suffix)?,
+            None => Vec::new(),
+        };
+        let new_dirs = create_output_dirs(&outputs).inspect_err(|_| restore_backups(&backups))?;
+        Ok(PreparedOutputs {
+            new_outputs,
+            backups,
+            new_dirs,
+        })
+    }
+
+    /**
+     * Estimate the size of each output from the size of the inputs, before running the operation:
+     * their sum for a merge, and a share of the input proportional to the pages written otherwise.
+     * @returns The output paths with their estimated size in bytes.
+     * @throws anyhow::Error if an input cannot be read or the split segments cannot be parsed.
+     */
+    pub fn estimated_sizes(&self) -> Result<Vec<(String, u64)>> {
+        let outputs = self.outputs()?;
+        let sizes = match self {
+            Operation::Merge { inputs, .. } => {
+                let mut total = 0;
+                for input in inputs {
+                    total += file_size(input)?;
+                }
+                vec![total]
+            }
+            Operation::Delete { input, pages, .. } => {
+                let info = inspect_document(input)?;
+                let kept = info.page_count().saturating_sub(pages.len());
+                vec![page_share(info.file_size, kept, info.page_count())]
+            }
+            Operation::Split {
+                input,
+                segments,
+                named,
+                ..
+            } => {
+                let info = inspect_document(input)?;
+                split_segments(segments, *named)?
+                    .iter()
+                    .map(|segment| {
+                        let pages = segment.get_pages().len();
+                        page_share(info.file_size, pages, info.page_count())
+                    })
+                    .collect()
+            }
+            Operation::Extract { input, pages, .. } => {
+                let info = inspect_document(input)?;
+                vec![page_share(info.file_size, pages.len(), info.page_count())]
+            }
+            // The whole document is written back, compression only ever makes it smaller and a
+            // note adds a few hundred bytes
+            Operation::Rotate { input, .. }
+            | Operation::Compress { input, .. }
+            | Operation::Annotate { input, .. } => vec![file_size(input)?],
+            Operation::Watermark { input, options, .. } => {
+                let image = match &options.source {
+                    WatermarkSource::Image(path) => file_size(path)?,
+                    WatermarkSource::Text(_) => 0,
+                };
+                vec![file_size(input)? + image]
+            }
+        };
+
+        Ok(outputs.into_iter().zip(sizes).collect())
+    }
+
+    /**
+     * Run the operation, creating the missing folders of its outputs first.
+     * When it fails, the outputs and folders it had already created are deleted, so no
+     * half-written result is left behind. Files that existed before are kept.
+     * @returns The files that were written.
+     * @throws anyhow::Error if the underlying PDF operation fails.
+     */
+    pub fn run(&self) -> Result<Vec<String>> {
+        self.run_with(|| self.write())
+    }
+
+    /**
+     * Write the outputs of the operation with `write` instead of the default writer, like run:
+     * creating the missing folders first and cleaning up when it fails. Used to get more than
+     * the outputs back, such as the report of a compression.
+     * @param write Writes the outputs.
+     * @returns What `write` returns.
+     * @throws anyhow::Error if a folder cannot be created or `write` fails.
+     */
+    pub fn run_with<T>(&self, write: impl FnOnce() -> Result<T>) -> Result<T> {
+        self.prepare_outputs(None)?.write_or_roll_back(write)
+    }
+
+    fn write(&self) -> Result<Vec<String>> {
+        match self {
+            Operation::Merge { inputs, output } => super::merge_pdfs(inputs, output)?,
+            Operation::Delete {
+                input,
+                output,
+                pages,
+            } => super::delete_pages(input, output, pages)?,
+            Operation::Split {
+                input,
+                output_prefix,
+                segments,
+                named,
+            } => {
+                let segments = split_segments(segments, *named)?;
+                return super::split::split_pdfs_with_segments(input, output_prefix, &segments);
+            }
+            Operation::Rotate {
+                input,
+                output,
+                rules,
+            } => {
+                let rules = rules
+                    .iter()
+                    .map(|rule| {
+                        Ok(RotationRule {
+                            pages: resolve_pages(input, &rule.pages)?,
+                            angle: rule.angle,
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                super::rotate_pages(input, output, &rules)?
+            }
+            Operation::Extract {
+                input,
+                output,
+                pages,
+                options,
+            } => super::extract_pages(input, output, pages, options)?,
+            Operation::Compress {
+                input,
+                output,
+                options,
+            } => {
+                super::compress_pdf(input, output, options)?;
+            }
+            Operation::Watermark {
+                input,
+                output,
+                pages,
+                options,
+            } => super::watermark_pages(input, output, &resolve_pages(input, pages)?, options)?,
+            Operation::Annotate {
+                input,
+                output,
+                note,
+            } => super::annotate_page(input, output, note)?,
+        }
+
+        self.outputs()
+    }
+}
+
+/// What getting the outputs of an operation ready changed on disk, undone when writing fails.
+#[derive(Debug, Default)]
+pub struct PreparedOutputs {
+    /// Outputs that did not exist before, deleted when writing fails.
+    new_outputs: Vec<String>,
+    /// Files the outputs replace, with the backup each was moved to, moved back when writing fails.
+    pub backups: Vec<(String, String)>,
+    /// Folders created for the outputs.
+    new_dirs: Vec<PathBuf>,
+}
+
+impl PreparedOutputs {
+    /**
+     * Write the outputs with `write`, cleaning up when it fails: the outputs that did not exist
+     * before are deleted, the replaced files are moved back from their backups and the folders
+     * created for the outputs are removed. Used by the CLI, which writes with options the
+     * operation does not carry.
+     * @param write Writes the outputs.
+     * @returns What `write` returns.
+     * @throws anyhow::Error if `write` fails.
+     */
+    pub fn write_or_roll_back<T>(&self, write: impl FnOnce() -> Result<T>) -> Result<T> {
+        write().inspect_err(|_| {
+            remove_partial_outputs(&self.new_outputs);
+            restore_backups(&self.backups);
+            remove_created_dirs(&self.new_dirs);
+        })
+    }
+}
+
+/**
+ * Move files back from their backups, replacing what was written in their place.
+ */
+fn restore_backups(backups: &[(String, String)]) {
+    for (path, backup) in backups {
+        match fs::rename(backup, path) {
+            Ok(()) => log::info!("Moved '{}' back to '{}'", backup, path),
+            Err(e) => log::warn!("Could not move '{}' back to '{}': {}", backup, path, e),
+        }
+    }
+}
+
+/**
+ * Delete the files a failed operation created before it stopped.
+ */
+fn remove_partial_outputs(outputs: &[String]) {
+    for output in outputs.iter().filter(|output| Path::new(output).exists()) {
+        match fs::remove_file(output) {
+            Ok(()) => log::info!("Removed partial output '{}'", output),
+            Err(e) => log::warn!("Could not remove partial output '{}': {}", output, e),
+        }
+    }
+}
+
+/**
+ * Pages an operation applies to: the given ones, or every page of the input when there are none.
+ * The input may be produced by an earlier step, so "all pages" is only resolved when running.
+ */
+fn resolve_pages(input: &str, pages: &[u32]) -> Result<Vec<u32>> {
+    if pages.is_empty() {
+        Ok((1..=inspect_document(input)?.page_count() as u32).collect())
+    } else {
+        Ok(pages.to_vec())
+    }
+}
+
+fn file_size(path: &str) -> Result<u64> {
+    Ok(fs::metadata(path)
+        .with_context(|| tr!("Failed to read '{path}'", path = path))?
+        .len())
+}
+
+/// Share of a document's size taken by some of its pages, assuming pages of similar weight.
+fn page_share(size: u64, pages: usize, total_pages: usize) -> u64 {
+    if total_pages == 0 {
+        return size;
+    }
+    size * pages.min(total_pages) as u64 / total_pages as u64
+}
+
+fn split_segments(segments: &str, named: bool) -> Result<Vec<PageSegment>> {
+    let segments = if named {
+        parse_named_segments(segments)
+    } else {
+        parse_page_segments(segments)
+    }?;
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::cancel::{CancelToken, Cancelled, watch_cancellation};
+
+    #[test]
+    fn test_operation_outputs() {
+        let split = Operation::Split {
+            input: "in.pdf".to_string(),
+            output_prefix: "part".to_string(),
+            segments: "1-2,5".to_string(),
+            named: false,
+        };
+        assert_eq!(
+            split.outputs().unwrap(),
+            vec!["part_001.pdf", "part_002.pdf"]
+        );
+
+        let merge = Operation::Merge {
+            inputs: vec!["a.pdf".to_string(), "b.pdf".to_string()],
+            output: "ab.pdf".to_string(),
+        };
+        assert_eq!(merge.outputs().unwrap(), vec!["ab.pdf"]);
+        assert!(merge.overwritten_inputs().is_empty());
+
+        let mut absolute = merge.clone();
+        absolute.make_paths_absolute();
+        let cwd = std::env::current_dir().unwrap();
+        assert_eq!(
+            absolute.inputs(),
+            vec![
+                cwd.join("a.pdf").to_str().unwrap(),
+                cwd.join("b.pdf").to_str().unwrap()
+            ]
+        );
+        assert_eq!(
+            absolute.outputs().unwrap(),
+            vec![cwd.join("ab.pdf").to_string_lossy()]
+        );
+
+        let in_place = Operation::Merge {
+            inputs: vec![
+                "tests/tests_pdf/a.pdf".to_string(),
+                "tests/tests_pdf/b.pdf".to_string(),
+            ],
+            output: "tests/tests_pdf/../tests_pdf/b.pdf".to_string(),
+        };
+        assert_eq!(in_place.overwritten_inputs(), vec!["tests/tests_pdf/b.pdf"]);
+    }
+
+    #[test]
+    fn test_back_up_outputs() {
+        let dir = std::env::temp_dir().join("pdfcutter_op_backup");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("in.pdf").to_string_lossy().into_owned();
+        let prefix = dir.join("part").to_string_lossy().into_owned();
+        fs::copy("tests/tests_pdf/c.pdf", &input).unwrap();
+        fs::write(format!("{}_001.pdf", prefix), b"old").unwrap();
+
+        // Only the existing output is moved, the other one is written fresh
+        let split = Operation::Split {
+            input: input.clone(),
+            output_prefix: prefix.clone(),
+            segments: "1,2".to_string(),
+            named: false,
+        };
+        let backups = split.back_up_outputs("bak").unwrap();
+        let first = format!("{}_001.pdf", prefix);
+        assert_eq!(backups, vec![(first.clone(), format!("{}.bak", first))]);
+        assert!(!Path::new(&first).exists());
+        assert_eq!(fs::read(&backups[0].1).unwrap(), b"old");
+
+        // An input written over is kept in place, since the operation reads it
+        let compress = Operation::Compress {
+            input: input.clone(),
+            output: input.clone(),
+            options: CompressOptions::default(),
+        };
+        assert!(compress.back_up_outputs("bak").unwrap().is_empty());
+        assert!(Path::new(&input).exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_failed_run_removes_partial_outputs() {
+        let dir = std::env::temp_dir().join("pdfcutter_op_partial");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let prefix = dir.join("part").to_string_lossy().into_owned();
+        let existing = format!("{}_002.pdf", prefix);
+        fs::write(&existing, b"old").unwrap();
+
+        // The first segments are written before the one past the end fails
+        let split = Operation::Split {
+            input: "tests/tests_pdf/c.pdf".to_string(),
+            output_prefix: prefix.clone(),
+            segments: "1,2,9".to_string(),
+            named: false,
+        };
+        assert!(split.run().is_err());
+        assert!(!Path::new(&format!("{}_001.pdf", prefix)).exists());
+        // A file that was there before the run is not deleted
+        assert!(Path::new(&existing).exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_failed_write_restores_backups() {
+        let dir = std::env::temp_dir().join("pdfcutter_op_restore");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let prefix = dir.join("part").to_string_lossy().into_owned();
+        let (first, second) = (format!("{}_001.pdf", prefix), format!("{}_002.pdf", prefix));
+        fs::write(&first, b"old").unwrap();
+
+        let split = Operation::Split {
+            input: "tests/tests_pdf/c.pdf".to_string(),
+            output_prefix: prefix.clone(),
+            segments: "1,2,9".to_string(),
+            named: false,
+        };
+        let prepared = split.prepare_outputs(Some("bak")).unwrap();
+        assert_eq!(
+            prepared.backups,
+            vec![(first.clone(), format!("{}.bak", first))]
+        );
+        // The output moved to its backup is not taken for a new one
+        assert_eq!(
+            prepared.new_outputs,
+            vec![second.clone(), format!("{}_003.pdf", prefix)]
+        );
+
+        // The first segments are written before the one past the end fails
+        assert!(prepared.write_or_roll_back(|| split.write()).is_err());
+        assert_eq!(fs::read(&first).unwrap(), b"old");
+        assert!(!Path::new(&format!("{}.bak", first)).exists());
+        assert!(!Path::new(&second).exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cancelled_run_leaves_nothing() {
+        let dir = std::env::temp_dir().join("pdfcutter_op_cancelled");
+        let _ = fs::remove_dir_all(&dir);
+        let output = dir.join("new").join("merged.pdf");
+        let merge = Operation::Merge {
+            inputs: vec!["tests/tests_pdf/a.pdf".to_string(); 2],
+            output: output.to_string_lossy().into_owned(),
+        };
+        let compress = Operation::Compress {
+            input: "tests/tests_pdf/a.pdf".to_string(),
+            output: output.to_string_lossy().into_owned(),
+            options: CompressOptions::default(),
+        };
+
+        // Cancelled on its worker before it starts, so it stops at its first page
+        let token = CancelToken::new();
+        token.cancel();
+        let results = std::thread::spawn(move || {
+            watch_cancellation(token);
+            [merge.run().map(drop), compress.run().map(drop)]
+        })
+        .join()
+        .unwrap();
+        for result in results {
+            assert!(result.unwrap_err().is::<Cancelled>());
+        }
+        // The folders created for the output are removed with it
+        assert!(!dir.join("new").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_estimated_sizes() {
+        let input = "tests/tests_pdf/c.pdf";
+        let size = fs::metadata(input).unwrap().len();
+
+        let split = Operation::Split {
+            input: input.to_string(),
+            output_prefix: "part".to_string(),
+            segments: "1-2,5".to_string(),
+            named: false,
+        };
+        let sizes = split.estimated_sizes().unwrap();
+        assert_eq!(sizes[0], ("part_001.pdf".to_string(), size * 2 / 7));
+        assert_eq!(sizes[1].1, size / 7);
+
+        let merge = Operation::Merge {
+            inputs: vec![input.to_string(), input.to_string()],
+            output: "cc.pdf".to_string(),
+        };
+        assert_eq!(merge.estimated_sizes().unwrap()[0].1, size * 2);
+
+        let delete = Operation::Delete {
+            input: "missing.pdf".to_string(),
+            output: "out.pdf".to_string(),
+            pages: vec![1],
+        };
+        assert!(delete.estimated_sizes().is_err());
+    }
+
+    #[test]
+    fn test_operation_run_chain() {
+        let dir = std::env::temp_dir();
+        let extracted = dir.join("pdfcutter_op_extract.pdf");
+        let rotated = dir.join("pdfcutter_op_rotate.pdf");
+
+        let extract = Operation::Extract {
+            input: "tests/tests_pdf/c.pdf".to_string(),
+            output: extracted.to_string_lossy().into_owned(),
+            pages: vec![1, 2, 3],
+            options: ExtractOptions::default(),
+        };
+        assert_eq!(extract.run().unwrap(), extract.outputs().unwrap());
+
+        // Rotating "all pages" of a file produced by the previous step
+        let rotate = Operation::Rotate {
+            input: extracted.to_string_lossy().into_owned(),
+            output: rotated.to_string_lossy().into_owned(),
+            rules: vec![RotationRule {
+                pages: Vec::new(),
+                angle: 90,
+            }],
+        };
+        rotate.run().unwrap();
+        let info = inspect_document(&rotated.to_string_lossy()).unwrap();
+        assert_eq!(info.page_count(), 3);
+
+        std::fs::remove_file(extracted).unwrap();
+        std::fs::remove_file(rotated).unwrap();
+    }
+}
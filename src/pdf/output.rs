@@ -0,0 +1,573 @@
+use crate::config::Config;
+use crate::i18n::tr;
+use anyhow::{Result, anyhow, bail};
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Default output name of a merge.
+pub const DEFAULT_MERGE_OUTPUT: &str = "output_merged.pdf";
+/// Default output name of a page deletion.
+pub const DEFAULT_DELETE_OUTPUT: &str = "output_deleted_pages.pdf";
+/// Default output name of a rotation.
+pub const DEFAULT_ROTATE_OUTPUT: &str = "output_rotated.pdf";
+/// Default output name of a page extraction.
+pub const DEFAULT_EXTRACT_OUTPUT: &str = "output_extracted.pdf";
+/// Default output name of a compression.
+pub const DEFAULT_COMPRESS_OUTPUT: &str = "output_compressed.pdf";
+/// Default output name of a watermark.
+pub const DEFAULT_WATERMARK_OUTPUT: &str = "output_watermarked.pdf";
+/// Default output name of a text note.
+pub const DEFAULT_ANNOTATE_OUTPUT: &str = "output_annotated.pdf";
+/// Default prefix of the files written by a split.
+pub const DEFAULT_SPLIT_PREFIX: &str = "split_output";
+
+/// Config key of the overwrite policy applied to default output names.
+pub const OVERWRITE_KEY: &str = "overwrite";
+
+/// Config key enabling a backup copy of the input before an operation writes over it.
+pub const BACKUP_IN_PLACE_KEY: &str = "backup_in_place";
+
+/// Config key keeping the files an output replaces: `true`, or the suffix of the backups.
+pub const BACKUP_KEY: &str = "backup";
+
+/// Suffix appended to the name of a backup: `report.pdf.bak`.
+pub const DEFAULT_BACKUP_SUFFIX: &str = "bak";
+
+/// Config key flushing every output and its directory to disk before a command finishes.
+pub const DURABLE_KEY: &str = "durable";
+
+static DURABLE: AtomicBool = AtomicBool::new(false);
+
+/// Whether outputs are flushed to disk once written, so a power loss cannot lose them.
+pub fn durable() -> bool {
+    DURABLE.load(Ordering::Relaxed)
+}
+
+pub fn set_durable(enabled: bool) {
+    DURABLE.store(enabled, Ordering::Relaxed);
+}
+
+/// Config key packing the small objects of outputs into compressed object streams.
+pub const OBJECT_STREAMS_KEY: &str = "object_streams";
+
+static OBJECT_STREAMS: AtomicBool = AtomicBool::new(true);
+
+/// Whether outputs pack their small objects into object streams, which PDF 1.4 readers cannot open.
+pub fn object_streams() -> bool {
+    OBJECT_STREAMS.load(Ordering::Relaxed)
+}
+
+pub fn set_object_streams(enabled: bool) {
+    OBJECT_STREAMS.store(enabled, Ordering::Relaxed);
+}
+
+/// What to do when a default output name is already taken by an existing file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverwritePolicy {
+    /// Write over the existing file.
+    Replace,
+    /// Append a counter: `output_merged_1.pdf`, `output_merged_2.pdf`...
+    #[default]
+    Counter,
+    /// Append the current date and time: `output_merged_20260115-093000.pdf`.
+    Timestamp,
+}
+
+impl OverwritePolicy {
+    /**
+     * Read the overwrite policy from the configuration.
+     * @param config The loaded configuration.
+     * @returns The configured policy, or the default one (counter) when none is set.
+     * @throws String if the configured value is not a known policy.
+     */
+    pub fn from_config(config: &Config) -> Result<Self, String> {
+        match config.get(OVERWRITE_KEY) {
+            None => Ok(Self::default()),
+            Some(value) => match value.trim().to_ascii_lowercase().as_str() {
+                "replace" => Ok(Self::Replace),
+                "counter" => Ok(Self::Counter),
+                "timestamp" => Ok(Self::Timestamp),
+                _ => Err(format!(
+                    "Invalid value '{}' for {} (expected replace, counter or timestamp)",
+                    value, OVERWRITE_KEY
+                )),
+            },
+        }
+    }
+
+    /**
+     * Pick a name that does not replace an existing file, following the policy.
+     * The suffix goes before the `.pdf` extension, or at the end of a split prefix.
+     * @param name The default output path or prefix.
+     * @param taken Whether writing to a candidate name would replace an existing file.
+     * @returns The name unchanged when it is free or the policy is to replace, otherwise a free variant.
+     */
+    pub fn free_name(&self, name: &str, taken: impl Fn(&str) -> bool) -> String {
+        if *self == Self::Replace || !taken(name) {
+            return name.to_string();
+        }
+        let (stem, extension) = match name.strip_suffix(".pdf") {
+            Some(stem) => (stem, ".pdf"),
+            None => (name, ""),
+        };
+        let stem = match self {
+            Self::Timestamp => {
+                let stamped = format!("{}_{}", stem, chrono::Local::now().format("%Y%m%d-%H%M%S"));
+                if !taken(&format!("{}{}", stamped, extension)) {
+                    return format!("{}{}", stamped, extension);
+                }
+                stamped
+            }
+            _ => stem.to_string(),
+        };
+        (1..)
+            .map(|n| format!("{}_{}{}", stem, n, extension))
+            .find(|candidate| !taken(candidate))
+            .expect("a free name exists")
+    }
+}
+
+/**
+ * Read from the configuration whether inputs are backed up before being written over.
+ * @param config The loaded configuration.
+ * @returns The configured value, false when none is set.
+ * @throws String if the configured value is not true or false.
+ */
+pub fn backup_in_place_from_config(config: &Config) -> Result<bool, String> {
+    Ok(config.get_bool(BACKUP_IN_PLACE_KEY)?.unwrap_or(false))
+}
+
+/**
+ * Decide whether outputs are flushed to disk: `--durable` forces it, then the `durable` setting
+ * applies.
+ * @param config The loaded configuration.
+ * @param flag Whether `--durable` was given.
+ * @returns true to flush every output and its directory once written.
+ * @throws String if the configured value is not true or false.
+ */
+pub fn durable_from_config(config: &Config, flag: bool) -> Result<bool, String> {
+    if flag {
+        return Ok(true);
+    }
+    Ok(config.get_bool(DURABLE_KEY)?.unwrap_or(false))
+}
+
+/**
+ * Decide whether outputs use object streams: `--no-object-streams` turns them off, then the
+ * `object_streams` setting applies.
+ * @param config The loaded configuration.
+ * @param disabled Whether `--no-object-streams` was given.
+ * @returns true to pack the small objects of outputs into object streams.
+ * @throws String if the configured value is not true or false.
+ */
+pub fn object_streams_from_config(config: &Config, disabled: bool) -> Result<bool, String> {
+    if disabled {
+        return Ok(false);
+    }
+    Ok(config.get_bool(OBJECT_STREAMS_KEY)?.unwrap_or(true))
+}
+
+/**
+ * Flush a written file to disk.
+ * @param path The file.
+ * @throws io::Error if the file cannot be opened or flushed.
+ */
+pub fn sync_file(path: &Path) -> io::Result<()> {
+    fs::OpenOptions::new().write(true).open(path)?.sync_all()
+}
+
+/**
+ * Flush the directory of a file to disk, so a file renamed into it stays there after a power
+ * loss. Directories cannot be opened for this on Windows, where the rename is flushed with the
+ * file.
+ * @param path The file whose directory is flushed.
+ * @throws io::Error if the directory cannot be opened or flushed.
+ */
+pub fn sync_parent_directory(path: &Path) -> io::Result<()> {
+    if cfg!(windows) {
+        return Ok(());
+    }
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    fs::File::open(directory)?.sync_all()
+}
+
+/**
+ * Read from the configuration whether files replaced by an output are kept, and under which suffix.
+ * @param config The loaded configuration.
+ * @returns The backup suffix, or None when replaced files are not kept.
+ * @throws String if the configured suffix is not valid.
+ */
+pub fn backup_suffix_from_config(config: &Config) -> Result<Option<String>, String> {
+    match config.get(BACKUP_KEY).map(str::trim) {
+        None | Some("false") => Ok(None),
+        Some("true") => Ok(Some(DEFAULT_BACKUP_SUFFIX.to_string())),
+        Some(value) => parse_backup_suffix(value)
+            .map(Some)
+            .map_err(|e| format!("{} for {}", e, BACKUP_KEY)),
+    }
+}
+
+/**
+ * Check a backup suffix given on the command line or in the configuration.
+ * @param value The suffix, with or without its leading dot (e.g., "bak" or ".orig").
+ * @returns The suffix without the leading dot.
+ * @throws String if the suffix is empty or contains a path separator.
+ */
+pub fn parse_backup_suffix(value: &str) -> Result<String, String> {
+    let suffix = value.trim().trim_start_matches('.');
+    if suffix.is_empty() || suffix.contains(['/', '\\']) {
+        return Err(format!("Invalid backup suffix '{}'", value));
+    }
+    Ok(suffix.to_string())
+}
+
+/// Path of the backup of a file: its name with the suffix appended, e.g. `report.pdf.bak`.
+pub fn backup_path(path: &str, suffix: &str) -> String {
+    format!("{}.{}", path, suffix)
+}
+
+/// Path an output is written to before being renamed over it: a hidden name in the same
+/// directory, e.g. `.report.pdf.1234.tmp`, so the rename never crosses file systems.
+pub fn temporary_path(path: &str) -> PathBuf {
+    let path = Path::new(path);
+    let name = path
+        .file_name()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy();
+    path.with_file_name(format!(".{}.{}.tmp", name, std::process::id()))
+}
+
+/**
+ * Whether two paths name the same existing file, through links and relative paths.
+ * @param a The first path.
+ * @param b The second path.
+ * @returns true if both exist and are the same file.
+ */
+pub fn same_file(a: &str, b: &str) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/**
+ * Copy a file next to itself with the backup suffix appended, replacing an older backup.
+ * Used for inputs written over, which are still read by the operation.
+ * @param path The file about to be written over.
+ * @param suffix The backup suffix.
+ * @returns The path of the backup copy.
+ * @throws io::Error if the file cannot be copied.
+ */
+pub fn backup_file(path: &str, suffix: &str) -> io::Result<String> {
+    let backup = backup_path(path, suffix);
+    fs::copy(path, &backup)?;
+    Ok(backup)
+}
+
+/**
+ * Rename a file an output is about to replace, appending the backup suffix; an older backup is replaced.
+ * @param path The existing file at the output path.
+ * @param suffix The backup suffix.
+ * @returns The path the file was moved to.
+ * @throws io::Error if the file cannot be renamed.
+ */
+pub fn move_to_backup(path: &str, suffix: &str) -> io::Result<String> {
+    let backup = backup_path(path, suffix);
+    fs::rename(path, &backup)?;
+    Ok(backup)
+}
+
+/**
+ * Resolve the full path an output file will be written to.
+ * An empty directory means the current directory, and an absolute filename ignores the directory.
+ * @param directory The output directory (may be empty or start with `~`).
+ * @param filename The output filename or prefix.
+ * @returns The absolute output path.
+ */
+pub fn resolve_output_path(directory: &str, filename: &str) -> PathBuf {
+    let path = expand_home(directory.trim()).join(filename);
+    std::path::absolute(&path).unwrap_or(path)
+}
+
+/**
+ * Expand a leading `~` to the home directory.
+ * @param path The path as typed by the user.
+ * @returns The expanded path, or the path unchanged when there is nothing to expand.
+ */
+pub fn expand_home(path: &str) -> PathBuf {
+    let rest = match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => rest.trim_start_matches('/'),
+        _ => return PathBuf::from(path),
+    };
+
+    match env::var_os("HOME") {
+        Some(home) => Path::new(&home).join(rest),
+        None => PathBuf::from(path),
+    }
+}
+
+/**
+ * Shorten a path under the home directory to start with `~`, for display.
+ * @param path The path to shorten.
+ * @returns The path as a string, starting with `~` when it is inside the home directory.
+ */
+pub fn contract_home(path: &Path) -> String {
+    if let Some(home) = env::var_os("HOME").filter(|home| !home.is_empty())
+        && let Ok(rest) = path.strip_prefix(&home)
+    {
+        return if rest.as_os_str().is_empty() {
+            "~".to_string()
+        } else {
+            format!("~/{}", rest.display())
+        };
+    }
+    path.display().to_string()
+}
+
+/**
+ * Create the missing folders above output files, such as `out/2024` for `out/2024/part_001.pdf`.
+ * @param outputs The output paths.
+ * @returns The folders created, outermost first, so that they can be removed if writing fails.
+ * @throws anyhow::Error naming the folder that cannot be created, and why when it is a
+ * permission problem or a file in the way.
+ */
+pub fn create_output_dirs(outputs: &[String]) -> Result<Vec<PathBuf>> {
+    let mut created = Vec::new();
+    for output in outputs {
+        let path = std::path::absolute(output).unwrap_or_else(|_| PathBuf::from(output));
+        let Some(parent) = path.parent() else {
+            continue;
+        };
+        if let Some(existing) = parent.ancestors().find(|dir| dir.exists())
+            && !existing.is_dir()
+        {
+            remove_created_dirs(&created);
+            bail!(tr!(
+                "Cannot write into '{path}': it is a file, not a folder",
+                path = existing.display()
+            ));
+        }
+        let mut missing: Vec<&Path> = parent.ancestors().take_while(|dir| !dir.exists()).collect();
+        missing.reverse();
+        for dir in missing {
+            if let Err(e) = fs::create_dir(dir) {
+                remove_created_dirs(&created);
+                return Err(output_dir_error(dir, e));
+            }
+            log::info!("Created folder '{}'", dir.display());
+            created.push(dir.to_path_buf());
+        }
+    }
+    Ok(created)
+}
+
+/**
+ * Remove folders created for outputs that were not written, innermost first.
+ * Folders that are no longer empty are kept.
+ */
+pub fn remove_created_dirs(dirs: &[PathBuf]) {
+    for dir in dirs.iter().rev() {
+        if fs::remove_dir(dir).is_ok() {
+            log::info!("Removed folder '{}'", dir.display());
+        }
+    }
+}
+
+fn output_dir_error(dir: &Path, e: io::Error) -> anyhow::Error {
+    let path = dir.display().to_string();
+    match e.kind() {
+        io::ErrorKind::PermissionDenied | io::ErrorKind::ReadOnlyFilesystem => anyhow!(tr!(
+            "Cannot create folder '{path}': permission denied",
+            path = path
+        )),
+        _ => anyhow!(e).context(tr!("Cannot create folder '{path}'", path = path)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_output_path() {
+        let cwd = env::current_dir().unwrap();
+        assert_eq!(resolve_output_path("", "out.pdf"), cwd.join("out.pdf"));
+        assert_eq!(
+            resolve_output_path("exports", "out.pdf"),
+            cwd.join("exports").join("out.pdf")
+        );
+        assert_eq!(
+            resolve_output_path("/tmp", "out.pdf"),
+            PathBuf::from("/tmp/out.pdf")
+        );
+        assert_eq!(
+            resolve_output_path("exports", "/tmp/out.pdf"),
+            PathBuf::from("/tmp/out.pdf")
+        );
+    }
+
+    #[test]
+    fn test_expand_home() {
+        let home = PathBuf::from(env::var_os("HOME").unwrap());
+        assert_eq!(expand_home("~"), home);
+        assert_eq!(expand_home("~/docs"), home.join("docs"));
+        assert_eq!(expand_home("~user/docs"), PathBuf::from("~user/docs"));
+        assert_eq!(expand_home("docs"), PathBuf::from("docs"));
+    }
+
+    #[test]
+    fn test_contract_home() {
+        let home = PathBuf::from(env::var_os("HOME").unwrap());
+        assert_eq!(contract_home(&home), "~");
+        assert_eq!(contract_home(&home.join("docs")), "~/docs");
+        assert_eq!(contract_home(Path::new("/nonexistent/x")), "/nonexistent/x");
+    }
+
+    #[test]
+    fn test_overwrite_policy() {
+        let existing = ["out.pdf", "out_1.pdf", "split"];
+        let taken = |name: &str| existing.contains(&name);
+
+        assert_eq!(
+            OverwritePolicy::Replace.free_name("out.pdf", taken),
+            "out.pdf"
+        );
+        assert_eq!(
+            OverwritePolicy::Counter.free_name("new.pdf", taken),
+            "new.pdf"
+        );
+        assert_eq!(
+            OverwritePolicy::Counter.free_name("out.pdf", taken),
+            "out_2.pdf"
+        );
+        assert_eq!(
+            OverwritePolicy::Counter.free_name("split", taken),
+            "split_1"
+        );
+        let stamped = OverwritePolicy::Timestamp.free_name("out.pdf", taken);
+        assert!(stamped.starts_with("out_20") && stamped.ends_with(".pdf"));
+        assert_eq!(stamped.len(), "out_YYYYMMDD-HHMMSS.pdf".len());
+
+        let config = Config::parse("overwrite = timestamp").unwrap();
+        assert_eq!(
+            OverwritePolicy::from_config(&config),
+            Ok(OverwritePolicy::Timestamp)
+        );
+        let config = Config::parse("").unwrap();
+        assert_eq!(
+            OverwritePolicy::from_config(&config),
+            Ok(OverwritePolicy::Counter)
+        );
+        let config = Config::parse("overwrite = never").unwrap();
+        assert!(OverwritePolicy::from_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_in_place_backup() {
+        let dir = env::temp_dir().join("pdfcutter_backup");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("report.pdf");
+        fs::copy("tests/tests_pdf/b.pdf", &file).unwrap();
+        let file = file.to_string_lossy().into_owned();
+
+        // The same file reached through a relative path
+        let relative = format!("{}/../pdfcutter_backup/report.pdf", dir.display());
+        assert!(same_file(&file, &relative));
+        assert!(!same_file(&file, "tests/tests_pdf/b.pdf"));
+        assert!(!same_file(&file, &format!("{}.new", file)));
+
+        let backup = backup_file(&file, DEFAULT_BACKUP_SUFFIX).unwrap();
+        assert_eq!(backup, format!("{}.bak", file));
+        assert_eq!(fs::read(&backup).unwrap(), fs::read(&file).unwrap());
+
+        // A replaced output is moved out of the way
+        let moved = move_to_backup(&file, "orig").unwrap();
+        assert_eq!(moved, format!("{}.orig", file));
+        assert!(!Path::new(&file).exists());
+
+        let config = Config::parse("backup_in_place = true").unwrap();
+        assert_eq!(backup_in_place_from_config(&config), Ok(true));
+        let config = Config::parse("").unwrap();
+        assert_eq!(backup_in_place_from_config(&config), Ok(false));
+        assert_eq!(backup_suffix_from_config(&config), Ok(None));
+        let config = Config::parse("backup_in_place = maybe").unwrap();
+        assert!(backup_in_place_from_config(&config).is_err());
+
+        let config = Config::parse("backup = true").unwrap();
+        assert_eq!(backup_suffix_from_config(&config), Ok(Some("bak".into())));
+        let config = Config::parse("backup = \".orig\"").unwrap();
+        assert_eq!(backup_suffix_from_config(&config), Ok(Some("orig".into())));
+        let config = Config::parse("backup = \"old/x\"").unwrap();
+        assert!(backup_suffix_from_config(&config).is_err());
+        assert!(parse_backup_suffix(".").is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_object_streams_setting() {
+        let config = Config::parse("").unwrap();
+        assert_eq!(object_streams_from_config(&config, false), Ok(true));
+        assert_eq!(object_streams_from_config(&config, true), Ok(false));
+        let config = Config::parse("object_streams = false").unwrap();
+        assert_eq!(object_streams_from_config(&config, false), Ok(false));
+        let config = Config::parse("object_streams = 1.4").unwrap();
+        assert!(object_streams_from_config(&config, false).is_err());
+        assert_eq!(object_streams_from_config(&config, true), Ok(false));
+    }
+
+    #[test]
+    fn test_durable_outputs() {
+        let config = Config::parse("durable = true").unwrap();
+        assert_eq!(durable_from_config(&config, false), Ok(true));
+        let config = Config::parse("").unwrap();
+        assert_eq!(durable_from_config(&config, false), Ok(false));
+        assert_eq!(durable_from_config(&config, true), Ok(true));
+        let config = Config::parse("durable = always").unwrap();
+        assert!(durable_from_config(&config, false).is_err());
+        assert_eq!(durable_from_config(&config, true), Ok(true));
+
+        let file = env::temp_dir().join("pdfcutter_durable.pdf");
+        fs::write(&file, b"%PDF").unwrap();
+        sync_file(&file).unwrap();
+        sync_parent_directory(&file).unwrap();
+        // A bare file name is in the current directory
+        sync_parent_directory(Path::new("Cargo.toml")).unwrap();
+        fs::remove_file(&file).unwrap();
+    }
+
+    #[test]
+    fn test_create_output_dirs() {
+        let dir = env::temp_dir().join("pdfcutter_output_dirs");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let part = dir.join("out/2024/part_001.pdf");
+        let other = dir.join("out/2025/part_002.pdf");
+        let outputs = [part.display().to_string(), other.display().to_string()];
+
+        let created = create_output_dirs(&outputs).unwrap();
+        assert_eq!(
+            created,
+            [dir.join("out"), dir.join("out/2024"), dir.join("out/2025")]
+        );
+        assert!(dir.join("out/2024").is_dir());
+        assert!(create_output_dirs(&outputs).unwrap().is_empty());
+
+        // Folders holding files are kept
+        fs::write(&part, "").unwrap();
+        remove_created_dirs(&created);
+        assert!(dir.join("out/2024").is_dir());
+        assert!(!dir.join("out/2025").exists());
+
+        // A file in the way
+        let blocked = part.join("x.pdf").display().to_string();
+        let error = create_output_dirs(&[blocked]).unwrap_err().to_string();
+        assert!(error.contains("is a file, not a folder"));
+    }
+}
@@ -0,0 +1,866 @@
+//! Page and segment specifications: the pages to work on ("1,3-5", "2-last:2", "!1") and the
+//! segments of a split ("1-3,(4,5),6", "intro:1-3,4-9").
+//!
+//! The TUI parses what is typed on every keystroke and the CLI its arguments, so anything can
+//! reach these parsers: the limits keep a typo such as "1-99999999" from listing pages for
+//! minutes, and errors tell where the specification goes wrong. The module only depends on the
+//! standard library, `thiserror` and `tr!`, so the fuzz target (`fuzz/`) can include it as it is.
+
+use crate::i18n::tr;
+use std::collections::HashSet;
+use thiserror::Error;
+
+/// Longest specification accepted, in characters: room for every other page of a large
+/// document picked on the page grid ("1,3,5,...").
+pub const MAX_SPEC_LENGTH: usize = 100_000;
+
+/// Highest page number, and most pages a specification can list in all.
+pub const MAX_PAGES: u32 = 100_000;
+
+/// Most parts (pages, ranges or segments) a specification can have.
+pub const MAX_PARTS: usize = 10_000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SpecError {
+    #[error("{}", tr!("too long ({length} characters, at most {max})", length = .length, max = MAX_SPEC_LENGTH))]
+    TooLong { length: usize },
+
+    #[error("{}", tr!("unexpected '{character}' at column {column}", character = .character, column = .column))]
+    Unexpected { character: String, column: usize },
+
+    #[error("{}", tr!("missing page number at column {column}", column = .column))]
+    MissingNumber { column: usize },
+
+    #[error("{}", tr!("number too large at column {column} (at most {max})", column = .column, max = MAX_PAGES))]
+    NumberTooLarge { column: usize },
+
+    #[error("{}", tr!("too many parts ({count}, at most {max})", count = .count, max = MAX_PARTS))]
+    TooManyParts { count: usize },
+
+    #[error("{}", tr!("too many pages ({count}, at most {max})", count = .count, max = MAX_PAGES))]
+    TooManyPages { count: u64 },
+
+    #[error("{}", tr!("page 0 at column {column} (pages start at 1)", column = .column))]
+    ZeroPage { column: usize },
+
+    #[error("{}", tr!("range {start}-{end} at column {column} goes backwards (start must be <= end)", start = .start, end = .end, column = .column))]
+    Reversed { start: u32, end: u32, column: usize },
+
+    #[error("{}", tr!("step at column {column} without a range (e.g. 1-20:2)", column = .column))]
+    StepWithoutRange { column: usize },
+
+    #[error("{}", tr!("step 0 at column {column} (at least 1)", column = .column))]
+    ZeroStep { column: usize },
+
+    #[error("{}", tr!("'{reference}' at column {column} needs the page count of the document", reference = .reference, column = .column))]
+    PageCountNeeded { reference: String, column: usize },
+
+    #[error("{}", tr!("'{reference}' at column {column} is before the first page (document has {page_count} pages)", reference = .reference, column = .column, page_count = .page_count))]
+    BeforeFirstPage {
+        reference: String,
+        column: usize,
+        page_count: usize,
+    },
+
+    #[error("{}", tr!("page {page} at column {column} is past the end (document has {page_count} pages)", page = .page, column = .column, page_count = .page_count))]
+    PageOutOfRange {
+        page: u32,
+        column: usize,
+        page_count: usize,
+    },
+
+    #[error("{}", tr!("missing segment name before ':' at column {column}", column = .column))]
+    MissingName { column: usize },
+
+    #[error("{}", tr!("segment name '{name}' at column {column} cannot be a file name (no '/', '\\' or '..', and not only dots)", name = .name, column = .column))]
+    InvalidName { name: String, column: usize },
+
+    #[error("{}", tr!("no pages listed"))]
+    NoPages,
+
+    #[error("{}", tr!("no segments listed"))]
+    NoSegments,
+
+    #[error("{}", tr!("no page is left outside {pages}", pages = .excluded))]
+    NothingLeft { excluded: String },
+
+    #[error("{}", tr!("cannot delete all {page_count} pages of the document", page_count = .page_count))]
+    AllPages { page_count: usize },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageSegment {
+    pub start: u32,
+    pub end: Option<u32>,
+    pub name: Option<String>,
+}
+
+impl PageSegment {
+    pub fn single(page: u32) -> Self {
+        PageSegment {
+            start: page,
+            end: None,
+            name: None,
+        }
+    }
+
+    pub fn range(start: u32, end: u32) -> Self {
+        Self {
+            start,
+            end: Some(end),
+            name: None,
+        }
+    }
+
+    pub fn named(start: u32, end: Option<u32>, name: String) -> Self {
+        Self {
+            start,
+            end,
+            name: Some(name),
+        }
+    }
+
+    pub fn get_pages(&self) -> Vec<u32> {
+        match self.end {
+            Some(end) => (self.start..=end).collect(),
+            None => vec![self.start],
+        }
+    }
+
+    /// Number of pages of the segment, counted without listing them.
+    pub fn page_count(&self) -> u64 {
+        self.end
+            .map_or(1, |end| end.saturating_sub(self.start) as u64 + 1)
+    }
+
+    /// The segment as it is typed: "5", "3-7" or "intro:1-3".
+    pub fn label(&self) -> String {
+        let pages = match self.end {
+            Some(end) if end != self.start => format!("{}-{}", self.start, end),
+            _ => self.start.to_string(),
+        };
+        match &self.name {
+            Some(name) => format!("{}:{}", name, pages),
+            None => pages,
+        }
+    }
+
+    /**
+     * The file a segment is written to: its name when it has one, else its position in the
+     * split, zero-padded so the files sort in order (`prefix_001.pdf`, ..., `prefix_010.pdf`).
+     * @param base_prefix The output prefix.
+     * @param index The position of the segment in the split, from 1.
+     * @param count The number of segments, which sets the padding (at least 3 digits).
+     * @returns The output file name.
+     */
+    pub fn generate_filename(&self, base_prefix: &str, index: usize, count: usize) -> String {
+        match &self.name {
+            Some(name) => format!("{}_{}.pdf", base_prefix, name),
+            None => {
+                let width = count.to_string().len().max(3);
+                format!("{}_{:0width$}.pdf", base_prefix, index, width = width)
+            }
+        }
+    }
+}
+
+/**
+ * Parse a page specification into sorted unique pages. `last` (or `last-2`) refers to the end of
+ * the document, and a specification starting with `!` or `^` selects every page except the ones
+ * it lists.
+ * @param input The specification, as typed.
+ * @param page_count The number of pages of the document, when known.
+ * @returns The pages, sorted.
+ * @throws SpecError naming the first problem and, when it is in a part, its column (from 1):
+ * a syntax error, a page past the end, or `last` or a complement without the page count.
+ */
+pub fn parse_page_set(input: &str, page_count: Option<usize>) -> Result<Vec<u32>, SpecError> {
+    let mut pages = parse_pages(input, page_count)?;
+    pages.sort_unstable();
+    pages.dedup();
+    Ok(pages)
+}
+
+/**
+ * Parse a page specification whose order matters (e.g., "3,1-2" puts page 3 first). A page typed
+ * twice is only kept where it first appears, and a complement keeps the pages in document order.
+ * @param input The specification, as typed.
+ * @param page_count The number of pages of the document, when known.
+ * @returns The unique pages, in the order they are typed.
+ * @throws SpecError as parse_page_set.
+ */
+pub fn parse_page_sequence(input: &str, page_count: Option<usize>) -> Result<Vec<u32>, SpecError> {
+    let mut seen = HashSet::new();
+    Ok(parse_pages(input, page_count)?
+        .into_iter()
+        .filter(|&page| seen.insert(page))
+        .collect())
+}
+
+/**
+ * Parse the pages to delete from a document, which must leave at least one page.
+ * @param input The specification, as typed.
+ * @param page_count The number of pages of the document, when known.
+ * @returns The pages, sorted.
+ * @throws SpecError as parse_page_set, or SpecError::AllPages if every page would be deleted.
+ */
+pub fn parse_pages_to_delete(
+    input: &str,
+    page_count: Option<usize>,
+) -> Result<Vec<u32>, SpecError> {
+    let pages = parse_page_set(input, page_count)?;
+    if let Some(page_count) = page_count
+        && pages.len() >= page_count
+    {
+        return Err(SpecError::AllPages { page_count });
+    }
+    Ok(pages)
+}
+
+/**
+ * Parse the segments of a split, parentheses grouping some of them.
+ * @param input The segments, e.g. "1,3-5,(7,9),11".
+ * @returns The segments, in order.
+ * @throws SpecError naming the first problem and, when it is in a segment, its column (from 1).
+ */
+pub fn parse_page_segments(input: &str) -> Result<Vec<PageSegment>, SpecError> {
+    let mut scanner = Scanner::new(input)?;
+    let segments = scanner.list(&[',', '(', ')'], Scanner::segment)?;
+    check_segments(segments)
+}
+
+/**
+ * Parse the segments of a split, each with an optional name.
+ * @param input The segments, e.g. "intro:1-3,chapter1:4-10,11". A name can hold anything but a
+ * comma or a colon, as long as it can end a file name: no `/`, `\` or `..`, and not only dots.
+ * @returns The segments, in order.
+ * @throws SpecError naming the first problem and, when it is in a segment, its column (from 1).
+ */
+pub fn parse_named_segments(input: &str) -> Result<Vec<PageSegment>, SpecError> {
+    let mut scanner = Scanner::new(input)?;
+    let segments = scanner.list(&[','], Scanner::named_segment)?;
+    check_segments(segments.into_iter().flatten().collect())
+}
+
+/// Refuse a split without segments, or whose segments list too many pages in all.
+fn check_segments(segments: Vec<PageSegment>) -> Result<Vec<PageSegment>, SpecError> {
+    if segments.is_empty() {
+        return Err(SpecError::NoSegments);
+    }
+    check_page_total(segments.iter().map(PageSegment::page_count).sum())?;
+    Ok(segments)
+}
+
+fn check_page_total(count: u64) -> Result<(), SpecError> {
+    if count > MAX_PAGES as u64 {
+        return Err(SpecError::TooManyPages { count });
+    }
+    Ok(())
+}
+
+/**
+ * The pages of a specification in the order they are typed, or for a complement the other pages
+ * of the document in order.
+ */
+fn parse_pages(input: &str, page_count: Option<usize>) -> Result<Vec<u32>, SpecError> {
+    let mut scanner = Scanner::new(input)?;
+    scanner.skip_spaces();
+    let complement = matches!(scanner.peek(), Some('!' | '^')).then(|| {
+        scanner.position += 1;
+        scanner.position
+    });
+    let excluded: String = scanner.chars[scanner.position..].iter().collect();
+    let parts = scanner.list(&[','], Scanner::page_part)?;
+    if let (Some(column), None) = (complement, page_count) {
+        return Err(SpecError::PageCountNeeded {
+            reference: format!("!{}", excluded.trim()),
+            column,
+        });
+    }
+
+    let mut pages = Vec::new();
+    for part in parts {
+        part.resolve_into(&mut pages, page_count)?;
+    }
+    if pages.is_empty() {
+        return Err(SpecError::NoPages);
+    }
+    let (Some(_), Some(page_count)) = (complement, page_count) else {
+        return Ok(pages);
+    };
+
+    let excluded_pages: HashSet<u32> = pages.into_iter().collect();
+    let pages: Vec<u32> = (1..=page_count as u32)
+        .filter(|page| !excluded_pages.contains(page))
+        .collect();
+    if pages.is_empty() {
+        return Err(SpecError::NothingLeft {
+            excluded: excluded.trim().to_string(),
+        });
+    }
+    Ok(pages)
+}
+
+/// A page as typed, with the column it starts at.
+#[derive(Debug, Clone, Copy)]
+enum PageRef {
+    Number(u32, usize),
+    /// `last`, or `last-N` for the page N pages before the last one.
+    Last(u32, usize),
+}
+
+impl PageRef {
+    fn resolve(self, page_count: Option<usize>) -> Result<u32, SpecError> {
+        match self {
+            PageRef::Number(0, column) => Err(SpecError::ZeroPage { column }),
+            PageRef::Number(page, _) => Ok(page),
+            PageRef::Last(offset, column) => {
+                let reference = match offset {
+                    0 => "last".to_string(),
+                    offset => format!("last-{}", offset),
+                };
+                let page_count = page_count.ok_or_else(|| SpecError::PageCountNeeded {
+                    reference: reference.clone(),
+                    column,
+                })?;
+                page_count
+                    .checked_sub(offset as usize)
+                    .filter(|&page| page > 0)
+                    .map(|page| page as u32)
+                    .ok_or(SpecError::BeforeFirstPage {
+                        reference,
+                        column,
+                        page_count,
+                    })
+            }
+        }
+    }
+}
+
+/// A part of a page specification: a page or a range, with an optional step: "5", "2-last",
+/// "1-20:2".
+struct PagePart {
+    column: usize,
+    start: PageRef,
+    end: Option<PageRef>,
+    /// The step and its column.
+    step: Option<(u32, usize)>,
+}
+
+impl PagePart {
+    /// Add the pages of the part to those of the parts before it.
+    fn resolve_into(
+        &self,
+        pages: &mut Vec<u32>,
+        page_count: Option<usize>,
+    ) -> Result<(), SpecError> {
+        let start = self.start.resolve(page_count)?;
+        let end = match self.end {
+            Some(end) => end.resolve(page_count)?,
+            None => start,
+        };
+        let step = match self.step {
+            None => 1,
+            Some((_, column)) if self.end.is_none() => {
+                return Err(SpecError::StepWithoutRange { column });
+            }
+            Some((0, column)) => return Err(SpecError::ZeroStep { column }),
+            Some((step, _)) => step,
+        };
+        if start > end {
+            return Err(SpecError::Reversed {
+                start,
+                end,
+                column: self.column,
+            });
+        }
+        // Counted before the pages are listed
+        check_page_total(pages.len() as u64 + ((end - start) / step) as u64 + 1)?;
+
+        let first = pages.len();
+        pages.extend((start..=end).step_by(step as usize));
+        if let Some(page_count) = page_count
+            && let Some(&page) = pages[first..]
+                .iter()
+                .find(|&&page| page as usize > page_count)
+        {
+            return Err(SpecError::PageOutOfRange {
+                page,
+                column: self.column,
+                page_count,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Reads a specification character by character, counting columns in characters.
+struct Scanner {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Scanner {
+    fn new(input: &str) -> Result<Self, SpecError> {
+        let chars: Vec<char> = input.chars().collect();
+        if chars.len() > MAX_SPEC_LENGTH {
+            return Err(SpecError::TooLong {
+                length: chars.len(),
+            });
+        }
+        Ok(Self { chars, position: 0 })
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    /// Column of the current position, from 1.
+    fn column(&self) -> usize {
+        self.position + 1
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    /// Skip spaces, then the character if it comes next.
+    fn accept(&mut self, expected: char) -> bool {
+        let start = self.position;
+        self.skip_spaces();
+        if self.peek() == Some(expected) {
+            self.position += 1;
+            return true;
+        }
+        self.position = start;
+        false
+    }
+
+    /// The error for what comes at the current position, which was not expected there.
+    fn unexpected(&self) -> SpecError {
+        match self.peek() {
+            Some(character) => SpecError::Unexpected {
+                character: character.escape_debug().to_string(),
+                column: self.column(),
+            },
+            None => SpecError::MissingNumber {
+                column: self.column(),
+            },
+        }
+    }
+
+    /// Parts separated by any of the separators; empty parts are skipped.
+    fn list<T>(
+        &mut self,
+        separators: &[char],
+        part: fn(&mut Self) -> Result<T, SpecError>,
+    ) -> Result<Vec<T>, SpecError> {
+        let mut parts = Vec::new();
+        loop {
+            self.skip_spaces();
+            match self.peek() {
+                None => return Ok(parts),
+                Some(c) if separators.contains(&c) => {}
+                Some(_) => {
+                    if parts.len() == MAX_PARTS {
+                        return Err(SpecError::TooManyParts {
+                            count: MAX_PARTS + 1,
+                        });
+                    }
+                    parts.push(part(self)?);
+                    self.skip_spaces();
+                }
+            }
+            match self.peek() {
+                None => return Ok(parts),
+                Some(c) if separators.contains(&c) => self.position += 1,
+                Some(_) => return Err(self.unexpected()),
+            }
+        }
+    }
+
+    /// A number up to MAX_PAGES after optional spaces, with its column.
+    fn number(&mut self) -> Result<(u32, usize), SpecError> {
+        self.skip_spaces();
+        let column = self.column();
+        let mut value: u64 = 0;
+        while let Some(digit) = self.peek().and_then(|c| c.to_digit(10)) {
+            value = (value * 10 + digit as u64).min(MAX_PAGES as u64 + 1);
+            self.position += 1;
+        }
+        if self.column() == column {
+            return Err(self.unexpected());
+        }
+        if value > MAX_PAGES as u64 {
+            return Err(SpecError::NumberTooLarge { column });
+        }
+        Ok((value as u32, column))
+    }
+
+    /// A page: a number, `last`, or `last-N`. A dash followed by a number right after `last`
+    /// belongs to it, so "last-2" is a single page.
+    fn page(&mut self) -> Result<PageRef, SpecError> {
+        self.skip_spaces();
+        let column = self.column();
+        let word: String = self.chars.iter().skip(self.position).take(4).collect();
+        if !word.eq_ignore_ascii_case("last") {
+            let (page, column) = self.number()?;
+            return Ok(PageRef::Number(page, column));
+        }
+        self.position += 4;
+        let after_last = self.position;
+        if self.accept('-') {
+            self.skip_spaces();
+            if self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                let (offset, _) = self.number()?;
+                return Ok(PageRef::Last(offset, column));
+            }
+            self.position = after_last;
+        }
+        Ok(PageRef::Last(0, column))
+    }
+
+    /// A page or a range of pages, with an optional step: "5", "2-last", "1-20:2".
+    fn page_part(&mut self) -> Result<PagePart, SpecError> {
+        self.skip_spaces();
+        let column = self.column();
+        let start = self.page()?;
+        let end = if self.accept('-') {
+            Some(self.page()?)
+        } else {
+            None
+        };
+        let step = if self.accept(':') {
+            Some(self.number()?)
+        } else {
+            None
+        };
+        Ok(PagePart {
+            column,
+            start,
+            end,
+            step,
+        })
+    }
+
+    /// A page or a range of pages of a split: "5", "3-7".
+    fn segment(&mut self) -> Result<PageSegment, SpecError> {
+        let (start, column) = self.number()?;
+        if start == 0 {
+            return Err(SpecError::ZeroPage { column });
+        }
+        if !self.accept('-') {
+            return Ok(PageSegment::single(start));
+        }
+        let (end, end_column) = self.number()?;
+        if end == 0 {
+            return Err(SpecError::ZeroPage { column: end_column });
+        }
+        if start > end {
+            return Err(SpecError::Reversed { start, end, column });
+        }
+        Ok(PageSegment::range(start, end))
+    }
+
+    /// A segment with a name, "intro:1-3", or without one, possibly in parentheses. None for
+    /// parentheses alone.
+    fn named_segment(&mut self) -> Result<Option<PageSegment>, SpecError> {
+        let end = self.chars[self.position..]
+            .iter()
+            .position(|&c| c == ',')
+            .map_or(self.chars.len(), |offset| self.position + offset);
+        let colon = self.chars[self.position..end]
+            .iter()
+            .position(|&c| c == ':')
+            .map(|offset| self.position + offset);
+        if let Some(colon) = colon {
+            let name: String = self.chars[self.position..colon].iter().collect();
+            let name = name.trim().to_string();
+            if name.is_empty() {
+                return Err(SpecError::MissingName { column: colon + 1 });
+            }
+            // The name ends the file name of the segment, so it must not lead out of the folder
+            if name.contains(['/', '\\']) || name.contains("..") || name.chars().all(|c| c == '.') {
+                self.skip_spaces();
+                return Err(SpecError::InvalidName {
+                    name,
+                    column: self.position + 1,
+                });
+            }
+            self.position = colon + 1;
+            let segment = self.segment()?;
+            return Ok(Some(PageSegment::named(segment.start, segment.end, name)));
+        }
+
+        self.skip_parentheses();
+        if self.position == end {
+            return Ok(None);
+        }
+        let segment = self.segment()?;
+        self.skip_parentheses();
+        Ok(Some(segment))
+    }
+
+    fn skip_parentheses(&mut self) {
+        while self
+            .peek()
+            .is_some_and(|c| c == '(' || c == ')' || c.is_whitespace())
+        {
+            self.position += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(error: SpecError) -> Option<usize> {
+        match error {
+            SpecError::Unexpected { column, .. }
+            | SpecError::MissingNumber { column }
+            | SpecError::NumberTooLarge { column }
+            | SpecError::ZeroPage { column }
+            | SpecError::Reversed { column, .. }
+            | SpecError::StepWithoutRange { column }
+            | SpecError::ZeroStep { column }
+            | SpecError::PageCountNeeded { column, .. }
+            | SpecError::BeforeFirstPage { column, .. }
+            | SpecError::PageOutOfRange { column, .. }
+            | SpecError::MissingName { column }
+            | SpecError::InvalidName { column, .. } => Some(column),
+            _ => None,
+        }
+    }
+
+    fn page_column(input: &str, page_count: Option<usize>) -> Option<usize> {
+        column(parse_page_set(input, page_count).unwrap_err())
+    }
+
+    #[test]
+    fn test_parse_page_specs() {
+        for (spec, pages) in [
+            ("1,3-5", vec![1, 3, 4, 5]),
+            (" 2 - 4 , 6 ", vec![2, 3, 4, 6]),
+            ("last", vec![9]),
+            (" LAST - 2 ", vec![7]),
+            ("last-2-last", vec![7, 8, 9]),
+            ("5-last-3", vec![5, 6]),
+            ("1-9:2", vec![1, 3, 5, 7, 9]),
+            ("2-last:3", vec![2, 5, 8]),
+            ("!1-last:2", vec![2, 4, 6, 8]),
+            (" ^ 7,1", vec![2, 3, 4, 5, 6, 8, 9]),
+            ("3,1,,3", vec![1, 3]),
+        ] {
+            assert_eq!(parse_page_set(spec, Some(9)), Ok(pages), "{spec}");
+        }
+        assert_eq!(
+            parse_page_sequence("last-1-last,1-5:4", Some(6)),
+            Ok(vec![5, 6, 1])
+        );
+        assert_eq!(parse_page_sequence("2,1-3", None), Ok(vec![2, 1, 3]));
+        assert_eq!(parse_page_sequence("!2-5", Some(7)), Ok(vec![1, 6, 7]));
+        // Pages past the end are only found with the page count
+        assert_eq!(parse_page_set("1,15", None), Ok(vec![1, 15]));
+    }
+
+    #[test]
+    fn test_pages_and_ranges() {
+        assert_eq!(parse_page_set("5", None), Ok(vec![5]));
+        assert_eq!(parse_page_set(" 10 ", None), Ok(vec![10]));
+        assert_eq!(parse_page_set("1-1", None), Ok(vec![1]));
+        assert_eq!(parse_page_set(" 2 - 4 ", None), Ok(vec![2, 3, 4]));
+        assert_eq!(parse_page_set("last-2", Some(9)), Ok(vec![7]));
+        for spec in ["0", "abc", "5-3", "1-0", "a-b", "1-2-3", "last-1-2", "1:2"] {
+            assert!(parse_page_set(spec, Some(9)).is_err(), "{spec}");
+        }
+        assert_eq!(
+            parse_page_set("last", None),
+            Err(SpecError::PageCountNeeded {
+                reference: "last".to_string(),
+                column: 1
+            })
+        );
+        assert_eq!(
+            parse_page_set("last-9", Some(9)),
+            Err(SpecError::BeforeFirstPage {
+                reference: "last-9".to_string(),
+                column: 1,
+                page_count: 9
+            })
+        );
+    }
+
+    #[test]
+    fn test_page_spec_errors_tell_the_column() {
+        assert_eq!(
+            parse_page_set("1-3,5-7-9", None),
+            Err(SpecError::Unexpected {
+                character: "-".to_string(),
+                column: 8
+            })
+        );
+        assert_eq!(
+            parse_page_set("1-3,5-7-9", None).unwrap_err().to_string(),
+            "unexpected '-' at column 8"
+        );
+        assert_eq!(page_column("3-", None), Some(3));
+        assert_eq!(page_column("2,!3", None), Some(3));
+        assert_eq!(page_column("1 2", None), Some(3));
+        assert_eq!(page_column("1-9:x", None), Some(5));
+        assert_eq!(page_column("lastx", Some(9)), Some(5));
+        assert_eq!(page_column("1,\u{1b}", None), Some(3));
+        assert_eq!(page_column("4,100001", None), Some(3));
+        assert_eq!(page_column("1, 0", None), Some(4));
+        assert_eq!(page_column("1,5-3", None), Some(3));
+        assert_eq!(page_column("1,3:2", None), Some(5));
+        assert_eq!(page_column("1-9:0", None), Some(5));
+        assert_eq!(page_column("2,last-9", Some(9)), Some(3));
+        assert_eq!(page_column("1,3-15", Some(14)), Some(3));
+        assert_eq!(
+            parse_page_set("1,3-15", Some(14)),
+            Err(SpecError::PageOutOfRange {
+                page: 15,
+                column: 3,
+                page_count: 14
+            })
+        );
+        assert_eq!(
+            parse_page_set("99999999999999999999999", None),
+            Err(SpecError::NumberTooLarge { column: 1 })
+        );
+    }
+
+    #[test]
+    fn test_last_and_complements_need_the_page_count() {
+        assert_eq!(
+            parse_page_set("1, last-2", None),
+            Err(SpecError::PageCountNeeded {
+                reference: "last-2".to_string(),
+                column: 4
+            })
+        );
+        assert_eq!(
+            parse_page_set(" !2-5", None),
+            Err(SpecError::PageCountNeeded {
+                reference: "!2-5".to_string(),
+                column: 2
+            })
+        );
+        assert_eq!(
+            parse_page_set("!2-9", Some(7)),
+            Err(SpecError::PageOutOfRange {
+                page: 8,
+                column: 2,
+                page_count: 7
+            })
+        );
+        assert_eq!(
+            parse_page_set("!1-7", Some(7)),
+            Err(SpecError::NothingLeft {
+                excluded: "1-7".to_string()
+            })
+        );
+        assert_eq!(parse_page_set("!", Some(7)), Err(SpecError::NoPages));
+        assert_eq!(parse_page_set(",,,", None), Err(SpecError::NoPages));
+        assert_eq!(parse_page_set("", None), Err(SpecError::NoPages));
+    }
+
+    #[test]
+    fn test_parse_pages_to_delete() {
+        assert_eq!(parse_pages_to_delete("1-3", Some(4)), Ok(vec![1, 2, 3]));
+        assert_eq!(
+            parse_pages_to_delete("1-4", Some(4)),
+            Err(SpecError::AllPages { page_count: 4 })
+        );
+        assert!(parse_pages_to_delete("5", Some(4)).is_err());
+        assert!(parse_pages_to_delete("1-4", None).is_ok());
+        assert_eq!(parse_pages_to_delete("!1", Some(3)), Ok(vec![2, 3]));
+    }
+
+    #[test]
+    fn test_parse_segments() {
+        let segments = parse_page_segments("1,3-5,(7,9),11").unwrap();
+        let labels: Vec<String> = segments.iter().map(PageSegment::label).collect();
+        assert_eq!(labels, ["1", "3-5", "7", "9", "11"]);
+        assert_eq!(segments[1].get_pages(), vec![3, 4, 5]);
+
+        let err = |input: &str| column(parse_page_segments(input).unwrap_err());
+        assert_eq!(err("1-2-3"), Some(4));
+        assert_eq!(err("intro:1"), Some(1));
+        assert_eq!(err("1-last"), Some(3));
+        assert_eq!(err("1,5-3"), Some(3));
+        assert_eq!(err("2,0"), Some(3));
+        assert_eq!(parse_page_segments(" , ()"), Err(SpecError::NoSegments));
+    }
+
+    #[test]
+    fn test_parse_named_segments() {
+        let segments = parse_named_segments("intro:1-3, chapter (2) : 4 - 9,10,(11)").unwrap();
+        let labels: Vec<String> = segments.iter().map(PageSegment::label).collect();
+        assert_eq!(labels, ["intro:1-3", "chapter (2):4-9", "10", "11"]);
+
+        let err = |input: &str| column(parse_named_segments(input).unwrap_err());
+        assert_eq!(err("intro:"), Some(7));
+        assert_eq!(err("a:b:1"), Some(3));
+        assert_eq!(err("intro:1-3x"), Some(10));
+        assert_eq!(err("1, :2"), Some(4));
+        assert_eq!(err("a:3-1"), Some(3));
+        assert_eq!(parse_named_segments("()"), Err(SpecError::NoSegments));
+    }
+
+    #[test]
+    fn test_segment_names_must_be_file_names() {
+        let err = |input: &str| parse_named_segments(input).unwrap_err();
+        let invalid = |input: &str, name: &str, column: usize| {
+            assert_eq!(
+                err(input),
+                SpecError::InvalidName {
+                    name: name.to_string(),
+                    column
+                }
+            )
+        };
+        invalid("../up:1", "../up", 1);
+        invalid("1, sub/part:2", "sub/part", 4);
+        invalid("a\\b:1", "a\\b", 1);
+        invalid("x..y:1", "x..y", 1);
+        invalid(".:1", ".", 1);
+        invalid("1,  ...:2", "...", 5);
+        // An empty name is missing, at the colon
+        assert_eq!(err("  :1"), SpecError::MissingName { column: 3 });
+
+        // Dots are fine inside a name
+        let segments = parse_named_segments("v1.2:1,.hidden:2").unwrap();
+        let names: Vec<_> = segments.iter().map(|s| s.name.as_deref()).collect();
+        assert_eq!(names, [Some("v1.2"), Some(".hidden")]);
+    }
+
+    #[test]
+    fn test_spec_limits() {
+        let long = "1,".repeat(MAX_SPEC_LENGTH);
+        assert_eq!(
+            parse_page_set(&long, None),
+            Err(SpecError::TooLong {
+                length: MAX_SPEC_LENGTH * 2
+            })
+        );
+        let many = "1,".repeat(MAX_PARTS + 1);
+        assert_eq!(
+            parse_page_segments(&many),
+            Err(SpecError::TooManyParts {
+                count: MAX_PARTS + 1
+            })
+        );
+        // Listing more pages than any document has is refused before the pages are listed
+        let spec = ["1-100000"; 2].join(",");
+        assert_eq!(
+            parse_page_sequence(&spec, None),
+            Err(SpecError::TooManyPages { count: 200_000 })
+        );
+        assert!(parse_page_segments(&spec).is_err());
+        assert!(parse_page_set("1-100000", None).is_ok());
+    }
+}
@@ -0,0 +1,283 @@
+use crate::i18n::tr;
+use anyhow::{Context, Result, bail};
+use lopdf::encryption::{PasswordAlgorithm, decrypt_object};
+use lopdf::xref::XrefEntry;
+use lopdf::{Document, EncryptionState, Object, ObjectStream, Reader};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::sync::Mutex;
+
+/// A candidate password of a password file, with the line it is on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasswordEntry {
+    /// The line of the password file (1-based).
+    pub line: usize,
+    pub password: String,
+}
+
+/// A locked input opened with a password file, and the line of the password that opened it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unlocked {
+    pub path: String,
+    pub line: usize,
+}
+
+/// Set by `--password-file`: the passwords tried on locked inputs, in order.
+static PASSWORDS: Mutex<Vec<PasswordEntry>> = Mutex::new(Vec::new());
+
+/// The inputs opened with a password since the last take_unlocked.
+static UNLOCKED: Mutex<Vec<Unlocked>> = Mutex::new(Vec::new());
+
+/**
+ * Read a password file: one candidate password per line, tried in order. Blank lines are
+ * skipped; spaces are kept, as they may be part of a password.
+ * @param path The password file path.
+ * @returns The passwords, with their lines.
+ * @throws anyhow::Error if the file cannot be read or holds no password.
+ */
+pub fn read_password_file(path: &str) -> Result<Vec<PasswordEntry>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| tr!("Failed to read the password file '{path}'", path = path))?;
+    let entries: Vec<PasswordEntry> = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(index, line)| PasswordEntry {
+            line: index + 1,
+            password: line.to_string(),
+        })
+        .collect();
+    if entries.is_empty() {
+        bail!(tr!(
+            "The password file '{path}' has no password",
+            path = path
+        ));
+    }
+    Ok(entries)
+}
+
+pub fn set_passwords(entries: Vec<PasswordEntry>) {
+    *PASSWORDS.lock().unwrap_or_else(|e| e.into_inner()) = entries;
+}
+
+/**
+ * Take the inputs opened with a password so far, to report them.
+ * @returns Each input opened, once, in the order they were first opened.
+ */
+pub fn take_unlocked() -> Vec<Unlocked> {
+    std::mem::take(&mut *UNLOCKED.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// Whether a document is encrypted with a password other than the empty one, so that its
+/// objects could not be read.
+pub fn is_locked(doc: &Document) -> bool {
+    doc.is_encrypted() && doc.encryption_state.is_none()
+}
+
+/**
+ * Open a locked document with the passwords of `--password-file`, if one is given, and
+ * remember which one opened it. Without a password file, the document is left as it is.
+ * @param doc The document as loaded, with its objects missing.
+ * @param path The file it was loaded from.
+ * @throws anyhow::Error if no password of the file opens it.
+ */
+pub(super) fn unlock_with_password_file(doc: &mut Document, path: &str) -> Result<()> {
+    let entries = PASSWORDS.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let Some(line) = unlock_document(doc, path, &entries)? else {
+        bail!(tr!(
+            "No password of the password file opens '{path}' ({count} tried)",
+            count = entries.len(),
+            path = path
+        ));
+    };
+    log::debug!("Opened '{}' with the password on line {}", path, line);
+    let mut unlocked = UNLOCKED.lock().unwrap_or_else(|e| e.into_inner());
+    if !unlocked.iter().any(|entry| entry.path == path) {
+        unlocked.push(Unlocked {
+            path: path.to_string(),
+            line,
+        });
+    }
+    Ok(())
+}
+
+/**
+ * Try passwords on a locked document, in order, until one opens it. Its objects are then read
+ * and decrypted, and its encryption removed, so it is saved without a password.
+ * @param doc The document as loaded, with its objects missing.
+ * @param path The file it was loaded from, read again for its objects.
+ * @param entries The passwords to try.
+ * @returns The line of the password that opened it, or None if none did.
+ * @throws anyhow::Error if the file cannot be read again.
+ */
+pub fn unlock_document(
+    doc: &mut Document,
+    path: &str,
+    entries: &[PasswordEntry],
+) -> Result<Option<usize>> {
+    let Some(entry) = entries
+        .iter()
+        .find(|entry| doc.authenticate_password(&entry.password).is_ok())
+    else {
+        return Ok(None);
+    };
+    let bytes = fs::read(path).with_context(|| tr!("Failed to load PDF '{path}'", path = path))?;
+    decrypt_document(doc, &bytes, &entry.password)
+        .with_context(|| tr!("Failed to load PDF '{path}'", path = path))?;
+    Ok(Some(entry.line))
+}
+
+/**
+ * Read every object of a locked document from its file and decrypt it, as lopdf does for
+ * documents opened with the empty password, then unpack its object streams.
+ * @param doc The document as loaded, with its cross-reference table and encryption dictionary.
+ * @param bytes The content of its file.
+ * @param password A password that opens it.
+ */
+fn decrypt_document(doc: &mut Document, bytes: &[u8], password: &str) -> lopdf::Result<()> {
+    let algorithm = PasswordAlgorithm::try_from(&*doc)?;
+    let password = algorithm.sanitize_password(password)?;
+    let state = EncryptionState::decode(&*doc, &password)?;
+    let encrypt_id = doc.trailer.get(b"Encrypt")?.as_reference()?;
+
+    let ids: Vec<_> = doc
+        .reference_table
+        .entries
+        .iter()
+        .filter_map(|(&number, entry)| match *entry {
+            XrefEntry::Normal { generation, .. } => Some((number, generation)),
+            _ => None,
+        })
+        .filter(|&id| id != encrypt_id)
+        .collect();
+    let reader = Reader {
+        buffer: bytes,
+        document: std::mem::replace(doc, Document::new()),
+        encryption_state: None,
+        raw_objects: BTreeMap::new(),
+    };
+    let mut objects = BTreeMap::new();
+    for id in ids {
+        // Objects that cannot be read are left out, as lopdf leaves them out
+        let Ok(mut object) = reader.get_object(id, &mut HashSet::new()) else {
+            continue;
+        };
+        if decrypt_object(&state, id, &mut object).is_ok() {
+            objects.insert(id, object);
+        }
+    }
+    *doc = reader.document;
+
+    let mut packed = Vec::new();
+    for object in objects.values_mut() {
+        if let Object::Stream(stream) = object
+            && stream.dict.has_type(b"ObjStm")
+            && let Ok(object_stream) = ObjectStream::new(stream)
+        {
+            packed.extend(object_stream.objects);
+        }
+    }
+    for (id, object) in packed {
+        objects.entry(id).or_insert(object);
+    }
+    doc.objects = objects;
+    doc.trailer.remove(b"Encrypt");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::utils::load_document;
+    use lopdf::{EncryptionVersion, Permissions};
+    use std::path::Path;
+
+    /// Save a copy of c.pdf that only opens with the given password.
+    fn save_locked(path: &Path, password: &str) {
+        let mut doc = load_document("tests/tests_pdf/c.pdf").unwrap();
+        let id = Object::String(
+            b"pdfcutter-test-id".to_vec(),
+            lopdf::StringFormat::Hexadecimal,
+        );
+        doc.trailer.set("ID", vec![id.clone(), id]);
+        let version = EncryptionVersion::V2 {
+            document: &doc,
+            owner_password: password,
+            user_password: password,
+            key_length: 128,
+            permissions: Permissions::all(),
+        };
+        let state = EncryptionState::try_from(version).unwrap();
+        doc.encrypt(&state).unwrap();
+        doc.save(path).unwrap();
+    }
+
+    #[test]
+    fn test_read_password_file() {
+        let dir = std::env::temp_dir().join("pdfcutter_password_file");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("pw.txt");
+        fs::write(&path, "first\r\n\n two words\n").unwrap();
+        let entries = read_password_file(&path.to_string_lossy()).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                PasswordEntry {
+                    line: 1,
+                    password: "first".to_string()
+                },
+                PasswordEntry {
+                    line: 3,
+                    password: " two words".to_string()
+                },
+            ]
+        );
+
+        fs::write(&path, "\n\n").unwrap();
+        assert!(read_password_file(&path.to_string_lossy()).is_err());
+        assert!(read_password_file(&dir.join("missing.txt").to_string_lossy()).is_err());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_unlock_document() {
+        let dir = std::env::temp_dir().join("pdfcutter_unlock");
+        fs::create_dir_all(&dir).unwrap();
+        let locked = dir.join("locked.pdf");
+        save_locked(&locked, "s3cret");
+        let path = locked.to_string_lossy();
+
+        let mut doc = Document::load(&locked).unwrap();
+        assert!(is_locked(&doc));
+        let entry = |line: usize, password: &str| PasswordEntry {
+            line,
+            password: password.to_string(),
+        };
+        let wrong = [entry(1, "guess"), entry(2, "")];
+        assert_eq!(unlock_document(&mut doc, &path, &wrong).unwrap(), None);
+        assert!(is_locked(&doc));
+
+        let entries = [entry(1, "guess"), entry(4, "s3cret")];
+        assert_eq!(unlock_document(&mut doc, &path, &entries).unwrap(), Some(4));
+        assert!(!doc.is_encrypted());
+        assert_eq!(doc.get_pages().len(), 7);
+        let original = load_document("tests/tests_pdf/c.pdf").unwrap();
+        assert_eq!(
+            doc.extract_text(&[1]).unwrap(),
+            original.extract_text(&[1]).unwrap()
+        );
+
+        // Saved without a password
+        let opened = dir.join("opened.pdf");
+        doc.save(&opened).unwrap();
+        let reloaded = Document::load(&opened).unwrap();
+        assert!(!reloaded.is_encrypted());
+        assert_eq!(reloaded.get_pages().len(), 7);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
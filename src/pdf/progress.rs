@@ -0,0 +1,198 @@
+use std::cell::RefCell;
+use std::time::Duration;
+
+/// A step of a merge or split, reported as it happens so a wrapper can show live progress.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Progress {
+    /// The command is about to write: `total` file or segment events follow.
+    Start {
+        command: &'static str,
+        inputs: Vec<String>,
+        total: usize,
+    },
+    /// An input of a merge was copied into the output (`index` counts from 1).
+    File {
+        index: usize,
+        total: usize,
+        path: String,
+        pages: usize,
+    },
+    /// A segment of a split was written (`index` counts from 1).
+    Segment {
+        index: usize,
+        total: usize,
+        name: Option<String>,
+        output: String,
+        pages: usize,
+    },
+    /// The command is over and its outputs are written.
+    Done {
+        command: &'static str,
+        outputs: Vec<String>,
+        elapsed: Duration,
+    },
+    /// The command failed.
+    Error { message: String },
+}
+
+impl Progress {
+    /**
+     * Describe the event as a JSON object on one line, for `--json`.
+     * e.g. {"event":"file","index":1,"total":3,"path":"a.pdf","pages":2}
+     * @returns The JSON object, without a line break.
+     */
+    pub fn to_json(&self) -> String {
+        let fields: Vec<(&str, String)> = match self {
+            Progress::Start {
+                command,
+                inputs,
+                total,
+            } => vec![
+                ("event", json_string("start")),
+                ("command", json_string(command)),
+                ("inputs", json_strings(inputs)),
+                ("total", total.to_string()),
+            ],
+            Progress::File {
+                index,
+                total,
+                path,
+                pages,
+            } => vec![
+                ("event", json_string("file")),
+                ("index", index.to_string()),
+                ("total", total.to_string()),
+                ("path", json_string(path)),
+                ("pages", pages.to_string()),
+            ],
+            Progress::Segment {
+                index,
+                total,
+                name,
+                output,
+                pages,
+            } => vec![
+                ("event", json_string("segment")),
+                ("index", index.to_string()),
+                ("total", total.to_string()),
+                (
+                    "name",
+                    name.as_deref()
+                        .map_or_else(|| "null".to_string(), json_string),
+                ),
+                ("output", json_string(output)),
+                ("pages", pages.to_string()),
+            ],
+            Progress::Done {
+                command,
+                outputs,
+                elapsed,
+            } => vec![
+                ("event", json_string("done")),
+                ("command", json_string(command)),
+                ("outputs", json_strings(outputs)),
+                ("elapsed_ms", elapsed.as_millis().to_string()),
+            ],
+            Progress::Error { message } => vec![
+                ("event", json_string("error")),
+                ("message", json_string(message)),
+            ],
+        };
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|(key, value)| format!("{}:{}", json_string(key), value))
+            .collect();
+        format!("{{{}}}", fields.join(","))
+    }
+}
+
+/// Told about each step of the operations run on a thread.
+type Observer = Box<dyn FnMut(&Progress)>;
+
+thread_local! {
+    // Each thread runs its own operation, so the TUI is not told about a CLI command
+    static OBSERVER: RefCell<Option<Observer>> = RefCell::new(None);
+}
+
+/// Send the progress of the operations run on the current thread to an observer from now on.
+pub fn watch_progress(observer: impl FnMut(&Progress) + 'static) {
+    OBSERVER.with(|slot| *slot.borrow_mut() = Some(Box::new(observer)));
+}
+
+/// Tell the observer of the current thread about a step, if one is watching.
+pub fn report(event: Progress) {
+    OBSERVER.with(|slot| {
+        if let Some(observer) = slot.borrow_mut().as_mut() {
+            observer(&event);
+        }
+    });
+}
+
+/// Quote a string for JSON, escaping quotes, backslashes and control characters.
+pub fn json_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn json_strings(values: &[String]) -> String {
+    let values: Vec<String> = values.iter().map(|value| json_string(value)).collect();
+    format!("[{}]", values.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_to_json() {
+        let start = Progress::Start {
+            command: "merge",
+            inputs: vec!["a.pdf".to_string(), "dir\\\"b\".pdf".to_string()],
+            total: 2,
+        };
+        assert_eq!(
+            start.to_json(),
+            r#"{"event":"start","command":"merge","inputs":["a.pdf","dir\\\"b\".pdf"],"total":2}"#
+        );
+        let segment = Progress::Segment {
+            index: 1,
+            total: 3,
+            name: None,
+            output: "part_001.pdf".to_string(),
+            pages: 4,
+        };
+        assert_eq!(
+            segment.to_json(),
+            r#"{"event":"segment","index":1,"total":3,"name":null,"output":"part_001.pdf","pages":4}"#
+        );
+        let done = Progress::Done {
+            command: "split",
+            outputs: vec![],
+            elapsed: Duration::from_micros(12_900),
+        };
+        assert_eq!(
+            done.to_json(),
+            r#"{"event":"done","command":"split","outputs":[],"elapsed_ms":12}"#
+        );
+        let error = Progress::Error {
+            message: "line\nnext\u{1}".to_string(),
+        };
+        assert_eq!(
+            error.to_json(),
+            r#"{"event":"error","message":"line\nnext\u0001"}"#
+        );
+    }
+}
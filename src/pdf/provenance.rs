@@ -0,0 +1,109 @@
+use crate::build_info::LONG_VERSION;
+use crate::i18n::tr;
+use anyhow::{Context, Result};
+use chrono::{DateTime, FixedOffset, SecondsFormat};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+/**
+ * Describe how an output was made, to record in its metadata so it can be traced later: the
+ * version of pdf-cutter, the command, the file name and SHA-256 hash of each source, and when
+ * it ran. Sources are named without their folders, which may be private.
+ * e.g. "pdf-cutter 0.1.0 (e465cfb); merge; a.pdf sha256:9f86d0…, b.pdf sha256:60303a…;
+ * 2025-01-31T09:30:00+01:00"
+ * @param operation The command, e.g. "merge".
+ * @param sources The files the output was made from, hashed before they may be replaced.
+ * @param date When the command ran, None to leave it out.
+ * @returns The receipt, on one line.
+ * @throws anyhow::Error if a source cannot be read.
+ */
+pub fn receipt(
+    operation: &str,
+    sources: &[String],
+    date: Option<DateTime<FixedOffset>>,
+) -> Result<String> {
+    let sources = sources
+        .iter()
+        .map(|source| {
+            let name = Path::new(source).file_name().map_or_else(
+                || source.clone(),
+                |name| name.to_string_lossy().into_owned(),
+            );
+            Ok(format!("{} sha256:{}", name, sha256_file(source)?))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let mut parts = vec![
+        format!("pdf-cutter {}", LONG_VERSION),
+        operation.to_string(),
+        sources.join(", "),
+    ];
+    if let Some(date) = date {
+        parts.push(date.to_rfc3339_opts(SecondsFormat::Secs, true));
+    }
+    Ok(parts.join("; "))
+}
+
+/**
+ * Hash a file with SHA-256, reading it in chunks.
+ * @param path The file path.
+ * @returns The hash, as 64 lowercase hexadecimal digits.
+ * @throws anyhow::Error if the file cannot be read.
+ */
+pub fn sha256_file(path: &str) -> Result<String> {
+    let mut file = File::open(path).with_context(|| tr!("Failed to read '{path}'", path = path))?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)
+        .with_context(|| tr!("Failed to read '{path}'", path = path))?;
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_receipt() {
+        let dir = std::env::temp_dir().join("pdfcutter_receipt");
+        fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("notes.txt");
+        fs::write(&source, "abc").unwrap();
+        let source = source.to_string_lossy().into_owned();
+        assert_eq!(
+            sha256_file(&source).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+
+        let date = DateTime::parse_from_rfc3339("2025-01-31T09:30:00+01:00").unwrap();
+        let text = receipt("merge", &[source.clone(), source.clone()], Some(date)).unwrap();
+        let hash =
+            "notes.txt sha256:ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert_eq!(
+            text,
+            format!(
+                "pdf-cutter {}; merge; {}, {}; 2025-01-31T09:30:00+01:00",
+                LONG_VERSION, hash, hash
+            )
+        );
+        assert!(!text.contains(&dir.to_string_lossy().into_owned()));
+        let undated = receipt("delete", &[source], None).unwrap();
+        assert!(undated.ends_with(&format!("; delete; {}", hash)));
+
+        assert!(
+            receipt(
+                "merge",
+                &[dir.join("missing.pdf").to_string_lossy().into_owned()],
+                None
+            )
+            .is_err()
+        );
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,167 @@
+use super::inspect::page_infos;
+use super::utils::{load_document, save_document};
+use crate::i18n::tr;
+use anyhow::{Context, Result, bail};
+use lopdf::{Document, Object};
+
+/// Some pages and the clockwise angle they are turned by.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RotationRule {
+    /// The pages to rotate (1-based).
+    pub pages: Vec<u32>,
+    /// The clockwise rotation in degrees, a multiple of 90 (may be negative).
+    pub angle: i64,
+}
+
+/**
+ * Rotate pages of a PDF clockwise and save the result.
+ * The angle is added to the current rotation of each page, so rotating twice by 90 gives 180.
+ * Rules are applied in order: a page listed by several rules is turned by each of them.
+ * @param input The input PDF file path.
+ * @param output The output PDF file path.
+ * @param rules The pages to rotate and their angles.
+ * @throws anyhow::Error if an angle is invalid, a page does not exist or the PDF cannot be written.
+ */
+pub fn rotate_pages(input: &str, output: &str, rules: &[RotationRule]) -> Result<()> {
+    check_rules(rules)?;
+
+    log::debug!("Loading '{}'", input);
+    let mut doc = load_document(input)?;
+    rotate_document(&mut doc, rules)?;
+
+    save_document(&mut doc, output)?;
+
+    Ok(())
+}
+
+/**
+ * Rotate pages of a loaded document clockwise, in memory.
+ * The angles add up the same way as with `rotate_pages`.
+ * @param doc The document to change.
+ * @param rules The pages to rotate and their angles.
+ * @throws anyhow::Error if an angle is invalid or a page does not exist.
+ */
+pub fn rotate_document(doc: &mut Document, rules: &[RotationRule]) -> Result<()> {
+    check_rules(rules)?;
+
+    let all_pages = doc.get_pages();
+    let mut rotations: Vec<i64> = page_infos(doc).iter().map(|page| page.rotation).collect();
+    let total_pages = all_pages.len();
+
+    for &page_num in rules.iter().flat_map(|rule| &rule.pages) {
+        if page_num == 0 || page_num as usize > total_pages {
+            bail!(tr!(
+                "Invalid page number: {page}. PDF has {count} pages (1-{count})",
+                page = page_num,
+                count = total_pages
+            ));
+        }
+    }
+
+    let mut touched = Vec::new();
+    for rule in rules {
+        for &page_num in &rule.pages {
+            rotations[page_num as usize - 1] += rule.angle;
+            if !touched.contains(&page_num) {
+                touched.push(page_num);
+            }
+        }
+    }
+
+    for page_num in touched {
+        let page_id = all_pages[&page_num];
+        let rotation = rotations[page_num as usize - 1].rem_euclid(360);
+        doc.get_dictionary_mut(page_id)
+            .with_context(|| tr!("Page {page} is not a dictionary", page = page_num))?
+            .set("Rotate", Object::Integer(rotation));
+    }
+
+    Ok(())
+}
+
+/// Reject angles that are not a multiple of 90 and rules without pages.
+pub fn check_rules(rules: &[RotationRule]) -> Result<()> {
+    for rule in rules {
+        if rule.angle % 90 != 0 {
+            bail!(tr!(
+                "Invalid rotation: {angle} (must be a multiple of 90)",
+                angle = rule.angle
+            ));
+        }
+    }
+    if rules.is_empty() || rules.iter().any(|rule| rule.pages.is_empty()) {
+        bail!(tr!("No pages to rotate"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn rule(pages: &[u32], angle: i64) -> RotationRule {
+        RotationRule {
+            pages: pages.to_vec(),
+            angle,
+        }
+    }
+
+    #[test]
+    fn test_rotate_pages() {
+        let input = "tests/tests_pdf/c.pdf";
+        let output = "test_rotate_output.pdf";
+
+        let before = page_infos(&Document::load(input).unwrap());
+        rotate_pages(input, output, &[rule(&[1, 3], 90)]).unwrap();
+        let after = page_infos(&Document::load(output).unwrap());
+
+        assert_eq!(after.len(), before.len());
+        assert_eq!(after[0].rotation, (before[0].rotation + 90) % 360);
+        assert_eq!(after[1].rotation, before[1].rotation);
+        assert_eq!(after[2].rotation, (before[2].rotation + 90) % 360);
+
+        // Rotations accumulate and wrap around
+        rotate_pages(output, output, &[rule(&[1], -180)]).unwrap();
+        let wrapped = page_infos(&Document::load(output).unwrap());
+        assert_eq!(wrapped[0].rotation, (before[0].rotation + 270) % 360);
+
+        std::fs::remove_file(output).unwrap();
+    }
+
+    #[test]
+    fn test_rotate_pages_with_rules() {
+        let input = "tests/tests_pdf/c.pdf";
+        let output = "test_rotate_rules_output.pdf";
+
+        let before = page_infos(&Document::load(input).unwrap());
+        rotate_pages(
+            input,
+            output,
+            &[rule(&[1, 2], 90), rule(&[3], 270), rule(&[2], 180)],
+        )
+        .unwrap();
+        let after = page_infos(&Document::load(output).unwrap());
+
+        assert_eq!(after[0].rotation, (before[0].rotation + 90) % 360);
+        // Page 2 is turned by both rules that list it
+        assert_eq!(after[1].rotation, (before[1].rotation + 270) % 360);
+        assert_eq!(after[2].rotation, (before[2].rotation + 270) % 360);
+        assert_eq!(after[3].rotation, before[3].rotation);
+
+        std::fs::remove_file(output).unwrap();
+    }
+
+    #[test]
+    fn test_rotate_invalid_input() {
+        let input = "tests/tests_pdf/c.pdf";
+        let output = "test_rotate_invalid.pdf";
+
+        assert!(rotate_pages(input, output, &[rule(&[1], 45)]).is_err());
+        assert!(rotate_pages(input, output, &[rule(&[99], 90)]).is_err());
+        assert!(rotate_pages(input, output, &[rule(&[], 90)]).is_err());
+        assert!(rotate_pages(input, output, &[]).is_err());
+        assert!(rotate_pages(input, output, &[rule(&[1], 90), rule(&[2], 45)]).is_err());
+        assert!(!Path::new(output).exists());
+    }
+}
@@ -0,0 +1,199 @@
+use super::operation::Operation;
+use crate::config::Config;
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Config key of the size above which an output is reported as large (e.g. "500 MB").
+pub const SIZE_WARNING_KEY: &str = "output_size_warning";
+
+/// Something worth knowing before writing the outputs of an operation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpaceWarning {
+    /// The outputs may not fit in the free space of the file system holding a directory.
+    NotEnoughSpace {
+        directory: PathBuf,
+        needed: u64,
+        available: u64,
+    },
+    /// An output is expected to be larger than the configured size.
+    LargeOutput {
+        output: String,
+        size: u64,
+        limit: u64,
+    },
+}
+
+/// Checks made before running an operation, so that it does not fail halfway through saving.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SpaceCheck {
+    /// Size in bytes above which an output is reported as large, if any.
+    pub size_warning: Option<u64>,
+}
+
+impl SpaceCheck {
+    /**
+     * Read the size warning threshold from the configuration.
+     * @param config The loaded configuration.
+     * @returns The checks to make, without a size threshold when none is configured.
+     * @throws String if the configured size cannot be parsed.
+     */
+    pub fn from_config(config: &Config) -> Result<Self, String> {
+        let size_warning = match config.get(SIZE_WARNING_KEY) {
+            Some(value) => Some(parse_size(value).ok_or_else(|| {
+                format!(
+                    "Invalid size '{}' for {} (e.g. 500 MB or 2 GB)",
+                    value, SIZE_WARNING_KEY
+                )
+            })?),
+            None => None,
+        };
+        Ok(Self { size_warning })
+    }
+
+    /**
+     * Estimate the outputs of an operation and compare them with the free disk space and the size threshold.
+     * Files about to be overwritten count as free space, since they are replaced.
+     * @param operation The operation about to run.
+     * @returns The warnings, empty when the outputs should fit.
+     * @throws anyhow::Error if the inputs cannot be read to estimate the outputs.
+     */
+    pub fn warnings(&self, operation: &Operation) -> Result<Vec<SpaceWarning>> {
+        let sizes = operation.estimated_sizes()?;
+        let mut warnings = Vec::new();
+
+        let mut needed: BTreeMap<PathBuf, u64> = BTreeMap::new();
+        for (output, size) in &sizes {
+            let path = std::path::absolute(output).unwrap_or_else(|_| PathBuf::from(output));
+            let replaced = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            let directory = path.parent().map(Path::to_path_buf).unwrap_or_default();
+            *needed.entry(directory).or_default() += size.saturating_sub(replaced);
+        }
+        for (directory, needed) in needed {
+            if let Some(available) = available_space(&directory)
+                && needed > available
+            {
+                warnings.push(SpaceWarning::NotEnoughSpace {
+                    directory,
+                    needed,
+                    available,
+                });
+            }
+        }
+
+        if let Some(limit) = self.size_warning {
+            warnings.extend(sizes.into_iter().filter(|&(_, size)| size > limit).map(
+                |(output, size)| SpaceWarning::LargeOutput {
+                    output,
+                    size,
+                    limit,
+                },
+            ));
+        }
+        Ok(warnings)
+    }
+}
+
+/**
+ * Parse a size such as "500 MB", "1.5G" or "1048576" (bytes). Units are powers of 1024.
+ * @param text The size as written by the user.
+ * @returns The size in bytes, or None if it cannot be parsed.
+ */
+pub fn parse_size(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" => 1 << 10,
+        "M" | "MB" => 1 << 20,
+        "G" | "GB" => 1 << 30,
+        "T" | "TB" => 1 << 40,
+        _ => return None,
+    };
+    Some((number * multiplier as f64) as u64)
+}
+
+/**
+ * Free space available to the user on the file system holding a path.
+ * The path may not exist yet: its closest existing ancestor is used.
+ * @param path The file or directory to write to.
+ * @returns The free space in bytes, or None when it cannot be known.
+ */
+pub fn available_space(path: &Path) -> Option<u64> {
+    let existing = path.ancestors().find(|dir| dir.exists())?;
+    free_space(existing)
+}
+
+#[cfg(unix)]
+#[allow(clippy::unnecessary_cast)]
+fn free_space(path: &Path) -> Option<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stats = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+    // SAFETY: the path is a valid C string and statvfs fills the struct when it returns 0
+    let stats = unsafe {
+        if libc::statvfs(path.as_ptr(), stats.as_mut_ptr()) != 0 {
+            return None;
+        }
+        stats.assume_init()
+    };
+    Some(stats.f_bavail as u64 * stats.f_frsize as u64)
+}
+
+#[cfg(not(unix))]
+fn free_space(_path: &Path) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("1024"), Some(1024));
+        assert_eq!(parse_size("500 MB"), Some(500 << 20));
+        assert_eq!(parse_size("1.5g"), Some(3 << 29));
+        assert_eq!(parse_size("2 KB "), Some(2048));
+        assert_eq!(parse_size("lots"), None);
+        assert_eq!(parse_size("5 PB"), None);
+    }
+
+    #[test]
+    fn test_space_warnings() {
+        let output = std::env::temp_dir().join("pdfcutter_space_merge.pdf");
+        let merge = Operation::Merge {
+            inputs: vec![
+                "tests/tests_pdf/a.pdf".to_string(),
+                "tests/tests_pdf/b.pdf".to_string(),
+            ],
+            output: output.to_string_lossy().into_owned(),
+        };
+        assert!(available_space(&output).is_some());
+        assert_eq!(SpaceCheck::default().warnings(&merge).unwrap(), Vec::new());
+
+        let check = SpaceCheck {
+            size_warning: Some(1024),
+        };
+        let warnings = check.warnings(&merge).unwrap();
+        assert!(matches!(
+            &warnings[..],
+            [SpaceWarning::LargeOutput { limit: 1024, .. }]
+        ));
+
+        let config = Config::parse("output_size_warning = \"2 GB\"").unwrap();
+        assert_eq!(
+            SpaceCheck::from_config(&config).unwrap().size_warning,
+            Some(2 << 30)
+        );
+        let config = Config::parse("output_size_warning = huge").unwrap();
+        assert!(SpaceCheck::from_config(&config).is_err());
+    }
+}
@@ -1,171 +1,112 @@
-use super::utils::{copy_page_with_resources, create_pages_structure, finalize_document};
+use super::intents::{add_output_intents, copy_output_intents};
+use super::metadata::{DocumentMetadata, apply_metadata};
+use super::page_spec::{PageSegment, parse_named_segments, parse_page_segments};
+use super::progress::{Progress, report};
+use super::utils::{PageCopier, create_pages_structure, finalize_document, load_document};
+use crate::i18n::tr;
 use anyhow::{Context, Result, bail};
-use lopdf::{Document, ObjectId};
-use std::collections::BTreeMap;
+use lopdf::{Dictionary, Document, Object};
+use std::collections::{BTreeMap, HashMap};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct PageSegment {
-    pub start: u32,
-    pub end: Option<u32>,
-    pub name: Option<String>,
-}
-
-impl PageSegment {
-    pub fn single(page: u32) -> Self {
-        PageSegment {
-            start: page,
-            end: None,
-            name: None,
-        }
-    }
-
-    pub fn range(start: u32, end: u32) -> Self {
-        Self {
-            start,
-            end: Some(end),
-            name: None,
-        }
-    }
-
-    pub fn named(start: u32, end: Option<u32>, name: String) -> Self {
-        Self {
-            start,
-            end,
-            name: Some(name),
-        }
-    }
-
-    pub fn get_pages(&self) -> Vec<u32> {
-        match self.end {
-            Some(end) => (self.start..=end).collect(),
-            None => vec![self.start],
-        }
+/**
+ * The files a split writes, one per segment in order.
+ * @param segments The segments, in order.
+ * @param base_prefix The output prefix.
+ * @returns The output file of each segment.
+ * @throws anyhow::Error if two segments would be written to the same file, such as a segment
+ * named "002" and the second unnamed one, or a name used twice: the second would replace the
+ * first.
+ */
+pub fn segment_filenames(segments: &[PageSegment], base_prefix: &str) -> Result<Vec<String>> {
+    if let Some(shared) = find_shared_output(segments, base_prefix) {
+        bail!(tr!(
+            "Segments '{first}' and '{second}' would both be written to '{file}'",
+            first = segments[shared.first].label(),
+            second = segments[shared.second].label(),
+            file = shared.file
+        ));
     }
+    Ok(filenames(segments, base_prefix))
+}
 
-    pub fn is_valid(&self) -> bool {
-        self.start > 0 && self.end.map_or(true, |end| end >= self.start)
-    }
+fn filenames(segments: &[PageSegment], base_prefix: &str) -> Vec<String> {
+    segments
+        .iter()
+        .enumerate()
+        .map(|(i, segment)| segment.generate_filename(base_prefix, i + 1, segments.len()))
+        .collect()
+}
 
-    pub fn generate_filename(&self, base_prefix: &str) -> String {
-        if let Some(ref name) = self.name {
-            format!("{}_{}.pdf", base_prefix, name)
-        } else {
-            match self.end {
-                Some(end) if end != self.start => {
-                    format!("{}_pages_{}_{}.pdf", base_prefix, self.start, end)
-                }
-                _ => {
-                    format!("{}_page_{}.pdf", base_prefix, self.start)
-                }
-            }
-        }
-    }
+/// Two segments of a split written to the same file, the second replacing the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SharedOutput {
+    /// Position of the first of the two segments in the split, from 0.
+    pub first: usize,
+    /// Position of the second segment, after the first one.
+    pub second: usize,
+    /// The file both segments would be written to.
+    pub file: String,
 }
 
 /**
- * Parse the input string into a vector of PageSegment.
- * @param input The input string (e.g., "1,3-5,(7,9),11")
- * @returns A vector of PageSegment if valid, Err(anyhow::Error) if invalid.
- * @throws anyhow::Error if the input is invalid.
+ * Find the first two segments of a split that would be written to the same file.
+ * @param segments The segments, in order.
+ * @param base_prefix The output prefix.
+ * @returns The first two segments sharing a file, or None.
  */
-pub fn parse_page_segments(input: &str) -> Result<Vec<PageSegment>> {
-    let mut segments = Vec::new();
-
-    let clean_input = input.replace(['(', ')'], ",");
-    let parts: Vec<&str> = clean_input
-        .split(',')
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-        .collect();
-
-    for part in parts {
-        let segment = if part.contains('-') {
-            let range_parts: Vec<&str> = part.split('-').collect();
-            if range_parts.len() != 2 {
-                bail!("Invalid range format: {}", part);
-            }
-
-            let start: u32 = range_parts[0]
-                .trim()
-                .parse()
-                .with_context(|| format!("Invalid start page: {}", range_parts[0]))?;
-            let end: u32 = range_parts[1]
-                .trim()
-                .parse()
-                .with_context(|| format!("Invalid end page: {}", range_parts[1]))?;
-
-            PageSegment::range(start, end)
-        } else {
-            let page: u32 = part
-                .parse()
-                .with_context(|| format!("Invalid page number: {}", part))?;
-            PageSegment::single(page)
-        };
-
-        if !segment.is_valid() {
-            bail!("Invalid segment: {:?}", segment);
+pub fn find_shared_output(segments: &[PageSegment], base_prefix: &str) -> Option<SharedOutput> {
+    let mut owners: HashMap<String, usize> = HashMap::new();
+    for (index, file) in filenames(segments, base_prefix).into_iter().enumerate() {
+        if let Some(&first) = owners.get(&file) {
+            return Some(SharedOutput {
+                first,
+                second: index,
+                file,
+            });
         }
-
-        segments.push(segment);
-    }
-
-    if segments.is_empty() {
-        bail!("No valid segments found");
+        owners.insert(file, index);
     }
+    None
+}
 
-    Ok(segments)
+/// Two segments of a split that include the same pages, which are then written to both files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SegmentOverlap {
+    /// Position of the first of the two segments in the split, from 0.
+    pub first: usize,
+    /// Position of the second segment, after the first one.
+    pub second: usize,
+    /// The pages both segments include, sorted.
+    pub pages: Vec<u32>,
 }
 
 /**
- * Parse named segments from a string like "intro:1-3,chapter1:4-10,conclusion:11"
- * @param input The input string with named segments
- * @returns A vector of PageSegment with names
- * @throws anyhow::Error if the input is invalid
+ * Find the first two segments of a split that include the same page, usually a typo such as
+ * "1-5,5-10" or a segment typed twice.
+ * @param segments The segments, in order.
+ * @returns The first overlap found, with every page the two segments share, or None.
  */
-pub fn parse_named_segments(input: &str) -> Result<Vec<PageSegment>> {
-    let mut segments = Vec::new();
-
-    for part in input.split(',') {
-        let part = part.trim();
-        if part.is_empty() {
-            continue;
-        }
-
-        let segment = if part.contains(':') {
-            let name_parts: Vec<&str> = part.split(':').collect();
-            if name_parts.len() != 2 {
-                bail!("Invalid named segment format: {}", part);
-            }
-
-            let name = name_parts[0].trim().to_string();
-            let page_part = name_parts[1].trim();
-
-            if page_part.contains('-') {
-                let range_parts: Vec<&str> = page_part.split('-').collect();
-                if range_parts.len() != 2 {
-                    bail!("Invalid range in named segment: {}", page_part);
-                }
-
-                let start: u32 = range_parts[0].parse()?;
-                let end: u32 = range_parts[1].parse()?;
-
-                PageSegment::named(start, Some(end), name)
-            } else {
-                let page: u32 = page_part.parse()?;
-                PageSegment::named(page, None, name)
+pub fn find_overlap(segments: &[PageSegment]) -> Option<SegmentOverlap> {
+    let mut owners: HashMap<u32, usize> = HashMap::new();
+    for (index, segment) in segments.iter().enumerate() {
+        for page in segment.get_pages() {
+            if let Some(&first) = owners.get(&page) {
+                let second_pages = segment.get_pages();
+                let pages = segments[first]
+                    .get_pages()
+                    .into_iter()
+                    .filter(|page| second_pages.contains(page))
+                    .collect();
+                return Some(SegmentOverlap {
+                    first,
+                    second: index,
+                    pages,
+                });
             }
-        } else {
-            parse_page_segments(part)?.into_iter().next().unwrap()
-        };
-
-        if !segment.is_valid() {
-            bail!("Invalid segment: {:?}", segment);
+            owners.insert(page, index);
         }
-
-        segments.push(segment);
     }
-
-    Ok(segments)
+    None
 }
 
 /**
@@ -173,6 +114,7 @@ pub fn parse_named_segments(input: &str) -> Result<Vec<PageSegment>> {
  */
 fn create_pdf_with_segment(
     source_doc: &Document,
+    input: &str,
     segment: &PageSegment,
     all_pages: &BTreeMap<u32, (u32, u16)>,
     total_pages: usize,
@@ -181,12 +123,11 @@ fn create_pdf_with_segment(
 
     for &page_num in &pages_to_include {
         if page_num == 0 || page_num > total_pages as u32 {
-            return Err(anyhow::anyhow!(
-                "Invalid page number: {}. PDF has {} pages (1-{})",
-                page_num,
-                total_pages,
-                total_pages
-            ));
+            return Err(anyhow::anyhow!(tr!(
+                "Invalid page number: {page}. PDF has {count} pages (1-{count})",
+                page = page_num,
+                count = total_pages
+            )));
         }
     }
 
@@ -201,53 +142,107 @@ fn create_pdf_with_segment(
     }
 
     if pages_to_keep.is_empty() {
-        return Err(anyhow::anyhow!("No pages to include in PDF"));
+        return Err(anyhow::anyhow!(tr!("No pages to include in PDF")));
     }
 
-    let mut target = Document::with_version("1.5");
-    let mut page_objects: Vec<ObjectId> = Vec::new();
-
-    for page_id in pages_to_keep {
-        let new_page_id = copy_page_with_resources(source_doc, page_id, &mut target)?;
-        page_objects.push(new_page_id);
-    }
+    let mut copier = PageCopier::new(Document::with_version("1.5"));
+    let page_objects = copier.copy_pages(source_doc, input, &pages_to_keep)?;
+    let output_intents = copy_output_intents(source_doc, input, &mut copier);
 
+    let mut target = copier.into_target();
     create_pages_structure(&mut target, &page_objects)?;
+    add_output_intents(&mut target, output_intents);
 
     Ok(target)
 }
 
 /**
- * Split PDF based on provided segments
+ * Make the output of a named segment self-describing: it keeps the information of the source
+ * (author, subject, dates...), its title becomes the segment name and its keywords tell its
+ * place in the split ("2 of 5").
+ * @param source The document being split.
+ * @param target The output of the segment.
+ * @param name The segment name.
+ * @param index The position of the segment in the split, from 1.
+ * @param count The number of segments.
+ */
+fn describe_segment(
+    source: &Document,
+    target: &mut Document,
+    name: &str,
+    index: usize,
+    count: usize,
+) {
+    if let Ok(info) = source.trailer.get(b"Info")
+        && let Ok((_, Object::Dictionary(info))) = source.dereference(info)
+    {
+        // Only plain values are copied, so that no entry refers to an object left behind
+        let mut copy = Dictionary::new();
+        for (key, value) in info.iter() {
+            if let Ok((_, value)) = source.dereference(value)
+                && matches!(
+                    value,
+                    Object::String(..) | Object::Name(_) | Object::Integer(_) | Object::Real(_)
+                )
+            {
+                copy.set(key.clone(), value.clone());
+            }
+        }
+        let id = target.add_object(copy);
+        target.trailer.set("Info", id);
+    }
+
+    let metadata = DocumentMetadata {
+        title: Some(name.to_string()),
+        keywords: Some(tr!("{index} of {count}", index = index, count = count)),
+        ..Default::default()
+    };
+    apply_metadata(target, &metadata);
+}
+
+/**
+ * Split PDF based on provided segments.
+ * The outputs of named segments are titled with the segment name.
  */
 pub fn split_pdfs_with_segments(
     input: &str,
     output_prefix: &str,
     segments: &[PageSegment],
 ) -> Result<Vec<String>> {
-    let doc = Document::load(input).with_context(|| format!("Failed to load PDF '{}'", input))?;
+    let filenames = segment_filenames(segments, output_prefix)?;
+    log::debug!("Loading '{}'", input);
+    let doc = load_document(input)?;
 
     let all_pages = doc.get_pages();
     let total_pages = all_pages.len();
 
     if total_pages == 0 {
-        return Err(anyhow::anyhow!("PDF has no pages"));
+        return Err(anyhow::anyhow!(tr!("PDF has no pages")));
     }
 
     let mut output_files = Vec::new();
 
-    for segment in segments {
-        let output_filename = segment.generate_filename(output_prefix);
-
-        let mut target_doc = create_pdf_with_segment(&doc, segment, &all_pages, total_pages)?;
+    for (index, (segment, output_filename)) in segments.iter().zip(filenames).enumerate() {
+        let mut target_doc =
+            create_pdf_with_segment(&doc, input, segment, &all_pages, total_pages)?;
+        if let Some(name) = &segment.name {
+            describe_segment(&doc, &mut target_doc, name, index + 1, segments.len());
+        }
 
         finalize_document(&mut target_doc, &output_filename)
-            .with_context(|| format!("Failed to save PDF '{}'", output_filename))?;
+            .with_context(|| tr!("Failed to save PDF '{path}'", path = output_filename))?;
 
         let pages = segment.get_pages();
+        report(Progress::Segment {
+            index: index + 1,
+            total: segments.len(),
+            name: segment.name.clone(),
+            output: output_filename.clone(),
+            pages: pages.len(),
+        });
         output_files.push(output_filename.clone());
-        println!(
-            "Created: {} (pages: {})",
+        log::debug!(
+            "Segment '{}' covers pages {}",
             output_filename,
             pages
                 .iter()
@@ -299,14 +294,27 @@ mod tests {
     fn test_page_segment_creation() {
         let single = PageSegment::single(5);
         assert_eq!(single.get_pages(), vec![5]);
-        assert_eq!(single.generate_filename("test"), "test_page_5.pdf");
+        assert_eq!(single.generate_filename("test", 1, 2), "test_001.pdf");
 
         let range = PageSegment::range(3, 7);
         assert_eq!(range.get_pages(), vec![3, 4, 5, 6, 7]);
-        assert_eq!(range.generate_filename("test"), "test_pages_3_7.pdf");
+        assert_eq!(range.generate_filename("test", 2, 2), "test_002.pdf");
+        assert_eq!(range.generate_filename("test", 42, 1500), "test_0042.pdf");
 
         let named = PageSegment::named(1, Some(3), "intro".to_string());
-        assert_eq!(named.generate_filename("test"), "test_intro.pdf");
+        assert_eq!(named.generate_filename("test", 1, 2), "test_intro.pdf");
+
+        // Sequence names sort like the segments, past 9 and 99
+        let pages: Vec<PageSegment> = (1..=120).map(PageSegment::single).collect();
+        let names = segment_filenames(&pages, "scan").unwrap();
+        let mut sorted = names.clone();
+        sorted.sort();
+        assert_eq!(sorted, names);
+        assert_eq!(names[9], "scan_010.pdf");
+
+        assert_eq!(single.label(), "5");
+        assert_eq!(range.label(), "3-7");
+        assert_eq!(named.label(), "intro:1-3");
     }
 
     #[test]
@@ -317,4 +325,128 @@ mod tests {
         assert_eq!(segments[1].get_pages(), vec![3, 4, 5]);
         assert_eq!(segments[2].get_pages(), vec![7]);
     }
+
+    #[test]
+    fn test_named_segments_are_titled() {
+        use crate::pdf::inspect::preview_document;
+
+        let dir = std::env::temp_dir().join("pdfcutter_split_titles");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let prefix = dir.join("part").display().to_string();
+
+        let segments = parse_named_segments("intro:1,2").unwrap();
+        let outputs =
+            split_pdfs_with_segments("tests/tests_pdf/a.pdf", &prefix, &segments).unwrap();
+        let entry = |output: &str, key: &str| {
+            let preview = preview_document(output).unwrap();
+            let entry = preview.metadata.into_iter().find(|(k, _)| *k == key);
+            entry.map(|(_, value)| value)
+        };
+        assert_eq!(entry(&outputs[0], "Title").as_deref(), Some("intro"));
+        assert_eq!(entry(&outputs[0], "Keywords").as_deref(), Some("1 of 2"));
+        // Unnamed segments are left untitled
+        assert_eq!(entry(&outputs[1], "Title"), None);
+    }
+
+    #[test]
+    fn test_split_reports_each_segment() {
+        use crate::pdf::progress::watch_progress;
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let seen = Rc::clone(&events);
+        watch_progress(move |event| seen.borrow_mut().push(event.clone()));
+        let dir = std::env::temp_dir().join("pdfcutter_split_progress");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let prefix = dir.join("part").display().to_string();
+
+        let outputs = split_pdfs_named("tests/tests_pdf/c.pdf", &prefix, "intro:1-3,4").unwrap();
+        assert_eq!(
+            *events.borrow(),
+            vec![
+                Progress::Segment {
+                    index: 1,
+                    total: 2,
+                    name: Some("intro".to_string()),
+                    output: outputs[0].clone(),
+                    pages: 3,
+                },
+                Progress::Segment {
+                    index: 2,
+                    total: 2,
+                    name: None,
+                    output: outputs[1].clone(),
+                    pages: 1,
+                },
+            ]
+        );
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_split_refuses_segments_sharing_a_file() {
+        let dir = std::env::temp_dir().join("pdfcutter_split_shared");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let prefix = dir.join("part").display().to_string();
+
+        // The second unnamed segment is also numbered 002
+        let error = split_pdfs_named("tests/tests_pdf/a.pdf", &prefix, "002:1,2").unwrap_err();
+        assert!(error.to_string().contains("'002:1' and '2'"), "{}", error);
+        let error = split_pdfs_named("tests/tests_pdf/a.pdf", &prefix, "a:1,a:2").unwrap_err();
+        assert!(error.to_string().contains("part_a.pdf"), "{}", error);
+        // Refused before anything is written
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+
+        assert_eq!(
+            segment_filenames(&parse_named_segments("a:1,b:2,3").unwrap(), "x").unwrap(),
+            vec!["x_a.pdf", "x_b.pdf", "x_003.pdf"]
+        );
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_find_overlap() {
+        assert_eq!(
+            find_overlap(&parse_page_segments("1-4,5,6-9").unwrap()),
+            None
+        );
+
+        let overlap = find_overlap(&parse_page_segments("1-4,8,3-6,5").unwrap()).unwrap();
+        assert_eq!((overlap.first, overlap.second), (0, 2));
+        assert_eq!(overlap.pages, vec![3, 4]);
+
+        // A segment typed twice
+        let segments = parse_named_segments("intro:1-2,body:3-9,intro2:1-2").unwrap();
+        let overlap = find_overlap(&segments).unwrap();
+        assert_eq!((overlap.first, overlap.second), (0, 2));
+        assert_eq!(overlap.pages, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_find_shared_output() {
+        let segments = parse_named_segments("intro:1-2,3,body:4-9").unwrap();
+        assert_eq!(find_shared_output(&segments, "book"), None);
+
+        // Found whether the pages overlap or not
+        for spec in ["a:1-3,a:3-5", "a:1-2,a:3-5"] {
+            assert_eq!(
+                find_shared_output(&parse_named_segments(spec).unwrap(), "book"),
+                Some(SharedOutput {
+                    first: 0,
+                    second: 1,
+                    file: "book_a.pdf".to_string()
+                })
+            );
+        }
+        let segments = parse_named_segments("002:1,2-3").unwrap();
+        assert!(find_overlap(&segments).is_none());
+        assert_eq!(
+            find_shared_output(&segments, "p").unwrap().file,
+            "p_002.pdf"
+        );
+    }
 }
@@ -0,0 +1,472 @@
+use super::extract::page_ids;
+use super::metadata::apply_output_metadata;
+use super::output::{object_streams, temporary_path};
+use super::timings::{Phase, measure};
+use super::utils::{PageCopier, move_into_place};
+use crate::i18n::tr;
+use anyhow::{Context, Result};
+use lopdf::{Dictionary, Document, Object, ObjectId, Stream, StringFormat, dictionary};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+/// Marks the file as binary for transfer programs, as lopdf does.
+const BINARY_MARK: &[u8] = b"%\xBB\xAD\xC0\xDE\n";
+
+/// Most objects packed into one object stream, as lopdf packs them.
+const MAX_PACKED_OBJECTS: usize = 100;
+
+/// Bytes of the type and generation fields of a cross-reference stream entry.
+const XREF_TYPE_WIDTH: usize = 1;
+const XREF_GENERATION_WIDTH: usize = 2;
+
+/**
+ * An output written while it is built. The streams of copied pages (contents, images, fonts...)
+ * are written to the file as soon as they are copied, and only the other objects are kept in
+ * the target document until it is finished, so the copies of large inputs are never all held
+ * in memory. Objects are numbered from the target, so objects added to it never clash with
+ * those already written.
+ */
+pub struct StreamingOutput {
+    path: String,
+    temp: PathBuf,
+    /// None once the file is closed.
+    file: Option<BufWriter<File>>,
+    position: u64,
+    offsets: BTreeMap<u32, (u64, u16)>,
+    /// The object stream and index of each packed object.
+    packed: BTreeMap<u32, (u32, u16)>,
+}
+
+impl StreamingOutput {
+    /**
+     * Start writing an output, to a temporary file renamed to its path once finished.
+     * @param path The output file path.
+     * @param version The PDF version of the output.
+     * @returns The output, with its header written.
+     * @throws anyhow::Error if the file cannot be created.
+     */
+    pub fn create(path: &str, version: &str) -> Result<Self> {
+        let temp = temporary_path(path);
+        let file =
+            File::create(&temp).with_context(|| tr!("Failed to save PDF '{path}'", path = path))?;
+        let mut output = StreamingOutput {
+            path: path.to_string(),
+            temp,
+            file: Some(BufWriter::new(file)),
+            position: 0,
+            offsets: BTreeMap::new(),
+            packed: BTreeMap::new(),
+        };
+        output
+            .emit(format!("%PDF-{}\n", version).as_bytes())
+            .and_then(|_| output.emit(BINARY_MARK))
+            .with_context(|| tr!("Failed to save PDF '{path}'", path = path))?;
+        Ok(output)
+    }
+
+    /**
+     * Copy pages of a document into the target of a copier, writing their streams out now.
+     * @param copier The copier building the output, the other objects of the copies going into
+     * its target.
+     * @param source The document to copy from.
+     * @param name The name the source is known by in the copier, such as its path.
+     * @param pages The pages to copy (1-based), in order.
+     * @returns The ids of the copied pages in the target, to put in its page tree.
+     * @throws anyhow::Error if a page does not exist, the streams cannot be written or the
+     * operation is cancelled between two pages.
+     */
+    pub fn copy_pages(
+        &mut self,
+        copier: &mut PageCopier,
+        source: &Document,
+        name: &str,
+        pages: &[u32],
+    ) -> Result<Vec<ObjectId>> {
+        let page_ids = page_ids(source, pages)?;
+        copier
+            .copy_pages_with(source, name, &page_ids, |_, id, stream, dict| {
+                Ok::<_, anyhow::Error>(self.write_stream(id, dict, &stream.content)?)
+            })
+            .with_context(|| tr!("Failed to save PDF '{path}'", path = self.path))
+    }
+
+    /**
+     * Write the objects of the target, the small ones packed into object streams unless they are
+     * turned off (see object_streams), the cross-reference stream and the trailer, then rename
+     * the file to its path. The entries of set_output_metadata are set first.
+     * @param target The document the pages were copied into, with its catalog.
+     * @returns The size of the output in bytes.
+     * @throws anyhow::Error if the file cannot be written or renamed.
+     */
+    pub fn finish(mut self, target: &mut Document) -> Result<u64> {
+        apply_output_metadata(target);
+        measure(Phase::Save, || {
+            self.write_document(target, object_streams())
+        })
+        .with_context(|| tr!("Failed to save PDF '{path}'", path = self.path))?;
+        // Closed before the rename, which Windows refuses on an open file
+        self.file = None;
+        move_into_place(&self.temp, &self.path)
+    }
+
+    fn write_document(&mut self, target: &mut Document, object_streams: bool) -> io::Result<()> {
+        // Only objects of generation 0 that are not streams can be packed
+        let packable = |id: &ObjectId, object: &Object| {
+            object_streams && id.1 == 0 && !matches!(object, Object::Stream(_))
+        };
+        let packed_count = target
+            .objects
+            .iter()
+            .filter(|(id, object)| packable(id, object))
+            .count();
+        let stream_ids: Vec<ObjectId> = (0..packed_count.div_ceil(MAX_PACKED_OBJECTS))
+            .map(|_| target.new_object_id())
+            .collect();
+        let mut packed = Vec::new();
+        for (&id, object) in &target.objects {
+            match object {
+                Object::Stream(stream) => {
+                    self.write_stream(id, stream.dict.clone(), &stream.content)?
+                }
+                _ if packable(&id, object) => packed.push((id.0, object)),
+                _ => self.write_object(id, object)?,
+            }
+        }
+        for (objects, &stream_id) in packed.chunks(MAX_PACKED_OBJECTS).zip(&stream_ids) {
+            self.write_object_stream(stream_id, objects)?;
+        }
+
+        let xref_id = target.new_object_id();
+        let xref_start = self.position;
+        self.offsets.insert(xref_id.0, (xref_start, 0));
+        let widest = xref_start.max(xref_id.0 as u64);
+        let offset_width = (u64::BITS - widest.leading_zeros()).div_ceil(8).max(1) as usize;
+        let mut entries = Vec::new();
+        for number in 0..=xref_id.0 {
+            match (self.offsets.get(&number), self.packed.get(&number)) {
+                (Some(&(offset, generation)), _) => {
+                    push_field(&mut entries, 1, XREF_TYPE_WIDTH);
+                    push_field(&mut entries, offset, offset_width);
+                    push_field(&mut entries, generation as u64, XREF_GENERATION_WIDTH);
+                }
+                (None, Some(&(stream, index))) => {
+                    push_field(&mut entries, 2, XREF_TYPE_WIDTH);
+                    push_field(&mut entries, stream as u64, offset_width);
+                    push_field(&mut entries, index as u64, XREF_GENERATION_WIDTH);
+                }
+                (None, None) => {
+                    let generation = if number == 0 { u16::MAX } else { 0 };
+                    push_field(&mut entries, 0, XREF_TYPE_WIDTH);
+                    push_field(&mut entries, 0, offset_width);
+                    push_field(&mut entries, generation as u64, XREF_GENERATION_WIDTH);
+                }
+            }
+        }
+        let mut trailer = target.trailer.clone();
+        for key in [b"Prev".as_slice(), b"XRefStm", b"Index", b"Filter"] {
+            trailer.remove(key);
+        }
+        trailer.set("Type", "XRef");
+        trailer.set("Size", xref_id.0 as i64 + 1);
+        trailer.set(
+            "W",
+            vec![
+                Object::from(XREF_TYPE_WIDTH as i64),
+                Object::from(offset_width as i64),
+                Object::from(XREF_GENERATION_WIDTH as i64),
+            ],
+        );
+        self.write_stream(xref_id, trailer, &entries)?;
+        self.offsets.remove(&xref_id.0);
+        self.emit(format!("startxref\n{}\n%%EOF\n", xref_start).as_bytes())?;
+        match self.file.as_mut() {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Pack objects into a compressed object stream, after the numbers and offsets of each.
+    fn write_object_stream(&mut self, id: ObjectId, objects: &[(u32, &Object)]) -> io::Result<()> {
+        let mut content = Vec::new();
+        let mut body = Vec::new();
+        for (index, &(number, object)) in objects.iter().enumerate() {
+            content.extend_from_slice(format!("{} {} ", number, body.len()).as_bytes());
+            write_object(&mut body, object);
+            body.push(b'\n');
+            self.packed.insert(number, (id.0, index as u16));
+        }
+        let first = content.len();
+        content.extend_from_slice(&body);
+        let dict = dictionary! {
+            "Type" => "ObjStm",
+            "N" => objects.len() as i64,
+            "First" => first as i64,
+        };
+        let mut stream = Stream::new(dict, content);
+        stream.compress().map_err(io::Error::other)?;
+        self.write_stream(id, stream.dict, &stream.content)
+    }
+
+    /// Write an indirect object that is not a stream.
+    fn write_object(&mut self, id: ObjectId, object: &Object) -> io::Result<()> {
+        let mut bytes = format!("{} {} obj\n", id.0, id.1).into_bytes();
+        write_object(&mut bytes, object);
+        bytes.extend_from_slice(b"\nendobj\n");
+        self.offsets.insert(id.0, (self.position, id.1));
+        self.emit(&bytes)
+    }
+
+    /// Write a stream from its dictionary and content, its /Length set from the content.
+    fn write_stream(
+        &mut self,
+        id: ObjectId,
+        mut dict: Dictionary,
+        content: &[u8],
+    ) -> io::Result<()> {
+        dict.set("Length", content.len() as i64);
+        let mut bytes = format!("{} {} obj\n", id.0, id.1).into_bytes();
+        write_dictionary(&mut bytes, &dict);
+        bytes.extend_from_slice(b"stream\n");
+        self.offsets.insert(id.0, (self.position, id.1));
+        self.emit(&bytes)?;
+        self.emit(content)?;
+        self.emit(b"\nendstream\nendobj\n")
+    }
+
+    fn emit(&mut self, bytes: &[u8]) -> io::Result<()> {
+        let Some(file) = self.file.as_mut() else {
+            return Err(io::Error::other("output already closed"));
+        };
+        file.write_all(bytes)?;
+        self.position += bytes.len() as u64;
+        Ok(())
+    }
+}
+
+impl Drop for StreamingOutput {
+    /// An output dropped before being finished is removed, so no partial file is left.
+    fn drop(&mut self) {
+        if self.file.take().is_some() {
+            let _ = fs::remove_file(&self.temp);
+        }
+    }
+}
+
+/// Append a big-endian field of a cross-reference stream entry.
+fn push_field(entries: &mut Vec<u8>, value: u64, width: usize) {
+    entries.extend_from_slice(&value.to_be_bytes()[8 - width..]);
+}
+
+/// Whether an object must be separated by a space from what it follows.
+fn needs_separator(object: &Object) -> bool {
+    matches!(
+        object,
+        Object::Null
+            | Object::Boolean(_)
+            | Object::Integer(_)
+            | Object::Real(_)
+            | Object::Reference(_)
+    )
+}
+
+/**
+ * Serialize a direct object in PDF syntax, as lopdf writes it. Streams are written by
+ * write_stream, a stream given here only has its dictionary written.
+ * @param bytes Where the object is written.
+ * @param object The object.
+ */
+fn write_object(bytes: &mut Vec<u8>, object: &Object) {
+    match object {
+        Object::Null => bytes.extend_from_slice(b"null"),
+        Object::Boolean(value) => bytes.extend_from_slice(value.to_string().as_bytes()),
+        Object::Integer(value) => bytes.extend_from_slice(value.to_string().as_bytes()),
+        Object::Real(value) => bytes.extend_from_slice(value.to_string().as_bytes()),
+        Object::Name(name) => write_name(bytes, name),
+        Object::String(text, StringFormat::Literal) => write_literal_string(bytes, text),
+        Object::String(text, StringFormat::Hexadecimal) => {
+            bytes.push(b'<');
+            for byte in text {
+                bytes.extend_from_slice(format!("{:02X}", byte).as_bytes());
+            }
+            bytes.push(b'>');
+        }
+        Object::Array(items) => {
+            bytes.push(b'[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 && needs_separator(item) {
+                    bytes.push(b' ');
+                }
+                write_object(bytes, item);
+            }
+            bytes.push(b']');
+        }
+        Object::Dictionary(dict) => write_dictionary(bytes, dict),
+        Object::Stream(stream) => write_dictionary(bytes, &stream.dict),
+        Object::Reference(id) => bytes.extend_from_slice(format!("{} {} R", id.0, id.1).as_bytes()),
+    }
+}
+
+fn write_dictionary(bytes: &mut Vec<u8>, dict: &Dictionary) {
+    bytes.extend_from_slice(b"<<");
+    for (key, value) in dict.iter() {
+        write_name(bytes, key);
+        if needs_separator(value) {
+            bytes.push(b' ');
+        }
+        write_object(bytes, value);
+    }
+    bytes.extend_from_slice(b">>");
+}
+
+/// Write a name, with white-space, delimiters and bytes outside ! to ~ as # sequences.
+fn write_name(bytes: &mut Vec<u8>, name: &[u8]) {
+    bytes.push(b'/');
+    for &byte in name {
+        if b" \t\n\r\x0C()<>[]{}/%#".contains(&byte) || !(33..=126).contains(&byte) {
+            bytes.extend_from_slice(format!("#{:02X}", byte).as_bytes());
+        } else {
+            bytes.push(byte);
+        }
+    }
+}
+
+/// Write a literal string, escaping backslashes, carriage returns and every parenthesis.
+fn write_literal_string(bytes: &mut Vec<u8>, text: &[u8]) {
+    bytes.push(b'(');
+    for &byte in text {
+        match byte {
+            b'(' | b')' | b'\\' => bytes.extend_from_slice(&[b'\\', byte]),
+            b'\r' => bytes.extend_from_slice(b"\\r"),
+            _ => bytes.push(byte),
+        }
+    }
+    bytes.push(b')');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::links::{LinkTarget, find_links};
+    use crate::pdf::text::page_text;
+    use crate::pdf::utils::create_pages_structure;
+    use lopdf::dictionary;
+
+    fn write_pages(sources: &[(&Document, &[u32])], output: &str) -> Document {
+        let mut copier = PageCopier::new(Document::with_version("1.5"));
+        let mut writer = StreamingOutput::create(output, "1.5").unwrap();
+        let mut pages = Vec::new();
+        for (index, (source, numbers)) in sources.iter().enumerate() {
+            let name = index.to_string();
+            pages.extend(
+                writer
+                    .copy_pages(&mut copier, source, &name, numbers)
+                    .unwrap(),
+            );
+        }
+        create_pages_structure(copier.target_mut(), &pages).unwrap();
+        writer.finish(copier.target_mut()).unwrap();
+        assert!(!temporary_path(output).exists());
+        let written = Document::load(output).unwrap();
+        std::fs::remove_file(output).unwrap();
+        written
+    }
+
+    #[test]
+    fn test_streamed_pages_keep_their_content() {
+        let output = std::env::temp_dir().join("pdfcutter_streaming_pages.pdf");
+        let a = Document::load("tests/tests_pdf/a.pdf").unwrap();
+        let c = Document::load("tests/tests_pdf/c.pdf").unwrap();
+        let a_pages: Vec<u32> = a.get_pages().into_keys().collect();
+        let written = write_pages(&[(&a, &a_pages), (&c, &[5, 2])], &output.to_string_lossy());
+
+        let count = a_pages.len() as u32;
+        assert_eq!(written.get_pages().len() as u32, count + 2);
+        for &page in &a_pages {
+            assert_eq!(page_text(&written, page), page_text(&a, page));
+        }
+        assert_eq!(page_text(&written, count + 1), page_text(&c, 5));
+        assert_eq!(page_text(&written, count + 2), page_text(&c, 2));
+    }
+
+    #[test]
+    fn test_streamed_pages_leave_other_pages_out() {
+        let output = std::env::temp_dir().join("pdfcutter_streaming_links.pdf");
+        let mut c = Document::load("tests/tests_pdf/c.pdf").unwrap();
+        let pages = c.get_pages();
+        // The media box is inherited from the page tree
+        let media_box = c.get_dictionary_mut(pages[&1]).unwrap().remove(b"MediaBox");
+        let tree = c.get_dictionary(pages[&1]).unwrap().get(b"Parent").unwrap();
+        let tree = tree.as_reference().unwrap();
+        c.get_dictionary_mut(tree)
+            .unwrap()
+            .set("MediaBox", media_box.unwrap());
+        let link = |page: u32| {
+            Object::Dictionary(dictionary! {
+                "Subtype" => "Link",
+                "Dest" => vec![pages[&page].into(), "Fit".into()],
+            })
+        };
+        c.get_dictionary_mut(pages[&1])
+            .unwrap()
+            .set("Annots", vec![link(3), link(5)]);
+
+        let written = write_pages(&[(&c, &[1, 3])], &output.to_string_lossy());
+        let page_objects = written
+            .objects
+            .values()
+            .filter(|object| object.type_name().is_ok_and(|name| name == b"Page"))
+            .count();
+        assert_eq!(page_objects, 2);
+        let first = written.get_pages()[&1];
+        assert!(written.get_dictionary(first).unwrap().has(b"MediaBox"));
+        let targets: Vec<LinkTarget> = find_links(&written).into_iter().map(|l| l.target).collect();
+        assert_eq!(
+            targets,
+            vec![LinkTarget::Page(Some(2)), LinkTarget::Page(None)]
+        );
+    }
+
+    #[test]
+    fn test_small_objects_are_packed() {
+        let output = std::env::temp_dir().join("pdfcutter_streaming_packed.pdf");
+        let output = output.to_string_lossy();
+        let c = Document::load("tests/tests_pdf/c.pdf").unwrap();
+        let mut copier = PageCopier::new(Document::with_version("1.5"));
+        let mut writer = StreamingOutput::create(&output, "1.5").unwrap();
+        let pages = writer
+            .copy_pages(&mut copier, &c, "c.pdf", &[1, 2, 3])
+            .unwrap();
+        let target = copier.target_mut();
+        create_pages_structure(target, &pages).unwrap();
+        writer.write_document(target, true).unwrap();
+        writer.file = None;
+
+        let bytes = std::fs::read(temporary_path(&output)).unwrap();
+        let written = Document::load_mem(&bytes).unwrap();
+        let object_streams = written
+            .objects
+            .values()
+            .filter(|object| object.type_name().is_ok_and(|name| name == b"ObjStm"))
+            .count();
+        assert!(object_streams > 0);
+        // The packed page dictionaries are found through the cross-reference stream
+        assert_eq!(written.get_pages().len(), 3);
+        assert_eq!(page_text(&written, 2), page_text(&c, 2));
+    }
+
+    #[test]
+    fn test_unfinished_output_is_removed() {
+        let output = std::env::temp_dir().join("pdfcutter_streaming_dropped.pdf");
+        let output = output.to_string_lossy();
+        let c = Document::load("tests/tests_pdf/c.pdf").unwrap();
+        let mut copier = PageCopier::new(Document::with_version("1.5"));
+        let mut writer = StreamingOutput::create(&output, "1.5").unwrap();
+        writer.copy_pages(&mut copier, &c, "c.pdf", &[1]).unwrap();
+        assert!(writer.copy_pages(&mut copier, &c, "c.pdf", &[8]).is_err());
+        drop(writer);
+        assert!(!temporary_path(&output).exists());
+        assert!(!std::path::Path::new(&*output).exists());
+    }
+}
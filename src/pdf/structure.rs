@@ -0,0 +1,676 @@
+use lopdf::{Dictionary, Document, Object, ObjectId, dictionary};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Deepest structure element followed, against cycles and malformed trees.
+const MAX_DEPTH: usize = 256;
+
+/// Why the accessibility tags of an input are not, or not all, in the output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagWarning {
+    /// The input is tagged, but keeping tags was not asked.
+    Dropped { path: String },
+    /// Other inputs are tagged but this one is not, so its pages are untagged in the output.
+    Untagged { path: String },
+    /// Part of the structure tree of the input could not be followed or copied.
+    Partial { path: String },
+}
+
+/**
+ * Whether a document is tagged: its catalog has a structure tree.
+ * @param doc The document.
+ */
+pub fn is_tagged(doc: &Document) -> bool {
+    doc.catalog()
+        .is_ok_and(|catalog| catalog.has(b"StructTreeRoot"))
+}
+
+/**
+ * Gathers the structure trees of the inputs of an operation into the output, pruned to the
+ * pages copied. Inputs are added as their pages are copied, then the tree is written once the
+ * page tree and catalog of the output exist.
+ */
+#[derive(Debug, Default)]
+pub struct StructureBuilder {
+    /// Whether the tags are copied at all; when not, tagged inputs only give a warning.
+    keep: bool,
+    /// The structure tree root of the output, reserved when the first tagged input is added.
+    root_id: Option<ObjectId>,
+    /// The top-level elements, from every input in order.
+    kids: Vec<Object>,
+    role_map: Dictionary,
+    class_map: Dictionary,
+    /// Elements with an /ID, by their ID, for the IDTree.
+    ids: BTreeMap<Vec<u8>, ObjectId>,
+    /// Entries of the parent tree, with consecutive keys from 0.
+    parent_tree: Vec<Object>,
+    lang: Option<Object>,
+    /// Inputs without a structure tree, warned about only when another input is tagged.
+    untagged: Vec<String>,
+    warnings: Vec<TagWarning>,
+}
+
+impl StructureBuilder {
+    /// A builder that copies the tags of the inputs when `keep` is true.
+    pub fn new(keep: bool) -> Self {
+        Self {
+            keep,
+            ..Self::default()
+        }
+    }
+
+    /**
+     * Copy the structure tree of an input into the output, leaving out the elements whose
+     * content is only on pages that were not copied.
+     * @param source The input document.
+     * @param path The path of the input, for warnings.
+     * @param pages Each page copied, as (page in the input, its copy in the output), in order.
+     * @param target The output document.
+     */
+    pub fn add(
+        &mut self,
+        source: &Document,
+        path: &str,
+        pages: &[(ObjectId, ObjectId)],
+        target: &mut Document,
+    ) {
+        if !self.keep {
+            if is_tagged(source) {
+                self.warnings.push(TagWarning::Dropped {
+                    path: path.to_string(),
+                });
+            }
+            return;
+        }
+        let Some(root) = struct_tree_root(source) else {
+            if is_tagged(source) {
+                self.warnings.push(TagWarning::Partial {
+                    path: path.to_string(),
+                });
+            } else {
+                self.untagged.push(path.to_string());
+            }
+            return;
+        };
+
+        let root_id = *self.root_id.get_or_insert_with(|| target.new_object_id());
+        let mut page_map = HashMap::new();
+        let mut annotation_map = HashMap::new();
+        for &(page, copy) in pages {
+            // A page copied twice keeps its tags on its first copy only
+            if page_map.contains_key(&page) {
+                continue;
+            }
+            page_map.insert(page, copy);
+            let copies = annotation_ids(target, copy);
+            for (annotation, copy) in annotation_ids(source, page).into_iter().zip(copies) {
+                annotation_map.insert(annotation, copy);
+            }
+        }
+        let mut copier = Copier {
+            source,
+            target,
+            page_map,
+            annotation_map,
+            copied: HashMap::new(),
+            visited: HashSet::new(),
+            marked: HashMap::new(),
+            annotations: Vec::new(),
+            ids: &mut self.ids,
+            lossy: false,
+        };
+
+        for kid in kids_of(source, root.get(b"K").ok()) {
+            if let Some(kid) = copier.kid(&kid, root_id, None, 0) {
+                self.kids.push(kid);
+            }
+        }
+        for (map, key) in [
+            (&mut self.role_map, b"RoleMap".as_slice()),
+            (&mut self.class_map, b"ClassMap"),
+        ] {
+            let entries = root
+                .get(key)
+                .and_then(|entries| source.dereference(entries))
+                .and_then(|(_, entries)| entries.as_dict());
+            for (name, value) in entries.into_iter().flat_map(|entries| entries.iter()) {
+                if !map.has(name) {
+                    let value = copier.value(value);
+                    map.set(name.clone(), value);
+                }
+            }
+        }
+        if self.lang.is_none() {
+            self.lang = source
+                .catalog()
+                .and_then(|catalog| catalog.get(b"Lang"))
+                .and_then(|lang| source.dereference(lang))
+                .map(|(_, lang)| lang.clone())
+                .ok();
+        }
+
+        // The parent tree is rebuilt: the keys of the input would clash with those of others
+        let Copier {
+            target,
+            marked,
+            annotations,
+            lossy,
+            ..
+        } = copier;
+        for &(_, copy) in pages {
+            let entry = marked.get(&copy).map(|marks| {
+                let length = marks.keys().next_back().map_or(0, |&mcid| mcid + 1);
+                let mut parents = vec![Object::Null; length as usize];
+                for (&mcid, &element) in marks {
+                    parents[mcid as usize] = Object::Reference(element);
+                }
+                Object::Array(parents)
+            });
+            let key = entry.is_some().then_some(self.parent_tree.len() as i64);
+            if let Ok(page) = target.get_dictionary_mut(copy) {
+                match key {
+                    Some(key) => page.set("StructParents", key),
+                    None => {
+                        page.remove(b"StructParents");
+                    }
+                }
+            }
+            self.parent_tree.extend(entry);
+            for annotation in annotation_ids(target, copy) {
+                if let Ok(annotation) = target.get_dictionary_mut(annotation) {
+                    annotation.remove(b"StructParent");
+                }
+            }
+        }
+        for (annotation, element) in annotations {
+            if let Ok(dict) = target.get_dictionary_mut(annotation) {
+                dict.set("StructParent", self.parent_tree.len() as i64);
+                self.parent_tree.push(Object::Reference(element));
+            }
+        }
+        if lossy {
+            self.warnings.push(TagWarning::Partial {
+                path: path.to_string(),
+            });
+        }
+    }
+
+    /**
+     * Write the structure tree into the output, once its catalog exists.
+     * @param target The output document.
+     * @returns Why tags of some inputs are missing from the output.
+     */
+    pub fn finish(self, target: &mut Document) -> Vec<TagWarning> {
+        let mut warnings = self.warnings;
+        let Some(root_id) = self.root_id else {
+            return warnings;
+        };
+        warnings.extend(
+            self.untagged
+                .into_iter()
+                .map(|path| TagWarning::Untagged { path }),
+        );
+
+        let numbers: Vec<Object> = self
+            .parent_tree
+            .into_iter()
+            .enumerate()
+            .flat_map(|(key, parents)| [Object::Integer(key as i64), parents])
+            .collect();
+        let mut root = dictionary! {
+            "Type" => "StructTreeRoot",
+            "K" => self.kids,
+            "ParentTreeNextKey" => (numbers.len() / 2) as i64,
+            "ParentTree" => dictionary! { "Nums" => numbers },
+        };
+        if !self.role_map.is_empty() {
+            root.set("RoleMap", self.role_map);
+        }
+        if !self.class_map.is_empty() {
+            root.set("ClassMap", self.class_map);
+        }
+        if !self.ids.is_empty() {
+            let names: Vec<Object> = self
+                .ids
+                .into_iter()
+                .flat_map(|(id, element)| [Object::string_literal(id), element.into()])
+                .collect();
+            root.set("IDTree", dictionary! { "Names" => names });
+        }
+        target.objects.insert(root_id, Object::Dictionary(root));
+
+        if let Ok(catalog_id) = target.trailer.get(b"Root").and_then(Object::as_reference)
+            && let Ok(catalog) = target.get_dictionary_mut(catalog_id)
+        {
+            catalog.set("StructTreeRoot", root_id);
+            catalog.set("MarkInfo", dictionary! { "Marked" => true });
+            if let Some(lang) = self.lang {
+                catalog.set("Lang", lang);
+            }
+        }
+        warnings
+    }
+}
+
+/// Copies the structure elements of one input into the output.
+struct Copier<'a> {
+    source: &'a Document,
+    target: &'a mut Document,
+    /// Pages of the input to their copy in the output.
+    page_map: HashMap<ObjectId, ObjectId>,
+    /// Annotations of the copied pages to their copy in the output.
+    annotation_map: HashMap<ObjectId, ObjectId>,
+    /// Other objects the elements use (attributes, classes) to their copy in the output.
+    copied: HashMap<ObjectId, ObjectId>,
+    /// Elements already met, so that a cycle is not followed forever.
+    visited: HashSet<ObjectId>,
+    /// For each page of the output, the element of each marked-content sequence (by MCID).
+    marked: HashMap<ObjectId, BTreeMap<i64, ObjectId>>,
+    /// Annotations of the output and the element they belong to.
+    annotations: Vec<(ObjectId, ObjectId)>,
+    ids: &'a mut BTreeMap<Vec<u8>, ObjectId>,
+    /// Whether some content could not be kept although its page was copied.
+    lossy: bool,
+}
+
+impl Copier<'_> {
+    /**
+     * Copy a kid of a structure element: a marked-content id, a marked-content or object
+     * reference, or another element.
+     * @param kid The kid, in the input.
+     * @param parent The copy of the element it belongs to.
+     * @param page The page its content is on, inherited from its ancestors.
+     * @param depth How deep the kid is in the tree.
+     * @returns The copied kid, or None when its content is on no copied page.
+     */
+    fn kid(
+        &mut self,
+        kid: &Object,
+        parent: ObjectId,
+        page: Option<ObjectId>,
+        depth: usize,
+    ) -> Option<Object> {
+        match kid {
+            Object::Integer(mcid) => {
+                let page = self.page_map.get(&page?).copied()?;
+                self.marked.entry(page).or_default().insert(*mcid, parent);
+                Some(Object::Integer(*mcid))
+            }
+            Object::Reference(id) => {
+                if !self.visited.insert(*id) {
+                    self.lossy = true;
+                    return None;
+                }
+                let dict = self.source.get_dictionary(*id).ok()?;
+                self.dictionary_kid(dict, parent, page, depth)
+            }
+            Object::Dictionary(dict) => self.dictionary_kid(dict, parent, page, depth),
+            _ => None,
+        }
+    }
+
+    fn dictionary_kid(
+        &mut self,
+        dict: &Dictionary,
+        parent: ObjectId,
+        page: Option<ObjectId>,
+        depth: usize,
+    ) -> Option<Object> {
+        let page = reference(dict.get(b"Pg").ok()).or(page);
+        match dict.get(b"Type").and_then(Object::as_name) {
+            Ok(b"MCR") => {
+                // Marked content of a form XObject: the copies of XObjects are not tracked
+                if dict.has(b"Stm") {
+                    self.lossy = true;
+                    return None;
+                }
+                let copy = self.page_map.get(&page?).copied()?;
+                let mcid = dict.get(b"MCID").and_then(Object::as_i64).ok()?;
+                self.marked.entry(copy).or_default().insert(mcid, parent);
+                Some(Object::Dictionary(dictionary! {
+                    "Type" => "MCR",
+                    "Pg" => copy,
+                    "MCID" => mcid,
+                }))
+            }
+            Ok(b"OBJR") => {
+                let object = reference(dict.get(b"Obj").ok())?;
+                let Some(&copy) = self.annotation_map.get(&object) else {
+                    // Only annotations are followed; other objects on a copied page are lost
+                    if page.is_some_and(|page| self.page_map.contains_key(&page)) {
+                        self.lossy = true;
+                    }
+                    return None;
+                };
+                self.annotations.push((copy, parent));
+                let mut reference = dictionary! { "Type" => "OBJR", "Obj" => copy };
+                if let Some(&page) = page.and_then(|page| self.page_map.get(&page)) {
+                    reference.set("Pg", page);
+                }
+                Some(Object::Dictionary(reference))
+            }
+            _ => self
+                .element(dict, parent, page, depth + 1)
+                .map(Object::Reference),
+        }
+    }
+
+    /**
+     * Copy a structure element and those of its kids whose content is on a copied page.
+     * @returns The id of the copy, or None when none of its content was copied.
+     */
+    fn element(
+        &mut self,
+        dict: &Dictionary,
+        parent: ObjectId,
+        page: Option<ObjectId>,
+        depth: usize,
+    ) -> Option<ObjectId> {
+        if depth > MAX_DEPTH {
+            self.lossy = true;
+            return None;
+        }
+        let id = self.target.new_object_id();
+        let kids = kids_of(self.source, dict.get(b"K").ok());
+        let copied_kids: Vec<Object> = kids
+            .iter()
+            .filter_map(|kid| self.kid(kid, id, page, depth))
+            .collect();
+        let on_copied_page = page.is_none_or(|page| self.page_map.contains_key(&page));
+        if copied_kids.is_empty() && (!kids.is_empty() || !on_copied_page) {
+            return None;
+        }
+
+        let mut copy = Dictionary::new();
+        for (key, value) in dict.iter() {
+            if !matches!(key.as_slice(), b"K" | b"P" | b"Pg") {
+                let value = self.value(value);
+                copy.set(key.clone(), value);
+            }
+        }
+        copy.set("P", parent);
+        if let Some(page) = reference(dict.get(b"Pg").ok()).and_then(|p| self.page_map.get(&p)) {
+            copy.set("Pg", *page);
+        }
+        match <[Object; 1]>::try_from(copied_kids) {
+            Ok([kid]) => copy.set("K", kid),
+            Err(kids) => copy.set("K", kids),
+        }
+        if let Ok(Object::String(element_id, _)) = dict.get(b"ID") {
+            self.ids.insert(element_id.clone(), id);
+        }
+        self.target.objects.insert(id, Object::Dictionary(copy));
+        Some(id)
+    }
+
+    /// Copy a value of an element, such as its attributes, with the objects it refers to.
+    fn value(&mut self, value: &Object) -> Object {
+        match value {
+            Object::Reference(id) => {
+                if let Some(&page) = self.page_map.get(id) {
+                    return Object::Reference(page);
+                }
+                if let Some(&copy) = self.copied.get(id) {
+                    return Object::Reference(copy);
+                }
+                let Ok(object) = self.source.get_object(*id) else {
+                    return Object::Null;
+                };
+                let copy = self.target.new_object_id();
+                self.copied.insert(*id, copy);
+                let object = self.value(object);
+                self.target.objects.insert(copy, object);
+                Object::Reference(copy)
+            }
+            Object::Array(items) => {
+                Object::Array(items.iter().map(|item| self.value(item)).collect())
+            }
+            Object::Dictionary(dict) => Object::Dictionary(self.dictionary(dict)),
+            Object::Stream(stream) => {
+                let mut stream = stream.clone();
+                stream.dict = self.dictionary(&stream.dict);
+                Object::Stream(stream)
+            }
+            other => other.clone(),
+        }
+    }
+
+    fn dictionary(&mut self, dict: &Dictionary) -> Dictionary {
+        let mut copy = Dictionary::new();
+        for (key, value) in dict.iter() {
+            let value = self.value(value);
+            copy.set(key.clone(), value);
+        }
+        copy
+    }
+}
+
+/// The structure tree root of a document, when it is a dictionary.
+fn struct_tree_root(doc: &Document) -> Option<&Dictionary> {
+    let root = doc.catalog().ok()?.get(b"StructTreeRoot").ok()?;
+    doc.dereference(root).ok()?.1.as_dict().ok()
+}
+
+/// The kids of a structure element: /K holds one kid or an array of them.
+fn kids_of(doc: &Document, kids: Option<&Object>) -> Vec<Object> {
+    match kids {
+        Some(Object::Array(kids)) => kids.clone(),
+        Some(Object::Reference(id)) => match doc.get_object(*id) {
+            Ok(Object::Array(kids)) => kids.clone(),
+            _ => vec![Object::Reference(*id)],
+        },
+        Some(kid) => vec![kid.clone()],
+        None => Vec::new(),
+    }
+}
+
+/// The referenced annotations of a page, in the order of its /Annots.
+fn annotation_ids(doc: &Document, page: ObjectId) -> Vec<ObjectId> {
+    doc.get_dictionary(page)
+        .and_then(|page| page.get(b"Annots"))
+        .and_then(|annotations| doc.dereference(annotations))
+        .and_then(|(_, annotations)| annotations.as_array())
+        .map(|annotations| {
+            annotations
+                .iter()
+                .filter_map(|annotation| annotation.as_reference().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn reference(object: Option<&Object>) -> Option<ObjectId> {
+    object?.as_reference().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::delete::delete_pages_with_tags;
+    use crate::pdf::merge::{MergeInput, MergeOptions, merge_inputs};
+
+    /// Tag c.pdf: a Document with one paragraph per page, the third one a link to page 4.
+    fn tagged_pdf(name: &str) -> String {
+        let mut doc = Document::load("tests/tests_pdf/c.pdf").unwrap();
+        let pages = doc.get_pages();
+        let root_id = doc.new_object_id();
+        let document_id = doc.new_object_id();
+        let link = doc.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Link",
+            "Rect" => vec![0.into(), 0.into(), 10.into(), 10.into()],
+            "Dest" => vec![pages[&4].into(), "Fit".into()],
+            "StructParent" => 100,
+        });
+        doc.get_dictionary_mut(pages[&3])
+            .unwrap()
+            .set("Annots", vec![link.into()]);
+        let mut paragraphs = Vec::new();
+        for (&number, &page) in &pages {
+            let mut kids = vec![Object::Integer(0)];
+            if number == 3 {
+                kids.push(dictionary! { "Type" => "OBJR", "Obj" => link }.into());
+            }
+            let mut paragraph = dictionary! {
+                "Type" => "StructElem",
+                "S" => "P",
+                "P" => document_id,
+                "Pg" => page,
+                "K" => kids,
+            };
+            if number == 5 {
+                paragraph.set("ID", Object::string_literal("p5"));
+            }
+            paragraphs.push(Object::Reference(doc.add_object(paragraph)));
+            doc.get_dictionary_mut(page)
+                .unwrap()
+                .set("StructParents", number as i64 - 1);
+        }
+        doc.objects.insert(
+            document_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "StructElem",
+                "S" => "Document",
+                "P" => root_id,
+                "K" => paragraphs,
+            }),
+        );
+        doc.objects.insert(
+            root_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "StructTreeRoot",
+                "K" => document_id,
+                "RoleMap" => dictionary! { "Para" => "P" },
+            }),
+        );
+        let catalog_id = doc.trailer.get(b"Root").unwrap().as_reference().unwrap();
+        let catalog = doc.get_dictionary_mut(catalog_id).unwrap();
+        catalog.set("StructTreeRoot", root_id);
+        catalog.set("Lang", Object::string_literal("fr-FR"));
+
+        let path = std::env::temp_dir().join(name);
+        doc.save(&path).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    /// The paragraphs of the output and the page number each is on.
+    fn paragraph_pages(doc: &Document) -> Vec<u32> {
+        let numbers: HashMap<ObjectId, u32> = doc
+            .get_pages()
+            .into_iter()
+            .map(|(number, id)| (id, number))
+            .collect();
+        let root = struct_tree_root(doc).unwrap();
+        let mut pages = Vec::new();
+        for document in kids_of(doc, root.get(b"K").ok()) {
+            let document = doc
+                .get_dictionary(document.as_reference().unwrap())
+                .unwrap();
+            for paragraph in kids_of(doc, document.get(b"K").ok()) {
+                let paragraph = doc
+                    .get_dictionary(paragraph.as_reference().unwrap())
+                    .unwrap();
+                let page = paragraph.get(b"Pg").unwrap().as_reference().unwrap();
+                pages.push(numbers[&page]);
+            }
+        }
+        pages
+    }
+
+    #[test]
+    fn test_delete_keeps_pruned_tags() {
+        let input = tagged_pdf("pdfcutter_structure_tagged.pdf");
+        let output = std::env::temp_dir().join("pdfcutter_structure_deleted.pdf");
+        let output = output.to_string_lossy();
+
+        let warnings = delete_pages_with_tags(&input, &output, &[2, 4], true).unwrap();
+        assert_eq!(warnings, Vec::new());
+        let doc = Document::load(&*output).unwrap();
+        assert_eq!(paragraph_pages(&doc), vec![1, 2, 3, 4, 5]);
+        let catalog = doc.catalog().unwrap();
+        assert_eq!(catalog.get(b"Lang").unwrap().as_str().unwrap(), b"fr-FR");
+
+        // Every page and the link have their own entry in the rebuilt parent tree
+        let root = struct_tree_root(&doc).unwrap();
+        assert_eq!(root.get(b"ParentTreeNextKey").unwrap().as_i64().unwrap(), 6);
+        let pages = doc.get_pages();
+        let keys: Vec<i64> = pages
+            .values()
+            .map(|&page| {
+                let page = doc.get_dictionary(page).unwrap();
+                page.get(b"StructParents").unwrap().as_i64().unwrap()
+            })
+            .collect();
+        assert_eq!(keys, vec![0, 1, 2, 3, 4]);
+        let link = annotation_ids(&doc, pages[&2])[0];
+        let link = doc.get_dictionary(link).unwrap();
+        assert_eq!(link.get(b"StructParent").unwrap().as_i64().unwrap(), 5);
+        assert!(root.has(b"IDTree") && root.has(b"RoleMap"));
+
+        // Without --keep-tags the tags are dropped, with a warning
+        let warnings = delete_pages_with_tags(&input, &output, &[2], false).unwrap();
+        assert_eq!(
+            warnings,
+            vec![TagWarning::Dropped {
+                path: input.clone()
+            }]
+        );
+        assert!(!is_tagged(&Document::load(&*output).unwrap()));
+
+        // The test files come tagged from their producer
+        let real = "tests/tests_pdf/c.pdf";
+        assert_eq!(
+            delete_pages_with_tags(real, &output, &[2, 5], true).unwrap(),
+            Vec::new()
+        );
+        assert!(is_tagged(&Document::load(&*output).unwrap()));
+
+        std::fs::remove_file(&input).unwrap();
+        std::fs::remove_file(&*output).unwrap();
+    }
+
+    #[test]
+    fn test_merge_keeps_tags() {
+        let input = tagged_pdf("pdfcutter_structure_merge.pdf");
+        let output = std::env::temp_dir().join("pdfcutter_structure_merged.pdf");
+        let output = output.to_string_lossy();
+        let untagged = std::env::temp_dir().join("pdfcutter_structure_untagged.pdf");
+        let untagged = untagged.to_string_lossy();
+        let mut doc = Document::load("tests/tests_pdf/b.pdf").unwrap();
+        let catalog_id = doc.trailer.get(b"Root").unwrap().as_reference().unwrap();
+        doc.get_dictionary_mut(catalog_id)
+            .unwrap()
+            .remove(b"StructTreeRoot");
+        doc.save(&*untagged).unwrap();
+        let inputs = [
+            MergeInput {
+                pages: Some(vec![7, 1]),
+                ..MergeInput::new(&input)
+            },
+            MergeInput::new(&untagged),
+            MergeInput {
+                pages: Some(vec![3]),
+                ..MergeInput::new(&input)
+            },
+        ];
+        let options = MergeOptions {
+            keep_tags: true,
+            ..MergeOptions::default()
+        };
+
+        let warnings = merge_inputs(&inputs, &options, &output).unwrap();
+        assert_eq!(
+            warnings,
+            vec![TagWarning::Untagged {
+                path: untagged.to_string()
+            }]
+        );
+        let doc = Document::load(&*output).unwrap();
+        let last = doc.get_pages().len() as u32;
+        // One Document element per tagged input, in merge order, with the pages taken
+        assert_eq!(paragraph_pages(&doc), vec![2, 1, last]);
+
+        std::fs::remove_file(&input).unwrap();
+        std::fs::remove_file(&*untagged).unwrap();
+        std::fs::remove_file(&*output).unwrap();
+    }
+}
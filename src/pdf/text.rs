@@ -0,0 +1,29 @@
+use lopdf::Document;
+
+/**
+ * Extract the text of a page, with runs of whitespace collapsed to single spaces.
+ * Only text drawn with simple fonts is found; scanned pages have none.
+ * @param doc The loaded document.
+ * @param page_number The page number (1-based).
+ * @returns The text, or None when the page has no extractable text.
+ */
+pub fn page_text(doc: &Document, page_number: u32) -> Option<String> {
+    let text = doc.extract_text(&[page_number]).ok()?;
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!text.is_empty()).then_some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_text() {
+        let doc = Document::load("tests/tests_pdf/a.pdf").unwrap();
+        let text = page_text(&doc, 1).unwrap();
+        assert!(text.starts_with("Lorem ipsum dolor sit amet"));
+        assert!(!text.contains("  "));
+        assert!(!text.contains('\n'));
+        assert_eq!(page_text(&doc, 999), None);
+    }
+}
@@ -0,0 +1,110 @@
+use crate::i18n::tr;
+use anyhow::{Context, Result, anyhow};
+use hayro::hayro_interpret::InterpreterSettings;
+use hayro::hayro_syntax::Pdf;
+use hayro::vello_cpu::color::palette::css::WHITE;
+use hayro::{PixmapSettings, RenderCache, RenderSettings, render};
+
+/// A page rendered to pixels, for the page previews of the TUI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Thumbnail {
+    pub width: u32,
+    pub height: u32,
+    /// RGBA pixels, row by row from the top.
+    pub rgba: Vec<u8>,
+}
+
+/// A document opened once to render several of its pages.
+pub struct ThumbnailRenderer {
+    pdf: Pdf,
+}
+
+impl ThumbnailRenderer {
+    /**
+     * Read a document for rendering.
+     * @param path The PDF file.
+     * @returns The renderer of its pages.
+     * @throws Error when the file cannot be read or parsed, or is protected by a password.
+     */
+    pub fn open(path: &str) -> Result<Self> {
+        let data =
+            std::fs::read(path).with_context(|| tr!("Failed to read '{path}'", path = path))?;
+        let pdf = Pdf::new(data)
+            .map_err(|e| anyhow!("{:?}", e))
+            .with_context(|| tr!("Failed to load PDF '{path}'", path = path))?;
+        Ok(Self { pdf })
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pdf.pages().len()
+    }
+
+    /**
+     * Render a page on a white background, as large as fits in the given box while keeping its
+     * proportions, as displayed by a viewer.
+     * @param page The page number (1-based).
+     * @param max_width The width of the box, in pixels.
+     * @param max_height The height of the box, in pixels.
+     * @returns The rendered page.
+     * @throws Error when the page does not exist.
+     */
+    pub fn render(&self, page: u32, max_width: u32, max_height: u32) -> Result<Thumbnail> {
+        let page_count = self.page_count();
+        let page = (page as usize)
+            .checked_sub(1)
+            .and_then(|index| self.pdf.pages().get(index))
+            .ok_or_else(|| {
+                anyhow!(tr!(
+                    "Page {page} is out of range (document has {page_count} pages)",
+                    page = page,
+                    page_count = page_count
+                ))
+            })?;
+
+        let (width, height) = page.render_dimensions();
+        let scale = (max_width as f32 / width).min(max_height as f32 / height);
+        let pixmap = render(
+            page,
+            &RenderCache::new(),
+            &InterpreterSettings::default(),
+            &RenderSettings::default(),
+            &PixmapSettings {
+                x_scale: scale,
+                y_scale: scale,
+                bg_color: WHITE,
+            },
+        );
+        // The background is opaque, so premultiplied pixels are plain RGBA
+        Ok(Thumbnail {
+            width: pixmap.width() as u32,
+            height: pixmap.height() as u32,
+            rgba: pixmap.data_as_u8_slice().to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_thumbnail() {
+        let renderer = ThumbnailRenderer::open("tests/tests_pdf/c.pdf").unwrap();
+        assert_eq!(renderer.page_count(), 7);
+
+        // A portrait page is as tall as the box, and narrower
+        let thumbnail = renderer.render(1, 120, 120).unwrap();
+        assert_eq!(thumbnail.height, 120);
+        assert!(thumbnail.width < 120);
+        assert_eq!(
+            thumbnail.rgba.len(),
+            (thumbnail.width * thumbnail.height * 4) as usize
+        );
+        // Something is drawn on the white page
+        assert!(thumbnail.rgba.chunks(4).any(|pixel| pixel[..3] != [255; 3]));
+
+        assert!(renderer.render(0, 120, 120).is_err());
+        assert!(renderer.render(8, 120, 120).is_err());
+        assert!(ThumbnailRenderer::open("tests/tests_pdf/missing.pdf").is_err());
+    }
+}
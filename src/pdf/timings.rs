@@ -0,0 +1,129 @@
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+/// A step of an operation whose duration is measured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Reading and parsing the input documents.
+    Load,
+    /// Copying pages and their resources into the output documents.
+    Copy,
+    /// Numbering the objects of the outputs and writing them.
+    Save,
+}
+
+/// Time spent in each phase, added up over every document an operation handles.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Timings {
+    pub load: Duration,
+    pub copy: Duration,
+    pub save: Duration,
+}
+
+thread_local! {
+    // Each thread runs its own operation, so the timings of one do not leak into another
+    static TIMINGS: Cell<Timings> = Cell::new(Timings::default());
+}
+
+/**
+ * Run a step of an operation and add its duration to the timings of the current thread.
+ * @param phase The phase the step belongs to.
+ * @param step The step to run.
+ * @returns What the step returns.
+ */
+pub fn measure<T>(phase: Phase, step: impl FnOnce() -> T) -> T {
+    let started = Instant::now();
+    let result = step();
+    let elapsed = started.elapsed();
+    TIMINGS.with(|timings| {
+        let mut total = timings.get();
+        match phase {
+            Phase::Load => total.load += elapsed,
+            Phase::Copy => total.copy += elapsed,
+            Phase::Save => total.save += elapsed,
+        }
+        timings.set(total);
+    });
+    result
+}
+
+/**
+ * Take the timings measured on the current thread, starting again from zero.
+ * @returns The time spent in each phase since the last call.
+ */
+pub fn take_timings() -> Timings {
+    TIMINGS.with(|timings| timings.take())
+}
+
+/**
+ * Format a duration for a timing report (e.g., "850 µs", "12.3 ms", "2.41 s").
+ * @param duration The duration.
+ * @returns The duration in the unit that keeps it short.
+ */
+pub fn format_elapsed(duration: Duration) -> String {
+    let micros = duration.as_micros();
+    if micros < 1000 {
+        format!("{} µs", micros)
+    } else if micros < 1_000_000 {
+        format!("{:.1} ms", micros as f64 / 1000.0)
+    } else {
+        format!("{:.2} s", duration.as_secs_f64())
+    }
+}
+
+/**
+ * Largest amount of memory the process has held so far (its peak resident set size).
+ * @returns The size in bytes, or None when it cannot be known.
+ */
+#[cfg(unix)]
+pub fn peak_memory() -> Option<u64> {
+    let mut usage = std::mem::MaybeUninit::<libc::rusage>::uninit();
+    // SAFETY: getrusage fills the struct when it returns 0
+    let usage = unsafe {
+        if libc::getrusage(libc::RUSAGE_SELF, usage.as_mut_ptr()) != 0 {
+            return None;
+        }
+        usage.assume_init()
+    };
+    let max_rss = u64::try_from(usage.ru_maxrss).ok()?;
+    // Reported in bytes on macOS, in kilobytes elsewhere
+    if cfg!(target_os = "macos") {
+        Some(max_rss)
+    } else {
+        Some(max_rss * 1024)
+    }
+}
+
+#[cfg(not(unix))]
+pub fn peak_memory() -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measure() {
+        take_timings();
+        let pages = measure(Phase::Copy, || {
+            std::thread::sleep(Duration::from_millis(2));
+            3
+        });
+        assert_eq!(pages, 3);
+        measure(Phase::Copy, || ());
+
+        let timings = take_timings();
+        assert!(timings.copy >= Duration::from_millis(2));
+        assert_eq!(timings.load, Duration::ZERO);
+        assert_eq!(take_timings(), Timings::default());
+        assert!(peak_memory().is_some_and(|bytes| bytes > 0));
+    }
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(Duration::from_micros(850)), "850 µs");
+        assert_eq!(format_elapsed(Duration::from_micros(12_340)), "12.3 ms");
+        assert_eq!(format_elapsed(Duration::from_millis(2_410)), "2.41 s");
+    }
+}
@@ -1,50 +1,339 @@
-use anyhow::Result;
-use lopdf::{Document, Object, ObjectId, dictionary};
+use super::cancel::{Cancelled, check_cancelled};
+use super::inspect::inherited_attribute;
+use super::metadata::apply_output_metadata;
+use super::output::{durable, object_streams, sync_file, sync_parent_directory, temporary_path};
+use super::passwords::{is_locked, unlock_with_password_file};
+use super::timings::{Phase, measure};
+use crate::i18n::tr;
+use anyhow::{Context, Result, bail};
+use lopdf::xref::XrefType;
+use lopdf::{Dictionary, Document, Object, ObjectId, SaveOptions, Stream, dictionary};
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::convert::Infallible;
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
 
-/// Copy a page and all its resources to the target document
-pub fn copy_page_with_resources(
-    source: &Document,
-    page_id: ObjectId,
-    target: &mut Document,
-) -> Result<ObjectId> {
-    let mut visited = HashSet::new();
-    let mut to_copy = VecDeque::new();
-    let mut id_mapping = HashMap::new();
+/// Largest PDF that can be read or written: lopdf keeps the offsets of objects on 32 bits.
+pub const MAX_PDF_SIZE: u64 = u32::MAX as u64;
 
-    // Start with the page object
-    to_copy.push_back(page_id);
+/// Flate level of the object streams of outputs, as lopdf compresses them by default.
+pub const OBJECT_STREAM_COMPRESSION: u32 = 6;
 
-    // Breadth-first traversal to collect all referenced objects
-    while let Some(current_id) = to_copy.pop_front() {
-        if visited.contains(&current_id) {
-            continue;
-        }
-        visited.insert(current_id);
+/// First PDF version with cross-reference and object streams.
+const XREF_STREAM_VERSION: &str = "1.5";
+
+/// Attributes a page can inherit from the nodes of its page tree, set on a copy itself since it
+/// is copied without them.
+const INHERITED_ATTRIBUTES: [&[u8]; 4] = [b"Resources", b"MediaBox", b"CropBox", b"Rotate"];
+
+/**
+ * Copies pages of one or more documents into a target document. Each source is known by a name,
+ * such as its path, and the copies made of its objects are kept, so the fonts, images and other
+ * objects its pages share are copied once however many times its pages are copied, and a link
+ * to a page already copied leads to its copy.
+ */
+pub struct PageCopier {
+    target: Document,
+    /// The copy of each object copied so far, by source name.
+    copies: HashMap<String, HashMap<ObjectId, ObjectId>>,
+}
 
-        if let Ok(obj) = source.get_object(current_id) {
-            // Find all object references in this object
-            collect_references(obj, &mut to_copy);
+impl PageCopier {
+    /// Start copying into a document.
+    pub fn new(target: Document) -> Self {
+        PageCopier {
+            target,
+            copies: HashMap::new(),
         }
     }
 
-    // Copy all collected objects to target document
-    for &obj_id in &visited {
-        if let Ok(obj) = source.get_object(obj_id) {
-            let new_id = target.add_object(obj.clone());
-            id_mapping.insert(obj_id, new_id);
+    /// The document pages are copied into.
+    pub fn target(&self) -> &Document {
+        &self.target
+    }
+
+    /// The document pages are copied into, to add objects of its own to it.
+    pub fn target_mut(&mut self) -> &mut Document {
+        &mut self.target
+    }
+
+    /**
+     * The copy of an object of a source, if it was copied: for a page or an annotation copied
+     * several times, its first copy.
+     * @param name The name the source is known by.
+     * @param id The object in the source.
+     */
+    pub fn copy_of(&self, name: &str, id: ObjectId) -> Option<ObjectId> {
+        self.copies.get(name)?.get(&id).copied()
+    }
+
+    /// Take the document the pages were copied into.
+    pub fn into_target(self) -> Document {
+        self.target
+    }
+
+    /**
+     * Copy pages of a source into the target.
+     * @param source The document to copy from.
+     * @param name The name the source is known by; sources given the same name must be the same
+     * document.
+     * @param page_ids The pages to copy, in order.
+     * @returns The ids of the copied pages in the target, to put in its page tree.
+     * @throws Cancelled if the operation is cancelled between two pages.
+     */
+    pub fn copy_pages(
+        &mut self,
+        source: &Document,
+        name: &str,
+        page_ids: &[ObjectId],
+    ) -> Result<Vec<ObjectId>, Cancelled> {
+        self.copy_pages_with(source, name, page_ids, |target, id, stream, dict| {
+            let Ok(()) = place_in_target(target, id, stream, dict);
+            Ok(())
+        })
+    }
+
+    /**
+     * Copy pages of a source into the target, handing their streams (contents, images, fonts...)
+     * to the caller instead of adding them to the target, such as to write them out at once. The
+     * pages are copied without their parent, with the attributes they inherit from it, and each
+     * copy of a page has annotations of its own. References to pages of the source that are not
+     * copied become null rather than copying them.
+     * @param source The document to copy from.
+     * @param name The name the source is known by; sources given the same name must be the same
+     * document.
+     * @param page_ids The pages to copy, in order.
+     * @param place_stream Called with the target, the id and the stream of each copied stream,
+     * and the dictionary of the copy, its references translated to the target.
+     * @returns The ids of the copied pages in the target, to put in its page tree.
+     * @throws The first error of place_stream, or Cancelled if the operation is cancelled between
+     * two pages, after which the target may be missing objects.
+     */
+    pub fn copy_pages_with<E: From<Cancelled>>(
+        &mut self,
+        source: &Document,
+        name: &str,
+        page_ids: &[ObjectId],
+        mut place_stream: impl FnMut(&mut Document, ObjectId, &Stream, Dictionary) -> Result<(), E>,
+    ) -> Result<Vec<ObjectId>, E> {
+        measure(Phase::Copy, || {
+            let target = &mut self.target;
+            let id_mapping = self.copies.entry(name.to_string()).or_default();
+            let mut pages = Vec::new();
+            let mut annotation_ids = HashSet::new();
+            let mut to_copy = VecDeque::new();
+            for &page_id in page_ids {
+                check_cancelled()?;
+                let copy = target.new_object_id();
+                // Links to a page copied twice lead to its first copy
+                id_mapping.entry(page_id).or_insert(copy);
+                let mut page = page_dictionary(source, page_id);
+                let mut annotations = Vec::new();
+                for id in page_annotations(source, &mut page) {
+                    if let Ok(annotation) = source.get_object(id) {
+                        collect_references(annotation, &mut to_copy);
+                        let annotation_copy = target.new_object_id();
+                        // Objects referring to an annotation, such as the form field it is a
+                        // widget of, lead to its first copy
+                        id_mapping.entry(id).or_insert(annotation_copy);
+                        annotations.push((id, annotation_copy));
+                        annotation_ids.insert(id);
+                    }
+                }
+                let page = Object::Dictionary(page);
+                collect_references(&page, &mut to_copy);
+                pages.push((page_id, copy, page, annotations));
+            }
+            copy_shared_objects(
+                source,
+                to_copy,
+                &annotation_ids,
+                target,
+                id_mapping,
+                &mut place_stream,
+            )?;
+
+            let mut copies = Vec::new();
+            for (page_id, copy, mut page, annotations) in pages {
+                check_cancelled()?;
+                // The page and its annotations are numbered for this copy alone while it is
+                // translated
+                let first_copy = id_mapping.insert(page_id, copy);
+                let first_annotations: Vec<(ObjectId, Option<ObjectId>)> = annotations
+                    .iter()
+                    .map(|&(id, annotation_copy)| (id, id_mapping.insert(id, annotation_copy)))
+                    .collect();
+                for &(id, annotation_copy) in &annotations {
+                    copy_object_as(
+                        source,
+                        id,
+                        annotation_copy,
+                        target,
+                        id_mapping,
+                        &mut place_stream,
+                    )?;
+                }
+                update_references(&mut page, id_mapping);
+                target.objects.insert(copy, page);
+                for (id, first_annotation) in first_annotations {
+                    if let Some(first_annotation) = first_annotation {
+                        id_mapping.insert(id, first_annotation);
+                    }
+                }
+                if let Some(first_copy) = first_copy {
+                    id_mapping.insert(page_id, first_copy);
+                }
+                copies.push(copy);
+            }
+            Ok(copies)
+        })
+    }
+
+    /**
+     * Copy an object that is not a page, such as an entry of the catalog, and every object it
+     * refers to that is not copied yet.
+     * @param source The document to copy from.
+     * @param name The name the source is known by.
+     * @param object The object, with its references to the source.
+     * @returns The copy, with its references to the target.
+     */
+    pub fn copy_object(&mut self, source: &Document, name: &str, object: &Object) -> Object {
+        let id_mapping = self.copies.entry(name.to_string()).or_default();
+        let mut to_copy = VecDeque::new();
+        collect_references(object, &mut to_copy);
+        let Ok(()) = copy_shared_objects(
+            source,
+            to_copy,
+            &HashSet::new(),
+            &mut self.target,
+            id_mapping,
+            &mut place_in_target,
+        );
+        let mut copy = object.clone();
+        update_references(&mut copy, id_mapping);
+        copy
+    }
+}
+
+/// Add a copied stream to the target itself.
+fn place_in_target(
+    target: &mut Document,
+    id: ObjectId,
+    stream: &Stream,
+    dict: Dictionary,
+) -> Result<(), Infallible> {
+    let mut copy = stream.clone();
+    copy.dict = dict;
+    target.objects.insert(id, Object::Stream(copy));
+    Ok(())
+}
+
+/// The dictionary of a page to copy: without its parent, with the attributes it inherits.
+fn page_dictionary(source: &Document, page_id: ObjectId) -> Dictionary {
+    let mut page = source.get_dictionary(page_id).cloned().unwrap_or_default();
+    for key in INHERITED_ATTRIBUTES {
+        if !page.has(key)
+            && let Some(value) = inherited_attribute(source, page_id, key)
+        {
+            page.set(key, value.clone());
         }
     }
+    page.remove(b"Parent");
+    page
+}
 
-    // Update all references in the copied objects
-    for &new_id in id_mapping.values() {
-        if let Ok(obj) = target.get_object_mut(new_id) {
-            update_references(obj, &id_mapping);
+/// The annotations of a page that are objects of their own, its /Annots array made direct so
+/// every copy of the page has its own.
+fn page_annotations(source: &Document, page: &mut Dictionary) -> Vec<ObjectId> {
+    let annots = match page.get(b"Annots") {
+        Ok(Object::Reference(id)) => match source.get_object(*id) {
+            Ok(annots) => annots.clone(),
+            Err(_) => return Vec::new(),
+        },
+        Ok(annots) => annots.clone(),
+        Err(_) => return Vec::new(),
+    };
+    let ids = match &annots {
+        Object::Array(items) => items
+            .iter()
+            .filter_map(|item| item.as_reference().ok())
+            .collect(),
+        _ => Vec::new(),
+    };
+    page.set("Annots", annots);
+    ids
+}
+
+/// Copy the objects referred to that are not copied yet, and everything they refer to, except
+/// the pages of the source, its page tree and the annotations copied with each page.
+fn copy_shared_objects<E>(
+    source: &Document,
+    mut to_copy: VecDeque<ObjectId>,
+    annotation_ids: &HashSet<ObjectId>,
+    target: &mut Document,
+    id_mapping: &mut HashMap<ObjectId, ObjectId>,
+    place_stream: &mut impl FnMut(&mut Document, ObjectId, &Stream, Dictionary) -> Result<(), E>,
+) -> Result<(), E> {
+    let source_pages: HashSet<ObjectId> = source.get_pages().into_values().collect();
+    // Objects in the order they are found, so the copies are numbered the same on every run
+    let mut found = Vec::new();
+    let mut visited = HashSet::new();
+    // Everything is numbered first, so references can be translated as each object is placed
+    while let Some(id) = to_copy.pop_front() {
+        if id_mapping.contains_key(&id) || annotation_ids.contains(&id) || !visited.insert(id) {
+            continue;
         }
+        let Ok(object) = source.get_object(id) else {
+            continue;
+        };
+        // Other pages and the page tree would bring the whole document along
+        let is_page_tree = object.type_name().is_ok_and(|name| name == b"Pages");
+        if source_pages.contains(&id) || is_page_tree {
+            continue;
+        }
+        collect_references(object, &mut to_copy);
+        id_mapping.insert(id, target.new_object_id());
+        found.push(id);
+    }
+    for id in found {
+        copy_object_as(
+            source,
+            id,
+            id_mapping[&id],
+            target,
+            id_mapping,
+            place_stream,
+        )?;
     }
+    Ok(())
+}
 
-    // Return the new page ID
-    Ok(id_mapping[&page_id])
+/// Place the copy of an object under its new id, its references translated.
+fn copy_object_as<E>(
+    source: &Document,
+    id: ObjectId,
+    copy: ObjectId,
+    target: &mut Document,
+    id_mapping: &HashMap<ObjectId, ObjectId>,
+    place_stream: &mut impl FnMut(&mut Document, ObjectId, &Stream, Dictionary) -> Result<(), E>,
+) -> Result<(), E> {
+    match source.get_object(id) {
+        Ok(Object::Stream(stream)) => {
+            let mut dict = stream.dict.clone();
+            for (_, value) in dict.iter_mut() {
+                update_references(value, id_mapping);
+            }
+            place_stream(target, copy, stream, dict)
+        }
+        Ok(object) => {
+            let mut object = object.clone();
+            update_references(&mut object, id_mapping);
+            target.objects.insert(copy, object);
+            Ok(())
+        }
+        Err(_) => Ok(()),
+    }
 }
 
 /// Create the Pages structure for a PDF document
@@ -67,10 +356,10 @@ pub fn create_pages_structure(target: &mut Document, page_objects: &[ObjectId])
 
     // Update all pages to reference the new Pages parent
     for &page_id in page_objects {
-        if let Ok(page_obj) = target.get_object_mut(page_id) {
-            if let Ok(page_dict) = page_obj.as_dict_mut() {
-                page_dict.set("Parent", pages_id);
-            }
+        if let Ok(page_obj) = target.get_object_mut(page_id)
+            && let Ok(page_dict) = page_obj.as_dict_mut()
+        {
+            page_dict.set("Parent", pages_id);
         }
     }
 
@@ -92,15 +381,213 @@ pub fn create_pages_structure(target: &mut Document, page_objects: &[ObjectId])
 
 /// Finalize and save the PDF document
 pub fn finalize_document(target: &mut Document, output: &str) -> Result<()> {
-    target.max_id = target.objects.len() as u32;
-    target.renumber_objects();
-    target.adjust_zero_pages();
-    target.save(output)?;
+    measure(Phase::Save, || renumber_objects(target));
+    save_document(target, output)?;
+    log::info!("Saved '{}' ({} pages)", output, target.get_pages().len());
+    Ok(())
+}
+
+/**
+ * Number the objects of a document 1, 2, 3... in the order of their current numbers, with
+ * generation 0. Unlike lopdf's renumbering, which only follows the objects reachable from the
+ * trailer, the references of every object are updated, so none is left pointing at the new
+ * number of another object.
+ * @param doc The document, whose numbers may be sparse.
+ */
+pub fn renumber_objects(doc: &mut Document) {
+    let id_mapping: HashMap<ObjectId, ObjectId> = doc
+        .objects
+        .keys()
+        .zip(1..)
+        .map(|(&id, number)| (id, (number, 0)))
+        .collect();
+    let objects = std::mem::take(&mut doc.objects);
+    doc.objects = objects
+        .into_iter()
+        .map(|(id, mut object)| {
+            update_references(&mut object, &id_mapping);
+            (id_mapping[&id], object)
+        })
+        .collect();
+    for (_, value) in doc.trailer.iter_mut() {
+        update_references(value, &id_mapping);
+    }
+    doc.max_id = doc.objects.len() as u32;
+}
+
+/// Raise the highest object number of a document to that of its objects, which the trailer of a
+/// loaded file may understate, so added objects never replace existing ones.
+fn sync_max_id(doc: &mut Document) {
+    let highest = doc.objects.keys().map(|&(number, _)| number).max();
+    doc.max_id = doc.max_id.max(highest.unwrap_or(0));
+}
+
+/**
+ * Load a PDF, refusing files too large for the offsets of their objects to be read. A file
+ * locked by a password is opened with the passwords of `--password-file`, when one is given.
+ * @param path The PDF file path.
+ * @returns The document.
+ * @throws anyhow::Error if the file cannot be loaded, is larger than MAX_PDF_SIZE or is locked
+ * by none of the passwords of the password file.
+ */
+pub fn load_document(path: &str) -> Result<Document> {
+    if let Ok(file) = fs::metadata(path) {
+        check_readable_size(path, file.len())?;
+    }
+    let mut doc = measure(Phase::Load, || Document::load(path))
+        .with_context(|| tr!("Failed to load PDF '{path}'", path = path))?;
+    if is_locked(&doc) {
+        measure(Phase::Load, || unlock_with_password_file(&mut doc, path))?;
+    }
+    sync_max_id(&mut doc);
+    Ok(doc)
+}
+
+/**
+ * Save a PDF with a cross-reference stream rather than a classic table, which is more compact
+ * for the many objects of large scanned files, and its small objects packed into compressed
+ * object streams unless they are turned off (see object_streams). The version is raised to 1.5
+ * if needed, and the entries of set_output_metadata are set. The file is written atomically,
+ * see write_atomically.
+ * @param doc The document.
+ * @param path The output file path.
+ * @throws anyhow::Error if the file cannot be written or would be larger than MAX_PDF_SIZE.
+ */
+pub fn save_document(doc: &mut Document, path: &str) -> Result<()> {
+    save_document_with(doc, path, object_streams(), OBJECT_STREAM_COMPRESSION)
+}
+
+/**
+ * Save a document like save_document, choosing how its objects are packed.
+ * @param doc The document, its version raised to 1.5 if lower.
+ * @param path The output file path.
+ * @param object_streams Whether the small objects are packed into object streams.
+ * @param compression_level The Flate level of the object streams (0-9).
+ * @throws anyhow::Error if the file cannot be written or would be larger than MAX_PDF_SIZE.
+ */
+pub fn save_document_with(
+    doc: &mut Document,
+    path: &str,
+    object_streams: bool,
+    compression_level: u32,
+) -> Result<()> {
+    // The cross-reference stream is numbered after the highest object
+    sync_max_id(doc);
+    apply_output_metadata(doc);
+    doc.reference_table.cross_reference_type = XrefType::CrossReferenceStream;
+    if doc
+        .version
+        .parse::<f32>()
+        .is_ok_and(|version| version < 1.5)
+    {
+        doc.version = XREF_STREAM_VERSION.to_string();
+    }
+    if !object_streams {
+        write_atomically(path, |temp| {
+            measure(Phase::Save, || doc.save(temp).map(drop))
+        })?;
+        return Ok(());
+    }
+    // The cross-reference stream and linearization data of the input are written anew, or not
+    doc.objects.retain(|_, object| {
+        !object
+            .type_name()
+            .is_ok_and(|name| [b"XRef".as_slice(), b"Linearized"].contains(&name))
+    });
+    let options = SaveOptions::builder()
+        .use_object_streams(true)
+        .use_xref_streams(true)
+        .compression_level(compression_level)
+        .build();
+    write_atomically(path, |temp| {
+        let mut file = BufWriter::new(fs::File::create(temp)?);
+        measure(Phase::Save, || doc.save_with_options(&mut file, options))?;
+        file.flush()
+    })?;
+    Ok(())
+}
+
+/**
+ * Write a PDF to a temporary file next to its path and rename it over the path once complete,
+ * so a failure or a crash while saving never leaves a truncated file where the output is
+ * expected. The temporary file is removed on failure. With durable outputs, the file is flushed
+ * to disk before the rename and its directory after it.
+ * @param path The output file path.
+ * @param write Writes the whole file to the temporary path it is given.
+ * @returns The size of the file in bytes.
+ * @throws anyhow::Error if the file cannot be written or renamed, or is larger than MAX_PDF_SIZE
+ * (the offsets of its objects would be wrong).
+ */
+pub fn write_atomically(path: &str, write: impl FnOnce(&Path) -> io::Result<()>) -> Result<u64> {
+    let temp = temporary_path(path);
+    match write(&temp) {
+        Ok(()) => move_into_place(&temp, path),
+        Err(error) => {
+            let _ = fs::remove_file(&temp);
+            Err(error).with_context(|| tr!("Failed to save PDF '{path}'", path = path))
+        }
+    }
+}
+
+/**
+ * Rename a completely written temporary file over the output path, the last step of
+ * write_atomically. The temporary file is removed on failure.
+ * @param temp The temporary file, from temporary_path.
+ * @param path The output file path.
+ * @returns The size of the file in bytes.
+ * @throws anyhow::Error if the file cannot be flushed or renamed, or is larger than MAX_PDF_SIZE.
+ */
+pub fn move_into_place(temp: &Path, path: &str) -> Result<u64> {
+    move_into_place_within(temp, path, MAX_PDF_SIZE)
+}
+
+/// Move a temporary file into place like move_into_place, refusing files larger than `limit`.
+fn move_into_place_within(temp: &Path, path: &str, limit: u64) -> Result<u64> {
+    let written = (if durable() { sync_file(temp) } else { Ok(()) })
+        .and_then(|_| fs::metadata(temp))
+        .with_context(|| tr!("Failed to save PDF '{path}'", path = path))
+        .and_then(|file| {
+            check_writable_size(path, file.len(), limit)?;
+            // A cancelled operation leaves no output, however far it got
+            check_cancelled()?;
+            fs::rename(temp, path)
+                .with_context(|| tr!("Failed to save PDF '{path}'", path = path))?;
+            if durable() {
+                sync_parent_directory(Path::new(path))
+                    .with_context(|| tr!("Failed to save PDF '{path}'", path = path))?;
+            }
+            Ok(file.len())
+        });
+    if written.is_err() {
+        let _ = fs::remove_file(temp);
+    }
+    written
+}
+
+/// Refuse a file of `len` bytes too large for the offsets of its objects to be read.
+fn check_readable_size(path: &str, len: u64) -> Result<()> {
+    if len > MAX_PDF_SIZE {
+        bail!(tr!(
+            "'{path}' is larger than 4 GB, the most a PDF can be read with",
+            path = path
+        ));
+    }
+    Ok(())
+}
+
+/// Refuse an output of `len` bytes larger than `limit`, MAX_PDF_SIZE outside of tests.
+fn check_writable_size(path: &str, len: u64, limit: u64) -> Result<()> {
+    if len > limit {
+        bail!(tr!(
+            "'{path}' would be larger than 4 GB, the most a PDF can be written with; split the input first",
+            path = path
+        ));
+    }
     Ok(())
 }
 
 /// Collect all object references from an object
-fn collect_references(obj: &Object, to_copy: &mut VecDeque<ObjectId>) {
+pub fn collect_references(obj: &Object, to_copy: &mut VecDeque<ObjectId>) {
     match obj {
         Object::Reference(id) => {
             to_copy.push_back(*id);
@@ -122,14 +609,15 @@ fn collect_references(obj: &Object, to_copy: &mut VecDeque<ObjectId>) {
     }
 }
 
-/// Update object references in a copied object
-fn update_references(obj: &mut Object, id_mapping: &HashMap<ObjectId, ObjectId>) {
+/// Update object references in a copied object. A reference to an object that is not in the
+/// mapping (missing from the source) becomes null, as PDF readers take it, rather than keeping a
+/// number that belongs to another object of the target.
+pub fn update_references(obj: &mut Object, id_mapping: &HashMap<ObjectId, ObjectId>) {
     match obj {
-        Object::Reference(id) => {
-            if let Some(&new_id) = id_mapping.get(id) {
-                *id = new_id;
-            }
-        }
+        Object::Reference(id) => match id_mapping.get(id) {
+            Some(&new_id) => *id = new_id,
+            None => *obj = Object::Null,
+        },
         Object::Dictionary(dict) => {
             for (_, value) in dict.iter_mut() {
                 update_references(value, id_mapping);
@@ -148,3 +636,225 @@ fn update_references(obj: &mut Object, id_mapping: &HashMap<ObjectId, ObjectId>)
         _ => {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::extract::extract_pages;
+
+    #[test]
+    fn test_outputs_use_xref_streams() {
+        let output = std::env::temp_dir().join("pdfcutter_utils_xref.pdf");
+        let output = output.to_string_lossy();
+        extract_pages("tests/tests_pdf/a.pdf", &output, &[1], &Default::default()).unwrap();
+        let bytes = std::fs::read(&*output).unwrap();
+        let contains = |text: &[u8]| bytes.windows(text.len()).any(|window| window == text);
+        assert!(contains(b"/XRef"));
+        assert!(!contains(b"trailer"));
+
+        let doc = load_document(&output).unwrap();
+        assert_eq!(doc.version, "1.5");
+        assert_eq!(doc.get_pages().len(), 1);
+        std::fs::remove_file(&*output).unwrap();
+    }
+
+    #[test]
+    fn test_object_streams_can_be_turned_off() {
+        let output = std::env::temp_dir().join("pdfcutter_utils_objstm.pdf");
+        let output = output.to_string_lossy();
+        let packed = |object_streams| {
+            let mut doc = load_document("tests/tests_pdf/c.pdf").unwrap();
+            save_document_with(&mut doc, &output, object_streams, OBJECT_STREAM_COMPRESSION)
+                .unwrap();
+            let bytes = std::fs::read(&*output).unwrap();
+            let saved = load_document(&output).unwrap();
+            assert_eq!(saved.get_pages().len(), 7);
+            bytes
+                .windows(7)
+                .filter(|window| window == b"/ObjStm")
+                .count()
+        };
+        assert!(packed(true) > 0);
+        assert_eq!(packed(false), 0);
+        std::fs::remove_file(&*output).unwrap();
+    }
+
+    #[test]
+    fn test_renumber_sparse_objects() {
+        let mut doc = Document::with_version("1.7");
+        doc.objects
+            .insert((3, 0), dictionary! { "Next" => (42, 1) }.into());
+        doc.objects
+            .insert((42, 1), dictionary! { "Missing" => (99, 0) }.into());
+        // Not reachable from the trailer, still written
+        doc.objects
+            .insert((7, 0), dictionary! { "Next" => (42, 1) }.into());
+        doc.trailer.set("Root", (3, 0));
+        doc.max_id = 3;
+
+        renumber_objects(&mut doc);
+        let ids: Vec<ObjectId> = doc.objects.keys().copied().collect();
+        assert_eq!(ids, vec![(1, 0), (2, 0), (3, 0)]);
+        assert_eq!(doc.max_id, 3);
+        assert_eq!(
+            doc.trailer.get(b"Root").unwrap(),
+            &Object::Reference((1, 0))
+        );
+        let next = |id| {
+            doc.get_dictionary(id)
+                .unwrap()
+                .get(b"Next")
+                .unwrap()
+                .clone()
+        };
+        assert_eq!(next((1, 0)), Object::Reference((3, 0)));
+        assert_eq!(next((2, 0)), Object::Reference((3, 0)));
+        let missing = doc.get_dictionary((3, 0)).unwrap().get(b"Missing").unwrap();
+        assert_eq!(missing, &Object::Null);
+        assert_eq!(doc.add_object(Object::Null), (4, 0));
+    }
+
+    #[test]
+    fn test_understated_max_id() {
+        let path = std::env::temp_dir().join("pdfcutter_utils_max_id.pdf");
+        let path = path.to_string_lossy();
+        let mut doc = load_document("tests/tests_pdf/c.pdf").unwrap();
+        let ids: Vec<ObjectId> = doc.objects.keys().copied().collect();
+        // As read from a trailer whose /Size is too small
+        doc.max_id = 1;
+        save_document(&mut doc, &path).unwrap();
+
+        // The cross-reference stream took a new number instead of that of an object
+        let mut saved = load_document(&path).unwrap();
+        let is_xref =
+            |id| saved.get_object(id).unwrap().type_name().ok() == Some(b"XRef".as_slice());
+        assert!(ids.iter().all(|&id| !is_xref(id)));
+        assert_eq!(saved.get_pages().len(), 7);
+        let highest = saved
+            .objects
+            .keys()
+            .map(|&(number, _)| number)
+            .max()
+            .unwrap();
+        assert!(saved.add_object(Object::Null).0 > highest);
+        std::fs::remove_file(&*path).unwrap();
+    }
+
+    #[test]
+    fn test_copy_drops_missing_references() {
+        let mut source = Document::load("tests/tests_pdf/a.pdf").unwrap();
+        let page_id = source.get_pages()[&1];
+        source
+            .get_dictionary_mut(page_id)
+            .unwrap()
+            .set("PieceInfo", Object::Reference((9999, 0)));
+        let mut target = Document::with_version("1.5");
+        target.add_object(Object::Null);
+        let mut copier = PageCopier::new(target);
+        let copy = copier.copy_pages(&source, "a.pdf", &[page_id]).unwrap()[0];
+        let piece_info = copier
+            .target()
+            .get_dictionary(copy)
+            .unwrap()
+            .get(b"PieceInfo");
+        assert_eq!(piece_info.unwrap(), &Object::Null);
+    }
+
+    #[test]
+    fn test_copier_shares_objects_between_copies() {
+        let mut source = Document::load("tests/tests_pdf/c.pdf").unwrap();
+        let pages = source.get_pages();
+        let link = dictionary! {
+            "Subtype" => "Link",
+            "Dest" => vec![pages[&2].into(), "Fit".into()],
+            "P" => pages[&1],
+        };
+        let link = source.add_object(link);
+        source
+            .get_dictionary_mut(pages[&1])
+            .unwrap()
+            .set("Annots", vec![link.into()]);
+        let other = Document::load("tests/tests_pdf/a.pdf").unwrap();
+
+        let mut copier = PageCopier::new(Document::with_version("1.5"));
+        let first = copier.copy_pages(&source, "c.pdf", &[pages[&1]]).unwrap()[0];
+        copier
+            .copy_pages(&other, "a.pdf", &[other.get_pages()[&1]])
+            .unwrap();
+        let copies = copier
+            .copy_pages(&source, "c.pdf", &[pages[&1], pages[&2]])
+            .unwrap();
+        let target = copier.into_target();
+
+        // The content of the page is copied once, its annotation once per copy
+        let page = |id| target.get_dictionary(id).unwrap();
+        assert_eq!(
+            page(first).get(b"Contents").unwrap(),
+            page(copies[0]).get(b"Contents").unwrap()
+        );
+        let annotation = |id| {
+            let annots = page(id).get(b"Annots").unwrap().as_array().unwrap();
+            annots[0].as_reference().unwrap()
+        };
+        assert_ne!(annotation(first), annotation(copies[0]));
+        for copy in [first, copies[0]] {
+            let annotation = target.get_dictionary(annotation(copy)).unwrap();
+            assert_eq!(annotation.get(b"P").unwrap(), &Object::Reference(copy));
+            // Page 2 was copied after the first copy of the link: it leads to nowhere
+            let dest = annotation.get(b"Dest").unwrap().as_array().unwrap();
+            let expected = if copy == first {
+                Object::Null
+            } else {
+                Object::Reference(copies[1])
+            };
+            assert_eq!(dest[0], expected);
+        }
+        let page_objects = target
+            .objects
+            .values()
+            .filter(|object| object.type_name().is_ok_and(|name| name == b"Page"))
+            .count();
+        assert_eq!(page_objects, 4);
+    }
+
+    #[test]
+    fn test_files_over_4_gb_are_refused() {
+        assert!(check_readable_size("in.pdf", MAX_PDF_SIZE).is_ok());
+        assert!(check_readable_size("in.pdf", MAX_PDF_SIZE + 1).is_err());
+        assert!(check_writable_size("out.pdf", MAX_PDF_SIZE, MAX_PDF_SIZE).is_ok());
+        assert!(check_writable_size("out.pdf", MAX_PDF_SIZE + 1, MAX_PDF_SIZE).is_err());
+    }
+
+    #[test]
+    fn test_oversized_output_is_not_moved_into_place() {
+        let path = std::env::temp_dir().join("pdfcutter_utils_oversized.pdf");
+        let path = path.to_string_lossy();
+        let temp = temporary_path(&path);
+        fs::write(&temp, b"12345").unwrap();
+
+        assert!(move_into_place_within(&temp, &path, 4).is_err());
+        assert!(!Path::new(&*path).exists());
+        assert!(!temp.exists());
+    }
+
+    #[test]
+    fn test_failed_save_keeps_previous_output() {
+        let path = std::env::temp_dir().join("pdfcutter_utils_atomic.pdf");
+        let path = path.to_string_lossy();
+        fs::write(&*path, b"previous").unwrap();
+
+        let written = write_atomically(&path, |temp| {
+            fs::write(temp, b"trunc")?;
+            Err(io::Error::other("interrupted"))
+        });
+        assert!(written.is_err());
+        assert_eq!(fs::read(&*path).unwrap(), b"previous");
+        assert!(!temporary_path(&path).exists());
+
+        let written = write_atomically(&path, |temp| fs::write(temp, b"complete"));
+        assert_eq!(written.unwrap(), 8);
+        assert_eq!(fs::read(&*path).unwrap(), b"complete");
+        assert!(!temporary_path(&path).exists());
+        fs::remove_file(&*path).unwrap();
+    }
+}
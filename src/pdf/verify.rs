@@ -0,0 +1,226 @@
+use super::utils::load_document;
+use crate::i18n::tr;
+use anyhow::{Context, Result, bail};
+use flate2::read::ZlibDecoder;
+use lopdf::content::Content;
+use lopdf::{Document, Object, ObjectId, Stream};
+use std::io::Read;
+
+/**
+ * Read an output back once it is written and check it holds what the operation put in it: the
+ * expected number of pages, each drawn by content streams that can be decompressed and decoded.
+ * This catches outputs that save without error but lost pages or their content on the way.
+ * @param path The output file.
+ * @param expected_pages The number of pages the operation wrote.
+ * @throws anyhow::Error naming the output, and the page, that does not hold up.
+ */
+pub fn verify_output(path: &str, expected_pages: usize) -> Result<()> {
+    log::debug!("Verifying '{}'", path);
+    let doc =
+        load_document(path).with_context(|| tr!("'{path}' cannot be read back", path = path))?;
+    verify_document(&doc, path, expected_pages)
+}
+
+/**
+ * Check the pages of a loaded output, see `verify_output`.
+ * @param doc The output, as read back.
+ * @param path The output file, for the messages.
+ * @param expected_pages The number of pages the operation wrote.
+ * @throws anyhow::Error naming the output, and the page, that does not hold up.
+ */
+pub fn verify_document(doc: &Document, path: &str, expected_pages: usize) -> Result<()> {
+    let pages = doc.get_pages();
+    if pages.len() != expected_pages {
+        bail!(tr!(
+            "'{path}' has {found} pages instead of {expected}",
+            path = path,
+            found = pages.len(),
+            expected = expected_pages
+        ));
+    }
+    for (page_num, page_id) in pages {
+        let content = page_content(doc, page_id).with_context(|| {
+            tr!(
+                "Page {page} of '{path}' has no readable content",
+                page = page_num,
+                path = path
+            )
+        })?;
+        // The decoder stops at the first token it cannot read, so content that decodes to no
+        // operation at all is garbage; a blank page has no content to decode
+        let decoded = Content::decode(&content).map(|content| content.operations.len());
+        if !matches!(decoded, Ok(count) if count > 0 || content.trim_ascii().is_empty()) {
+            bail!(tr!(
+                "The content of page {page} of '{path}' cannot be decoded",
+                page = page_num,
+                path = path
+            ));
+        }
+    }
+    Ok(())
+}
+
+/**
+ * The content of a page, its streams put end to end. Unlike `Document::get_page_content`, a
+ * missing stream or one that cannot be decompressed is an error instead of being skipped.
+ * @throws anyhow::Error if the page has no content streams or one of them cannot be read.
+ */
+fn page_content(doc: &Document, page_id: ObjectId) -> Result<Vec<u8>> {
+    let page = doc.get_dictionary(page_id)?;
+    let ids = match page.get(b"Contents") {
+        Ok(Object::Reference(id)) => vec![*id],
+        Ok(Object::Array(items)) => items
+            .iter()
+            .map(|item| item.as_reference().map_err(Into::into))
+            .collect::<Result<Vec<_>>>()?,
+        Ok(_) => bail!(tr!("the content is not a stream")),
+        Err(_) => bail!(tr!("no content stream")),
+    };
+    if ids.is_empty() {
+        bail!(tr!("no content stream"));
+    }
+    let mut content = Vec::new();
+    for id in ids {
+        let stream = doc.get_object(id).and_then(Object::as_stream)?;
+        content.extend(decompressed(stream)?);
+        // Streams are split between tokens, which the end of a stream also separates
+        content.push(b'\n');
+    }
+    Ok(content)
+}
+
+/**
+ * The data of a content stream once its filters are undone. lopdf takes a Flate stream that
+ * cannot be inflated for an empty one, which would pass for a blank page, so the usual lone
+ * FlateDecode filter is undone here to report it.
+ * @throws anyhow::Error if the data cannot be decompressed.
+ */
+fn decompressed(stream: &Stream) -> Result<Vec<u8>> {
+    let filters = stream.filters().unwrap_or_default();
+    match filters[..] {
+        [] => Ok(stream.content.clone()),
+        [b"FlateDecode"] if stream.dict.get(b"DecodeParms").is_err() => {
+            let mut data = Vec::new();
+            ZlibDecoder::new(stream.content.as_slice()).read_to_end(&mut data)?;
+            Ok(data)
+        }
+        _ => Ok(stream.decompressed_content()?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::delete::delete_pages;
+    use crate::pdf::merge::{MergeInput, MergeOptions, Separator, merge_inputs};
+    use crate::pdf::page_spec::parse_named_segments;
+    use crate::pdf::split::split_pdfs_with_segments;
+    use crate::pdf::utils::save_document;
+    use lopdf::{Dictionary, Stream, dictionary};
+    use std::fs;
+
+    const INPUTS: [&str; 3] = [
+        "tests/tests_pdf/a.pdf",
+        "tests/tests_pdf/b.pdf",
+        "tests/tests_pdf/c.pdf",
+    ];
+
+    fn page_count(path: &str) -> usize {
+        load_document(path).unwrap().get_pages().len()
+    }
+
+    #[test]
+    fn test_outputs_keep_their_pages() {
+        let dir = std::env::temp_dir().join("pdfcutter_verify_round_trip");
+        fs::create_dir_all(&dir).unwrap();
+        let path = |name: &str| dir.join(name).to_string_lossy().into_owned();
+        let counts: Vec<usize> = INPUTS.iter().map(|input| page_count(input)).collect();
+
+        // Merge, with every kind of separator and some pages taken twice
+        let mut inputs: Vec<MergeInput> = INPUTS[..2]
+            .iter()
+            .map(|input| MergeInput::new(input))
+            .collect();
+        inputs[1].pages = Some(vec![2, 1, 2]);
+        inputs[1].rotation = 90;
+        for (separator, between) in [
+            (None, 0),
+            (Some(Separator::Blank), 1),
+            (Some(Separator::Title), 1),
+            (Some(Separator::File(INPUTS[1].to_string())), counts[1]),
+        ] {
+            let options = MergeOptions {
+                separator,
+                source_footer: true,
+                keep_tags: true,
+                ..MergeOptions::default()
+            };
+            let output = path("merged.pdf");
+            merge_inputs(&inputs, &options, &output).unwrap();
+            let expected = counts[0] + 3 + between;
+            verify_output(&output, expected).unwrap();
+            assert!(verify_output(&output, expected + 1).is_err());
+        }
+
+        // Delete the first and the last page, then every page but one
+        for pages in [vec![1, counts[2] as u32], (2..=counts[2] as u32).collect()] {
+            let output = path("deleted.pdf");
+            delete_pages(INPUTS[2], &output, &pages).unwrap();
+            verify_output(&output, counts[2] - pages.len()).unwrap();
+        }
+
+        // Split into segments that share pages
+        let segments = parse_named_segments("first:1,all:1-4,(2-3)").unwrap();
+        let outputs = split_pdfs_with_segments(INPUTS[2], &path("part"), &segments).unwrap();
+        for (output, segment) in outputs.iter().zip(&segments) {
+            verify_output(output, segment.get_pages().len()).unwrap();
+        }
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_verify_reports_broken_pages() {
+        let dir = std::env::temp_dir().join("pdfcutter_verify_broken");
+        fs::create_dir_all(&dir).unwrap();
+        let output = dir.join("broken.pdf").to_string_lossy().into_owned();
+
+        let mut doc = load_document(INPUTS[2]).unwrap();
+        let pages: Vec<ObjectId> = doc.get_pages().into_values().collect();
+        doc.get_dictionary_mut(pages[1])
+            .unwrap()
+            .remove(b"Contents");
+        save_document(&mut doc, &output).unwrap();
+        let error = format!("{:#}", verify_output(&output, pages.len()).unwrap_err());
+        assert!(error.starts_with("Page 2 of"), "{error}");
+
+        let mut doc = load_document(INPUTS[2]).unwrap();
+        let garbage = doc.add_object(Stream::new(Dictionary::new(), b"\xff\x00{}".to_vec()));
+        let truncated = doc.add_object(Stream::new(
+            dictionary! { "Filter" => "FlateDecode" },
+            b"not deflate".to_vec(),
+        ));
+        doc.get_dictionary_mut(pages[2])
+            .unwrap()
+            .set("Contents", garbage);
+        doc.get_dictionary_mut(pages[4])
+            .unwrap()
+            .set("Contents", vec![truncated.into()]);
+        save_document(&mut doc, &output).unwrap();
+        let error = verify_output(&output, pages.len()).unwrap_err();
+        assert!(
+            error.to_string().starts_with("The content of page 3"),
+            "{error}"
+        );
+        // Once page 3 is blank, the stream of page 5 that cannot be decompressed is reported
+        doc.objects
+            .insert(garbage, Stream::new(Dictionary::new(), vec![]).into());
+        let error = verify_document(&doc, &output, pages.len()).unwrap_err();
+        assert!(error.to_string().starts_with("Page 5 of"), "{error}");
+
+        fs::write(&output, b"%PDF-1.5\nnot a pdf").unwrap();
+        assert!(verify_output(&output, pages.len()).is_err());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,490 @@
+use super::image::add_image;
+use super::inspect::{inherited_attribute, media_box, page_infos};
+use super::utils::{load_document, save_document};
+use crate::i18n::tr;
+use anyhow::{Context, Result, bail};
+use lopdf::content::{Content, Operation};
+use lopdf::{Dictionary, Document, Object, ObjectId, Stream, StringFormat, dictionary};
+
+/// Distance between a corner watermark and the edges of the page, in points.
+const MARGIN: f32 = 28.0;
+
+/// Font size of corner text watermarks, in points.
+const CORNER_FONT_SIZE: f32 = 14.0;
+
+/// Largest font size of centered and diagonal text watermarks, in points.
+const MAX_FONT_SIZE: f32 = 120.0;
+
+/// Share of the page a centered or diagonal watermark spans; corner ones use a third of it.
+const WATERMARK_SPAN: f32 = 0.6;
+
+/// Widths of the printable ASCII characters (32 to 126) in Helvetica, per 1000 units of font size.
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+/// Width of the other Latin-1 characters, close to most Helvetica letters.
+const DEFAULT_WIDTH: u16 = 556;
+
+/// Height of Helvetica capitals, per 1000 units of font size.
+const CAP_HEIGHT: f32 = 718.0;
+
+/// What is stamped on the pages.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WatermarkSource {
+    Text(String),
+    /// Path of a JPEG or PNG file.
+    Image(String),
+}
+
+/// Where the watermark is placed, as seen in a viewer (page rotation is taken into account).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatermarkPosition {
+    Center,
+    /// Centered, along the diagonal from the bottom-left to the top-right corner.
+    Diagonal,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl WatermarkPosition {
+    /// Every position, in the order the TUI cycles through them.
+    pub const ALL: [WatermarkPosition; 6] = [
+        WatermarkPosition::Diagonal,
+        WatermarkPosition::Center,
+        WatermarkPosition::TopLeft,
+        WatermarkPosition::TopRight,
+        WatermarkPosition::BottomLeft,
+        WatermarkPosition::BottomRight,
+    ];
+}
+
+/// How a watermark looks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatermarkOptions {
+    pub source: WatermarkSource,
+    pub position: WatermarkPosition,
+    /// Opacity in percent, from 1 to 100.
+    pub opacity: u8,
+}
+
+/**
+ * Stamp a text or image watermark on pages of a PDF and save the result.
+ * Text is set in Helvetica, so characters outside Latin-1 are replaced with '?'.
+ * @param input The input PDF file path.
+ * @param output The output PDF file path.
+ * @param pages The pages to stamp (1-based).
+ * @param options The watermark source, position and opacity.
+ * @throws anyhow::Error if the options are invalid, a page does not exist or the PDF cannot be written.
+ */
+pub fn watermark_pages(
+    input: &str,
+    output: &str,
+    pages: &[u32],
+    options: &WatermarkOptions,
+) -> Result<()> {
+    if !(1..=100).contains(&options.opacity) {
+        bail!(tr!(
+            "Invalid opacity: {opacity} (must be between 1 and 100)",
+            opacity = options.opacity
+        ));
+    }
+    if let WatermarkSource::Text(text) = &options.source
+        && text.trim().is_empty()
+    {
+        bail!(tr!("Watermark text cannot be empty"));
+    }
+    if pages.is_empty() {
+        bail!(tr!("No pages to watermark"));
+    }
+
+    log::debug!("Loading '{}'", input);
+    let mut doc = load_document(input)?;
+
+    let all_pages = doc.get_pages();
+    let total_pages = all_pages.len();
+    for &page_num in pages {
+        if page_num == 0 || page_num as usize > total_pages {
+            bail!(tr!(
+                "Invalid page number: {page}. PDF has {count} pages (1-{count})",
+                page = page_num,
+                count = total_pages
+            ));
+        }
+    }
+
+    let opacity = options.opacity as f32 / 100.0;
+    let graphics_state = doc.add_object(dictionary! {
+        "Type" => "ExtGState",
+        "ca" => opacity,
+        "CA" => opacity,
+    });
+    let stamp = match &options.source {
+        WatermarkSource::Text(text) => Stamp::Text {
+            font: doc.add_object(dictionary! {
+                "Type" => "Font",
+                "Subtype" => "Type1",
+                "BaseFont" => "Helvetica",
+                "Encoding" => "WinAnsiEncoding",
+            }),
+            text: win_ansi(text),
+        },
+        WatermarkSource::Image(path) => Stamp::Image(add_image(&mut doc, path)?),
+    };
+    // Existing content is wrapped in q/Q so that its graphics state does not leak into the watermark
+    let save_state = doc.add_object(Stream::new(Dictionary::new(), b"q\n".to_vec()));
+
+    let infos = page_infos(&doc);
+    for &page_num in pages {
+        let page_id = all_pages[&page_num];
+        let bounds = media_box(&doc, page_id).unwrap_or([0.0, 0.0, 595.28, 841.89]);
+        let rotation = infos[page_num as usize - 1].rotation;
+
+        let name = unused_resource_name(&doc, page_id, "PdfCutterWatermark");
+        add_page_resource(&mut doc, page_id, b"ExtGState", &name, graphics_state)?;
+        let category: &[u8] = match stamp {
+            Stamp::Text { .. } => b"Font",
+            Stamp::Image(_) => b"XObject",
+        };
+        let stamp_id = match stamp {
+            Stamp::Text { font, .. } => font,
+            Stamp::Image(image) => image.id,
+        };
+        add_page_resource(&mut doc, page_id, category, &name, stamp_id)?;
+
+        let content = Content {
+            operations: stamp_operations(&stamp, &name, options.position, bounds, rotation),
+        }
+        .encode()
+        .with_context(|| tr!("Failed to save PDF '{path}'", path = output))?;
+        append_page_content(&mut doc, page_id, save_state, content)
+            .with_context(|| tr!("Page {page} is not a dictionary", page = page_num))?;
+    }
+
+    save_document(&mut doc, output)?;
+
+    Ok(())
+}
+
+/// A watermark added to the document, shared by every stamped page.
+enum Stamp {
+    Text { font: ObjectId, text: Vec<u8> },
+    Image(super::image::ImageXObject),
+}
+
+/**
+ * Drawing operations of a watermark on one page.
+ * @param stamp The text or image to draw.
+ * @param name The resource name of the font or image, and of the graphics state.
+ * @param position Where the watermark goes, as seen in a viewer.
+ * @param bounds The MediaBox of the page.
+ * @param rotation The clockwise rotation of the page, in degrees.
+ */
+fn stamp_operations(
+    stamp: &Stamp,
+    name: &str,
+    position: WatermarkPosition,
+    bounds: [f32; 4],
+    rotation: i64,
+) -> Vec<Operation> {
+    let (width, height) = (bounds[2] - bounds[0], bounds[3] - bounds[1]);
+    let (shown_width, shown_height) = if rotation % 180 == 0 {
+        (width, height)
+    } else {
+        (height, width)
+    };
+    let corner = !matches!(
+        position,
+        WatermarkPosition::Center | WatermarkPosition::Diagonal
+    );
+    let span = if corner {
+        WATERMARK_SPAN / 3.0
+    } else {
+        WATERMARK_SPAN
+    };
+    let available_width = match position {
+        WatermarkPosition::Diagonal => shown_width.hypot(shown_height),
+        _ => shown_width,
+    } * span;
+
+    let mut operations = vec![
+        Operation::new("Q", vec![]),
+        Operation::new("q", vec![]),
+        Operation::new("cm", reals(&display_matrix(rotation, bounds))),
+        Operation::new("gs", vec![Object::Name(name.as_bytes().to_vec())]),
+    ];
+    match stamp {
+        Stamp::Text { text, .. } => {
+            let units = text_width(text).max(1.0);
+            let size = if corner {
+                CORNER_FONT_SIZE
+            } else {
+                (available_width * 1000.0 / units).min(MAX_FONT_SIZE)
+            };
+            let size_box = (units * size / 1000.0, CAP_HEIGHT * size / 1000.0);
+            let matrix = placement(position, (shown_width, shown_height), size_box);
+            operations.extend([
+                Operation::new("BT", vec![]),
+                Operation::new(
+                    "Tf",
+                    vec![Object::Name(name.as_bytes().to_vec()), size.into()],
+                ),
+                Operation::new("g", vec![0.5.into()]),
+                Operation::new("Tm", reals(&matrix)),
+                Operation::new(
+                    "Tj",
+                    vec![Object::String(text.clone(), StringFormat::Literal)],
+                ),
+                Operation::new("ET", vec![]),
+            ]);
+        }
+        Stamp::Image(image) => {
+            let scale = (available_width / image.width as f32)
+                .min(shown_height * span / image.height as f32);
+            let size_box = (image.width as f32 * scale, image.height as f32 * scale);
+            let matrix = placement(position, (shown_width, shown_height), size_box);
+            operations.extend([
+                Operation::new("cm", reals(&matrix)),
+                Operation::new("cm", reals(&[size_box.0, 0.0, 0.0, size_box.1, 0.0, 0.0])),
+                Operation::new("Do", vec![Object::Name(name.as_bytes().to_vec())]),
+            ]);
+        }
+    }
+    operations.push(Operation::new("Q", vec![]));
+    operations
+}
+
+/**
+ * Draw over a page, after its existing content.
+ * @param doc The document the page belongs to.
+ * @param page_id The page.
+ * @param save_state A stream holding `q`, put before the existing content so that its graphics
+ * state does not leak into what is drawn over it, which must start with `Q`.
+ * @param content The encoded drawing operations.
+ * @throws anyhow::Error if the page is not a dictionary.
+ */
+pub fn append_page_content(
+    doc: &mut Document,
+    page_id: ObjectId,
+    save_state: ObjectId,
+    content: Vec<u8>,
+) -> Result<()> {
+    let content_id = doc.add_object(Stream::new(Dictionary::new(), content));
+    let page = doc.get_dictionary_mut(page_id)?;
+    let mut contents = vec![Object::Reference(save_state)];
+    match page.get(b"Contents") {
+        Ok(Object::Array(existing)) => contents.extend(existing.iter().cloned()),
+        Ok(existing) => contents.push(existing.clone()),
+        Err(_) => {}
+    }
+    contents.push(Object::Reference(content_id));
+    page.set("Contents", contents);
+    Ok(())
+}
+
+pub fn reals(values: &[f32]) -> Vec<Object> {
+    values.iter().map(|&value| value.into()).collect()
+}
+
+/**
+ * Matrix from the page as seen in a viewer (origin at the bottom-left corner) to user space.
+ * @param rotation The clockwise rotation of the page, in degrees.
+ * @param bounds The MediaBox of the page.
+ */
+pub fn display_matrix(rotation: i64, bounds: [f32; 4]) -> [f32; 6] {
+    let [left, bottom, right, top] = bounds;
+    match rotation.rem_euclid(360) {
+        90 => [0.0, 1.0, -1.0, 0.0, right, bottom],
+        180 => [-1.0, 0.0, 0.0, -1.0, right, top],
+        270 => [0.0, -1.0, 1.0, 0.0, left, top],
+        _ => [1.0, 0.0, 0.0, 1.0, left, bottom],
+    }
+}
+
+/**
+ * Matrix placing a box of the given size (its origin at its bottom-left corner) on the page.
+ * @param position Where the box goes.
+ * @param page The page size, as seen in a viewer.
+ * @param size The size of the box.
+ */
+pub fn placement(position: WatermarkPosition, page: (f32, f32), size: (f32, f32)) -> [f32; 6] {
+    let (page_width, page_height) = page;
+    let (width, height) = size;
+    let (x, y) = match position {
+        WatermarkPosition::Diagonal => {
+            let angle = page_height.atan2(page_width);
+            let (sin, cos) = angle.sin_cos();
+            // Move back from the center by half the box, along and across the diagonal
+            let x = page_width / 2.0 - width / 2.0 * cos + height / 2.0 * sin;
+            let y = page_height / 2.0 - width / 2.0 * sin - height / 2.0 * cos;
+            return [cos, sin, -sin, cos, x, y];
+        }
+        WatermarkPosition::Center => ((page_width - width) / 2.0, (page_height - height) / 2.0),
+        WatermarkPosition::TopLeft => (MARGIN, page_height - MARGIN - height),
+        WatermarkPosition::TopRight => (page_width - MARGIN - width, page_height - MARGIN - height),
+        WatermarkPosition::BottomLeft => (MARGIN, MARGIN),
+        WatermarkPosition::BottomRight => (page_width - MARGIN - width, MARGIN),
+    };
+    [1.0, 0.0, 0.0, 1.0, x, y]
+}
+
+/**
+ * Encode text for a WinAnsiEncoding font: Latin-1 characters are kept, others become '?'.
+ */
+pub fn win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c as u32 {
+            code @ (32..=126 | 160..=255) => code as u8,
+            _ => b'?',
+        })
+        .collect()
+}
+
+/**
+ * Width of WinAnsi-encoded text in Helvetica, in units of 1/1000 of the font size.
+ */
+pub fn text_width(text: &[u8]) -> f32 {
+    text.iter()
+        .map(|&byte| match byte {
+            32..=126 => HELVETICA_WIDTHS[(byte - 32) as usize],
+            _ => DEFAULT_WIDTH,
+        } as f32)
+        .sum()
+}
+
+/**
+ * A resource name that the page does not use yet, so that stamping a file twice keeps both watermarks.
+ * @param prefix The start of the name, followed by the first free number.
+ */
+pub fn unused_resource_name(doc: &Document, page_id: ObjectId, prefix: &str) -> String {
+    let resources = inherited_attribute(doc, page_id, b"Resources").and_then(|r| r.as_dict().ok());
+    let is_used = |name: &str| {
+        resources.is_some_and(|resources| {
+            [b"Font".as_slice(), b"XObject", b"ExtGState"]
+                .iter()
+                .filter_map(|category| resources.get(category).ok())
+                .filter_map(|entries| doc.dereference(entries).ok()?.1.as_dict().ok())
+                .any(|entries| entries.has(name.as_bytes()))
+        })
+    };
+    (1..)
+        .map(|n| format!("{prefix}{n}"))
+        .find(|name| !is_used(name))
+        .unwrap_or_default()
+}
+
+/**
+ * Add an entry to a category (Font, XObject...) of the page resources.
+ * The resources are copied into the page first, so that pages sharing or inheriting them are not affected.
+ */
+pub fn add_page_resource(
+    doc: &mut Document,
+    page_id: ObjectId,
+    category: &[u8],
+    name: &str,
+    id: ObjectId,
+) -> Result<()> {
+    let mut resources = inherited_attribute(doc, page_id, b"Resources")
+        .and_then(|resources| resources.as_dict().ok())
+        .cloned()
+        .unwrap_or_default();
+    let mut entries = resources
+        .get(category)
+        .ok()
+        .and_then(|entries| doc.dereference(entries).ok())
+        .and_then(|(_, entries)| entries.as_dict().ok())
+        .cloned()
+        .unwrap_or_default();
+    entries.set(name, id);
+    resources.set(category, entries);
+    doc.get_dictionary_mut(page_id)?.set("Resources", resources);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page_content(doc: &Document, page: u32) -> String {
+        let page_id = doc.get_pages()[&page];
+        String::from_utf8_lossy(&doc.get_page_content(page_id).unwrap()).into_owned()
+    }
+
+    #[test]
+    fn test_watermark_text() {
+        let input = "tests/tests_pdf/c.pdf";
+        let output = std::env::temp_dir().join("pdfcutter_watermark_text.pdf");
+        let output = output.to_string_lossy();
+        let options = WatermarkOptions {
+            source: WatermarkSource::Text("DRAFT (v2)".to_string()),
+            position: WatermarkPosition::Diagonal,
+            opacity: 30,
+        };
+
+        watermark_pages(input, &output, &[1, 3], &options).unwrap();
+        let doc = Document::load(&*output).unwrap();
+        assert!(page_content(&doc, 1).contains("(DRAFT (v2)) Tj"));
+        assert!(page_content(&doc, 1).starts_with("q\n"));
+        assert!(!page_content(&doc, 2).contains("PdfCutterWatermark"));
+        let page_id = doc.get_pages()[&3];
+        let fonts = inherited_attribute(&doc, page_id, b"Resources")
+            .unwrap()
+            .as_dict()
+            .unwrap()
+            .get(b"Font")
+            .unwrap()
+            .as_dict()
+            .unwrap();
+        assert!(fonts.has(b"PdfCutterWatermark1"));
+
+        // Stamping again keeps the first watermark's resources
+        watermark_pages(&output, &output, &[1], &options).unwrap();
+        let doc = Document::load(&*output).unwrap();
+        assert!(page_content(&doc, 1).contains("/PdfCutterWatermark2 gs"));
+
+        assert!(watermark_pages(input, &output, &[99], &options).is_err());
+        let blank = WatermarkOptions {
+            source: WatermarkSource::Text("  ".to_string()),
+            ..options
+        };
+        assert!(watermark_pages(input, &output, &[1], &blank).is_err());
+        std::fs::remove_file(&*output).unwrap();
+    }
+
+    #[test]
+    fn test_placement() {
+        // On a rotated page, the viewer's bottom-left corner is another corner of the MediaBox
+        let bounds = [0.0, 0.0, 600.0, 800.0];
+        let apply = |m: [f32; 6], (x, y): (f32, f32)| {
+            (m[0] * x + m[2] * y + m[4], m[1] * x + m[3] * y + m[5])
+        };
+        assert_eq!(apply(display_matrix(0, bounds), (0.0, 0.0)), (0.0, 0.0));
+        assert_eq!(apply(display_matrix(90, bounds), (0.0, 0.0)), (600.0, 0.0));
+        assert_eq!(
+            apply(display_matrix(180, bounds), (0.0, 0.0)),
+            (600.0, 800.0)
+        );
+        assert_eq!(apply(display_matrix(270, bounds), (0.0, 0.0)), (0.0, 800.0));
+
+        let corner = placement(
+            WatermarkPosition::BottomRight,
+            (600.0, 800.0),
+            (100.0, 20.0),
+        );
+        assert_eq!((corner[4], corner[5]), (600.0 - MARGIN - 100.0, MARGIN));
+
+        // The center of a diagonal box is the center of the page
+        let diagonal = placement(WatermarkPosition::Diagonal, (600.0, 800.0), (100.0, 20.0));
+        let (x, y) = apply(diagonal, (50.0, 10.0));
+        assert!((x - 300.0).abs() < 0.01 && (y - 400.0).abs() < 0.01);
+
+        assert_eq!(win_ansi("Café ✓"), b"Caf\xe9 ?");
+        assert_eq!(text_width(b"Hi"), 722.0 + 222.0);
+    }
+}
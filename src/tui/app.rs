@@ -1,15 +1,64 @@
+use super::state::ui_state::{DiscardAction, ToastKind};
 use super::state::{
-    CurrentScreen, DeleteConfig, FileState, MergeConfig, OperationMode, SplitConfig, UiState,
+    BrowserState, CompressConfig, CurrentScreen, DeleteConfig, ExtractConfig, FilePreview,
+    FileState, FileValidation, FolderPrompt, InPlacePrompt, InspectCache, LogViewState,
+    MergeConfig, OperationMode, OutputLocation, PageGridState, PageOrderState, PaletteState,
+    ProcessingState, QueueState, ResultState, RotateConfig, SpacePrompt, SplitConfig,
+    ThumbnailState, UiState, ValidationState, WatermarkConfig,
 };
+use crate::config;
+use crate::pdf::inspect::preview_document;
+use crate::pdf::output::{
+    DEFAULT_BACKUP_SUFFIX, DEFAULT_SPLIT_PREFIX, OverwritePolicy, contract_home,
+    resolve_output_path,
+};
+use crate::pdf::space::SpaceCheck;
+use crate::tui::errors::{TuiError, TuiResult};
+use crate::tui::graphics::detect_graphics_protocol;
+use crate::tui::state::browser_state::FAVORITES_SECTION;
+use crate::tui::utils::{error_chain, find_pdfs, list_directory, preview_split_outputs};
+use std::env;
+use std::path::{Path, PathBuf};
 
 pub struct App {
     pub current_screen: CurrentScreen,
     pub operation_mode: OperationMode,
 
     pub file_state: FileState,
+    pub browser: BrowserState,
     pub merge_config: MergeConfig,
     pub delete_config: DeleteConfig,
     pub split_config: SplitConfig,
+    pub rotate_config: RotateConfig,
+    pub extract_config: ExtractConfig,
+    pub compress_config: CompressConfig,
+    pub watermark_config: WatermarkConfig,
+    pub output_location: OutputLocation,
+    /// Checks made before writing outputs, from the config file.
+    pub space_check: SpaceCheck,
+    /// What to do when a default output name is taken, from the config file.
+    pub overwrite_policy: OverwritePolicy,
+    pub space_prompt: Option<SpacePrompt>,
+    /// Whether the in-place confirmation starts with the backup enabled, from the config file.
+    pub backup_in_place: bool,
+    /// Suffix the files replaced by an output are kept under, from the config file.
+    /// None lets outputs replace existing files.
+    pub backup_suffix: Option<String>,
+    pub in_place_prompt: Option<InPlacePrompt>,
+    /// Config file that settings changed in the TUI (pinned folders) are saved to.
+    /// None keeps them for the session only.
+    pub config_path: Option<PathBuf>,
+    pub page_grid: PageGridState,
+    pub page_order: PageOrderState,
+    /// The operation started from a configuration screen, while it runs.
+    pub processing: Option<ProcessingState>,
+    pub result: ResultState,
+    pub queue: QueueState,
+    pub palette: PaletteState,
+    pub logs: LogViewState,
+    pub inspect_cache: InspectCache,
+    pub validation: ValidationState,
+    pub thumbnails: ThumbnailState,
     pub ui_state: UiState,
 }
 
@@ -19,9 +68,32 @@ impl App {
             current_screen: CurrentScreen::Main,
             operation_mode: OperationMode::None,
             file_state: FileState::new(),
+            browser: BrowserState::new(),
             merge_config: MergeConfig::new(),
             delete_config: DeleteConfig::new(),
             split_config: SplitConfig::new(),
+            rotate_config: RotateConfig::new(),
+            extract_config: ExtractConfig::new(),
+            compress_config: CompressConfig::new(),
+            watermark_config: WatermarkConfig::new(),
+            output_location: OutputLocation::new(),
+            space_check: SpaceCheck::default(),
+            overwrite_policy: OverwritePolicy::default(),
+            space_prompt: None,
+            backup_in_place: false,
+            backup_suffix: None,
+            in_place_prompt: None,
+            config_path: None,
+            page_grid: PageGridState::new(),
+            page_order: PageOrderState::new(),
+            processing: None,
+            result: ResultState::new(),
+            queue: QueueState::new(),
+            palette: PaletteState::new(),
+            logs: LogViewState::new(),
+            inspect_cache: InspectCache::new(),
+            validation: ValidationState::new(),
+            thumbnails: ThumbnailState::new(detect_graphics_protocol()),
             ui_state: UiState::new(),
         }
     }
@@ -30,20 +102,57 @@ impl App {
         self.operation_mode = OperationMode::None;
         self.current_screen = CurrentScreen::Main;
         self.file_state.reset();
+        self.validation.reset();
         self.merge_config.reset();
         self.delete_config.reset();
         self.split_config.reset();
+        self.rotate_config.reset();
+        self.extract_config.reset();
+        self.compress_config.reset();
+        self.watermark_config.reset();
+        self.output_location.reset();
+        self.space_prompt = None;
+        self.in_place_prompt = None;
+        self.page_grid.reset();
+        self.page_order.reset();
+        self.result.reset();
         self.ui_state.reset();
     }
 
     pub fn set_error(&mut self, message: String) {
+        log::error!("{}", message);
         self.ui_state.set_error(message);
     }
 
+    /// Report a failed operation, keeping its full cause chain for the details view of the
+    /// result screen.
+    pub fn set_failure(&mut self, message: String, error: &anyhow::Error) {
+        let details = error_chain(error);
+        log::debug!("{}", details);
+        self.result.details = Some(details);
+        self.set_error(message);
+    }
+
     pub fn set_success(&mut self, message: String) {
+        log::info!("{}", message);
         self.ui_state.set_success(message);
     }
 
+    /// Show a transient error (e.g. a validation failure) without leaving the current screen.
+    pub fn notify_error(&mut self, message: String) {
+        log::warn!("{}", message);
+        self.ui_state.show_toast(ToastKind::Error, message);
+    }
+
+    /// Show a transient piece of information without leaving the current screen.
+    pub fn notify(&mut self, message: String) {
+        self.ui_state.show_toast(ToastKind::Info, message);
+    }
+
+    pub fn toast_error(&self) -> Option<&str> {
+        self.ui_state.toast_error()
+    }
+
     pub fn selected_files(&self) -> &Vec<String> {
         &self.file_state.selected_files
     }
@@ -100,10 +209,267 @@ impl App {
         self.ui_state.current_input = input;
     }
 
+    /// Add a file to the selection and load its page count and size.
     pub fn add_file(&mut self, file_path: String) {
+        let _ = self.inspect_cache.refresh(&file_path);
+        self.file_state.add_file(file_path);
+    }
+
+    /// Add a file typed by the user, which is loaded on the validation worker.
+    pub fn add_typed_file(&mut self, file_path: String) {
+        self.validate_file(&file_path);
         self.file_state.add_file(file_path);
     }
 
+    /// Offer to add the PDFs of a folder typed in the file input.
+    pub fn open_folder_prompt(&mut self, directory: &str) -> TuiResult<()> {
+        let files = find_pdfs(directory, false).map_err(|e| TuiError::UnreadableDirectory {
+            path: directory.to_string(),
+            reason: e.to_string(),
+        })?;
+        self.file_state.folder_prompt = Some(FolderPrompt {
+            directory: directory.to_string(),
+            recursive: false,
+            files,
+        });
+        Ok(())
+    }
+
+    /**
+     * List a directory in the file browser.
+     * @param directory The directory to list.
+     * @param select The entry to select (e.g. the folder just left), or None for the first one.
+     * @throws TuiError::UnreadableDirectory if the directory cannot be read.
+     */
+    pub fn browse(&mut self, directory: PathBuf, select: Option<&Path>) -> TuiResult<()> {
+        let entries = list_directory(&directory, &self.browser).map_err(|e| {
+            TuiError::UnreadableDirectory {
+                path: contract_home(&directory),
+                reason: e.to_string(),
+            }
+        })?;
+        self.browser.selected = select
+            .and_then(|path| entries.iter().position(|entry| entry.path == path))
+            .unwrap_or(0);
+        self.browser.entries = entries;
+        self.browser.directory = directory;
+        Ok(())
+    }
+
+    /**
+     * Make a directory the working directory, which relative paths and an empty output
+     * directory resolve against. The files already selected or queued keep pointing to the
+     * same files.
+     * @param directory The new working directory.
+     * @throws TuiError::ValidationPending while a selected file is being loaded.
+     * @throws TuiError::UnusableWorkingDirectory if the directory cannot be entered.
+     */
+    pub fn change_working_directory(&mut self, directory: &Path) -> TuiResult<()> {
+        // Loading results come back under the path as typed
+        if let Some(file) = self
+            .file_state
+            .selected_files
+            .iter()
+            .find(|file| self.validation.status(file) == Some(&FileValidation::Pending))
+        {
+            return Err(TuiError::ValidationPending { path: file.clone() });
+        }
+
+        let absolute = |path: &str| match std::path::absolute(path) {
+            Ok(absolute) if !path.is_empty() => absolute.to_string_lossy().into_owned(),
+            _ => path.to_string(),
+        };
+        let selected_files: Vec<String> = self
+            .file_state
+            .selected_files
+            .iter()
+            .map(|file| absolute(file))
+            .collect();
+        let image_path = absolute(&self.watermark_config.image_path);
+
+        env::set_current_dir(directory).map_err(|e| TuiError::UnusableWorkingDirectory {
+            path: contract_home(directory),
+            reason: e.to_string(),
+        })?;
+        for (old, new) in self.file_state.selected_files.iter().zip(&selected_files) {
+            if let Some(status) = self.validation.status(old).cloned() {
+                self.validation.set(new, status);
+            }
+        }
+        self.file_state.selected_files = selected_files;
+        self.watermark_config.image_path = image_path;
+        for step in &mut self.queue.steps {
+            step.operation.make_paths_absolute();
+        }
+        log::info!("Working directory: {}", directory.display());
+        Ok(())
+    }
+
+    /// Save the pinned directories to the config file, when there is one.
+    pub fn save_favorites(&self) -> anyhow::Result<()> {
+        match &self.config_path {
+            Some(path) => {
+                config::save_section(path, FAVORITES_SECTION, &self.browser.favorite_entries())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Include or leave out the PDFs of subfolders in the folder prompt.
+    pub fn toggle_folder_recursion(&mut self) -> TuiResult<()> {
+        if let Some(prompt) = &mut self.file_state.folder_prompt {
+            let recursive = !prompt.recursive;
+            prompt.files = find_pdfs(&prompt.directory, recursive).map_err(|e| {
+                TuiError::UnreadableDirectory {
+                    path: prompt.directory.clone(),
+                    reason: e.to_string(),
+                }
+            })?;
+            prompt.recursive = recursive;
+        }
+        Ok(())
+    }
+
+    /// Add the PDFs of the folder prompt that are not selected yet, then close it.
+    pub fn confirm_folder_prompt(&mut self) -> TuiResult<usize> {
+        let Some(prompt) = self.file_state.folder_prompt.take() else {
+            return Ok(0);
+        };
+        if prompt.files.is_empty() {
+            return Err(TuiError::NoPdfInDirectory {
+                path: prompt.directory,
+            });
+        }
+        let new_files: Vec<String> = prompt
+            .files
+            .into_iter()
+            .filter(|file| !self.file_state.selected_files.contains(file))
+            .collect();
+        let count = new_files.len();
+        for file in new_files {
+            self.add_typed_file(file);
+        }
+        Ok(count)
+    }
+
+    /// Load the selected file and show its quick preview.
+    pub fn open_preview(&mut self) -> TuiResult<()> {
+        let Some(path) = self
+            .selected_files()
+            .get(self.selected_file_index())
+            .cloned()
+        else {
+            return Ok(());
+        };
+        let preview = preview_document(&path)?;
+        self.inspect_cache.insert(&path, preview.info.clone());
+        self.file_state.preview = Some(FilePreview { path, preview });
+        Ok(())
+    }
+
+    /// Replace the path of a selected file, which is loaded on the validation worker.
+    pub fn replace_file(&mut self, index: usize, file_path: String) {
+        self.validate_file(&file_path);
+        self.file_state.replace_file(index, file_path);
+    }
+
+    /// Outputs of queued operations do not exist yet and are not loaded.
+    fn validate_file(&mut self, file_path: &str) {
+        if !self.queue.will_produce(file_path) {
+            self.validation.start(file_path);
+        }
+    }
+
+    /// Apply the files loaded by the validation worker: cache their page count and size,
+    /// or report the ones that are not valid PDFs, are encrypted or have no page.
+    pub fn poll_validations(&mut self) {
+        for (path, result) in self.validation.take_results() {
+            match result {
+                Ok(info) => {
+                    let (status, error) = if info.encrypted {
+                        let error = TuiError::EncryptedPdf { path: path.clone() };
+                        (FileValidation::Encrypted, Some(error))
+                    } else if info.page_count() == 0 {
+                        let error = TuiError::EmptyPdf { path: path.clone() };
+                        (FileValidation::NoPages, Some(error))
+                    } else {
+                        (FileValidation::Valid, None)
+                    };
+                    self.inspect_cache.insert(&path, info);
+                    self.validation.set(&path, status);
+                    if let Some(error) = error
+                        && self.file_state.selected_files.contains(&path)
+                    {
+                        log::debug!("'{}' cannot be used: {}", path, error);
+                        self.notify_error(error.to_string());
+                    }
+                }
+                Err(reason) => {
+                    log::debug!("'{}' is not a valid PDF: {}", path, reason);
+                    self.validation.set(&path, FileValidation::Invalid);
+                    if self.file_state.selected_files.contains(&path) {
+                        self.notify_error(TuiError::InvalidPdf { path }.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    /**
+     * Mark the selected files that were moved or deleted, and load again the ones that are
+     * back. Outputs of queued operations are left alone, since they do not exist yet.
+     */
+    pub fn check_files_exist(&mut self) {
+        let files = self.file_state.selected_files.clone();
+        for file in files {
+            if self.queue.will_produce(&file) {
+                continue;
+            }
+            let missing = self.validation.status(&file) == Some(&FileValidation::Missing);
+            match (Path::new(&file).exists(), missing) {
+                (false, false) => {
+                    log::warn!("'{}' was moved or deleted", file);
+                    self.validation.set(&file, FileValidation::Missing);
+                    self.inspect_cache.remove(&file);
+                }
+                (true, true) => self.validation.start(&file),
+                _ => {}
+            }
+        }
+    }
+
+    /// Why the selected files cannot be used yet: one is still being loaded, is invalid,
+    /// encrypted, empty or gone.
+    pub fn file_validation_error(&self) -> Option<TuiError> {
+        self.file_state
+            .selected_files
+            .iter()
+            .find_map(|file| match self.validation.status(file) {
+                Some(FileValidation::Pending) => {
+                    Some(TuiError::ValidationPending { path: file.clone() })
+                }
+                Some(FileValidation::Invalid) => Some(TuiError::InvalidPdf { path: file.clone() }),
+                Some(FileValidation::Encrypted) => {
+                    Some(TuiError::EncryptedPdf { path: file.clone() })
+                }
+                Some(FileValidation::NoPages) => Some(TuiError::EmptyPdf { path: file.clone() }),
+                Some(FileValidation::Missing) => {
+                    Some(TuiError::FileNotFound { path: file.clone() })
+                }
+                _ => None,
+            })
+    }
+
+    /// Open the path of the selected file in the input field to correct it.
+    pub fn start_editing_file(&mut self) {
+        let index = self.file_state.selected_file_index;
+        if let Some(file) = self.file_state.selected_files.get(index) {
+            self.ui_state.current_input = Some(file.clone());
+            self.ui_state.editing_input = true;
+            self.file_state.editing_file_index = Some(index);
+        }
+    }
+
     pub fn remove_current_file(&mut self) {
         let index = self.file_state.selected_file_index;
         self.file_state.remove_file(index);
@@ -113,6 +479,27 @@ impl App {
         self.file_state.swap_files(index1, index2);
     }
 
+    /// Move the file selected on the current screen to the start or the end of the merge order,
+    /// keeping it selected.
+    pub fn move_selected_file(&mut self, to_end: bool) {
+        let from = if self.current_screen == CurrentScreen::MergeConfig {
+            self.file_state.merge_file_index
+        } else {
+            self.file_state.selected_file_index
+        };
+        let to = if to_end {
+            self.files_len().saturating_sub(1)
+        } else {
+            0
+        };
+        self.file_state.move_file(from, to);
+        if self.current_screen == CurrentScreen::MergeConfig {
+            self.file_state.merge_file_index = to;
+        } else {
+            self.file_state.selected_file_index = to;
+        }
+    }
+
     pub fn files_len(&self) -> usize {
         self.file_state.len()
     }
@@ -120,4 +507,267 @@ impl App {
     pub fn files_is_empty(&self) -> bool {
         self.file_state.is_empty()
     }
+
+    /// Make sure the inspect cache holds up-to-date information for every selected file.
+    /// Files that cannot be inspected are left out of the cache.
+    pub fn refresh_inspect_cache(&mut self) {
+        for file in &self.file_state.selected_files {
+            let _ = self.inspect_cache.refresh(file);
+        }
+    }
+
+    /// Page count of the first selected file, when it is known.
+    pub fn first_file_page_count(&self) -> Option<usize> {
+        self.selected_files()
+            .first()
+            .and_then(|file| self.inspect_cache.page_count(file))
+    }
+
+    /// Full path of an output file (or prefix) inside the chosen output directory.
+    pub fn resolve_output(&self, name: &str) -> String {
+        resolve_output_path(&self.output_location.directory, name)
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    /// Suffix of the copies made of inputs written over: the configured one, or `bak`.
+    pub fn in_place_backup_suffix(&self) -> &str {
+        self.backup_suffix
+            .as_deref()
+            .unwrap_or(DEFAULT_BACKUP_SUFFIX)
+    }
+
+    /**
+     * Name an operation writes to: the typed name, or the default name when the field is empty.
+     * Only the default name follows the overwrite policy; a typed name replaces an existing file.
+     * @param name The name typed in the output field.
+     * @param default The default output name of the operation.
+     * @returns The output filename, relative to the output directory.
+     */
+    pub fn output_name(&self, name: &str, default: &str) -> String {
+        if !name.is_empty() {
+            return name.to_string();
+        }
+        self.overwrite_policy.free_name(default, |candidate| {
+            Path::new(&self.resolve_output(candidate)).exists()
+        })
+    }
+
+    /// Prefix of the split outputs: the typed one, or the default one following the overwrite policy.
+    pub fn split_output_prefix(&self) -> String {
+        let config = &self.split_config;
+        if !config.output_prefix.is_empty() {
+            return config.output_prefix.clone();
+        }
+        self.overwrite_policy
+            .free_name(DEFAULT_SPLIT_PREFIX, |candidate| {
+                preview_split_outputs(&config.segments, config.use_named_segments, candidate)
+                    .is_ok_and(|outputs| {
+                        outputs
+                            .iter()
+                            .any(|(filename, _)| Path::new(&self.resolve_output(filename)).exists())
+                    })
+            })
+    }
+
+    /// Record the files written by an operation and load their page counts and sizes.
+    pub fn set_outputs(&mut self, outputs: Vec<String>) {
+        for output in &outputs {
+            let _ = self.inspect_cache.refresh(output);
+        }
+        self.result.set_outputs(outputs);
+    }
+
+    /// Start a fresh operation, optionally with a first input file already selected.
+    pub fn start_operation(&mut self, mode: OperationMode, input: Option<String>) {
+        self.reset();
+        self.operation_mode = mode;
+        if let Some(file) = input {
+            self.add_file(file);
+        }
+        self.current_screen = CurrentScreen::FileSelection;
+    }
+
+    /// Start an operation on a file produced by the previous one.
+    /// The output directory and operation settings (rotation angle, named segments) are kept,
+    /// and single-file operations go straight to their configuration screen.
+    pub fn continue_with(&mut self, mode: OperationMode, input: String) {
+        let directory = self.output_location.directory.clone();
+        let angle = self.rotate_config.rule().angle;
+        let named_segments = self.split_config.use_named_segments;
+        let compress_options = self.compress_config.options;
+        let watermark = self.watermark_config.clone();
+
+        self.start_operation(mode, Some(input));
+        self.output_location.directory = directory;
+        self.rotate_config.rule_mut().angle = angle;
+        self.split_config.use_named_segments = named_segments;
+        self.compress_config.options = compress_options;
+        // The watermark itself is kept, but not the pages it was stamped on
+        self.watermark_config = WatermarkConfig {
+            pages: String::new(),
+            output_filename: String::new(),
+            ..watermark
+        };
+
+        if mode != OperationMode::Merge {
+            self.refresh_inspect_cache();
+            self.current_screen = self.config_screen();
+        }
+    }
+
+    /// Whether keys currently go to a text field or prompt rather than to screen shortcuts.
+    pub fn is_editing_text(&self) -> bool {
+        self.ui_state.editing_input
+            || self.file_state.folder_prompt.is_some()
+            || self.file_state.preview.is_some()
+            || self.output_location.editing
+            || self.merge_config.editing_output
+            || self.delete_config.editing_pages
+            || self.delete_config.editing_output
+            || self.split_config.editing_segments
+            || self.split_config.editing_prefix
+            || self.rotate_config.editing_pages
+            || self.rotate_config.editing_output
+            || self.extract_config.editing_pages
+            || self.extract_config.editing_output
+            || self.compress_config.editing_output
+            || self.watermark_config.editing_text
+            || self.watermark_config.editing_image
+            || self.watermark_config.editing_pages
+            || self.watermark_config.editing_output
+    }
+
+    /// Ask for exit confirmation from any state (Ctrl+C): overlays are closed and a running
+    /// operation or queue is cancelled.
+    pub fn request_exit(&mut self) {
+        self.palette.close();
+        self.ui_state.show_cheat_sheet = false;
+        self.ui_state.discard_prompt = None;
+        self.space_prompt = None;
+        self.in_place_prompt = None;
+        if let Some(processing) = &self.processing {
+            processing.job.cancel();
+        }
+        if self.queue.running {
+            self.queue.cancel();
+        }
+        self.current_screen = CurrentScreen::Exiting;
+    }
+
+    /// Whether an operation is running on a worker thread, from a configuration screen or the queue.
+    pub fn is_busy(&self) -> bool {
+        self.processing.is_some() || self.queue.running
+    }
+
+    /// Cancel the running operation and wait for it to stop, so that quitting never leaves a
+    /// half-written output behind.
+    pub fn cancel_and_wait(&mut self) {
+        // Whatever the outcome, a failed or cancelled operation has cleaned up after itself
+        if let Some(processing) = self.processing.take() {
+            processing.job.cancel();
+            let _ = processing.job.wait();
+        }
+        if let Some((_, job)) = self.queue.job.take() {
+            job.cancel();
+            let _ = job.wait();
+        }
+    }
+
+    /// Whether files were picked for an operation that has not run yet, so going back to the
+    /// main menu or starting over would lose them with their settings.
+    pub fn has_unsaved_work(&self) -> bool {
+        self.operation_mode != OperationMode::None
+            && !self.files_is_empty()
+            && self.ui_state.message.is_none()
+    }
+
+    /// Whether pages, segments, output names or a watermark were typed on the configuration
+    /// screen of the current operation.
+    pub fn has_typed_config(&self) -> bool {
+        match self.operation_mode {
+            OperationMode::Merge => self.merge_config.has_typed_input(),
+            OperationMode::Delete => self.delete_config.has_typed_input(),
+            OperationMode::Split => self.split_config.has_typed_input(),
+            OperationMode::Rotate => self.rotate_config.has_typed_input(),
+            OperationMode::Extract => self.extract_config.has_typed_input(),
+            OperationMode::Compress => self.compress_config.has_typed_input(),
+            OperationMode::Watermark => self.watermark_config.has_typed_input(),
+            OperationMode::None => false,
+        }
+    }
+
+    /// Clear the configuration of the current operation, keeping its files.
+    pub fn reset_config(&mut self) {
+        match self.operation_mode {
+            OperationMode::Merge => self.merge_config.reset(),
+            OperationMode::Delete => self.delete_config.reset(),
+            OperationMode::Split => self.split_config.reset(),
+            OperationMode::Rotate => self.rotate_config.reset(),
+            OperationMode::Extract => self.extract_config.reset(),
+            OperationMode::Compress => self.compress_config.reset(),
+            OperationMode::Watermark => self.watermark_config.reset(),
+            OperationMode::None => {}
+        }
+    }
+
+    /// Go back from a configuration screen to the file list (Esc), asking first whether to
+    /// drop what was typed on it.
+    pub fn leave_config_screen(&mut self) {
+        if self.ui_state.confirm_discard && self.has_typed_config() {
+            self.ui_state.discard_prompt = Some(DiscardAction::Configuration);
+        } else {
+            self.current_screen = CurrentScreen::FileSelection;
+        }
+    }
+
+    /// Go back to the main menu (Ctrl+H) or start the operation over, asking first when
+    /// files and settings would be lost, unless the confirmation is turned off.
+    pub fn request_discard(&mut self, action: DiscardAction) {
+        self.palette.close();
+        self.ui_state.show_cheat_sheet = false;
+        if self.ui_state.confirm_discard && self.has_unsaved_work() {
+            self.ui_state.discard_prompt = Some(action);
+        } else {
+            self.discard(action);
+        }
+    }
+
+    /// Drop the files and settings of the current operation, or only its typed configuration.
+    /// The queue is kept.
+    pub fn discard(&mut self, action: DiscardAction) {
+        match action {
+            DiscardAction::GoHome => self.reset(),
+            DiscardAction::StartOver => self.start_operation(self.operation_mode, None),
+            DiscardAction::Configuration => {
+                self.reset_config();
+                self.current_screen = CurrentScreen::FileSelection;
+            }
+        }
+    }
+
+    /// Open the log viewer, or go back to the screen it was opened from.
+    pub fn toggle_logs(&mut self) {
+        if self.current_screen == CurrentScreen::Logs {
+            self.current_screen = self.logs.previous_screen;
+        } else {
+            self.logs.previous_screen = self.current_screen;
+            self.logs.scroll = 0;
+            self.current_screen = CurrentScreen::Logs;
+        }
+    }
+
+    /// Configuration screen of the current operation mode.
+    pub fn config_screen(&self) -> CurrentScreen {
+        match self.operation_mode {
+            OperationMode::Merge => CurrentScreen::MergeConfig,
+            OperationMode::Delete => CurrentScreen::DeleteConfig,
+            OperationMode::Split => CurrentScreen::SplitConfig,
+            OperationMode::Rotate => CurrentScreen::RotateConfig,
+            OperationMode::Extract => CurrentScreen::ExtractConfig,
+            OperationMode::Compress => CurrentScreen::CompressConfig,
+            OperationMode::Watermark => CurrentScreen::WatermarkConfig,
+            OperationMode::None => CurrentScreen::Main,
+        }
+    }
 }
@@ -0,0 +1,190 @@
+use crate::config::Config;
+use ratatui::buffer::Buffer;
+use ratatui::text::Span;
+use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Config key drawing plain ASCII instead of emoji and symbols: `true`, `false`, or unset to
+/// decide from the terminal.
+pub const ASCII_KEY: &str = "ascii";
+
+static ASCII_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Whether the next frames are drawn with ASCII only.
+pub fn ascii_mode() -> bool {
+    ASCII_MODE.load(Ordering::Relaxed)
+}
+
+pub fn set_ascii_mode(enabled: bool) {
+    ASCII_MODE.store(enabled, Ordering::Relaxed);
+}
+
+/**
+ * Decide whether to draw with ASCII only: `--ascii` forces it, then the `ascii` setting
+ * applies, and without it the terminal is checked.
+ * @param config The user configuration.
+ * @param flag Whether `--ascii` was given.
+ * @returns true to replace emoji and symbols.
+ * @throws String if the configured value is not true or false.
+ */
+pub fn ascii_mode_from_config(config: &Config, flag: bool) -> Result<bool, String> {
+    if flag {
+        return Ok(true);
+    }
+    Ok(config
+        .get_bool(ASCII_KEY)?
+        .unwrap_or_else(detect_ascii_terminal))
+}
+
+/**
+ * Detect a terminal that cannot show Unicode symbols: a locale without UTF-8 (e.g. `C` or
+ * `en_US.ISO-8859-1`), or the Linux console, whose font has no emoji.
+ * Detection is based on the environment only, so it never writes queries to the terminal.
+ * @returns true when emoji and symbols would render as boxes.
+ */
+pub fn detect_ascii_terminal() -> bool {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .map(|name| env::var(name).unwrap_or_default())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default();
+    needs_ascii(&locale, &env::var("TERM").unwrap_or_default())
+}
+
+fn needs_ascii(locale: &str, term: &str) -> bool {
+    let locale = locale.to_lowercase();
+    let utf8 = locale.is_empty() || locale.contains("utf-8") || locale.contains("utf8");
+    !utf8 || term == "linux"
+}
+
+/**
+ * ASCII replacement of a symbol drawn in one cell, None to keep it (ASCII, letters).
+ * Decorative emoji (screen titles, menu entries) are dropped, symbols carrying a meaning
+ * (status, arrows, checkboxes) get a short label.
+ * @param symbol The grapheme of the cell.
+ * @returns The replacement, at most as wide as the symbol for the label to be kept whole.
+ */
+fn ascii_symbol(symbol: &str) -> Option<&'static str> {
+    let base = symbol.trim_end_matches('\u{fe0f}');
+    let mut chars = base.chars();
+    let c = chars.next()?;
+    if c.is_ascii() || chars.next().is_some_and(|next| next != '\u{200d}') {
+        return None;
+    }
+    Some(match c {
+        '✅' => "OK",
+        '❌' | '✗' => "x",
+        '✓' => "v",
+        '⚠' => "!!",
+        '⏳' => "..",
+        '⏭' => ">>",
+        '❓' => "?",
+        'ℹ' => "i",
+        '📌' => "*",
+        '☑' => "X",
+        '☐' => "_",
+        '●' => "*",
+        '○' => "o",
+        '▶' => ">",
+        '◀' => "<",
+        '→' => ">",
+        '←' => "<",
+        '↑' => "^",
+        '↓' => "v",
+        '•' => "-",
+        '—' => "-",
+        '…' => ".",
+        '“' | '”' => "\"",
+        '×' => "x",
+        '≤' => "<",
+        // Box drawing: borders, and the scrollbar track
+        '─' | '━' | '═' | '┄' | '┅' | '┈' | '┉' | '╌' | '╍' | '╴' | '╶' | '╸' | '╺' => {
+            "-"
+        }
+        '│' | '┃' | '║' | '┆' | '┇' | '┊' | '┋' | '╎' | '╏' | '╵' | '╷' | '╹' | '╻' => {
+            "|"
+        }
+        '\u{2500}'..='\u{257f}' => "+",
+        // Block elements: gauges, page thumbnails and scrollbar thumbs
+        '░' => ".",
+        '\u{2580}'..='\u{259f}' => "#",
+        '▲' => "^",
+        '▼' => "v",
+        // Other symbols and emoji are decoration
+        '\u{2190}'..='\u{2bff}' | '\u{1f000}'.. => "",
+        _ => return None,
+    })
+}
+
+/**
+ * Replace the emoji and special symbols of a drawn frame by plain ASCII.
+ * Works on the buffer once every widget is drawn, so every screen, translation and widget
+ * symbol is covered. A wide symbol is replaced over both of its cells, keeping the layout.
+ * @param buffer The frame buffer.
+ */
+pub fn to_ascii(buffer: &mut Buffer) {
+    let area = buffer.area;
+    for y in area.top()..area.bottom() {
+        let mut x = area.left();
+        while x < area.right() {
+            let symbol = buffer[(x, y)].symbol().to_string();
+            let width = Span::raw(symbol.as_str()).width().max(1) as u16;
+            if let Some(label) = ascii_symbol(&symbol) {
+                let mut label = label.chars();
+                for offset in 0..width.min(area.right() - x) {
+                    let c = label.next().unwrap_or(' ');
+                    buffer[(x + offset, y)].set_char(c);
+                }
+            }
+            x += width;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+    use ratatui::style::Style;
+
+    #[test]
+    fn test_needs_ascii() {
+        assert!(!needs_ascii("en_US.UTF-8", "xterm-256color"));
+        assert!(!needs_ascii("fr_FR.utf8", "xterm"));
+        assert!(!needs_ascii("", "xterm"));
+        assert!(needs_ascii("C", "xterm"));
+        assert!(needs_ascii("en_US.ISO-8859-1", "xterm"));
+        assert!(needs_ascii("en_US.UTF-8", "linux"));
+    }
+
+    #[test]
+    fn test_ascii_mode_from_config() {
+        let config = Config::parse("ascii = false").unwrap();
+        assert_eq!(ascii_mode_from_config(&config, true), Ok(true));
+        assert_eq!(ascii_mode_from_config(&config, false), Ok(false));
+        let config = Config::parse("ascii = true").unwrap();
+        assert_eq!(ascii_mode_from_config(&config, false), Ok(true));
+        assert!(ascii_mode_from_config(&Config::parse("ascii = yes").unwrap(), false).is_err());
+    }
+
+    #[test]
+    fn test_to_ascii() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 30, 3));
+        buffer.set_string(0, 0, "📋 Queue • ✅ été", Style::default());
+        buffer.set_string(0, 1, "┌─┐ ▶ ↑↓ ⚠️ ░█", Style::default());
+        buffer.set_string(0, 2, "✓ ✗ ☑ ☐ ○ ●", Style::default());
+        to_ascii(&mut buffer);
+
+        let row = |y: u16| -> String {
+            (0..30)
+                .map(|x| buffer[(x, y)].symbol().to_string())
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        };
+        // Decorative emoji become blank, accented letters are kept
+        assert_eq!(row(0), "   Queue - OK été");
+        assert_eq!(row(1), "+-+ > ^v !! .#");
+        assert_eq!(row(2), "v x X _ o *");
+    }
+}
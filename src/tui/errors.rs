@@ -1,30 +1,107 @@
+use crate::i18n::tr;
+use crate::pdf::page_spec::SpecError;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum TuiError {
-    #[error("File not found: {path}")]
+    #[error("{}", tr!("File not found: {path}", path = .path))]
     FileNotFound { path: String },
 
-    #[error("Invalid PDF file: {path}")]
+    #[error("{}", tr!("Invalid PDF file: {path}", path = .path))]
     InvalidPdf { path: String },
 
-    #[error("Invalid page range: {input}")]
+    #[error("{}", tr!("Encrypted PDF, remove its password first: {path}", path = .path))]
+    EncryptedPdf { path: String },
+
+    #[error("{}", tr!("PDF without any page: {path}", path = .path))]
+    EmptyPdf { path: String },
+
+    #[error("{}", tr!("Unsupported image (use a JPEG or PNG file): {path}", path = .path))]
+    UnsupportedImage { path: String },
+
+    #[error("{}", tr!("Still checking {path}, please wait", path = .path))]
+    ValidationPending { path: String },
+
+    #[error("{}", tr!("No PDF files found in {path}", path = .path))]
+    NoPdfInDirectory { path: String },
+
+    #[error("{}", tr!("Cannot read folder {path}: {reason}", path = .path, reason = .reason))]
+    UnreadableDirectory { path: String, reason: String },
+
+    #[error("{}", tr!("Cannot work in {path}: {reason}", path = .path, reason = .reason))]
+    UnusableWorkingDirectory { path: String, reason: String },
+
+    #[error("{}", tr!("{path} is a file, not a folder", path = .path))]
+    OutputDirectoryIsFile { path: String },
+
+    #[error("{}", tr!("Invalid output name '{name}': {reason}", name = .name, reason = .reason))]
+    InvalidOutputName { name: String, reason: String },
+
+    #[error("{}", tr!("Invalid page range: {input}", input = .input))]
     InvalidPageRange { input: String },
 
-    #[error("Not enough files for merge (need at least 2, got {count})")]
+    #[error("{}", tr!("'{segment}' has a name: press Space to use named segments", segment = .segment))]
+    NamedSegmentInPageMode { segment: String },
+
+    #[error("{}", tr!("'{segment}' needs a name before ':'", segment = .segment))]
+    UnnamedSegment { segment: String },
+
+    #[error("{}", tr!("Page {page} is out of range (document has {page_count} pages)", page = .page, page_count = .page_count))]
+    PageOutOfRange { page: u32, page_count: usize },
+
+    #[error("{}", tr!("'{first}' and '{second}' both include page {page} (O allows overlaps)", first = .first, second = .second, page = .page))]
+    OverlappingSegments {
+        first: String,
+        second: String,
+        page: u32,
+    },
+
+    #[error("{}", tr!("'{first}' and '{second}' would both be written to '{file}'", first = .first, second = .second, file = .file))]
+    SharedOutputFile {
+        first: String,
+        second: String,
+        file: String,
+    },
+
+    #[error("{}", tr!("Cannot delete all {page_count} pages of the document", page_count = .page_count))]
+    AllPagesSelected { page_count: usize },
+
+    #[error("{}", tr!("'{input}' needs the page count of the document", input = .input))]
+    PageCountNeeded { input: String },
+
+    #[error("{}", tr!("Not enough files for merge (need at least 2, got {count})", count = .count))]
     InsufficientFiles { count: usize },
 
-    #[error("No files selected for delete operation (need exactly 1)")]
+    #[error("{}", tr!("No file selected (this operation needs exactly 1)"))]
     NoFilesSelected,
 
-    #[error("Too many files for delete operation (need exactly 1, got {count})")]
+    #[error("{}", tr!("Too many files (this operation needs exactly 1, got {count})", count = .count))]
     TooManyFiles { count: usize },
 
-    #[error("PDF operation failed: {source}")]
+    #[error("{}", tr!("PDF operation failed: {source}", source = .source))]
     PdfOperation {
         #[from]
         source: anyhow::Error,
     },
 }
 
+/// Errors of page and segment specifications, reported with the TUI's own wording where it has
+/// one.
+impl From<SpecError> for TuiError {
+    fn from(error: SpecError) -> Self {
+        match error {
+            SpecError::PageOutOfRange {
+                page, page_count, ..
+            } => TuiError::PageOutOfRange { page, page_count },
+            SpecError::AllPages { page_count } => TuiError::AllPagesSelected { page_count },
+            SpecError::PageCountNeeded { reference, .. } => {
+                TuiError::PageCountNeeded { input: reference }
+            }
+            error => TuiError::InvalidPageRange {
+                input: error.to_string(),
+            },
+        }
+    }
+}
+
 pub type TuiResult<T> = Result<T, TuiError>;
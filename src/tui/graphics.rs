@@ -0,0 +1,311 @@
+use crate::pdf::thumbnail::Thumbnail;
+use crate::tui::state::thumbnail_state::DEFAULT_CELL_SIZE;
+use crate::tui::system::base64_encode;
+use crossterm::terminal::window_size;
+use flate2::Compression;
+use flate2::write::ZlibEncoder;
+use std::env;
+use std::io::Write;
+
+/// Id of the page thumbnail among the kitty images, replaced each time another page is shown.
+const KITTY_IMAGE_ID: u32 = 144;
+
+/// Longest base64 payload of one kitty graphics command.
+const KITTY_CHUNK_SIZE: usize = 4096;
+
+/// Levels of each channel in the sixel color cube.
+const SIXEL_CUBE_LEVELS: u32 = 6;
+
+/// Shades of gray after the color cube, so scanned and text pages keep their antialiasing.
+const SIXEL_GRAY_LEVELS: u32 = 40;
+
+/// Inline image protocols a terminal may understand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsProtocol {
+    Kitty,
+    Sixel,
+    None,
+}
+
+impl GraphicsProtocol {
+    pub fn label(&self) -> &'static str {
+        match self {
+            GraphicsProtocol::Kitty => "kitty",
+            GraphicsProtocol::Sixel => "sixel",
+            GraphicsProtocol::None => "none",
+        }
+    }
+
+    /**
+     * Escape sequence drawing a thumbnail with its top-left corner at the cursor.
+     * @param thumbnail The rendered page.
+     * @param columns The width it covers, in terminal cells.
+     * @param rows The height it covers, in terminal cells.
+     * @returns The sequence, empty without a protocol.
+     */
+    pub fn image(&self, thumbnail: &Thumbnail, columns: u16, rows: u16) -> String {
+        match self {
+            GraphicsProtocol::Kitty => kitty_image(thumbnail, columns, rows),
+            GraphicsProtocol::Sixel => sixel_image(thumbnail),
+            GraphicsProtocol::None => String::new(),
+        }
+    }
+}
+
+/**
+ * Detect which inline image protocol the running terminal supports.
+ * Detection is based on the environment only, so it never writes queries to the terminal.
+ * @returns The detected protocol, or GraphicsProtocol::None.
+ */
+pub fn detect_graphics_protocol() -> GraphicsProtocol {
+    protocol_from_env(
+        &env::var("TERM").unwrap_or_default(),
+        &env::var("TERM_PROGRAM").unwrap_or_default(),
+        env::var_os("KITTY_WINDOW_ID").is_some(),
+    )
+}
+
+fn protocol_from_env(term: &str, term_program: &str, in_kitty: bool) -> GraphicsProtocol {
+    let term = term.to_lowercase();
+    let term_program = term_program.to_lowercase();
+
+    if in_kitty || term.contains("kitty") || ["wezterm", "ghostty"].contains(&term_program.as_str())
+    {
+        GraphicsProtocol::Kitty
+    } else if term.contains("sixel")
+        || ["foot", "mlterm", "contour"]
+            .iter()
+            .any(|t| term.starts_with(t))
+    {
+        GraphicsProtocol::Sixel
+    } else {
+        GraphicsProtocol::None
+    }
+}
+
+/// Size of a terminal cell in pixels, or a common size when the terminal does not report it.
+pub fn cell_size() -> (u32, u32) {
+    match window_size() {
+        Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => (
+            (size.width / size.columns) as u32,
+            (size.height / size.rows) as u32,
+        ),
+        _ => DEFAULT_CELL_SIZE,
+    }
+}
+
+/// Kitty command removing the thumbnail from the screen and freeing its pixels.
+pub fn kitty_delete() -> String {
+    format!("\x1b_Ga=d,d=I,i={},q=2\x1b\\", KITTY_IMAGE_ID)
+}
+
+/**
+ * Kitty graphics commands sending the compressed pixels of a thumbnail in chunks, then placing
+ * it scaled to the given cells without moving the cursor.
+ */
+fn kitty_image(thumbnail: &Thumbnail, columns: u16, rows: u16) -> String {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    // Writing to memory cannot fail
+    let _ = encoder.write_all(&thumbnail.rgba);
+    let payload = base64_encode(&encoder.finish().unwrap_or_default());
+
+    let chunks: Vec<&[u8]> = payload.as_bytes().chunks(KITTY_CHUNK_SIZE).collect();
+    let mut sequence = String::with_capacity(payload.len() + chunks.len() * 16 + 64);
+    for (index, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(index + 1 < chunks.len());
+        if index == 0 {
+            sequence.push_str(&format!(
+                "\x1b_Ga=T,f=32,o=z,s={},v={},c={},r={},i={},q=2,C=1,m={};",
+                thumbnail.width, thumbnail.height, columns, rows, KITTY_IMAGE_ID, more
+            ));
+        } else {
+            sequence.push_str(&format!("\x1b_Gm={};", more));
+        }
+        sequence.push_str(&String::from_utf8_lossy(chunk));
+        sequence.push_str("\x1b\\");
+    }
+    sequence
+}
+
+/// Palette register of a pixel: a shade of gray when its channels are close, else the color cube.
+fn sixel_color(pixel: &[u8]) -> usize {
+    let (r, g, b) = (pixel[0] as u32, pixel[1] as u32, pixel[2] as u32);
+    if r.max(g).max(b) - r.min(g).min(b) < 16 {
+        let gray = (r + g + b) / 3;
+        let cube = SIXEL_CUBE_LEVELS.pow(3);
+        return (cube + (gray * (SIXEL_GRAY_LEVELS - 1) + 127) / 255) as usize;
+    }
+    let level = |c: u32| (c * (SIXEL_CUBE_LEVELS - 1) + 127) / 255;
+    (level(r) * SIXEL_CUBE_LEVELS * SIXEL_CUBE_LEVELS + level(g) * SIXEL_CUBE_LEVELS + level(b))
+        as usize
+}
+
+/// Sixel palette definitions of the color cube and the gray ramp, in percent.
+fn sixel_palette() -> String {
+    let mut palette = String::new();
+    let step = 100 / (SIXEL_CUBE_LEVELS - 1);
+    for r in 0..SIXEL_CUBE_LEVELS {
+        for g in 0..SIXEL_CUBE_LEVELS {
+            for b in 0..SIXEL_CUBE_LEVELS {
+                let index = r * SIXEL_CUBE_LEVELS * SIXEL_CUBE_LEVELS + g * SIXEL_CUBE_LEVELS + b;
+                palette.push_str(&format!(
+                    "#{};2;{};{};{}",
+                    index,
+                    r * step,
+                    g * step,
+                    b * step
+                ));
+            }
+        }
+    }
+    for level in 0..SIXEL_GRAY_LEVELS {
+        let percent = level * 100 / (SIXEL_GRAY_LEVELS - 1);
+        palette.push_str(&format!(
+            "#{};2;{};{};{}",
+            SIXEL_CUBE_LEVELS.pow(3) + level,
+            percent,
+            percent,
+            percent
+        ));
+    }
+    palette
+}
+
+/// Append a run of identical sixels, with the repeat introducer once it is shorter.
+fn push_sixel_run(sequence: &mut String, sixel: char, count: usize) {
+    if count > 3 {
+        sequence.push_str(&format!("!{}{}", count, sixel));
+    } else {
+        sequence.extend(std::iter::repeat_n(sixel, count));
+    }
+}
+
+/**
+ * Sixel sequence of a thumbnail: bands of six pixel rows, each drawn once per color it uses.
+ */
+fn sixel_image(thumbnail: &Thumbnail) -> String {
+    let width = thumbnail.width as usize;
+    let height = thumbnail.height as usize;
+    let colors: Vec<usize> = thumbnail.rgba.chunks(4).map(sixel_color).collect();
+    let register_count = (SIXEL_CUBE_LEVELS.pow(3) + SIXEL_GRAY_LEVELS) as usize;
+
+    let mut sequence = format!("\x1bPq\"1;1;{};{}", width, height);
+    sequence.push_str(&sixel_palette());
+    for top in (0..height).step_by(6) {
+        let rows = top..(top + 6).min(height);
+        let mut used = vec![false; register_count];
+        for y in rows.clone() {
+            for &color in &colors[y * width..(y + 1) * width] {
+                used[color] = true;
+            }
+        }
+
+        for color in (0..register_count).filter(|&c| used[c]) {
+            sequence.push_str(&format!("#{}", color));
+            let mut run = ('?', 0);
+            for x in 0..width {
+                let bits = rows
+                    .clone()
+                    .filter(|&y| colors[y * width + x] == color)
+                    .fold(0u8, |bits, y| bits | 1 << (y - top));
+                let sixel = (b'?' + bits) as char;
+                if sixel == run.0 {
+                    run.1 += 1;
+                } else {
+                    push_sixel_run(&mut sequence, run.0, run.1);
+                    run = (sixel, 1);
+                }
+            }
+            push_sixel_run(&mut sequence, run.0, run.1);
+            // Back to the start of the band for the next color
+            sequence.push('$');
+        }
+        sequence.push('-');
+    }
+    sequence.push_str("\x1b\\");
+    sequence
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thumbnail(width: u32, height: u32, pixel: [u8; 4]) -> Thumbnail {
+        Thumbnail {
+            width,
+            height,
+            rgba: pixel.repeat((width * height) as usize),
+        }
+    }
+
+    #[test]
+    fn test_protocol_from_env() {
+        assert_eq!(
+            protocol_from_env("xterm-kitty", "", false),
+            GraphicsProtocol::Kitty
+        );
+        assert_eq!(
+            protocol_from_env("xterm-256color", "", true),
+            GraphicsProtocol::Kitty
+        );
+        assert_eq!(
+            protocol_from_env("xterm-256color", "WezTerm", false),
+            GraphicsProtocol::Kitty
+        );
+        assert_eq!(
+            protocol_from_env("foot-extra", "", false),
+            GraphicsProtocol::Sixel
+        );
+        assert_eq!(
+            protocol_from_env("xterm-256color", "", false),
+            GraphicsProtocol::None
+        );
+    }
+
+    #[test]
+    fn test_kitty_image() {
+        let sequence = GraphicsProtocol::Kitty.image(&thumbnail(2, 3, [255; 4]), 1, 1);
+        assert!(sequence.starts_with("\x1b_Ga=T,f=32,o=z,s=2,v=3,c=1,r=1,i=144,q=2,C=1,m=0;"));
+        assert!(sequence.ends_with("\x1b\\"));
+
+        // Large images are sent in chunks, each but the last announcing another one
+        let mut noise = thumbnail(200, 200, [0; 4]);
+        let mut state = 0x2545_f491_u32;
+        for byte in noise.rgba.iter_mut() {
+            // xorshift, so the pixels do not compress
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            *byte = state as u8;
+        }
+        let sequence = kitty_image(&noise, 20, 10);
+        let commands: Vec<&str> = sequence.split("\x1b\\").filter(|c| !c.is_empty()).collect();
+        assert!(commands.len() > 1);
+        assert!(commands[0].contains(",m=1;"));
+        assert!(
+            commands[1..commands.len() - 1]
+                .iter()
+                .all(|c| c.starts_with("\x1b_Gm=1;"))
+        );
+        assert!(commands.last().unwrap().starts_with("\x1b_Gm=0;"));
+        assert!(commands.iter().all(|c| c.len() <= KITTY_CHUNK_SIZE + 128));
+        assert_eq!(GraphicsProtocol::None.image(&noise, 20, 10), "");
+    }
+
+    #[test]
+    fn test_sixel_image() {
+        assert_eq!(sixel_color(&[0, 0, 0, 255]), 216);
+        assert_eq!(sixel_color(&[255, 255, 255, 255]), 255);
+        assert_eq!(sixel_color(&[255, 0, 0, 255]), 180);
+
+        // A white image of 3 × 7 pixels: a full band and a band of one row
+        let sequence = sixel_image(&thumbnail(3, 7, [255; 4]));
+        assert!(sequence.starts_with("\x1bPq\"1;1;3;7#0;2;0;0;0"));
+        assert!(sequence.contains("#255;2;100;100;100"));
+        assert!(sequence.ends_with("#255~~~$-#255@@@$-\x1b\\"));
+
+        // Long runs use the repeat introducer
+        let sequence = sixel_image(&thumbnail(10, 6, [0, 0, 0, 255]));
+        assert!(sequence.ends_with("#216!10~$-\x1b\\"));
+    }
+}
@@ -0,0 +1,27 @@
+use crate::build_info;
+use crate::i18n::tr;
+use crate::tui::app::App;
+use crate::tui::state::CurrentScreen;
+use crate::tui::system::copy_to_clipboard;
+use crossterm::event::KeyCode;
+
+/**
+ * Handle input in the About screen.
+ * The build details can be copied as plain text, to paste them in a bug report.
+ * @param key The key event.
+ * @param app The application state.
+ */
+pub fn handle_about_input(key: KeyCode, app: &mut App) {
+    match key {
+        KeyCode::Char('c') | KeyCode::Char('C') => {
+            match copy_to_clipboard(&build_info::report(app.config_path.as_deref())) {
+                Ok(()) => app.notify(tr!("Copied the build details to the clipboard").to_string()),
+                Err(e) => {
+                    app.notify_error(tr!("Could not copy the build details: {error}", error = e))
+                }
+            }
+        }
+        KeyCode::Esc => app.current_screen = CurrentScreen::Main,
+        _ => {}
+    }
+}
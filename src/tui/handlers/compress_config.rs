@@ -0,0 +1,161 @@
+use crate::i18n::tr;
+use crate::pdf::operation::Operation;
+use crate::pdf::output::DEFAULT_COMPRESS_OUTPUT;
+use crate::tui::app::App;
+use crate::tui::handlers::output_location::{confirm_run, handle_output_directory_input};
+use crate::tui::handlers::processing::start_processing;
+use crate::tui::handlers::queue::queue_operation;
+use crate::tui::state::Completion;
+use crate::tui::utils::{
+    describe_operation, format_size, validate_output_directory, validate_output_name,
+};
+use crossterm::event::KeyCode;
+
+/**
+ * Handle input in the compress configuration screen.
+ * Allows toggling stream recompression, metadata stripping and annotation flattening, choosing
+ * the image resolution,
+ * editing the output filename, and starting the compression.
+ * @param key The key event.
+ * @param app The application state.
+ */
+pub fn handle_compress_config_input(key: KeyCode, app: &mut App) {
+    if app.output_location.editing {
+        handle_output_directory_input(key, app);
+        return;
+    }
+
+    if app.compress_config.editing_output {
+        match key {
+            KeyCode::Char(c) => {
+                app.compress_config.output_filename.push(c);
+            }
+            KeyCode::Backspace => {
+                app.compress_config.output_filename.pop();
+            }
+            KeyCode::Enter | KeyCode::Tab => {
+                app.compress_config.editing_output = false;
+
+                if !app.compress_config.output_filename.ends_with(".pdf")
+                    && !app.compress_config.output_filename.is_empty()
+                {
+                    app.compress_config.output_filename.push_str(".pdf");
+                }
+            }
+            KeyCode::Esc => {
+                app.compress_config.editing_output = false;
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    match key {
+        KeyCode::Char('s') | KeyCode::Char('S') => {
+            let options = &mut app.compress_config.options;
+            options.recompress_streams = !options.recompress_streams;
+        }
+
+        KeyCode::Char('i') | KeyCode::Char('I') | KeyCode::Right => {
+            app.compress_config.cycle_image_dpi(true);
+        }
+
+        KeyCode::Left => {
+            app.compress_config.cycle_image_dpi(false);
+        }
+
+        KeyCode::Char('m') | KeyCode::Char('M') => {
+            let options = &mut app.compress_config.options;
+            options.strip_metadata = !options.strip_metadata;
+        }
+
+        KeyCode::Char('f') | KeyCode::Char('F') => {
+            let options = &mut app.compress_config.options;
+            options.flatten_annotations = !options.flatten_annotations;
+        }
+
+        KeyCode::Tab => {
+            app.compress_config.editing_output = true;
+        }
+
+        KeyCode::Char('d') | KeyCode::Char('D') => {
+            app.output_location.editing = true;
+        }
+
+        KeyCode::Enter => match compress_operation(app) {
+            Ok(operation) if confirm_run(app, &operation) => perform_compress(app, operation),
+            Ok(_) => {}
+            Err(e) => app.notify_error(e),
+        },
+
+        KeyCode::Char('a') | KeyCode::Char('A') => match compress_operation(app) {
+            Ok(operation) => queue_operation(app, operation),
+            Err(e) => app.notify_error(e),
+        },
+
+        KeyCode::Esc => app.leave_config_screen(),
+
+        _ => {}
+    }
+}
+
+/**
+ * Build the compress operation from the configuration, checking that it can run.
+ * @param app The application state.
+ * @returns The operation, or the error to show to the user.
+ */
+fn compress_operation(app: &App) -> Result<Operation, String> {
+    let Some(input) = app.selected_files().first() else {
+        return Err(tr!("No file selected").to_string());
+    };
+    let output = app.output_name(
+        &app.compress_config.output_filename,
+        DEFAULT_COMPRESS_OUTPUT,
+    );
+    validate_output_name(&output).map_err(|e| e.to_string())?;
+    validate_output_directory(&app.output_location.directory).map_err(|e| e.to_string())?;
+
+    Ok(Operation::Compress {
+        input: input.clone(),
+        output: app.resolve_output(&output),
+        options: app.compress_config.options,
+    })
+}
+
+/**
+ * Perform the PDF compression using the selected file, options and output filename, on a worker
+ * thread while the processing screen is shown.
+ * The result screen then shows success or error messages, and the sizes before and after.
+ * @param app The application state.
+ * @param operation The validated compress operation.
+ */
+pub fn perform_compress(app: &mut App, operation: Operation) {
+    use crate::pdf;
+
+    let input = app.selected_files()[0].clone();
+    let output = app.resolve_output(&app.output_name(
+        &app.compress_config.output_filename,
+        DEFAULT_COMPRESS_OUTPUT,
+    ));
+    let options = app.compress_config.options;
+    // Run like the other operations, keeping the report of the compression for the result screen
+    start_processing(
+        app,
+        describe_operation(&operation),
+        |e| tr!("Failed to compress PDF: {error}", error = e),
+        move || {
+            let report = operation.run_with(|| pdf::compress_pdf(&input, &output, &options))?;
+            Ok(Box::new(move |app: &mut App| {
+                app.set_outputs(vec![output.clone()]);
+                app.result.compression = Some(report);
+                app.set_success(tr!(
+                    "✅ Successfully compressed '{input}' from {before} to {after} and saved to '{output}'",
+                    input = app.selected_files()[0],
+                    before = format_size(report.before),
+                    after = format_size(report.after),
+                    output = output
+                ));
+            }) as Completion)
+        },
+    );
+}
@@ -1,6 +1,12 @@
+use crate::i18n::tr;
+use crate::pdf::operation::Operation;
+use crate::pdf::output::DEFAULT_DELETE_OUTPUT;
 use crate::tui::app::App;
-use crate::tui::state::CurrentScreen;
-use crate::tui::utils::validate_page_ranges;
+use crate::tui::handlers::output_location::{confirm_run, handle_output_directory_input};
+use crate::tui::handlers::page_grid::open_page_grid;
+use crate::tui::handlers::processing::start_operation;
+use crate::tui::handlers::queue::queue_operation;
+use crate::tui::utils::{validate_delete_pages, validate_output_directory, validate_output_name};
 use crossterm::event::KeyCode;
 
 /**
@@ -11,8 +17,8 @@ use crossterm::event::KeyCode;
  *
  */
 pub fn handle_delete_config_input(key: KeyCode, app: &mut App) {
-    if app.ui_state.get_error_message().is_some() && key != KeyCode::Esc {
-        app.ui_state.clear_message();
+    if app.output_location.editing {
+        handle_output_directory_input(key, app);
         return;
     }
 
@@ -24,21 +30,8 @@ pub fn handle_delete_config_input(key: KeyCode, app: &mut App) {
             KeyCode::Backspace => {
                 app.delete_config.pages_to_delete.pop();
             }
-            KeyCode::Enter | KeyCode::Tab => {
-                app.delete_config.editing_pages = false;
-
-                if !app.delete_config.pages_to_delete.is_empty() {
-                    match validate_page_ranges(&app.delete_config.pages_to_delete) {
-                        Ok(_) => {
-                            app.ui_state.clear_message();
-                        }
-                        Err(e) => {
-                            app.set_error(e.to_string());
-                        }
-                    }
-                }
-            }
-            KeyCode::Esc => {
+            // Errors are shown on the field itself as the user types
+            KeyCode::Enter | KeyCode::Tab | KeyCode::Esc => {
                 app.delete_config.editing_pages = false;
             }
             _ => {}
@@ -62,10 +55,6 @@ pub fn handle_delete_config_input(key: KeyCode, app: &mut App) {
                 {
                     app.delete_config.output_filename.push_str(".pdf");
                 }
-
-                if app.delete_config.output_filename.is_empty() {
-                    app.delete_config.output_filename = "output_deleted_pages.pdf".to_string();
-                }
             }
             KeyCode::Esc => {
                 app.delete_config.editing_output = false;
@@ -80,71 +69,83 @@ pub fn handle_delete_config_input(key: KeyCode, app: &mut App) {
             app.delete_config.editing_pages = true;
         }
 
+        KeyCode::Char('g') | KeyCode::Char('G') => {
+            open_page_grid(app);
+        }
+
         KeyCode::Tab => {
             app.delete_config.editing_output = true;
         }
 
-        KeyCode::Enter => {
-            if app.selected_files().is_empty() {
-                app.set_error("No file selected".to_string());
-            } else if app.delete_config.pages_to_delete.is_empty() {
-                app.set_error("Please specify pages to delete".to_string());
-            } else if app.delete_config.output_filename.is_empty() {
-                app.set_error("Output filename cannot be empty".to_string());
-            } else {
-                match validate_page_ranges(&app.delete_config.pages_to_delete) {
-                    Ok(_) => {
-                        perform_delete(app);
-                    }
-                    Err(e) => {
-                        app.set_error(e.to_string());
-                    }
-                }
-            }
+        KeyCode::Char('d') | KeyCode::Char('D') => {
+            app.output_location.editing = true;
         }
 
-        KeyCode::Esc => {
-            app.current_screen = CurrentScreen::FileSelection;
-        }
+        KeyCode::Enter => match delete_operation(app) {
+            Ok(operation) if confirm_run(app, &operation) => perform_delete(app, operation),
+            Ok(_) => {}
+            Err(e) => app.notify_error(e),
+        },
+
+        KeyCode::Char('a') | KeyCode::Char('A') => match delete_operation(app) {
+            Ok(operation) => queue_operation(app, operation),
+            Err(e) => app.notify_error(e),
+        },
+
+        KeyCode::Esc => app.leave_config_screen(),
 
         _ => {}
     }
 }
 
 /**
- * Perform the PDF page deletion operation using the selected file, pages to delete, and output filename.
- * Updates the app state with success or error messages.
+ * Build the delete operation from the configuration, checking that it can run.
  * @param app The application state.
- * @returns Nothing. Updates app state directly.
+ * @returns The operation, or the error to show to the user.
  */
-pub fn perform_delete(app: &mut App) {
-    use crate::pdf;
-
-    match validate_page_ranges(&app.delete_config.pages_to_delete) {
-        Ok(pages_to_delete) => {
-            match pdf::delete_pages(
-                &app.selected_files()[0],
-                &app.delete_config.output_filename,
-                &pages_to_delete,
-            ) {
-                Ok(()) => {
-                    app.set_success(format!(
-                        "✅ Successfully deleted pages {} from '{}' and saved to '{}'",
-                        app.delete_config.pages_to_delete,
-                        app.selected_files()[0],
-                        app.delete_config.output_filename
-                    ));
-                    app.current_screen = CurrentScreen::Result;
-                }
-                Err(e) => {
-                    app.set_error(format!("Failed to delete pages: {}", e));
-                    app.current_screen = CurrentScreen::Result;
-                }
-            }
-        }
-        Err(e) => {
-            app.set_error(e.to_string());
-            app.current_screen = CurrentScreen::Result;
-        }
+fn delete_operation(app: &App) -> Result<Operation, String> {
+    let Some(input) = app.selected_files().first() else {
+        return Err(tr!("No file selected").to_string());
+    };
+    if app.delete_config.pages_to_delete.is_empty() {
+        return Err(tr!("Please specify pages to delete").to_string());
     }
+    let output = app.output_name(&app.delete_config.output_filename, DEFAULT_DELETE_OUTPUT);
+    validate_output_name(&output).map_err(|e| e.to_string())?;
+    validate_output_directory(&app.output_location.directory).map_err(|e| e.to_string())?;
+    let pages = validate_delete_pages(
+        &app.delete_config.pages_to_delete,
+        app.first_file_page_count(),
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(Operation::Delete {
+        input: input.clone(),
+        output: app.resolve_output(&output),
+        pages,
+    })
+}
+
+/**
+ * Perform the PDF page deletion operation using the selected file, pages to delete, and output
+ * filename, on a worker thread while the processing screen is shown.
+ * The result screen then shows success or error messages.
+ * @param app The application state.
+ * @param operation The validated delete operation.
+ */
+pub fn perform_delete(app: &mut App, operation: Operation) {
+    start_operation(
+        app,
+        operation,
+        |app, outputs| {
+            app.set_success(tr!(
+                "✅ Successfully deleted pages {pages} from '{input}' and saved to '{output}'",
+                pages = app.delete_config.pages_to_delete,
+                input = app.selected_files()[0],
+                output = outputs[0]
+            ));
+            app.set_outputs(outputs);
+        },
+        |e| tr!("Failed to delete pages: {error}", error = e),
+    );
 }
@@ -0,0 +1,162 @@
+use crate::i18n::tr;
+use crate::pdf::operation::Operation;
+use crate::pdf::output::DEFAULT_EXTRACT_OUTPUT;
+use crate::tui::app::App;
+use crate::tui::handlers::output_location::{confirm_run, handle_output_directory_input};
+use crate::tui::handlers::page_grid::open_page_grid;
+use crate::tui::handlers::page_order::open_page_order;
+use crate::tui::handlers::processing::start_operation;
+use crate::tui::handlers::queue::queue_operation;
+use crate::tui::utils::{
+    validate_output_directory, validate_output_name, validate_page_order_in_document,
+};
+use crossterm::event::KeyCode;
+
+/**
+ * Handle input in the extract configuration screen.
+ * Allows choosing the pages to keep and their order, keeping or leaving out their annotations,
+ * editing the output filename, and starting the extraction.
+ * @param key The key event.
+ * @param app The application state.
+ */
+pub fn handle_extract_config_input(key: KeyCode, app: &mut App) {
+    if app.output_location.editing {
+        handle_output_directory_input(key, app);
+        return;
+    }
+
+    if app.extract_config.editing_pages {
+        match key {
+            KeyCode::Char(c) => {
+                app.extract_config.pages.push(c);
+            }
+            KeyCode::Backspace => {
+                app.extract_config.pages.pop();
+            }
+            // Errors are shown on the field itself as the user types
+            KeyCode::Enter | KeyCode::Tab | KeyCode::Esc => {
+                app.extract_config.editing_pages = false;
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    if app.extract_config.editing_output {
+        match key {
+            KeyCode::Char(c) => {
+                app.extract_config.output_filename.push(c);
+            }
+            KeyCode::Backspace => {
+                app.extract_config.output_filename.pop();
+            }
+            KeyCode::Enter | KeyCode::Tab => {
+                app.extract_config.editing_output = false;
+
+                if !app.extract_config.output_filename.ends_with(".pdf")
+                    && !app.extract_config.output_filename.is_empty()
+                {
+                    app.extract_config.output_filename.push_str(".pdf");
+                }
+            }
+            KeyCode::Esc => {
+                app.extract_config.editing_output = false;
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    match key {
+        KeyCode::Char('p') | KeyCode::Char('P') => {
+            app.extract_config.editing_pages = true;
+        }
+
+        KeyCode::Char('g') | KeyCode::Char('G') => {
+            open_page_grid(app);
+        }
+
+        KeyCode::Char('o') | KeyCode::Char('O') => {
+            open_page_order(app);
+        }
+
+        KeyCode::Char('n') | KeyCode::Char('N') => {
+            let options = &mut app.extract_config.options;
+            options.keep_annotations = !options.keep_annotations;
+        }
+
+        KeyCode::Tab => {
+            app.extract_config.editing_output = true;
+        }
+
+        KeyCode::Char('d') | KeyCode::Char('D') => {
+            app.output_location.editing = true;
+        }
+
+        KeyCode::Enter => match extract_operation(app) {
+            Ok(operation) if confirm_run(app, &operation) => perform_extract(app, operation),
+            Ok(_) => {}
+            Err(e) => app.notify_error(e),
+        },
+
+        KeyCode::Char('a') | KeyCode::Char('A') => match extract_operation(app) {
+            Ok(operation) => queue_operation(app, operation),
+            Err(e) => app.notify_error(e),
+        },
+
+        KeyCode::Esc => app.leave_config_screen(),
+
+        _ => {}
+    }
+}
+
+/**
+ * Build the extract operation from the configuration, checking that it can run.
+ * @param app The application state.
+ * @returns The operation, or the error to show to the user.
+ */
+fn extract_operation(app: &App) -> Result<Operation, String> {
+    let Some(input) = app.selected_files().first() else {
+        return Err(tr!("No file selected").to_string());
+    };
+    if app.extract_config.pages.is_empty() {
+        return Err(tr!("Please specify pages to extract").to_string());
+    }
+    let output = app.output_name(&app.extract_config.output_filename, DEFAULT_EXTRACT_OUTPUT);
+    validate_output_name(&output).map_err(|e| e.to_string())?;
+    validate_output_directory(&app.output_location.directory).map_err(|e| e.to_string())?;
+    let pages =
+        validate_page_order_in_document(&app.extract_config.pages, app.first_file_page_count())
+            .map_err(|e| e.to_string())?;
+
+    Ok(Operation::Extract {
+        input: input.clone(),
+        output: app.resolve_output(&output),
+        pages,
+        options: app.extract_config.options,
+    })
+}
+
+/**
+ * Perform the PDF page extraction using the selected file, pages and output filename, on a
+ * worker thread while the processing screen is shown.
+ * The result screen then shows success or error messages.
+ * @param app The application state.
+ * @param operation The validated extract operation.
+ */
+pub fn perform_extract(app: &mut App, operation: Operation) {
+    start_operation(
+        app,
+        operation,
+        |app, outputs| {
+            app.set_success(tr!(
+                "✅ Successfully extracted pages {pages} from '{input}' and saved to '{output}'",
+                pages = app.extract_config.pages,
+                input = app.selected_files()[0],
+                output = outputs[0]
+            ));
+            app.set_outputs(outputs);
+        },
+        |e| tr!("Failed to extract pages: {error}", error = e),
+    );
+}
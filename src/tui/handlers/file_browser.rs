@@ -0,0 +1,199 @@
+use crate::i18n::{tr, translate};
+use crate::pdf::output::contract_home;
+use crate::tui::app::App;
+use crate::tui::state::{BrowserFocus, CurrentScreen, OperationMode};
+use crate::tui::utils::{error_chain, list_navigation, validate_file_input};
+use crossterm::event::KeyCode;
+use std::env;
+
+/**
+ * Open the file browser from the file selection, in the directory it was left in.
+ * Like typing a path, it only adds a file when the operation can take one more.
+ * @param app The application state.
+ */
+pub fn open_file_browser(app: &mut App) {
+    if app.operation_mode != OperationMode::Merge && !app.files_is_empty() {
+        return;
+    }
+
+    // The directory may have been removed since the browser was closed
+    let directory = app.browser.directory.clone();
+    let selected = app.browser.selected_entry().map(|entry| entry.path.clone());
+    if app.browse(directory, selected.as_deref()).is_err() {
+        let current = env::current_dir().unwrap_or_default();
+        if let Err(e) = app.browse(current, None) {
+            app.notify_error(e.to_string());
+            return;
+        }
+    }
+    app.browser.focus = BrowserFocus::Entries;
+    app.current_screen = CurrentScreen::FileBrowser;
+}
+
+/**
+ * Handle input in the file browser.
+ * Enter opens a folder or adds a PDF, Backspace goes up, F pins the folder, W works in it,
+ * Tab reaches the pinned folders.
+ * @param key The key event.
+ * @param app The application state.
+ */
+pub fn handle_file_browser_input(key: KeyCode, app: &mut App) {
+    if app.browser.focus == BrowserFocus::Favorites {
+        handle_favorites_input(key, app);
+        return;
+    }
+
+    let browser = &mut app.browser;
+    if let Some(index) = list_navigation(key, browser.selected, browser.entries.len()) {
+        browser.selected = index;
+        return;
+    }
+    match key {
+        KeyCode::Enter | KeyCode::Right => open_selected_entry(app),
+        KeyCode::Backspace | KeyCode::Left => {
+            let current = app.browser.directory.clone();
+            if let Some(parent) = current.parent()
+                && let Err(e) = app.browse(parent.to_path_buf(), Some(&current))
+            {
+                app.notify_error(e.to_string());
+            }
+        }
+        KeyCode::Char('f') | KeyCode::Char('F') => toggle_favorite(app),
+        KeyCode::Char('w') | KeyCode::Char('W') => work_in_current_folder(app),
+        KeyCode::Char(c @ '1'..='9') => go_to_favorite(app, c as usize - '1' as usize),
+        KeyCode::Char('s') | KeyCode::Char('S') => {
+            app.browser.sort = app.browser.sort.next();
+            let sort = translate(app.browser.sort.label());
+            app.notify(tr!("Sorted by {sort}", sort = sort));
+            relist(app);
+        }
+        KeyCode::Char('h') | KeyCode::Char('H') => {
+            app.browser.show_hidden = !app.browser.show_hidden;
+            if app.browser.show_hidden {
+                app.notify(tr!("Showing hidden entries").to_string());
+            } else {
+                app.notify(tr!("Hiding hidden entries").to_string());
+            }
+            relist(app);
+        }
+        KeyCode::Char('a') | KeyCode::Char('A') => {
+            app.browser.show_all_files = !app.browser.show_all_files;
+            if app.browser.show_all_files {
+                app.notify(tr!("Showing all files").to_string());
+            } else {
+                app.notify(tr!("Showing PDF files only").to_string());
+            }
+            relist(app);
+        }
+        KeyCode::Tab if app.browser.favorites.is_empty() => {
+            app.notify(tr!("No pinned folders yet: press F to pin this one").to_string());
+        }
+        KeyCode::Tab => app.browser.focus = BrowserFocus::Favorites,
+        KeyCode::Esc => app.current_screen = CurrentScreen::FileSelection,
+        _ => {}
+    }
+}
+
+/**
+ * Handle input while the pinned folders have the focus.
+ * Enter goes to the selected folder, X unpins it, Tab or Esc goes back to the entries.
+ */
+fn handle_favorites_input(key: KeyCode, app: &mut App) {
+    let browser = &mut app.browser;
+    if let Some(index) = list_navigation(key, browser.favorite_index, browser.favorites.len()) {
+        browser.favorite_index = index;
+        return;
+    }
+    match key {
+        KeyCode::Enter | KeyCode::Right => go_to_favorite(app, app.browser.favorite_index),
+        KeyCode::Char('x') | KeyCode::Char('X') | KeyCode::Delete => {
+            let index = browser.favorite_index;
+            browser.remove_favorite(index);
+            if browser.favorites.is_empty() {
+                browser.focus = BrowserFocus::Entries;
+            }
+            save_favorites(app);
+        }
+        KeyCode::Tab | KeyCode::Esc => browser.focus = BrowserFocus::Entries,
+        _ => {}
+    }
+}
+
+/// Make the listed folder the working directory, where relative paths and default outputs go.
+pub fn work_in_current_folder(app: &mut App) {
+    let directory = app.browser.directory.clone();
+    match app.change_working_directory(&directory) {
+        Ok(()) => app.notify(tr!(
+            "Working directory: {path}",
+            path = contract_home(&directory)
+        )),
+        Err(e) => app.notify_error(e.to_string()),
+    }
+}
+
+/// List the directory again after a sort or filter change, keeping the selected entry when still listed.
+fn relist(app: &mut App) {
+    let directory = app.browser.directory.clone();
+    let selected = app.browser.selected_entry().map(|entry| entry.path.clone());
+    if let Err(e) = app.browse(directory, selected.as_deref()) {
+        app.notify_error(e.to_string());
+    }
+}
+
+/// Enter the selected folder, or add the selected PDF and go back to the file selection.
+fn open_selected_entry(app: &mut App) {
+    let Some(entry) = app.browser.selected_entry().cloned() else {
+        return;
+    };
+    if entry.is_dir {
+        if let Err(e) = app.browse(entry.path, None) {
+            app.notify_error(e.to_string());
+        }
+        return;
+    }
+
+    let file = entry.path.to_string_lossy().into_owned();
+    // Listed when showing all files, but only PDFs can be added
+    if let Err(e) = validate_file_input(&file) {
+        app.notify_error(e.to_string());
+        return;
+    }
+    app.add_typed_file(file);
+    app.set_selected_file_index(app.files_len() - 1);
+    app.notify(tr!("Added {file}", file = entry.name));
+    app.current_screen = CurrentScreen::FileSelection;
+}
+
+/// List a pinned folder, giving the focus back to the entries.
+fn go_to_favorite(app: &mut App, index: usize) {
+    let Some(favorite) = app.browser.favorites.get(index).cloned() else {
+        return;
+    };
+    match app.browse(favorite.path, None) {
+        Ok(()) => {
+            app.browser.favorite_index = index;
+            app.browser.focus = BrowserFocus::Entries;
+        }
+        Err(e) => app.notify_error(e.to_string()),
+    }
+}
+
+/// Pin or unpin the listed folder and save the change.
+fn toggle_favorite(app: &mut App) {
+    let directory = contract_home(&app.browser.directory);
+    if app.browser.toggle_favorite() {
+        app.notify(tr!("Pinned {directory}", directory = directory));
+    } else {
+        app.notify(tr!("Unpinned {directory}", directory = directory));
+    }
+    save_favorites(app);
+}
+
+fn save_favorites(app: &mut App) {
+    if let Err(e) = app.save_favorites() {
+        app.notify_error(tr!(
+            "Could not save the pinned folders: {error}",
+            error = error_chain(&e)
+        ));
+    }
+}
@@ -1,20 +1,29 @@
+use crate::i18n::tr;
 use crate::tui::app::App;
+use crate::tui::errors::TuiError;
+use crate::tui::handlers::file_browser::open_file_browser;
 use crate::tui::state::{CurrentScreen, OperationMode};
 use crate::tui::utils::{
-    validate_delete_requirements, validate_file_input, validate_merge_requirements,
-    validate_split_requirements,
+    list_navigation, quote_path, split_paths, validate_delete_requirements, validate_file_input,
+    validate_merge_requirements, validate_single_file_requirements, validate_split_requirements,
 };
 use crossterm::event::{KeyCode, KeyModifiers};
+use std::path::Path;
 
 /**
  * Handle input in the file selection screen.
- * Allows adding/removing files, navigating the list, and proceeding to the next configuration screen.
+ * Allows adding/removing/editing files, navigating the list, and proceeding to the next configuration screen.
  * @param key The key event.
  * @param app The application state.
  */
 pub fn handle_file_selection_input(key: KeyCode, key_event_modifier: KeyModifiers, app: &mut App) {
-    if app.ui_state.get_error_message().is_some() && key != KeyCode::Esc {
-        app.ui_state.clear_message();
+    // Any key closes the preview
+    if app.file_state.preview.take().is_some() {
+        return;
+    }
+
+    if app.file_state.folder_prompt.is_some() {
+        handle_folder_prompt_input(key, app);
         return;
     }
 
@@ -27,25 +36,52 @@ pub fn handle_file_selection_input(key: KeyCode, key_event_modifier: KeyModifier
                 app.ui_state.input_backspace();
             }
             KeyCode::Enter => {
-                let input_text = app.ui_state.get_input_text();
-                if !input_text.is_empty() {
-                    match validate_file_input(input_text) {
+                let paths = typed_paths(app);
+                let input_text = paths.first().map_or("", String::as_str);
+                // Several paths are added at once, and a folder offers to add all its PDFs
+                // when building a merge
+                if paths.len() > 1 {
+                    add_typed_paths(app, paths);
+                } else if app.operation_mode == OperationMode::Merge
+                    && app.file_state.editing_file_index.is_none()
+                    && Path::new(input_text).is_dir()
+                {
+                    let directory = input_text.to_string();
+                    app.ui_state.stop_input();
+                    if let Err(e) = app.open_folder_prompt(&directory) {
+                        app.notify_error(e.to_string());
+                    }
+                } else if !input_text.is_empty() {
+                    // Outputs of queued operations do not exist yet but can be chained
+                    let validation = if app.queue.will_produce(input_text) {
+                        Ok(())
+                    } else {
+                        validate_file_input(input_text)
+                    };
+                    match validation {
                         Ok(()) => {
-                            app.add_file(input_text.to_string());
+                            let file = input_text.to_string();
+                            match app.file_state.editing_file_index.take() {
+                                Some(index) => app.replace_file(index, file),
+                                None => app.add_typed_file(file),
+                            }
                             app.ui_state.stop_input();
                             app.ui_state.clear_message();
                         }
                         Err(e) => {
-                            app.set_error(e.to_string());
+                            app.notify_error(e.to_string());
                             app.set_editing_input(false);
+                            app.file_state.editing_file_index = None;
                         }
                     }
                 } else {
                     app.set_editing_input(false);
+                    app.file_state.editing_file_index = None;
                 }
             }
             KeyCode::Esc => {
                 app.ui_state.stop_input();
+                app.file_state.editing_file_index = None;
             }
             _ => {}
         }
@@ -53,71 +89,88 @@ pub fn handle_file_selection_input(key: KeyCode, key_event_modifier: KeyModifier
     }
 
     match (key, key_event_modifier) {
-        (KeyCode::Up, KeyModifiers::ALT) => {
-            if app.selected_file_index() > 0 {
-                let current_index = app.selected_file_index();
-                app.swap_files(current_index, current_index - 1);
-                app.set_selected_file_index(current_index - 1);
-            }
+        (KeyCode::Up, KeyModifiers::ALT) if app.selected_file_index() > 0 => {
+            let current_index = app.selected_file_index();
+            app.swap_files(current_index, current_index - 1);
+            app.set_selected_file_index(current_index - 1);
         }
-        (KeyCode::Down, KeyModifiers::ALT) => {
-            if app.selected_file_index() < app.files_len().saturating_sub(1) {
-                let current_index = app.selected_file_index();
-                app.swap_files(current_index, current_index + 1);
-                app.set_selected_file_index(current_index + 1);
-            }
+        (KeyCode::Down, KeyModifiers::ALT)
+            if app.selected_file_index() < app.files_len().saturating_sub(1) =>
+        {
+            let current_index = app.selected_file_index();
+            app.swap_files(current_index, current_index + 1);
+            app.set_selected_file_index(current_index + 1);
         }
+        (KeyCode::Home, KeyModifiers::ALT) => app.move_selected_file(false),
+        (KeyCode::End, KeyModifiers::ALT) => app.move_selected_file(true),
         (key, KeyModifiers::NONE) | (key, KeyModifiers::SHIFT) => match key {
-            KeyCode::Up => {
-                if app.selected_file_index() > 0 {
-                    app.set_selected_file_index(app.selected_file_index() - 1);
-                }
-            }
-            KeyCode::Down => {
-                if app.selected_file_index() < app.files_len().saturating_sub(1) {
-                    app.set_selected_file_index(app.selected_file_index() + 1);
+            KeyCode::Up
+            | KeyCode::Down
+            | KeyCode::PageUp
+            | KeyCode::PageDown
+            | KeyCode::Home
+            | KeyCode::End => {
+                if let Some(index) =
+                    list_navigation(key, app.selected_file_index(), app.files_len())
+                {
+                    app.set_selected_file_index(index);
                 }
             }
 
             KeyCode::Tab => {
-                if (app.operation_mode == OperationMode::Delete
-                    || app.operation_mode == OperationMode::Split)
-                    && !app.files_is_empty()
-                {
+                if app.operation_mode != OperationMode::Merge && !app.files_is_empty() {
                     return;
                 }
                 app.set_editing_input(true);
                 app.set_current_input(Some(String::new()));
             }
 
+            KeyCode::Char('e') => {
+                app.start_editing_file();
+            }
+
+            KeyCode::Char('b') => open_file_browser(app),
+
+            KeyCode::Char('p') => {
+                if let Err(e) = app.open_preview() {
+                    app.notify_error(e.to_string());
+                }
+            }
+
             KeyCode::Enter | KeyCode::Right => {
+                if let Some(e) = app.file_validation_error() {
+                    app.notify_error(e.to_string());
+                    return;
+                }
                 let validation_result = match app.operation_mode {
-                    OperationMode::Merge => validate_merge_requirements(&app.selected_files()),
-                    OperationMode::Delete => validate_delete_requirements(&app.selected_files()),
-                    OperationMode::Split => validate_split_requirements(&app.selected_files()),
+                    OperationMode::Merge => validate_merge_requirements(app.selected_files()),
+                    OperationMode::Delete => validate_delete_requirements(app.selected_files()),
+                    OperationMode::Split => validate_split_requirements(app.selected_files()),
+                    OperationMode::Rotate
+                    | OperationMode::Extract
+                    | OperationMode::Compress
+                    | OperationMode::Watermark => {
+                        validate_single_file_requirements(app.selected_files())
+                    }
                     _ => Ok(()),
                 };
 
                 match validation_result {
                     Ok(()) => {
-                        app.current_screen = match app.operation_mode {
-                            OperationMode::Merge => CurrentScreen::MergeConfig,
-                            OperationMode::Delete => CurrentScreen::DeleteConfig,
-                            OperationMode::Split => CurrentScreen::SplitConfig,
-                            _ => CurrentScreen::Main,
-                        };
+                        app.refresh_inspect_cache();
+                        app.current_screen = app.config_screen();
                         app.ui_state.clear_message();
                     }
                     Err(e) => {
-                        app.set_error(e.to_string());
+                        app.notify_error(e.to_string());
                     }
                 }
             }
 
-            KeyCode::Backspace => {
-                if !app.files_is_empty() && app.selected_file_index() < app.files_len() {
-                    app.remove_current_file();
-                }
+            KeyCode::Backspace
+                if !app.files_is_empty() && app.selected_file_index() < app.files_len() =>
+            {
+                app.remove_current_file();
             }
 
             KeyCode::Esc => {
@@ -129,3 +182,122 @@ pub fn handle_file_selection_input(key: KeyCode, key_event_modifier: KeyModifier
         _ => {}
     }
 }
+
+/**
+ * Paths in the file input: the text as typed when it names an existing file (even with
+ * spaces) or a queued output, otherwise every path it lists.
+ */
+fn typed_paths(app: &App) -> Vec<String> {
+    let input = app.ui_state.get_input_text();
+    if Path::new(input).exists() || app.queue.will_produce(input) {
+        vec![input.to_string()]
+    } else {
+        split_paths(input)
+    }
+}
+
+/**
+ * Add every path typed or pasted at once in the file input. Each one is checked like a
+ * single typed path; the ones that fail are reported and stay in the input to be corrected.
+ * @param app The application state.
+ * @param paths The paths, at least two.
+ */
+fn add_typed_paths(app: &mut App, paths: Vec<String>) {
+    // Only a merge takes several files, and a file of the list is replaced by one path
+    if app.operation_mode != OperationMode::Merge || app.file_state.editing_file_index.is_some() {
+        let count = app.files_len() + paths.len();
+        app.notify_error(TuiError::TooManyFiles { count }.to_string());
+        return;
+    }
+
+    let total = paths.len();
+    let mut failed = Vec::new();
+    for path in paths {
+        let validation = if app.queue.will_produce(&path) {
+            Ok(())
+        } else {
+            validate_file_input(&path)
+        };
+        match validation {
+            Ok(()) => app.add_typed_file(path),
+            Err(e) => failed.push((path, e)),
+        }
+    }
+
+    if failed.is_empty() {
+        app.ui_state.stop_input();
+        app.ui_state.clear_message();
+        app.notify(tr!("Added {count} files", count = total));
+        return;
+    }
+    let retry: Vec<String> = failed.iter().map(|(path, _)| quote_path(path)).collect();
+    let errors: Vec<String> = failed.iter().map(|(_, e)| e.to_string()).collect();
+    app.set_current_input(Some(retry.join(" ")));
+    app.notify_error(tr!(
+        "Added {added} of {total} files. Not added: {errors}",
+        added = total - failed.len(),
+        total = total,
+        errors = errors.join("; ")
+    ));
+}
+
+/**
+ * Insert pasted text in the file input. Each pasted line is one path, so a line naming an
+ * existing file with spaces is quoted for split_paths to keep it whole.
+ * @param app The application state.
+ * @param text The pasted text.
+ */
+pub fn paste_into_file_input(app: &mut App, text: &str) {
+    let lines: Vec<&str> = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    let pasted = match lines.as_slice() {
+        [line] => line.to_string(),
+        lines => lines
+            .iter()
+            .map(|line| {
+                if Path::new(line).exists() {
+                    quote_path(line)
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+    };
+    let mut input = app.ui_state.get_input_text().to_string();
+    if lines.len() > 1 && !input.is_empty() && !input.ends_with(char::is_whitespace) {
+        input.push(' ');
+    }
+    input.push_str(&pasted);
+    app.set_current_input(Some(input));
+}
+
+/**
+ * Handle input in the prompt offering to add the PDFs of a folder.
+ * Enter adds them, R includes or leaves out subfolders, and Esc cancels.
+ * @param key The key event.
+ * @param app The application state.
+ */
+fn handle_folder_prompt_input(key: KeyCode, app: &mut App) {
+    match key {
+        KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+            match app.confirm_folder_prompt() {
+                Ok(1) => app.notify(tr!("Added 1 PDF file").to_string()),
+                Ok(count) => app.notify(tr!("Added {count} PDF files", count = count)),
+                Err(e) => app.notify_error(e.to_string()),
+            }
+        }
+        KeyCode::Char('r') | KeyCode::Char('R') => {
+            if let Err(e) = app.toggle_folder_recursion() {
+                app.notify_error(e.to_string());
+            }
+        }
+        KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
+            app.file_state.folder_prompt = None;
+        }
+        _ => {}
+    }
+}
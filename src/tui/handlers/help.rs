@@ -0,0 +1,37 @@
+use crate::tui::app::App;
+use crate::tui::state::CurrentScreen;
+use crate::tui::ui::help_content;
+use crate::tui::utils::PAGE_STEP;
+use crossterm::event::KeyCode;
+
+/**
+ * Handle input in the help screen.
+ * The key reference can be longer than the terminal, so it scrolls, by line or by section.
+ * @param key The key event.
+ * @param app The application state.
+ */
+pub fn handle_help_input(key: KeyCode, app: &mut App) {
+    let help = help_content();
+    let max_scroll = help.lines.len().saturating_sub(1) as u16;
+    let sections = help.section_starts.iter().map(|&start| start as u16);
+    let scroll = &mut app.ui_state.help_scroll;
+    match key {
+        KeyCode::Up => *scroll = scroll.saturating_sub(1),
+        KeyCode::Down => *scroll = (*scroll + 1).min(max_scroll),
+        KeyCode::PageUp => *scroll = scroll.saturating_sub(PAGE_STEP as u16),
+        KeyCode::PageDown => *scroll = (*scroll + PAGE_STEP as u16).min(max_scroll),
+        KeyCode::Home => *scroll = 0,
+        KeyCode::End => *scroll = max_scroll,
+        KeyCode::Tab => {
+            if let Some(next) = sections.clone().find(|&start| start > *scroll) {
+                *scroll = next;
+            }
+        }
+        KeyCode::BackTab => *scroll = sections.rev().find(|&start| start < *scroll).unwrap_or(0),
+        KeyCode::Esc => {
+            app.ui_state.help_scroll = 0;
+            app.current_screen = CurrentScreen::Main;
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,35 @@
+use crate::tui::app::App;
+use crate::tui::log_buffer;
+use crate::tui::utils::PAGE_STEP;
+use crossterm::event::KeyCode;
+
+/**
+ * Handle input in the log viewer.
+ * The newest lines are shown at the bottom; scrolling goes back in time.
+ * @param key The key event.
+ * @param app The application state.
+ */
+pub fn handle_logs_input(key: KeyCode, app: &mut App) {
+    // Scrolling is by screen row, and a message may span several rows
+    let rows: usize = log_buffer::recent_lines(app.logs.min_level)
+        .iter()
+        .map(|line| line.message.lines().count())
+        .sum();
+    let max_scroll = rows.saturating_sub(1);
+    let scroll = &mut app.logs.scroll;
+    match key {
+        KeyCode::Up => *scroll = (*scroll + 1).min(max_scroll),
+        KeyCode::Down => *scroll = scroll.saturating_sub(1),
+        KeyCode::PageUp => *scroll = (*scroll + PAGE_STEP).min(max_scroll),
+        KeyCode::PageDown => *scroll = scroll.saturating_sub(PAGE_STEP),
+        KeyCode::Home => *scroll = max_scroll,
+        KeyCode::End => *scroll = 0,
+        KeyCode::Char('f') | KeyCode::Char('F') => app.logs.cycle_level(),
+        KeyCode::Char('c') | KeyCode::Char('C') => {
+            log_buffer::clear();
+            app.logs.scroll = 0;
+        }
+        KeyCode::Esc => app.toggle_logs(),
+        _ => {}
+    }
+}
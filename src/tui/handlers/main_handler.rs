@@ -2,33 +2,24 @@ use crate::tui::app::App;
 use crate::tui::state::{CurrentScreen, OperationMode};
 use crossterm::event::KeyCode;
 
+/// Number of entries rendered in the main menu.
+pub const MAIN_MENU_ITEMS: usize = 11;
+
 pub fn handle_main_input(key: KeyCode, app: &mut App) {
-    let number_of_menu_items = 4;
+    let number_of_menu_items = MAIN_MENU_ITEMS - 1;
 
     match key {
         KeyCode::Char('q') | KeyCode::Char('Q') => {
             app.current_screen = CurrentScreen::Exiting;
         }
-        KeyCode::Char('1') => {
-            app.reset();
-            app.set_menu_mode_index(0);
-            app.operation_mode = OperationMode::Merge;
-            app.current_screen = CurrentScreen::FileSelection;
-        }
-        KeyCode::Char('2') => {
-            app.reset();
-            app.set_menu_mode_index(1);
-            app.operation_mode = OperationMode::Delete;
-            app.current_screen = CurrentScreen::FileSelection;
+        KeyCode::Char('a') | KeyCode::Char('A') => {
+            select_menu_item(9, app);
+            app.set_menu_mode_index(9);
         }
-        KeyCode::Char('3') => {
-            app.set_menu_mode_index(3);
-            app.operation_mode = OperationMode::Split;
-            app.current_screen = CurrentScreen::FileSelection;
-        }
-        KeyCode::Char('4') => {
-            app.set_menu_mode_index(2);
-            app.current_screen = CurrentScreen::Help;
+        KeyCode::Char(c @ '1'..='9') => {
+            let index = c as usize - '1' as usize;
+            select_menu_item(index, app);
+            app.set_menu_mode_index(index);
         }
         KeyCode::Up => {
             if app.menu_mode_index() > 0 {
@@ -44,33 +35,40 @@ pub fn handle_main_input(key: KeyCode, app: &mut App) {
                 app.set_menu_mode_index(0);
             }
         }
-        KeyCode::Enter => match app.menu_mode_index() {
-            0 => {
-                app.reset();
-                app.operation_mode = OperationMode::Merge;
-                app.current_screen = CurrentScreen::FileSelection;
-            }
-            1 => {
-                app.reset();
-                app.operation_mode = OperationMode::Delete;
-                app.current_screen = CurrentScreen::FileSelection;
-            }
-            2 => {
-                app.reset();
-                app.operation_mode = OperationMode::Split;
-                app.current_screen = CurrentScreen::FileSelection;
-            }
-            3 => {
-                app.current_screen = CurrentScreen::Help;
-            }
-            4 => {
-                app.current_screen = CurrentScreen::Exiting;
-            }
-            _ => {}
-        },
+        KeyCode::Enter => select_menu_item(app.menu_mode_index(), app),
         KeyCode::Esc => {
             app.current_screen = CurrentScreen::Exiting;
         }
         _ => {}
     }
 }
+
+/**
+ * Run the main menu entry at the given index: start an operation, show the queue, help or about, or exit.
+ * @param index The menu entry index.
+ * @param app The application state.
+ */
+fn select_menu_item(index: usize, app: &mut App) {
+    match index {
+        0 => app.start_operation(OperationMode::Merge, None),
+        1 => app.start_operation(OperationMode::Delete, None),
+        2 => app.start_operation(OperationMode::Split, None),
+        3 => app.start_operation(OperationMode::Rotate, None),
+        4 => app.start_operation(OperationMode::Extract, None),
+        5 => app.start_operation(OperationMode::Compress, None),
+        6 => app.start_operation(OperationMode::Watermark, None),
+        7 => {
+            app.current_screen = CurrentScreen::Queue;
+        }
+        8 => {
+            app.current_screen = CurrentScreen::Help;
+        }
+        9 => {
+            app.current_screen = CurrentScreen::About;
+        }
+        10 => {
+            app.current_screen = CurrentScreen::Exiting;
+        }
+        _ => {}
+    }
+}
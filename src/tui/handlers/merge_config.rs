@@ -1,6 +1,13 @@
+use crate::i18n::tr;
+use crate::pdf::operation::Operation;
+use crate::pdf::output::DEFAULT_MERGE_OUTPUT;
 use crate::tui::app::App;
-use crate::tui::state::CurrentScreen;
-use crate::tui::utils::validate_merge_requirements;
+use crate::tui::handlers::output_location::{confirm_run, handle_output_directory_input};
+use crate::tui::handlers::processing::start_operation;
+use crate::tui::handlers::queue::queue_operation;
+use crate::tui::utils::{
+    validate_merge_requirements, validate_output_directory, validate_output_name,
+};
 use crossterm::event::KeyCode;
 
 /**
@@ -11,8 +18,8 @@ use crossterm::event::KeyCode;
  *
  */
 pub fn handle_merge_config_input(key: KeyCode, app: &mut App) {
-    if app.error_message().is_some() && key != KeyCode::Esc {
-        app.set_error("Cannot edit output while there's an error".to_string());
+    if app.output_location.editing {
+        handle_output_directory_input(key, app);
         return;
     }
 
@@ -32,10 +39,6 @@ pub fn handle_merge_config_input(key: KeyCode, app: &mut App) {
                 {
                     app.merge_config.output_filename.push_str(".pdf");
                 }
-
-                if app.merge_config.output_filename.is_empty() {
-                    app.merge_config.output_filename = "output_merged.pdf".to_string();
-                }
             }
             KeyCode::Esc => {
                 app.merge_config.editing_output = false;
@@ -49,62 +52,73 @@ pub fn handle_merge_config_input(key: KeyCode, app: &mut App) {
         KeyCode::Tab => {
             app.merge_config.editing_output = true;
         }
-        KeyCode::Up => {
-            if app.merge_file_index() > 0 {
-                let current_index = app.merge_file_index();
-                app.set_merge_file_index(current_index - 1);
-                app.selected_files_mut()
-                    .swap(current_index - 1, current_index);
-            }
+        KeyCode::Char('d') | KeyCode::Char('D') => {
+            app.output_location.editing = true;
         }
-        KeyCode::Down => {
-            if app.merge_file_index() < app.selected_files().len().saturating_sub(1) {
-                let current_index = app.merge_file_index();
-                app.selected_files_mut()
-                    .swap(current_index, current_index + 1);
-                app.set_merge_file_index(current_index + 1);
-            }
+        KeyCode::Up if app.merge_file_index() > 0 => {
+            let current_index = app.merge_file_index();
+            app.set_merge_file_index(current_index - 1);
+            app.selected_files_mut()
+                .swap(current_index - 1, current_index);
         }
-        KeyCode::Enter => match validate_merge_requirements(&app.selected_files()) {
-            Ok(()) => {
-                if app.merge_config.output_filename.is_empty() {
-                    app.set_error("Output filename cannot be empty".to_string());
-                } else {
-                    perform_merge(app);
-                }
-            }
-            Err(e) => {
-                app.set_error(e.to_string());
-            }
-        },
-        KeyCode::Esc => {
-            app.current_screen = CurrentScreen::FileSelection;
+        KeyCode::Down if app.merge_file_index() < app.selected_files().len().saturating_sub(1) => {
+            let current_index = app.merge_file_index();
+            app.selected_files_mut()
+                .swap(current_index, current_index + 1);
+            app.set_merge_file_index(current_index + 1);
         }
+        KeyCode::Home => app.move_selected_file(false),
+        KeyCode::End => app.move_selected_file(true),
+        KeyCode::Enter => match merge_operation(app) {
+            Ok(operation) if confirm_run(app, &operation) => perform_merge(app, operation),
+            Ok(_) => {}
+            Err(e) => app.notify_error(e),
+        },
+        KeyCode::Char('a') | KeyCode::Char('A') => match merge_operation(app) {
+            Ok(operation) => queue_operation(app, operation),
+            Err(e) => app.notify_error(e),
+        },
+        KeyCode::Esc => app.leave_config_screen(),
         _ => {}
     }
 }
 
 /**
- * Perform the PDF merge operation using the selected files and output filename.
- * Updates the app state with success or error messages.
+ * Build the merge operation from the configuration, checking that it can run.
  * @param app The application state.
- * @retiurns Nothing. Updates app state directly.
+ * @returns The operation, or the error to show to the user.
  */
-pub fn perform_merge(app: &mut App) {
-    use crate::pdf;
+fn merge_operation(app: &App) -> Result<Operation, String> {
+    validate_merge_requirements(app.selected_files()).map_err(|e| e.to_string())?;
+    let output = app.output_name(&app.merge_config.output_filename, DEFAULT_MERGE_OUTPUT);
+    validate_output_name(&output).map_err(|e| e.to_string())?;
+    validate_output_directory(&app.output_location.directory).map_err(|e| e.to_string())?;
 
-    match pdf::merge_pdfs(&app.selected_files(), &app.merge_config.output_filename) {
-        Ok(()) => {
-            app.set_success(format!(
-                "✅ Successfully merged {} files into '{}'",
-                app.selected_files().len(),
-                app.merge_config.output_filename
+    Ok(Operation::Merge {
+        inputs: app.selected_files().clone(),
+        output: app.resolve_output(&output),
+    })
+}
+
+/**
+ * Perform the PDF merge operation using the selected files and output filename, on a worker
+ * thread while the processing screen is shown.
+ * The result screen then shows success or error messages.
+ * @param app The application state.
+ * @param operation The validated merge operation.
+ */
+pub fn perform_merge(app: &mut App, operation: Operation) {
+    start_operation(
+        app,
+        operation,
+        |app, outputs| {
+            app.set_success(tr!(
+                "✅ Successfully merged {count} files into '{output}'",
+                count = app.selected_files().len(),
+                output = outputs[0]
             ));
-            app.current_screen = CurrentScreen::Result;
-        }
-        Err(e) => {
-            app.set_error(format!("Failed to merge PDFs: {}", e));
-            app.current_screen = CurrentScreen::Result;
-        }
-    }
+            app.set_outputs(outputs);
+        },
+        |e| tr!("Failed to merge PDFs: {error}", error = e),
+    );
 }
@@ -1,13 +1,45 @@
-pub mod main_handler;
+pub mod about;
+pub mod compress_config;
+pub mod delete_config;
+pub mod extract_config;
+pub mod file_browser;
 pub mod file_selection;
+pub mod help;
+pub mod logs;
+pub mod main_handler;
 pub mod merge_config;
-pub mod delete_config;
+pub mod mouse;
+pub mod onboarding;
+pub mod output_location;
+pub mod overlay;
+pub mod page_grid;
+pub mod page_order;
+pub mod palette;
+pub mod processing;
+pub mod queue;
 pub mod result;
+pub mod rotate_config;
 pub mod split_config;
+pub mod watermark_config;
 
+pub use about::handle_about_input;
+pub use compress_config::handle_compress_config_input;
+pub use delete_config::handle_delete_config_input;
+pub use extract_config::handle_extract_config_input;
+pub use file_browser::handle_file_browser_input;
+pub use file_selection::{handle_file_selection_input, paste_into_file_input};
+pub use help::handle_help_input;
+pub use logs::handle_logs_input;
 pub use main_handler::handle_main_input;
-pub use file_selection::handle_file_selection_input;
 pub use merge_config::handle_merge_config_input;
-pub use delete_config::handle_delete_config_input;
+pub use mouse::handle_mouse_input;
+pub use onboarding::handle_onboarding_input;
+pub use overlay::handle_overlay_input;
+pub use page_grid::handle_page_grid_input;
+pub use page_order::handle_page_order_input;
+pub use processing::handle_processing_input;
+pub use queue::handle_queue_input;
 pub use result::handle_result_input;
+pub use rotate_config::handle_rotate_config_input;
 pub use split_config::handle_split_config_input;
+pub use watermark_config::handle_watermark_config_input;
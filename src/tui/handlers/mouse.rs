@@ -0,0 +1,389 @@
+use crate::tui::app::App;
+use crate::tui::handlers::main_handler::MAIN_MENU_ITEMS;
+use crate::tui::handlers::output_location::handle_output_directory_input;
+use crate::tui::handlers::{
+    handle_compress_config_input, handle_delete_config_input, handle_extract_config_input,
+    handle_file_browser_input, handle_file_selection_input, handle_help_input, handle_logs_input,
+    handle_main_input, handle_merge_config_input, handle_page_order_input, handle_queue_input,
+    handle_result_input, handle_rotate_config_input, handle_split_config_input,
+    handle_watermark_config_input,
+};
+use crate::tui::state::page_grid_state::PAGE_GRID_COLUMNS;
+use crate::tui::state::{BrowserFocus, CurrentScreen};
+use crate::tui::ui::{
+    browser_areas, list_index_at, page_grid_areas, page_grid_page_at, screen_layout,
+};
+use crossterm::event::{KeyCode, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Position, Rect};
+
+/**
+ * Handle a mouse event on the current screen.
+ * Left clicks select list items and focus input fields, the wheel scrolls lists.
+ * @param mouse The mouse event.
+ * @param area The full terminal area, used to recompute the screen layout.
+ * @param app The application state.
+ */
+pub fn handle_mouse_input(mouse: MouseEvent, area: Rect, app: &mut App) {
+    // The command palette is keyboard only; the screen below it does not react to clicks
+    if app.palette.open {
+        return;
+    }
+    if app.ui_state.show_cheat_sheet {
+        if mouse.kind == MouseEventKind::Down(MouseButton::Left) {
+            app.ui_state.show_cheat_sheet = false;
+        }
+        return;
+    }
+    // Only Enter confirms a space warning or an in-place write, a click cancels the operation held back
+    if app.space_prompt.is_some() || app.in_place_prompt.is_some() {
+        if mouse.kind == MouseEventKind::Down(MouseButton::Left) {
+            app.space_prompt = None;
+            app.in_place_prompt = None;
+        }
+        return;
+    }
+
+    let position = Position::new(mouse.column, mouse.row);
+
+    let scroll_key = match mouse.kind {
+        MouseEventKind::ScrollUp => Some(KeyCode::Up),
+        MouseEventKind::ScrollDown => Some(KeyCode::Down),
+        MouseEventKind::Down(MouseButton::Left) => None,
+        _ => return,
+    };
+
+    // A click acknowledges the current toast; fields committed by the click may raise a new one
+    if scroll_key.is_none() && app.toast_error().is_some() {
+        app.ui_state.dismiss_toast();
+    }
+
+    let chunks = screen_layout(&app.current_screen, area);
+
+    match scroll_key {
+        Some(key) => handle_scroll(key, app),
+        None => handle_click(position, mouse.modifiers, &chunks, app),
+    }
+}
+
+/**
+ * Scroll the list of the current screen by one item.
+ * Merge configuration uses Up/Down to reorder files, so the wheel only moves the cursor there.
+ */
+fn handle_scroll(key: KeyCode, app: &mut App) {
+    match app.current_screen {
+        CurrentScreen::Main => handle_main_input(key, app),
+        CurrentScreen::FileSelection => handle_file_selection_input(key, KeyModifiers::NONE, app),
+        CurrentScreen::FileBrowser => handle_file_browser_input(key, app),
+        CurrentScreen::PageGrid => {
+            let columns = PAGE_GRID_COLUMNS as i64;
+            app.page_grid.move_cursor(if key == KeyCode::Up {
+                -columns
+            } else {
+                columns
+            });
+        }
+        CurrentScreen::PageOrder => handle_page_order_input(key, KeyModifiers::NONE, app),
+        CurrentScreen::Result => handle_result_input(key, app),
+        CurrentScreen::Queue => handle_queue_input(key, app),
+        CurrentScreen::Help => handle_help_input(key, app),
+        CurrentScreen::Logs => handle_logs_input(key, app),
+        CurrentScreen::MergeConfig => {
+            let index = app.merge_file_index();
+            if key == KeyCode::Up && index > 0 {
+                app.set_merge_file_index(index - 1);
+            } else if key == KeyCode::Down && index + 1 < app.files_len() {
+                app.set_merge_file_index(index + 1);
+            }
+        }
+        _ => {}
+    }
+}
+
+/**
+ * Dispatch a left click to the widget rendered under it.
+ */
+fn handle_click(position: Position, modifiers: KeyModifiers, chunks: &[Rect], app: &mut App) {
+    let clicked = |index: usize| chunks.get(index).is_some_and(|c| c.contains(position));
+
+    match app.current_screen {
+        CurrentScreen::Main => {
+            if clicked(1)
+                && let Some(index) = list_index_at(
+                    chunks[1],
+                    position.y,
+                    app.menu_mode_index(),
+                    MAIN_MENU_ITEMS,
+                )
+            {
+                app.set_menu_mode_index(index);
+                handle_main_input(KeyCode::Enter, app);
+            }
+        }
+        CurrentScreen::FileSelection => {
+            if clicked(1) {
+                if let Some(index) = list_index_at(
+                    chunks[1],
+                    position.y,
+                    app.selected_file_index(),
+                    app.files_len(),
+                ) {
+                    app.set_selected_file_index(index);
+                }
+            } else if clicked(2) && !app.editing_input() {
+                handle_file_selection_input(KeyCode::Tab, KeyModifiers::NONE, app);
+            }
+        }
+        CurrentScreen::FileBrowser => {
+            let (sidebar, entries) = browser_areas(chunks[1]);
+            let browser = &mut app.browser;
+            if sidebar.contains(position)
+                && let Some(index) = list_index_at(
+                    sidebar,
+                    position.y,
+                    browser.favorite_index,
+                    browser.favorites.len(),
+                )
+            {
+                browser.favorite_index = index;
+                browser.focus = BrowserFocus::Favorites;
+                handle_file_browser_input(KeyCode::Enter, app);
+            } else if entries.contains(position)
+                && let Some(index) =
+                    list_index_at(entries, position.y, browser.selected, browser.entries.len())
+            {
+                // A second click on the selected entry opens it
+                let open = browser.focus == BrowserFocus::Entries && browser.selected == index;
+                browser.focus = BrowserFocus::Entries;
+                browser.selected = index;
+                if open {
+                    handle_file_browser_input(KeyCode::Enter, app);
+                }
+            }
+        }
+        CurrentScreen::MergeConfig => {
+            if clicked(1) {
+                if let Some(index) = list_index_at(
+                    chunks[1],
+                    position.y,
+                    app.merge_file_index(),
+                    app.files_len(),
+                ) {
+                    app.set_merge_file_index(index);
+                }
+            } else if clicked(3) && !app.output_location.editing {
+                if app.merge_config.editing_output {
+                    handle_merge_config_input(KeyCode::Enter, app);
+                }
+                app.output_location.editing = true;
+            } else if clicked(4) && !app.merge_config.editing_output {
+                commit_output_directory(app);
+                if app.toast_error().is_none() {
+                    handle_merge_config_input(KeyCode::Tab, app);
+                }
+            }
+        }
+        CurrentScreen::DeleteConfig => {
+            if clicked(2) && !app.delete_config.editing_pages {
+                commit_delete_fields(app);
+                if app.toast_error().is_none() {
+                    app.delete_config.editing_pages = true;
+                }
+            } else if clicked(3) && !app.output_location.editing {
+                commit_delete_fields(app);
+                if app.toast_error().is_none() {
+                    app.output_location.editing = true;
+                }
+            } else if clicked(4) && !app.delete_config.editing_output {
+                commit_delete_fields(app);
+                if app.toast_error().is_none() {
+                    app.delete_config.editing_output = true;
+                }
+            }
+        }
+        CurrentScreen::SplitConfig => {
+            commit_output_directory(app);
+            if app.toast_error().is_some() {
+                return;
+            }
+            let editing = app.split_config.editing_segments || app.split_config.editing_prefix;
+            if clicked(2) && !app.split_config.editing_segments {
+                if editing {
+                    handle_split_config_input(KeyCode::Enter, app);
+                }
+                app.split_config.editing_segments = true;
+            } else if clicked(3) && !editing {
+                handle_split_config_input(KeyCode::Char(' '), app);
+            } else if clicked(4) {
+                if editing {
+                    handle_split_config_input(KeyCode::Enter, app);
+                }
+                app.output_location.editing = true;
+            } else if clicked(5) && !app.split_config.editing_prefix {
+                if editing {
+                    handle_split_config_input(KeyCode::Enter, app);
+                }
+                app.split_config.editing_prefix = true;
+            }
+        }
+        CurrentScreen::RotateConfig => {
+            let config = &app.rotate_config;
+            if config.editing_pages || config.editing_output || app.output_location.editing {
+                handle_rotate_config_input(KeyCode::Enter, app);
+            }
+            if app.toast_error().is_some() {
+                return;
+            }
+            if clicked(1) {
+                let config = &app.rotate_config;
+                if let Some(index) = list_index_at(
+                    chunks[1],
+                    position.y,
+                    config.selected_rule,
+                    config.rules.len(),
+                ) {
+                    app.rotate_config.selected_rule = index;
+                }
+            } else if clicked(2) {
+                app.rotate_config.editing_pages = true;
+            } else if clicked(3) {
+                app.rotate_config.cycle_angle(true);
+            } else if clicked(4) {
+                app.output_location.editing = true;
+            } else if clicked(5) {
+                app.rotate_config.editing_output = true;
+            }
+        }
+        CurrentScreen::ExtractConfig => {
+            let config = &app.extract_config;
+            if config.editing_pages || config.editing_output || app.output_location.editing {
+                handle_extract_config_input(KeyCode::Enter, app);
+            }
+            if app.toast_error().is_some() {
+                return;
+            }
+            if clicked(2) {
+                app.extract_config.editing_pages = true;
+            } else if clicked(3) {
+                let options = &mut app.extract_config.options;
+                options.keep_annotations = !options.keep_annotations;
+            } else if clicked(4) {
+                app.output_location.editing = true;
+            } else if clicked(5) {
+                app.extract_config.editing_output = true;
+            }
+        }
+        CurrentScreen::CompressConfig => {
+            if app.compress_config.editing_output || app.output_location.editing {
+                handle_compress_config_input(KeyCode::Enter, app);
+            }
+            if app.toast_error().is_some() {
+                return;
+            }
+            let options = &mut app.compress_config.options;
+            if clicked(2) {
+                options.recompress_streams = !options.recompress_streams;
+            } else if clicked(3) {
+                app.compress_config.cycle_image_dpi(true);
+            } else if clicked(4) {
+                options.strip_metadata = !options.strip_metadata;
+            } else if clicked(5) {
+                options.flatten_annotations = !options.flatten_annotations;
+            } else if clicked(6) {
+                app.output_location.editing = true;
+            } else if clicked(7) {
+                app.compress_config.editing_output = true;
+            }
+        }
+        CurrentScreen::WatermarkConfig => {
+            let config = &app.watermark_config;
+            if config.editing_text
+                || config.editing_image
+                || config.editing_pages
+                || config.editing_output
+                || app.output_location.editing
+            {
+                handle_watermark_config_input(KeyCode::Enter, app);
+            }
+            if app.toast_error().is_some() {
+                return;
+            }
+            let config = &mut app.watermark_config;
+            if clicked(2) {
+                if config.use_image {
+                    config.editing_image = true;
+                } else {
+                    config.editing_text = true;
+                }
+            } else if clicked(3) {
+                config.cycle_position();
+            } else if clicked(4) {
+                // Left half lowers the opacity, right half raises it
+                config.adjust_opacity(position.x >= chunks[4].x + chunks[4].width / 2);
+            } else if clicked(5) {
+                config.editing_pages = true;
+            } else if clicked(6) {
+                app.output_location.editing = true;
+            } else if clicked(7) {
+                config.editing_output = true;
+            }
+        }
+        CurrentScreen::PageGrid => {
+            if clicked(1)
+                && let Some(page) =
+                    page_grid_page_at(page_grid_areas(chunks[1]).0, position, &app.page_grid)
+            {
+                app.page_grid.cursor = page;
+                if modifiers.contains(KeyModifiers::SHIFT) {
+                    app.page_grid.select_to_cursor();
+                } else {
+                    app.page_grid.toggle_current();
+                }
+            }
+        }
+        CurrentScreen::PageOrder => {
+            let state = &app.page_order;
+            if clicked(1)
+                && let Some(index) =
+                    list_index_at(chunks[1], position.y, state.cursor, state.order.len())
+            {
+                app.page_order.cursor = index;
+            }
+        }
+        CurrentScreen::Result => {
+            if clicked(2) {
+                if let Some(index) = list_index_at(
+                    chunks[2],
+                    position.y,
+                    app.result.selected,
+                    app.result.outputs.len(),
+                ) {
+                    app.result.selected = index;
+                }
+            } else {
+                handle_result_input(KeyCode::Enter, app);
+            }
+        }
+        _ => {}
+    }
+}
+
+/**
+ * Leave whichever delete field is being edited, running the same validation as the keyboard.
+ */
+fn commit_delete_fields(app: &mut App) {
+    if app.delete_config.editing_pages
+        || app.delete_config.editing_output
+        || app.output_location.editing
+    {
+        handle_delete_config_input(KeyCode::Enter, app);
+    }
+}
+
+/**
+ * Leave the output directory field if it is being edited, validating the directory.
+ */
+fn commit_output_directory(app: &mut App) {
+    if app.output_location.editing {
+        handle_output_directory_input(KeyCode::Enter, app);
+    }
+}
@@ -0,0 +1,61 @@
+use crate::config;
+use crate::i18n::tr;
+use crate::pdf::output::contract_home;
+use crate::tui::app::App;
+use crate::tui::state::CurrentScreen;
+use crate::tui::ui::onboarding_pages;
+use crate::tui::utils::error_chain;
+use crossterm::event::KeyCode;
+
+/**
+ * Handle input in the welcome tour shown on the first run.
+ * @param key The key event.
+ * @param app The application state.
+ */
+pub fn handle_onboarding_input(key: KeyCode, app: &mut App) {
+    let last = onboarding_pages(app).len() - 1;
+    let page = &mut app.ui_state.onboarding_page;
+    match key {
+        KeyCode::Right | KeyCode::Enter | KeyCode::Char(' ') if *page < last => *page += 1,
+        KeyCode::Right | KeyCode::Enter | KeyCode::Char(' ') => finish_onboarding(app),
+        KeyCode::Left | KeyCode::Backspace => *page = page.saturating_sub(1),
+        KeyCode::Esc => finish_onboarding(app),
+        _ => {}
+    }
+}
+
+/**
+ * Open the welcome tour on its first page.
+ * @param app The application state.
+ */
+pub fn start_onboarding(app: &mut App) {
+    app.ui_state.onboarding_page = 0;
+    app.current_screen = CurrentScreen::Onboarding;
+}
+
+/**
+ * Leave the welcome tour for the main menu, creating the default config file if there is none,
+ * so the tour is not shown again.
+ * @param app The application state.
+ */
+fn finish_onboarding(app: &mut App) {
+    app.ui_state.onboarding_page = 0;
+    app.current_screen = CurrentScreen::Main;
+
+    let Some(path) = app.config_path.clone().filter(|path| !path.exists()) else {
+        return;
+    };
+    match config::create_default(&path) {
+        Ok(()) => {
+            log::info!("Created config file '{}'", path.display());
+            app.notify(tr!(
+                "Settings can be changed in {path}",
+                path = contract_home(&path)
+            ));
+        }
+        Err(e) => app.notify_error(tr!(
+            "Could not create the config file: {error}",
+            error = error_chain(&e)
+        )),
+    }
+}
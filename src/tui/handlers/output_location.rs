@@ -0,0 +1,237 @@
+use crate::i18n::tr;
+use crate::pdf::operation::Operation;
+use crate::pdf::output::backup_file;
+use crate::tui::app::App;
+use crate::tui::handlers::queue::queue_operation;
+use crate::tui::state::{InPlacePrompt, SpacePrompt};
+use crate::tui::utils::{complete_directory, error_chain, validate_output_directory};
+use crossterm::event::KeyCode;
+
+/**
+ * Handle input while the output directory field is being edited.
+ * Tab completes the typed path; pressing it again cycles through the matching directories.
+ * @param key The key event.
+ * @param app The application state.
+ */
+pub fn handle_output_directory_input(key: KeyCode, app: &mut App) {
+    let location = &mut app.output_location;
+
+    match key {
+        KeyCode::Char(c) => {
+            location.directory.push(c);
+            location.clear_completions();
+        }
+        KeyCode::Backspace => {
+            location.directory.pop();
+            location.clear_completions();
+        }
+        KeyCode::Tab => cycle_completion(app, 1),
+        KeyCode::BackTab => cycle_completion(app, -1),
+        KeyCode::Enter => {
+            location.editing = false;
+            location.clear_completions();
+            if let Err(e) = validate_output_directory(&location.directory) {
+                app.notify_error(e.to_string());
+            }
+        }
+        KeyCode::Esc => {
+            location.editing = false;
+            location.clear_completions();
+        }
+        _ => {}
+    }
+}
+
+/**
+ * Replace the typed directory with the next (or previous) matching directory.
+ * A single match is accepted directly so that the next Tab descends into it.
+ */
+fn cycle_completion(app: &mut App, step: isize) {
+    let location = &mut app.output_location;
+
+    if location.completions.is_empty() {
+        let completions = complete_directory(&location.directory);
+        match completions.len() {
+            0 => return,
+            1 => {
+                location.directory = completions[0].clone();
+                return;
+            }
+            _ => {
+                location.completions = completions;
+                location.completion_index = if step > 0 {
+                    0
+                } else {
+                    location.completions.len() - 1
+                };
+            }
+        }
+    } else {
+        let len = location.completions.len() as isize;
+        location.completion_index =
+            (location.completion_index as isize + step).rem_euclid(len) as usize;
+    }
+
+    location.directory = location.completions[location.completion_index].clone();
+}
+
+/// Word to type in the in-place confirmation, in the user's language.
+pub fn in_place_word() -> &'static str {
+    tr!("overwrite")
+}
+
+/**
+ * Check an operation before running it: writing over one of its inputs needs a typed confirmation,
+ * then its outputs must fit on disk and stay under the configured size.
+ * Once it can run, the files its outputs replace are kept when a backup suffix is configured.
+ * @param app The application state.
+ * @param operation The operation about to run.
+ * @returns true if the operation can run now.
+ */
+pub fn confirm_run(app: &mut App, operation: &Operation) -> bool {
+    if !confirm_in_place(app, operation, false) || !confirm_space(app, operation) {
+        return false;
+    }
+    app.in_place_prompt = None;
+    match back_up_outputs(app, operation) {
+        Ok(()) => true,
+        Err(e) => {
+            app.notify_error(error_chain(&e));
+            false
+        }
+    }
+}
+
+/**
+ * Move the files the outputs of an operation replace out of the way, when a backup suffix is configured.
+ * @param app The application state.
+ * @param operation The operation about to run.
+ * @throws anyhow::Error if a replaced file cannot be renamed.
+ */
+pub fn back_up_outputs(app: &App, operation: &Operation) -> anyhow::Result<()> {
+    let Some(suffix) = &app.backup_suffix else {
+        return Ok(());
+    };
+    for (path, backup) in operation.back_up_outputs(suffix)? {
+        log::info!("Moved '{}' to '{}'", path, backup);
+    }
+    Ok(())
+}
+
+/**
+ * Hold back an operation that writes over one of its inputs until the confirmation word is typed.
+ * The confirmed prompt stays until the operation runs, so the output size warning can follow it.
+ * @param app The application state.
+ * @param operation The operation about to run or be queued.
+ * @param queue Whether the operation is added to the queue once confirmed.
+ * @returns true if the operation writes new files only, or writing over its inputs was confirmed.
+ */
+pub fn confirm_in_place(app: &mut App, operation: &Operation, queue: bool) -> bool {
+    let files = operation.overwritten_inputs();
+    if files.is_empty() || app.in_place_prompt.as_ref().is_some_and(|p| p.confirmed) {
+        return true;
+    }
+    app.in_place_prompt = Some(InPlacePrompt {
+        operation: operation.clone(),
+        files,
+        typed: String::new(),
+        backup: app.backup_in_place || app.backup_suffix.is_some(),
+        queue,
+        confirmed: false,
+    });
+    false
+}
+
+/**
+ * Handle a key in the in-place confirmation.
+ * Enter with the confirmation word typed backs the inputs up when asked to, then queues the operation
+ * or lets the screen run it again; Tab toggles the backup and Esc cancels.
+ * @param key The key event.
+ * @param app The application state.
+ * @returns true if the key was consumed, false to let the screen run the confirmed operation.
+ */
+pub fn handle_in_place_input(key: KeyCode, app: &mut App) -> bool {
+    let Some(prompt) = &mut app.in_place_prompt else {
+        return false;
+    };
+    match key {
+        KeyCode::Char(c) => prompt.typed.push(c),
+        KeyCode::Backspace => {
+            prompt.typed.pop();
+        }
+        KeyCode::Tab => prompt.backup = !prompt.backup,
+        KeyCode::Esc => app.in_place_prompt = None,
+        KeyCode::Enter if !prompt.typed.trim().eq_ignore_ascii_case(in_place_word()) => {
+            app.notify_error(tr!("Type '{word}' to confirm", word = in_place_word()));
+        }
+        KeyCode::Enter => {
+            let prompt = prompt.clone();
+            if prompt.backup {
+                for file in &prompt.files {
+                    match backup_file(file, app.in_place_backup_suffix()) {
+                        Ok(backup) => app.notify(tr!(
+                            "Backed up '{path}' to '{backup}'",
+                            path = file,
+                            backup = backup
+                        )),
+                        Err(e) => {
+                            app.notify_error(tr!(
+                                "Could not back up '{path}': {error}",
+                                path = file,
+                                error = e
+                            ));
+                            app.in_place_prompt = None;
+                            return true;
+                        }
+                    }
+                }
+            }
+            if let Some(held) = &mut app.in_place_prompt {
+                held.confirmed = true;
+            }
+            if prompt.queue {
+                queue_operation(app, prompt.operation);
+                return true;
+            }
+            return false;
+        }
+        _ => {}
+    }
+    true
+}
+
+/**
+ * Check that the outputs of an operation fit on disk and stay under the configured size before running it.
+ * When they may not, the warnings are shown and the operation is held back; Enter in the warning
+ * runs it anyway.
+ * @param app The application state.
+ * @param operation The operation about to run.
+ * @returns true if the operation can run now.
+ */
+fn confirm_space(app: &mut App, operation: &Operation) -> bool {
+    if app
+        .space_prompt
+        .take()
+        .is_some_and(|prompt| prompt.confirmed)
+    {
+        return true;
+    }
+
+    let warnings = match app.space_check.warnings(operation) {
+        Ok(warnings) => warnings,
+        Err(e) => {
+            // Unreadable inputs are reported by the operation itself
+            log::debug!("Could not estimate the output size: {}", error_chain(&e));
+            return true;
+        }
+    };
+    if warnings.is_empty() {
+        return true;
+    }
+    app.space_prompt = Some(SpacePrompt {
+        operation: operation.clone(),
+        warnings,
+        confirmed: false,
+    });
+    false
+}
@@ -0,0 +1,100 @@
+use crate::tui::app::App;
+use crate::tui::handlers::output_location::handle_in_place_input;
+use crate::tui::handlers::palette::{handle_palette_input, is_palette_shortcut};
+use crate::tui::state::CurrentScreen;
+use crate::tui::state::ui_state::DiscardAction;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/**
+ * Handle keys that open or drive an overlay shown on top of any screen:
+ * the command palette (Ctrl+K) and the key cheat-sheet ('?'), the log viewer toggle ('L'),
+ * the way back to the main menu (Ctrl+H) and the confirmation it asks for,
+ * the confirmation asked before writing over an input, and the warning shown when the outputs
+ * of an operation may not fit.
+ * @param key The key event.
+ * @param app The application state.
+ * @returns true if the key was consumed by an overlay.
+ */
+pub fn handle_overlay_input(key: &KeyEvent, app: &mut App) -> bool {
+    if app.palette.open {
+        handle_palette_input(key.code, app);
+        return true;
+    }
+
+    if app.in_place_prompt.as_ref().is_some_and(|p| !p.confirmed) {
+        return handle_in_place_input(key.code, app);
+    }
+
+    // Enter goes on to the screen, which runs the held back operation; any other key cancels it
+    if let Some(prompt) = &mut app.space_prompt {
+        if key.code == KeyCode::Enter {
+            prompt.confirmed = true;
+            return false;
+        }
+        app.space_prompt = None;
+        app.in_place_prompt = None;
+        return true;
+    }
+
+    // Enter or Y drops the current operation, any other key keeps it
+    if let Some(action) = app.ui_state.discard_prompt.take() {
+        if matches!(
+            key.code,
+            KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y')
+        ) {
+            app.discard(action);
+        }
+        return true;
+    }
+
+    // Any key closes the cheat-sheet
+    if app.ui_state.show_cheat_sheet {
+        app.ui_state.show_cheat_sheet = false;
+        return true;
+    }
+
+    if app.current_screen == CurrentScreen::Exiting {
+        return false;
+    }
+
+    if is_palette_shortcut(key) {
+        app.palette.show();
+        return true;
+    }
+
+    if key.code == KeyCode::Char('?') && !app.is_editing_text() {
+        app.ui_state.show_cheat_sheet = true;
+        return true;
+    }
+
+    if matches!(key.code, KeyCode::Char('l') | KeyCode::Char('L')) && !app.is_editing_text() {
+        app.toggle_logs();
+        return true;
+    }
+
+    if is_home_shortcut(key, app) {
+        app.request_discard(DiscardAction::GoHome);
+        return true;
+    }
+
+    false
+}
+
+/// Whether a key goes back to the main menu: Ctrl+H anywhere, or H outside of text fields
+/// and the file browser, where H shows hidden files.
+fn is_home_shortcut(key: &KeyEvent, app: &App) -> bool {
+    if app.current_screen == CurrentScreen::Main {
+        return false;
+    }
+    match key.code {
+        KeyCode::Char('h') | KeyCode::Char('H')
+            if key.modifiers.contains(KeyModifiers::CONTROL) =>
+        {
+            true
+        }
+        KeyCode::Char('H') => {
+            !app.is_editing_text() && app.current_screen != CurrentScreen::FileBrowser
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,105 @@
+use crate::i18n::tr;
+use crate::tui::app::App;
+use crate::tui::state::page_grid_state::PAGE_GRID_COLUMNS;
+use crate::tui::state::{CurrentScreen, OperationMode};
+use crate::tui::utils::{format_page_ranges, validate_page_ranges_in_document};
+use crossterm::event::{KeyCode, KeyModifiers};
+
+/**
+ * Open the page grid for the selected file.
+ * Loads the page geometry from the document and pre-selects the pages of the current specification.
+ * @param app The application state.
+ */
+pub fn open_page_grid(app: &mut App) {
+    let Some(file) = app.selected_files().first().cloned() else {
+        app.notify_error(tr!("No file selected").to_string());
+        return;
+    };
+
+    let pages = match app.inspect_cache.refresh(&file) {
+        Ok(info) => info.pages.clone(),
+        Err(e) => {
+            app.notify_error(tr!("Failed to read pages: {error}", error = e));
+            return;
+        }
+    };
+
+    let preselected = validate_page_ranges_in_document(current_page_spec(app), Some(pages.len()))
+        .unwrap_or_default();
+    app.page_grid.open(&file, pages, &preselected);
+    app.current_screen = CurrentScreen::PageGrid;
+}
+
+/**
+ * Handle input in the page grid screen.
+ * Arrows move the cursor, Space toggles a page, Shift extends a range from the last toggled page.
+ * @param key The key event.
+ * @param modifiers The key modifiers (Shift selects ranges).
+ * @param app The application state.
+ */
+pub fn handle_page_grid_input(key: KeyCode, modifiers: KeyModifiers, app: &mut App) {
+    let extend = modifiers.contains(KeyModifiers::SHIFT);
+    let columns = PAGE_GRID_COLUMNS as i64;
+
+    let movement = match key {
+        KeyCode::Left => Some(-1),
+        KeyCode::Right => Some(1),
+        KeyCode::Up => Some(-columns),
+        KeyCode::Down => Some(columns),
+        KeyCode::Home => Some(-(app.page_grid.page_count as i64)),
+        KeyCode::End => Some(app.page_grid.page_count as i64),
+        _ => None,
+    };
+
+    if let Some(delta) = movement {
+        app.page_grid.move_cursor(delta);
+        if extend {
+            app.page_grid.select_to_cursor();
+        }
+        return;
+    }
+
+    match key {
+        KeyCode::Char(' ') if extend => app.page_grid.select_to_cursor(),
+        KeyCode::Char(' ') => app.page_grid.toggle_current(),
+        KeyCode::Char('a') | KeyCode::Char('A') => app.page_grid.toggle_all(),
+        KeyCode::Enter => apply_page_grid(app),
+        KeyCode::Esc => app.current_screen = return_screen(app),
+        _ => {}
+    }
+}
+
+/**
+ * Write the grid selection back into the page specification of the current operation.
+ */
+fn apply_page_grid(app: &mut App) {
+    let spec = format_page_ranges(&app.page_grid.selected_pages());
+    match app.operation_mode {
+        OperationMode::Delete => app.delete_config.pages_to_delete = spec,
+        OperationMode::Rotate => app.rotate_config.rule_mut().pages = spec,
+        OperationMode::Extract => app.extract_config.pages = spec,
+        OperationMode::Watermark => app.watermark_config.pages = spec,
+        _ => {}
+    }
+    app.current_screen = return_screen(app);
+}
+
+fn current_page_spec(app: &App) -> &str {
+    match app.operation_mode {
+        OperationMode::Delete => &app.delete_config.pages_to_delete,
+        OperationMode::Rotate => &app.rotate_config.rule().pages,
+        OperationMode::Extract => &app.extract_config.pages,
+        OperationMode::Watermark => &app.watermark_config.pages,
+        _ => "",
+    }
+}
+
+fn return_screen(app: &App) -> CurrentScreen {
+    match app.operation_mode {
+        OperationMode::Delete => CurrentScreen::DeleteConfig,
+        OperationMode::Rotate => CurrentScreen::RotateConfig,
+        OperationMode::Extract => CurrentScreen::ExtractConfig,
+        OperationMode::Watermark => CurrentScreen::WatermarkConfig,
+        _ => CurrentScreen::Main,
+    }
+}
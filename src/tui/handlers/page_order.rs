@@ -0,0 +1,80 @@
+use crate::i18n::tr;
+use crate::tui::app::App;
+use crate::tui::state::CurrentScreen;
+use crate::tui::utils::{format_page_ranges, list_navigation, validate_page_order_in_document};
+use crossterm::event::{KeyCode, KeyModifiers};
+
+/**
+ * Open the page order screen for the selected file.
+ * Starts from the order typed in the extract configuration, or from the document order.
+ * @param app The application state.
+ */
+pub fn open_page_order(app: &mut App) {
+    let Some(file) = app.selected_files().first().cloned() else {
+        app.notify_error(tr!("No file selected").to_string());
+        return;
+    };
+
+    let pages = match app.inspect_cache.refresh(&file) {
+        Ok(info) => info.pages.clone(),
+        Err(e) => {
+            app.notify_error(tr!("Failed to read pages: {error}", error = e));
+            return;
+        }
+    };
+
+    let initial_order =
+        validate_page_order_in_document(&app.extract_config.pages, Some(pages.len()))
+            .unwrap_or_default();
+    app.page_order.open(pages, &initial_order);
+    app.current_screen = CurrentScreen::PageOrder;
+}
+
+/**
+ * Handle input in the page order screen.
+ * Alt+Up/Down moves the page under the cursor, D marks it for removal, and Enter writes
+ * the resulting order into the extract configuration.
+ * @param key The key event.
+ * @param modifiers The key modifiers (Alt moves pages).
+ * @param app The application state.
+ */
+pub fn handle_page_order_input(key: KeyCode, modifiers: KeyModifiers, app: &mut App) {
+    let state = &mut app.page_order;
+    let last = state.order.len().saturating_sub(1);
+
+    if modifiers.contains(KeyModifiers::ALT) {
+        match key {
+            KeyCode::Up => state.move_current(state.cursor.saturating_sub(1)),
+            KeyCode::Down => state.move_current(state.cursor + 1),
+            KeyCode::Home => state.move_current(0),
+            KeyCode::End => state.move_current(last),
+            _ => {}
+        }
+        return;
+    }
+
+    if let Some(index) = list_navigation(key, state.cursor, state.order.len()) {
+        state.cursor = index;
+        return;
+    }
+
+    match key {
+        KeyCode::Char('d') | KeyCode::Char('D') | KeyCode::Delete => state.toggle_removed(),
+        KeyCode::Enter => apply_page_order(app),
+        KeyCode::Esc => app.current_screen = CurrentScreen::ExtractConfig,
+        _ => {}
+    }
+}
+
+/**
+ * Write the kept pages, in order, as the pages to extract.
+ */
+fn apply_page_order(app: &mut App) {
+    let pages = app.page_order.kept_pages();
+    if pages.is_empty() {
+        app.notify_error(tr!("Keep at least one page").to_string());
+        return;
+    }
+    app.extract_config.pages = format_page_ranges(&pages);
+    app.current_screen = CurrentScreen::ExtractConfig;
+}
@@ -0,0 +1,252 @@
+use crate::i18n::{tr, translate};
+use crate::tui::app::App;
+use crate::tui::handlers::file_browser::{open_file_browser, work_in_current_folder};
+use crate::tui::handlers::onboarding::start_onboarding;
+use crate::tui::handlers::page_grid::open_page_grid;
+use crate::tui::handlers::page_order::open_page_order;
+use crate::tui::handlers::queue::handle_queue_input;
+use crate::tui::state::ui_state::DiscardAction;
+use crate::tui::state::{CurrentScreen, OperationMode};
+use crate::tui::theme::{current_theme, set_theme};
+use crate::tui::utils::fuzzy_score;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Actions offered by the command palette.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PaletteAction {
+    StartOperation(OperationMode),
+    AddFile,
+    MoveFileToTop,
+    MoveFileToBottom,
+    ToggleNamedSegments,
+    EditOutputDirectory,
+    ChangeWorkingDirectory,
+    PickPages,
+    OrderPages,
+    ShowQueue,
+    RunQueue,
+    SwitchTheme,
+    ShowLogs,
+    ShowHelp,
+    ShowOnboarding,
+    ShowAbout,
+    MainMenu,
+    StartOver,
+    Quit,
+}
+
+const ALL_ACTIONS: &[PaletteAction] = &[
+    PaletteAction::StartOperation(OperationMode::Merge),
+    PaletteAction::StartOperation(OperationMode::Delete),
+    PaletteAction::StartOperation(OperationMode::Split),
+    PaletteAction::StartOperation(OperationMode::Rotate),
+    PaletteAction::StartOperation(OperationMode::Extract),
+    PaletteAction::StartOperation(OperationMode::Compress),
+    PaletteAction::StartOperation(OperationMode::Watermark),
+    PaletteAction::AddFile,
+    PaletteAction::MoveFileToTop,
+    PaletteAction::MoveFileToBottom,
+    PaletteAction::ToggleNamedSegments,
+    PaletteAction::EditOutputDirectory,
+    PaletteAction::ChangeWorkingDirectory,
+    PaletteAction::PickPages,
+    PaletteAction::OrderPages,
+    PaletteAction::ShowQueue,
+    PaletteAction::RunQueue,
+    PaletteAction::SwitchTheme,
+    PaletteAction::ShowLogs,
+    PaletteAction::ShowHelp,
+    PaletteAction::ShowOnboarding,
+    PaletteAction::ShowAbout,
+    PaletteAction::MainMenu,
+    PaletteAction::StartOver,
+    PaletteAction::Quit,
+];
+
+impl PaletteAction {
+    /// Label shown and searched in the palette, in the current language.
+    pub fn label(&self) -> &'static str {
+        translate(match self {
+            PaletteAction::StartOperation(OperationMode::Merge) => "Start: Merge PDFs",
+            PaletteAction::StartOperation(OperationMode::Delete) => "Start: Delete pages",
+            PaletteAction::StartOperation(OperationMode::Split) => "Start: Split pages",
+            PaletteAction::StartOperation(OperationMode::Rotate) => "Start: Rotate pages",
+            PaletteAction::StartOperation(OperationMode::Extract) => "Start: Extract pages",
+            PaletteAction::StartOperation(OperationMode::Compress) => "Start: Compress PDF",
+            PaletteAction::StartOperation(OperationMode::Watermark) => "Start: Add a watermark",
+            PaletteAction::StartOperation(OperationMode::None) => "Start",
+            PaletteAction::AddFile => "Add a file",
+            PaletteAction::MoveFileToTop => "Move file to the top of the merge order",
+            PaletteAction::MoveFileToBottom => "Move file to the bottom of the merge order",
+            PaletteAction::ToggleNamedSegments => "Toggle named segments",
+            PaletteAction::EditOutputDirectory => "Change output directory",
+            PaletteAction::ChangeWorkingDirectory => "Change working directory",
+            PaletteAction::PickPages => "Pick pages in the page grid",
+            PaletteAction::OrderPages => "Reorder or remove pages in the page list",
+            PaletteAction::ShowQueue => "Show operation queue",
+            PaletteAction::RunQueue => "Run operation queue",
+            PaletteAction::SwitchTheme => {
+                "Switch theme (dark, light, high contrast, color-blind, mono)"
+            }
+            PaletteAction::ShowLogs => "Show logs",
+            PaletteAction::ShowHelp => "Help",
+            PaletteAction::ShowOnboarding => "Show the welcome tour",
+            PaletteAction::ShowAbout => "About: version and build details",
+            PaletteAction::MainMenu => "Back to main menu",
+            PaletteAction::StartOver => "Start over: reset the current operation",
+            PaletteAction::Quit => "Quit",
+        })
+    }
+
+    /// Whether the action makes sense on the current screen.
+    pub fn is_available(&self, app: &App) -> bool {
+        match self {
+            PaletteAction::AddFile => {
+                app.current_screen == CurrentScreen::FileSelection
+                    && (app.operation_mode == OperationMode::Merge || app.files_is_empty())
+            }
+            PaletteAction::MoveFileToTop | PaletteAction::MoveFileToBottom => {
+                app.operation_mode == OperationMode::Merge
+                    && matches!(
+                        app.current_screen,
+                        CurrentScreen::FileSelection | CurrentScreen::MergeConfig
+                    )
+                    && app.files_len() > 1
+            }
+            PaletteAction::ToggleNamedSegments => app.current_screen == CurrentScreen::SplitConfig,
+            PaletteAction::EditOutputDirectory => matches!(
+                app.current_screen,
+                CurrentScreen::MergeConfig
+                    | CurrentScreen::DeleteConfig
+                    | CurrentScreen::SplitConfig
+                    | CurrentScreen::RotateConfig
+                    | CurrentScreen::ExtractConfig
+                    | CurrentScreen::CompressConfig
+                    | CurrentScreen::WatermarkConfig
+            ),
+            PaletteAction::ChangeWorkingDirectory => match app.current_screen {
+                CurrentScreen::FileBrowser => true,
+                CurrentScreen::FileSelection => {
+                    app.operation_mode == OperationMode::Merge || app.files_is_empty()
+                }
+                _ => false,
+            },
+            PaletteAction::PickPages => matches!(
+                app.current_screen,
+                CurrentScreen::DeleteConfig
+                    | CurrentScreen::RotateConfig
+                    | CurrentScreen::ExtractConfig
+                    | CurrentScreen::WatermarkConfig
+            ),
+            PaletteAction::OrderPages => app.current_screen == CurrentScreen::ExtractConfig,
+            PaletteAction::RunQueue => !app.queue.steps.is_empty(),
+            PaletteAction::ShowLogs => app.current_screen != CurrentScreen::Logs,
+            PaletteAction::ShowOnboarding => app.current_screen != CurrentScreen::Onboarding,
+            PaletteAction::ShowAbout => app.current_screen != CurrentScreen::About,
+            PaletteAction::MainMenu => app.current_screen != CurrentScreen::Main,
+            PaletteAction::StartOver => {
+                app.operation_mode != OperationMode::None
+                    && app.current_screen != CurrentScreen::Main
+            }
+            _ => true,
+        }
+    }
+
+    fn run(&self, app: &mut App) {
+        match self {
+            PaletteAction::StartOperation(mode) => app.start_operation(*mode, None),
+            PaletteAction::AddFile => {
+                app.set_editing_input(true);
+                app.set_current_input(Some(String::new()));
+            }
+            PaletteAction::MoveFileToTop => app.move_selected_file(false),
+            PaletteAction::MoveFileToBottom => app.move_selected_file(true),
+            PaletteAction::ToggleNamedSegments => app.split_config.toggle_named_segments(),
+            PaletteAction::EditOutputDirectory => app.output_location.editing = true,
+            PaletteAction::ChangeWorkingDirectory => {
+                if app.current_screen == CurrentScreen::FileBrowser {
+                    work_in_current_folder(app);
+                } else {
+                    open_file_browser(app);
+                    app.notify(tr!("Go to a folder and press W to work in it").to_string());
+                }
+            }
+            PaletteAction::PickPages => open_page_grid(app),
+            PaletteAction::OrderPages => open_page_order(app),
+            PaletteAction::ShowQueue => app.current_screen = CurrentScreen::Queue,
+            PaletteAction::RunQueue => {
+                app.current_screen = CurrentScreen::Queue;
+                handle_queue_input(KeyCode::Enter, app);
+            }
+            PaletteAction::SwitchTheme => {
+                let theme = current_theme().next_preset();
+                set_theme(theme);
+                app.notify(tr!("Theme: {name}", name = theme.name));
+            }
+            PaletteAction::ShowLogs => app.toggle_logs(),
+            PaletteAction::ShowHelp => app.current_screen = CurrentScreen::Help,
+            PaletteAction::ShowOnboarding => start_onboarding(app),
+            PaletteAction::ShowAbout => app.current_screen = CurrentScreen::About,
+            PaletteAction::MainMenu => app.request_discard(DiscardAction::GoHome),
+            PaletteAction::StartOver => app.request_discard(DiscardAction::StartOver),
+            PaletteAction::Quit => app.current_screen = CurrentScreen::Exiting,
+        }
+    }
+}
+
+/// Whether a key event opens the command palette (Ctrl+K).
+pub fn is_palette_shortcut(key: &KeyEvent) -> bool {
+    key.modifiers.contains(KeyModifiers::CONTROL)
+        && matches!(key.code, KeyCode::Char('k') | KeyCode::Char('K'))
+}
+
+/**
+ * Actions available on the current screen that match the palette query, best match first.
+ * @param app The application state.
+ * @returns The matching actions.
+ */
+pub fn palette_matches(app: &App) -> Vec<PaletteAction> {
+    let mut matches: Vec<(usize, PaletteAction)> = ALL_ACTIONS
+        .iter()
+        .filter(|action| action.is_available(app))
+        .filter_map(|action| Some((fuzzy_score(&app.palette.query, action.label())?, *action)))
+        .collect();
+    // Stable sort keeps the table order between equally good matches
+    matches.sort_by_key(|(score, _)| *score);
+    matches.into_iter().map(|(_, action)| action).collect()
+}
+
+/**
+ * Handle input while the command palette is open.
+ * Typing filters the actions, Enter runs the selected one, Esc closes the palette.
+ * @param key The key event.
+ * @param app The application state.
+ */
+pub fn handle_palette_input(key: KeyCode, app: &mut App) {
+    match key {
+        KeyCode::Esc => app.palette.close(),
+        KeyCode::Up if app.palette.selected > 0 => {
+            app.palette.selected -= 1;
+        }
+        KeyCode::Down if app.palette.selected + 1 < palette_matches(app).len() => {
+            app.palette.selected += 1;
+        }
+        KeyCode::Char(c) => {
+            app.palette.query.push(c);
+            app.palette.selected = 0;
+        }
+        KeyCode::Backspace => {
+            app.palette.query.pop();
+            app.palette.selected = 0;
+        }
+        KeyCode::Enter => {
+            let action = palette_matches(app).get(app.palette.selected).copied();
+            app.palette.close();
+            if let Some(action) = action {
+                app.ui_state.dismiss_toast();
+                action.run(app);
+            }
+        }
+        _ => {}
+    }
+}
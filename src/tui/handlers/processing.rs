@@ -0,0 +1,104 @@
+use crate::i18n::tr;
+use crate::pdf::operation::Operation;
+use crate::tui::app::App;
+use crate::tui::state::{Completion, CurrentScreen, Job, ProcessingState};
+use crate::tui::utils::describe_operation;
+use anyhow::Result;
+use crossterm::event::KeyCode;
+
+/**
+ * Run an operation started from a configuration screen on a worker thread, showing the
+ * processing screen until it is over.
+ * @param app The application state.
+ * @param operation The checked and confirmed operation.
+ * @param show Shows the files it wrote on the result screen.
+ * @param failure The message of the result screen when it fails.
+ */
+pub fn start_operation(
+    app: &mut App,
+    operation: Operation,
+    show: fn(&mut App, Vec<String>),
+    failure: fn(&anyhow::Error) -> String,
+) {
+    let description = describe_operation(&operation);
+    start_processing(app, description, failure, move || {
+        let outputs = operation.run()?;
+        Ok(Box::new(move |app: &mut App| show(app, outputs)) as Completion)
+    });
+}
+
+/**
+ * Run the work of a configuration screen on a worker thread, showing the processing screen
+ * until it is over.
+ * @param app The application state.
+ * @param description What is being done, shown on the processing screen.
+ * @param failure The message of the result screen when the work fails.
+ * @param work Writes the outputs like Operation::run_with, deleting them when it fails or is
+ * cancelled, and returns what shows them on the result screen.
+ */
+pub fn start_processing(
+    app: &mut App,
+    description: String,
+    failure: fn(&anyhow::Error) -> String,
+    work: impl FnOnce() -> Result<Completion> + Send + 'static,
+) {
+    app.processing = Some(ProcessingState {
+        job: Job::spawn(work),
+        description,
+        return_screen: app.current_screen,
+        failure,
+    });
+    app.current_screen = CurrentScreen::Processing;
+}
+
+/**
+ * Handle input in the processing screen: Esc or C cancels the operation, which stops at its
+ * next page.
+ * @param key The key event.
+ * @param app The application state.
+ */
+pub fn handle_processing_input(key: KeyCode, app: &mut App) {
+    if matches!(key, KeyCode::Esc | KeyCode::Char('c') | KeyCode::Char('C'))
+        && let Some(processing) = &app.processing
+    {
+        processing.job.cancel();
+    }
+}
+
+/**
+ * Show the outcome of the running operation once it is over: the result screen, or after a
+ * cancellation, the configuration screen it was started from with its settings unchanged.
+ * Called by the event loop between redraws.
+ * @param app The application state.
+ */
+pub fn poll_processing(app: &mut App) {
+    let Some(outcome) = app.processing.as_ref().and_then(|p| p.job.outcome()) else {
+        return;
+    };
+    let Some(processing) = app.processing.take() else {
+        return;
+    };
+    // Ctrl+C may have opened the exit confirmation meanwhile, which stays shown
+    let shown = app.current_screen == CurrentScreen::Processing;
+    match outcome {
+        Err(_) if processing.job.is_cancelled() => {
+            log::info!("Cancelled: {}", processing.description);
+            app.notify(
+                tr!("Cancelled: the files the operation had started writing were deleted")
+                    .to_string(),
+            );
+            if shown {
+                app.current_screen = processing.return_screen;
+            }
+            return;
+        }
+        Ok(show) => show(app),
+        Err(e) => {
+            app.set_outputs(Vec::new());
+            app.set_failure((processing.failure)(&e), &e);
+        }
+    }
+    if shown {
+        app.current_screen = CurrentScreen::Result;
+    }
+}
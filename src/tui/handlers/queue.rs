@@ -0,0 +1,165 @@
+use crate::i18n::tr;
+use crate::pdf::operation::Operation;
+use crate::pdf::space::SpaceWarning;
+use crate::tui::app::App;
+use crate::tui::handlers::output_location::{back_up_outputs, confirm_in_place};
+use crate::tui::state::{CurrentScreen, Job, StepStatus};
+use crate::tui::utils::{describe_space_warning, error_chain};
+use crossterm::event::KeyCode;
+
+/**
+ * Add a configured operation to the queue and show the queue.
+ * An operation writing over one of its inputs is only added once confirmed.
+ * @param app The application state.
+ * @param operation The operation to run later.
+ */
+pub fn queue_operation(app: &mut App, operation: Operation) {
+    if !confirm_in_place(app, &operation, true) {
+        return;
+    }
+    app.in_place_prompt = None;
+    app.queue.push(operation);
+    app.current_screen = CurrentScreen::Queue;
+}
+
+/**
+ * Handle input in the queue screen.
+ * Enter runs every step in order and F runs again the failed and skipped ones; while running,
+ * only Esc or C (cancel the running step and the remaining ones) is accepted.
+ * @param key The key event.
+ * @param app The application state.
+ */
+pub fn handle_queue_input(key: KeyCode, app: &mut App) {
+    if app.queue.running {
+        if matches!(key, KeyCode::Esc | KeyCode::Char('c') | KeyCode::Char('C')) {
+            app.queue.cancel();
+        }
+        return;
+    }
+
+    match key {
+        KeyCode::Up if app.queue.selected > 0 => {
+            app.queue.selected -= 1;
+        }
+        KeyCode::Down if app.queue.selected + 1 < app.queue.steps.len() => {
+            app.queue.selected += 1;
+        }
+        KeyCode::Backspace | KeyCode::Delete | KeyCode::Char('x') | KeyCode::Char('X') => {
+            app.queue.remove_selected();
+        }
+        KeyCode::Char('c') | KeyCode::Char('C') => {
+            app.queue.clear();
+        }
+        KeyCode::Enter | KeyCode::Char('r') | KeyCode::Char('R') => {
+            if app.queue.steps.is_empty() {
+                app.notify_error(
+                    tr!("The queue is empty: press 'a' in a configuration screen to add an operation")
+                        .to_string(),
+                );
+            } else {
+                app.queue.start();
+            }
+        }
+        KeyCode::Char('f') | KeyCode::Char('F') => {
+            if app.queue.has_failures() {
+                app.queue.retry_failed();
+            } else {
+                app.notify_error(tr!("No failed step to retry").to_string());
+            }
+        }
+        KeyCode::Esc => {
+            app.current_screen = CurrentScreen::Main;
+        }
+        _ => {}
+    }
+}
+
+/**
+ * Move a running queue forward: take the outcome of the running step once it is over, or start
+ * the next pending step on a worker thread.
+ * Called by the event loop between redraws, so the progress stays visible while the queue runs.
+ * @param app The application state.
+ */
+pub fn advance_queue(app: &mut App) {
+    if app.queue.job.is_some() {
+        finish_queue_step(app);
+    } else if app.queue.running {
+        start_next_queue_step(app);
+    }
+}
+
+/**
+ * Start the next pending step of a running queue, once its outputs fit on disk.
+ * @param app The application state.
+ */
+fn start_next_queue_step(app: &mut App) {
+    let Some(index) = app.queue.next_pending() else {
+        app.queue.finish();
+        return;
+    };
+
+    app.queue.selected = index;
+    // Earlier steps have written this step's inputs by now, so its outputs can be estimated
+    let warnings = app
+        .space_check
+        .warnings(&app.queue.steps[index].operation)
+        .unwrap_or_default();
+    for warning in &warnings {
+        log::warn!("Step {}: {}", index + 1, describe_space_warning(warning));
+    }
+    if let Some(full) = warnings
+        .iter()
+        .find(|warning| matches!(warning, SpaceWarning::NotEnoughSpace { .. }))
+    {
+        app.queue.fail_step(index, describe_space_warning(full));
+        return;
+    }
+    if let Err(e) = back_up_outputs(app, &app.queue.steps[index].operation) {
+        log::error!("Step {} failed: {}", index + 1, error_chain(&e));
+        app.queue.fail_step(index, e.to_string());
+        return;
+    }
+    let operation = app.queue.steps[index].operation.clone();
+    app.queue.job = Some((index, Job::spawn(move || operation.run())));
+}
+
+/**
+ * Record the outcome of the running step once it is over.
+ * A failing step skips the later steps reading its outputs, or stops the queue when
+ * configured to. A cancelled step has deleted what it wrote; it is skipped with the steps
+ * after it.
+ * @param app The application state.
+ */
+fn finish_queue_step(app: &mut App) {
+    let Some(outcome) = app.queue.job.as_ref().and_then(|(_, job)| job.outcome()) else {
+        return;
+    };
+    let Some((index, job)) = app.queue.job.take() else {
+        return;
+    };
+    match outcome {
+        Ok(outputs) => {
+            log::info!("Step {} done", index + 1);
+            for output in &outputs {
+                if let Ok(info) = app.inspect_cache.refresh(output) {
+                    app.queue.pages_written += info.page_count();
+                }
+            }
+            app.queue.steps[index].status = StepStatus::Done(outputs);
+        }
+        Err(_) if job.is_cancelled() => {
+            log::info!("Step {} cancelled", index + 1);
+            app.queue.steps[index].status = StepStatus::Skipped;
+        }
+        Err(e) => {
+            log::error!("Step {} failed: {}", index + 1, error_chain(&e));
+            app.queue.fail_step(index, e.to_string());
+        }
+    }
+
+    if job.is_cancelled() {
+        app.queue.stop();
+    } else if app.queue.next_pending().is_none() {
+        app.queue.finish();
+    }
+}
@@ -1,18 +1,144 @@
+use crate::build_info;
+use crate::i18n::tr;
 use crate::tui::app::App;
-use crate::tui::state::CurrentScreen;
+use crate::tui::log_buffer;
+use crate::tui::state::{CurrentScreen, OperationMode};
+use crate::tui::system::copy_to_clipboard;
 use crossterm::event::KeyCode;
+use log::Level;
+use std::path::Path;
+
+/// Number of log lines shown with the details of a failure.
+pub const FAILURE_LOG_LINES: usize = 12;
 
 /**
  * Handle input in the result screen.
- * Shows success/error messages and allows returning to main menu.
+ * Lists the produced files and offers follow-up actions on the selected one:
+ * open it or its folder, copy its path, or use it as the input of the next operation in one keystroke.
+ * After a failure, D shows the details (cause chain and log tail) and C copies them.
  * @param key The key event.
  * @param app The application state.
  */
 pub fn handle_result_input(key: KeyCode, app: &mut App) {
     match key {
+        KeyCode::Up if app.result.selected > 0 => {
+            app.result.selected -= 1;
+        }
+        KeyCode::Down if app.result.selected + 1 < app.result.outputs.len() => {
+            app.result.selected += 1;
+        }
+        KeyCode::Char('o') | KeyCode::Char('O') => {
+            // Opened by the event loop, which suspends the terminal while the viewer starts
+            app.result.pending_open = app.result.selected_output().map(str::to_string);
+        }
+        KeyCode::Char('f') | KeyCode::Char('F') => {
+            app.result.pending_open = app.result.selected_output().map(output_folder);
+        }
+        KeyCode::Char('c') | KeyCode::Char('C') => {
+            if let Some(output) = app.result.selected_output() {
+                app.result.notice = Some(match copy_to_clipboard(output) {
+                    Ok(()) => tr!("Copied path to clipboard").to_string(),
+                    Err(e) => tr!("Could not copy path: {error}", error = e),
+                });
+            } else if let Some(report) = failure_report(app) {
+                match copy_to_clipboard(&report) {
+                    Ok(()) => {
+                        app.notify(tr!("Copied the error details to the clipboard").to_string())
+                    }
+                    Err(e) => app
+                        .notify_error(tr!("Could not copy the error details: {error}", error = e)),
+                }
+            }
+        }
+        KeyCode::Char('d') | KeyCode::Char('D') if app.error_message().is_some() => {
+            app.result.show_details = !app.result.show_details;
+        }
+        KeyCode::Esc if app.result.show_details => {
+            app.result.show_details = false;
+        }
+        KeyCode::Char(c @ '1'..='7') => {
+            let mode = match c {
+                '1' => OperationMode::Merge,
+                '2' => OperationMode::Delete,
+                '3' => OperationMode::Split,
+                '4' => OperationMode::Rotate,
+                '5' => OperationMode::Extract,
+                '6' => OperationMode::Compress,
+                _ => OperationMode::Watermark,
+            };
+            continue_with_selected_output(mode, app);
+        }
+        KeyCode::Char('n') | KeyCode::Char('N') if app.operation_mode != OperationMode::None => {
+            continue_with_selected_output(app.operation_mode, app);
+        }
         KeyCode::Esc | KeyCode::Enter | KeyCode::Char(' ') => {
             app.current_screen = CurrentScreen::Main;
         }
         _ => {}
     }
 }
+
+/**
+ * Folder containing an output, for the file manager.
+ * @param output The output path, possibly relative to the current directory.
+ * @returns The parent directory, "." for a bare file name.
+ */
+pub fn output_folder(output: &str) -> String {
+    match Path::new(output).parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_string_lossy().into_owned(),
+        _ => ".".to_string(),
+    }
+}
+
+/**
+ * Start an operation with the selected output as its input, keeping the current settings.
+ */
+fn continue_with_selected_output(mode: OperationMode, app: &mut App) {
+    if let Some(output) = app.result.selected_output().map(str::to_string) {
+        app.continue_with(mode, output);
+    }
+}
+
+/**
+ * Describe the last failure for its details view: the full cause chain, then the most recent
+ * log lines.
+ * @param app The application state.
+ * @returns The details, None when the last operation did not fail.
+ */
+pub fn failure_details(app: &App) -> Option<String> {
+    let message = app.error_message()?;
+    let chain = app.result.details.as_deref().unwrap_or(message);
+    let lines = log_buffer::recent_lines(Level::Info);
+    let tail: Vec<String> = lines[lines.len().saturating_sub(FAILURE_LOG_LINES)..]
+        .iter()
+        .map(|line| {
+            format!(
+                "{:>8.3}s {:<5} {}",
+                line.elapsed.as_secs_f64(),
+                line.level,
+                line.message
+            )
+        })
+        .collect();
+    let tail = if tail.is_empty() {
+        tr!("Nothing logged yet").to_string()
+    } else {
+        tail.join("\n")
+    };
+    Some(format!("{}\n\n{}\n{}", chain, tr!("Recent log:"), tail))
+}
+
+/**
+ * Describe the last failure as plain text to paste in a bug report: the message, its details
+ * and the build details.
+ * @param app The application state.
+ * @returns The report, None when the last operation did not fail.
+ */
+pub fn failure_report(app: &App) -> Option<String> {
+    Some(format!(
+        "{}\n\n{}\n\n{}",
+        app.error_message()?,
+        failure_details(app)?,
+        build_info::report(app.config_path.as_deref())
+    ))
+}
@@ -0,0 +1,247 @@
+use crate::i18n::tr;
+use crate::pdf::RotationRule;
+use crate::pdf::operation::Operation;
+use crate::pdf::output::DEFAULT_ROTATE_OUTPUT;
+use crate::tui::app::App;
+use crate::tui::handlers::output_location::{confirm_run, handle_output_directory_input};
+use crate::tui::handlers::page_grid::open_page_grid;
+use crate::tui::handlers::processing::start_operation;
+use crate::tui::handlers::queue::queue_operation;
+use crate::tui::state::CurrentScreen;
+use crate::tui::utils::{
+    validate_output_directory, validate_output_name, validate_page_ranges_in_document,
+};
+use crossterm::event::KeyCode;
+
+/**
+ * Handle input in the rotate configuration screen.
+ * Allows editing a list of rules (pages and angle), the output filename, and starting the rotation.
+ * A rule with an empty page list rotates every page.
+ * @param key The key event.
+ * @param app The application state.
+ */
+pub fn handle_rotate_config_input(key: KeyCode, app: &mut App) {
+    if app.output_location.editing {
+        handle_output_directory_input(key, app);
+        return;
+    }
+
+    if app.rotate_config.editing_pages {
+        match key {
+            KeyCode::Char(c) => {
+                app.rotate_config.rule_mut().pages.push(c);
+            }
+            KeyCode::Backspace => {
+                app.rotate_config.rule_mut().pages.pop();
+            }
+            // Errors are shown on the field itself as the user types
+            KeyCode::Enter | KeyCode::Tab | KeyCode::Esc => {
+                app.rotate_config.editing_pages = false;
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    if app.rotate_config.editing_output {
+        match key {
+            KeyCode::Char(c) => {
+                app.rotate_config.output_filename.push(c);
+            }
+            KeyCode::Backspace => {
+                app.rotate_config.output_filename.pop();
+            }
+            KeyCode::Enter | KeyCode::Tab => {
+                app.rotate_config.editing_output = false;
+
+                if !app.rotate_config.output_filename.ends_with(".pdf")
+                    && !app.rotate_config.output_filename.is_empty()
+                {
+                    app.rotate_config.output_filename.push_str(".pdf");
+                }
+            }
+            KeyCode::Esc => {
+                app.rotate_config.editing_output = false;
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    match key {
+        KeyCode::Char('p') | KeyCode::Char('P') => {
+            app.rotate_config.editing_pages = true;
+        }
+
+        KeyCode::Char('r') | KeyCode::Char('R') | KeyCode::Char(' ') | KeyCode::Right => {
+            app.rotate_config.cycle_angle(true);
+        }
+
+        KeyCode::Left => {
+            app.rotate_config.cycle_angle(false);
+        }
+
+        KeyCode::Up if app.rotate_config.selected_rule > 0 => {
+            app.rotate_config.selected_rule -= 1;
+        }
+
+        KeyCode::Down if app.rotate_config.selected_rule + 1 < app.rotate_config.rules.len() => {
+            app.rotate_config.selected_rule += 1;
+        }
+
+        KeyCode::Char('n') | KeyCode::Char('N') => {
+            app.rotate_config.add_rule();
+            app.rotate_config.editing_pages = true;
+        }
+
+        KeyCode::Char('x') | KeyCode::Char('X') | KeyCode::Delete => {
+            app.rotate_config.remove_rule();
+        }
+
+        KeyCode::Char('g') | KeyCode::Char('G') => {
+            open_page_grid(app);
+        }
+
+        KeyCode::Tab => {
+            app.rotate_config.editing_output = true;
+        }
+
+        KeyCode::Char('d') | KeyCode::Char('D') => {
+            app.output_location.editing = true;
+        }
+
+        KeyCode::Enter => match rotate_operation(app) {
+            Ok(operation) if confirm_run(app, &operation) => perform_rotate(app, operation),
+            Ok(_) => {}
+            Err(e) => app.notify_error(e),
+        },
+
+        KeyCode::Char('a') | KeyCode::Char('A') => match rotate_operation(app) {
+            Ok(operation) => queue_operation(app, operation),
+            Err(e) => app.notify_error(e),
+        },
+
+        KeyCode::Esc => app.leave_config_screen(),
+
+        _ => {}
+    }
+}
+
+/**
+ * Build the rotate operation from the configuration, checking that it can run.
+ * Empty page lists are kept empty so that they still mean "every page" when queued.
+ * @param app The application state.
+ * @returns The operation, or the error to show to the user.
+ */
+fn rotate_operation(app: &App) -> Result<Operation, String> {
+    let Some(input) = app.selected_files().first() else {
+        return Err(tr!("No file selected").to_string());
+    };
+    let output = app.output_name(&app.rotate_config.output_filename, DEFAULT_ROTATE_OUTPUT);
+    validate_output_name(&output).map_err(|e| e.to_string())?;
+    validate_output_directory(&app.output_location.directory).map_err(|e| e.to_string())?;
+    let rules = rotation_rules(app)?;
+
+    Ok(Operation::Rotate {
+        input: input.clone(),
+        output: app.resolve_output(&output),
+        rules,
+    })
+}
+
+/**
+ * Turn the typed rules into rotation rules, numbering the rule in errors when there are several.
+ * @param app The application state.
+ * @returns The rules, with an empty page list for rules left empty.
+ */
+fn rotation_rules(app: &App) -> Result<Vec<RotationRule>, String> {
+    let several = app.rotate_config.rules.len() > 1;
+    app.rotate_config
+        .rules
+        .iter()
+        .enumerate()
+        .map(|(index, rule)| {
+            let pages = if rule.pages.is_empty() {
+                Vec::new()
+            } else {
+                validate_page_ranges_in_document(&rule.pages, app.first_file_page_count()).map_err(
+                    |e| {
+                        if several {
+                            tr!("Rule {rule}: {error}", rule = index + 1, error = e)
+                        } else {
+                            e.to_string()
+                        }
+                    },
+                )?
+            };
+            Ok(RotationRule {
+                pages,
+                angle: rule.angle,
+            })
+        })
+        .collect()
+}
+
+/**
+ * Rules the rotation applies, with empty page lists replaced by every page.
+ */
+fn rules_to_apply(app: &App) -> Result<Vec<RotationRule>, String> {
+    let mut rules = rotation_rules(app)?;
+    for rule in rules.iter_mut().filter(|rule| rule.pages.is_empty()) {
+        rule.pages = match app.first_file_page_count() {
+            Some(count) => (1..=count as u32).collect(),
+            None => return Err(tr!("Cannot read the page count of the document").to_string()),
+        };
+    }
+    Ok(rules)
+}
+
+/**
+ * Perform the PDF page rotation using the selected file, rules and output filename, on a worker
+ * thread while the processing screen is shown.
+ * The result screen then shows success or error messages.
+ * @param app The application state.
+ * @param operation The validated rotate operation.
+ */
+pub fn perform_rotate(app: &mut App, operation: Operation) {
+    if let Err(e) = rules_to_apply(app) {
+        app.set_outputs(Vec::new());
+        app.set_error(e);
+        app.current_screen = CurrentScreen::Result;
+        return;
+    }
+
+    start_operation(
+        app,
+        operation,
+        |app, outputs| {
+            // The settings are left alone while the operation runs, so the rules still apply
+            let rules = rules_to_apply(app).unwrap_or_default();
+            let message = match rules.as_slice() {
+                [rule] => tr!(
+                    "✅ Successfully rotated {count} pages of '{input}' by {angle}° and saved to '{output}'",
+                    count = rule.pages.len(),
+                    input = app.selected_files()[0],
+                    angle = rule.angle,
+                    output = outputs[0]
+                ),
+                _ => {
+                    let mut pages: Vec<u32> =
+                        rules.iter().flat_map(|rule| rule.pages.clone()).collect();
+                    pages.sort_unstable();
+                    pages.dedup();
+                    tr!(
+                        "✅ Successfully rotated {count} pages of '{input}' with {rules} rules and saved to '{output}'",
+                        count = pages.len(),
+                        input = app.selected_files()[0],
+                        rules = rules.len(),
+                        output = outputs[0]
+                    )
+                }
+            };
+            app.set_success(message);
+            app.set_outputs(outputs);
+        },
+        |e| tr!("Failed to rotate pages: {error}", error = e),
+    );
+}
@@ -1,10 +1,18 @@
+use crate::i18n::tr;
+use crate::pdf::operation::Operation;
 use crate::tui::app::App;
-use crate::tui::state::CurrentScreen;
+use crate::tui::handlers::output_location::{confirm_run, handle_output_directory_input};
+use crate::tui::handlers::processing::start_operation;
+use crate::tui::handlers::queue::queue_operation;
+use crate::tui::state::config_state::{NAMED_SEGMENTS_EXAMPLE, PAGE_SEGMENTS_EXAMPLE};
+use crate::tui::utils::{
+    validate_output_directory, validate_output_name, validate_split_in_document,
+};
 use crossterm::event::KeyCode;
 
 pub fn handle_split_config_input(key: KeyCode, app: &mut App) {
-    if app.ui_state.get_error_message().is_some() && key != KeyCode::Esc {
-        app.ui_state.clear_message();
+    if app.output_location.editing {
+        handle_output_directory_input(key, app);
         return;
     }
 
@@ -19,12 +27,12 @@ pub fn handle_split_config_input(key: KeyCode, app: &mut App) {
             KeyCode::Enter | KeyCode::Tab => {
                 app.split_config.editing_segments = false;
                 if app.split_config.segments.is_empty() {
-                    if app.split_config.use_named_segments {
-                        app.split_config.segments =
-                            "intro:1-3,chapter1:4-10,conclusion:11".to_string();
+                    app.split_config.segments = if app.split_config.use_named_segments {
+                        NAMED_SEGMENTS_EXAMPLE
                     } else {
-                        app.split_config.segments = "1-3,5,7-9".to_string();
+                        PAGE_SEGMENTS_EXAMPLE
                     }
+                    .to_string();
                 }
             }
             KeyCode::Esc => {
@@ -45,9 +53,6 @@ pub fn handle_split_config_input(key: KeyCode, app: &mut App) {
             }
             KeyCode::Enter | KeyCode::Tab => {
                 app.split_config.editing_prefix = false;
-                if app.split_config.output_prefix.is_empty() {
-                    app.split_config.output_prefix = "split_output".to_string();
-                }
             }
             KeyCode::Esc => {
                 app.split_config.editing_prefix = false;
@@ -62,59 +67,82 @@ pub fn handle_split_config_input(key: KeyCode, app: &mut App) {
             app.split_config.editing_segments = true;
         }
         KeyCode::Char(' ') => {
-            app.split_config.use_named_segments = !app.split_config.use_named_segments;
-            app.split_config.segments.clear();
+            app.split_config.toggle_named_segments();
+        }
+        KeyCode::Char('o') | KeyCode::Char('O') => {
+            app.split_config.allow_overlaps = !app.split_config.allow_overlaps;
         }
         KeyCode::Char('p') | KeyCode::Char('P') => {
             app.split_config.editing_prefix = true;
         }
-        KeyCode::Enter => {
-            if app.selected_files().is_empty() {
-                app.set_error("No file selected".to_string());
-            } else if app.split_config.segments.is_empty() {
-                app.set_error("Please specify page segments".to_string());
-            } else if app.split_config.output_prefix.is_empty() {
-                app.set_error("Output prefix cannot be empty".to_string());
-            } else {
-                perform_split(app);
-            }
-        }
-        KeyCode::Esc => {
-            app.current_screen = CurrentScreen::FileSelection;
+        KeyCode::Char('d') | KeyCode::Char('D') => {
+            app.output_location.editing = true;
         }
+        KeyCode::Enter => match split_operation(app) {
+            Ok(operation) if confirm_run(app, &operation) => perform_split(app, operation),
+            Ok(_) => {}
+            Err(e) => app.notify_error(e),
+        },
+        KeyCode::Char('a') | KeyCode::Char('A') => match split_operation(app) {
+            Ok(operation) => queue_operation(app, operation),
+            Err(e) => app.notify_error(e),
+        },
+        KeyCode::Esc => app.leave_config_screen(),
         _ => {}
     }
 }
 
-pub fn perform_split(app: &mut App) {
-    use crate::pdf;
+/**
+ * Build the split operation from the configuration, checking that it can run.
+ * @param app The application state.
+ * @returns The operation, or the error to show to the user.
+ */
+fn split_operation(app: &App) -> Result<Operation, String> {
+    let Some(input) = app.selected_files().first() else {
+        return Err(tr!("No file selected").to_string());
+    };
+    if app.split_config.segments.is_empty() {
+        return Err(tr!("Please specify page segments").to_string());
+    }
+    validate_split_in_document(
+        &app.split_config.segments,
+        app.split_config.use_named_segments,
+        app.first_file_page_count(),
+        app.split_config.allow_overlaps,
+    )
+    .map_err(|e| e.to_string())?;
+    let output_prefix = app.split_output_prefix();
+    validate_output_name(&output_prefix).map_err(|e| e.to_string())?;
+    validate_output_directory(&app.output_location.directory).map_err(|e| e.to_string())?;
 
-    let result = if app.split_config.use_named_segments {
-        pdf::split::split_pdfs_named(
-            &app.selected_files()[0],
-            &app.split_config.output_prefix,
-            &app.split_config.segments,
-        )
-    } else {
-        pdf::split::split_pdfs(
-            &app.selected_files()[0],
-            &app.split_config.output_prefix,
-            &app.split_config.segments,
-        )
+    let operation = Operation::Split {
+        input: input.clone(),
+        output_prefix: app.resolve_output(&output_prefix),
+        segments: app.split_config.segments.clone(),
+        named: app.split_config.use_named_segments,
     };
+    operation.outputs().map_err(|e| e.to_string())?;
+    Ok(operation)
+}
 
-    match result {
-        Ok(output_files) => {
-            app.set_success(format!(
-                "✅ Successfully split PDF into {} files: {}",
-                output_files.len(),
-                output_files.join(", ")
+/**
+ * Perform the PDF split using the selected file and segments, on a worker thread while the
+ * processing screen is shown.
+ * The result screen then shows success or error messages.
+ * @param app The application state.
+ * @param operation The validated split operation.
+ */
+pub fn perform_split(app: &mut App, operation: Operation) {
+    start_operation(
+        app,
+        operation,
+        |app, outputs| {
+            app.set_success(tr!(
+                "✅ Successfully split PDF into {count} files",
+                count = outputs.len()
             ));
-            app.current_screen = CurrentScreen::Result;
-        }
-        Err(e) => {
-            app.set_error(format!("Failed to split PDF: {}", e));
-            app.current_screen = CurrentScreen::Result;
-        }
-    }
+            app.set_outputs(outputs);
+        },
+        |e| tr!("Failed to split PDF: {error}", error = e),
+    );
 }
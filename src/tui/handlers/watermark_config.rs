@@ -0,0 +1,195 @@
+use crate::i18n::tr;
+use crate::pdf::operation::Operation;
+use crate::pdf::output::DEFAULT_WATERMARK_OUTPUT;
+use crate::pdf::watermark::WatermarkSource;
+use crate::tui::app::App;
+use crate::tui::handlers::output_location::{confirm_run, handle_output_directory_input};
+use crate::tui::handlers::page_grid::open_page_grid;
+use crate::tui::handlers::processing::start_operation;
+use crate::tui::handlers::queue::queue_operation;
+use crate::tui::utils::{
+    validate_image_input, validate_output_directory, validate_output_name,
+    validate_page_ranges_in_document,
+};
+use crossterm::event::KeyCode;
+
+/**
+ * Handle input in the watermark configuration screen.
+ * Allows typing the text or choosing an image, the position, opacity and pages,
+ * editing the output filename, and stamping the watermark. An empty page list stamps every page.
+ * @param key The key event.
+ * @param app The application state.
+ */
+pub fn handle_watermark_config_input(key: KeyCode, app: &mut App) {
+    if app.output_location.editing {
+        handle_output_directory_input(key, app);
+        return;
+    }
+
+    let config = &mut app.watermark_config;
+    let field = if config.editing_text {
+        Some(&mut config.text)
+    } else if config.editing_image {
+        Some(&mut config.image_path)
+    } else if config.editing_pages {
+        Some(&mut config.pages)
+    } else {
+        None
+    };
+    if let Some(field) = field {
+        match key {
+            KeyCode::Char(c) => {
+                field.push(c);
+            }
+            KeyCode::Backspace => {
+                field.pop();
+            }
+            // Errors are shown on the fields themselves as the user types
+            KeyCode::Enter | KeyCode::Tab | KeyCode::Esc => {
+                config.editing_text = false;
+                config.editing_image = false;
+                config.editing_pages = false;
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    if config.editing_output {
+        match key {
+            KeyCode::Char(c) => {
+                config.output_filename.push(c);
+            }
+            KeyCode::Backspace => {
+                config.output_filename.pop();
+            }
+            KeyCode::Enter | KeyCode::Tab => {
+                config.editing_output = false;
+
+                if !config.output_filename.ends_with(".pdf") && !config.output_filename.is_empty() {
+                    config.output_filename.push_str(".pdf");
+                }
+            }
+            KeyCode::Esc => {
+                config.editing_output = false;
+            }
+            _ => {}
+        }
+        return;
+    }
+
+    match key {
+        KeyCode::Char('t') | KeyCode::Char('T') => {
+            config.use_image = false;
+            config.editing_text = true;
+        }
+
+        KeyCode::Char('i') | KeyCode::Char('I') => {
+            config.use_image = true;
+            config.editing_image = true;
+        }
+
+        KeyCode::Char('o') | KeyCode::Char('O') | KeyCode::Char(' ') => {
+            config.cycle_position();
+        }
+
+        KeyCode::Left => config.adjust_opacity(false),
+        KeyCode::Right => config.adjust_opacity(true),
+
+        KeyCode::Char('p') | KeyCode::Char('P') => {
+            config.editing_pages = true;
+        }
+
+        KeyCode::Char('g') | KeyCode::Char('G') => {
+            open_page_grid(app);
+        }
+
+        KeyCode::Tab => {
+            config.editing_output = true;
+        }
+
+        KeyCode::Char('d') | KeyCode::Char('D') => {
+            app.output_location.editing = true;
+        }
+
+        KeyCode::Enter => match watermark_operation(app) {
+            Ok(operation) if confirm_run(app, &operation) => perform_watermark(app, operation),
+            Ok(_) => {}
+            Err(e) => app.notify_error(e),
+        },
+
+        KeyCode::Char('a') | KeyCode::Char('A') => match watermark_operation(app) {
+            Ok(operation) => queue_operation(app, operation),
+            Err(e) => app.notify_error(e),
+        },
+
+        KeyCode::Esc => app.leave_config_screen(),
+
+        _ => {}
+    }
+}
+
+/**
+ * Build the watermark operation from the configuration, checking that it can run.
+ * An empty page list is kept empty so that it still means "every page" when queued.
+ * @param app The application state.
+ * @returns The operation, or the error to show to the user.
+ */
+fn watermark_operation(app: &App) -> Result<Operation, String> {
+    let config = &app.watermark_config;
+    let Some(input) = app.selected_files().first() else {
+        return Err(tr!("No file selected").to_string());
+    };
+    let source = if config.use_image {
+        WatermarkSource::Image(validate_image_input(&config.image_path).map_err(|e| e.to_string())?)
+    } else if config.text.trim().is_empty() {
+        return Err(tr!("Please type the watermark text").to_string());
+    } else {
+        WatermarkSource::Text(config.text.clone())
+    };
+    let output = app.output_name(&config.output_filename, DEFAULT_WATERMARK_OUTPUT);
+    validate_output_name(&output).map_err(|e| e.to_string())?;
+    validate_output_directory(&app.output_location.directory).map_err(|e| e.to_string())?;
+    let pages = if config.pages.is_empty() {
+        Vec::new()
+    } else {
+        validate_page_ranges_in_document(&config.pages, app.first_file_page_count())
+            .map_err(|e| e.to_string())?
+    };
+
+    Ok(Operation::Watermark {
+        input: input.clone(),
+        output: app.resolve_output(&output),
+        pages,
+        options: config.options(source),
+    })
+}
+
+/**
+ * Stamp the configured watermark on the selected file, on a worker thread while the processing
+ * screen is shown.
+ * The result screen then shows success or error messages.
+ * @param app The application state.
+ * @param operation The validated watermark operation.
+ */
+pub fn perform_watermark(app: &mut App, operation: Operation) {
+    start_operation(
+        app,
+        operation,
+        |app, outputs| {
+            let pages = if app.watermark_config.pages.is_empty() {
+                tr!("all pages").to_string()
+            } else {
+                tr!("pages {pages}", pages = app.watermark_config.pages)
+            };
+            app.set_success(tr!(
+                "✅ Successfully stamped {pages} of '{input}' and saved to '{output}'",
+                pages = pages,
+                input = app.selected_files()[0],
+                output = outputs[0]
+            ));
+            app.set_outputs(outputs);
+        },
+        |e| tr!("Failed to add the watermark: {error}", error = e),
+    );
+}
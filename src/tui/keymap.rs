@@ -0,0 +1,450 @@
+use crate::i18n::translate;
+use crate::tui::state::CurrentScreen;
+
+/// A key (or group of keys) and what it does on a screen.
+/// Both are English messages, translated when displayed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyBinding {
+    pub keys: &'static str,
+    pub action: &'static str,
+}
+
+const fn bind(keys: &'static str, action: &'static str) -> KeyBinding {
+    KeyBinding { keys, action }
+}
+
+/// Keys available on every screen, except while typing in a field.
+pub const GLOBAL_BINDINGS: &[KeyBinding] = &[
+    bind("Ctrl+K", "Command palette"),
+    bind("?", "Keys of the current screen"),
+    bind("L", "Log viewer"),
+    bind("Ctrl+H / H", "Back to the main menu"),
+    bind("Ctrl+C", "Quit (asks for confirmation)"),
+];
+
+/// Keys available while typing in an input field.
+pub const TEXT_FIELD_BINDINGS: &[KeyBinding] = &[
+    bind("Type / Backspace", "Edit the field"),
+    bind("Enter / Tab", "Confirm"),
+    bind("Esc", "Stop editing"),
+];
+
+/// Keys available while editing the output directory.
+pub const OUTPUT_DIRECTORY_BINDINGS: &[KeyBinding] = &[
+    bind("Type / Backspace", "Edit the directory (~ is expanded)"),
+    bind("Tab / Shift+Tab", "Complete, press again to cycle"),
+    bind("Enter", "Save the directory (it must exist)"),
+    bind("Esc", "Cancel"),
+];
+
+const MAIN_BINDINGS: &[KeyBinding] = &[
+    bind("↑ / ↓", "Navigate the menu"),
+    bind("Enter", "Select the entry"),
+    bind("1-9", "Select an entry directly"),
+    bind("a", "About: version and build details"),
+    bind("q / Esc", "Quit"),
+];
+
+const FILE_SELECTION_BINDINGS: &[KeyBinding] = &[
+    bind("↑ / ↓", "Select a file"),
+    bind("PgUp / PgDn", "Move by ten items"),
+    bind("Home / End", "First / last item"),
+    bind("Tab", "Type the path of a file to add"),
+    bind("Tab + folder", "Add all the PDFs of a folder (merge)"),
+    bind("b", "Browse folders to pick a file"),
+    bind("e", "Edit the path of the selected file"),
+    bind("p", "Preview the selected file"),
+    bind("Backspace", "Remove the selected file"),
+    bind("Alt+↑ / Alt+↓", "Reorder files"),
+    bind(
+        "Alt+Home / Alt+End",
+        "Move the selected file to the top or bottom",
+    ),
+    bind("Enter / →", "Continue to the configuration"),
+    bind("Esc", "Back to the main menu"),
+];
+
+const FILE_BROWSER_BINDINGS: &[KeyBinding] = &[
+    bind("↑ / ↓", "Select an entry"),
+    bind("PgUp / PgDn", "Move by ten items"),
+    bind("Home / End", "First / last item"),
+    bind("Enter / →", "Open the folder, or add the PDF"),
+    bind("Backspace / ←", "Go up to the parent folder"),
+    bind("S", "Sort by name, date modified or size"),
+    bind("H", "Show or hide hidden entries"),
+    bind("A", "List all files, or PDFs only"),
+    bind("F", "Pin or unpin the current folder"),
+    bind("W", "Make the current folder the working directory"),
+    bind("1-9", "Go to a pinned folder"),
+    bind("Tab", "Switch between the files and the pinned folders"),
+    bind("X / Del", "Unpin the selected folder (pinned folders)"),
+    bind("Esc", "Back to the file selection"),
+];
+
+const MERGE_CONFIG_BINDINGS: &[KeyBinding] = &[
+    bind("↑ / ↓", "Move the selected file up or down"),
+    bind("Home / End", "Move the selected file to the top or bottom"),
+    bind("Tab", "Edit the output filename"),
+    bind("D", "Edit the output directory"),
+    bind("Enter", "Merge"),
+    bind("A", "Add to the operation queue"),
+    bind("Esc", "Back to the file selection"),
+];
+
+const DELETE_CONFIG_BINDINGS: &[KeyBinding] = &[
+    bind("P", "Edit the pages to delete"),
+    bind("G", "Pick pages in the page grid"),
+    bind("Tab", "Edit the output filename"),
+    bind("D", "Edit the output directory"),
+    bind("Enter", "Delete the pages"),
+    bind("A", "Add to the operation queue"),
+    bind("Esc", "Back to the file selection"),
+];
+
+const SPLIT_CONFIG_BINDINGS: &[KeyBinding] = &[
+    bind("S", "Edit the page segments"),
+    bind("Space", "Toggle named segments"),
+    bind("O", "Allow or forbid pages in several segments"),
+    bind("P", "Edit the output prefix"),
+    bind("D", "Edit the output directory"),
+    bind("Enter", "Split"),
+    bind("A", "Add to the operation queue"),
+    bind("Esc", "Back to the file selection"),
+];
+
+const ROTATE_CONFIG_BINDINGS: &[KeyBinding] = &[
+    bind("↑ / ↓", "Select a rule"),
+    bind("P", "Edit the pages of the rule (empty: every page)"),
+    bind("← / →", "Change the angle of the rule (90°, 180°, 270°)"),
+    bind("R / Space", "Cycle the angle of the rule"),
+    bind("N", "Add a rule"),
+    bind("X / Del", "Remove the selected rule"),
+    bind("G", "Pick the pages of the rule in the page grid"),
+    bind("Tab", "Edit the output filename"),
+    bind("D", "Edit the output directory"),
+    bind("Enter", "Rotate"),
+    bind("A", "Add to the operation queue"),
+    bind("Esc", "Back to the file selection"),
+];
+
+const EXTRACT_CONFIG_BINDINGS: &[KeyBinding] = &[
+    bind(
+        "P",
+        "Edit the pages to extract (written in the order typed)",
+    ),
+    bind("G", "Pick pages in the page grid"),
+    bind("O", "Reorder or remove pages in the page list"),
+    bind("N", "Keep or leave out the annotations"),
+    bind("Tab", "Edit the output filename"),
+    bind("D", "Edit the output directory"),
+    bind("Enter", "Extract"),
+    bind("A", "Add to the operation queue"),
+    bind("Esc", "Back to the file selection"),
+];
+
+const COMPRESS_CONFIG_BINDINGS: &[KeyBinding] = &[
+    bind("S", "Toggle stream recompression"),
+    bind("I / ← / →", "Cycle the image resolution"),
+    bind("M", "Toggle metadata stripping"),
+    bind("F", "Toggle annotation flattening"),
+    bind("Tab", "Edit the output filename"),
+    bind("D", "Edit the output directory"),
+    bind("Enter", "Compress"),
+    bind("A", "Add to the operation queue"),
+    bind("Esc", "Back to the file selection"),
+];
+
+const WATERMARK_CONFIG_BINDINGS: &[KeyBinding] = &[
+    bind("T", "Edit the watermark text"),
+    bind("I", "Edit the path of a JPEG or PNG image to stamp"),
+    bind("O / Space", "Cycle the position"),
+    bind("← / →", "Lower / raise the opacity"),
+    bind("P", "Edit the pages to stamp"),
+    bind("G", "Pick pages in the page grid"),
+    bind("Tab", "Edit the output filename"),
+    bind("D", "Edit the output directory"),
+    bind("Enter", "Add the watermark"),
+    bind("A", "Add to the operation queue"),
+    bind("Esc", "Back to the file selection"),
+];
+
+const PAGE_GRID_BINDINGS: &[KeyBinding] = &[
+    bind("Arrows", "Move between pages"),
+    bind("Home / End", "First / last page"),
+    bind("Space", "Toggle the current page"),
+    bind("Shift+Arrows", "Select a range from the last toggled page"),
+    bind("A", "Select or clear every page"),
+    bind("Enter", "Apply the selection"),
+    bind("Esc", "Cancel"),
+];
+
+const PAGE_ORDER_BINDINGS: &[KeyBinding] = &[
+    bind("↑ / ↓", "Select a page"),
+    bind("PgUp / PgDn", "Move by ten items"),
+    bind("Alt+↑ / Alt+↓", "Move the page up or down"),
+    bind("Alt+Home / Alt+End", "Move the page to the top or bottom"),
+    bind("D / Del", "Remove or restore the page"),
+    bind("Enter", "Apply the order"),
+    bind("Esc", "Cancel"),
+];
+
+const QUEUE_BINDINGS: &[KeyBinding] = &[
+    bind("↑ / ↓", "Select a step"),
+    bind("X / Del", "Remove the selected step"),
+    bind("C", "Clear the queue, or cancel a run"),
+    bind("Enter / R", "Run every step"),
+    bind("F", "Run again the failed and skipped steps"),
+    bind("Esc", "Cancel a run, or back to the main menu"),
+];
+
+const PROCESSING_BINDINGS: &[KeyBinding] = &[bind(
+    "Esc / C",
+    "Cancel the operation, deleting the files it had started writing",
+)];
+
+const RESULT_BINDINGS: &[KeyBinding] = &[
+    bind("↑ / ↓", "Select an output file"),
+    bind("O", "Open it in the system viewer"),
+    bind("F", "Open its folder in the file manager"),
+    bind(
+        "C",
+        "Copy its path, or the details of a failure, to the clipboard",
+    ),
+    bind("N", "Run the same operation on it"),
+    bind(
+        "1-7",
+        "Merge, delete, split, rotate, extract, compress or watermark it",
+    ),
+    bind("D", "Show or hide the details of a failure"),
+    bind("Enter / Esc", "Back to the main menu"),
+];
+
+const LOGS_BINDINGS: &[KeyBinding] = &[
+    bind("↑ / ↓", "Scroll back / forward"),
+    bind("Home / End", "Oldest / newest line"),
+    bind("F", "Cycle the level filter (error, warn, info, debug)"),
+    bind("C", "Clear the log"),
+    bind("L / Esc", "Back to the previous screen"),
+];
+
+const HELP_BINDINGS: &[KeyBinding] = &[
+    bind("↑ / ↓", "Scroll"),
+    bind("PgUp / PgDn", "Scroll by a page"),
+    bind("Tab / Shift+Tab", "Next / previous section"),
+    bind("Home / End", "Top / bottom"),
+    bind("Esc", "Back to the main menu"),
+];
+
+const ABOUT_BINDINGS: &[KeyBinding] = &[
+    bind("C", "Copy the details for a bug report"),
+    bind("Esc", "Back to the main menu"),
+];
+
+const ONBOARDING_BINDINGS: &[KeyBinding] = &[
+    bind("→ / Enter", "Next page"),
+    bind("← / Backspace", "Previous page"),
+    bind("Esc", "Skip the tour"),
+];
+
+const EXITING_BINDINGS: &[KeyBinding] = &[bind("Y / Ctrl+C", "Quit"), bind("N / Esc", "Stay")];
+
+/// A section of the Help screen: what a part of the program does and the keys of its screens.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HelpSection {
+    pub title: &'static str,
+    pub summary: &'static str,
+    pub screens: &'static [CurrentScreen],
+}
+
+const fn section(
+    title: &'static str,
+    summary: &'static str,
+    screens: &'static [CurrentScreen],
+) -> HelpSection {
+    HelpSection {
+        title,
+        summary,
+        screens,
+    }
+}
+
+/// Sections of the Help screen, in order, after the keys available everywhere.
+pub const HELP_SECTIONS: &[HelpSection] = &[
+    section(
+        "🧭 Getting started",
+        "Pick an operation, add its input files, then configure and run it.",
+        &[
+            CurrentScreen::Onboarding,
+            CurrentScreen::Main,
+            CurrentScreen::FileSelection,
+        ],
+    ),
+    section(
+        "📂 File browser",
+        "Browse folders to pick PDFs, and pin the folders you use often (saved in the config file).",
+        &[CurrentScreen::FileBrowser],
+    ),
+    section(
+        "🔗 Merge PDFs",
+        "Combine several PDF files into one, in the order of the list.",
+        &[CurrentScreen::MergeConfig],
+    ),
+    section(
+        "✂️  Delete pages",
+        "Remove pages from a PDF and save the rest as a new file.",
+        &[CurrentScreen::DeleteConfig],
+    ),
+    section(
+        "🔪 Split pages",
+        "Cut a PDF into several files, one per page segment (optionally named).",
+        &[CurrentScreen::SplitConfig],
+    ),
+    section(
+        "🔄 Rotate pages",
+        "Turn some or all pages by 90°, 180° or 270°.",
+        &[CurrentScreen::RotateConfig],
+    ),
+    section(
+        "📑 Extract pages",
+        "Copy selected pages into a new PDF.",
+        &[CurrentScreen::ExtractConfig],
+    ),
+    section(
+        "🗜️  Compress PDF",
+        "Make a PDF smaller: recompress its streams, downsample its images and strip its metadata.",
+        &[CurrentScreen::CompressConfig],
+    ),
+    section(
+        "💧 Watermark",
+        "Stamp a text or an image on some or all pages, with a position and an opacity.",
+        &[CurrentScreen::WatermarkConfig],
+    ),
+    section(
+        "🔢 Page grid",
+        "Pick the pages to delete, rotate, extract or watermark instead of typing them.",
+        &[CurrentScreen::PageGrid],
+    ),
+    section(
+        "🔀 Page order",
+        "Reorder the pages to extract and leave some out, seeing each page's size.",
+        &[CurrentScreen::PageOrder],
+    ),
+    section(
+        "📋 Operation queue",
+        "Queue configured operations with A, then run them one after the other.",
+        &[CurrentScreen::Queue],
+    ),
+    section(
+        "✅ Results",
+        "Open, copy or keep working on the files an operation wrote.",
+        &[CurrentScreen::Result],
+    ),
+    section(
+        "📜 Logs",
+        "See what was loaded and saved, and the full cause of a failure.",
+        &[CurrentScreen::Logs],
+    ),
+    section(
+        "ℹ️  About",
+        "Version, build and configuration file details, to include when reporting a bug.",
+        &[CurrentScreen::About],
+    ),
+];
+
+/// Keys handled by a screen.
+pub fn screen_bindings(screen: &CurrentScreen) -> &'static [KeyBinding] {
+    match screen {
+        CurrentScreen::Main => MAIN_BINDINGS,
+        CurrentScreen::FileSelection => FILE_SELECTION_BINDINGS,
+        CurrentScreen::FileBrowser => FILE_BROWSER_BINDINGS,
+        CurrentScreen::MergeConfig => MERGE_CONFIG_BINDINGS,
+        CurrentScreen::DeleteConfig => DELETE_CONFIG_BINDINGS,
+        CurrentScreen::SplitConfig => SPLIT_CONFIG_BINDINGS,
+        CurrentScreen::RotateConfig => ROTATE_CONFIG_BINDINGS,
+        CurrentScreen::ExtractConfig => EXTRACT_CONFIG_BINDINGS,
+        CurrentScreen::CompressConfig => COMPRESS_CONFIG_BINDINGS,
+        CurrentScreen::WatermarkConfig => WATERMARK_CONFIG_BINDINGS,
+        CurrentScreen::PageGrid => PAGE_GRID_BINDINGS,
+        CurrentScreen::PageOrder => PAGE_ORDER_BINDINGS,
+        CurrentScreen::Processing => PROCESSING_BINDINGS,
+        CurrentScreen::Queue => QUEUE_BINDINGS,
+        CurrentScreen::Result => RESULT_BINDINGS,
+        CurrentScreen::Logs => LOGS_BINDINGS,
+        CurrentScreen::Help => HELP_BINDINGS,
+        CurrentScreen::Onboarding => ONBOARDING_BINDINGS,
+        CurrentScreen::About => ABOUT_BINDINGS,
+        CurrentScreen::Exiting => EXITING_BINDINGS,
+    }
+}
+
+/// Name of a screen, as used in the cheat-sheet and Help titles, in the current language.
+pub fn screen_name(screen: &CurrentScreen) -> &'static str {
+    translate(match screen {
+        CurrentScreen::Main => "Main menu",
+        CurrentScreen::FileSelection => "File selection",
+        CurrentScreen::FileBrowser => "File browser",
+        CurrentScreen::MergeConfig => "Merge configuration",
+        CurrentScreen::DeleteConfig => "Delete configuration",
+        CurrentScreen::SplitConfig => "Split configuration",
+        CurrentScreen::RotateConfig => "Rotate configuration",
+        CurrentScreen::ExtractConfig => "Extract configuration",
+        CurrentScreen::CompressConfig => "Compress configuration",
+        CurrentScreen::WatermarkConfig => "Watermark configuration",
+        CurrentScreen::PageGrid => "Page grid",
+        CurrentScreen::PageOrder => "Page order",
+        CurrentScreen::Processing => "Processing",
+        CurrentScreen::Queue => "Operation queue",
+        CurrentScreen::Result => "Result",
+        CurrentScreen::Logs => "Log viewer",
+        CurrentScreen::Help => "Help",
+        CurrentScreen::Onboarding => "Welcome tour",
+        CurrentScreen::About => "About",
+        CurrentScreen::Exiting => "Exit confirmation",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_screen_bindings() {
+        for screen in HELP_SECTIONS.iter().flat_map(|section| section.screens) {
+            let bindings = screen_bindings(screen);
+            assert!(!bindings.is_empty(), "{}", screen_name(screen));
+            // Every screen offers a way back
+            assert!(
+                bindings.iter().any(|b| b.keys.contains("Esc")),
+                "{}",
+                screen_name(screen)
+            );
+        }
+    }
+
+    #[test]
+    fn test_key_reference_is_translated() {
+        use crate::i18n::{Locale, has_translation};
+
+        let mut bindings: Vec<&KeyBinding> = HELP_SECTIONS
+            .iter()
+            .flat_map(|section| section.screens)
+            .chain([&CurrentScreen::Help, &CurrentScreen::Exiting])
+            .flat_map(|screen| screen_bindings(screen))
+            .collect();
+        bindings.extend(GLOBAL_BINDINGS);
+        bindings.extend(TEXT_FIELD_BINDINGS);
+        bindings.extend(OUTPUT_DIRECTORY_BINDINGS);
+        for binding in bindings {
+            assert!(
+                has_translation(Locale::French, binding.action),
+                "{}",
+                binding.action
+            );
+        }
+
+        for section in HELP_SECTIONS {
+            assert!(has_translation(Locale::French, section.title));
+            assert!(has_translation(Locale::French, section.summary));
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::collections::VecDeque;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Number of log lines kept for the log viewer; older lines are dropped.
+pub const LOG_CAPACITY: usize = 1000;
+
+/// One captured log event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogLine {
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+    /// Time since the logger was installed.
+    pub elapsed: Duration,
+}
+
+/// Logger keeping the most recent events in memory, since the TUI owns the terminal
+/// and nothing can be printed while it runs.
+pub struct RingLogger {
+    lines: Mutex<VecDeque<LogLine>>,
+    capacity: usize,
+    started: Instant,
+}
+
+static LOGGER: LazyLock<RingLogger> = LazyLock::new(|| RingLogger::new(LOG_CAPACITY));
+
+impl RingLogger {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn push(&self, level: Level, target: &str, message: String) {
+        let mut lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        if lines.len() == self.capacity {
+            lines.pop_front();
+        }
+        lines.push_back(LogLine {
+            level,
+            target: target.to_string(),
+            message,
+            elapsed: self.started.elapsed(),
+        });
+    }
+
+    /// Captured lines at `min_level` or more severe, oldest first.
+    pub fn lines(&self, min_level: Level) -> Vec<LogLine> {
+        let lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        lines
+            .iter()
+            .filter(|line| line.level <= min_level)
+            .cloned()
+            .collect()
+    }
+
+    pub fn clear(&self) {
+        self.lines.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
+
+impl Log for RingLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Debug
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            self.push(record.level(), record.target(), record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Route `log` events to the in-memory buffer. Does nothing if a logger is already installed.
+pub fn init() {
+    if log::set_logger(&*LOGGER).is_ok() {
+        log::set_max_level(LevelFilter::Debug);
+    }
+}
+
+/// Recent log lines at `min_level` or more severe, oldest first.
+pub fn recent_lines(min_level: Level) -> Vec<LogLine> {
+    LOGGER.lines(min_level)
+}
+
+pub fn clear() {
+    LOGGER.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_logger() {
+        let logger = RingLogger::new(3);
+        logger.push(Level::Debug, "pdf", "loading".to_string());
+        logger.push(Level::Info, "pdf", "saved".to_string());
+        logger.push(Level::Error, "tui", "failed".to_string());
+        logger.push(Level::Warn, "tui", "retry".to_string());
+
+        // The oldest line was dropped to stay within capacity
+        let all: Vec<String> = logger
+            .lines(Level::Trace)
+            .into_iter()
+            .map(|line| line.message)
+            .collect();
+        assert_eq!(all, vec!["saved", "failed", "retry"]);
+
+        let warnings = logger.lines(Level::Warn);
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings.iter().all(|line| line.level <= Level::Warn));
+
+        logger.clear();
+        assert!(logger.lines(Level::Trace).is_empty());
+    }
+}
@@ -1,43 +1,129 @@
 pub mod app;
+pub mod ascii;
 pub mod errors;
+pub mod graphics;
 pub mod handlers;
+pub mod keymap;
+pub mod log_buffer;
 pub mod state;
+pub mod system;
+pub mod terminal;
+pub mod theme;
 pub mod ui;
 pub mod utils;
 
-use anyhow::Result;
+use crate::config::{Config, config_path};
+use crate::i18n::tr;
+use crate::pdf::output::{OverwritePolicy, backup_in_place_from_config, backup_suffix_from_config};
+use crate::pdf::space::SpaceCheck;
+use anyhow::{Context, Result, anyhow};
 use app::App;
+use ascii::{ascii_mode_from_config, set_ascii_mode};
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{
+        self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        Event, KeyCode, KeyEvent,
+    },
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
-use ratatui::{Terminal, backend::CrosstermBackend};
+use graphics::GraphicsProtocol;
+use ratatui::{
+    Terminal,
+    backend::{Backend, CrosstermBackend},
+    buffer::Buffer,
+    layout::Rect,
+};
 use state::CurrentScreen;
+use state::browser_state::favorites_from_config;
+use state::queue_state::stop_on_error_from_config;
+use state::ui_state::confirm_discard_from_config;
 use std::io;
+use std::time::Duration;
+use terminal::{Signals, is_interrupt_key, restore_terminal};
+use theme::{Theme, set_theme};
 
 use handlers::*;
 
-pub fn run() -> Result<()> {
-    // Setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+/// Longest time the event loop waits for input before checking for signals.
+const SIGNAL_POLL_INTERVAL: Duration = Duration::from_millis(250);
 
-    // Create app state
-    let mut app = App::new();
-    let res = run_app(&mut terminal, &mut app);
+/// How often the selected files are checked to still exist.
+const EXISTENCE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
 
-    // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+/// Redraw interval while files are being validated, so the spinner keeps turning.
+const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+
+/**
+ * Run the TUI until the user quits.
+ * @param ascii Whether `--ascii` was given, forcing plain ASCII drawing.
+ * @returns Ok(()) once the terminal is restored.
+ * @throws anyhow::Error if the config file is invalid or the terminal cannot be set up.
+ */
+pub fn run(ascii: bool) -> Result<()> {
+    // Load the theme before touching the terminal, so config errors are printed normally
+    let config = Config::load()?;
+    let theme = Theme::from_config(&config)
+        .map_err(|e| anyhow!(e))
+        .context(tr!("Invalid theme in config file"))?;
+    set_theme(theme);
+    let ascii = ascii_mode_from_config(&config, ascii)
+        .map_err(|e| anyhow!(e))
+        .context(tr!("Invalid ascii setting in config file"))?;
+    set_ascii_mode(ascii);
+    let space_check = SpaceCheck::from_config(&config)
+        .map_err(|e| anyhow!(e))
+        .context(tr!("Invalid output size warning in config file"))?;
+    let overwrite_policy = OverwritePolicy::from_config(&config)
+        .map_err(|e| anyhow!(e))
+        .context(tr!("Invalid overwrite policy in config file"))?;
+    let backup_in_place = backup_in_place_from_config(&config)
+        .map_err(|e| anyhow!(e))
+        .context(tr!("Invalid backup setting in config file"))?;
+    let backup_suffix = backup_suffix_from_config(&config)
+        .map_err(|e| anyhow!(e))
+        .context(tr!("Invalid backup setting in config file"))?;
+    let queue_stop_on_error = stop_on_error_from_config(&config)
+        .map_err(|e| anyhow!(e))
+        .context(tr!("Invalid queue setting in config file"))?;
+    let confirm_discard = confirm_discard_from_config(&config)
+        .map_err(|e| anyhow!(e))
+        .context(tr!("Invalid confirm_discard setting in config file"))?;
+    log_buffer::init();
+
+    // Whatever happens from now on, the terminal must be given back in a usable state
+    terminal::install_panic_hook();
+    let signals = Signals::register()?;
+
+    enable_raw_mode()?;
+    let res = (|| {
+        execute!(
+            io::stdout(),
+            EnterAlternateScreen,
+            EnableMouseCapture,
+            EnableBracketedPaste
+        )?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+        let mut app = App::new();
+        app.space_check = space_check;
+        app.overwrite_policy = overwrite_policy;
+        app.backup_in_place = backup_in_place;
+        app.backup_suffix = backup_suffix;
+        app.queue.stop_on_error = queue_stop_on_error;
+        app.ui_state.confirm_discard = confirm_discard;
+        app.browser.favorites = favorites_from_config(&config);
+        app.config_path = config_path();
+        app.thumbnails.cell_size = graphics::cell_size();
+        // Without a config file this is the first run: introduce the program before the menu
+        if app.config_path.as_ref().is_some_and(|path| !path.exists()) {
+            onboarding::start_onboarding(&mut app);
+        }
+        let res = run_app(&mut terminal, &mut app, &signals);
+        // Quitting while an operation runs cancels it, once it has deleted what it wrote
+        app.cancel_and_wait();
+        res
+    })();
+    restore_terminal()?;
 
     if let Err(err) = res {
         println!("{err:?}");
@@ -46,46 +132,282 @@ pub fn run() -> Result<()> {
     Ok(())
 }
 
-fn run_app<B: ratatui::backend::Backend>(
+fn run_app<B: Backend + io::Write>(
     terminal: &mut Terminal<B>,
     app: &mut App,
+    signals: &Signals,
 ) -> io::Result<()> {
     loop {
-        terminal.draw(|f| ui::ui(f, app))?;
-
-        if let Event::Key(key) = event::read()? {
-            match app.current_screen {
-                CurrentScreen::Main => handle_main_input(key.code, app),
-                CurrentScreen::Exiting => match key.code {
-                    KeyCode::Char('y') | KeyCode::Char('Y') => return Ok(()),
-                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
-                        app.current_screen = CurrentScreen::Main;
+        if signals.should_terminate() {
+            return Ok(());
+        }
+        if signals.take_interrupt() {
+            app.request_exit();
+        }
+
+        app.ui_state.expire_toast();
+        app.poll_validations();
+        app.thumbnails.take_results();
+        if app.validation.existence_check_due(EXISTENCE_CHECK_INTERVAL) {
+            app.check_files_exist();
+        }
+        let drawn = terminal.draw(|f| ui::ui(f, app))?;
+        if app.thumbnails.protocol != GraphicsProtocol::None {
+            let drawn = drawn.buffer.clone();
+            draw_page_thumbnail(terminal, app, &drawn)?;
+        }
+
+        // Operations run on a worker thread: while one runs, keys can only cancel it (or quit
+        // once asked), and the screen is redrawn regularly so its progress stays visible
+        if app.is_busy() {
+            if event::poll(SPINNER_INTERVAL)?
+                && let Event::Key(key) = event::read()?
+            {
+                if app.current_screen == CurrentScreen::Exiting {
+                    if is_interrupt_key(&key) || handle_key_event(key, app) {
+                        return Ok(());
                     }
-                    _ => {}
-                },
-                CurrentScreen::FileSelection => {
-                    handle_file_selection_input(key.code, key.modifiers, app)
+                } else if is_interrupt_key(&key) {
+                    app.request_exit();
+                } else if app.processing.is_some() {
+                    handle_processing_input(key.code, app);
+                } else {
+                    handle_queue_input(key.code, app);
                 }
-                CurrentScreen::MergeConfig => handle_merge_config_input(key.code, app),
-                CurrentScreen::DeleteConfig => handle_delete_config_input(key.code, app),
-                CurrentScreen::SplitConfig => handle_split_config_input(key.code, app),
-                CurrentScreen::Result => handle_result_input(key.code, app),
-
-                _ => {
-                    if key.code == KeyCode::Esc {
-                        app.current_screen = CurrentScreen::Main;
-                    }
+            }
+            processing::poll_processing(app);
+            queue::advance_queue(app);
+            continue;
+        }
+
+        // Wake up regularly to notice signals, and when the toast expires so it disappears
+        let interval = if app.validation.is_pending() || app.thumbnails.is_pending() {
+            SPINNER_INTERVAL
+        } else {
+            SIGNAL_POLL_INTERVAL
+        };
+        let timeout = app
+            .ui_state
+            .toast_remaining()
+            .map_or(interval, |remaining| remaining.min(interval));
+        if !event::poll(timeout)? {
+            continue;
+        }
+
+        match event::read()? {
+            // Ctrl+C asks for confirmation, a second one quits
+            Event::Key(key) if is_interrupt_key(&key) => {
+                if app.current_screen == CurrentScreen::Exiting {
+                    return Ok(());
+                }
+                app.request_exit();
+            }
+            Event::Key(key) if handle_key_event(key, app) => return Ok(()),
+            Event::Paste(text) if handle_paste(&text, app) => return Ok(()),
+            Event::Mouse(mouse) => {
+                let size = terminal.size()?;
+                let area = Rect::new(0, 0, size.width, size.height);
+                // Nothing clickable is drawn while the "too small" warning is shown
+                if !ui::is_too_small(area) {
+                    handle_mouse_input(mouse, area, app);
+                }
+            }
+            // Layouts are recomputed from the new size on the next draw
+            Event::Resize(_, _) => {
+                terminal.autoresize()?;
+                // The thumbnail is drawn again where the new layout puts it, at the new cell size
+                if app.thumbnails.protocol == GraphicsProtocol::Kitty {
+                    terminal
+                        .backend_mut()
+                        .write_all(graphics::kitty_delete().as_bytes())?;
                 }
+                app.thumbnails.shown = None;
+                app.thumbnails.cell_size = graphics::cell_size();
             }
+            _ => {}
+        }
+
+        if let Some(path) = app.result.pending_open.take() {
+            let opened = with_suspended_terminal(terminal, || system::open_in_viewer(&path))?;
+            app.result.notice = Some(match opened {
+                Ok(()) => tr!("Opened '{path}'", path = path),
+                Err(e) => tr!("Could not open '{path}': {error}", path = path, error = e),
+            });
+        }
+    }
+}
+
+/**
+ * Draw the thumbnail of the page under the grid cursor over the frame just drawn, through the
+ * graphics protocol of the terminal, once the worker has rendered it. The thumbnail shown
+ * before is removed first when another page, or none, is to be shown.
+ * @param terminal The terminal, written to directly for the image.
+ * @param app The application state.
+ * @param drawn The frame just drawn.
+ */
+fn draw_page_thumbnail<B: Backend + io::Write>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    drawn: &Buffer,
+) -> io::Result<()> {
+    let wanted = ui::page_thumbnail_area(drawn.area, app).map(|cells| {
+        let key = app
+            .thumbnails
+            .key(&app.page_grid.path, app.page_grid.cursor, cells);
+        (key, cells)
+    });
+    if let Some((key, _)) = &wanted {
+        app.thumbnails.request(key);
+    }
+    let target = wanted.filter(|(key, _)| app.thumbnails.get(key).is_some());
+    if app.thumbnails.shown == target {
+        return Ok(());
+    }
+
+    let backend = terminal.backend_mut();
+    if let Some((_, cells)) = app.thumbnails.shown.take() {
+        match app.thumbnails.protocol {
+            GraphicsProtocol::Kitty => backend.write_all(graphics::kitty_delete().as_bytes())?,
+            // Sixel pixels stay on screen until the cells under them are written again
+            _ => backend.draw(cells.positions().map(|p| (p.x, p.y, &drawn[(p.x, p.y)])))?,
         }
     }
+    if let Some((key, cells)) = &target
+        && let Some(thumbnail) = app.thumbnails.get(key)
+    {
+        let (cell_width, cell_height) = app.thumbnails.cell_size;
+        let columns = (thumbnail.width.div_ceil(cell_width) as u16).min(cells.width);
+        let rows = (thumbnail.height.div_ceil(cell_height) as u16).min(cells.height);
+        backend.set_cursor_position((cells.x + (cells.width - columns) / 2, cells.y))?;
+        let image = app.thumbnails.protocol.image(thumbnail, columns, rows);
+        backend.write_all(image.as_bytes())?;
+    }
+    Backend::flush(backend)?;
+    app.thumbnails.shown = target;
+    Ok(())
+}
+
+/**
+ * Send a key to the overlays, then to the current screen.
+ * @param key The key event.
+ * @param app The application state.
+ * @returns true when the key confirms quitting.
+ */
+fn handle_key_event(key: KeyEvent, app: &mut App) -> bool {
+    if handle_overlay_input(&key, app) {
+        return false;
+    }
+    match app.current_screen {
+        CurrentScreen::Main => handle_main_input(key.code, app),
+        CurrentScreen::Exiting => match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => return true,
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                app.current_screen = CurrentScreen::Main;
+            }
+            _ => {}
+        },
+        CurrentScreen::FileSelection => handle_file_selection_input(key.code, key.modifiers, app),
+        CurrentScreen::FileBrowser => handle_file_browser_input(key.code, app),
+        CurrentScreen::MergeConfig => handle_merge_config_input(key.code, app),
+        CurrentScreen::DeleteConfig => handle_delete_config_input(key.code, app),
+        CurrentScreen::SplitConfig => handle_split_config_input(key.code, app),
+        CurrentScreen::RotateConfig => handle_rotate_config_input(key.code, app),
+        CurrentScreen::ExtractConfig => handle_extract_config_input(key.code, app),
+        CurrentScreen::CompressConfig => handle_compress_config_input(key.code, app),
+        CurrentScreen::WatermarkConfig => handle_watermark_config_input(key.code, app),
+        CurrentScreen::PageGrid => handle_page_grid_input(key.code, key.modifiers, app),
+        CurrentScreen::PageOrder => handle_page_order_input(key.code, key.modifiers, app),
+        CurrentScreen::Processing => handle_processing_input(key.code, app),
+        CurrentScreen::Result => handle_result_input(key.code, app),
+        CurrentScreen::Queue => handle_queue_input(key.code, app),
+        CurrentScreen::Logs => handle_logs_input(key.code, app),
+        CurrentScreen::Help => handle_help_input(key.code, app),
+        CurrentScreen::Onboarding => handle_onboarding_input(key.code, app),
+        CurrentScreen::About => handle_about_input(key.code, app),
+    }
+    false
+}
+
+/**
+ * Handle pasted text. In the file input it may list several paths, one per line; other
+ * fields get it as typed characters, without its line breaks. Outside of a field, a paste
+ * is ignored rather than run as shortcuts.
+ * @param text The pasted text.
+ * @param app The application state.
+ * @returns true when the text confirms quitting.
+ */
+fn handle_paste(text: &str, app: &mut App) -> bool {
+    if app.current_screen == CurrentScreen::FileSelection && app.ui_state.editing_input {
+        paste_into_file_input(app, text);
+        return false;
+    }
+    if !app.is_editing_text() {
+        return false;
+    }
+    text.chars()
+        .filter(|c| !c.is_control())
+        .any(|c| handle_key_event(KeyEvent::from(KeyCode::Char(c)), app))
+}
+
+/**
+ * Run a function outside of the TUI, e.g. to launch an external program.
+ * Raw mode, the alternate screen and mouse capture are released while it runs,
+ * then restored and the whole screen is redrawn.
+ * @param terminal The terminal to restore.
+ * @param f The function to run.
+ * @returns The function result once the terminal is restored.
+ */
+fn with_suspended_terminal<B: ratatui::backend::Backend, T>(
+    terminal: &mut Terminal<B>,
+    f: impl FnOnce() -> T,
+) -> io::Result<T> {
+    disable_raw_mode()?;
+    execute!(
+        io::stdout(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        DisableBracketedPaste
+    )?;
+
+    let result = f();
+
+    enable_raw_mode()?;
+    execute!(
+        io::stdout(),
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableBracketedPaste
+    )?;
+    terminal.clear()?;
+    Ok(result)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pdf::watermark::WatermarkPosition;
     use crossterm::event::KeyModifiers;
-    use state::OperationMode;
+    use state::{OperationMode, StepStatus};
+
+    /// Move the running queue forward once, as the event loop does between redraws.
+    fn advance_queue(app: &mut App) {
+        queue::advance_queue(app);
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    fn run_queue(app: &mut App) {
+        while app.queue.running {
+            advance_queue(app);
+        }
+    }
+
+    /// Wait for the operation started from a configuration screen to be over.
+    fn finish_processing(app: &mut App) {
+        while app.processing.is_some() {
+            processing::poll_processing(app);
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
 
     #[test]
     fn test_handle_main_input() {
@@ -101,7 +423,7 @@ mod tests {
 
         // Test wrapping
         handle_main_input(KeyCode::Up, &mut app);
-        assert_eq!(app.menu_mode_index(), 4);
+        assert_eq!(app.menu_mode_index(), 10);
 
         // Test entering merge mode
         app.set_menu_mode_index(0);
@@ -116,12 +438,44 @@ mod tests {
         assert_eq!(app.operation_mode, OperationMode::Delete);
         assert_eq!(app.current_screen, CurrentScreen::FileSelection);
 
-        // Test help screen
+        // Test rotate and extract modes, by menu entry and by number key
         app.reset();
         app.set_menu_mode_index(3);
         handle_main_input(KeyCode::Enter, &mut app);
+        assert_eq!(app.operation_mode, OperationMode::Rotate);
+        assert_eq!(app.current_screen, CurrentScreen::FileSelection);
+
+        app.reset();
+        handle_main_input(KeyCode::Char('5'), &mut app);
+        assert_eq!(app.operation_mode, OperationMode::Extract);
+        assert_eq!(app.menu_mode_index(), 4);
+
+        app.reset();
+        handle_main_input(KeyCode::Char('6'), &mut app);
+        assert_eq!(app.operation_mode, OperationMode::Compress);
+
+        app.reset();
+        handle_main_input(KeyCode::Char('7'), &mut app);
+        assert_eq!(app.operation_mode, OperationMode::Watermark);
+
+        // Test queue and help screens
+        app.reset();
+        handle_main_input(KeyCode::Char('8'), &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::Queue);
+
+        app.reset();
+        app.set_menu_mode_index(8);
+        handle_main_input(KeyCode::Enter, &mut app);
         assert_eq!(app.current_screen, CurrentScreen::Help);
 
+        // Test about screen
+        app.reset();
+        handle_main_input(KeyCode::Char('a'), &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::About);
+        assert_eq!(app.menu_mode_index(), 9);
+        handle_about_input(KeyCode::Esc, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::Main);
+
         // Test exit
         app.reset();
         handle_main_input(KeyCode::Char('q'), &mut app);
@@ -150,18 +504,18 @@ mod tests {
 
         // Test with invalid file (should set error and exit edit mode)
         handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
-        assert!(app.error_message().is_some());
+        assert!(app.toast_error().is_some());
         assert!(!app.editing_input()); // Should exit edit mode even with error
 
         // Test with valid PDF file (if it exists)
-        app.ui_state.clear_message();
+        app.ui_state.dismiss_toast();
         app.set_editing_input(true); // Re-enter edit mode
         if std::path::Path::new("tests/tests_pdf/a.pdf").exists() {
             app.set_current_input(Some("tests/tests_pdf/a.pdf".to_string()));
             handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
             assert_eq!(app.selected_files().len(), 1);
             assert_eq!(app.selected_files()[0], "tests/tests_pdf/a.pdf");
-            assert!(app.error_message().is_none());
+            assert!(app.toast_error().is_none());
             assert!(!app.editing_input()); // Should exit edit mode after successful add
         } else {
             // If no test PDF exists, just add a mock PDF to test file removal
@@ -179,10 +533,10 @@ mod tests {
         // Test navigation to next screen with insufficient files for merge
         app.selected_files_mut().push("file1.pdf".to_string());
         handle_file_selection_input(KeyCode::Right, KeyModifiers::NONE, &mut app);
-        assert!(app.error_message().is_some()); // Not enough files for merge
+        assert!(app.toast_error().is_some()); // Not enough files for merge
 
         // Test with enough files for merge
-        app.ui_state.clear_message();
+        app.ui_state.dismiss_toast();
         app.selected_files_mut().push("file2.pdf".to_string());
         handle_file_selection_input(KeyCode::Right, KeyModifiers::NONE, &mut app);
         assert_eq!(app.current_screen, CurrentScreen::MergeConfig);
@@ -190,7 +544,7 @@ mod tests {
         app.reset();
         app.operation_mode = OperationMode::Delete;
         handle_file_selection_input(KeyCode::Right, KeyModifiers::NONE, &mut app);
-        assert!(app.error_message().is_some()); // Not enough files for delete
+        assert!(app.toast_error().is_some()); // Not enough files for delete
 
         // Test delete mode validation
         app.reset();
@@ -198,7 +552,384 @@ mod tests {
         app.selected_files_mut().push("file1.pdf".to_string());
         app.selected_files_mut().push("file2.pdf".to_string());
         handle_file_selection_input(KeyCode::Right, KeyModifiers::NONE, &mut app);
-        assert!(app.error_message().is_some()); // Too many files for delete
+        assert!(app.toast_error().is_some()); // Too many files for delete
+    }
+
+    #[test]
+    fn test_edit_file_path() {
+        let mut app = App::new();
+        app.operation_mode = OperationMode::Merge;
+        app.selected_files_mut()
+            .push("tests/tests_pdf/a.pdf".to_string());
+        app.selected_files_mut()
+            .push("tests/tests_pdf/typo.pdf".to_string());
+        app.set_selected_file_index(1);
+
+        // 'e' opens the selected path in the input field
+        handle_file_selection_input(KeyCode::Char('e'), KeyModifiers::NONE, &mut app);
+        assert!(app.editing_input());
+        assert_eq!(app.current_input(), Some("tests/tests_pdf/typo.pdf"));
+
+        // Esc leaves the entry untouched
+        handle_file_selection_input(KeyCode::Esc, KeyModifiers::NONE, &mut app);
+        assert!(!app.editing_input());
+        assert_eq!(app.selected_files()[1], "tests/tests_pdf/typo.pdf");
+
+        // An invalid correction is rejected and the entry is kept
+        handle_file_selection_input(KeyCode::Char('e'), KeyModifiers::NONE, &mut app);
+        handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        assert!(app.toast_error().is_some());
+        assert_eq!(app.selected_files()[1], "tests/tests_pdf/typo.pdf");
+
+        // A valid correction replaces the entry in place
+        app.ui_state.dismiss_toast();
+        handle_file_selection_input(KeyCode::Char('e'), KeyModifiers::NONE, &mut app);
+        for _ in "typo.pdf".chars() {
+            handle_file_selection_input(KeyCode::Backspace, KeyModifiers::NONE, &mut app);
+        }
+        for c in "b.pdf".chars() {
+            handle_file_selection_input(KeyCode::Char(c), KeyModifiers::NONE, &mut app);
+        }
+        handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        assert!(app.toast_error().is_none());
+        assert!(!app.editing_input());
+        assert_eq!(
+            *app.selected_files(),
+            ["tests/tests_pdf/a.pdf", "tests/tests_pdf/b.pdf"]
+        );
+        assert!(app.file_state.editing_file_index.is_none());
+
+        // Tab still adds a new file afterwards
+        handle_file_selection_input(KeyCode::Tab, KeyModifiers::NONE, &mut app);
+        for c in "tests/tests_pdf/c.pdf".chars() {
+            handle_file_selection_input(KeyCode::Char(c), KeyModifiers::NONE, &mut app);
+        }
+        handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        assert_eq!(app.selected_files().len(), 3);
+    }
+
+    #[test]
+    fn test_move_file_to_top_and_bottom() {
+        use handlers::palette::{PaletteAction, handle_palette_input, palette_matches};
+
+        let mut app = App::new();
+        app.start_operation(OperationMode::Merge, None);
+        for file in ["a.pdf", "b.pdf", "c.pdf", "d.pdf"] {
+            app.selected_files_mut().push(file.to_string());
+        }
+
+        app.set_selected_file_index(2);
+        handle_file_selection_input(KeyCode::Home, KeyModifiers::ALT, &mut app);
+        assert_eq!(*app.selected_files(), ["c.pdf", "a.pdf", "b.pdf", "d.pdf"]);
+        assert_eq!(app.selected_file_index(), 0);
+
+        handle_file_selection_input(KeyCode::End, KeyModifiers::ALT, &mut app);
+        assert_eq!(*app.selected_files(), ["a.pdf", "b.pdf", "d.pdf", "c.pdf"]);
+        assert_eq!(app.selected_file_index(), 3);
+
+        // The merge configuration moves its own selection, also from the palette
+        app.current_screen = CurrentScreen::MergeConfig;
+        app.set_merge_file_index(1);
+        handle_merge_config_input(KeyCode::End, &mut app);
+        assert_eq!(*app.selected_files(), ["a.pdf", "d.pdf", "c.pdf", "b.pdf"]);
+        assert_eq!(app.merge_file_index(), 3);
+
+        app.palette.show();
+        for c in "top".chars() {
+            handle_palette_input(KeyCode::Char(c), &mut app);
+        }
+        assert_eq!(
+            palette_matches(&app).first(),
+            Some(&PaletteAction::MoveFileToTop)
+        );
+        handle_palette_input(KeyCode::Enter, &mut app);
+        assert_eq!(*app.selected_files(), ["b.pdf", "a.pdf", "d.pdf", "c.pdf"]);
+        assert_eq!(app.merge_file_index(), 0);
+
+        // Single-file operations have no merge order
+        app.start_operation(OperationMode::Rotate, None);
+        app.selected_files_mut().push("a.pdf".to_string());
+        app.palette.show();
+        assert!(!palette_matches(&app).contains(&PaletteAction::MoveFileToTop));
+    }
+
+    #[test]
+    fn test_file_preview() {
+        let mut app = App::new();
+        app.start_operation(OperationMode::Merge, None);
+        app.selected_files_mut().push("missing.pdf".to_string());
+        app.add_file("tests/tests_pdf/c.pdf".to_string());
+
+        // A file that cannot be loaded has no preview
+        handle_file_selection_input(KeyCode::Char('p'), KeyModifiers::NONE, &mut app);
+        assert!(app.file_state.preview.is_none());
+        assert!(app.toast_error().is_some());
+
+        app.set_selected_file_index(1);
+        handle_file_selection_input(KeyCode::Char('p'), KeyModifiers::NONE, &mut app);
+        let preview = &app.file_state.preview.as_ref().unwrap().preview;
+        assert_eq!(preview.info.page_count(), 7);
+        assert!(preview.first_page_text.is_some());
+        assert!(app.is_editing_text());
+
+        // Any key closes it without acting on the list
+        handle_file_selection_input(KeyCode::Backspace, KeyModifiers::NONE, &mut app);
+        assert!(app.file_state.preview.is_none());
+        assert_eq!(app.selected_files().len(), 2);
+    }
+
+    #[test]
+    fn test_add_pdfs_from_folder() {
+        let mut app = App::new();
+        app.start_operation(OperationMode::Merge, None);
+        app.add_file("tests/tests_pdf/b.pdf".to_string());
+
+        // Typing a folder opens the prompt instead of adding it
+        handle_file_selection_input(KeyCode::Tab, KeyModifiers::NONE, &mut app);
+        app.set_current_input(Some("tests".to_string()));
+        handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        assert!(!app.editing_input());
+        let prompt = app.file_state.folder_prompt.as_ref().unwrap();
+        assert!(prompt.files.is_empty());
+
+        // R looks into subfolders, and keys no longer reach the file list
+        handle_file_selection_input(KeyCode::Char('r'), KeyModifiers::NONE, &mut app);
+        assert_eq!(
+            app.file_state.folder_prompt.as_ref().unwrap().files.len(),
+            3
+        );
+        assert!(app.is_editing_text());
+
+        // Files already selected are not added twice
+        handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        assert!(app.file_state.folder_prompt.is_none());
+        assert_eq!(
+            *app.selected_files(),
+            [
+                "tests/tests_pdf/b.pdf",
+                "tests/tests_pdf/a.pdf",
+                "tests/tests_pdf/c.pdf"
+            ]
+        );
+
+        // Esc cancels, and an empty folder cannot be confirmed
+        app.set_current_input(Some("tests".to_string()));
+        app.set_editing_input(true);
+        handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        handle_file_selection_input(KeyCode::Esc, KeyModifiers::NONE, &mut app);
+        assert!(app.file_state.folder_prompt.is_none());
+        app.set_current_input(Some("tests".to_string()));
+        app.set_editing_input(true);
+        handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        assert!(app.toast_error().is_some());
+        assert_eq!(app.selected_files().len(), 3);
+
+        // Other operations take a single file, so a folder is rejected as before
+        app.start_operation(OperationMode::Rotate, None);
+        handle_file_selection_input(KeyCode::Tab, KeyModifiers::NONE, &mut app);
+        app.set_current_input(Some("tests/tests_pdf".to_string()));
+        handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        assert!(app.file_state.folder_prompt.is_none());
+        assert!(app.toast_error().is_some());
+    }
+
+    #[test]
+    fn test_add_several_paths_at_once() {
+        let mut app = App::new();
+        app.start_operation(OperationMode::Merge, None);
+        handle_file_selection_input(KeyCode::Tab, KeyModifiers::NONE, &mut app);
+
+        // A paste lists one path per line, each line is kept whole
+        let spaced = std::env::temp_dir().join("pdf cutter spaced.pdf");
+        std::fs::copy("tests/tests_pdf/a.pdf", &spaced).unwrap();
+        let spaced = spaced.to_string_lossy().into_owned();
+        handle_paste(
+            &format!("tests/tests_pdf/b.pdf\n{}\r\nmissing.pdf\n", spaced),
+            &mut app,
+        );
+        assert_eq!(
+            app.current_input(),
+            Some(format!("tests/tests_pdf/b.pdf \"{}\" missing.pdf", spaced).as_str())
+        );
+
+        // The valid ones are added, the failed one is reported and stays in the field
+        handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        assert_eq!(
+            app.selected_files(),
+            &vec!["tests/tests_pdf/b.pdf".to_string(), spaced.clone()]
+        );
+        assert!(app.toast_error().unwrap().contains("missing.pdf"));
+        assert!(app.editing_input());
+        assert_eq!(app.current_input(), Some("missing.pdf"));
+
+        // Typed paths are separated by spaces too
+        app.set_current_input(Some(
+            "tests/tests_pdf/a.pdf tests/tests_pdf/c.pdf".to_string(),
+        ));
+        handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        assert_eq!(app.selected_files().len(), 4);
+        assert!(app.toast_error().is_none());
+        assert!(!app.editing_input());
+
+        // An existing file with spaces in its name is still one path
+        handle_file_selection_input(KeyCode::Tab, KeyModifiers::NONE, &mut app);
+        app.set_current_input(Some(spaced.clone()));
+        handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        assert_eq!(app.selected_files().len(), 5);
+
+        // Operations on a single file refuse several paths
+        app.start_operation(OperationMode::Rotate, None);
+        handle_file_selection_input(KeyCode::Tab, KeyModifiers::NONE, &mut app);
+        app.set_current_input(Some(
+            "tests/tests_pdf/a.pdf tests/tests_pdf/b.pdf".to_string(),
+        ));
+        handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        assert!(app.toast_error().is_some());
+        assert!(app.files_is_empty());
+
+        // Outside of a field, a paste does nothing
+        app.ui_state.dismiss_toast();
+        app.set_editing_input(false);
+        handle_paste("q", &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::FileSelection);
+        std::fs::remove_file(&spaced).unwrap();
+    }
+
+    #[test]
+    fn test_file_validation_runs_in_background() {
+        use lopdf::dictionary;
+
+        let wait_for_validations = |app: &mut App| {
+            for _ in 0..500 {
+                app.poll_validations();
+                if !app.validation.is_pending() {
+                    return;
+                }
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            panic!("validation did not finish");
+        };
+
+        let mut app = App::new();
+        app.start_operation(OperationMode::Merge, None);
+        for file in ["tests/tests_pdf/a.pdf", "tests/tests_pdf/b.pdf"] {
+            handle_file_selection_input(KeyCode::Tab, KeyModifiers::NONE, &mut app);
+            app.set_current_input(Some(file.to_string()));
+            handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        }
+        // The files are listed right away, and checked before moving on
+        assert_eq!(app.selected_files().len(), 2);
+        if app.validation.is_pending() {
+            handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+            assert_eq!(app.current_screen, CurrentScreen::FileSelection);
+            app.ui_state.dismiss_toast();
+        }
+
+        wait_for_validations(&mut app);
+        assert_eq!(
+            app.validation.status("tests/tests_pdf/a.pdf"),
+            Some(&state::FileValidation::Valid)
+        );
+        assert!(
+            app.inspect_cache
+                .page_count("tests/tests_pdf/a.pdf")
+                .is_some()
+        );
+        handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::MergeConfig);
+
+        // A file that is not a PDF is flagged in the list and blocks the next step
+        let broken = std::env::temp_dir().join("pdf_cutter_broken.pdf");
+        std::fs::write(&broken, "not a pdf").unwrap();
+        let broken = broken.to_string_lossy().into_owned();
+        app.start_operation(OperationMode::Rotate, None);
+        app.add_typed_file(broken.clone());
+        wait_for_validations(&mut app);
+        assert_eq!(
+            app.validation.status(&broken),
+            Some(&state::FileValidation::Invalid)
+        );
+        assert!(app.toast_error().is_some());
+        app.ui_state.dismiss_toast();
+        handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        assert!(app.toast_error().is_some());
+        assert_eq!(app.current_screen, CurrentScreen::FileSelection);
+        app.ui_state.dismiss_toast();
+
+        // Encrypted and empty documents are flagged too, and block the next step
+        let mut doc = lopdf::Document::with_version("1.5");
+        let pages_id = doc.add_object(dictionary! {
+            "Type" => "Pages",
+            "Kids" => vec![],
+            "Count" => 0,
+        });
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+        let empty = std::env::temp_dir().join("pdf_cutter_empty.pdf");
+        doc.save(&empty).unwrap();
+        let encrypt_id = doc.add_object(dictionary! {
+            "Filter" => "Standard",
+            "V" => 1,
+            "R" => 2,
+            "O" => lopdf::Object::string_literal(vec![0u8; 32]),
+            "U" => lopdf::Object::string_literal(vec![0u8; 32]),
+            "P" => -4,
+        });
+        doc.trailer.set("Encrypt", encrypt_id);
+        let encrypted = std::env::temp_dir().join("pdf_cutter_encrypted.pdf");
+        doc.save(&encrypted).unwrap();
+        for (file, status) in [
+            (empty, state::FileValidation::NoPages),
+            (encrypted, state::FileValidation::Encrypted),
+        ] {
+            let file = file.to_string_lossy().into_owned();
+            app.start_operation(OperationMode::Merge, None);
+            app.add_typed_file(file.clone());
+            wait_for_validations(&mut app);
+            assert_eq!(app.validation.status(&file), Some(&status));
+            assert!(app.toast_error().is_some());
+            app.ui_state.dismiss_toast();
+            handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+            assert!(app.toast_error().is_some());
+            assert_eq!(app.current_screen, CurrentScreen::FileSelection);
+            app.ui_state.dismiss_toast();
+            std::fs::remove_file(&file).unwrap();
+        }
+
+        // A file moved away after it was checked is flagged, and loaded again once it is back
+        let moved = std::env::temp_dir().join("pdf_cutter_moved.pdf");
+        std::fs::copy("tests/tests_pdf/a.pdf", &moved).unwrap();
+        let moved = moved.to_string_lossy().into_owned();
+        app.start_operation(OperationMode::Rotate, None);
+        app.add_typed_file(moved.clone());
+        wait_for_validations(&mut app);
+        app.check_files_exist();
+        assert_eq!(
+            app.validation.status(&moved),
+            Some(&state::FileValidation::Valid)
+        );
+        std::fs::remove_file(&moved).unwrap();
+        app.check_files_exist();
+        assert_eq!(
+            app.validation.status(&moved),
+            Some(&state::FileValidation::Missing)
+        );
+        assert!(app.inspect_cache.get(&moved).is_none());
+        handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        assert!(app.toast_error().is_some());
+        assert_eq!(app.current_screen, CurrentScreen::FileSelection);
+        std::fs::copy("tests/tests_pdf/a.pdf", &moved).unwrap();
+        app.check_files_exist();
+        wait_for_validations(&mut app);
+        assert_eq!(
+            app.validation.status(&moved),
+            Some(&state::FileValidation::Valid)
+        );
+        std::fs::remove_file(&moved).unwrap();
     }
 
     #[test]
@@ -233,6 +964,7 @@ mod tests {
         // Test merge execution with valid config
         app.merge_config.output_filename = "valid_output.pdf".to_string();
         handle_merge_config_input(KeyCode::Enter, &mut app);
+        finish_processing(&mut app);
         // Should attempt merge and set error message (files don't exist)
         assert!(app.error_message().is_some());
     }
@@ -274,28 +1006,1488 @@ mod tests {
 
         // Test delete execution
         handle_delete_config_input(KeyCode::Enter, &mut app);
+        finish_processing(&mut app);
         // Should attempt delete and set error message (file doesn't exist)
         assert!(app.error_message().is_some());
     }
 
     #[test]
-    fn test_handle_result_input() {
+    fn test_handle_mouse_input() {
+        use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+        use ratatui::layout::Rect;
+
+        let area = Rect::new(0, 0, 80, 24);
+        let mouse = |kind, column, row| MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        };
+
+        // Clicking the second menu entry (title takes 3 rows, list border 1) opens Delete
         let mut app = App::new();
-        app.current_screen = CurrentScreen::Result;
-        app.set_success("Success!".to_string());
+        handle_mouse_input(
+            mouse(MouseEventKind::Down(MouseButton::Left), 5, 5),
+            area,
+            &mut app,
+        );
+        assert_eq!(app.operation_mode, OperationMode::Delete);
+        assert_eq!(app.current_screen, CurrentScreen::FileSelection);
 
-        // Test returning to main menu
-        handle_result_input(KeyCode::Enter, &mut app);
-        assert_eq!(app.current_screen, CurrentScreen::Main);
+        // Wheel scrolls the main menu
+        app.reset();
+        handle_mouse_input(mouse(MouseEventKind::ScrollDown, 5, 5), area, &mut app);
+        assert_eq!(app.menu_mode_index(), 1);
+        handle_mouse_input(mouse(MouseEventKind::ScrollUp, 5, 5), area, &mut app);
+        assert_eq!(app.menu_mode_index(), 0);
 
-        // Test with Esc
-        app.current_screen = CurrentScreen::Result;
-        handle_result_input(KeyCode::Esc, &mut app);
-        assert_eq!(app.current_screen, CurrentScreen::Main);
+        // Clicking a file selects it, clicking the input field starts editing
+        app.reset();
+        app.operation_mode = OperationMode::Merge;
+        app.current_screen = CurrentScreen::FileSelection;
+        app.selected_files_mut().push("file1.pdf".to_string());
+        app.selected_files_mut().push("file2.pdf".to_string());
+        handle_mouse_input(
+            mouse(MouseEventKind::Down(MouseButton::Left), 5, 5),
+            area,
+            &mut app,
+        );
+        assert_eq!(app.selected_file_index(), 1);
+        handle_mouse_input(
+            mouse(MouseEventKind::Down(MouseButton::Left), 5, 19),
+            area,
+            &mut app,
+        );
+        assert!(app.editing_input());
 
-        // Test with Space
-        app.current_screen = CurrentScreen::Result;
-        handle_result_input(KeyCode::Char(' '), &mut app);
-        assert_eq!(app.current_screen, CurrentScreen::Main);
+        // The wheel in merge config moves the cursor without reordering files
+        app.set_editing_input(false);
+        app.current_screen = CurrentScreen::MergeConfig;
+        app.set_merge_file_index(0);
+        handle_mouse_input(mouse(MouseEventKind::ScrollDown, 5, 5), area, &mut app);
+        assert_eq!(app.merge_file_index(), 1);
+        assert_eq!(app.selected_files()[0], "file1.pdf");
+
+        // The output field sits below the summary panel
+        handle_mouse_input(
+            mouse(MouseEventKind::Down(MouseButton::Left), 5, 19),
+            area,
+            &mut app,
+        );
+        assert!(app.merge_config.editing_output);
+        app.merge_config.editing_output = false;
+
+        // Clicking the checkbox toggles named segments in split config
+        app.current_screen = CurrentScreen::SplitConfig;
+        handle_mouse_input(
+            mouse(MouseEventKind::Down(MouseButton::Left), 5, 13),
+            area,
+            &mut app,
+        );
+        assert!(app.split_config.use_named_segments);
+
+        // Clicking the output directory field starts editing it
+        handle_mouse_input(
+            mouse(MouseEventKind::Down(MouseButton::Left), 5, 16),
+            area,
+            &mut app,
+        );
+        assert!(app.output_location.editing);
+    }
+
+    #[test]
+    fn test_output_directory_input() {
+        let mut app = App::new();
+        app.operation_mode = OperationMode::Merge;
+        app.current_screen = CurrentScreen::MergeConfig;
+
+        // Tab completes a unique directory match
+        handle_merge_config_input(KeyCode::Char('d'), &mut app);
+        assert!(app.output_location.editing);
+        for c in "tests/tests_".chars() {
+            handle_merge_config_input(KeyCode::Char(c), &mut app);
+        }
+        handle_merge_config_input(KeyCode::Tab, &mut app);
+        assert_eq!(app.output_location.directory, "tests/tests_pdf/");
+        handle_merge_config_input(KeyCode::Enter, &mut app);
+        assert!(!app.output_location.editing);
+        assert!(app.toast_error().is_none());
+
+        let resolved = app.resolve_output("merged.pdf");
+        assert!(resolved.ends_with("tests/tests_pdf/merged.pdf"));
+        assert!(std::path::Path::new(&resolved).is_absolute());
+
+        // A missing directory is created when writing, a file in the way is reported
+        handle_merge_config_input(KeyCode::Char('d'), &mut app);
+        handle_merge_config_input(KeyCode::Char('x'), &mut app);
+        handle_merge_config_input(KeyCode::Enter, &mut app);
+        assert!(app.toast_error().is_none());
+        handle_merge_config_input(KeyCode::Char('d'), &mut app);
+        handle_merge_config_input(KeyCode::Backspace, &mut app);
+        for c in "a.pdf".chars() {
+            handle_merge_config_input(KeyCode::Char(c), &mut app);
+        }
+        handle_merge_config_input(KeyCode::Enter, &mut app);
+        assert!(
+            app.toast_error()
+                .is_some_and(|e| e.contains("is a file, not a folder"))
+        );
+    }
+
+    #[test]
+    fn test_delete_config_validates_against_page_count() {
+        let mut app = App::new();
+        app.operation_mode = OperationMode::Delete;
+        app.selected_files_mut()
+            .push("tests/tests_pdf/c.pdf".to_string());
+
+        // Moving to the config screen loads the document into the inspect cache
+        handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::DeleteConfig);
+        assert_eq!(app.first_file_page_count(), Some(7));
+
+        // Page 8 does not exist: flagged on the field, then rejected when running
+        handle_delete_config_input(KeyCode::Char('p'), &mut app);
+        handle_delete_config_input(KeyCode::Char('8'), &mut app);
+        handle_delete_config_input(KeyCode::Enter, &mut app);
+        app.delete_config.output_filename = "out.pdf".to_string();
+        handle_delete_config_input(KeyCode::Enter, &mut app);
+        assert_eq!(
+            app.toast_error(),
+            Some("Page 8 is out of range (document has 7 pages)")
+        );
+
+        // Deleting every page is rejected before running the operation
+        app.ui_state.dismiss_toast();
+        app.delete_config.pages_to_delete = "1-7".to_string();
+        handle_delete_config_input(KeyCode::Enter, &mut app);
+        assert!(app.toast_error().is_some());
+        assert_eq!(app.current_screen, CurrentScreen::DeleteConfig);
+
+        // Folders are not accepted in the output filename
+        app.delete_config.pages_to_delete = "1".to_string();
+        app.delete_config.output_filename = "sub/out.pdf".to_string();
+        handle_delete_config_input(KeyCode::Enter, &mut app);
+        assert!(
+            app.toast_error()
+                .is_some_and(|e| e.starts_with("Invalid output name"))
+        );
+        assert_eq!(app.current_screen, CurrentScreen::DeleteConfig);
+    }
+
+    #[test]
+    fn test_split_segments_in_document() {
+        use crate::tui::utils::validate_split_in_document;
+
+        let mut app = App::new();
+        app.start_operation(OperationMode::Split, None);
+        app.add_file("tests/tests_pdf/c.pdf".to_string());
+        app.current_screen = CurrentScreen::SplitConfig;
+        let page_count = app.first_file_page_count().unwrap();
+
+        // Pages past the end are refused before anything runs
+        app.split_config.segments = format!("1-{}", page_count + 1);
+        handle_split_config_input(KeyCode::Enter, &mut app);
+        assert!(
+            app.toast_error()
+                .is_some_and(|e| e.contains("out of range"))
+        );
+        assert_eq!(app.current_screen, CurrentScreen::SplitConfig);
+
+        // So are overlapping segments, until O allows them
+        app.ui_state.dismiss_toast();
+        app.split_config.segments = "1-2,2-3".to_string();
+        handle_split_config_input(KeyCode::Char('a'), &mut app);
+        assert!(
+            app.toast_error()
+                .is_some_and(|e| e.contains("both include page 2"))
+        );
+        assert!(app.queue.steps.is_empty());
+        handle_split_config_input(KeyCode::Char('o'), &mut app);
+        assert!(app.split_config.allow_overlaps);
+        assert!(validate_split_in_document("1-2,2-3", false, Some(page_count), true).is_ok());
+        handle_split_config_input(KeyCode::Char('a'), &mut app);
+        assert_eq!(app.queue.steps.len(), 1);
+    }
+
+    #[test]
+    fn test_status_bar_state() {
+        let mut app = App::new();
+        app.operation_mode = OperationMode::Merge;
+        app.add_file("tests/tests_pdf/c.pdf".to_string());
+        app.add_file("tests/tests_pdf/b.pdf".to_string());
+        // Files are inspected as soon as they are selected, so totals are known on every screen
+        assert_eq!(
+            app.inspect_cache.page_count("tests/tests_pdf/b.pdf"),
+            Some(2)
+        );
+
+        // The last message outlives the toast and the operation that produced it
+        app.notify_error("Page 9 is out of range".to_string());
+        app.ui_state.dismiss_toast();
+        app.reset();
+        assert!(matches!(
+            &app.ui_state.last_message,
+            Some(state::MessageType::Error(message)) if message == "Page 9 is out of range"
+        ));
+    }
+
+    #[test]
+    fn test_toast_keeps_screen_interactive() {
+        use state::ui_state::TOAST_DURATION;
+        use std::time::Instant;
+
+        let mut app = App::new();
+        app.operation_mode = OperationMode::Rotate;
+        app.current_screen = CurrentScreen::RotateConfig;
+        app.selected_files_mut()
+            .push("tests/tests_pdf/c.pdf".to_string());
+        app.refresh_inspect_cache();
+
+        handle_rotate_config_input(KeyCode::Char('p'), &mut app);
+        handle_rotate_config_input(KeyCode::Char('9'), &mut app);
+        handle_rotate_config_input(KeyCode::Enter, &mut app);
+        handle_rotate_config_input(KeyCode::Enter, &mut app);
+        assert!(app.toast_error().is_some());
+
+        // The next key is handled by the screen instead of closing a popup
+        handle_rotate_config_input(KeyCode::Char('p'), &mut app);
+        assert!(app.rotate_config.editing_pages);
+        assert!(app.toast_error().is_some());
+
+        // The toast goes away on its own
+        app.ui_state.expire_toast();
+        assert!(app.ui_state.toast.is_some());
+        if let Some(toast) = app.ui_state.toast.as_mut() {
+            toast.shown_at = Instant::now() - TOAST_DURATION;
+        }
+        assert_eq!(app.ui_state.toast_remaining(), Some(Duration::ZERO));
+        app.ui_state.expire_toast();
+        assert!(app.ui_state.toast.is_none());
+    }
+
+    #[test]
+    fn test_handle_page_grid_input() {
+        let mut app = App::new();
+        app.operation_mode = OperationMode::Delete;
+        app.current_screen = CurrentScreen::DeleteConfig;
+        app.selected_files_mut()
+            .push("tests/tests_pdf/c.pdf".to_string());
+        app.delete_config.pages_to_delete = "2".to_string();
+
+        // Opening the grid loads the page count and keeps the current spec
+        handle_delete_config_input(KeyCode::Char('g'), &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::PageGrid);
+        assert_eq!(app.page_grid.page_count, 7);
+        assert_eq!(app.page_grid.selected_pages(), vec![2]);
+
+        // Toggle page 4, then Shift+Right extends the range from it
+        handle_page_grid_input(KeyCode::Right, KeyModifiers::NONE, &mut app);
+        handle_page_grid_input(KeyCode::Right, KeyModifiers::NONE, &mut app);
+        handle_page_grid_input(KeyCode::Right, KeyModifiers::NONE, &mut app);
+        handle_page_grid_input(KeyCode::Char(' '), KeyModifiers::NONE, &mut app);
+        handle_page_grid_input(KeyCode::Right, KeyModifiers::SHIFT, &mut app);
+        handle_page_grid_input(KeyCode::Right, KeyModifiers::SHIFT, &mut app);
+        assert_eq!(app.page_grid.selected_pages(), vec![2, 4, 5, 6]);
+
+        // The cursor cannot leave the document
+        handle_page_grid_input(KeyCode::Down, KeyModifiers::NONE, &mut app);
+        assert_eq!(app.page_grid.cursor, 7);
+
+        // The thumbnail goes inside the outline of the preview panel, and not under a popup
+        assert_eq!(app.page_grid.path, "tests/tests_pdf/c.pdf");
+        let frame = Rect::new(0, 0, 120, 40);
+        let cells = ui::page_thumbnail_area(frame, &app).unwrap();
+        assert!(cells.x > 62 && cells.right() < 119 && cells.height > 10);
+        assert!(ui::page_thumbnail_area(Rect::new(0, 0, 80, 40), &app).is_none());
+        app.ui_state.show_cheat_sheet = true;
+        assert!(ui::page_thumbnail_area(frame, &app).is_none());
+        app.ui_state.show_cheat_sheet = false;
+
+        // Enter writes the selection back as a page spec
+        handle_page_grid_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::DeleteConfig);
+        assert_eq!(app.delete_config.pages_to_delete, "2,4-6");
+
+        // Esc leaves the spec untouched
+        handle_delete_config_input(KeyCode::Char('g'), &mut app);
+        handle_page_grid_input(KeyCode::Char('a'), KeyModifiers::NONE, &mut app);
+        handle_page_grid_input(KeyCode::Esc, KeyModifiers::NONE, &mut app);
+        assert_eq!(app.delete_config.pages_to_delete, "2,4-6");
+    }
+
+    #[test]
+    fn test_handle_page_order_input() {
+        let mut app = App::new();
+        app.operation_mode = OperationMode::Extract;
+        app.current_screen = CurrentScreen::ExtractConfig;
+        app.selected_files_mut()
+            .push("tests/tests_pdf/c.pdf".to_string());
+        app.extract_config.pages = "3,1-2".to_string();
+
+        // The typed order comes first, the other pages follow as removed
+        handle_extract_config_input(KeyCode::Char('o'), &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::PageOrder);
+        assert_eq!(app.page_order.order, vec![3, 1, 2, 4, 5, 6, 7]);
+        assert_eq!(app.page_order.kept_pages(), vec![3, 1, 2]);
+
+        // Move page 1 to the top, restore page 7 and move it up one
+        handle_page_order_input(KeyCode::Down, KeyModifiers::NONE, &mut app);
+        handle_page_order_input(KeyCode::Up, KeyModifiers::ALT, &mut app);
+        assert_eq!(app.page_order.cursor, 0);
+        handle_page_order_input(KeyCode::End, KeyModifiers::NONE, &mut app);
+        handle_page_order_input(KeyCode::Char('d'), KeyModifiers::NONE, &mut app);
+        handle_page_order_input(KeyCode::Home, KeyModifiers::ALT, &mut app);
+        assert_eq!(app.page_order.kept_pages(), vec![7, 1, 3, 2]);
+
+        // Enter writes the order back as the pages to extract
+        handle_page_order_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::ExtractConfig);
+        assert_eq!(app.extract_config.pages, "7,1,3,2");
+
+        // At least one page has to be kept
+        app.extract_config.pages = "2".to_string();
+        handle_extract_config_input(KeyCode::Char('o'), &mut app);
+        handle_page_order_input(KeyCode::Char('d'), KeyModifiers::NONE, &mut app);
+        handle_page_order_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::PageOrder);
+        assert!(app.toast_error().is_some());
+        handle_page_order_input(KeyCode::Esc, KeyModifiers::NONE, &mut app);
+        assert_eq!(app.extract_config.pages, "2");
+    }
+
+    #[test]
+    fn test_rotate_and_extract_wizards() {
+        let output_dir = std::env::temp_dir().to_string_lossy().into_owned();
+
+        // Rotate: an empty page list rotates every page
+        let mut app = App::new();
+        app.start_operation(
+            OperationMode::Rotate,
+            Some("tests/tests_pdf/c.pdf".to_string()),
+        );
+        assert_eq!(ui::wizard_step(&app.current_screen), Some(0));
+        handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::RotateConfig);
+        assert_eq!(ui::wizard_step(&app.current_screen), Some(1));
+
+        handle_rotate_config_input(KeyCode::Char('r'), &mut app);
+        assert_eq!(app.rotate_config.rule().angle, 180);
+
+        // A second rule turns pages 2-3 a quarter turn counterclockwise on top of the first
+        handle_rotate_config_input(KeyCode::Char('n'), &mut app);
+        assert_eq!(app.rotate_config.selected_rule, 1);
+        assert!(app.rotate_config.editing_pages);
+        for c in "2-9".chars() {
+            handle_rotate_config_input(KeyCode::Char(c), &mut app);
+        }
+        handle_rotate_config_input(KeyCode::Enter, &mut app);
+        handle_rotate_config_input(KeyCode::Left, &mut app);
+        assert_eq!(app.rotate_config.rule().angle, 270);
+        handle_rotate_config_input(KeyCode::Enter, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::RotateConfig);
+        assert!(app.toast_error().unwrap().starts_with("Rule 2: "));
+        app.ui_state.toast = None;
+        app.rotate_config.rule_mut().pages = "2-3".to_string();
+
+        // Removing a rule keeps a valid selection, and the last rule is only cleared
+        handle_rotate_config_input(KeyCode::Char('n'), &mut app);
+        handle_rotate_config_input(KeyCode::Esc, &mut app);
+        handle_rotate_config_input(KeyCode::Char('x'), &mut app);
+        assert_eq!(app.rotate_config.rules.len(), 2);
+        assert_eq!(app.rotate_config.selected_rule, 1);
+        handle_rotate_config_input(KeyCode::Up, &mut app);
+        handle_rotate_config_input(KeyCode::Up, &mut app);
+        assert_eq!(app.rotate_config.selected_rule, 0);
+
+        app.output_location.directory = output_dir.clone();
+        app.rotate_config.output_filename = "pdfcutter_test_rotate.pdf".to_string();
+        handle_rotate_config_input(KeyCode::Enter, &mut app);
+        finish_processing(&mut app);
+        assert_eq!(app.current_screen, CurrentScreen::Result);
+        assert!(app.success_message().is_some(), "{:?}", app.error_message());
+        let rotated = app.result.outputs[0].clone();
+        assert_eq!(app.inspect_cache.page_count(&rotated), Some(7));
+        let before = crate::pdf::inspect::inspect_document("tests/tests_pdf/c.pdf").unwrap();
+        let after = crate::pdf::inspect::inspect_document(&rotated).unwrap();
+        assert_eq!(
+            after.pages[0].rotation,
+            (before.pages[0].rotation + 180) % 360
+        );
+        assert_eq!(
+            after.pages[1].rotation,
+            (before.pages[1].rotation + 90) % 360
+        );
+        std::fs::remove_file(&rotated).unwrap();
+
+        // Extract: pages past the end are rejected, valid ones are copied
+        let mut app = App::new();
+        app.start_operation(
+            OperationMode::Extract,
+            Some("tests/tests_pdf/c.pdf".to_string()),
+        );
+        handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::ExtractConfig);
+
+        handle_extract_config_input(KeyCode::Char('p'), &mut app);
+        handle_extract_config_input(KeyCode::Char('9'), &mut app);
+        handle_extract_config_input(KeyCode::Enter, &mut app);
+        // Page 9 is flagged on the field; running the extraction is refused
+        assert!(app.toast_error().is_none());
+        handle_extract_config_input(KeyCode::Enter, &mut app);
+        assert!(app.toast_error().is_some());
+        assert_eq!(app.current_screen, CurrentScreen::ExtractConfig);
+
+        app.extract_config.pages = "2-3".to_string();
+        handle_extract_config_input(KeyCode::Char('n'), &mut app);
+        assert!(!app.extract_config.options.keep_annotations);
+        app.output_location.directory = output_dir;
+        app.extract_config.output_filename = "pdfcutter_test_extract.pdf".to_string();
+        handle_extract_config_input(KeyCode::Enter, &mut app);
+        finish_processing(&mut app);
+        assert!(app.success_message().is_some(), "{:?}", app.error_message());
+        let extracted = app.result.outputs[0].clone();
+        assert_eq!(app.inspect_cache.page_count(&extracted), Some(2));
+        std::fs::remove_file(&extracted).unwrap();
+    }
+
+    #[test]
+    fn test_compress_config() {
+        let mut app = App::new();
+        app.start_operation(
+            OperationMode::Compress,
+            Some("tests/tests_pdf/c.pdf".to_string()),
+        );
+        handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::CompressConfig);
+        assert_eq!(ui::wizard_step(&app.current_screen), Some(1));
+
+        // Options are toggled and cycled in place
+        assert!(app.compress_config.options.recompress_streams);
+        handle_compress_config_input(KeyCode::Char('s'), &mut app);
+        assert!(!app.compress_config.options.recompress_streams);
+        handle_compress_config_input(KeyCode::Char('s'), &mut app);
+        handle_compress_config_input(KeyCode::Char('m'), &mut app);
+        assert!(app.compress_config.options.strip_metadata);
+        handle_compress_config_input(KeyCode::Char('f'), &mut app);
+        assert!(app.compress_config.options.flatten_annotations);
+        handle_compress_config_input(KeyCode::Char('i'), &mut app);
+        assert_eq!(app.compress_config.options.max_image_dpi, Some(300));
+        handle_compress_config_input(KeyCode::Left, &mut app);
+        handle_compress_config_input(KeyCode::Left, &mut app);
+        assert_eq!(app.compress_config.options.max_image_dpi, Some(72));
+
+        // The output name is checked before compressing
+        app.compress_config.output_filename = "sub/out.pdf".to_string();
+        handle_compress_config_input(KeyCode::Enter, &mut app);
+        assert!(app.toast_error().is_some());
+        assert_eq!(app.current_screen, CurrentScreen::CompressConfig);
+
+        app.output_location.directory = std::env::temp_dir().to_string_lossy().into_owned();
+        app.compress_config.output_filename = "pdfcutter_test_compress.pdf".to_string();
+        handle_compress_config_input(KeyCode::Enter, &mut app);
+        finish_processing(&mut app);
+        assert_eq!(app.current_screen, CurrentScreen::Result);
+        assert!(app.success_message().is_some(), "{:?}", app.error_message());
+        let report = app.result.compression.unwrap();
+        assert!(report.after < report.before);
+        let compressed = app.result.outputs[0].clone();
+        assert_eq!(app.inspect_cache.page_count(&compressed), Some(7));
+
+        // The options are kept when compressing the output again
+        handle_result_input(KeyCode::Char('n'), &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::CompressConfig);
+        assert_eq!(app.compress_config.options.max_image_dpi, Some(72));
+        std::fs::remove_file(&compressed).unwrap();
+    }
+
+    #[test]
+    fn test_watermark_config() {
+        let mut app = App::new();
+        app.start_operation(
+            OperationMode::Watermark,
+            Some("tests/tests_pdf/c.pdf".to_string()),
+        );
+        handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::WatermarkConfig);
+
+        // The opacity slider moves by steps and stays within bounds
+        handle_watermark_config_input(KeyCode::Right, &mut app);
+        assert_eq!(app.watermark_config.opacity, 35);
+        for _ in 0..30 {
+            handle_watermark_config_input(KeyCode::Left, &mut app);
+        }
+        assert_eq!(app.watermark_config.opacity, 5);
+        handle_watermark_config_input(KeyCode::Char('o'), &mut app);
+        assert_ne!(app.watermark_config.position, WatermarkPosition::ALL[0]);
+
+        // Text is required, and choosing an image switches the source
+        app.output_location.directory = std::env::temp_dir().to_string_lossy().into_owned();
+        app.watermark_config.output_filename = "pdfcutter_test_watermark.pdf".to_string();
+        handle_watermark_config_input(KeyCode::Enter, &mut app);
+        assert!(app.toast_error().is_some());
+        handle_watermark_config_input(KeyCode::Char('i'), &mut app);
+        assert!(app.watermark_config.use_image && app.is_editing_text());
+        for c in "missing.png".chars() {
+            handle_watermark_config_input(KeyCode::Char(c), &mut app);
+        }
+        handle_watermark_config_input(KeyCode::Enter, &mut app);
+        handle_watermark_config_input(KeyCode::Enter, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::WatermarkConfig);
+
+        handle_watermark_config_input(KeyCode::Char('t'), &mut app);
+        assert!(!app.watermark_config.use_image);
+        for c in "DRAFT".chars() {
+            handle_watermark_config_input(KeyCode::Char(c), &mut app);
+        }
+        handle_watermark_config_input(KeyCode::Esc, &mut app);
+        app.watermark_config.pages = "2-3".to_string();
+        handle_watermark_config_input(KeyCode::Enter, &mut app);
+        finish_processing(&mut app);
+        assert_eq!(app.current_screen, CurrentScreen::Result);
+        assert!(app.success_message().is_some(), "{:?}", app.error_message());
+        let stamped = app.result.outputs[0].clone();
+        assert_eq!(app.inspect_cache.page_count(&stamped), Some(7));
+
+        // Stamping the output again keeps the watermark but not the pages
+        handle_result_input(KeyCode::Char('n'), &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::WatermarkConfig);
+        assert_eq!(app.watermark_config.text, "DRAFT");
+        assert!(app.watermark_config.pages.is_empty());
+        std::fs::remove_file(&stamped).unwrap();
+    }
+
+    #[test]
+    fn test_default_output_names() {
+        use crate::pdf::output::{DEFAULT_MERGE_OUTPUT, OverwritePolicy};
+
+        let directory = std::env::temp_dir().join("pdfcutter_default_names");
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+        let mut app = App::new();
+        app.output_location.directory = directory.to_string_lossy().into_owned();
+        app.operation_mode = OperationMode::Merge;
+        app.selected_files_mut()
+            .push("tests/tests_pdf/a.pdf".to_string());
+        app.selected_files_mut()
+            .push("tests/tests_pdf/b.pdf".to_string());
+
+        // An empty output field writes to the default name, then keeps the existing file
+        handle_merge_config_input(KeyCode::Enter, &mut app);
+        finish_processing(&mut app);
+        assert!(app.success_message().is_some(), "{:?}", app.error_message());
+        assert!(directory.join(DEFAULT_MERGE_OUTPUT).exists());
+        assert_eq!(
+            app.output_name("", DEFAULT_MERGE_OUTPUT),
+            "output_merged_1.pdf"
+        );
+        app.current_screen = CurrentScreen::MergeConfig;
+        handle_merge_config_input(KeyCode::Enter, &mut app);
+        finish_processing(&mut app);
+        assert!(directory.join("output_merged_1.pdf").exists());
+
+        // A typed name, or the replace policy, writes over the existing file
+        assert_eq!(
+            app.output_name("mine.pdf", DEFAULT_MERGE_OUTPUT),
+            "mine.pdf"
+        );
+        app.overwrite_policy = OverwritePolicy::Replace;
+        assert_eq!(
+            app.output_name("", DEFAULT_MERGE_OUTPUT),
+            DEFAULT_MERGE_OUTPUT
+        );
+
+        // A split prefix is kept apart when any of its files exists
+        app.overwrite_policy = OverwritePolicy::Counter;
+        app.split_config.segments = "1".to_string();
+        assert_eq!(app.split_output_prefix(), "split_output");
+        std::fs::write(directory.join("split_output_001.pdf"), b"").unwrap();
+        assert_eq!(app.split_output_prefix(), "split_output_1");
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_file_browser() {
+        use crate::config::Config;
+        use crate::tui::state::BrowserFocus;
+        use crate::tui::state::browser_state::{BrowserSort, favorites_from_config};
+
+        let directory = std::env::temp_dir().join("pdfcutter_browser");
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(directory.join("scans")).unwrap();
+        std::fs::copy("tests/tests_pdf/a.pdf", directory.join("a.pdf")).unwrap();
+        std::fs::write(directory.join("notes.txt"), b"").unwrap();
+        std::fs::write(directory.join(".hidden.pdf"), b"").unwrap();
+
+        let mut app = App::new();
+        app.start_operation(OperationMode::Merge, None);
+        app.browser.directory = directory.clone();
+        app.config_path = Some(directory.join("config.toml"));
+
+        // Folders come first, other files and hidden ones are left out
+        handle_file_selection_input(KeyCode::Char('b'), KeyModifiers::NONE, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::FileBrowser);
+        let names: Vec<_> = app.browser.entries.iter().map(|e| &e.name[..]).collect();
+        assert_eq!(names, ["scans", "a.pdf"]);
+
+        // Going up selects the folder that was left
+        handle_file_browser_input(KeyCode::Enter, &mut app);
+        assert_eq!(app.browser.directory, directory.join("scans"));
+        assert!(app.browser.entries.is_empty());
+        handle_file_browser_input(KeyCode::Char('f'), &mut app);
+        handle_file_browser_input(KeyCode::Backspace, &mut app);
+        assert_eq!(app.browser.directory, directory);
+        assert_eq!(app.browser.selected, 0);
+
+        // The pinned folder is saved in the config file and reachable from anywhere
+        let config =
+            Config::parse(&std::fs::read_to_string(directory.join("config.toml")).unwrap())
+                .unwrap();
+        let favorites = favorites_from_config(&config);
+        assert_eq!(favorites, app.browser.favorites);
+        assert_eq!(favorites[0].name, "scans");
+        handle_file_browser_input(KeyCode::Char('1'), &mut app);
+        assert_eq!(app.browser.directory, directory.join("scans"));
+        handle_file_browser_input(KeyCode::Tab, &mut app);
+        assert_eq!(app.browser.focus, BrowserFocus::Favorites);
+        handle_file_browser_input(KeyCode::Char('x'), &mut app);
+        assert!(app.browser.favorites.is_empty());
+        assert_eq!(app.browser.focus, BrowserFocus::Entries);
+        let config = std::fs::read_to_string(directory.join("config.toml")).unwrap();
+        assert!(!config.contains("[favorites]"));
+
+        // A PDF is added to the selection
+        handle_file_browser_input(KeyCode::Left, &mut app);
+        handle_file_browser_input(KeyCode::Down, &mut app);
+        handle_file_browser_input(KeyCode::Enter, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::FileSelection);
+        assert_eq!(
+            app.selected_files(),
+            &[directory.join("a.pdf").to_string_lossy().into_owned()]
+        );
+
+        // The browser opens where it was left
+        handle_file_selection_input(KeyCode::Char('b'), KeyModifiers::NONE, &mut app);
+        assert_eq!(app.browser.directory, directory);
+        assert_eq!(app.browser.selected, 1);
+
+        // Sorting and filters keep the selected entry
+        handle_file_browser_input(KeyCode::Char('s'), &mut app);
+        handle_file_browser_input(KeyCode::Char('s'), &mut app);
+        assert_eq!(app.browser.sort, BrowserSort::Size);
+        handle_file_browser_input(KeyCode::Char('a'), &mut app);
+        handle_file_browser_input(KeyCode::Char('h'), &mut app);
+        let names: Vec<_> = app.browser.entries.iter().map(|e| &e.name[..]).collect();
+        assert_eq!(
+            names,
+            ["scans", "a.pdf", ".hidden.pdf", "config.toml", "notes.txt"]
+        );
+        assert_eq!(app.browser.selected, 1);
+        assert_eq!(app.browser.entries[1].size, 69286);
+
+        // Other files are listed but cannot be added
+        handle_file_browser_input(KeyCode::End, &mut app);
+        handle_file_browser_input(KeyCode::Enter, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::FileBrowser);
+        assert_eq!(app.selected_files().len(), 1);
+
+        handle_file_browser_input(KeyCode::Char('s'), &mut app);
+        handle_file_browser_input(KeyCode::Char('a'), &mut app);
+        handle_file_browser_input(KeyCode::Char('h'), &mut app);
+        assert_eq!(app.browser.sort, BrowserSort::Name);
+        assert_eq!(app.browser.entries.len(), 2);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_change_working_directory() {
+        use crate::pdf::operation::Operation;
+        use state::FileValidation;
+
+        // Staying in the same directory, since the other tests use relative paths
+        let cwd = std::env::current_dir().unwrap();
+        let mut app = App::new();
+        app.start_operation(OperationMode::Merge, None);
+        app.browse(cwd.clone(), None).unwrap();
+        app.current_screen = CurrentScreen::FileBrowser;
+        app.file_state.add_file("tests/tests_pdf/a.pdf".to_string());
+        app.validation
+            .set("tests/tests_pdf/a.pdf", FileValidation::Pending);
+        app.queue.push(Operation::Extract {
+            input: "tests/tests_pdf/c.pdf".to_string(),
+            output: "extracted.pdf".to_string(),
+            pages: vec![1],
+            options: Default::default(),
+        });
+
+        // Not while a selected file is being loaded
+        handle_file_browser_input(KeyCode::Char('w'), &mut app);
+        assert!(app.toast_error().is_some());
+        assert_eq!(app.selected_files()[0], "tests/tests_pdf/a.pdf");
+        app.ui_state.dismiss_toast();
+
+        // Selected and queued files keep pointing to the same files, with their status
+        app.validation
+            .set("tests/tests_pdf/a.pdf", FileValidation::Valid);
+        handle_file_browser_input(KeyCode::Char('w'), &mut app);
+        assert!(app.toast_error().is_none());
+        let absolute = cwd
+            .join("tests/tests_pdf/a.pdf")
+            .to_string_lossy()
+            .into_owned();
+        assert_eq!(app.selected_files(), &vec![absolute.clone()]);
+        assert_eq!(
+            app.validation.status(&absolute),
+            Some(&FileValidation::Valid)
+        );
+        assert_eq!(
+            app.queue.steps[0].operation.outputs().unwrap(),
+            vec![cwd.join("extracted.pdf").to_string_lossy()]
+        );
+
+        // A folder that cannot be entered is reported
+        let missing = cwd.join("pdfcutter_missing_directory");
+        assert!(matches!(
+            app.change_working_directory(&missing),
+            Err(errors::TuiError::UnusableWorkingDirectory { .. })
+        ));
+        assert_eq!(std::env::current_dir().unwrap(), cwd);
+    }
+
+    #[test]
+    fn test_space_warning() {
+        use crate::pdf::space::{SpaceCheck, SpaceWarning};
+        use crossterm::event::KeyEvent;
+
+        let mut app = App::new();
+        app.space_check = SpaceCheck {
+            size_warning: Some(1024),
+        };
+        app.start_operation(
+            OperationMode::Compress,
+            Some("tests/tests_pdf/c.pdf".to_string()),
+        );
+        handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        app.output_location.directory = std::env::temp_dir().to_string_lossy().into_owned();
+        app.compress_config.output_filename = "pdfcutter_test_space.pdf".to_string();
+
+        // The large output is reported before anything is written, Esc goes back
+        handle_compress_config_input(KeyCode::Enter, &mut app);
+        let prompt = app.space_prompt.as_ref().unwrap();
+        assert!(matches!(
+            prompt.warnings[..],
+            [SpaceWarning::LargeOutput { .. }]
+        ));
+        assert_eq!(app.current_screen, CurrentScreen::CompressConfig);
+        let esc = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+        assert!(handle_overlay_input(&esc, &mut app));
+        assert!(app.space_prompt.is_none());
+        assert_eq!(app.current_screen, CurrentScreen::CompressConfig);
+
+        // Enter in the warning runs the operation anyway
+        handle_compress_config_input(KeyCode::Enter, &mut app);
+        let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+        assert!(!handle_overlay_input(&enter, &mut app));
+        handle_compress_config_input(KeyCode::Enter, &mut app);
+        finish_processing(&mut app);
+        assert!(app.space_prompt.is_none());
+        assert_eq!(app.current_screen, CurrentScreen::Result);
+        assert!(app.success_message().is_some(), "{:?}", app.error_message());
+        std::fs::remove_file(&app.result.outputs[0]).unwrap();
+    }
+
+    #[test]
+    fn test_in_place_confirmation() {
+        use crossterm::event::KeyEvent;
+
+        let directory = std::env::temp_dir().join("pdfcutter_in_place");
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+        let input = directory.join("report.pdf");
+        std::fs::copy("tests/tests_pdf/c.pdf", &input).unwrap();
+        let input = input.to_string_lossy().into_owned();
+        let press = |app: &mut App, code: KeyCode| {
+            handle_overlay_input(&KeyEvent::new(code, KeyModifiers::NONE), app)
+        };
+
+        let mut app = App::new();
+        app.start_operation(OperationMode::Compress, Some(input.clone()));
+        handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        app.output_location.directory = directory.to_string_lossy().into_owned();
+        app.compress_config.output_filename = "report.pdf".to_string();
+
+        // Writing over the input waits for the word to be typed, Esc goes back
+        handle_compress_config_input(KeyCode::Enter, &mut app);
+        assert_eq!(app.in_place_prompt.as_ref().unwrap().files, [&input[..]]);
+        assert!(press(&mut app, KeyCode::Esc));
+        assert!(app.in_place_prompt.is_none());
+
+        // A wrong word is refused
+        handle_compress_config_input(KeyCode::Enter, &mut app);
+        assert!(press(&mut app, KeyCode::Char('y')));
+        assert!(press(&mut app, KeyCode::Enter));
+        assert!(!app.in_place_prompt.as_ref().unwrap().confirmed);
+        assert!(press(&mut app, KeyCode::Backspace));
+
+        // Once confirmed, the input is backed up and the screen runs the operation
+        for c in "overwrite".chars() {
+            assert!(press(&mut app, KeyCode::Char(c)));
+        }
+        assert!(press(&mut app, KeyCode::Tab));
+        assert!(!press(&mut app, KeyCode::Enter));
+        handle_compress_config_input(KeyCode::Enter, &mut app);
+        finish_processing(&mut app);
+        assert!(app.in_place_prompt.is_none());
+        assert_eq!(app.current_screen, CurrentScreen::Result);
+        assert!(app.success_message().is_some(), "{:?}", app.error_message());
+        assert_eq!(
+            std::fs::read(format!("{}.bak", input)).unwrap(),
+            std::fs::read("tests/tests_pdf/c.pdf").unwrap()
+        );
+
+        // Adding to the queue asks too, then shows the queue
+        app.current_screen = CurrentScreen::CompressConfig;
+        handle_compress_config_input(KeyCode::Char('a'), &mut app);
+        assert!(app.in_place_prompt.as_ref().unwrap().queue);
+        for c in "OVERWRITE".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        assert!(press(&mut app, KeyCode::Enter));
+        assert!(app.in_place_prompt.is_none());
+        assert_eq!(app.current_screen, CurrentScreen::Queue);
+        assert_eq!(app.queue.steps.len(), 1);
+
+        // With a backup suffix, an existing output is moved aside instead of being replaced
+        app.backup_suffix = Some("orig".to_string());
+        std::fs::write(directory.join("copy.pdf"), b"old").unwrap();
+        app.compress_config.output_filename = "copy.pdf".to_string();
+        app.current_screen = CurrentScreen::CompressConfig;
+        handle_compress_config_input(KeyCode::Enter, &mut app);
+        finish_processing(&mut app);
+        assert_eq!(app.current_screen, CurrentScreen::Result);
+        assert_eq!(
+            std::fs::read(directory.join("copy.pdf.orig")).unwrap(),
+            b"old"
+        );
+        assert!(std::fs::metadata(directory.join("copy.pdf")).unwrap().len() > 3);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_handle_result_input() {
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::Result;
+        app.set_success("Success!".to_string());
+
+        // Test returning to main menu
+        handle_result_input(KeyCode::Enter, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::Main);
+
+        // Test with Esc
+        app.current_screen = CurrentScreen::Result;
+        handle_result_input(KeyCode::Esc, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::Main);
+
+        // Test with Space
+        app.current_screen = CurrentScreen::Result;
+        handle_result_input(KeyCode::Char(' '), &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::Main);
+    }
+
+    #[test]
+    fn test_result_failure_details() {
+        use handlers::result::{failure_details, failure_report};
+
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::Result;
+        app.set_success("Success!".to_string());
+        assert!(failure_details(&app).is_none());
+        handle_result_input(KeyCode::Char('d'), &mut app);
+        assert!(!app.result.show_details);
+
+        // The details keep every cause, the message only the outermost one
+        let error = anyhow!("No such file").context("Could not open 'a.pdf'");
+        app.set_outputs(Vec::new());
+        app.set_failure(format!("Failed to merge PDFs: {}", error), &error);
+        let details = failure_details(&app).unwrap();
+        assert!(details.contains("Could not open 'a.pdf'"));
+        assert!(details.contains("No such file"));
+        let report = failure_report(&app).unwrap();
+        assert!(report.starts_with("Failed to merge PDFs: Could not open 'a.pdf'"));
+        assert!(report.contains("pdf-cutter"));
+
+        // D toggles the details view, Esc closes it before leaving the screen
+        handle_result_input(KeyCode::Char('d'), &mut app);
+        assert!(app.result.show_details);
+        handle_result_input(KeyCode::Esc, &mut app);
+        assert!(!app.result.show_details);
+        assert_eq!(app.current_screen, CurrentScreen::Result);
+        handle_result_input(KeyCode::Esc, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::Main);
+
+        // A new result forgets the details of the failure
+        app.set_outputs(Vec::new());
+        assert!(app.result.details.is_none());
+    }
+
+    #[test]
+    fn test_result_follow_up_operation() {
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::Result;
+        app.set_outputs(vec![
+            "tests/tests_pdf/b.pdf".to_string(),
+            "tests/tests_pdf/c.pdf".to_string(),
+        ]);
+        assert_eq!(
+            app.inspect_cache.page_count("tests/tests_pdf/c.pdf"),
+            Some(7)
+        );
+
+        handle_result_input(KeyCode::Down, &mut app);
+        handle_result_input(KeyCode::Down, &mut app);
+        assert_eq!(app.result.selected, 1);
+
+        // Opening is deferred to the event loop, which suspends the terminal first
+        handle_result_input(KeyCode::Char('o'), &mut app);
+        assert_eq!(
+            app.result.pending_open.as_deref(),
+            Some("tests/tests_pdf/c.pdf")
+        );
+        // F opens the folder containing it instead
+        handle_result_input(KeyCode::Char('f'), &mut app);
+        assert_eq!(app.result.pending_open.as_deref(), Some("tests/tests_pdf"));
+        assert_eq!(handlers::result::output_folder("a.pdf"), ".");
+        app.result.pending_open = None;
+
+        // One keystroke starts a single-file operation on the selected output,
+        // keeping the output directory and the operation settings
+        app.operation_mode = OperationMode::Rotate;
+        app.output_location.directory = "tests".to_string();
+        app.rotate_config.rule_mut().angle = 270;
+        app.rotate_config.rule_mut().pages = "1".to_string();
+        handle_result_input(KeyCode::Char('n'), &mut app);
+        assert_eq!(app.operation_mode, OperationMode::Rotate);
+        assert_eq!(app.current_screen, CurrentScreen::RotateConfig);
+        assert_eq!(
+            app.selected_files(),
+            &vec!["tests/tests_pdf/c.pdf".to_string()]
+        );
+        assert_eq!(app.first_file_page_count(), Some(7));
+        assert_eq!(app.output_location.directory, "tests");
+        assert_eq!(app.rotate_config.rule().angle, 270);
+        assert!(app.rotate_config.rule().pages.is_empty());
+        assert!(app.result.outputs.is_empty());
+
+        // Merge keeps the file selection open to add more files
+        app.current_screen = CurrentScreen::Result;
+        app.set_outputs(vec!["tests/tests_pdf/b.pdf".to_string()]);
+        handle_result_input(KeyCode::Char('1'), &mut app);
+        assert_eq!(app.operation_mode, OperationMode::Merge);
+        assert_eq!(app.current_screen, CurrentScreen::FileSelection);
+        assert_eq!(
+            app.selected_files(),
+            &vec!["tests/tests_pdf/b.pdf".to_string()]
+        );
+
+        // Without outputs there is nothing to start from
+        app.current_screen = CurrentScreen::Result;
+        handle_result_input(KeyCode::Char('2'), &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::Result);
+    }
+
+    #[test]
+    fn test_operation_queue() {
+        let output_dir = std::env::temp_dir().to_string_lossy().into_owned();
+
+        // Running an empty queue is an error
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::Queue;
+        handle_queue_input(KeyCode::Enter, &mut app);
+        assert!(app.toast_error().is_some());
+        assert!(!app.queue.running);
+        app.ui_state.dismiss_toast();
+
+        // Step 1: extract pages from an existing file
+        app.start_operation(
+            OperationMode::Extract,
+            Some("tests/tests_pdf/c.pdf".to_string()),
+        );
+        handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        app.extract_config.pages = "1-3".to_string();
+        app.output_location.directory = output_dir.clone();
+        app.extract_config.output_filename = "pdfcutter_test_queue_extract.pdf".to_string();
+        handle_extract_config_input(KeyCode::Char('a'), &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::Queue);
+        assert_eq!(app.queue.steps.len(), 1);
+        let extracted = app.queue.steps[0].operation.outputs().unwrap()[0].clone();
+        assert!(!std::path::Path::new(&extracted).exists());
+
+        // Step 2: rotate the output of step 1, which does not exist yet
+        app.start_operation(OperationMode::Rotate, None);
+        handle_file_selection_input(KeyCode::Tab, KeyModifiers::NONE, &mut app);
+        for c in extracted.chars() {
+            handle_file_selection_input(KeyCode::Char(c), KeyModifiers::NONE, &mut app);
+        }
+        handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        assert_eq!(app.selected_files(), &vec![extracted.clone()]);
+        handle_file_selection_input(KeyCode::Enter, KeyModifiers::NONE, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::RotateConfig);
+        app.output_location.directory = output_dir;
+        app.rotate_config.output_filename = "pdfcutter_test_queue_rotate.pdf".to_string();
+        handle_rotate_config_input(KeyCode::Char('a'), &mut app);
+        assert_eq!(app.queue.steps.len(), 2);
+
+        // Run both steps, as the event loop does
+        handle_queue_input(KeyCode::Enter, &mut app);
+        assert!(app.queue.running);
+        assert!(app.queue.eta().is_none());
+        while app.queue.finished_count() == 0 {
+            advance_queue(&mut app);
+        }
+        assert!(app.queue.eta().is_some());
+        run_queue(&mut app);
+        assert_eq!(app.queue.finished_count(), 2);
+        // The run statistics count the pages of both outputs and stop with the run
+        assert_eq!(app.queue.pages_written, 6);
+        assert!(app.queue.run_time.is_some());
+        assert!(app.queue.eta().is_none());
+        let StepStatus::Done(outputs) = &app.queue.steps[1].status else {
+            panic!("{:?}", app.queue.steps[1].status);
+        };
+        let rotated = outputs[0].clone();
+        assert_eq!(app.inspect_cache.page_count(&rotated), Some(3));
+
+        // A failing step does not keep the steps that do not read its outputs from running
+        std::fs::remove_file(&extracted).unwrap();
+        std::fs::remove_file(&rotated).unwrap();
+        app.queue.steps.swap(0, 1);
+        handle_queue_input(KeyCode::Char('r'), &mut app);
+        run_queue(&mut app);
+        assert!(matches!(app.queue.steps[0].status, StepStatus::Failed(_)));
+        assert!(matches!(app.queue.steps[1].status, StepStatus::Done(_)));
+        assert_eq!(app.queue.summary(), (1, 1, 0));
+
+        // F runs the failed step again, now that its input exists
+        handle_queue_input(KeyCode::Char('f'), &mut app);
+        assert!(app.queue.running);
+        run_queue(&mut app);
+        assert_eq!(app.queue.summary(), (2, 0, 0));
+        assert!(std::path::Path::new(&rotated).exists());
+        handle_queue_input(KeyCode::Char('f'), &mut app);
+        assert!(!app.queue.running);
+        assert!(app.toast_error().is_some());
+        app.ui_state.dismiss_toast();
+
+        // The steps reading the outputs of a failed step are skipped
+        std::fs::remove_file(&extracted).unwrap();
+        std::fs::remove_file(&rotated).unwrap();
+        app.queue.steps.swap(0, 1);
+        if let crate::pdf::operation::Operation::Extract { input, .. } =
+            &mut app.queue.steps[0].operation
+        {
+            *input = "tests/tests_pdf/missing.pdf".to_string();
+        }
+        handle_queue_input(KeyCode::Char('r'), &mut app);
+        run_queue(&mut app);
+        assert!(matches!(app.queue.steps[0].status, StepStatus::Failed(_)));
+        assert_eq!(app.queue.steps[1].status, StepStatus::Skipped);
+        assert!(!std::path::Path::new(&extracted).exists());
+
+        // When configured to, the run stops at the first failure
+        app.queue.steps.swap(0, 1);
+        app.queue.stop_on_error = true;
+        handle_queue_input(KeyCode::Char('r'), &mut app);
+        run_queue(&mut app);
+        assert!(matches!(app.queue.steps[0].status, StepStatus::Failed(_)));
+        assert_eq!(app.queue.steps[1].status, StepStatus::Skipped);
+
+        // C cancels a run before its next step, keeping the steps
+        handle_queue_input(KeyCode::Char('r'), &mut app);
+        handle_queue_input(KeyCode::Char('c'), &mut app);
+        assert!(!app.queue.running);
+        assert_eq!(app.queue.steps.len(), 2);
+        assert_eq!(app.queue.finished_count(), 2);
+        assert_eq!(app.queue.steps[0].status, StepStatus::Skipped);
+
+        handle_queue_input(KeyCode::Char('x'), &mut app);
+        assert_eq!(app.queue.steps.len(), 1);
+        handle_queue_input(KeyCode::Char('c'), &mut app);
+        assert!(app.queue.steps.is_empty());
+        handle_queue_input(KeyCode::Esc, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::Main);
+    }
+
+    #[test]
+    fn test_cancel_processing() {
+        use crate::pdf::operation::Operation;
+        use state::Job;
+        use std::sync::mpsc;
+
+        let directory = std::env::temp_dir().join("pdfcutter_cancel_processing");
+        let _ = std::fs::remove_dir_all(&directory);
+        let output = directory.join("new").join("merged.pdf");
+        let merge = Operation::Merge {
+            inputs: vec!["tests/tests_pdf/a.pdf".to_string(); 2],
+            output: output.to_string_lossy().into_owned(),
+        };
+
+        // The operation waits to be released, so it is cancelled while it runs
+        let mut app = App::new();
+        app.operation_mode = OperationMode::Merge;
+        app.current_screen = CurrentScreen::MergeConfig;
+        app.merge_config.output_filename = "merged.pdf".to_string();
+        let (release, released) = mpsc::channel::<()>();
+        let operation = merge.clone();
+        processing::start_processing(
+            &mut app,
+            "merge".to_string(),
+            |e| e.to_string(),
+            move || {
+                let _ = released.recv();
+                let outputs = operation.run()?;
+                Ok(Box::new(move |app: &mut App| app.set_outputs(outputs)) as state::Completion)
+            },
+        );
+        assert_eq!(app.current_screen, CurrentScreen::Processing);
+        assert!(app.is_busy());
+        handle_processing_input(KeyCode::Esc, &mut app);
+        release.send(()).unwrap();
+        finish_processing(&mut app);
+        // Back to the configuration, unchanged, without the output or its folder
+        assert_eq!(app.current_screen, CurrentScreen::MergeConfig);
+        assert_eq!(app.merge_config.output_filename, "merged.pdf");
+        assert!(app.toast_error().is_none());
+        assert!(!directory.join("new").exists());
+
+        // Cancelling the queue skips the running step and stops the run
+        app.queue.push(merge);
+        app.queue.start();
+        let (release, released) = mpsc::channel::<()>();
+        let operation = app.queue.steps[0].operation.clone();
+        app.queue.job = Some((
+            0,
+            Job::spawn(move || {
+                let _ = released.recv();
+                operation.run()
+            }),
+        ));
+        handle_queue_input(KeyCode::Char('c'), &mut app);
+        assert!(app.queue.running);
+        release.send(()).unwrap();
+        run_queue(&mut app);
+        assert_eq!(app.queue.steps[0].status, StepStatus::Skipped);
+        assert!(!directory.join("new").exists());
+        let _ = std::fs::remove_dir_all(&directory);
+    }
+
+    #[test]
+    fn test_command_palette() {
+        use crossterm::event::KeyEvent;
+        use handlers::palette::{
+            PaletteAction, handle_palette_input, is_palette_shortcut, palette_matches,
+        };
+
+        let mut app = App::new();
+        assert!(is_palette_shortcut(&KeyEvent::new(
+            KeyCode::Char('k'),
+            KeyModifiers::CONTROL
+        )));
+        assert!(!is_palette_shortcut(&KeyEvent::new(
+            KeyCode::Char('k'),
+            KeyModifiers::NONE
+        )));
+
+        // Typing filters the actions, Enter runs the best match
+        app.palette.show();
+        for c in "rot".chars() {
+            handle_palette_input(KeyCode::Char(c), &mut app);
+        }
+        assert_eq!(
+            palette_matches(&app).first(),
+            Some(&PaletteAction::StartOperation(OperationMode::Rotate))
+        );
+        handle_palette_input(KeyCode::Enter, &mut app);
+        assert!(!app.palette.open);
+        assert_eq!(app.operation_mode, OperationMode::Rotate);
+        assert_eq!(app.current_screen, CurrentScreen::FileSelection);
+
+        // Screen specific actions are only offered where they apply
+        app.palette.show();
+        assert!(palette_matches(&app).contains(&PaletteAction::AddFile));
+        assert!(!palette_matches(&app).contains(&PaletteAction::ToggleNamedSegments));
+        handle_palette_input(KeyCode::Esc, &mut app);
+        assert!(!app.palette.open);
+
+        app.start_operation(OperationMode::Split, None);
+        app.current_screen = CurrentScreen::SplitConfig;
+        app.split_config.segments = "1-2".to_string();
+        app.palette.show();
+        for c in "named".chars() {
+            handle_palette_input(KeyCode::Char(c), &mut app);
+        }
+        handle_palette_input(KeyCode::Enter, &mut app);
+        assert!(app.split_config.use_named_segments);
+        assert_eq!(app.split_config.segments, "1-2");
+
+        // Nothing happens when no action matches
+        app.palette.show();
+        for c in "zzz".chars() {
+            handle_palette_input(KeyCode::Char(c), &mut app);
+        }
+        assert!(palette_matches(&app).is_empty());
+        handle_palette_input(KeyCode::Enter, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::SplitConfig);
+    }
+
+    #[test]
+    fn test_go_home_and_start_over() {
+        use crossterm::event::KeyEvent;
+        use handlers::palette::{PaletteAction, handle_palette_input, palette_matches};
+        let ctrl_h = KeyEvent::new(KeyCode::Char('h'), KeyModifiers::CONTROL);
+        let shift_h = KeyEvent::new(KeyCode::Char('H'), KeyModifiers::SHIFT);
+
+        // Without any file selected, nothing is lost and no confirmation is asked
+        let mut app = App::new();
+        app.start_operation(OperationMode::Split, None);
+        assert!(handle_overlay_input(&ctrl_h, &mut app));
+        assert_eq!(app.current_screen, CurrentScreen::Main);
+        assert_eq!(app.operation_mode, OperationMode::None);
+        assert!(!handle_overlay_input(&ctrl_h, &mut app));
+
+        // With files selected, the popup asks first and any other key stays
+        app.start_operation(OperationMode::Split, None);
+        app.add_file("tests/tests_pdf/c.pdf".to_string());
+        app.current_screen = CurrentScreen::SplitConfig;
+        app.split_config.segments = "1-2".to_string();
+        assert!(handle_overlay_input(&shift_h, &mut app));
+        assert_eq!(
+            app.ui_state.discard_prompt,
+            Some(state::ui_state::DiscardAction::GoHome)
+        );
+        let n = KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE);
+        assert!(handle_overlay_input(&n, &mut app));
+        assert!(app.ui_state.discard_prompt.is_none());
+        assert_eq!(app.current_screen, CurrentScreen::SplitConfig);
+        assert_eq!(app.split_config.segments, "1-2");
+
+        // H is a regular character while typing
+        app.split_config.editing_prefix = true;
+        assert!(!handle_overlay_input(&shift_h, &mut app));
+        app.split_config.editing_prefix = false;
+
+        // Start over keeps the operation and drops its files and settings
+        app.palette.show();
+        for c in "start over".chars() {
+            handle_palette_input(KeyCode::Char(c), &mut app);
+        }
+        assert_eq!(
+            palette_matches(&app).first(),
+            Some(&PaletteAction::StartOver)
+        );
+        handle_palette_input(KeyCode::Enter, &mut app);
+        let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+        assert!(handle_overlay_input(&enter, &mut app));
+        assert_eq!(app.operation_mode, OperationMode::Split);
+        assert_eq!(app.current_screen, CurrentScreen::FileSelection);
+        assert!(app.files_is_empty());
+        assert!(app.split_config.segments.is_empty());
+
+        // Once the operation has run, its result is not asked about
+        app.add_file("tests/tests_pdf/c.pdf".to_string());
+        app.set_success("done".to_string());
+        app.current_screen = CurrentScreen::Result;
+        assert!(handle_overlay_input(&ctrl_h, &mut app));
+        assert_eq!(app.current_screen, CurrentScreen::Main);
+    }
+
+    #[test]
+    fn test_leaving_a_typed_configuration() {
+        use crossterm::event::KeyEvent;
+        use state::ui_state::DiscardAction;
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+
+        // Nothing typed: Esc goes straight back
+        let mut app = App::new();
+        app.start_operation(OperationMode::Extract, None);
+        app.add_file("tests/tests_pdf/c.pdf".to_string());
+        app.current_screen = CurrentScreen::ExtractConfig;
+        handle_extract_config_input(KeyCode::Esc, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::FileSelection);
+
+        // Typed pages: Esc asks, another key stays with the pages
+        app.current_screen = CurrentScreen::ExtractConfig;
+        app.extract_config.pages = "1-3".to_string();
+        handle_extract_config_input(KeyCode::Esc, &mut app);
+        assert_eq!(
+            app.ui_state.discard_prompt,
+            Some(DiscardAction::Configuration)
+        );
+        assert!(handle_overlay_input(&key(KeyCode::Esc), &mut app));
+        assert_eq!(app.current_screen, CurrentScreen::ExtractConfig);
+        assert_eq!(app.extract_config.pages, "1-3");
+
+        // Confirming clears them and goes back to the files, which are kept
+        handle_extract_config_input(KeyCode::Esc, &mut app);
+        assert!(handle_overlay_input(&key(KeyCode::Char('y')), &mut app));
+        assert_eq!(app.current_screen, CurrentScreen::FileSelection);
+        assert!(app.extract_config.pages.is_empty());
+        assert_eq!(app.files_len(), 1);
+
+        // The confirmation can be turned off in the config file
+        let config = crate::config::Config::parse("confirm_discard = false").unwrap();
+        app.ui_state.confirm_discard =
+            state::ui_state::confirm_discard_from_config(&config).unwrap();
+        app.current_screen = CurrentScreen::ExtractConfig;
+        app.extract_config.pages = "2".to_string();
+        handle_extract_config_input(KeyCode::Esc, &mut app);
+        assert!(app.ui_state.discard_prompt.is_none());
+        assert_eq!(app.current_screen, CurrentScreen::FileSelection);
+        assert_eq!(app.extract_config.pages, "2");
+        app.request_discard(DiscardAction::GoHome);
+        assert_eq!(app.current_screen, CurrentScreen::Main);
+    }
+
+    #[test]
+    fn test_cheat_sheet_and_help() {
+        use crossterm::event::KeyEvent;
+        let question_mark = KeyEvent::new(KeyCode::Char('?'), KeyModifiers::SHIFT);
+
+        // '?' opens the cheat-sheet, any key closes it without reaching the screen
+        let mut app = App::new();
+        app.start_operation(OperationMode::Split, None);
+        app.current_screen = CurrentScreen::SplitConfig;
+        assert!(handle_overlay_input(&question_mark, &mut app));
+        assert!(app.ui_state.show_cheat_sheet);
+        let space = KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE);
+        assert!(handle_overlay_input(&space, &mut app));
+        assert!(!app.ui_state.show_cheat_sheet);
+        assert!(!app.split_config.use_named_segments);
+
+        // While typing, '?' is a regular character
+        app.split_config.editing_prefix = true;
+        assert!(!handle_overlay_input(&question_mark, &mut app));
+        assert!(!app.ui_state.show_cheat_sheet);
+
+        // The help screen scrolls by line or by section, and resets when left
+        app.current_screen = CurrentScreen::Help;
+        handle_help_input(KeyCode::Down, &mut app);
+        handle_help_input(KeyCode::PageDown, &mut app);
+        assert_eq!(app.ui_state.help_scroll, 11);
+        let help = ui::help_content();
+        handle_help_input(KeyCode::Home, &mut app);
+        handle_help_input(KeyCode::Tab, &mut app);
+        assert_eq!(app.ui_state.help_scroll as usize, help.section_starts[1]);
+        handle_help_input(KeyCode::Tab, &mut app);
+        handle_help_input(KeyCode::BackTab, &mut app);
+        assert_eq!(app.ui_state.help_scroll as usize, help.section_starts[1]);
+        handle_help_input(KeyCode::End, &mut app);
+        handle_help_input(KeyCode::Down, &mut app);
+        assert_eq!(app.ui_state.help_scroll as usize, help.lines.len() - 1);
+        handle_help_input(KeyCode::Esc, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::Main);
+        assert_eq!(app.ui_state.help_scroll, 0);
+    }
+
+    #[test]
+    fn test_onboarding() {
+        use crate::config::{Config, DEFAULT_CONFIG};
+
+        let directory = std::env::temp_dir().join("pdfcutter_onboarding");
+        let _ = std::fs::remove_dir_all(&directory);
+        let config_path = directory.join("config.toml");
+
+        let mut app = App::new();
+        app.config_path = Some(config_path.clone());
+        onboarding::start_onboarding(&mut app);
+        assert_eq!(app.current_screen, CurrentScreen::Onboarding);
+
+        // The pages are walked through back and forth, without going before the first one
+        let pages = ui::onboarding_pages(&app).len();
+        handle_onboarding_input(KeyCode::Left, &mut app);
+        assert_eq!(app.ui_state.onboarding_page, 0);
+        for _ in 1..pages {
+            handle_onboarding_input(KeyCode::Right, &mut app);
+        }
+        assert_eq!(app.ui_state.onboarding_page, pages - 1);
+        handle_onboarding_input(KeyCode::Backspace, &mut app);
+        assert_eq!(app.ui_state.onboarding_page, pages - 2);
+        assert!(!config_path.exists());
+
+        // Finishing the tour creates the default config, so it is not shown again
+        handle_onboarding_input(KeyCode::Enter, &mut app);
+        handle_onboarding_input(KeyCode::Enter, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::Main);
+        assert_eq!(app.ui_state.onboarding_page, 0);
+        assert_eq!(
+            std::fs::read_to_string(&config_path).unwrap(),
+            DEFAULT_CONFIG
+        );
+
+        // Replayed from the palette, skipping it keeps the edited config
+        std::fs::write(&config_path, "theme = \"light\"\n").unwrap();
+        onboarding::start_onboarding(&mut app);
+        handle_onboarding_input(KeyCode::Right, &mut app);
+        handle_onboarding_input(KeyCode::Esc, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::Main);
+        let config = Config::parse(&std::fs::read_to_string(&config_path).unwrap()).unwrap();
+        assert_eq!(config.get("theme"), Some("light"));
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_log_viewer() {
+        use crossterm::event::KeyEvent;
+        use log::Level;
+        let l = KeyEvent::new(KeyCode::Char('l'), KeyModifiers::NONE);
+
+        // 'L' opens the viewer from any screen and goes back to it
+        let mut app = App::new();
+        app.start_operation(OperationMode::Delete, None);
+        app.current_screen = CurrentScreen::DeleteConfig;
+        assert!(handle_overlay_input(&l, &mut app));
+        assert_eq!(app.current_screen, CurrentScreen::Logs);
+
+        handle_logs_input(KeyCode::Char('f'), &mut app);
+        assert_eq!(app.logs.min_level, Level::Debug);
+        handle_logs_input(KeyCode::Char('f'), &mut app);
+        assert_eq!(app.logs.min_level, Level::Error);
+
+        handle_logs_input(KeyCode::Esc, &mut app);
+        assert_eq!(app.current_screen, CurrentScreen::DeleteConfig);
+
+        // While typing, 'l' is a regular character
+        app.delete_config.editing_output = true;
+        assert!(!handle_overlay_input(&l, &mut app));
+        assert_eq!(app.current_screen, CurrentScreen::DeleteConfig);
+    }
+
+    #[test]
+    fn test_ctrl_c_requests_exit() {
+        use crossterm::event::KeyEvent;
+        use terminal::is_interrupt_key;
+
+        assert!(is_interrupt_key(&KeyEvent::new(
+            KeyCode::Char('c'),
+            KeyModifiers::CONTROL
+        )));
+        assert!(!is_interrupt_key(&KeyEvent::new(
+            KeyCode::Char('c'),
+            KeyModifiers::NONE
+        )));
+
+        // Overlays are closed and a running queue does not start another step
+        let mut app = App::new();
+        app.current_screen = CurrentScreen::Queue;
+        app.queue.push(crate::pdf::operation::Operation::Merge {
+            inputs: vec!["a.pdf".to_string(), "b.pdf".to_string()],
+            output: "ab.pdf".to_string(),
+        });
+        app.queue.running = true;
+        app.palette.show();
+        app.request_exit();
+        assert_eq!(app.current_screen, CurrentScreen::Exiting);
+        assert!(!app.palette.open);
+        assert!(!app.queue.running);
+        assert_eq!(app.queue.steps[0].status, StepStatus::Skipped);
     }
 }
@@ -0,0 +1,202 @@
+use crate::config::Config;
+use crate::pdf::output::{contract_home, expand_home};
+use crate::tui::utils::natural_cmp;
+use std::cmp::Ordering;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Config section of the directories pinned in the file browser, as `name = "path"` pairs.
+pub const FAVORITES_SECTION: &str = "favorites";
+
+/// Part of the file browser the keys go to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BrowserFocus {
+    Entries,
+    Favorites,
+}
+
+/// Order of the browser entries; folders always come before files.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BrowserSort {
+    /// Natural order of the names (scan_2 before scan_10).
+    Name,
+    /// Most recently modified first.
+    Modified,
+    /// Largest first; folders keep their name order.
+    Size,
+}
+
+impl BrowserSort {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Name => Self::Modified,
+            Self::Modified => Self::Size,
+            Self::Size => Self::Name,
+        }
+    }
+
+    /// English name of the order, translated when displayed.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Modified => "date modified",
+            Self::Size => "size",
+        }
+    }
+
+    /**
+     * Compare two entries: folders first, then by this order, then by name.
+     * @param a The first entry.
+     * @param b The second entry.
+     * @returns The ordering of a relative to b.
+     */
+    pub fn compare(self, a: &BrowserEntry, b: &BrowserEntry) -> Ordering {
+        let by_order = match self {
+            Self::Name => Ordering::Equal,
+            Self::Modified => b.modified.cmp(&a.modified),
+            Self::Size if a.is_dir && b.is_dir => Ordering::Equal,
+            Self::Size => b.size.cmp(&a.size),
+        };
+        b.is_dir
+            .cmp(&a.is_dir)
+            .then(by_order)
+            .then_with(|| natural_cmp(&a.name, &b.name))
+    }
+}
+
+/// A directory pinned in the browser sidebar.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Favorite {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// A folder or PDF file listed in the browser.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrowserEntry {
+    pub name: String,
+    pub path: PathBuf,
+    pub is_dir: bool,
+    /// Size in bytes, 0 for a folder.
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+}
+
+/// File browser opened from the file selection, with the pinned directories in a sidebar.
+#[derive(Debug, Clone)]
+pub struct BrowserState {
+    /// Directory listed; kept when the browser is closed so browsing resumes where it stopped.
+    pub directory: PathBuf,
+    pub entries: Vec<BrowserEntry>,
+    pub selected: usize,
+    pub favorites: Vec<Favorite>,
+    pub favorite_index: usize,
+    pub focus: BrowserFocus,
+    pub sort: BrowserSort,
+    /// Whether entries starting with a dot are listed.
+    pub show_hidden: bool,
+    /// Whether files other than PDFs are listed too.
+    pub show_all_files: bool,
+}
+
+impl BrowserState {
+    pub fn new() -> Self {
+        Self {
+            directory: env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+            entries: Vec::new(),
+            selected: 0,
+            favorites: Vec::new(),
+            favorite_index: 0,
+            focus: BrowserFocus::Entries,
+            sort: BrowserSort::Name,
+            show_hidden: false,
+            show_all_files: false,
+        }
+    }
+
+    pub fn selected_entry(&self) -> Option<&BrowserEntry> {
+        self.entries.get(self.selected)
+    }
+
+    /// Whether the listed directory is pinned.
+    pub fn is_favorite(&self) -> bool {
+        self.favorites.iter().any(|f| f.path == self.directory)
+    }
+
+    /**
+     * Pin the listed directory, or unpin it when it already is.
+     * It is named after its last component, numbered when another favorite has the same name.
+     * Favorites are kept sorted by name, the order the config file gives them back in.
+     * @returns true if the directory is now pinned.
+     */
+    pub fn toggle_favorite(&mut self) -> bool {
+        if let Some(index) = self.favorites.iter().position(|f| f.path == self.directory) {
+            self.remove_favorite(index);
+            return false;
+        }
+
+        let base = favorite_name(&self.directory);
+        let name = (1..)
+            .map(|n| match n {
+                1 => base.clone(),
+                n => format!("{} {}", base, n),
+            })
+            .find(|name| self.favorites.iter().all(|f| &f.name != name))
+            .expect("a free name exists");
+        self.favorites.push(Favorite {
+            name,
+            path: self.directory.clone(),
+        });
+        self.favorites.sort_by(|a, b| a.name.cmp(&b.name));
+        true
+    }
+
+    pub fn remove_favorite(&mut self, index: usize) {
+        if index < self.favorites.len() {
+            self.favorites.remove(index);
+            self.favorite_index = self
+                .favorite_index
+                .min(self.favorites.len().saturating_sub(1));
+        }
+    }
+
+    /// The favorites as config entries, with paths under the home directory starting with `~`.
+    pub fn favorite_entries(&self) -> Vec<(String, String)> {
+        self.favorites
+            .iter()
+            .map(|f| (f.name.clone(), contract_home(&f.path)))
+            .collect()
+    }
+}
+
+/**
+ * Read the pinned directories from the configuration.
+ * @param config The loaded configuration.
+ * @returns The favorites sorted by name, with `~` expanded.
+ */
+pub fn favorites_from_config(config: &Config) -> Vec<Favorite> {
+    config
+        .section(FAVORITES_SECTION)
+        .into_iter()
+        .map(|(name, path)| Favorite {
+            name: name.to_string(),
+            path: expand_home(path),
+        })
+        .collect()
+}
+
+/// Name of a new favorite: the last component of its path, without characters the config file reserves.
+fn favorite_name(path: &Path) -> String {
+    let name: String = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+        .chars()
+        .map(|c| if "=#[]\"".contains(c) { '_' } else { c })
+        .collect();
+    match name.trim() {
+        "" => "root".to_string(),
+        name => name.to_string(),
+    }
+}
@@ -1,3 +1,7 @@
+use crate::pdf::compress::CompressOptions;
+use crate::pdf::extract::ExtractOptions;
+use crate::pdf::watermark::{WatermarkOptions, WatermarkPosition, WatermarkSource};
+
 #[derive(Debug, Clone)]
 pub struct MergeConfig {
     pub output_filename: String,
@@ -16,6 +20,10 @@ impl MergeConfig {
         self.output_filename.clear();
         self.editing_output = false;
     }
+
+    pub fn has_typed_input(&self) -> bool {
+        !self.output_filename.is_empty()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -42,13 +50,23 @@ impl DeleteConfig {
         self.editing_pages = false;
         self.editing_output = false;
     }
+
+    pub fn has_typed_input(&self) -> bool {
+        !self.pages_to_delete.is_empty() || !self.output_filename.is_empty()
+    }
 }
 
+/// Segments used when the field is left empty, also shown as the example of each mode.
+pub const PAGE_SEGMENTS_EXAMPLE: &str = "1-3,5,7-9";
+pub const NAMED_SEGMENTS_EXAMPLE: &str = "intro:1-3,chapter1:4-10,conclusion:11";
+
 #[derive(Debug, Clone)]
 pub struct SplitConfig {
     pub segments: String,
     pub output_prefix: String,
     pub use_named_segments: bool,
+    /// Whether a page may be written to several files.
+    pub allow_overlaps: bool,
     pub editing_segments: bool,
     pub editing_prefix: bool,
 }
@@ -59,6 +77,7 @@ impl SplitConfig {
             segments: String::new(),
             output_prefix: String::new(),
             use_named_segments: false,
+            allow_overlaps: false,
             editing_segments: false,
             editing_prefix: false,
         }
@@ -68,7 +87,272 @@ impl SplitConfig {
         self.segments.clear();
         self.output_prefix.clear();
         self.use_named_segments = false;
+        self.allow_overlaps = false;
         self.editing_segments = false;
         self.editing_prefix = false;
     }
+
+    pub fn has_typed_input(&self) -> bool {
+        !self.segments.is_empty() || !self.output_prefix.is_empty()
+    }
+
+    /// Switch between page segments and named segments. The typed segments are kept and
+    /// checked against the new mode, so names typed in page mode are not lost.
+    pub fn toggle_named_segments(&mut self) {
+        self.use_named_segments = !self.use_named_segments;
+    }
+}
+
+/// Clockwise rotations offered by the rotate wizard.
+pub const ROTATION_ANGLES: [i64; 3] = [90, 180, 270];
+
+/// Pages of the rotate wizard and the angle they are turned by, as typed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RotateRule {
+    /// Page specification; empty means every page.
+    pub pages: String,
+    pub angle: i64,
+}
+
+impl RotateRule {
+    pub fn new() -> Self {
+        Self {
+            pages: String::new(),
+            angle: ROTATION_ANGLES[0],
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RotateConfig {
+    /// Always holds at least one rule, applied in order.
+    pub rules: Vec<RotateRule>,
+    pub selected_rule: usize,
+    pub output_filename: String,
+    pub editing_pages: bool,
+    pub editing_output: bool,
+}
+
+impl RotateConfig {
+    pub fn new() -> Self {
+        Self {
+            rules: vec![RotateRule::new()],
+            selected_rule: 0,
+            output_filename: String::new(),
+            editing_pages: false,
+            editing_output: false,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.rules = vec![RotateRule::new()];
+        self.selected_rule = 0;
+        self.output_filename.clear();
+        self.editing_pages = false;
+        self.editing_output = false;
+    }
+
+    /// Whether pages, extra rules or an output name were typed; the angle alone is one key.
+    pub fn has_typed_input(&self) -> bool {
+        self.rules.len() > 1 || !self.rules[0].pages.is_empty() || !self.output_filename.is_empty()
+    }
+
+    /// The rule being edited.
+    pub fn rule(&self) -> &RotateRule {
+        &self.rules[self.selected_rule]
+    }
+
+    pub fn rule_mut(&mut self) -> &mut RotateRule {
+        &mut self.rules[self.selected_rule]
+    }
+
+    /// Switch the selected rule to the next rotation angle (90 → 180 → 270 → 90), or the
+    /// previous one when going backward.
+    pub fn cycle_angle(&mut self, forward: bool) {
+        let rule = self.rule_mut();
+        let index = ROTATION_ANGLES
+            .iter()
+            .position(|&a| a == rule.angle)
+            .unwrap_or(0);
+        let step = if forward {
+            1
+        } else {
+            ROTATION_ANGLES.len() - 1
+        };
+        rule.angle = ROTATION_ANGLES[(index + step) % ROTATION_ANGLES.len()];
+    }
+
+    /// Add an empty rule after the last one and select it.
+    pub fn add_rule(&mut self) {
+        self.rules.push(RotateRule::new());
+        self.selected_rule = self.rules.len() - 1;
+    }
+
+    /// Remove the selected rule. The last remaining rule is cleared instead.
+    pub fn remove_rule(&mut self) {
+        if self.rules.len() == 1 {
+            self.rules[0] = RotateRule::new();
+            return;
+        }
+        self.rules.remove(self.selected_rule);
+        self.selected_rule = self.selected_rule.min(self.rules.len() - 1);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExtractConfig {
+    pub pages: String,
+    pub options: ExtractOptions,
+    pub output_filename: String,
+    pub editing_pages: bool,
+    pub editing_output: bool,
+}
+
+impl ExtractConfig {
+    pub fn new() -> Self {
+        Self {
+            pages: String::new(),
+            options: ExtractOptions::default(),
+            output_filename: String::new(),
+            editing_pages: false,
+            editing_output: false,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.pages.clear();
+        self.options = ExtractOptions::default();
+        self.output_filename.clear();
+        self.editing_pages = false;
+        self.editing_output = false;
+    }
+
+    pub fn has_typed_input(&self) -> bool {
+        !self.pages.is_empty() || !self.output_filename.is_empty()
+    }
+}
+
+/// Image resolutions offered by the compress wizard, in dots per inch; None keeps images as they are.
+pub const IMAGE_DPI_PRESETS: [Option<u32>; 4] = [None, Some(300), Some(150), Some(72)];
+
+#[derive(Debug, Clone)]
+pub struct CompressConfig {
+    pub options: CompressOptions,
+    pub output_filename: String,
+    pub editing_output: bool,
+}
+
+impl CompressConfig {
+    pub fn new() -> Self {
+        Self {
+            options: CompressOptions::default(),
+            output_filename: String::new(),
+            editing_output: false,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.options = CompressOptions::default();
+        self.output_filename.clear();
+        self.editing_output = false;
+    }
+
+    pub fn has_typed_input(&self) -> bool {
+        !self.output_filename.is_empty()
+    }
+
+    /// Switch to the next (`forward`) or previous image resolution, wrapping around.
+    pub fn cycle_image_dpi(&mut self, forward: bool) {
+        let count = IMAGE_DPI_PRESETS.len();
+        let index = IMAGE_DPI_PRESETS
+            .iter()
+            .position(|&dpi| dpi == self.options.max_image_dpi)
+            .unwrap_or(0);
+        let next = if forward {
+            (index + 1) % count
+        } else {
+            (index + count - 1) % count
+        };
+        self.options.max_image_dpi = IMAGE_DPI_PRESETS[next];
+    }
+}
+
+/// Step of the watermark opacity slider, in percent.
+pub const OPACITY_STEP: u8 = 5;
+
+#[derive(Debug, Clone)]
+pub struct WatermarkConfig {
+    pub text: String,
+    pub image_path: String,
+    /// Whether the image is stamped instead of the text.
+    pub use_image: bool,
+    pub position: WatermarkPosition,
+    /// Opacity in percent, a multiple of OPACITY_STEP.
+    pub opacity: u8,
+    pub pages: String,
+    pub output_filename: String,
+    pub editing_text: bool,
+    pub editing_image: bool,
+    pub editing_pages: bool,
+    pub editing_output: bool,
+}
+
+impl WatermarkConfig {
+    pub fn new() -> Self {
+        Self {
+            text: String::new(),
+            image_path: String::new(),
+            use_image: false,
+            position: WatermarkPosition::ALL[0],
+            opacity: 30,
+            pages: String::new(),
+            output_filename: String::new(),
+            editing_text: false,
+            editing_image: false,
+            editing_pages: false,
+            editing_output: false,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    pub fn has_typed_input(&self) -> bool {
+        !self.text.is_empty()
+            || !self.image_path.is_empty()
+            || !self.pages.is_empty()
+            || !self.output_filename.is_empty()
+    }
+
+    /// Switch to the next position preset, wrapping around.
+    pub fn cycle_position(&mut self) {
+        let positions = WatermarkPosition::ALL;
+        let index = positions
+            .iter()
+            .position(|&p| p == self.position)
+            .unwrap_or(0);
+        self.position = positions[(index + 1) % positions.len()];
+    }
+
+    /// Move the opacity slider one step up (`more`) or down, between one step and 100%.
+    pub fn adjust_opacity(&mut self, more: bool) {
+        self.opacity = if more {
+            (self.opacity + OPACITY_STEP).min(100)
+        } else {
+            self.opacity.saturating_sub(OPACITY_STEP).max(OPACITY_STEP)
+        };
+    }
+
+    /**
+     * Options of the watermark as configured.
+     * @param source The text or the validated image path.
+     */
+    pub fn options(&self, source: WatermarkSource) -> WatermarkOptions {
+        WatermarkOptions {
+            source,
+            position: self.position,
+            opacity: self.opacity,
+        }
+    }
 }
@@ -1,8 +1,31 @@
+use crate::pdf::inspect::DocumentPreview;
+
+/// Confirmation shown when a folder is typed in the file input, to add all its PDFs at once.
+#[derive(Debug, Clone)]
+pub struct FolderPrompt {
+    pub directory: String,
+    /// Whether the PDFs of subfolders are included.
+    pub recursive: bool,
+    /// PDFs that would be added, in natural order.
+    pub files: Vec<String>,
+}
+
+/// Quick look at a selected file, opened with 'p'.
+#[derive(Debug, Clone)]
+pub struct FilePreview {
+    pub path: String,
+    pub preview: DocumentPreview,
+}
+
 #[derive(Debug, Clone)]
 pub struct FileState {
     pub selected_files: Vec<String>,
     pub selected_file_index: usize,
     pub merge_file_index: usize,
+    /// Entry whose path is being corrected in the input field, if any.
+    pub editing_file_index: Option<usize>,
+    pub folder_prompt: Option<FolderPrompt>,
+    pub preview: Option<FilePreview>,
 }
 
 impl FileState {
@@ -11,6 +34,9 @@ impl FileState {
             selected_files: Vec::new(),
             selected_file_index: 0,
             merge_file_index: 0,
+            editing_file_index: None,
+            folder_prompt: None,
+            preview: None,
         }
     }
 
@@ -18,12 +44,21 @@ impl FileState {
         self.selected_files.clear();
         self.selected_file_index = 0;
         self.merge_file_index = 0;
+        self.editing_file_index = None;
+        self.folder_prompt = None;
+        self.preview = None;
     }
 
     pub fn add_file(&mut self, file_path: String) {
         self.selected_files.push(file_path);
     }
 
+    pub fn replace_file(&mut self, index: usize, file_path: String) {
+        if let Some(file) = self.selected_files.get_mut(index) {
+            *file = file_path;
+        }
+    }
+
     pub fn remove_file(&mut self, index: usize) {
         if index < self.selected_files.len() {
             self.selected_files.remove(index);
@@ -46,6 +81,14 @@ impl FileState {
         }
     }
 
+    /// Move a file to another position, shifting the files in between.
+    pub fn move_file(&mut self, from: usize, to: usize) {
+        if from < self.selected_files.len() && to < self.selected_files.len() {
+            let file = self.selected_files.remove(from);
+            self.selected_files.insert(to, file);
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         self.selected_files.is_empty()
     }
@@ -0,0 +1,68 @@
+use crate::pdf::inspect::{DocumentInfo, inspect_document};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::fs;
+use std::time::SystemTime;
+
+#[derive(Debug)]
+struct CachedDocument {
+    modified: Option<SystemTime>,
+    info: DocumentInfo,
+}
+
+/// Document information for the selected files, so screens can show page counts without
+/// reloading PDFs on every frame.
+#[derive(Debug)]
+pub struct InspectCache {
+    entries: HashMap<String, CachedDocument>,
+}
+
+impl InspectCache {
+    pub fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Load a document into the cache, or reload it if it changed on disk since it was cached.
+    pub fn refresh(&mut self, path: &str) -> Result<&DocumentInfo> {
+        let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+        let stale = self
+            .entries
+            .get(path)
+            .is_none_or(|cached| cached.modified != modified || modified.is_none());
+
+        if stale {
+            let info = match inspect_document(path) {
+                Ok(info) => info,
+                Err(e) => {
+                    self.entries.remove(path);
+                    return Err(e);
+                }
+            };
+            self.entries
+                .insert(path.to_string(), CachedDocument { modified, info });
+        }
+
+        Ok(&self.entries[path].info)
+    }
+
+    /// Store information loaded elsewhere (e.g. on the validation worker).
+    pub fn insert(&mut self, path: &str, info: DocumentInfo) {
+        let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+        self.entries
+            .insert(path.to_string(), CachedDocument { modified, info });
+    }
+
+    pub fn remove(&mut self, path: &str) {
+        self.entries.remove(path);
+    }
+
+    pub fn get(&self, path: &str) -> Option<&DocumentInfo> {
+        self.entries.get(path).map(|cached| &cached.info)
+    }
+
+    pub fn page_count(&self, path: &str) -> Option<usize> {
+        self.get(path).map(DocumentInfo::page_count)
+    }
+}
@@ -0,0 +1,34 @@
+use super::CurrentScreen;
+use log::Level;
+
+/// Log viewer screen, toggled with 'L' from any screen.
+#[derive(Debug, Clone)]
+pub struct LogViewState {
+    /// Screen to go back to when the viewer is closed.
+    pub previous_screen: CurrentScreen,
+    /// Least severe level shown.
+    pub min_level: Level,
+    /// Number of lines scrolled up from the most recent one.
+    pub scroll: usize,
+}
+
+impl LogViewState {
+    pub fn new() -> Self {
+        Self {
+            previous_screen: CurrentScreen::Main,
+            min_level: Level::Info,
+            scroll: 0,
+        }
+    }
+
+    /// Show one more level of detail, wrapping from debug back to errors only.
+    pub fn cycle_level(&mut self) {
+        self.min_level = match self.min_level {
+            Level::Error => Level::Warn,
+            Level::Warn => Level::Info,
+            Level::Info => Level::Debug,
+            Level::Debug | Level::Trace => Level::Error,
+        };
+        self.scroll = 0;
+    }
+}
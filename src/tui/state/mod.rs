@@ -1,29 +1,72 @@
+pub mod browser_state;
 pub mod config_state;
 pub mod file_state;
+pub mod inspect_cache;
+pub mod log_view_state;
+pub mod output_state;
+pub mod page_grid_state;
+pub mod page_order_state;
+pub mod palette_state;
+pub mod processing_state;
+pub mod queue_state;
+pub mod result_state;
+pub mod thumbnail_state;
 pub mod ui_state;
+pub mod validation_state;
 
-pub use config_state::{DeleteConfig, MergeConfig, SplitConfig};
-pub use file_state::FileState;
+pub use browser_state::{BrowserEntry, BrowserFocus, BrowserState};
+pub use config_state::{
+    CompressConfig, DeleteConfig, ExtractConfig, MergeConfig, RotateConfig, SplitConfig,
+    WatermarkConfig,
+};
+pub use file_state::{FilePreview, FileState, FolderPrompt};
+pub use inspect_cache::InspectCache;
+pub use log_view_state::LogViewState;
+pub use output_state::{InPlacePrompt, OutputLocation, SpacePrompt};
+pub use page_grid_state::PageGridState;
+pub use page_order_state::PageOrderState;
+pub use palette_state::PaletteState;
+pub use processing_state::{Completion, Job, ProcessingState};
+pub use queue_state::{QueueState, StepStatus};
+pub use result_state::ResultState;
+pub use thumbnail_state::ThumbnailState;
 pub use ui_state::UiState;
+pub use validation_state::{FileValidation, ValidationState};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum CurrentScreen {
     Main,
     FileSelection,
+    FileBrowser,
     MergeConfig,
     DeleteConfig,
     SplitConfig,
+    RotateConfig,
+    ExtractConfig,
+    CompressConfig,
+    WatermarkConfig,
+    PageGrid,
+    PageOrder,
+    Processing,
+    Queue,
     Result,
+    Logs,
     Help,
+    Onboarding,
+    About,
     Exiting,
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum OperationMode {
     None,
     Merge,
     Delete,
     Split,
+    Rotate,
+    Extract,
+    Compress,
+    Watermark,
 }
 
 #[derive(Debug)]
@@ -0,0 +1,62 @@
+use crate::pdf::operation::Operation;
+use crate::pdf::space::SpaceWarning;
+
+/// Directory that output files are written to, shared by every operation.
+#[derive(Debug, Clone)]
+pub struct OutputLocation {
+    pub directory: String,
+    pub editing: bool,
+    /// Directory completions offered by Tab, cycled through on repeated presses.
+    pub completions: Vec<String>,
+    pub completion_index: usize,
+}
+
+impl OutputLocation {
+    pub fn new() -> Self {
+        Self {
+            directory: String::new(),
+            editing: false,
+            completions: Vec::new(),
+            completion_index: 0,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.directory.clear();
+        self.editing = false;
+        self.clear_completions();
+    }
+
+    pub fn clear_completions(&mut self) {
+        self.completions.clear();
+        self.completion_index = 0;
+    }
+}
+
+/// Typed confirmation asked before an operation writes over one of its own inputs.
+#[derive(Debug, Clone)]
+pub struct InPlacePrompt {
+    /// The operation held back.
+    pub operation: Operation,
+    /// Inputs the operation writes over.
+    pub files: Vec<String>,
+    /// Text typed so far; the confirmation word must be typed in full.
+    pub typed: String,
+    /// Whether a `.bak` copy of the inputs is made before writing, toggled with Tab.
+    pub backup: bool,
+    /// Whether the operation goes to the queue instead of running now.
+    pub queue: bool,
+    /// Set once the word is typed, until the operation runs: the output size check may still hold it back.
+    pub confirmed: bool,
+}
+
+/// Warnings about the outputs of an operation, shown before running it until confirmed or dismissed.
+#[derive(Debug, Clone)]
+pub struct SpacePrompt {
+    /// The operation held back.
+    pub operation: Operation,
+    pub warnings: Vec<SpaceWarning>,
+    /// Set by Enter in the warning: the screen runs its operation again, this time without checking.
+    /// The operation is not compared, since a timestamped default output name changes every second.
+    pub confirmed: bool,
+}
@@ -0,0 +1,101 @@
+use crate::pdf::inspect::PageInfo;
+use std::collections::BTreeSet;
+
+/// Number of page cells rendered on each row of the grid.
+pub const PAGE_GRID_COLUMNS: u32 = 10;
+
+#[derive(Debug, Clone)]
+pub struct PageGridState {
+    /// The document whose pages are shown.
+    pub path: String,
+    pub pages: Vec<PageInfo>,
+    pub page_count: u32,
+    pub cursor: u32,
+    pub anchor: u32,
+    pub selected: BTreeSet<u32>,
+}
+
+impl PageGridState {
+    pub fn new() -> Self {
+        Self {
+            path: String::new(),
+            pages: Vec::new(),
+            page_count: 0,
+            cursor: 1,
+            anchor: 1,
+            selected: BTreeSet::new(),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.path.clear();
+        self.pages.clear();
+        self.page_count = 0;
+        self.cursor = 1;
+        self.anchor = 1;
+        self.selected.clear();
+    }
+
+    /// Start a new selection over a document, keeping only the pages that exist in it.
+    pub fn open(&mut self, path: &str, pages: Vec<PageInfo>, preselected: &[u32]) {
+        let page_count = pages.len() as u32;
+        self.path = path.to_string();
+        self.pages = pages;
+        self.page_count = page_count;
+        self.cursor = 1;
+        self.anchor = 1;
+        self.selected = preselected
+            .iter()
+            .copied()
+            .filter(|&p| p >= 1 && p <= page_count)
+            .collect();
+    }
+
+    /// Move the cursor by a signed number of cells, clamped to the document pages.
+    pub fn move_cursor(&mut self, delta: i64) {
+        if self.page_count == 0 {
+            return;
+        }
+        let target = (self.cursor as i64 + delta).clamp(1, self.page_count as i64);
+        self.cursor = target as u32;
+    }
+
+    pub fn toggle_current(&mut self) {
+        if !self.selected.remove(&self.cursor) {
+            self.selected.insert(self.cursor);
+        }
+        self.anchor = self.cursor;
+    }
+
+    /// Select every page between the anchor and the cursor (inclusive).
+    pub fn select_to_cursor(&mut self) {
+        let (start, end) = if self.anchor <= self.cursor {
+            (self.anchor, self.cursor)
+        } else {
+            (self.cursor, self.anchor)
+        };
+        self.selected.extend(start..=end);
+    }
+
+    pub fn toggle_all(&mut self) {
+        if self.selected.len() as u32 == self.page_count {
+            self.selected.clear();
+        } else {
+            self.selected = (1..=self.page_count).collect();
+        }
+    }
+
+    pub fn selected_pages(&self) -> Vec<u32> {
+        self.selected.iter().copied().collect()
+    }
+
+    /// Geometry of the page under the cursor.
+    pub fn current_page(&self) -> Option<&PageInfo> {
+        self.pages.get(self.cursor as usize - 1)
+    }
+
+    /// Zero-based row of the cursor in the grid.
+    pub fn cursor_row(&self) -> u32 {
+        (self.cursor - 1) / PAGE_GRID_COLUMNS
+    }
+}
@@ -0,0 +1,89 @@
+use crate::pdf::inspect::PageInfo;
+use std::collections::BTreeSet;
+
+#[derive(Debug, Clone)]
+pub struct PageOrderState {
+    /// Geometry of the document pages, indexed by page number - 1.
+    pub pages: Vec<PageInfo>,
+    /// Every page of the document, in the order it will be written.
+    pub order: Vec<u32>,
+    /// Pages left out of the output.
+    pub removed: BTreeSet<u32>,
+    /// Position of the cursor in `order`.
+    pub cursor: usize,
+}
+
+impl PageOrderState {
+    pub fn new() -> Self {
+        Self {
+            pages: Vec::new(),
+            order: Vec::new(),
+            removed: BTreeSet::new(),
+            cursor: 0,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.pages.clear();
+        self.order.clear();
+        self.removed.clear();
+        self.cursor = 0;
+    }
+
+    /// Start from an existing order: its pages come first, the other pages of the document
+    /// follow in their own order and are marked as removed. An empty order keeps every page.
+    pub fn open(&mut self, pages: Vec<PageInfo>, initial_order: &[u32]) {
+        let page_count = pages.len() as u32;
+        self.pages = pages;
+        self.order = initial_order
+            .iter()
+            .copied()
+            .filter(|&p| p >= 1 && p <= page_count)
+            .collect();
+        self.removed = if self.order.is_empty() {
+            BTreeSet::new()
+        } else {
+            (1..=page_count)
+                .filter(|p| !self.order.contains(p))
+                .collect()
+        };
+        let rest: Vec<u32> = (1..=page_count)
+            .filter(|p| !self.order.contains(p))
+            .collect();
+        self.order.extend(rest);
+        self.cursor = 0;
+    }
+
+    /// Move the page under the cursor to another position, the cursor following it.
+    pub fn move_current(&mut self, to: usize) {
+        if self.order.is_empty() {
+            return;
+        }
+        let to = to.min(self.order.len() - 1);
+        let page = self.order.remove(self.cursor);
+        self.order.insert(to, page);
+        self.cursor = to;
+    }
+
+    pub fn toggle_removed(&mut self) {
+        let Some(&page) = self.order.get(self.cursor) else {
+            return;
+        };
+        if !self.removed.remove(&page) {
+            self.removed.insert(page);
+        }
+    }
+
+    /// Pages written to the output, in order.
+    pub fn kept_pages(&self) -> Vec<u32> {
+        self.order
+            .iter()
+            .copied()
+            .filter(|page| !self.removed.contains(page))
+            .collect()
+    }
+
+    pub fn page_info(&self, page: u32) -> Option<&PageInfo> {
+        self.pages.get(page.checked_sub(1)? as usize)
+    }
+}
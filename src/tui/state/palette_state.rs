@@ -0,0 +1,30 @@
+/// Command palette overlay, opened with Ctrl+K on top of any screen.
+#[derive(Debug, Clone)]
+pub struct PaletteState {
+    pub open: bool,
+    pub query: String,
+    /// Index in the filtered list of actions.
+    pub selected: usize,
+}
+
+impl PaletteState {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            query: String::new(),
+            selected: 0,
+        }
+    }
+
+    pub fn show(&mut self) {
+        self.open = true;
+        self.query.clear();
+        self.selected = 0;
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+        self.query.clear();
+        self.selected = 0;
+    }
+}
@@ -0,0 +1,96 @@
+use super::CurrentScreen;
+use crate::i18n::tr;
+use crate::pdf::cancel::{CancelToken, watch_cancellation};
+use crate::tui::app::App;
+use anyhow::{Result, anyhow};
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Work run on a worker thread, so the interface keeps drawing and can cancel it.
+pub struct Job<T> {
+    token: CancelToken,
+    result: Receiver<Result<T>>,
+    started_at: Instant,
+}
+
+impl<T: Send + 'static> Job<T> {
+    /**
+     * Start the work on a worker thread watching the cancellation token of the job.
+     * @param work The work, such as running an operation.
+     * @returns The running job.
+     */
+    pub fn spawn(work: impl FnOnce() -> Result<T> + Send + 'static) -> Self {
+        let token = CancelToken::new();
+        let worker_token = token.clone();
+        let (done, result) = mpsc::channel();
+        thread::spawn(move || {
+            watch_cancellation(worker_token);
+            // Nobody waits for the result any more once the TUI quit
+            let _ = done.send(work());
+        });
+        Self {
+            token,
+            result,
+            started_at: Instant::now(),
+        }
+    }
+}
+
+impl<T> Job<T> {
+    /// Ask the work to stop at its next page; it then fails with `Cancelled`.
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+
+    /// What the work returned, once it is over.
+    pub fn outcome(&self) -> Option<Result<T>> {
+        match self.result.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(worker_lost())),
+        }
+    }
+
+    /// Wait for the work to be over, such as before quitting so its outputs are cleaned up.
+    pub fn wait(&self) -> Result<T> {
+        self.result.recv().unwrap_or_else(|_| Err(worker_lost()))
+    }
+}
+
+impl<T> fmt::Debug for Job<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Job")
+            .field("cancelled", &self.is_cancelled())
+            .field("started_at", &self.started_at)
+            .finish()
+    }
+}
+
+fn worker_lost() -> anyhow::Error {
+    anyhow!(tr!("The operation stopped unexpectedly"))
+}
+
+/// Shows the outcome of a finished operation, on the interface thread.
+pub type Completion = Box<dyn FnOnce(&mut App) + Send>;
+
+/// An operation started from a configuration screen, running while the processing screen is shown.
+#[derive(Debug)]
+pub struct ProcessingState {
+    pub job: Job<Completion>,
+    /// What is being done, shown on the processing screen.
+    pub description: String,
+    /// The configuration screen the operation was started from, shown again when it is cancelled.
+    pub return_screen: CurrentScreen,
+    /// The message of the result screen when the operation fails.
+    pub failure: fn(&anyhow::Error) -> String,
+}
@@ -0,0 +1,242 @@
+use super::Job;
+use crate::config::Config;
+use crate::pdf::operation::Operation;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Config key stopping a queue run at its first failing step.
+pub const QUEUE_STOP_ON_ERROR_KEY: &str = "queue_stop_on_error";
+
+/**
+ * Read from the config whether a queue run stops at its first failing step.
+ * @param config The loaded config.
+ * @returns The configured value, false when not set.
+ * @throws String if the configured value is not true or false.
+ */
+pub fn stop_on_error_from_config(config: &Config) -> Result<bool, String> {
+    Ok(config.get_bool(QUEUE_STOP_ON_ERROR_KEY)?.unwrap_or(false))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StepStatus {
+    Pending,
+    Done(Vec<String>),
+    Failed(String),
+    /// Not run because the run was cancelled, or an earlier step it reads from failed.
+    Skipped,
+}
+
+#[derive(Debug, Clone)]
+pub struct QueuedStep {
+    pub operation: Operation,
+    pub status: StepStatus,
+}
+
+/// Operations composed in the TUI, executed one after the other.
+/// The queue survives going back to the main menu, so steps can be added from several wizards.
+#[derive(Debug)]
+pub struct QueueState {
+    pub steps: Vec<QueuedStep>,
+    pub selected: usize,
+    pub running: bool,
+    /// The step running on a worker thread, with its index.
+    pub job: Option<(usize, Job<Vec<String>>)>,
+    /// When the current or last run started.
+    pub started_at: Option<Instant>,
+    /// How long the last run took, once it is over.
+    pub run_time: Option<Duration>,
+    /// Pages written by the steps of the current or last run.
+    pub pages_written: usize,
+    /// Stop the run at the first failing step, instead of running the steps that do not need
+    /// its outputs.
+    pub stop_on_error: bool,
+}
+
+impl QueueState {
+    pub fn new() -> Self {
+        Self {
+            steps: Vec::new(),
+            selected: 0,
+            running: false,
+            job: None,
+            started_at: None,
+            run_time: None,
+            pages_written: 0,
+            stop_on_error: false,
+        }
+    }
+
+    pub fn push(&mut self, operation: Operation) {
+        self.steps.push(QueuedStep {
+            operation,
+            status: StepStatus::Pending,
+        });
+        self.selected = self.steps.len() - 1;
+    }
+
+    pub fn remove_selected(&mut self) {
+        if self.selected < self.steps.len() {
+            self.steps.remove(self.selected);
+            self.selected = self.selected.min(self.steps.len().saturating_sub(1));
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.steps.clear();
+        self.selected = 0;
+        self.running = false;
+        self.started_at = None;
+        self.run_time = None;
+        self.pages_written = 0;
+    }
+
+    /// Mark every step as pending and start running from the first one.
+    pub fn start(&mut self) {
+        for step in &mut self.steps {
+            step.status = StepStatus::Pending;
+        }
+        self.running = !self.steps.is_empty();
+        self.started_at = Some(Instant::now());
+        self.run_time = None;
+        self.pages_written = 0;
+    }
+
+    /// Run again the steps that failed or were skipped, keeping the outputs of those done.
+    pub fn retry_failed(&mut self) {
+        for step in &mut self.steps {
+            if matches!(step.status, StepStatus::Failed(_) | StepStatus::Skipped) {
+                step.status = StepStatus::Pending;
+            }
+        }
+        self.running = self.next_pending().is_some();
+        self.started_at = Some(Instant::now());
+        self.run_time = None;
+        self.pages_written = 0;
+    }
+
+    /// Record a failing step, then either stop the run or skip the pending steps that read a
+    /// file it was to write, so the independent steps still run.
+    pub fn fail_step(&mut self, index: usize, error: String) {
+        self.steps[index].status = StepStatus::Failed(error);
+        if self.stop_on_error {
+            self.stop();
+            return;
+        }
+
+        let mut missing: Vec<std::path::PathBuf> = Vec::new();
+        for step in &mut self.steps {
+            let needs_missing = step.status == StepStatus::Pending
+                && step.operation.inputs().iter().any(|input| {
+                    std::path::absolute(input).is_ok_and(|input| missing.contains(&input))
+                });
+            if needs_missing {
+                step.status = StepStatus::Skipped;
+            }
+            if matches!(step.status, StepStatus::Failed(_) | StepStatus::Skipped) {
+                missing.extend(
+                    step.operation
+                        .outputs()
+                        .unwrap_or_default()
+                        .iter()
+                        .filter_map(|output| std::path::absolute(output).ok()),
+                );
+            }
+        }
+    }
+
+    /// Cancel the run: the running step stops at its next page and deletes what it wrote, then
+    /// the run stops. Without a running step, the run stops at once.
+    pub fn cancel(&mut self) {
+        match &self.job {
+            Some((_, job)) => job.cancel(),
+            None => self.stop(),
+        }
+    }
+
+    /// Stop running, skipping the steps that did not run yet.
+    pub fn stop(&mut self) {
+        for step in &mut self.steps {
+            if step.status == StepStatus::Pending {
+                step.status = StepStatus::Skipped;
+            }
+        }
+        self.finish();
+    }
+
+    /// End the run, keeping its duration for the statistics.
+    pub fn finish(&mut self) {
+        self.running = false;
+        if self.run_time.is_none() {
+            self.run_time = self.started_at.map(|started| started.elapsed());
+        }
+    }
+
+    /// Time spent in the current run, or in the last one once it is over.
+    pub fn elapsed(&self) -> Duration {
+        self.run_time
+            .or_else(|| self.started_at.map(|started| started.elapsed()))
+            .unwrap_or_default()
+    }
+
+    /// Pages written per second over the run, once something has been written.
+    pub fn pages_per_second(&self) -> Option<f64> {
+        let seconds = self.elapsed().as_secs_f64();
+        (self.pages_written > 0 && seconds > 0.0).then(|| self.pages_written as f64 / seconds)
+    }
+
+    /// Estimated time left, from the average duration of the steps that have finished.
+    pub fn eta(&self) -> Option<Duration> {
+        let finished = self.finished_count();
+        if !self.running || finished == 0 {
+            return None;
+        }
+        let remaining = (self.steps.len() - finished) as u32;
+        Some(self.elapsed() / finished as u32 * remaining)
+    }
+
+    pub fn next_pending(&self) -> Option<usize> {
+        self.steps
+            .iter()
+            .position(|step| step.status == StepStatus::Pending)
+    }
+
+    /// Number of steps done, failed and skipped.
+    pub fn summary(&self) -> (usize, usize, usize) {
+        let mut summary = (0, 0, 0);
+        for step in &self.steps {
+            match step.status {
+                StepStatus::Done(_) => summary.0 += 1,
+                StepStatus::Failed(_) => summary.1 += 1,
+                StepStatus::Skipped => summary.2 += 1,
+                StepStatus::Pending => {}
+            }
+        }
+        summary
+    }
+
+    pub fn has_failures(&self) -> bool {
+        self.steps
+            .iter()
+            .any(|step| matches!(step.status, StepStatus::Failed(_) | StepStatus::Skipped))
+    }
+
+    /// Number of steps that have finished, successfully or not.
+    pub fn finished_count(&self) -> usize {
+        self.steps
+            .iter()
+            .filter(|step| step.status != StepStatus::Pending)
+            .count()
+    }
+
+    /// Whether a queued step will write the given file, so it can be used as a later input.
+    pub fn will_produce(&self, path: &str) -> bool {
+        let Ok(target) = std::path::absolute(path) else {
+            return false;
+        };
+        self.steps
+            .iter()
+            .filter_map(|step| step.operation.outputs().ok())
+            .flatten()
+            .any(|output| std::path::absolute(Path::new(&output)).is_ok_and(|o| o == target))
+    }
+}
@@ -0,0 +1,50 @@
+use crate::pdf::compress::CompressReport;
+
+/// Files produced by the last operation, and the follow-up actions offered on them.
+#[derive(Debug, Clone)]
+pub struct ResultState {
+    pub outputs: Vec<String>,
+    pub selected: usize,
+    /// Output to open in the system viewer, once the terminal has been suspended.
+    pub pending_open: Option<String>,
+    /// Feedback from the last follow-up action (e.g., "Copied path to clipboard").
+    pub notice: Option<String>,
+    /// Sizes before and after, when the last operation was a compression.
+    pub compression: Option<CompressReport>,
+    /// Full cause chain of the last failure, for the details view.
+    pub details: Option<String>,
+    pub show_details: bool,
+}
+
+impl ResultState {
+    pub fn new() -> Self {
+        Self {
+            outputs: Vec::new(),
+            selected: 0,
+            pending_open: None,
+            notice: None,
+            compression: None,
+            details: None,
+            show_details: false,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.outputs.clear();
+        self.selected = 0;
+        self.pending_open = None;
+        self.notice = None;
+        self.compression = None;
+        self.details = None;
+        self.show_details = false;
+    }
+
+    pub fn set_outputs(&mut self, outputs: Vec<String>) {
+        self.reset();
+        self.outputs = outputs;
+    }
+
+    pub fn selected_output(&self) -> Option<&str> {
+        self.outputs.get(self.selected).map(String::as_str)
+    }
+}
@@ -0,0 +1,182 @@
+use crate::pdf::thumbnail::{Thumbnail, ThumbnailRenderer};
+use crate::tui::graphics::GraphicsProtocol;
+use ratatui::layout::Rect;
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+/// Size of a terminal cell in pixels when the terminal does not tell.
+pub const DEFAULT_CELL_SIZE: (u32, u32) = (8, 16);
+
+/// Rendered pages kept in memory before the oldest renders are dropped.
+const MAX_RENDERED: usize = 64;
+
+/// A page to render, and the box it must fit in, in pixels.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ThumbnailKey {
+    pub path: String,
+    pub page: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Outcome of rendering a page on the thumbnail worker.
+pub type ThumbnailResult = (ThumbnailKey, Result<Thumbnail, String>);
+
+/// Page thumbnails of the page grid, rendered on a worker thread so moving the cursor stays
+/// responsive, and drawn through the graphics protocol of the terminal.
+#[derive(Debug)]
+pub struct ThumbnailState {
+    pub protocol: GraphicsProtocol,
+    /// Size of a terminal cell in pixels, read when the TUI starts and when it is resized.
+    pub cell_size: (u32, u32),
+    /// Rendered pages, or None for those that could not be rendered.
+    rendered: HashMap<ThumbnailKey, Option<Thumbnail>>,
+    /// The page being rendered; the next one is only asked once it is done.
+    pending: Option<ThumbnailKey>,
+    jobs: Sender<ThumbnailKey>,
+    results: Receiver<ThumbnailResult>,
+    /// The thumbnail on screen and the cells it covers, so it is only sent again once it changes.
+    pub shown: Option<(ThumbnailKey, Rect)>,
+}
+
+impl ThumbnailState {
+    pub fn new(protocol: GraphicsProtocol) -> Self {
+        let (jobs, pending_jobs) = mpsc::channel::<ThumbnailKey>();
+        let (done, results) = mpsc::channel();
+        // The worker stops once the state is dropped and the job channel is closed
+        thread::spawn(move || {
+            // Pages are mostly rendered from the same document, which is only read once
+            let mut document: Option<(String, Result<ThumbnailRenderer, String>)> = None;
+            for key in pending_jobs {
+                if document.as_ref().is_none_or(|(path, _)| *path != key.path) {
+                    let renderer =
+                        ThumbnailRenderer::open(&key.path).map_err(|e| format!("{:#}", e));
+                    document = Some((key.path.clone(), renderer));
+                }
+                let result = match document.as_ref().map(|(_, renderer)| renderer) {
+                    Some(Ok(renderer)) => renderer
+                        .render(key.page, key.width, key.height)
+                        .map_err(|e| format!("{:#}", e)),
+                    Some(Err(e)) => Err(e.clone()),
+                    None => continue,
+                };
+                if done.send((key, result)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            protocol,
+            cell_size: DEFAULT_CELL_SIZE,
+            rendered: HashMap::new(),
+            pending: None,
+            jobs,
+            results,
+            shown: None,
+        }
+    }
+
+    /**
+     * The thumbnail filling some cells with a page of a document.
+     * @param path The document.
+     * @param page The page number (1-based).
+     * @param area The cells the thumbnail must fit in.
+     */
+    pub fn key(&self, path: &str, page: u32, area: Rect) -> ThumbnailKey {
+        ThumbnailKey {
+            path: path.to_string(),
+            page,
+            width: area.width as u32 * self.cell_size.0,
+            height: area.height as u32 * self.cell_size.1,
+        }
+    }
+
+    /// The rendered thumbnail, once the worker is done with it.
+    pub fn get(&self, key: &ThumbnailKey) -> Option<&Thumbnail> {
+        self.rendered.get(key).and_then(Option::as_ref)
+    }
+
+    /// Ask the worker for a thumbnail not rendered yet, unless another one is being rendered.
+    pub fn request(&mut self, key: &ThumbnailKey) {
+        if self.protocol == GraphicsProtocol::None
+            || self.pending.is_some()
+            || self.rendered.contains_key(key)
+        {
+            return;
+        }
+        if self.jobs.send(key.clone()).is_ok() {
+            self.pending = Some(key.clone());
+        } else {
+            self.rendered.insert(key.clone(), None);
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Keep the thumbnails the worker finished since the last call.
+    pub fn take_results(&mut self) {
+        for (key, result) in self.results.try_iter() {
+            if self.pending.as_ref() == Some(&key) {
+                self.pending = None;
+            }
+            if self.rendered.len() >= MAX_RENDERED {
+                self.rendered.clear();
+            }
+            let thumbnail = result
+                .inspect_err(|e| log::debug!("No thumbnail of page {}: {}", key.page, e))
+                .ok();
+            self.rendered.insert(key, thumbnail);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn wait(state: &mut ThumbnailState) {
+        while state.is_pending() {
+            state.take_results();
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn test_render_on_worker() {
+        let mut state = ThumbnailState::new(GraphicsProtocol::Kitty);
+        let area = Rect::new(0, 0, 10, 8);
+        let key = state.key("tests/tests_pdf/c.pdf", 2, area);
+        assert_eq!((key.width, key.height), (80, 128));
+
+        state.request(&key);
+        assert!(state.is_pending());
+        // Only one page is rendered at a time
+        let next = state.key("tests/tests_pdf/c.pdf", 3, area);
+        state.request(&next);
+        wait(&mut state);
+        assert!(
+            state
+                .get(&key)
+                .is_some_and(|t| t.width <= 80 && t.height <= 128)
+        );
+        assert!(state.get(&next).is_none());
+
+        // A page that cannot be rendered is not asked again
+        let missing = state.key("tests/tests_pdf/missing.pdf", 1, area);
+        state.request(&missing);
+        wait(&mut state);
+        assert!(state.get(&missing).is_none());
+        state.request(&missing);
+        assert!(!state.is_pending());
+
+        // Without a graphics protocol nothing is rendered
+        let mut state = ThumbnailState::new(GraphicsProtocol::None);
+        state.request(&key);
+        assert!(!state.is_pending());
+    }
+}
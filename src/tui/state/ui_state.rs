@@ -1,4 +1,47 @@
 use super::MessageType;
+use crate::config::Config;
+use std::time::{Duration, Instant};
+
+/// How long a toast stays on screen.
+pub const TOAST_DURATION: Duration = Duration::from_secs(4);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ToastKind {
+    Error,
+    Info,
+}
+
+/// Config key asking before settings are dropped (Ctrl+H, Esc on a configuration screen).
+pub const CONFIRM_DISCARD_KEY: &str = "confirm_discard";
+
+/**
+ * Read from the config whether to ask before dropping files and typed settings.
+ * @param config The loaded config.
+ * @returns The configured value, true when not set.
+ * @throws String if the configured value is not true or false.
+ */
+pub fn confirm_discard_from_config(config: &Config) -> Result<bool, String> {
+    Ok(config.get_bool(CONFIRM_DISCARD_KEY)?.unwrap_or(true))
+}
+
+/// What is done once the user agrees to drop the settings of the current operation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiscardAction {
+    /// Back to the main menu (Ctrl+H).
+    GoHome,
+    /// The same operation again, from an empty file list.
+    StartOver,
+    /// Back to the file list from a configuration screen (Esc), clearing what was typed.
+    Configuration,
+}
+
+/// A short-lived notification drawn over the bottom of the screen, which stays interactive.
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub message: String,
+    pub kind: ToastKind,
+    pub shown_at: Instant,
+}
 
 #[derive(Debug)]
 pub struct UiState {
@@ -6,6 +49,18 @@ pub struct UiState {
     pub editing_input: bool,
     pub menu_mode_index: usize,
     pub message: Option<MessageType>,
+    /// Key cheat-sheet popup of the current screen, toggled with '?'.
+    pub show_cheat_sheet: bool,
+    pub help_scroll: u16,
+    /// Page of the welcome tour being shown.
+    pub onboarding_page: usize,
+    pub toast: Option<Toast>,
+    /// Most recent result or notification, kept across operations for the status bar.
+    pub last_message: Option<MessageType>,
+    /// Confirmation asked before dropping the files and settings of the current operation.
+    pub discard_prompt: Option<DiscardAction>,
+    /// Whether that confirmation is asked, from the config file.
+    pub confirm_discard: bool,
 }
 
 impl UiState {
@@ -15,6 +70,13 @@ impl UiState {
             editing_input: false,
             menu_mode_index: 0,
             message: None,
+            show_cheat_sheet: false,
+            help_scroll: 0,
+            onboarding_page: 0,
+            toast: None,
+            last_message: None,
+            discard_prompt: None,
+            confirm_discard: true,
         }
     }
 
@@ -23,13 +85,20 @@ impl UiState {
         self.editing_input = false;
         self.menu_mode_index = 0;
         self.message = None;
+        self.show_cheat_sheet = false;
+        self.help_scroll = 0;
+        self.onboarding_page = 0;
+        self.toast = None;
+        self.discard_prompt = None;
     }
 
     pub fn set_error(&mut self, message: String) {
+        self.last_message = Some(MessageType::Error(message.clone()));
         self.message = Some(MessageType::Error(message));
     }
 
     pub fn set_success(&mut self, message: String) {
+        self.last_message = Some(MessageType::Success(message.clone()));
         self.message = Some(MessageType::Success(message));
     }
 
@@ -51,7 +120,42 @@ impl UiState {
         }
     }
 
+    pub fn show_toast(&mut self, kind: ToastKind, message: String) {
+        self.last_message = Some(match kind {
+            ToastKind::Error => MessageType::Error(message.clone()),
+            ToastKind::Info => MessageType::Success(message.clone()),
+        });
+        self.toast = Some(Toast {
+            message,
+            kind,
+            shown_at: Instant::now(),
+        });
+    }
 
+    pub fn dismiss_toast(&mut self) {
+        self.toast = None;
+    }
+
+    /// Time left before the toast disappears, if one is shown.
+    pub fn toast_remaining(&self) -> Option<Duration> {
+        self.toast
+            .as_ref()
+            .map(|toast| TOAST_DURATION.saturating_sub(toast.shown_at.elapsed()))
+    }
+
+    /// Remove the toast once it has been shown long enough.
+    pub fn expire_toast(&mut self) {
+        if self.toast_remaining() == Some(Duration::ZERO) {
+            self.toast = None;
+        }
+    }
+
+    pub fn toast_error(&self) -> Option<&str> {
+        match &self.toast {
+            Some(toast) if toast.kind == ToastKind::Error => Some(&toast.message),
+            _ => None,
+        }
+    }
 
     pub fn stop_input(&mut self) {
         self.editing_input = false;
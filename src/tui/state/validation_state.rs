@@ -0,0 +1,105 @@
+use crate::pdf::inspect::{DocumentInfo, inspect_document};
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Progress of the check of a file typed in the file selection screen.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileValidation {
+    Pending,
+    Valid,
+    Invalid,
+    /// Protected by a password.
+    Encrypted,
+    /// Loads, but its page tree holds no page.
+    NoPages,
+    /// Moved or deleted since it was selected.
+    Missing,
+}
+
+/// Outcome of loading a file on the validation worker.
+pub type ValidationResult = (String, Result<DocumentInfo, String>);
+
+/// Typed files are loaded on a worker thread, so big PDFs do not freeze the interface.
+#[derive(Debug)]
+pub struct ValidationState {
+    statuses: HashMap<String, FileValidation>,
+    jobs: Sender<String>,
+    results: Receiver<ValidationResult>,
+    /// Start of the spinner animation drawn next to pending files.
+    pub started_at: Instant,
+    /// Last time the selected files were checked to still exist.
+    existence_checked_at: Instant,
+}
+
+impl ValidationState {
+    pub fn new() -> Self {
+        let (jobs, pending_jobs) = mpsc::channel::<String>();
+        let (done, results) = mpsc::channel();
+        // The worker stops once the state is dropped and the job channel is closed
+        thread::spawn(move || {
+            for path in pending_jobs {
+                log::debug!("Validating '{}'", path);
+                let result = inspect_document(&path).map_err(|e| format!("{:#}", e));
+                if done.send((path, result)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            statuses: HashMap::new(),
+            jobs,
+            results,
+            started_at: Instant::now(),
+            existence_checked_at: Instant::now(),
+        }
+    }
+
+    /// Forget every status. Results of files still being loaded are dropped when they arrive.
+    pub fn reset(&mut self) {
+        self.statuses.clear();
+    }
+
+    /// Queue a file for loading on the worker.
+    pub fn start(&mut self, path: &str) {
+        self.statuses
+            .insert(path.to_string(), FileValidation::Pending);
+        if self.jobs.send(path.to_string()).is_err() {
+            self.statuses
+                .insert(path.to_string(), FileValidation::Invalid);
+        }
+    }
+
+    pub fn set(&mut self, path: &str, status: FileValidation) {
+        self.statuses.insert(path.to_string(), status);
+    }
+
+    pub fn status(&self, path: &str) -> Option<&FileValidation> {
+        self.statuses.get(path)
+    }
+
+    /// Whether the selected files are due for another existence check, restarting the delay.
+    pub fn existence_check_due(&mut self, interval: Duration) -> bool {
+        if self.existence_checked_at.elapsed() < interval {
+            return false;
+        }
+        self.existence_checked_at = Instant::now();
+        true
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.statuses
+            .values()
+            .any(|status| *status == FileValidation::Pending)
+    }
+
+    /// Results the worker finished since the last call, for files that are still awaited.
+    pub fn take_results(&mut self) -> Vec<ValidationResult> {
+        self.results
+            .try_iter()
+            .filter(|(path, _)| self.statuses.get(path) == Some(&FileValidation::Pending))
+            .collect()
+    }
+}
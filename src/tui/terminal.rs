@@ -0,0 +1,77 @@
+use crossterm::{
+    cursor::Show,
+    event::{DisableBracketedPaste, DisableMouseCapture, KeyCode, KeyEvent, KeyModifiers},
+    execute,
+    terminal::{LeaveAlternateScreen, disable_raw_mode},
+};
+use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
+use std::io;
+use std::panic;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Signals received while the TUI runs, checked by the event loop between events.
+#[derive(Debug, Clone, Default)]
+pub struct Signals {
+    /// SIGTERM or SIGHUP: leave right away.
+    pub terminate: Arc<AtomicBool>,
+    /// SIGINT (e.g. `kill -INT`): ask for confirmation like Ctrl+C.
+    pub interrupt: Arc<AtomicBool>,
+}
+
+impl Signals {
+    /**
+     * Register the signal handlers.
+     * @returns The flags raised by the handlers.
+     * @throws io::Error if a handler cannot be registered.
+     */
+    pub fn register() -> io::Result<Self> {
+        let signals = Self::default();
+        signal_hook::flag::register(SIGTERM, Arc::clone(&signals.terminate))?;
+        signal_hook::flag::register(SIGHUP, Arc::clone(&signals.terminate))?;
+        signal_hook::flag::register(SIGINT, Arc::clone(&signals.interrupt))?;
+        Ok(signals)
+    }
+
+    pub fn should_terminate(&self) -> bool {
+        self.terminate.load(Ordering::Relaxed)
+    }
+
+    /// Whether SIGINT was received since the last call.
+    pub fn take_interrupt(&self) -> bool {
+        self.interrupt.swap(false, Ordering::Relaxed)
+    }
+}
+
+/**
+ * Put the terminal back in its normal state: cooked mode, main screen, no mouse capture
+ * or bracketed paste.
+ * Safe to call more than once.
+ * @throws io::Error if the terminal cannot be written to.
+ */
+pub fn restore_terminal() -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(
+        io::stdout(),
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        DisableBracketedPaste,
+        Show
+    )
+}
+
+/// Restore the terminal before a panic message is printed, so it is readable and the
+/// shell stays usable whatever layer panicked.
+pub fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let _ = restore_terminal();
+        default_hook(info);
+    }));
+}
+
+/// Whether a key event is Ctrl+C, which raw mode delivers as a key instead of SIGINT.
+pub fn is_interrupt_key(key: &KeyEvent) -> bool {
+    key.modifiers.contains(KeyModifiers::CONTROL)
+        && matches!(key.code, KeyCode::Char('c') | KeyCode::Char('C'))
+}
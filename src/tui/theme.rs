@@ -0,0 +1,408 @@
+use crate::config::Config;
+use ratatui::style::{Color, Modifier, Style};
+use std::env;
+use std::str::FromStr;
+use std::sync::RwLock;
+
+/// Built-in palettes, selected with `theme = "..."` in the config file.
+pub const THEME_PRESETS: &[&str] = &["dark", "light", "high-contrast", "color-blind", "mono"];
+
+/// Config key putting "ERROR:" and "OK:" before messages, whatever the theme.
+pub const MESSAGE_PREFIXES_KEY: &str = "message_prefixes";
+
+/// Styles used by every widget of the TUI.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    pub name: &'static str,
+    pub title: Style,
+    pub input: Style,
+    pub footer: Style,
+    pub error: Style,
+    pub success: Style,
+    pub warning: Style,
+    pub highlight: Style,
+    pub normal: Style,
+    pub accent: Style,
+    /// Background of popups and toasts (exit confirmation, notifications).
+    pub popup: Style,
+    /// Status bar at the bottom of every screen.
+    pub status: Style,
+    pub menu_merge: Style,
+    pub menu_delete: Style,
+    pub menu_split: Style,
+    pub menu_rotate: Style,
+    pub menu_extract: Style,
+    pub menu_compress: Style,
+    pub menu_watermark: Style,
+    pub menu_queue: Style,
+    pub menu_help: Style,
+    pub menu_about: Style,
+    pub menu_exit: Style,
+    /// Put "ERROR:" and "OK:" before result messages, so they are told apart without colors.
+    pub message_prefixes: bool,
+}
+
+static CURRENT_THEME: RwLock<Theme> = RwLock::new(Theme::dark());
+
+/// Theme used to draw the next frames.
+pub fn current_theme() -> Theme {
+    *CURRENT_THEME.read().unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn set_theme(theme: Theme) {
+    *CURRENT_THEME.write().unwrap_or_else(|e| e.into_inner()) = theme;
+}
+
+const fn fg(color: Color) -> Style {
+    Style::new().fg(color)
+}
+
+impl Theme {
+    /// Default palette, meant for dark terminal backgrounds.
+    pub const fn dark() -> Self {
+        Self {
+            name: "dark",
+            title: fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            input: fg(Color::Yellow),
+            footer: fg(Color::Gray),
+            error: fg(Color::Red),
+            success: fg(Color::Green),
+            warning: fg(Color::LightYellow).add_modifier(Modifier::BOLD),
+            highlight: Style::new().add_modifier(Modifier::REVERSED),
+            normal: fg(Color::White),
+            accent: fg(Color::Magenta),
+            popup: Style::new().bg(Color::DarkGray),
+            status: fg(Color::White).bg(Color::DarkGray),
+            menu_merge: fg(Color::Green),
+            menu_delete: fg(Color::Red),
+            menu_split: fg(Color::Blue),
+            menu_rotate: fg(Color::Cyan),
+            menu_extract: fg(Color::LightGreen),
+            menu_compress: fg(Color::LightCyan),
+            menu_watermark: fg(Color::LightMagenta),
+            menu_queue: fg(Color::LightBlue),
+            menu_help: fg(Color::Yellow),
+            menu_about: fg(Color::Gray),
+            menu_exit: fg(Color::Magenta),
+            message_prefixes: false,
+        }
+    }
+
+    /// Palette for light terminal backgrounds: no white, yellow or light colors.
+    pub const fn light() -> Self {
+        Self {
+            name: "light",
+            title: fg(Color::Blue).add_modifier(Modifier::BOLD),
+            input: fg(Color::Magenta),
+            footer: fg(Color::DarkGray),
+            error: fg(Color::Red),
+            success: fg(Color::Green),
+            warning: fg(Color::Red).add_modifier(Modifier::BOLD),
+            highlight: Style::new().add_modifier(Modifier::REVERSED),
+            normal: fg(Color::Black),
+            accent: fg(Color::Magenta),
+            popup: Style::new().bg(Color::Gray),
+            status: fg(Color::Black).bg(Color::Gray),
+            menu_merge: fg(Color::Green),
+            menu_delete: fg(Color::Red),
+            menu_split: fg(Color::Blue),
+            menu_rotate: fg(Color::Magenta),
+            menu_extract: fg(Color::Green),
+            menu_compress: fg(Color::Cyan),
+            menu_watermark: fg(Color::Magenta),
+            menu_queue: fg(Color::Blue),
+            menu_help: fg(Color::Black),
+            menu_about: fg(Color::DarkGray),
+            menu_exit: fg(Color::Magenta),
+            message_prefixes: false,
+        }
+    }
+
+    /// Bright, bold colors on the default background, for low vision or washed-out displays.
+    pub const fn high_contrast() -> Self {
+        let bold = Modifier::BOLD;
+        Self {
+            name: "high-contrast",
+            title: fg(Color::White).add_modifier(bold.union(Modifier::UNDERLINED)),
+            input: fg(Color::LightYellow).add_modifier(bold),
+            footer: fg(Color::White),
+            error: fg(Color::LightRed).add_modifier(bold),
+            success: fg(Color::LightGreen).add_modifier(bold),
+            warning: fg(Color::LightYellow).add_modifier(bold),
+            highlight: Style::new().add_modifier(Modifier::REVERSED.union(bold)),
+            normal: fg(Color::White),
+            accent: fg(Color::LightMagenta).add_modifier(bold),
+            popup: Style::new().bg(Color::Black),
+            status: Style::new().add_modifier(Modifier::REVERSED.union(bold)),
+            menu_merge: fg(Color::White).add_modifier(bold),
+            menu_delete: fg(Color::White).add_modifier(bold),
+            menu_split: fg(Color::White).add_modifier(bold),
+            menu_rotate: fg(Color::White).add_modifier(bold),
+            menu_extract: fg(Color::White).add_modifier(bold),
+            menu_compress: fg(Color::White).add_modifier(bold),
+            menu_watermark: fg(Color::White).add_modifier(bold),
+            menu_queue: fg(Color::White).add_modifier(bold),
+            menu_help: fg(Color::White).add_modifier(bold),
+            menu_about: fg(Color::White).add_modifier(bold),
+            menu_exit: fg(Color::White).add_modifier(bold),
+            message_prefixes: true,
+        }
+    }
+
+    /// Errors in vermillion and successes in sky blue (Okabe-Ito colors), which stay apart
+    /// with red-green color blindness, and message prefixes on.
+    pub const fn color_blind() -> Self {
+        let bold = Modifier::BOLD;
+        let vermillion = Color::Rgb(213, 94, 0);
+        let orange = Color::Rgb(230, 159, 0);
+        let sky_blue = Color::Rgb(86, 180, 233);
+        let blue = Color::Rgb(0, 114, 178);
+        let yellow = Color::Rgb(240, 228, 66);
+        let purple = Color::Rgb(204, 121, 167);
+        Self {
+            name: "color-blind",
+            title: fg(sky_blue).add_modifier(bold),
+            input: fg(yellow),
+            footer: fg(Color::Gray),
+            error: fg(vermillion).add_modifier(bold.union(Modifier::UNDERLINED)),
+            success: fg(sky_blue),
+            warning: fg(orange).add_modifier(bold),
+            highlight: Style::new().add_modifier(Modifier::REVERSED),
+            normal: fg(Color::White),
+            accent: fg(purple),
+            popup: Style::new().bg(Color::DarkGray),
+            status: fg(Color::White).bg(Color::DarkGray),
+            menu_merge: fg(sky_blue),
+            menu_delete: fg(vermillion),
+            menu_split: fg(blue),
+            menu_rotate: fg(yellow),
+            menu_extract: fg(orange),
+            menu_compress: fg(purple),
+            menu_watermark: fg(sky_blue),
+            menu_queue: fg(blue),
+            menu_help: fg(yellow),
+            menu_about: fg(Color::Gray),
+            menu_exit: fg(purple),
+            message_prefixes: true,
+        }
+    }
+
+    /// No colors at all, only text attributes (used when `NO_COLOR` is set).
+    pub const fn mono() -> Self {
+        let plain = Style::new();
+        Self {
+            name: "mono",
+            title: plain.add_modifier(Modifier::BOLD),
+            input: plain,
+            footer: plain,
+            error: plain.add_modifier(Modifier::BOLD),
+            success: plain,
+            warning: plain.add_modifier(Modifier::BOLD),
+            highlight: plain.add_modifier(Modifier::REVERSED),
+            normal: plain,
+            accent: plain,
+            popup: plain,
+            status: plain.add_modifier(Modifier::REVERSED),
+            menu_merge: plain,
+            menu_delete: plain,
+            menu_split: plain,
+            menu_rotate: plain,
+            menu_extract: plain,
+            menu_compress: plain,
+            menu_watermark: plain,
+            menu_queue: plain,
+            menu_help: plain,
+            menu_about: plain,
+            menu_exit: plain,
+            message_prefixes: true,
+        }
+    }
+
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            "color-blind" => Some(Self::color_blind()),
+            "mono" => Some(Self::mono()),
+            _ => None,
+        }
+    }
+
+    /// The next built-in preset, used to cycle themes from the command palette.
+    pub fn next_preset(&self) -> Self {
+        let index = THEME_PRESETS
+            .iter()
+            .position(|name| *name == self.name)
+            .map_or(0, |i| (i + 1) % THEME_PRESETS.len());
+        Self::preset(THEME_PRESETS[index]).unwrap_or_else(Self::dark)
+    }
+
+    /**
+     * Build the theme described by the configuration.
+     * `theme` picks a preset (default: dark, or mono when `NO_COLOR` is set) and
+     * each entry of the `[colors]` section overrides one style, e.g. `error = "bold #ff5555"`.
+     * `message_prefixes` turns the "ERROR:" and "OK:" prefixes on or off.
+     * @param config The user configuration.
+     * @returns The theme.
+     * @throws String describing an unknown preset, style name or color.
+     */
+    pub fn from_config(config: &Config) -> Result<Self, String> {
+        let no_color = env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        Self::resolve(config, no_color)
+    }
+
+    fn resolve(config: &Config, no_color: bool) -> Result<Self, String> {
+        let mut theme = match config.get("theme") {
+            Some(name) => Self::preset(name).ok_or_else(|| {
+                format!(
+                    "Unknown theme '{}' (expected one of: {})",
+                    name,
+                    THEME_PRESETS.join(", ")
+                )
+            })?,
+            None if no_color => Self::mono(),
+            None => Self::dark(),
+        };
+
+        for (key, value) in config.section("colors") {
+            let style = parse_style(value)?;
+            let slot = theme
+                .style_mut(key)
+                .ok_or_else(|| format!("Unknown theme color '{}'", key))?;
+            *slot = style;
+        }
+        if let Some(prefixes) = config.get_bool(MESSAGE_PREFIXES_KEY)? {
+            theme.message_prefixes = prefixes;
+        }
+
+        Ok(theme)
+    }
+
+    fn style_mut(&mut self, key: &str) -> Option<&mut Style> {
+        Some(match key {
+            "title" => &mut self.title,
+            "input" => &mut self.input,
+            "footer" => &mut self.footer,
+            "error" => &mut self.error,
+            "success" => &mut self.success,
+            "warning" => &mut self.warning,
+            "highlight" => &mut self.highlight,
+            "normal" => &mut self.normal,
+            "accent" => &mut self.accent,
+            "popup" => &mut self.popup,
+            "status" => &mut self.status,
+            "menu_merge" => &mut self.menu_merge,
+            "menu_delete" => &mut self.menu_delete,
+            "menu_split" => &mut self.menu_split,
+            "menu_rotate" => &mut self.menu_rotate,
+            "menu_extract" => &mut self.menu_extract,
+            "menu_compress" => &mut self.menu_compress,
+            "menu_watermark" => &mut self.menu_watermark,
+            "menu_queue" => &mut self.menu_queue,
+            "menu_help" => &mut self.menu_help,
+            "menu_about" => &mut self.menu_about,
+            "menu_exit" => &mut self.menu_exit,
+            _ => return None,
+        })
+    }
+}
+
+/**
+ * Parse a style such as "bold yellow", "#ff8800 on black" or "reversed".
+ * @param spec Space separated modifiers and colors; a color after "on" is the background.
+ * @returns The style.
+ * @throws String describing the word that is neither a modifier nor a color.
+ */
+pub fn parse_style(spec: &str) -> Result<Style, String> {
+    let mut style = Style::new();
+    let mut background = false;
+
+    for word in spec.split_whitespace() {
+        let modifier = match word.to_lowercase().as_str() {
+            "bold" => Some(Modifier::BOLD),
+            "dim" => Some(Modifier::DIM),
+            "italic" => Some(Modifier::ITALIC),
+            "underlined" => Some(Modifier::UNDERLINED),
+            "reversed" => Some(Modifier::REVERSED),
+            "on" => {
+                background = true;
+                continue;
+            }
+            _ => None,
+        };
+
+        if let Some(modifier) = modifier {
+            style = style.add_modifier(modifier);
+            continue;
+        }
+
+        let color = Color::from_str(word).map_err(|_| format!("Unknown color '{}'", word))?;
+        style = if background {
+            style.bg(color)
+        } else {
+            style.fg(color)
+        };
+    }
+
+    Ok(style)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_style() {
+        assert_eq!(
+            parse_style("bold yellow").unwrap(),
+            Style::new().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        );
+        assert_eq!(
+            parse_style("#ff8800 on black").unwrap(),
+            Style::new().fg(Color::Rgb(255, 136, 0)).bg(Color::Black)
+        );
+        assert!(parse_style("sparkly").is_err());
+    }
+
+    #[test]
+    fn test_theme_from_config() {
+        let default = Theme::resolve(&Config::default(), false).unwrap();
+        assert_eq!(default, Theme::dark());
+
+        // NO_COLOR only changes the default, an explicit theme still wins
+        assert_eq!(
+            Theme::resolve(&Config::default(), true).unwrap(),
+            Theme::mono()
+        );
+        let light = Config::parse("theme = light").unwrap();
+        assert_eq!(Theme::resolve(&light, true).unwrap(), Theme::light());
+
+        let custom = Config::parse("theme = light\n[colors]\nerror = bold magenta").unwrap();
+        let theme = Theme::resolve(&custom, false).unwrap();
+        assert_eq!(theme.error, parse_style("bold magenta").unwrap());
+        assert_eq!(theme.title, Theme::light().title);
+
+        // Prefixes follow the preset unless configured
+        assert!(!default.message_prefixes);
+        let prefixed = Config::parse("theme = color-blind").unwrap();
+        assert!(Theme::resolve(&prefixed, false).unwrap().message_prefixes);
+        let prefixed = Config::parse("message_prefixes = true").unwrap();
+        assert!(Theme::resolve(&prefixed, false).unwrap().message_prefixes);
+        let plain = Config::parse("theme = mono\nmessage_prefixes = false").unwrap();
+        assert!(!Theme::resolve(&plain, false).unwrap().message_prefixes);
+        assert!(Theme::resolve(&Config::parse("message_prefixes = 1").unwrap(), false).is_err());
+
+        assert!(Theme::resolve(&Config::parse("theme = neon").unwrap(), false).is_err());
+        assert!(Theme::resolve(&Config::parse("[colors]\nbogus = red").unwrap(), false).is_err());
+    }
+
+    #[test]
+    fn test_next_preset() {
+        let mut theme = Theme::dark();
+        for name in ["light", "high-contrast", "color-blind", "mono", "dark"] {
+            theme = theme.next_preset();
+            assert_eq!(theme.name, name);
+        }
+    }
+}
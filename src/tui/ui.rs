@@ -1,55 +1,58 @@
 use ratatui::{
     Frame,
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
-    text::{Line, Text},
-    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Position, Rect},
+    style::Modifier,
+    text::{Line, Span, Text},
+    widgets::{
+        Block, Borders, Clear, Gauge, List, ListItem, ListState, Padding, Paragraph, Scrollbar,
+        ScrollbarOrientation, ScrollbarState, Wrap,
+    },
 };
 
+use crate::build_info;
+use crate::i18n::{tr, translate};
+use crate::pdf::inspect::PageInfo;
+use crate::pdf::output::{
+    DEFAULT_COMPRESS_OUTPUT, DEFAULT_DELETE_OUTPUT, DEFAULT_EXTRACT_OUTPUT, DEFAULT_MERGE_OUTPUT,
+    DEFAULT_ROTATE_OUTPUT, DEFAULT_WATERMARK_OUTPUT, contract_home, expand_home, same_file,
+};
+use crate::pdf::watermark::WatermarkPosition;
 use crate::tui::app::App;
-use crate::tui::state::{CurrentScreen, OperationMode};
+use crate::tui::ascii::{ascii_mode, to_ascii};
+use crate::tui::handlers::output_location::in_place_word;
+use crate::tui::handlers::palette::palette_matches;
+use crate::tui::handlers::result::failure_details;
+use crate::tui::keymap::{
+    GLOBAL_BINDINGS, HELP_SECTIONS, KeyBinding, OUTPUT_DIRECTORY_BINDINGS, TEXT_FIELD_BINDINGS,
+    screen_bindings, screen_name,
+};
+use crate::tui::log_buffer;
+use crate::tui::state::config_state::{
+    NAMED_SEGMENTS_EXAMPLE, OPACITY_STEP, PAGE_SEGMENTS_EXAMPLE,
+};
+use crate::tui::state::page_grid_state::PAGE_GRID_COLUMNS;
+use crate::tui::state::ui_state::{DiscardAction, ToastKind};
+use crate::tui::state::{
+    BrowserEntry, BrowserFocus, CurrentScreen, FilePreview, FileValidation, FolderPrompt,
+    InPlacePrompt, MessageType, OperationMode, PageGridState, SpacePrompt, StepStatus,
+};
+use crate::tui::theme::current_theme;
+use crate::tui::utils::{
+    complement_pages, describe_operation, describe_page_sizes, describe_space_warning, ellipsize,
+    fit_hints, format_duration, format_page_ranges, format_size, preview_split_outputs,
+    validate_delete_pages, validate_image_input, validate_output_directory, validate_output_name,
+    validate_page_order_in_document, validate_page_ranges_in_document, validate_split_in_document,
+};
+use chrono::{DateTime, Local};
+use log::Level;
+use std::cmp::Ordering;
+use std::path::Path;
+use std::time::Duration;
 
+/// Style of the current theme, e.g. `app_theme!(error)`.
 macro_rules! app_theme {
-    (title) => {
-        Style::default()
-            .fg(Color::Cyan)
-            .add_modifier(Modifier::BOLD)
-    };
-    (input) => {
-        Style::default().fg(Color::Yellow)
-    };
-    (footer) => {
-        Style::default().fg(Color::Gray)
-    };
-    (error) => {
-        Style::default().fg(Color::Red)
-    };
-    (success) => {
-        Style::default().fg(Color::Green)
-    };
-    (highlight) => {
-        Style::default().add_modifier(Modifier::REVERSED)
-    };
-    (normal) => {
-        Style::default().fg(Color::White)
-    };
-    (accent) => {
-        Style::default().fg(Color::Magenta)
-    };
-    (menu_merge) => {
-        Style::default().fg(Color::Green)
-    };
-    (menu_delete) => {
-        Style::default().fg(Color::Red)
-    };
-    (menu_split) => {
-        Style::default().fg(Color::Blue)
-    };
-    (menu_help) => {
-        Style::default().fg(Color::Yellow)
-    };
-    (menu_exit) => {
-        Style::default().fg(Color::Magenta)
+    ($name:ident) => {
+        current_theme().$name
     };
 }
 
@@ -87,10 +90,22 @@ pub fn create_file_list<'a>(
     title: &'a str,
     selected_index: Option<usize>,
 ) -> (List<'a>, ListState) {
-    let file_items: Vec<ListItem> = files
-        .iter()
+    create_labeled_file_list(files.to_vec(), title, selected_index)
+}
+
+/**
+ * Create a numbered file list from display labels (e.g., a path followed by details).
+ */
+pub fn create_labeled_file_list<'a>(
+    labels: Vec<String>,
+    title: &'a str,
+    selected_index: Option<usize>,
+) -> (List<'a>, ListState) {
+    let count = labels.len();
+    let file_items: Vec<ListItem> = labels
+        .into_iter()
         .enumerate()
-        .map(|(i, file)| ListItem::new(format!("{}. {}", i + 1, file)))
+        .map(|(i, label)| ListItem::new(format!("{}. {}", i + 1, label)))
         .collect();
 
     let file_list = List::new(file_items)
@@ -100,21 +115,142 @@ pub fn create_file_list<'a>(
         .highlight_symbol("▶ ");
 
     let mut list_state = ListState::default();
-    if let Some(index) = selected_index {
-        if index < files.len() {
-            list_state.select(Some(index));
-        }
+    if let Some(index) = selected_index
+        && index < count
+    {
+        list_state.select(Some(index));
     }
 
     (file_list, list_state)
 }
 
-pub fn create_title(text: &str) -> Paragraph {
+pub fn create_title(text: &str) -> Paragraph<'_> {
     themed_widget!(title, text)
 }
 
-pub fn create_footer(text: &str) -> Paragraph {
-    themed_widget!(footer, text)
+/// Steps of an operation, shown as a breadcrumb under the title of its screens.
+const WIZARD_STEPS: &[&str] = &["File selection", "Configuration", "Run", "Result"];
+
+/// Frames of the spinner drawn next to files being validated.
+const SPINNER_FRAMES: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// Spinner frames in ASCII mode.
+const ASCII_SPINNER_FRAMES: &[&str] = &["|", "/", "-", "\\"];
+
+/// Milliseconds each spinner frame stays on screen.
+const SPINNER_FRAME_MILLIS: u128 = 100;
+
+/// Frame of the spinner after some time spent waiting.
+fn spinner_frame(elapsed: Duration) -> &'static str {
+    let frames = if ascii_mode() {
+        ASCII_SPINNER_FRAMES
+    } else {
+        SPINNER_FRAMES
+    };
+    frames[(elapsed.as_millis() / SPINNER_FRAME_MILLIS) as usize % frames.len()]
+}
+
+/// Longest document title shown after a file name, in characters.
+const TITLE_MAX_CHARS: usize = 40;
+
+/**
+ * Label a typed file with the state of its validation: a spinner while it is loaded on the
+ * worker, then a checkmark or an error mark. Files that were not typed keep their bare path.
+ * The document title follows the path when it is known, to tell apart generic file names.
+ */
+fn file_selection_label(app: &App, file: &str) -> String {
+    let title = app
+        .inspect_cache
+        .get(file)
+        .and_then(|info| info.title.as_deref());
+    let name = match title {
+        Some(title) => format!("{} — “{}”", file, ellipsize(title, TITLE_MAX_CHARS)),
+        None => file.to_string(),
+    };
+    match app.validation.status(file) {
+        Some(FileValidation::Pending) => tr!(
+            "{spinner} {file} — validating…",
+            spinner = spinner_frame(app.validation.started_at.elapsed()),
+            file = name
+        ),
+        Some(FileValidation::Valid) => format!("✓ {}", name),
+        Some(FileValidation::Invalid) => tr!("✗ {file} — not a valid PDF", file = name),
+        Some(FileValidation::Encrypted) => {
+            tr!(
+                "🔒 {file} — encrypted, remove its password first",
+                file = name
+            )
+        }
+        Some(FileValidation::NoPages) => tr!("⚠️ {file} — no pages", file = name),
+        Some(FileValidation::Missing) => tr!("❌ {file} — moved or deleted", file = name),
+        None => name,
+    }
+}
+
+/**
+ * Step of the operation wizard a screen belongs to.
+ * @param screen The current screen.
+ * @returns The index in WIZARD_STEPS, or None for screens outside an operation.
+ */
+pub fn wizard_step(screen: &CurrentScreen) -> Option<usize> {
+    match screen {
+        CurrentScreen::FileSelection | CurrentScreen::FileBrowser => Some(0),
+        CurrentScreen::MergeConfig
+        | CurrentScreen::DeleteConfig
+        | CurrentScreen::SplitConfig
+        | CurrentScreen::RotateConfig
+        | CurrentScreen::ExtractConfig
+        | CurrentScreen::CompressConfig
+        | CurrentScreen::WatermarkConfig
+        | CurrentScreen::PageGrid
+        | CurrentScreen::PageOrder => Some(1),
+        CurrentScreen::Processing => Some(2),
+        CurrentScreen::Result => Some(3),
+        _ => None,
+    }
+}
+
+/**
+ * Create the title of an operation screen, with the wizard breadcrumb in its bottom border.
+ * Done steps are dimmed and the current step is highlighted.
+ * @param text The title text.
+ * @param app The application state.
+ */
+pub fn create_wizard_title<'a>(text: &'a str, app: &App) -> Paragraph<'a> {
+    let Some(current) =
+        wizard_step(&app.current_screen).filter(|_| app.operation_mode != OperationMode::None)
+    else {
+        return create_title(text);
+    };
+
+    let mut spans = Vec::new();
+    for (i, step) in WIZARD_STEPS.iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::styled(" → ", app_theme!(footer)));
+        }
+        let label = format!(" {}. {} ", i + 1, translate(step));
+        spans.push(match i.cmp(&current) {
+            Ordering::Less => Span::styled(label, app_theme!(footer)),
+            Ordering::Equal => Span::styled(label, app_theme!(highlight)),
+            Ordering::Greater => Span::styled(label, app_theme!(normal)),
+        });
+    }
+
+    create_title(text).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title_bottom(Line::from(spans).centered()),
+    )
+}
+
+/**
+ * Create the footer of key hints, dropping hints that do not fit in the given width.
+ * @param text The " • " separated key hints.
+ * @param width The width of the footer area, borders included.
+ * @returns The footer widget.
+ */
+pub fn create_footer(text: &str, width: u16) -> Paragraph<'static> {
+    themed_widget!(footer, fit_hints(text, width.saturating_sub(2) as usize))
 }
 
 pub fn create_input_field<'a>(
@@ -125,12 +261,12 @@ pub fn create_input_field<'a>(
 ) -> Paragraph<'a> {
     let display_text = format!(
         "{}: {}",
-        title.split(' ').next().unwrap_or("Input"),
+        title.split(' ').next().unwrap_or(tr!("Input")),
         content
     );
 
     if let Some(error) = error_message {
-        themed_widget!(error_input, format!("ERROR: {}", error), title)
+        themed_widget!(error_input, tr!("ERROR: {error}", error = error), title)
     } else if is_editing {
         Paragraph::new(display_text)
             .style(app_theme!(input).add_modifier(Modifier::UNDERLINED))
@@ -140,58 +276,531 @@ pub fn create_input_field<'a>(
     }
 }
 
-pub fn create_checkbox<'a>(title: &'a str, is_checked: bool, is_focused: bool) -> Paragraph<'a> {
-    let checkbox_symbol = if is_checked { "☑" } else { "☐" };
-    let display_text = format!("{} {}", checkbox_symbol, title);
+/**
+ * Create an input field that is validated as the user types.
+ * When invalid, the field turns red and the error is shown under its content.
+ */
+pub fn create_validated_input_field<'a>(
+    content: &'a str,
+    title: &'a str,
+    is_editing: bool,
+    validation_error: Option<&'a str>,
+) -> Paragraph<'a> {
+    let Some(error) = validation_error else {
+        return create_input_field(content, title, is_editing, None);
+    };
+
+    let display_text = format!(
+        "{}: {}",
+        title.split(' ').next().unwrap_or(tr!("Input")),
+        content
+    );
+    let style = if is_editing {
+        app_theme!(error).add_modifier(Modifier::UNDERLINED)
+    } else {
+        app_theme!(error)
+    };
+
+    Paragraph::new(display_text).style(style).block(
+        Block::default()
+            .title(title)
+            .title_bottom(format!("✗ {}", error))
+            .borders(Borders::ALL)
+            .border_style(app_theme!(error)),
+    )
+}
+
+/**
+ * Validation error of an output filename or prefix field, if the typed name is not usable.
+ * An empty field is valid: the default name is used.
+ */
+fn output_name_error(name: &str) -> Option<String> {
+    if name.is_empty() {
+        return None;
+    }
+    validate_output_name(name).err().map(|e| e.to_string())
+}
+
+/**
+ * Create the output directory field, with the resolved output path shown under it.
+ * The path is flagged with a warning when writing would replace existing files, or an input,
+ * tells where replaced files are kept when backups are enabled, and notes a folder that will be
+ * created.
+ * @param app The application state.
+ * @param resolved The resolved output path (or path pattern when several files are written).
+ * @param existing The number of output files that already exist.
+ */
+pub fn create_output_directory_field(
+    app: &App,
+    resolved: &str,
+    existing: usize,
+) -> Paragraph<'static> {
+    let location = &app.output_location;
+    let content = if location.directory.is_empty() && !location.editing {
+        tr!("(current directory)")
+    } else {
+        &location.directory
+    };
+    let title = match location.completions.len() {
+        0 | 1 if location.editing => tr!("Output Directory (Tab: complete)").to_string(),
+        0 | 1 => tr!("Output Directory").to_string(),
+        count => tr!(
+            "Output Directory (match {index}/{count})",
+            index = location.completion_index + 1,
+            count = count
+        ),
+    };
 
-    let style = if is_focused {
-        app_theme!(highlight)
+    let (bottom, style) = if let Err(e) = validate_output_directory(&location.directory) {
+        (format!("✗ {}", e), app_theme!(error))
+    } else if existing == 1 && app.selected_files().iter().any(|f| same_file(f, resolved)) {
+        (
+            tr!(
+                "⚠ {path} is an input file: it will be written over",
+                path = resolved
+            ),
+            app_theme!(warning),
+        )
+    } else if let Some(suffix) = app.backup_suffix.as_deref().filter(|_| existing == 1) {
+        (
+            tr!(
+                "⚠ {path} already exists • kept as .{suffix}",
+                path = resolved,
+                suffix = suffix
+            ),
+            app_theme!(warning),
+        )
+    } else if let Some(suffix) = app.backup_suffix.as_deref().filter(|_| existing > 1) {
+        (
+            tr!(
+                "⚠ {path} • {count} files already exist • kept as .{suffix}",
+                path = resolved,
+                count = existing,
+                suffix = suffix
+            ),
+            app_theme!(warning),
+        )
+    } else if existing == 1 {
+        (
+            tr!("⚠ {path} already exists", path = resolved),
+            app_theme!(warning),
+        )
+    } else if existing > 1 {
+        (
+            tr!(
+                "⚠ {path} • {count} files already exist",
+                path = resolved,
+                count = existing
+            ),
+            app_theme!(warning),
+        )
+    } else if !location.directory.trim().is_empty()
+        && !expand_home(location.directory.trim()).is_dir()
+    {
+        (
+            tr!("→ {path} • new folder", path = resolved),
+            app_theme!(input),
+        )
+    } else {
+        (format!("→ {}", resolved), app_theme!(input))
+    };
+    let style = if location.editing {
+        style.add_modifier(Modifier::UNDERLINED)
     } else {
-        app_theme!(normal)
+        style
     };
 
-    Paragraph::new(display_text)
+    Paragraph::new(tr!("Directory: {directory}", directory = content))
         .style(style)
-        .block(Block::default().borders(Borders::ALL))
+        .block(
+            Block::default()
+                .title(title)
+                .title_bottom(bottom)
+                .borders(Borders::ALL),
+        )
 }
 
-pub fn create_standard_layout(frame_area: Rect, sections: &[u16]) -> Vec<Rect> {
-    let constraints: Vec<Constraint> = sections
-        .iter()
-        .map(|&size| {
-            if size == 0 {
-                Constraint::Min(0)
+/**
+ * Create the selector of the split segment syntax: both modes with an example of each,
+ * the selected one highlighted, and whether segments may overlap.
+ * @param use_named_segments Whether named segments are selected.
+ * @param allow_overlaps Whether a page may be part of several segments.
+ * @returns The selector widget.
+ */
+fn create_segment_mode_selector(
+    use_named_segments: bool,
+    allow_overlaps: bool,
+) -> Paragraph<'static> {
+    let mut spans = Vec::new();
+    for (named, label, example) in [
+        (false, tr!("Page segments"), PAGE_SEGMENTS_EXAMPLE),
+        // Shorter than the default, so both modes fit on one line
+        (true, tr!("Named segments"), "intro:1-3,body:4-10"),
+    ] {
+        if named {
+            spans.push(Span::raw("   "));
+        }
+        if named == use_named_segments {
+            spans.push(Span::styled(
+                format!("● {}", label),
+                app_theme!(highlight).add_modifier(Modifier::BOLD),
+            ));
+            spans.push(Span::styled(format!("  {}", example), app_theme!(input)));
+        } else {
+            spans.push(Span::styled(format!("○ {}", label), app_theme!(normal)));
+            spans.push(Span::styled(format!("  {}", example), app_theme!(footer)));
+        }
+    }
+
+    Paragraph::new(Line::from(spans)).block(
+        Block::default()
+            .title(tr!("Segment mode (Space to switch)"))
+            .title_bottom(if allow_overlaps {
+                tr!("☑ Overlapping segments allowed (O)")
             } else {
-                Constraint::Length(size)
-            }
-        })
-        .collect();
+                tr!("☐ Overlapping segments allowed (O)")
+            })
+            .borders(Borders::ALL),
+    )
+}
 
+/**
+ * Compute the vertical sections of a screen.
+ * Shared by the draw functions and the mouse handler so that hit-testing always
+ * matches what is rendered.
+ * @param screen The screen being laid out.
+ * @param frame_area The full frame area.
+ * @returns The list of sections, from top to bottom.
+ */
+pub fn screen_layout(screen: &CurrentScreen, frame_area: Rect) -> Vec<Rect> {
+    let sections: &[u16] = match screen {
+        CurrentScreen::FileSelection | CurrentScreen::PageGrid | CurrentScreen::PageOrder => {
+            &[3, 0, 3, 3]
+        }
+        CurrentScreen::MergeConfig => &[3, 0, 3, 3, 3, 3],
+        CurrentScreen::DeleteConfig => &[3, 0, 5, 3, 5, 3],
+        CurrentScreen::SplitConfig | CurrentScreen::RotateConfig => &[3, 0, 3, 3, 3, 3, 3],
+        CurrentScreen::ExtractConfig => &[3, 0, 3, 3, 3, 3, 3],
+        CurrentScreen::CompressConfig => &[3, 0, 3, 3, 3, 3, 3, 3, 3],
+        CurrentScreen::WatermarkConfig => &[3, 0, 3, 3, 3, 3, 3, 3, 3],
+        CurrentScreen::Result => &[3, 3, 0, 3],
+        CurrentScreen::Queue => &[3, 0, 3, 3],
+        _ => &[3, 0, 3],
+    };
+    let area = content_area(frame_area);
     Layout::default()
         .direction(Direction::Vertical)
-        .constraints(constraints)
-        .split(frame_area)
+        .constraints(compact_sections(sections, area.height))
+        .split(area)
         .to_vec()
 }
 
-pub fn render_error_if_exists(frame: &mut Frame, error_message: Option<&str>) {
-    if let Some(error) = error_message {
-        draw_error_popup(frame, error);
+/// Rows the main section of a screen (the one sized 0 in its layout) needs to stay usable.
+const MIN_BODY_HEIGHT: u16 = 3;
+
+/// Smallest terminal the screens are drawn in; below it only a warning is shown.
+pub const MIN_TERMINAL_WIDTH: u16 = 40;
+pub const MIN_TERMINAL_HEIGHT: u16 = 12;
+
+/// Whether the terminal is too small to draw the screens.
+pub fn is_too_small(frame_area: Rect) -> bool {
+    frame_area.width < MIN_TERMINAL_WIDTH || frame_area.height < MIN_TERMINAL_HEIGHT
+}
+
+/**
+ * Fit a screen layout into a short terminal by hiding sections the screen can do without:
+ * the footer first (its keys stay available with '?'), then the title.
+ * @param sections The section heights (0 fills the remaining space), the first being the
+ *                 title and the last the footer.
+ * @param height The height available.
+ * @returns The layout constraints, hidden sections getting a length of 0.
+ */
+fn compact_sections(sections: &[u16], height: u16) -> Vec<Constraint> {
+    let mut sizes: Vec<u16> = sections.to_vec();
+    let fixed = |sizes: &[u16]| sizes.iter().sum::<u16>();
+    let last = sizes.len() - 1;
+
+    if fixed(&sizes) + MIN_BODY_HEIGHT > height {
+        sizes[last] = 0;
+    }
+    if fixed(&sizes) + MIN_BODY_HEIGHT > height {
+        sizes[0] = 0;
+    }
+
+    sizes
+        .iter()
+        .zip(sections)
+        .map(|(&size, &original)| match (size, original) {
+            (_, 0) => Constraint::Min(0),
+            (size, _) => Constraint::Length(size),
+        })
+        .collect()
+}
+
+/// Height of the status bar drawn under every screen.
+const STATUS_BAR_HEIGHT: u16 = 1;
+
+/// Part of the frame left to screens once the status bar is drawn.
+pub fn content_area(frame_area: Rect) -> Rect {
+    Rect {
+        height: frame_area.height.saturating_sub(STATUS_BAR_HEIGHT),
+        ..frame_area
+    }
+}
+
+fn status_bar_area(frame_area: Rect) -> Rect {
+    let height = STATUS_BAR_HEIGHT.min(frame_area.height);
+    Rect::new(
+        frame_area.x,
+        frame_area.bottom() - height,
+        frame_area.width,
+        height,
+    )
+}
+
+/**
+ * Find which item of a bordered list sits under the given terminal row.
+ * Mirrors the scrolling ratatui applies to keep the selected item visible.
+ * @param area The area the list is rendered in (including borders).
+ * @param row The terminal row that was clicked.
+ * @param selected The currently selected index.
+ * @param len The number of items in the list.
+ * @returns The index of the item under the row, if any.
+ */
+pub fn list_index_at(area: Rect, row: u16, selected: usize, len: usize) -> Option<usize> {
+    let inner_top = area.y + 1;
+    let visible_rows = area.height.saturating_sub(2) as usize;
+    if row < inner_top || visible_rows == 0 || row >= inner_top + visible_rows as u16 {
+        return None;
+    }
+
+    let offset = (selected + 1).saturating_sub(visible_rows);
+    let index = offset + (row - inner_top) as usize;
+    (index < len).then_some(index)
+}
+
+/**
+ * Render a bordered list, with a scrollbar on its right border when its items do not fit.
+ * The thumb follows the offset ratatui scrolled the list to, so it matches the visible rows.
+ * @param frame The frame to draw on.
+ * @param list The list to render.
+ * @param area The area of the list, borders included.
+ * @param state The selection of the list.
+ */
+pub fn render_list(frame: &mut Frame, list: List, area: Rect, state: &mut ListState) {
+    let len = list.len();
+    frame.render_stateful_widget(list, area, state);
+    let visible_rows = area.height.saturating_sub(2) as usize;
+    // Each offset from the first item to the one showing the last page is a scroll position
+    render_scrollbar(
+        frame,
+        area,
+        (len + 1).saturating_sub(visible_rows),
+        visible_rows,
+        state.offset(),
+    );
+}
+
+/**
+ * Draw a vertical scrollbar over the right border of an area, when there is something to scroll.
+ * @param frame The frame to draw on.
+ * @param area The bordered area that scrolls.
+ * @param positions The number of scroll positions.
+ * @param visible_rows The number of rows shown at once.
+ * @param position The current scroll position.
+ */
+fn render_scrollbar(
+    frame: &mut Frame,
+    area: Rect,
+    positions: usize,
+    visible_rows: usize,
+    position: usize,
+) {
+    if positions <= 1 || visible_rows == 0 {
+        return;
     }
+    let mut state = ScrollbarState::new(positions)
+        .viewport_content_length(visible_rows)
+        .position(position);
+    frame.render_stateful_widget(
+        Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(None)
+            .end_symbol(None)
+            .style(app_theme!(footer)),
+        area.inner(Margin {
+            vertical: 1,
+            horizontal: 0,
+        }),
+        &mut state,
+    );
+}
+
+/// Width in columns of the pinned folders sidebar of the file browser.
+const BROWSER_SIDEBAR_WIDTH: u16 = 28;
+
+/**
+ * Split the browser section into the pinned folders sidebar and the folder entries.
+ * @param area The section the browser screen reserves for its content.
+ * @returns The sidebar area and the entries area.
+ */
+pub fn browser_areas(area: Rect) -> (Rect, Rect) {
+    let sidebar_width = BROWSER_SIDEBAR_WIDTH.min(area.width / 3);
+    let areas = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(sidebar_width), Constraint::Min(0)])
+        .split(area);
+    (areas[0], areas[1])
+}
+
+/// Tallest a toast gets (borders included); longer messages are cut.
+const TOAST_MAX_HEIGHT: u16 = 5;
+
+/// Width in columns of one page cell in the page grid.
+const PAGE_GRID_CELL_WIDTH: u16 = 6;
+
+/// Width in columns of the page preview panel next to the grid.
+const PAGE_PREVIEW_WIDTH: u16 = 32;
+
+/**
+ * Split the grid section into the page grid and, when there is room, the preview panel.
+ * @param area The section the grid screen reserves for its content.
+ * @returns The grid area and the optional preview area.
+ */
+pub fn page_grid_areas(area: Rect) -> (Rect, Option<Rect>) {
+    let grid_width = PAGE_GRID_CELL_WIDTH * PAGE_GRID_COLUMNS as u16 + 2;
+    if area.width < grid_width + PAGE_PREVIEW_WIDTH {
+        return (area, None);
+    }
+
+    let split = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Min(grid_width),
+            Constraint::Length(PAGE_PREVIEW_WIDTH),
+        ])
+        .split(area);
+    (split[0], Some(split[1]))
+}
+
+/**
+ * First grid row to render so that the cursor stays visible.
+ * @param grid The page grid state.
+ * @param visible_rows The number of rows that fit in the grid area.
+ */
+pub fn page_grid_first_row(grid: &PageGridState, visible_rows: u16) -> u32 {
+    grid.cursor_row()
+        .saturating_sub((visible_rows as u32).saturating_sub(1))
+}
+
+/**
+ * Find which page cell of the grid sits under the given position.
+ * @param area The area the grid is rendered in (including borders).
+ * @param position The clicked terminal position.
+ * @param grid The page grid state.
+ * @returns The page number under the position, if any.
+ */
+pub fn page_grid_page_at(area: Rect, position: Position, grid: &PageGridState) -> Option<u32> {
+    let visible_rows = area.height.saturating_sub(2);
+    if position.x <= area.x || position.y <= area.y || position.y > area.y + visible_rows {
+        return None;
+    }
+
+    let column = ((position.x - area.x - 1) / PAGE_GRID_CELL_WIDTH) as u32;
+    if column >= PAGE_GRID_COLUMNS {
+        return None;
+    }
+
+    let row = page_grid_first_row(grid, visible_rows) + (position.y - area.y - 1) as u32;
+    let page = row * PAGE_GRID_COLUMNS + column + 1;
+    (page <= grid.page_count).then_some(page)
 }
 
 pub fn ui(frame: &mut Frame, app: &App) {
+    if is_too_small(frame.area()) {
+        draw_too_small(frame);
+    } else {
+        draw_screen(frame, app);
+    }
+
+    if ascii_mode() {
+        to_ascii(frame.buffer_mut());
+    }
+}
+
+/**
+ * Draw the current screen, then the popups over it.
+ * @param frame The frame to draw on.
+ * @param app The application state.
+ */
+fn draw_screen(frame: &mut Frame, app: &App) {
     match app.current_screen {
         CurrentScreen::Main => draw_main_screen(frame, app),
         CurrentScreen::FileSelection => draw_file_selection_screen(frame, app),
+        CurrentScreen::FileBrowser => draw_file_browser_screen(frame, app),
         CurrentScreen::MergeConfig => draw_merge_config_screen(frame, app),
         CurrentScreen::DeleteConfig => draw_delete_config_screen(frame, app),
         CurrentScreen::SplitConfig => draw_split_config_screen(frame, app),
-        // CurrentScreen::Processing => draw_processing_screen(frame, app),
+        CurrentScreen::RotateConfig => draw_rotate_config_screen(frame, app),
+        CurrentScreen::ExtractConfig => draw_extract_config_screen(frame, app),
+        CurrentScreen::CompressConfig => draw_compress_config_screen(frame, app),
+        CurrentScreen::WatermarkConfig => draw_watermark_config_screen(frame, app),
+        CurrentScreen::PageGrid => draw_page_grid_screen(frame, app),
+        CurrentScreen::PageOrder => draw_page_order_screen(frame, app),
+        CurrentScreen::Processing => draw_processing_screen(frame, app),
         CurrentScreen::Result => draw_result_screen(frame, app),
-        CurrentScreen::Help => draw_help_screen(frame),
+        CurrentScreen::Queue => draw_queue_screen(frame, app),
+        CurrentScreen::Logs => draw_logs_screen(frame, app),
+        CurrentScreen::Help => draw_help_screen(frame, app),
+        CurrentScreen::Onboarding => draw_onboarding_screen(frame, app),
+        CurrentScreen::About => draw_about_screen(frame, app),
         CurrentScreen::Exiting => draw_exit_screen(frame, app),
     }
+    draw_status_bar(frame, app);
+
+    if let Some(prompt) = &app.space_prompt {
+        draw_space_prompt(frame, prompt);
+    } else if let Some(prompt) = app.in_place_prompt.as_ref().filter(|p| !p.confirmed) {
+        draw_in_place_prompt(frame, prompt);
+    } else if let Some(action) = app.ui_state.discard_prompt {
+        draw_discard_prompt(frame, app, action);
+    }
+
+    if app.ui_state.toast.is_some() {
+        draw_toast(frame, app);
+    }
+
+    if app.palette.open {
+        draw_command_palette(frame, app);
+    } else if app.ui_state.show_cheat_sheet {
+        draw_cheat_sheet(frame, app);
+    }
+}
+
+/**
+ * Draw the warning shown instead of the screens when the terminal is too small.
+ * @param frame The frame to draw on.
+ */
+fn draw_too_small(frame: &mut Frame) {
+    let area = frame.area();
+    let message = tr!(
+        "Terminal too small: {width}×{height}\nNeed at least {min_width}×{min_height}",
+        width = area.width,
+        height = area.height,
+        min_width = MIN_TERMINAL_WIDTH,
+        min_height = MIN_TERMINAL_HEIGHT
+    );
+    let top = area.height.saturating_sub(2) / 2;
+    frame.render_widget(
+        Paragraph::new(message)
+            .style(app_theme!(warning))
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true }),
+        Rect {
+            y: area.y + top,
+            height: area.height - top,
+            ..area
+        },
+    );
 }
 
 /**
@@ -201,22 +810,32 @@ pub fn ui(frame: &mut Frame, app: &App) {
  * @param app The application state.
  */
 fn draw_main_screen(frame: &mut Frame, app: &App) {
-    let chunks = create_standard_layout(frame.area(), &[3, 0, 3]);
+    let chunks = screen_layout(&CurrentScreen::Main, frame.area());
 
     frame.render_widget(create_title("📄 PDF Cutter - TUI"), chunks[0]);
 
     let menu_items = vec![
-        ListItem::new("1. 🔗 Merge PDFs").style(app_theme!(menu_merge)),
-        ListItem::new("2. ✂️  Delete Pages").style(app_theme!(menu_delete)),
-        ListItem::new("3. 🔪  Split Pages").style(app_theme!(menu_split)),
-        ListItem::new("4. ❓ Help").style(app_theme!(menu_help)),
-        ListItem::new("q. 🚪 Exit").style(app_theme!(menu_exit)),
+        ListItem::new(tr!("1. 🔗 Merge PDFs")).style(app_theme!(menu_merge)),
+        ListItem::new(tr!("2. ✂️  Delete Pages")).style(app_theme!(menu_delete)),
+        ListItem::new(tr!("3. 🔪  Split Pages")).style(app_theme!(menu_split)),
+        ListItem::new(tr!("4. 🔄 Rotate Pages")).style(app_theme!(menu_rotate)),
+        ListItem::new(tr!("5. 📑 Extract Pages")).style(app_theme!(menu_extract)),
+        ListItem::new(tr!("6. 🗜️  Compress PDF")).style(app_theme!(menu_compress)),
+        ListItem::new(tr!("7. 💧 Add a Watermark")).style(app_theme!(menu_watermark)),
+        ListItem::new(tr!(
+            "8. 📋 Operation Queue ({count})",
+            count = app.queue.steps.len()
+        ))
+        .style(app_theme!(menu_queue)),
+        ListItem::new(tr!("9. ❓ Help")).style(app_theme!(menu_help)),
+        ListItem::new(tr!("a. ℹ️  About")).style(app_theme!(menu_about)),
+        ListItem::new(tr!("q. 🚪 Exit")).style(app_theme!(menu_exit)),
     ];
 
     let menu = List::new(menu_items)
         .block(
             Block::default()
-                .title("Select Operation")
+                .title(tr!("Select Operation"))
                 .borders(Borders::ALL),
         )
         .style(app_theme!(normal))
@@ -230,7 +849,10 @@ fn draw_main_screen(frame: &mut Frame, app: &App) {
     );
 
     frame.render_widget(
-        create_footer("↑↓: Navigate • Enter: Select • 1-3: Direct select • q: Quit"),
+        create_footer(
+            tr!("↑↓: Navigate • Enter: Select • 1-9: Direct select • Ctrl+K: Commands • q: Quit"),
+            chunks[2].width,
+        ),
         chunks[2],
     );
 }
@@ -241,323 +863,2573 @@ fn draw_main_screen(frame: &mut Frame, app: &App) {
  *
 */
 fn draw_file_selection_screen(frame: &mut Frame, app: &App) {
-    let chunks = create_standard_layout(frame.area(), &[3, 0, 3, 3]);
+    let chunks = screen_layout(&CurrentScreen::FileSelection, frame.area());
 
     let title_text = match app.operation_mode {
-        OperationMode::Merge => "📄 Select PDFs to Merge",
-        OperationMode::Delete => "📄 Select PDF for Page Deletion",
-        _ => "📄 File Selection",
+        OperationMode::Merge => tr!("📄 Select PDFs to Merge"),
+        OperationMode::Delete => tr!("📄 Select PDF for Page Deletion"),
+        OperationMode::Rotate => tr!("📄 Select PDF to Rotate"),
+        OperationMode::Extract => tr!("📄 Select PDF to Extract Pages From"),
+        OperationMode::Compress => tr!("📄 Select PDF to Compress"),
+        OperationMode::Watermark => tr!("📄 Select PDF to Watermark"),
+        _ => tr!("📄 File Selection"),
     };
 
-    frame.render_widget(create_title(title_text), chunks[0]);
+    frame.render_widget(create_wizard_title(title_text, app), chunks[0]);
 
-    let (file_list, mut list_state) = create_file_list(
-        &app.selected_files(),
-        "Selected Files",
+    let labels = app
+        .selected_files()
+        .iter()
+        .map(|file| file_selection_label(app, file))
+        .collect();
+    let title = if app.operation_mode == OperationMode::Merge {
+        merge_list_title(app)
+    } else {
+        tr!("Selected Files").to_string()
+    };
+    let (file_list, mut list_state) = create_labeled_file_list(
+        labels,
+        &title,
         if app.file_state.is_empty() {
             None
         } else {
             Some(app.selected_file_index())
         },
     );
-    frame.render_stateful_widget(file_list, chunks[1], &mut list_state);
+    render_list(frame, file_list, chunks[1], &mut list_state);
 
     let binding = String::new();
     let input_text = app.current_input().unwrap_or(&binding);
-    let input_title = if app.editing_input() {
-        "Enter file path (Enter to add, Esc to cancel)"
+    let correcting = app.file_state.editing_file_index.is_some();
+    let input_title = if app.editing_input() && correcting {
+        tr!("Edit file path (Enter to save, Esc to cancel)")
+    } else if app.editing_input() {
+        tr!("Enter file path (Enter to add, Esc to cancel)")
     } else {
-        "File path (Tab add file)"
+        tr!("File path (Tab add file)")
     };
 
     let input_field = create_input_field(
         input_text,
         input_title,
         app.editing_input(),
-        app.error_message(),
+        app.toast_error(),
     );
     frame.render_widget(input_field, chunks[2]);
 
-    let instructions = if app.editing_input() {
-        "Enter: Add file | Esc: Cancel"
+    let instructions = if app.editing_input() && correcting {
+        tr!("Enter: Save file | Esc: Cancel")
+    } else if app.editing_input() {
+        tr!("Enter: Add file | Esc: Cancel")
     } else {
         match app.operation_mode {
             OperationMode::Merge => {
-                "↑/↓: Navigate • Tab: Add file • <-: Delete • Enter: Next • Alt+↑/↓: Reorder • Esc: Back"
+                tr!(
+                    "↑/↓: Navigate • Tab: Add file • b: Browse • e: Edit • p: Preview • Backspace: Remove • Alt+↑/↓: Reorder • Enter: Next • ?: Keys"
+                )
             }
             OperationMode::Delete => {
-                "↑/↓: Navigate • Tab: Add file • <-: Delete • Enter: Next • Esc: Back"
+                tr!(
+                    "↑/↓: Navigate • Tab: Add file • b: Browse • e: Edit • p: Preview • Backspace: Remove • Enter: Next • Esc: Back • ?: Keys"
+                )
+            }
+            _ => {
+                tr!(
+                    "↑/↓: Navigate • Tab: Add file • b: Browse • e: Edit • p: Preview • Backspace: Remove • Enter: Next • Esc: Back • ?: Keys"
+                )
             }
-            _ => "↑/↓: Navigate • Tab: Add file • <-: Delete • Enter: Next • Esc: Back",
         }
     };
 
-    frame.render_widget(create_footer(instructions), chunks[3]);
-    render_error_if_exists(frame, app.error_message().as_deref());
+    frame.render_widget(create_footer(instructions, chunks[3].width), chunks[3]);
+
+    if let Some(prompt) = &app.file_state.folder_prompt {
+        draw_folder_prompt(frame, prompt);
+    }
+    if let Some(preview) = &app.file_state.preview {
+        draw_file_preview(frame, preview);
+    }
 }
 
 /**
- * Draw the merge configuration screen UI.
- * Display selected files, output filename input, and footer instructions.
+ * Draw the file browser: the pinned folders on the left, the folders and PDFs of the current
+ * folder on the right.
  */
-fn draw_merge_config_screen(frame: &mut Frame, app: &App) {
-    let chunks = create_standard_layout(frame.area(), &[3, 0, 3, 3]);
-
-    frame.render_widget(create_title("🔗 Merge Configuration"), chunks[0]);
-
-    let (file_list, mut list_state) = create_file_list(
-        &app.selected_files(),
-        "Files to Merge (in order)",
-        Some(app.merge_file_index()),
-    );
-    frame.render_stateful_widget(file_list, chunks[1], &mut list_state);
+fn draw_file_browser_screen(frame: &mut Frame, app: &App) {
+    let chunks = screen_layout(&CurrentScreen::FileBrowser, frame.area());
+    let browser = &app.browser;
+
+    frame.render_widget(create_wizard_title(tr!("📂 Browse Files"), app), chunks[0]);
+
+    let (sidebar, entries_area) = browser_areas(chunks[1]);
+    let favorites_focused = browser.focus == BrowserFocus::Favorites;
+    if browser.favorites.is_empty() {
+        frame.render_widget(
+            Paragraph::new(tr!("F: pin this folder"))
+                .style(app_theme!(footer))
+                .wrap(Wrap { trim: true })
+                .block(
+                    Block::default()
+                        .title(tr!("📌 Pinned"))
+                        .borders(Borders::ALL),
+                ),
+            sidebar,
+        );
+    } else {
+        let labels = browser
+            .favorites
+            .iter()
+            .map(|favorite| favorite.name.clone())
+            .collect();
+        let (list, mut state) = create_labeled_file_list(
+            labels,
+            tr!("📌 Pinned"),
+            favorites_focused.then_some(browser.favorite_index),
+        );
+        render_list(frame, list, sidebar, &mut state);
+    }
 
-    let output_text = if app.merge_config.output_filename.is_empty() {
-        "merged_output.pdf"
+    let directory = contract_home(&browser.directory);
+    let mut title = if browser.is_favorite() {
+        format!("📌 {}", directory)
     } else {
-        &app.merge_config.output_filename
+        directory
     };
+    title.push_str(&tr!(
+        " • Sort: {sort}",
+        sort = translate(browser.sort.label())
+    ));
+    if browser.show_all_files {
+        title.push_str(tr!(" • All files"));
+    }
+    if browser.show_hidden {
+        title.push_str(tr!(" • Hidden shown"));
+    }
+    if browser.entries.is_empty() {
+        let empty = if browser.show_all_files {
+            tr!("No files here")
+        } else {
+            tr!("No folders or PDF files here")
+        };
+        frame.render_widget(
+            Paragraph::new(empty)
+                .style(app_theme!(footer))
+                .block(Block::default().title(title).borders(Borders::ALL)),
+            entries_area,
+        );
+    } else {
+        let items: Vec<ListItem> = browser
+            .entries
+            .iter()
+            .map(|entry| {
+                if entry.is_dir {
+                    ListItem::new(format!("📁 {}/", entry.name))
+                } else {
+                    ListItem::new(format!("📄 {}  {}", entry.name, entry_details(entry)))
+                }
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::default().title(title).borders(Borders::ALL))
+            .style(app_theme!(normal))
+            .highlight_style(app_theme!(highlight))
+            .highlight_symbol("▶ ");
+        let mut state = ListState::default();
+        if !favorites_focused {
+            state.select(Some(browser.selected));
+        }
+        render_list(frame, list, entries_area, &mut state);
+    }
 
-    let output_field = create_input_field(
-        output_text,
-        "Output Filename",
-        app.merge_config.editing_output,
-        None,
-    );
-    frame.render_widget(output_field, chunks[2]);
-
-    frame.render_widget(
-        create_footer("Tab: Edit output name • Enter: Start merge • Esc: Back"),
-        chunks[3],
-    );
-
-    render_error_if_exists(frame, app.error_message().as_deref());
+    let instructions = if favorites_focused {
+        tr!("↑↓: Select • Enter: Go • X: Unpin • Tab/Esc: Files • ?: Keys")
+    } else {
+        tr!(
+            "↑↓: Select • Enter: Open/Add • Backspace: Up • S: Sort • H: Hidden • A: All files • F: Pin • W: Work here • 1-9: Pinned • Tab: Pinned list • Esc: Back • ?: Keys"
+        )
+    };
+    frame.render_widget(create_footer(instructions, chunks[2].width), chunks[2]);
+}
+
+/// Size and modification date of a browser file, e.g. "1.2 MB • 2024-03-05 14:20".
+fn entry_details(entry: &BrowserEntry) -> String {
+    match entry.modified {
+        Some(modified) => format!(
+            "{} • {}",
+            format_size(entry.size),
+            DateTime::<Local>::from(modified).format("%Y-%m-%d %H:%M")
+        ),
+        None => format_size(entry.size),
+    }
+}
+
+/// Longest first-page text shown in a file preview, in characters.
+const PREVIEW_TEXT_MAX_CHARS: usize = 400;
+
+/**
+ * Name of a document information entry, as shown in a file preview.
+ * @param key The entry name in the PDF (e.g., "ModDate").
+ * @returns The translated label.
+ */
+fn metadata_label(key: &str) -> &'static str {
+    match key {
+        "Title" => tr!("Title"),
+        "Author" => tr!("Author"),
+        "Subject" => tr!("Subject"),
+        "Keywords" => tr!("Keywords"),
+        "Creator" => tr!("Creator"),
+        "Producer" => tr!("Producer"),
+        "CreationDate" => tr!("Created"),
+        "ModDate" => tr!("Modified"),
+        "Provenance" => tr!("Provenance"),
+        _ => tr!("Other"),
+    }
+}
+
+/**
+ * Draw the quick preview of a selected file: page count, size, page sizes, encryption,
+ * metadata and the beginning of the first page text.
+ * @param frame The frame to draw on.
+ * @param file The previewed file and what was read from it.
+ */
+fn draw_file_preview(frame: &mut Frame, file: &FilePreview) {
+    let area = centered_rect(80, 80, frame.area());
+    frame.render_widget(Clear, area);
+
+    let preview = &file.preview;
+    let label = |text: &str| Span::styled(format!("{} ", text), app_theme!(accent));
+    let mut lines = vec![
+        Line::from(vec![
+            label(tr!("Pages:")),
+            Span::raw(preview.info.page_count().to_string()),
+        ]),
+        Line::from(vec![
+            label(tr!("Size:")),
+            Span::raw(format_size(preview.info.file_size)),
+        ]),
+        Line::from(vec![
+            label(tr!("Encrypted:")),
+            Span::raw(if preview.info.encrypted {
+                tr!("yes")
+            } else {
+                tr!("no")
+            }),
+        ]),
+        Line::from(label(tr!("Page sizes:"))),
+    ];
+
+    lines.extend(
+        describe_page_sizes(&preview.info.pages)
+            .into_iter()
+            .map(|size| Line::from(format!("  {}", size))),
+    );
+
+    lines.push(Line::from(""));
+    if preview.metadata.is_empty() {
+        lines.push(Line::styled(tr!("No metadata"), app_theme!(footer)));
+    }
+    for (key, value) in &preview.metadata {
+        lines.push(Line::from(vec![
+            label(&format!("{}:", metadata_label(key))),
+            Span::raw(value.clone()),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(label(tr!("First page:"))));
+    lines.push(match &preview.first_page_text {
+        Some(text) => Line::from(ellipsize(text, PREVIEW_TEXT_MAX_CHARS)),
+        None => Line::styled(tr!("(no text found)"), app_theme!(footer)),
+    });
+
+    let name = Path::new(&file.path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| file.path.clone());
+    let popup = Paragraph::new(Text::from(lines))
+        .style(app_theme!(popup))
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .title(format!("🔍 {}", name))
+                .title_bottom(tr!("Press any key to close"))
+                .borders(Borders::ALL),
+        );
+    frame.render_widget(popup, area);
+}
+
+/**
+ * Draw the prompt offering to add the PDFs of a folder, with the files that would be added.
+ * @param frame The frame to draw on.
+ * @param prompt The folder and the PDFs found in it.
+ */
+fn draw_folder_prompt(frame: &mut Frame, prompt: &FolderPrompt) {
+    let area = centered_rect(70, 60, frame.area());
+    frame.render_widget(Clear, area);
+
+    let directory = prompt.directory.as_str();
+    let summary = match prompt.files.len() {
+        0 => tr!("No PDF files in {directory}", directory = directory),
+        1 => tr!("1 PDF file in {directory}", directory = directory),
+        count => tr!(
+            "{count} PDF files in {directory}",
+            count = count,
+            directory = directory
+        ),
+    };
+    let mut lines = vec![Line::styled(summary, app_theme!(highlight))];
+    if prompt.recursive {
+        lines.push(Line::from(tr!("(subfolders included)")));
+    }
+    lines.push(Line::from(""));
+
+    // Paths are shown relative to the folder, as many as fit in the popup
+    let room = (area.height as usize).saturating_sub(lines.len() + 2);
+    let shown = if prompt.files.len() > room {
+        room.saturating_sub(1)
+    } else {
+        prompt.files.len()
+    };
+    lines.extend(prompt.files.iter().take(shown).map(|file| {
+        let name = Path::new(file)
+            .strip_prefix(directory)
+            .map(|relative| relative.to_string_lossy().into_owned())
+            .unwrap_or_else(|_| file.clone());
+        Line::from(format!("  {}", name))
+    }));
+    if shown < prompt.files.len() {
+        lines.push(Line::styled(
+            tr!("… and {count} more", count = prompt.files.len() - shown),
+            app_theme!(accent),
+        ));
+    }
+
+    let hints = if prompt.recursive {
+        tr!("Enter: Add all • R: Leave out subfolders • Esc: Cancel")
+    } else {
+        tr!("Enter: Add all • R: Include subfolders • Esc: Cancel")
+    };
+    let popup = Paragraph::new(Text::from(lines))
+        .style(app_theme!(popup))
+        .block(
+            Block::default()
+                .title(tr!("📁 Add PDFs from a folder"))
+                .title_bottom(hints)
+                .borders(Borders::ALL),
+        );
+    frame.render_widget(popup, area);
+}
+
+/**
+ * Draw the warning shown before running an operation whose outputs may not fit on disk
+ * or are larger than the configured size.
+ * @param frame The frame to draw on.
+ * @param prompt The warnings and the operation they hold back.
+ */
+fn draw_space_prompt(frame: &mut Frame, prompt: &SpacePrompt) {
+    let area = centered_rect(70, 40, frame.area());
+    frame.render_widget(Clear, area);
+
+    let mut lines = vec![
+        Line::styled(describe_operation(&prompt.operation), app_theme!(highlight)),
+        Line::from(""),
+    ];
+    lines.extend(prompt.warnings.iter().map(|warning| {
+        Line::styled(
+            format!("⚠ {}", describe_space_warning(warning)),
+            app_theme!(warning),
+        )
+    }));
+    lines.push(Line::from(""));
+    lines.push(Line::from(tr!(
+        "Sizes are estimated from the input files before anything is written."
+    )));
+
+    let popup = Paragraph::new(Text::from(lines))
+        .style(app_theme!(popup))
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .title(tr!("💾 Check the output size"))
+                .title_bottom(tr!("Enter: Run anyway • Esc: Back"))
+                .borders(Borders::ALL),
+        );
+    frame.render_widget(popup, area);
+}
+
+/**
+ * Draw the confirmation asked before an operation writes over its own inputs:
+ * the files replaced, the backup toggle and the confirmation word being typed.
+ */
+fn draw_in_place_prompt(frame: &mut Frame, prompt: &InPlacePrompt) {
+    let area = centered_rect(70, 50, frame.area());
+    frame.render_widget(Clear, area);
+
+    let mut lines = vec![
+        Line::styled(describe_operation(&prompt.operation), app_theme!(highlight)),
+        Line::from(""),
+        Line::styled(
+            tr!("⚠ This writes over the original file:"),
+            app_theme!(warning),
+        ),
+    ];
+    lines.extend(
+        prompt
+            .files
+            .iter()
+            .map(|file| Line::from(format!("  {}", file))),
+    );
+    lines.push(Line::from(""));
+    lines.push(Line::from(if prompt.backup {
+        tr!("[x] Keep a .bak copy of the original (Tab)")
+    } else {
+        tr!("[ ] Keep a .bak copy of the original (Tab)")
+    }));
+    lines.push(Line::from(""));
+    lines.push(Line::from(tr!(
+        "Type '{word}' and press Enter to confirm:",
+        word = in_place_word()
+    )));
+    lines.push(Line::styled(
+        format!("> {}█", prompt.typed),
+        app_theme!(input),
+    ));
+
+    let popup = Paragraph::new(Text::from(lines))
+        .style(app_theme!(popup))
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .title(tr!("⚠ Overwrite the input?"))
+                .title_bottom(tr!("Enter: Confirm • Tab: Backup • Esc: Back"))
+                .borders(Borders::ALL),
+        );
+    frame.render_widget(popup, area);
+}
+
+/**
+ * Draw the confirmation asked before dropping the files and settings of the current operation,
+ * or what was typed on its configuration screen.
+ * @param frame The frame to draw on.
+ * @param app The application state.
+ * @param action Where the user is going once confirmed.
+ */
+fn draw_discard_prompt(frame: &mut Frame, app: &App, action: DiscardAction) {
+    let area = centered_rect(60, 30, frame.area());
+    frame.render_widget(Clear, area);
+
+    let (title, question, warning) = match action {
+        DiscardAction::GoHome | DiscardAction::StartOver => (
+            tr!("⚠ Discard the current operation?"),
+            if action == DiscardAction::GoHome {
+                tr!("Go back to the main menu?")
+            } else {
+                tr!("Start this operation over?")
+            },
+            tr!(
+                "⚠ The selected files ({count}) and the settings of this operation will be lost.",
+                count = app.files_len()
+            ),
+        ),
+        DiscardAction::Configuration => (
+            tr!("⚠ Discard the configuration?"),
+            tr!("Go back to the file list?"),
+            tr!("⚠ The pages, segments and output names typed on this screen will be lost.")
+                .to_string(),
+        ),
+    };
+    let lines = vec![
+        Line::styled(question, app_theme!(highlight)),
+        Line::from(""),
+        Line::styled(warning, app_theme!(warning)),
+        Line::from(tr!("Queued steps are kept.")),
+    ];
+
+    let popup = Paragraph::new(Text::from(lines))
+        .style(app_theme!(popup))
+        .wrap(Wrap { trim: true })
+        .block(
+            Block::default()
+                .title(title)
+                .title_bottom(tr!("Enter/Y: Discard • Any other key: Stay"))
+                .borders(Borders::ALL),
+        );
+    frame.render_widget(popup, area);
+}
+
+/**
+ * Draw the merge configuration screen UI.
+ * Display selected files, output filename input, and footer instructions.
+ */
+fn draw_merge_config_screen(frame: &mut Frame, app: &App) {
+    let chunks = screen_layout(&CurrentScreen::MergeConfig, frame.area());
+
+    frame.render_widget(
+        create_wizard_title(tr!("🔗 Merge Configuration"), app),
+        chunks[0],
+    );
+
+    let labels = app
+        .selected_files()
+        .iter()
+        .map(|file| match app.inspect_cache.get(file) {
+            Some(info) => tr!(
+                "{file} — {pages} pages, {size}",
+                file = file,
+                pages = info.page_count(),
+                size = format_size(info.file_size)
+            ),
+            None => tr!("{file} — unreadable", file = file),
+        })
+        .collect();
+    let title = merge_list_title(app);
+    let (file_list, mut list_state) =
+        create_labeled_file_list(labels, &title, Some(app.merge_file_index()));
+    render_list(frame, file_list, chunks[1], &mut list_state);
+
+    frame.render_widget(create_merge_summary(app), chunks[2]);
+
+    let output_text = &app.output_name(&app.merge_config.output_filename, DEFAULT_MERGE_OUTPUT);
+
+    let output_error = output_name_error(&app.merge_config.output_filename);
+    let output_field = create_validated_input_field(
+        output_text,
+        tr!("Output Filename"),
+        app.merge_config.editing_output,
+        output_error.as_deref(),
+    );
+    frame.render_widget(output_field, chunks[4]);
+
+    let resolved = app.resolve_output(output_text);
+    let existing = usize::from(Path::new(&resolved).exists());
+    frame.render_widget(
+        create_output_directory_field(app, &resolved, existing),
+        chunks[3],
+    );
+
+    frame.render_widget(
+        create_footer(
+            tr!("Tab: Output name • D: Directory • Enter: Merge • A: Queue • Esc: Back • ?: Keys"),
+            chunks[5].width,
+        ),
+        chunks[5],
+    );
+}
+
+/**
+ * Add up the selected files known to the inspect cache.
+ * @returns The total page count, the total size, and how many files are not in the cache.
+ */
+fn merge_totals(app: &App) -> (usize, u64, usize) {
+    let infos: Vec<_> = app
+        .selected_files()
+        .iter()
+        .filter_map(|file| app.inspect_cache.get(file))
+        .collect();
+    let total_pages: usize = infos.iter().map(|info| info.page_count()).sum();
+    let total_size: u64 = infos.iter().map(|info| info.file_size).sum();
+    (total_pages, total_size, app.files_len() - infos.len())
+}
+
+/**
+ * Title of the list of files to merge, with their count, total pages and estimated size.
+ * Files still being loaded are left out of the totals until they are read.
+ */
+fn merge_list_title(app: &App) -> String {
+    if app.files_is_empty() {
+        return tr!("Files to Merge").to_string();
+    }
+    let (total_pages, total_size, _) = merge_totals(app);
+    tr!(
+        "Files to Merge ({files} files, {pages} pages total, est. {size})",
+        files = app.files_len(),
+        pages = total_pages,
+        size = format_size(total_size)
+    )
+}
+
+/**
+ * Summarize the merge output: total pages and estimated size (the sum of the inputs).
+ */
+fn create_merge_summary(app: &App) -> Paragraph<'_> {
+    let (total_pages, total_size, unreadable) = merge_totals(app);
+
+    let mut summary = tr!(
+        "{files} files • {pages} pages • estimated size {size}",
+        files = app.files_len(),
+        pages = total_pages,
+        size = format_size(total_size)
+    );
+    let style = if unreadable > 0 {
+        summary.push_str(&tr!(" • {count} unreadable", count = unreadable));
+        app_theme!(error)
+    } else {
+        app_theme!(success)
+    };
+
+    Paragraph::new(summary).style(style).block(
+        Block::default()
+            .title(tr!("Output Summary"))
+            .borders(Borders::ALL),
+    )
+}
+
+/**
+ * Draw the delete configuration screen UI.
+ * Display selected files, pages to delete input, output filename input, and footer instructions.
+ */
+fn draw_delete_config_screen(frame: &mut Frame, app: &App) {
+    let chunks = screen_layout(&CurrentScreen::DeleteConfig, frame.area());
+
+    frame.render_widget(
+        create_wizard_title(tr!("✂️ Delete Configuration"), app),
+        chunks[0],
+    );
+
+    let (file_list, mut list_state) = create_file_list(
+        app.selected_files(),
+        tr!("File to Delete Pages From"),
+        Some(app.merge_file_index()),
+    );
+    render_list(frame, file_list, chunks[1], &mut list_state);
+
+    let page_count = app.first_file_page_count();
+    let pages_title = match page_count {
+        Some(count) => tr!(
+            "Pages to Delete (e.g., 1,3-5) • document has {count} pages",
+            count = count
+        ),
+        None => tr!("Pages to Delete (e.g., 1,3-5)").to_string(),
+    };
+    let validated = (!app.delete_config.pages_to_delete.is_empty())
+        .then(|| validate_delete_pages(&app.delete_config.pages_to_delete, page_count));
+    let pages_error = match &validated {
+        Some(Err(e)) => Some(e.to_string()),
+        _ => None,
+    };
+
+    let pages_field = create_validated_input_field(
+        &app.delete_config.pages_to_delete,
+        &pages_title,
+        app.delete_config.editing_pages,
+        pages_error.as_deref(),
+    );
+    frame.render_widget(pages_field, chunks[2]);
+    // The pages that remain, under the typed ones, so an inverted selection stands out
+    if let Some(Ok(pages)) = &validated
+        && let Some(count) = page_count
+        && !pages.is_empty()
+        && chunks[2].height >= 4
+    {
+        let kept = complement_pages(pages, count);
+        let deleted = count - kept.len();
+        let line = if kept.len() < deleted {
+            Span::styled(
+                tr!(
+                    "⚠ Keeping only {pages} — {kept} of {count} pages",
+                    pages = format_page_ranges(&kept),
+                    kept = kept.len(),
+                    count = count
+                ),
+                app_theme!(warning),
+            )
+        } else {
+            Span::styled(
+                tr!(
+                    "Keeping {pages} — {kept}",
+                    pages = format_page_ranges(&kept),
+                    kept = pages_label(kept.len())
+                ),
+                app_theme!(success),
+            )
+        };
+        let area = chunks[2].inner(Margin::new(1, 1));
+        frame.render_widget(
+            Paragraph::new(line),
+            Rect::new(area.x, area.y + 1, area.width, 1),
+        );
+    }
+
+    let output_text = &app.output_name(&app.delete_config.output_filename, DEFAULT_DELETE_OUTPUT);
+
+    let output_error = output_name_error(&app.delete_config.output_filename);
+    let output_field = create_validated_input_field(
+        output_text,
+        tr!("Output Filename"),
+        app.delete_config.editing_output,
+        output_error.as_deref(),
+    );
+    frame.render_widget(output_field, chunks[4]);
+
+    let resolved = app.resolve_output(output_text);
+    let existing = usize::from(Path::new(&resolved).exists());
+    frame.render_widget(
+        create_output_directory_field(app, &resolved, existing),
+        chunks[3],
+    );
+
+    frame.render_widget(
+        create_footer(
+            tr!("p: Pages • g: Grid • Tab: Output name • d: Directory • Enter: Delete • a: Queue • ?: Keys"), chunks[5].width),
+        chunks[5],
+    );
+}
+
+/**
+ * Draw the rotate configuration screen UI.
+ * Display the rotation rules of the selected file, the pages and angle of the selected rule,
+ * output location, and footer instructions.
+ */
+fn draw_rotate_config_screen(frame: &mut Frame, app: &App) {
+    let chunks = screen_layout(&CurrentScreen::RotateConfig, frame.area());
+    let config = &app.rotate_config;
+    let rule = config.rule();
+
+    frame.render_widget(
+        create_wizard_title(tr!("🔄 Rotate Configuration"), app),
+        chunks[0],
+    );
+
+    let page_count = app.first_file_page_count();
+    let file = app.selected_files().first().map_or("", |file| {
+        Path::new(file)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(file)
+    });
+    let rules_title = tr!("Rotation Rules • {file} (N: Add • X: Remove)", file = file);
+    let (rule_list, mut list_state) = create_rotate_rule_list(app, page_count, &rules_title);
+    render_list(frame, rule_list, chunks[1], &mut list_state);
+
+    let pages_title = match page_count {
+        Some(count) => tr!(
+            "Pages to Rotate (e.g., 1,3-5, empty for all) • document has {count} pages",
+            count = count
+        ),
+        None => tr!("Pages to Rotate (e.g., 1,3-5, empty for all)").to_string(),
+    };
+    let pages_error = if rule.pages.is_empty() {
+        None
+    } else {
+        validate_page_ranges_in_document(&rule.pages, page_count)
+            .err()
+            .map(|e| e.to_string())
+    };
+    let pages_text = if rule.pages.is_empty() && !config.editing_pages {
+        tr!("all pages")
+    } else {
+        &rule.pages
+    };
+    let pages_field = create_validated_input_field(
+        pages_text,
+        &pages_title,
+        config.editing_pages,
+        pages_error.as_deref(),
+    );
+    frame.render_widget(pages_field, chunks[2]);
+
+    let angle_text = format!("◀ {} ▶", tr!("{angle}° clockwise", angle = rule.angle));
+    frame.render_widget(
+        create_input_field(
+            &angle_text,
+            tr!("Rotation (←/→: 90° / 180° / 270°)"),
+            false,
+            None,
+        ),
+        chunks[3],
+    );
+
+    let output_text = &app.output_name(&config.output_filename, DEFAULT_ROTATE_OUTPUT);
+    let resolved = app.resolve_output(output_text);
+    let existing = usize::from(Path::new(&resolved).exists());
+    frame.render_widget(
+        create_output_directory_field(app, &resolved, existing),
+        chunks[4],
+    );
+    let output_error = output_name_error(&config.output_filename);
+    frame.render_widget(
+        create_validated_input_field(
+            output_text,
+            tr!("Output Filename"),
+            config.editing_output,
+            output_error.as_deref(),
+        ),
+        chunks[5],
+    );
+
+    frame.render_widget(
+        create_footer(
+            tr!("↑/↓: Rule • p: Pages • ←/→: Angle • g: Grid • Tab: Output name • d: Directory • Enter: Rotate • ?: Keys"), chunks[6].width),
+        chunks[6],
+    );
+}
+
+/**
+ * Create the list of rotation rules, one line per rule with its pages and angle.
+ * The selected rule shows its angle between arrows, and invalid pages are flagged on their rule.
+ * @param app The application state.
+ * @param page_count The page count of the document, when known.
+ * @param title The title of the list.
+ * @returns The list and its selection.
+ */
+fn create_rotate_rule_list<'a>(
+    app: &App,
+    page_count: Option<usize>,
+    title: &'a str,
+) -> (List<'a>, ListState) {
+    let config = &app.rotate_config;
+    let items: Vec<ListItem> = config
+        .rules
+        .iter()
+        .enumerate()
+        .map(|(i, rule)| {
+            let pages = if rule.pages.is_empty() {
+                tr!("all pages").to_string()
+            } else {
+                tr!("pages {pages}", pages = rule.pages)
+            };
+            let angle = if i == config.selected_rule {
+                format!("◀ {}° ▶", rule.angle)
+            } else {
+                format!("{}°", rule.angle)
+            };
+            let text = format!("{}. {} → {}", i + 1, pages, angle);
+            let error = (!rule.pages.is_empty())
+                .then(|| validate_page_ranges_in_document(&rule.pages, page_count).err())
+                .flatten();
+            match error {
+                Some(e) => ListItem::new(format!("{}  ✗ {}", text, e)).style(app_theme!(error)),
+                None => ListItem::new(text),
+            }
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .style(app_theme!(normal))
+        .highlight_style(app_theme!(highlight))
+        .highlight_symbol("▶ ");
+    (
+        list,
+        ListState::default().with_selected(Some(config.selected_rule)),
+    )
+}
+
+/**
+ * Draw the extract configuration screen UI.
+ * Display the selected file, pages to extract, output location, and footer instructions.
+ */
+fn draw_extract_config_screen(frame: &mut Frame, app: &App) {
+    let chunks = screen_layout(&CurrentScreen::ExtractConfig, frame.area());
+    let config = &app.extract_config;
+
+    frame.render_widget(
+        create_wizard_title(tr!("📑 Extract Configuration"), app),
+        chunks[0],
+    );
+
+    let (file_list, mut list_state) = create_file_list(
+        app.selected_files(),
+        tr!("File to Extract Pages From"),
+        Some(0),
+    );
+    render_list(frame, file_list, chunks[1], &mut list_state);
+
+    let page_count = app.first_file_page_count();
+    let pages_title = match page_count {
+        Some(count) => tr!(
+            "Pages to Extract (e.g., 1,3-5) • document has {count} pages",
+            count = count
+        ),
+        None => tr!("Pages to Extract (e.g., 1,3-5)").to_string(),
+    };
+    let pages_error = if config.pages.is_empty() {
+        None
+    } else {
+        validate_page_order_in_document(&config.pages, page_count)
+            .err()
+            .map(|e| e.to_string())
+    };
+    let pages_field = create_validated_input_field(
+        &config.pages,
+        &pages_title,
+        config.editing_pages,
+        pages_error.as_deref(),
+    );
+    frame.render_widget(pages_field, chunks[2]);
+    let annotations_text = if config.options.keep_annotations {
+        tr!("kept, with the links between extracted pages")
+    } else {
+        tr!("left out")
+    };
+    frame.render_widget(
+        create_input_field(annotations_text, tr!("Annotations (N: keep)"), false, None),
+        chunks[3],
+    );
+
+    let output_text = &app.output_name(&config.output_filename, DEFAULT_EXTRACT_OUTPUT);
+    let resolved = app.resolve_output(output_text);
+    let existing = usize::from(Path::new(&resolved).exists());
+    frame.render_widget(
+        create_output_directory_field(app, &resolved, existing),
+        chunks[4],
+    );
+    let output_error = output_name_error(&config.output_filename);
+    frame.render_widget(
+        create_validated_input_field(
+            output_text,
+            tr!("Output Filename"),
+            config.editing_output,
+            output_error.as_deref(),
+        ),
+        chunks[5],
+    );
+
+    frame.render_widget(
+        create_footer(
+            tr!("p: Pages • g: Grid • o: Order • n: Annotations • Tab: Output name • d: Directory • Enter: Extract • a: Queue • ?: Keys"), chunks[6].width),
+        chunks[6],
+    );
+}
+
+/**
+ * Draw the compress configuration screen UI.
+ * Display the selected file, the compression options, output location, and footer instructions.
+ */
+fn draw_compress_config_screen(frame: &mut Frame, app: &App) {
+    let chunks = screen_layout(&CurrentScreen::CompressConfig, frame.area());
+    let config = &app.compress_config;
+    let options = &config.options;
+
+    frame.render_widget(
+        create_wizard_title(tr!("🗜️  Compress Configuration"), app),
+        chunks[0],
+    );
+
+    let (file_list, mut list_state) =
+        create_file_list(app.selected_files(), tr!("File to Compress"), Some(0));
+    render_list(frame, file_list, chunks[1], &mut list_state);
+
+    let streams_text = if options.recompress_streams {
+        tr!("recompressed at the best level")
+    } else {
+        tr!("left unchanged")
+    };
+    frame.render_widget(
+        create_input_field(streams_text, tr!("Streams (S: recompress)"), false, None),
+        chunks[2],
+    );
+    let dpi_text = match options.max_image_dpi {
+        Some(dpi) => tr!("downsampled above {dpi} dpi", dpi = dpi),
+        None => tr!("kept as they are").to_string(),
+    };
+    frame.render_widget(
+        create_input_field(&dpi_text, tr!("Images (I / ← →: resolution)"), false, None),
+        chunks[3],
+    );
+    let metadata_text = if options.strip_metadata {
+        tr!("stripped")
+    } else {
+        tr!("kept")
+    };
+    frame.render_widget(
+        create_input_field(metadata_text, tr!("Metadata (M: strip)"), false, None),
+        chunks[4],
+    );
+    let annotations_text = if options.flatten_annotations {
+        tr!("drawn into the pages, then removed")
+    } else {
+        tr!("kept")
+    };
+    frame.render_widget(
+        create_input_field(
+            annotations_text,
+            tr!("Annotations (F: flatten)"),
+            false,
+            None,
+        ),
+        chunks[5],
+    );
+
+    let output_text = &app.output_name(&config.output_filename, DEFAULT_COMPRESS_OUTPUT);
+    let resolved = app.resolve_output(output_text);
+    let existing = usize::from(Path::new(&resolved).exists());
+    frame.render_widget(
+        create_output_directory_field(app, &resolved, existing),
+        chunks[6],
+    );
+    let output_error = output_name_error(&config.output_filename);
+    frame.render_widget(
+        create_validated_input_field(
+            output_text,
+            tr!("Output Filename"),
+            config.editing_output,
+            output_error.as_deref(),
+        ),
+        chunks[7],
+    );
+
+    frame.render_widget(
+        create_footer(
+            tr!("s: Streams • i: Images • m: Metadata • f: Annotations • Tab: Output name • d: Directory • Enter: Compress • a: Queue • ?: Keys"), chunks[8].width),
+        chunks[8],
+    );
+}
+
+/**
+ * Draw the watermark configuration screen UI.
+ * Display the selected file next to a live preview of the stamp, the watermark settings,
+ * output location, and footer instructions.
+ */
+fn draw_watermark_config_screen(frame: &mut Frame, app: &App) {
+    let chunks = screen_layout(&CurrentScreen::WatermarkConfig, frame.area());
+    let config = &app.watermark_config;
+
+    frame.render_widget(
+        create_wizard_title(tr!("💧 Watermark Configuration"), app),
+        chunks[0],
+    );
+
+    let body = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+        .split(chunks[1]);
+    let (file_list, mut list_state) =
+        create_file_list(app.selected_files(), tr!("File to Watermark"), Some(0));
+    render_list(frame, file_list, body[0], &mut list_state);
+    draw_watermark_preview(frame, app, body[1]);
+
+    let image_error = if !config.use_image || config.image_path.is_empty() || config.editing_image {
+        None
+    } else {
+        validate_image_input(&config.image_path)
+            .err()
+            .map(|e| e.to_string())
+    };
+    let source_field = if config.use_image {
+        create_validated_input_field(
+            &config.image_path,
+            tr!("Image (I: JPEG or PNG path • T: text instead)"),
+            config.editing_image,
+            image_error.as_deref(),
+        )
+    } else {
+        create_input_field(
+            &config.text,
+            tr!("Text (T: edit • I: image instead)"),
+            config.editing_text,
+            None,
+        )
+    };
+    frame.render_widget(source_field, chunks[2]);
+
+    frame.render_widget(
+        create_input_field(
+            watermark_position_label(config.position),
+            tr!("Position (O: next preset)"),
+            false,
+            None,
+        ),
+        chunks[3],
+    );
+
+    let opacity_text = opacity_bar(config.opacity);
+    frame.render_widget(
+        create_input_field(&opacity_text, tr!("Opacity (← →)"), false, None),
+        chunks[4],
+    );
+
+    let page_count = app.first_file_page_count();
+    let pages_title = match page_count {
+        Some(count) => tr!(
+            "Pages to Stamp (e.g., 1,3-5, empty for all) • document has {count} pages",
+            count = count
+        ),
+        None => tr!("Pages to Stamp (e.g., 1,3-5, empty for all)").to_string(),
+    };
+    let pages_error = if config.pages.is_empty() {
+        None
+    } else {
+        validate_page_ranges_in_document(&config.pages, page_count)
+            .err()
+            .map(|e| e.to_string())
+    };
+    let pages_text = if config.pages.is_empty() && !config.editing_pages {
+        tr!("all pages")
+    } else {
+        &config.pages
+    };
+    frame.render_widget(
+        create_validated_input_field(
+            pages_text,
+            &pages_title,
+            config.editing_pages,
+            pages_error.as_deref(),
+        ),
+        chunks[5],
+    );
+
+    let output_text = &app.output_name(&config.output_filename, DEFAULT_WATERMARK_OUTPUT);
+    let resolved = app.resolve_output(output_text);
+    let existing = usize::from(Path::new(&resolved).exists());
+    frame.render_widget(
+        create_output_directory_field(app, &resolved, existing),
+        chunks[6],
+    );
+    let output_error = output_name_error(&config.output_filename);
+    frame.render_widget(
+        create_validated_input_field(
+            output_text,
+            tr!("Output Filename"),
+            config.editing_output,
+            output_error.as_deref(),
+        ),
+        chunks[7],
+    );
+
+    frame.render_widget(
+        create_footer(
+            tr!("t: Text • i: Image • o: Position • ←→: Opacity • p: Pages • g: Grid • Tab: Output name • d: Directory • Enter: Stamp • a: Queue • ?: Keys"), chunks[8].width),
+        chunks[8],
+    );
+}
+
+/// Name of a watermark position preset, in the current language.
+fn watermark_position_label(position: WatermarkPosition) -> &'static str {
+    translate(match position {
+        WatermarkPosition::Center => "centered",
+        WatermarkPosition::Diagonal => "diagonal, across the page",
+        WatermarkPosition::TopLeft => "top left corner",
+        WatermarkPosition::TopRight => "top right corner",
+        WatermarkPosition::BottomLeft => "bottom left corner",
+        WatermarkPosition::BottomRight => "bottom right corner",
+    })
+}
+
+/// Cells of the opacity slider, one per OPACITY_STEP.
+const OPACITY_BAR_CELLS: usize = (100 / OPACITY_STEP) as usize;
+
+/// The opacity as a slider, e.g. "██████░░░░░░░░░░░░░░ 30%".
+fn opacity_bar(opacity: u8) -> String {
+    let filled = usize::from(opacity / OPACITY_STEP).min(OPACITY_BAR_CELLS);
+    format!(
+        "{}{} {}%",
+        "█".repeat(filled),
+        "░".repeat(OPACITY_BAR_CELLS - filled),
+        opacity
+    )
+}
+
+/**
+ * Draw the live preview of the watermark: what is stamped on which pages,
+ * and a sketch of the first page showing where the stamp lands.
+ * @param frame The frame to draw on.
+ * @param app The application state.
+ * @param area The area of the preview, borders included.
+ */
+fn draw_watermark_preview(frame: &mut Frame, app: &App, area: Rect) {
+    let config = &app.watermark_config;
+    let info = app
+        .selected_files()
+        .first()
+        .and_then(|file| app.inspect_cache.get(file));
+
+    let stamp = if config.use_image {
+        match Path::new(&config.image_path).file_name() {
+            Some(name) => tr!("image {name}", name = name.to_string_lossy()),
+            None => tr!("no image chosen yet").to_string(),
+        }
+    } else if config.text.trim().is_empty() {
+        tr!("no text typed yet").to_string()
+    } else {
+        format!("“{}”", config.text)
+    };
+    let pages = match (config.pages.is_empty(), info) {
+        (true, Some(info)) => tr!("all {count} pages", count = info.page_count()),
+        (true, None) => tr!("all pages").to_string(),
+        (false, _) => tr!("pages {pages}", pages = config.pages),
+    };
+    let mut lines = vec![
+        Line::from(Span::styled(stamp, app_theme!(accent))),
+        Line::from(tr!(
+            "{position} • {opacity}% opaque • {pages}",
+            position = watermark_position_label(config.position),
+            opacity = config.opacity,
+            pages = pages
+        )),
+    ];
+
+    // Terminal cells are about twice as tall as wide
+    let page_size = info
+        .and_then(|info| info.pages.first())
+        .map(|page| page.displayed_size())
+        .unwrap_or((595.0, 842.0));
+    let inner_height = area.height.saturating_sub(2 + lines.len() as u16);
+    let inner_width = area.width.saturating_sub(2);
+    let rows = inner_height.min(11) as usize;
+    let columns = ((rows as f32 * 2.0 * page_size.0 / page_size.1).round() as usize)
+        .min(inner_width as usize);
+    if rows >= 4 && columns >= 4 {
+        let mark = if config.use_image {
+            "▓"
+        } else {
+            config.text.trim()
+        };
+        lines.extend(
+            watermark_sketch(config.position, mark, rows, columns)
+                .into_iter()
+                .map(|row| Line::from(Span::styled(row, app_theme!(normal)))),
+        );
+    }
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .style(app_theme!(normal))
+            .block(Block::default().title(tr!("Preview")).borders(Borders::ALL)),
+        area,
+    );
+}
+
+/**
+ * Sketch a page with its border, and the watermark at its position.
+ * @param position Where the watermark is placed.
+ * @param mark Characters the watermark is drawn with, repeated (the text, or a block for an image).
+ * @param rows Height of the sketch, border included.
+ * @param columns Width of the sketch, border included.
+ * @returns The rows of the sketch.
+ */
+fn watermark_sketch(
+    position: WatermarkPosition,
+    mark: &str,
+    rows: usize,
+    columns: usize,
+) -> Vec<String> {
+    let (height, width) = (rows - 2, columns - 2);
+    let mut cells = vec![vec![' '; width]; height];
+    let mut mark = mark.chars().filter(|c| !c.is_whitespace()).cycle();
+    let mut put = |row: usize, column: usize| {
+        cells[row][column] = mark.next().unwrap_or('▒');
+    };
+
+    let span = (width * 3 / 5).max(1);
+    let corner = (width / 3).max(1);
+    match position {
+        WatermarkPosition::Diagonal => {
+            for column in 0..width {
+                put(height - 1 - column * height / width, column);
+            }
+        }
+        WatermarkPosition::Center => {
+            let start = (width - span) / 2;
+            (start..start + span).for_each(|column| put(height / 2, column));
+        }
+        WatermarkPosition::TopLeft => (0..corner).for_each(|column| put(0, column)),
+        WatermarkPosition::TopRight => {
+            (width - corner..width).for_each(|column| put(0, column));
+        }
+        WatermarkPosition::BottomLeft => (0..corner).for_each(|column| put(height - 1, column)),
+        WatermarkPosition::BottomRight => {
+            (width - corner..width).for_each(|column| put(height - 1, column));
+        }
+    }
+
+    let border = "─".repeat(width);
+    std::iter::once(format!("┌{border}┐"))
+        .chain(
+            cells
+                .into_iter()
+                .map(|row| format!("│{}│", row.into_iter().collect::<String>())),
+        )
+        .chain(std::iter::once(format!("└{border}┘")))
+        .collect()
+}
+
+/**
+ * Draw the processing screen: the operation running on its worker, with the time spent so far.
+ * @param frame The frame to draw on.
+ * @param app The application state.
+ */
+fn draw_processing_screen(frame: &mut Frame, app: &App) {
+    let chunks = screen_layout(&CurrentScreen::Processing, frame.area());
+
+    frame.render_widget(create_wizard_title(tr!("⏳ Processing"), app), chunks[0]);
+
+    let Some(processing) = &app.processing else {
+        return;
+    };
+    let elapsed = processing.job.elapsed();
+    let mut lines = vec![
+        Line::from(format!(
+            "{} {}",
+            spinner_frame(elapsed),
+            processing.description
+        )),
+        Line::styled(
+            tr!("Elapsed {time}", time = format_duration(elapsed)),
+            app_theme!(footer),
+        ),
+    ];
+    if processing.job.is_cancelled() {
+        lines.push(Line::styled(
+            tr!("Cancelling… the files being written will be deleted"),
+            app_theme!(error),
+        ));
+    }
+    let body = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(tr!("Progress"))
+                .borders(Borders::ALL),
+        )
+        .wrap(Wrap { trim: false });
+    frame.render_widget(body, chunks[1]);
+
+    frame.render_widget(
+        create_footer(tr!("Esc/c: Cancel"), chunks[2].width),
+        chunks[2],
+    );
+}
+
+fn draw_split_config_screen(frame: &mut Frame, app: &App) {
+    let chunks = screen_layout(&CurrentScreen::SplitConfig, frame.area());
+
+    frame.render_widget(
+        create_wizard_title(tr!("🔪 Split Configuration"), app),
+        chunks[0],
+    );
+
+    // File to split, with the preview of the generated files below it
+    let file_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(chunks[1]);
+
+    let (file_list, mut list_state) = create_file_list(
+        app.selected_files(),
+        tr!("File to Split"),
+        Some(0), // Only one file for split
+    );
+    render_list(frame, file_list, file_chunks[0], &mut list_state);
+    draw_split_preview(frame, app, file_chunks[1]);
+
+    // Page segments input
+    let (segments_text, segments_title) = if app.split_config.use_named_segments {
+        (
+            if app.split_config.segments.is_empty() {
+                NAMED_SEGMENTS_EXAMPLE
+            } else {
+                &app.split_config.segments
+            },
+            tr!("Named Segments (e.g., intro:1-3,chapter1:4-10,conclusion:11)"),
+        )
+    } else {
+        (
+            if app.split_config.segments.is_empty() {
+                PAGE_SEGMENTS_EXAMPLE
+            } else {
+                &app.split_config.segments
+            },
+            tr!("Page Segments (e.g., 1-3,5,7-9)"),
+        )
+    };
+
+    let segments_error = if app.split_config.segments.is_empty() {
+        None
+    } else {
+        validate_split_in_document(
+            &app.split_config.segments,
+            app.split_config.use_named_segments,
+            app.first_file_page_count(),
+            app.split_config.allow_overlaps,
+        )
+        .err()
+        .map(|e| e.to_string())
+    };
+    let segments_field = create_validated_input_field(
+        segments_text,
+        segments_title,
+        app.split_config.editing_segments,
+        segments_error.as_deref(),
+    );
+    frame.render_widget(segments_field, chunks[2]);
+
+    frame.render_widget(
+        create_segment_mode_selector(
+            app.split_config.use_named_segments,
+            app.split_config.allow_overlaps,
+        ),
+        chunks[3],
+    );
+
+    let output_text = &app.split_output_prefix();
+
+    let output_error = output_name_error(&app.split_config.output_prefix);
+    let output_field = create_validated_input_field(
+        output_text,
+        tr!("Output Prefix"),
+        app.split_config.editing_prefix,
+        output_error.as_deref(),
+    );
+    frame.render_widget(output_field, chunks[5]);
+
+    let resolved = format!("{}_*.pdf", app.resolve_output(output_text));
+    let existing = split_output_paths(app)
+        .iter()
+        .filter(|path| Path::new(path).exists())
+        .count();
+    frame.render_widget(
+        create_output_directory_field(app, &resolved, existing),
+        chunks[4],
+    );
+
+    let instructions = if app.split_config.editing_segments {
+        tr!("Enter: Save segments • Esc: Cancel")
+    } else if app.split_config.editing_prefix {
+        tr!("Enter: Save prefix • Esc: Cancel")
+    } else if app.output_location.editing {
+        tr!("Tab: Complete directory • Enter: Save directory • Esc: Cancel")
+    } else {
+        tr!(
+            "S: Segments • Space: Named • O: Overlaps • P: Prefix • D: Directory • Enter: Split • A: Queue • ?: Keys"
+        )
+    };
+
+    frame.render_widget(create_footer(instructions, chunks[6].width), chunks[6]);
+}
+
+/**
+ * Draw the page grid screen UI.
+ * Display every page of the document as a cell, with selected pages and the cursor highlighted.
+ * @param frame The frame to draw on.
+ * @param app The application state.
+ */
+fn draw_page_grid_screen(frame: &mut Frame, app: &App) {
+    let chunks = screen_layout(&CurrentScreen::PageGrid, frame.area());
+    let grid = &app.page_grid;
+
+    let title = match app.operation_mode {
+        OperationMode::Delete => tr!("🔢 Select Pages to Delete"),
+        OperationMode::Rotate => tr!("🔢 Select Pages to Rotate"),
+        OperationMode::Extract => tr!("🔢 Select Pages to Extract"),
+        OperationMode::Watermark => tr!("🔢 Select Pages to Watermark"),
+        _ => tr!("🔢 Select Pages"),
+    };
+    frame.render_widget(create_wizard_title(title, app), chunks[0]);
+
+    let (grid_area, preview_area) = page_grid_areas(chunks[1]);
+    let visible_rows = grid_area.height.saturating_sub(2);
+    let first_row = page_grid_first_row(grid, visible_rows);
+    let total_rows = grid.page_count.div_ceil(PAGE_GRID_COLUMNS);
+
+    let lines: Vec<Line> = (first_row..total_rows)
+        .take(visible_rows as usize)
+        .map(|row| {
+            let spans: Vec<Span> = (1..=PAGE_GRID_COLUMNS)
+                .map(|column| row * PAGE_GRID_COLUMNS + column)
+                .take_while(|&page| page <= grid.page_count)
+                .map(|page| {
+                    let mut style = if grid.selected.contains(&page) {
+                        app_theme!(error).add_modifier(Modifier::BOLD)
+                    } else {
+                        app_theme!(normal)
+                    };
+                    if page == grid.cursor {
+                        style = style.add_modifier(Modifier::REVERSED);
+                    }
+                    let marker = if grid.selected.contains(&page) {
+                        "*"
+                    } else {
+                        " "
+                    };
+                    Span::styled(format!(" {:>3}{} ", page, marker), style)
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect();
+
+    let grid_title = tr!(
+        "Document has {pages} pages ({selected} selected)",
+        pages = grid.page_count,
+        selected = grid.selected.len()
+    );
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::default().title(grid_title).borders(Borders::ALL)),
+        grid_area,
+    );
+
+    if let Some(preview_area) = preview_area {
+        draw_page_preview(frame, app, preview_area);
+    }
+
+    let spec = format_page_ranges(&grid.selected_pages());
+    frame.render_widget(
+        create_input_field(&spec, tr!("Pages"), false, None),
+        chunks[2],
+    );
+
+    frame.render_widget(
+        create_footer(
+            tr!("←↑↓→: Move • Space: Toggle • Shift+Move: Select range • a: All • Enter: Apply • Esc: Cancel"), chunks[3].width),
+        chunks[3],
+    );
+}
+
+/**
+ * Draw the page order screen.
+ * List every page of the document in output order with its size, removed pages crossed out.
+ * @param frame The frame to draw on.
+ * @param app The application state.
+ */
+fn draw_page_order_screen(frame: &mut Frame, app: &App) {
+    let chunks = screen_layout(&CurrentScreen::PageOrder, frame.area());
+    let state = &app.page_order;
+
+    frame.render_widget(
+        create_wizard_title(tr!("🔀 Order Pages to Extract"), app),
+        chunks[0],
+    );
+
+    let items: Vec<ListItem> = state
+        .order
+        .iter()
+        .enumerate()
+        .map(|(i, &page)| {
+            let size = state
+                .page_info(page)
+                .map(|info| info.describe())
+                .unwrap_or_default();
+            let label = tr!("Page {page}", page = page);
+            if state.removed.contains(&page) {
+                ListItem::new(format!("{:>3}. ✗ {} • {}", i + 1, label, size))
+                    .style(app_theme!(footer).add_modifier(Modifier::CROSSED_OUT))
+            } else {
+                ListItem::new(format!("{:>3}. {} • {}", i + 1, label, size))
+            }
+        })
+        .collect();
+    let kept = state.kept_pages();
+    let list_title = tr!(
+        "Document has {pages} pages ({kept} kept)",
+        pages = state.order.len(),
+        kept = kept.len()
+    );
+    let list = List::new(items)
+        .block(Block::default().title(list_title).borders(Borders::ALL))
+        .style(app_theme!(normal))
+        .highlight_style(app_theme!(highlight))
+        .highlight_symbol("▶ ");
+    let mut list_state = ListState::default().with_selected(Some(state.cursor));
+    render_list(frame, list, chunks[1], &mut list_state);
+
+    let spec = format_page_ranges(&kept);
+    frame.render_widget(
+        create_input_field(&spec, tr!("Pages"), false, None),
+        chunks[2],
+    );
+
+    frame.render_widget(
+        create_footer(
+            tr!("↑/↓: Select • Alt+↑/↓: Move • d: Remove/restore • Enter: Apply • Esc: Cancel"),
+            chunks[3].width,
+        ),
+        chunks[3],
+    );
+}
+
+/// Lines of page details under the outline of the page preview.
+const PAGE_PREVIEW_INFO_LINES: u16 = 2;
+
+/**
+ * Outline of the page under the grid cursor in the preview panel, with the page proportions.
+ * @param inner The inside of the preview panel.
+ * @param page The page under the cursor.
+ * @returns The outline, or None when the panel is too small for one.
+ */
+fn page_outline(inner: Rect, page: &PageInfo) -> Option<Rect> {
+    // Terminal cells are roughly twice as tall as they are wide
+    let (width, height) = page.displayed_size();
+    let max_height = inner.height.saturating_sub(PAGE_PREVIEW_INFO_LINES + 1);
+    let mut outline_height = max_height;
+    let mut outline_width = (outline_height as f32 * 2.0 * width / height).round() as u16;
+    if outline_width > inner.width {
+        outline_width = inner.width;
+        outline_height = (outline_width as f32 * height / (2.0 * width)).round() as u16;
+    }
+
+    (outline_height >= 3 && outline_width >= 3).then(|| {
+        Rect::new(
+            inner.x + (inner.width - outline_width) / 2,
+            inner.y,
+            outline_width,
+            outline_height,
+        )
+    })
+}
+
+/**
+ * Cells the thumbnail of the page under the grid cursor is drawn over: the inside of its
+ * outline in the preview panel, while no popup covers the screen.
+ * @param frame_area The whole terminal.
+ * @param app The application state.
+ * @returns The cells, or None when no thumbnail is to be shown.
+ */
+pub fn page_thumbnail_area(frame_area: Rect, app: &App) -> Option<Rect> {
+    let popup_shown = app.space_prompt.is_some()
+        || app.in_place_prompt.as_ref().is_some_and(|p| !p.confirmed)
+        || app.ui_state.discard_prompt.is_some()
+        || app.ui_state.toast.is_some()
+        || app.palette.open
+        || app.ui_state.show_cheat_sheet;
+    if app.current_screen != CurrentScreen::PageGrid || popup_shown || is_too_small(frame_area) {
+        return None;
+    }
+
+    let chunks = screen_layout(&CurrentScreen::PageGrid, frame_area);
+    let preview_area = page_grid_areas(chunks[1]).1?;
+    let inner = Block::default().borders(Borders::ALL).inner(preview_area);
+    let outline = page_outline(inner, app.page_grid.current_page()?)?;
+    Some(Block::default().borders(Borders::ALL).inner(outline))
+}
+
+/**
+ * Draw the preview of the page under the grid cursor: an outline with the page proportions,
+ * its paper size, orientation and rotation.
+ * Once the page is rendered, the outline is left empty for the event loop to draw the thumbnail
+ * over it through the graphics protocol of the terminal; until then, and on terminals without
+ * one, it holds the page number.
+ */
+fn draw_page_preview(frame: &mut Frame, app: &App, area: Rect) {
+    let grid = &app.page_grid;
+    let block = Block::default()
+        .title(tr!("Page {page}", page = grid.cursor))
+        .borders(Borders::ALL);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let Some(page) = grid.current_page() else {
+        return;
+    };
+
+    let info_lines = vec![
+        Line::from(page.describe()),
+        Line::from(tr!("Rotation: {angle}°", angle = page.rotation)),
+    ];
+
+    if let Some(outline) = page_outline(inner, page) {
+        let (label, style) = if grid.selected.contains(&grid.cursor) {
+            (tr!("selected"), app_theme!(error))
+        } else {
+            ("", app_theme!(normal))
+        };
+        let thumbnail_shown = page_thumbnail_area(frame.area(), app).is_some_and(|cells| {
+            let key = app.thumbnails.key(&grid.path, grid.cursor, cells);
+            app.thumbnails.get(&key).is_some()
+        });
+        let content = if thumbnail_shown {
+            Vec::new()
+        } else {
+            let text_row = outline.height.saturating_sub(2) / 2;
+            let mut content = vec![Line::from(""); text_row as usize];
+            content.push(Line::from(grid.cursor.to_string()));
+            content.push(Line::from(label));
+            content
+        };
+        let mut outline_block = Block::default().borders(Borders::ALL);
+        if thumbnail_shown {
+            outline_block = outline_block.title(label);
+        }
+        frame.render_widget(
+            Paragraph::new(content)
+                .style(style)
+                .alignment(Alignment::Center)
+                .block(outline_block),
+            outline,
+        );
+    }
+
+    let info_area = Rect::new(
+        inner.x,
+        inner.y + inner.height.saturating_sub(PAGE_PREVIEW_INFO_LINES),
+        inner.width,
+        PAGE_PREVIEW_INFO_LINES.min(inner.height),
+    );
+    frame.render_widget(
+        Paragraph::new(info_lines).wrap(Wrap { trim: true }),
+        info_area,
+    );
+}
+
+/**
+ * Resolved paths of the files the split would write, or nothing while the segments are invalid.
+ */
+fn split_output_paths(app: &App) -> Vec<String> {
+    preview_split_outputs(
+        &app.split_config.segments,
+        app.split_config.use_named_segments,
+        &app.split_output_prefix(),
+    )
+    .map(|outputs| {
+        outputs
+            .iter()
+            .map(|(filename, _)| app.resolve_output(filename))
+            .collect()
+    })
+    .unwrap_or_default()
+}
+
+/**
+ * Draw the list of files the split will generate, with their page counts.
+ * Segments past the end of the document are flagged so mistakes show up before running.
+ */
+fn draw_split_preview(frame: &mut Frame, app: &App, area: Rect) {
+    let prefix = &app.split_output_prefix();
+    let page_count = app.first_file_page_count();
+
+    let (items, title): (Vec<ListItem>, String) = if app.split_config.segments.is_empty() {
+        (
+            vec![
+                ListItem::new(tr!("Type segments to preview the generated files"))
+                    .style(app_theme!(footer)),
+            ],
+            tr!("Output Preview").to_string(),
+        )
+    } else {
+        match preview_split_outputs(
+            &app.split_config.segments,
+            app.split_config.use_named_segments,
+            prefix,
+        ) {
+            Ok(outputs) => {
+                let items = outputs
+                    .iter()
+                    .map(|(filename, segment)| {
+                        let pages = segment.get_pages();
+                        let last_page = pages.iter().copied().max().unwrap_or(0) as usize;
+                        match page_count {
+                            Some(count) if last_page > count => ListItem::new(tr!(
+                                "{file} — page {page} is past the end ({count} pages)",
+                                file = filename,
+                                page = last_page,
+                                count = count
+                            ))
+                            .style(app_theme!(error)),
+                            _ if Path::new(&app.resolve_output(filename)).exists() => {
+                                ListItem::new(tr!(
+                                    "{file} — {pages} • ⚠ exists",
+                                    file = filename,
+                                    pages = pages_label(pages.len())
+                                ))
+                                .style(app_theme!(warning))
+                            }
+                            _ => ListItem::new(format!(
+                                "{} — {}",
+                                filename,
+                                pages_label(pages.len())
+                            )),
+                        }
+                    })
+                    .collect();
+                (
+                    items,
+                    tr!("Output Preview ({count} files)", count = outputs.len()),
+                )
+            }
+            Err(e) => (
+                vec![ListItem::new(e.to_string()).style(app_theme!(error))],
+                tr!("Output Preview").to_string(),
+            ),
+        }
+    };
+
+    let preview = List::new(items)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .style(app_theme!(normal));
+    frame.render_widget(preview, area);
+}
+
+/**
+ * Draw the result screen UI.
+ * Display success or error message after operation.
+ * @param frame The frame to draw on.
+ * @param app The application state.
+ */
+fn draw_result_screen(frame: &mut Frame, app: &App) {
+    let chunks = screen_layout(&CurrentScreen::Result, frame.area());
+
+    frame.render_widget(
+        create_wizard_title(tr!("📋 Operation Result"), app),
+        chunks[0],
+    );
+
+    let (message, style) = if let Some(err) = app.error_message() {
+        (prefixed_message(err, true), app_theme!(error))
+    } else if let Some(success) = app.success_message() {
+        (prefixed_message(success, false), app_theme!(success))
+    } else {
+        (tr!("No result available").to_string(), app_theme!(input))
+    };
+    let message_paragraph = Paragraph::new(message)
+        .style(style)
+        .wrap(Wrap { trim: false })
+        .block(Block::default().borders(Borders::ALL));
+    frame.render_widget(message_paragraph, chunks[1]);
+
+    let result = &app.result;
+    if result.show_details
+        && let Some(details) = failure_details(app)
+    {
+        let details_paragraph = Paragraph::new(details)
+            .style(app_theme!(normal))
+            .wrap(Wrap { trim: false })
+            .block(Block::default().title(tr!("Details")).borders(Borders::ALL));
+        frame.render_widget(details_paragraph, chunks[2]);
+        let footer = tr!("c: Copy for a bug report • d/Esc: Hide details • L: Logs");
+        frame.render_widget(create_footer(footer, chunks[3].width), chunks[3]);
+        return;
+    }
+
+    let labels: Vec<String> = if result.outputs.is_empty() {
+        vec![tr!("No files were written").to_string()]
+    } else {
+        result
+            .outputs
+            .iter()
+            .map(
+                |output| match (app.inspect_cache.get(output), &result.compression) {
+                    (Some(info), Some(report)) => tr!(
+                        "{file} — {pages} pages, {before} → {after} ({saving}% saved)",
+                        file = output,
+                        pages = info.page_count(),
+                        before = format_size(report.before),
+                        after = format_size(report.after),
+                        saving = format!("{:.1}", report.saving_percent())
+                    ),
+                    (Some(info), None) => tr!(
+                        "{file} — {pages} pages, {size}",
+                        file = output,
+                        pages = info.page_count(),
+                        size = format_size(info.file_size)
+                    ),
+                    (None, _) => tr!("{file} — could not be read back", file = output),
+                },
+            )
+            .collect()
+    };
+    let (output_list, mut list_state) = create_labeled_file_list(
+        labels,
+        tr!("Output Files"),
+        (!result.outputs.is_empty()).then_some(result.selected),
+    );
+    // Follow-up actions are listed under the outputs, unless feedback from the last one is shown
+    let hint = match (&result.notice, result.selected_output()) {
+        (Some(notice), _) => Some(notice.clone()),
+        (None, Some(_)) => Some(tr!(
+            "Use as input → {again}1 Merge • 2 Delete • 3 Split • 4 Rotate • 5 Extract • 6 Compress • 7 Watermark",
+            again = match app.operation_mode {
+                OperationMode::None => String::new(),
+                mode => tr!("n: {operation} again • ", operation = mode_label(mode)),
+            }
+        )),
+        (None, None) => None,
+    };
+    let output_list = match hint {
+        Some(hint) => output_list.block(
+            Block::default()
+                .title(tr!("Output Files"))
+                .title_bottom(hint)
+                .borders(Borders::ALL),
+        ),
+        None => output_list,
+    };
+    render_list(frame, output_list, chunks[2], &mut list_state);
+
+    let footer = if result.selected_output().is_some() {
+        tr!("↑↓: Select • o: Open • f: Open folder • c: Copy path • Enter/Esc: Main menu")
+    } else if app.error_message().is_some() {
+        tr!("d: Details • c: Copy details • L: Logs • Enter/Esc: Main menu")
+    } else {
+        tr!("Enter/Esc: Main menu")
+    };
+    frame.render_widget(create_footer(footer, chunks[3].width), chunks[3]);
+}
+
+/// Name of an operation as shown in the menus.
+fn mode_label(mode: OperationMode) -> &'static str {
+    translate(match mode {
+        OperationMode::Merge => "Merge",
+        OperationMode::Delete => "Delete",
+        OperationMode::Split => "Split",
+        OperationMode::Rotate => "Rotate",
+        OperationMode::Extract => "Extract",
+        OperationMode::Compress => "Compress",
+        OperationMode::Watermark => "Watermark",
+        OperationMode::None => "",
+    })
+}
+
+/// A number of pages, e.g. "1 page" or "3 pages".
+fn pages_label(count: usize) -> String {
+    if count == 1 {
+        tr!("1 page").to_string()
+    } else {
+        tr!("{count} pages", count = count)
+    }
+}
+
+/**
+ * Draw the operation queue screen UI.
+ * Display every queued step with its status, and the progress of the current run.
+ * @param frame The frame to draw on.
+ * @param app The application state.
+ */
+fn draw_queue_screen(frame: &mut Frame, app: &App) {
+    let chunks = screen_layout(&CurrentScreen::Queue, frame.area());
+
+    frame.render_widget(create_title(tr!("📋 Operation Queue")), chunks[0]);
+
+    let queue = &app.queue;
+    let items: Vec<ListItem> = if queue.steps.is_empty() {
+        vec![
+            ListItem::new(tr!(
+                "No operation queued: press 'a' in a configuration screen to add one"
+            ))
+            .style(app_theme!(footer)),
+        ]
+    } else {
+        queue
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(i, step)| {
+                let description = format!("{}. {}", i + 1, describe_operation(&step.operation));
+                let (icon, detail, style) = match &step.status {
+                    StepStatus::Pending if queue.running && queue.next_pending() == Some(i) => {
+                        ("⏳", None, app_theme!(input))
+                    }
+                    StepStatus::Pending => ("•", None, app_theme!(normal)),
+                    StepStatus::Done(outputs) => (
+                        "✅",
+                        Some(tr!("wrote {files}", files = outputs.join(", "))),
+                        app_theme!(success),
+                    ),
+                    StepStatus::Failed(e) => ("❌", Some(e.clone()), app_theme!(error)),
+                    StepStatus::Skipped => {
+                        ("⏭", Some(tr!("skipped").to_string()), app_theme!(footer))
+                    }
+                };
+
+                let mut lines = vec![Line::from(format!("{} {}", icon, description))];
+                if let Some(detail) = detail {
+                    lines.push(Line::from(format!("    {}", detail)));
+                }
+                ListItem::new(Text::from(lines)).style(style)
+            })
+            .collect()
+    };
+
+    let list = List::new(items)
+        .block(Block::default().title(tr!("Steps")).borders(Borders::ALL))
+        .highlight_style(app_theme!(highlight))
+        .highlight_symbol("▶ ");
+    let selected = (!queue.steps.is_empty()).then_some(queue.selected);
+    frame.render_stateful_widget(
+        list,
+        chunks[1],
+        &mut ListState::default().with_selected(selected),
+    );
+
+    let total = queue.steps.len();
+    let finished = queue.finished_count();
+    let ratio = if total == 0 {
+        0.0
+    } else {
+        finished as f64 / total as f64
+    };
+    let mut gauge_block = Block::default()
+        .title(tr!("Progress"))
+        .borders(Borders::ALL);
+    if queue.started_at.is_some() {
+        let stats = queue_run_stats(app);
+        let width = chunks[2].width.saturating_sub(4) as usize;
+        gauge_block = gauge_block.title_bottom(format!(" {} ", ellipsize(&stats, width)));
+    }
+    let (done, failed, skipped) = queue.summary();
+    let label = if !queue.running && failed + skipped > 0 {
+        tr!(
+            "{done} done • {failed} failed • {skipped} skipped",
+            done = done,
+            failed = failed,
+            skipped = skipped
+        )
+    } else {
+        tr!(
+            "{finished}/{total} steps",
+            finished = finished,
+            total = total
+        )
+    };
+    let gauge = Gauge::default()
+        .block(gauge_block)
+        .gauge_style(app_theme!(success))
+        .ratio(ratio)
+        .label(label);
+    frame.render_widget(gauge, chunks[2]);
+
+    let footer = if queue.running {
+        tr!("Running… • Esc/c: Cancel remaining steps")
+    } else if queue.has_failures() {
+        tr!(
+            "↑↓: Select • f: Retry failed • Enter/r: Run all • x/Del: Remove • c: Clear • Esc: Main menu"
+        )
+    } else {
+        tr!("↑↓: Select • Enter/r: Run all • x/Del: Remove • c: Clear • Esc: Main menu")
+    };
+    frame.render_widget(create_footer(footer, chunks[3].width), chunks[3]);
 }
 
 /**
- * Draw the delete configuration screen UI.
- * Display selected files, pages to delete input, output filename input, and footer instructions.
+ * Describe the current or last queue run: elapsed time, pages written per second, and while
+ * running, the estimated time left and the files the next step writes.
+ * @param app The application state.
+ * @returns The statistics, separated by bullets.
  */
-fn draw_delete_config_screen(frame: &mut Frame, app: &App) {
-    let chunks = create_standard_layout(frame.area(), &[3, 0, 5, 5, 3]);
+fn queue_run_stats(app: &App) -> String {
+    let queue = &app.queue;
+    let mut stats = vec![tr!(
+        "Elapsed {time}",
+        time = format_duration(queue.elapsed())
+    )];
+    if let Some(rate) = queue.pages_per_second() {
+        stats.push(tr!("{rate} pages/s", rate = format!("{:.1}", rate)));
+    }
+    if let Some(eta) = queue.eta() {
+        stats.push(tr!("ETA {time}", time = format_duration(eta)));
+    }
+    if queue.running
+        && let Some(index) = queue.next_pending()
+        && let Ok(outputs) = queue.steps[index].operation.outputs()
+    {
+        let names: Vec<&str> = outputs
+            .iter()
+            .map(|output| {
+                Path::new(output)
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .unwrap_or(output)
+            })
+            .collect();
+        stats.push(tr!("Writing {files}", files = names.join(", ")));
+    }
+    stats.join(" • ")
+}
 
-    frame.render_widget(create_title("✂️ Delete Configuration"), chunks[0]);
+/**
+ * Draw the log viewer.
+ * Display the captured log lines at or above the selected level, newest at the bottom.
+ * @param frame The frame to draw on.
+ * @param app The application state.
+ */
+fn draw_logs_screen(frame: &mut Frame, app: &App) {
+    let chunks = screen_layout(&CurrentScreen::Logs, frame.area());
 
-    let (file_list, mut list_state) = create_file_list(
-        &app.selected_files(),
-        "File to Delete Pages From",
-        Some(app.merge_file_index()),
-    );
-    frame.render_stateful_widget(file_list, chunks[1], &mut list_state);
+    frame.render_widget(create_title(tr!("📜 Logs")), chunks[0]);
 
-    let pages_field = create_input_field(
-        &app.delete_config.pages_to_delete,
-        "Pages to Delete (e.g., 1,3-5)",
-        app.delete_config.editing_pages,
-        None,
-    );
-    frame.render_widget(pages_field, chunks[2]);
+    // Multi-line messages (e.g. an error with its causes) get one row per line
+    let lines: Vec<Line> = log_buffer::recent_lines(app.logs.min_level)
+        .iter()
+        .flat_map(|line| {
+            let style = match line.level {
+                Level::Error => app_theme!(error),
+                Level::Warn => app_theme!(warning),
+                Level::Info => app_theme!(normal),
+                Level::Debug | Level::Trace => app_theme!(footer),
+            };
+            let prefix = format!(
+                "{:>8.3}s {:<5} {} ",
+                line.elapsed.as_secs_f64(),
+                line.level,
+                line.target.trim_start_matches("pdf_cutter::")
+            );
+            line.message
+                .lines()
+                .enumerate()
+                .map(|(i, text)| {
+                    let lead = if i == 0 {
+                        prefix.clone()
+                    } else {
+                        " ".repeat(prefix.chars().count())
+                    };
+                    Line::from(vec![
+                        Span::styled(lead, app_theme!(footer)),
+                        Span::styled(text.to_string(), style),
+                    ])
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
 
-    let output_text = if app.delete_config.output_filename.is_empty() {
-        "modified_output.pdf"
+    let visible = chunks[1].height.saturating_sub(2) as usize;
+    let end = lines.len().saturating_sub(app.logs.scroll);
+    let start = end.saturating_sub(visible);
+    let title = tr!(
+        "Level: {level} and above • {count} lines{scrolled}",
+        level = app.logs.min_level,
+        count = lines.len(),
+        scrolled = if app.logs.scroll > 0 {
+            tr!(" • scrolled")
+        } else {
+            ""
+        }
+    );
+    let body = if lines.is_empty() {
+        Paragraph::new(tr!("Nothing logged yet")).style(app_theme!(footer))
     } else {
-        &app.delete_config.output_filename
+        Paragraph::new(Text::from(lines[start..end].to_vec()))
     };
-
-    let output_field = create_input_field(
-        output_text,
-        "Output Filename",
-        app.delete_config.editing_output,
-        None,
+    frame.render_widget(
+        body.block(Block::default().borders(Borders::ALL).title(title)),
+        chunks[1],
     );
-    frame.render_widget(output_field, chunks[3]);
 
     frame.render_widget(
         create_footer(
-            "p: Edit pages to delete • Tab: Edit output name • Enter: Start delete • Esc: Back",
+            tr!("↑↓: Scroll • F: Level • C: Clear • L/Esc: Back • ?: Keys"),
+            chunks[2].width,
         ),
-        chunks[4],
+        chunks[2],
     );
-
-    render_error_if_exists(frame, app.error_message().as_deref());
 }
 
-// fn draw_processing_screen(frame: &mut Frame, app: &App) {
-//     // TODO: Implement processing screen
-//     return;
-// }
-
-fn draw_split_config_screen(frame: &mut Frame, app: &App) {
-    let chunks = create_standard_layout(frame.area(), &[3, 0, 4, 4, 4, 3]);
+fn draw_help_screen(frame: &mut Frame, app: &App) {
+    let chunks = screen_layout(&CurrentScreen::Help, frame.area());
 
-    frame.render_widget(create_title("🔪 Split Configuration"), chunks[0]);
+    frame.render_widget(create_title(tr!("❓ Help")), chunks[0]);
 
-    // File to split
-    let (file_list, mut list_state) = create_file_list(
-        &app.selected_files(),
-        "File to Split",
-        Some(0), // Only one file for split
+    let help = help_content();
+    let scroll = app.ui_state.help_scroll as usize;
+    // Name the section at the top of the view, so its position is clear once scrolled
+    let current = help
+        .section_starts
+        .iter()
+        .rposition(|&start| start <= scroll)
+        .unwrap_or(0);
+    let title = format!(
+        "{} ({}/{})",
+        help.section_titles[current],
+        current + 1,
+        help.section_starts.len()
     );
-    frame.render_stateful_widget(file_list, chunks[1], &mut list_state);
 
-    // Page segments input
-    let (segments_text, segments_title) = if app.split_config.use_named_segments {
-        (
-            if app.split_config.segments.is_empty() {
-                "intro:1-3,chapter1:4-10,conclusion:11"
-            } else {
-                &app.split_config.segments
-            },
-            "Named Segments (e.g., intro:1-3,chapter1:4-10,conclusion:11)",
-        )
-    } else {
-        (
-            if app.split_config.segments.is_empty() {
-                "1-3,5,7-9"
-            } else {
-                &app.split_config.segments
-            },
-            "Page Segments (e.g., 1-3,5,7-9)",
-        )
-    };
+    let line_count = help.lines.len();
+    let help_paragraph = Paragraph::new(Text::from(help.lines))
+        .style(app_theme!(normal))
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .wrap(Wrap { trim: false })
+        .scroll((app.ui_state.help_scroll, 0))
+        .alignment(Alignment::Left);
 
-    let segments_field = create_input_field(
-        segments_text,
-        segments_title,
-        app.split_config.editing_segments,
-        None,
+    frame.render_widget(help_paragraph, chunks[1]);
+    // Counts the lines before wrapping, close enough to place the thumb
+    render_scrollbar(
+        frame,
+        chunks[1],
+        line_count,
+        chunks[1].height.saturating_sub(2) as usize,
+        scroll,
     );
-    frame.render_widget(segments_field, chunks[2]);
-
-    // Checkbox for using named segments
-    let checkbox = create_checkbox(
-        "Use named segments",
-        app.split_config.use_named_segments,
-        false,
+    frame.render_widget(
+        create_footer(
+            tr!("↑↓/PgUp/PgDn: Scroll • Tab/Shift+Tab: Sections • Esc: Return to main menu"),
+            chunks[2].width,
+        ),
+        chunks[2],
     );
-    frame.render_widget(checkbox, chunks[3]);
+}
 
-    let output_text = if app.split_config.output_prefix.is_empty() {
-        "split_output"
-    } else {
-        &app.split_config.output_prefix
+/// Text of the Help screen, with where each of its sections starts.
+pub struct HelpContent {
+    pub lines: Vec<Line<'static>>,
+    pub section_titles: Vec<&'static str>,
+    /// Index in `lines` of the heading of each section.
+    pub section_starts: Vec<usize>,
+}
+
+/**
+ * Build the Help screen text: the keys available everywhere, then one section per
+ * operation or screen. Key lines come from the same table as the '?' cheat-sheet.
+ * @returns The Help text.
+ */
+pub fn help_content() -> HelpContent {
+    let mut help = HelpContent {
+        lines: Vec::new(),
+        section_titles: Vec::new(),
+        section_starts: Vec::new(),
     };
 
-    let output_field = create_input_field(
-        output_text,
-        "Output Prefix",
-        app.split_config.editing_prefix,
-        None,
+    let heading = |help: &mut HelpContent, title: &'static str| {
+        if !help.lines.is_empty() {
+            help.lines.push(Line::from(""));
+        }
+        let title = translate(title);
+        help.section_titles.push(title);
+        help.section_starts.push(help.lines.len());
+        help.lines
+            .push(Line::from(Span::styled(title, app_theme!(title))));
+    };
+    let keys = |help: &mut HelpContent, title: &str, bindings: &[KeyBinding]| {
+        help.lines.push(Line::from(Span::styled(
+            format!(" {}:", title),
+            app_theme!(accent),
+        )));
+        help.lines.extend(bindings.iter().map(binding_line));
+    };
+
+    heading(&mut help, "⌨️  Everywhere");
+    keys(&mut help, tr!("Any screen"), GLOBAL_BINDINGS);
+    keys(
+        &mut help,
+        tr!("While typing in a field"),
+        TEXT_FIELD_BINDINGS,
+    );
+    keys(
+        &mut help,
+        tr!("While editing the output directory"),
+        OUTPUT_DIRECTORY_BINDINGS,
     );
-    frame.render_widget(output_field, chunks[4]);
 
-    let instructions = if app.split_config.editing_segments {
-        "Enter: Save segments • Esc: Cancel"
-    } else if app.split_config.editing_prefix {
-        "Enter: Save prefix • Esc: Cancel"
-    } else {
-        "S: Edit segments • Space: Toggle named segments • P: Edit prefix • Enter: Split • Esc: Back"
-    };
+    for section in HELP_SECTIONS {
+        heading(&mut help, section.title);
+        help.lines
+            .push(Line::from(format!(" {}", translate(section.summary))));
+        for screen in section.screens {
+            keys(&mut help, screen_name(screen), screen_bindings(screen));
+        }
+    }
+
+    help
+}
 
-    frame.render_widget(create_footer(instructions), chunks[5]);
+/// One line of a key reference: the keys, padded into a column, then the action.
+fn binding_line(binding: &KeyBinding) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(
+            format!("  {:<18}", translate(binding.keys)),
+            app_theme!(input),
+        ),
+        Span::raw(translate(binding.action)),
+    ])
+}
 
-    render_error_if_exists(frame, app.error_message().as_deref());
+/// One page of the welcome tour.
+pub struct OnboardingPage {
+    pub title: &'static str,
+    pub lines: Vec<Line<'static>>,
 }
 
 /**
- * Draw the result screen UI.
- * Display success or error message after operation.
- * @param frame The frame to draw on.
+ * Build the pages of the welcome tour shown on the first run: how to move around,
+ * the operation wizards, the keys they share and where their outputs go.
  * @param app The application state.
+ * @returns The pages, in order.
  */
-fn draw_result_screen(frame: &mut Frame, app: &App) {
-    frame.render_widget(Clear, frame.area());
+pub fn onboarding_pages(app: &App) -> Vec<OnboardingPage> {
+    let heading = |text: &str| Line::styled(text.to_string(), app_theme!(accent));
+    let text = |text: &str| Line::from(text.to_string());
+
+    let mut welcome = vec![
+        text(tr!(
+            "PDF Cutter merges, splits and edits PDF files without leaving the terminal."
+        )),
+        text(tr!(
+            "Everything is done with the keyboard, and the mouse works too."
+        )),
+        Line::from(""),
+        heading(screen_name(&CurrentScreen::Main)),
+    ];
+    welcome.extend(
+        screen_bindings(&CurrentScreen::Main)
+            .iter()
+            .map(binding_line),
+    );
+    welcome.push(heading(tr!("Any screen")));
+    welcome.extend(GLOBAL_BINDINGS.iter().map(binding_line));
+
+    let mut wizards = vec![
+        text(tr!(
+            "Merge, Delete and Split are wizards that go through the same steps:"
+        )),
+        Line::from(Span::styled(
+            format!(
+                "  {}",
+                WIZARD_STEPS
+                    .iter()
+                    .enumerate()
+                    .map(|(i, step)| format!("{}. {}", i + 1, translate(step)))
+                    .collect::<Vec<_>>()
+                    .join(" → ")
+            ),
+            app_theme!(input),
+        )),
+        Line::from(""),
+    ];
+    for (title, summary) in [
+        (
+            tr!("🔗 Merge PDFs"),
+            tr!("Add the files in the order they should appear, then name the output."),
+        ),
+        (
+            tr!("✂️  Delete pages"),
+            tr!("Add one file, then type the pages to remove (e.g. 1,3,5-7) or pick them with G."),
+        ),
+        (
+            tr!("🔪 Split pages"),
+            tr!(
+                "Add one file, then type where to cut (e.g. 1-3,4-10): each segment becomes a file."
+            ),
+        ),
+    ] {
+        wizards.push(heading(title));
+        wizards.push(text(summary));
+    }
+    wizards.push(Line::from(""));
+    wizards.push(text(tr!(
+        "Rotate, Extract, Compress and Watermark work the same way."
+    )));
+
+    let keys = vec![
+        text(tr!(
+            "Enter goes to the next step and Esc goes back, on every screen."
+        )),
+        Line::from(""),
+        heading(tr!("Files")),
+        text(tr!(
+            "Tab types the path of a file, b browses folders, Backspace removes the selected file."
+        )),
+        heading(tr!("Configuration")),
+        text(tr!(
+            "Tab edits the output name (P when splitting), D the output directory."
+        )),
+        text(tr!(
+            "A adds the operation to the queue instead of running it, to run several in a row."
+        )),
+        heading(tr!("Lost?")),
+        text(tr!(
+            "? lists the keys of the current screen, Ctrl+K searches its actions, and Help (9 in the main menu) explains everything."
+        )),
+    ];
 
-    let popup_block = Block::default()
-        .title("Operation Result")
-        .borders(Borders::ALL)
-        .style(Style::default().bg(Color::DarkGray));
+    let directory = std::env::current_dir()
+        .map(|dir| contract_home(&dir))
+        .unwrap_or_else(|_| ".".to_string());
+    let mut outputs = vec![
+        text(&tr!(
+            "Outputs are written to the current directory, {directory}, unless you press D on a configuration screen to pick another one.",
+            directory = directory
+        )),
+        Line::from(""),
+        text(tr!(
+            "Leaving the output name empty uses a default name such as output_merged.pdf, with a number added when that file already exists."
+        )),
+        text(tr!(
+            "Writing over one of the input files always asks for a confirmation first."
+        )),
+    ];
+    if let Some(path) = &app.config_path {
+        outputs.push(Line::from(""));
+        outputs.push(text(&if path.exists() {
+            tr!(
+                "Settings (theme, language, backups...) are in {path}.",
+                path = contract_home(path)
+            )
+        } else {
+            tr!(
+                "Settings (theme, language, backups...) will be in {path}, created with every setting commented out when this tour ends.",
+                path = contract_home(path)
+            )
+        }));
+    }
 
-    let (message, color) = if let Some(err) = app.error_message() {
-        (err, Color::Red)
-    } else if let Some(success) = app.success_message() {
-        (success, Color::Green)
-    } else {
-        ("No result available", Color::Yellow)
-    };
+    vec![
+        OnboardingPage {
+            title: tr!("👋 Welcome to PDF Cutter"),
+            lines: welcome,
+        },
+        OnboardingPage {
+            title: tr!("🧭 The operation wizards"),
+            lines: wizards,
+        },
+        OnboardingPage {
+            title: tr!("⌨️  Keys worth knowing"),
+            lines: keys,
+        },
+        OnboardingPage {
+            title: tr!("📁 Where outputs are written"),
+            lines: outputs,
+        },
+    ]
+}
 
-    let result_text = Text::styled(message, Style::default().fg(color));
+fn draw_onboarding_screen(frame: &mut Frame, app: &App) {
+    let chunks = screen_layout(&CurrentScreen::Onboarding, frame.area());
 
-    let result_paragraph = Paragraph::new(result_text)
-        .block(popup_block)
-        .wrap(Wrap { trim: false })
-        .alignment(Alignment::Center);
+    let mut pages = onboarding_pages(app);
+    let count = pages.len();
+    let current = app.ui_state.onboarding_page.min(count - 1);
+    let page = pages.swap_remove(current);
 
-    let area = centered_rect(60, 25, frame.area());
-    frame.render_widget(result_paragraph, area);
+    frame.render_widget(create_title(page.title), chunks[0]);
+
+    // One dot per page, the current one filled
+    let progress = (0..count)
+        .map(|i| if i == current { "●" } else { "○" })
+        .collect::<Vec<_>>()
+        .join(" ");
+    let body = Paragraph::new(Text::from(page.lines))
+        .style(app_theme!(normal))
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .padding(Padding::horizontal(1))
+                .title(tr!(
+                    "Welcome tour ({page}/{count})",
+                    page = current + 1,
+                    count = count
+                ))
+                .title_bottom(Line::from(format!(" {} ", progress)).centered()),
+        );
+    frame.render_widget(body, chunks[1]);
+
+    let footer = if current + 1 < count {
+        tr!("→/Enter: Next • ←: Previous • Esc: Skip the tour")
+    } else {
+        tr!("Enter: Start • ←: Previous • Esc: Skip the tour")
+    };
+    frame.render_widget(create_footer(footer, chunks[2].width), chunks[2]);
 }
 
-fn draw_help_screen(frame: &mut Frame) {
-    let chunks = create_standard_layout(frame.area(), &[3, 0, 3]);
+/**
+ * Draw the About screen: version and build of the program, and where its settings are read.
+ * @param frame The frame to draw on.
+ * @param app The application state.
+ */
+fn draw_about_screen(frame: &mut Frame, app: &App) {
+    let chunks = screen_layout(&CurrentScreen::About, frame.area());
 
-    frame.render_widget(create_title("❓ Help"), chunks[0]);
+    frame.render_widget(create_title(tr!("ℹ️  About PDF Cutter")), chunks[0]);
 
-    let help_text = Text::from_iter([
-        Line::from("📄 PDF Cutter TUI Help"),
-        Line::from(""),
-        Line::from("🔧 Operations:"),
-        Line::from("  1. 🔗 Merge PDFs: Select multiple PDF files to combine them into one."),
-        Line::from("  2. ✂️  Delete Pages: Select a PDF and specify pages to remove."),
-        Line::from(""),
-        Line::from("🧭 Navigation:"),
-        Line::from("  • Use number keys (1, 2, 3) to select operations from the main menu."),
-        Line::from("  • In file selection: Tab/A to add files, D/← to remove, Enter to continue."),
-        Line::from("  • In merge config: Tab to edit output filename, Enter to start merging."),
-        Line::from("  • Use Esc to go back to previous screen."),
-        Line::from(""),
-        Line::from("⌨️  File Selection Shortcuts:"),
-        Line::from("  • Tab: Add file (enter edit mode)"),
-        Line::from("  • ↑↓: Navigate file list"),
-        Line::from("  • Del: Delete selected file"),
-        Line::from("  • Alt+↑↓: Reorder files (merge mode)"),
-        Line::from("  • Enter: Continue to next step"),
-        Line::from(""),
-        Line::from("✏️  Edit Mode (when adding files):"),
-        Line::from("  • Type: Enter file path"),
-        Line::from("  • Enter: Add file and exit edit mode"),
-        Line::from("  • Esc: Cancel and exit edit mode"),
-        Line::from(""),
-        Line::from("🎯 General Shortcuts:"),
-        Line::from("  • ↑↓: Navigate lists"),
-        Line::from("  • Enter: Confirm/Select"),
-        Line::from("  • Tab: Switch input fields"),
-        Line::from("  • Esc: Go back"),
-        Line::from("  • q: Quit application"),
-        Line::from(""),
-        Line::from("Press Esc to return to the main menu."),
-    ]);
+    let features = build_info::features();
+    let config = match &app.config_path {
+        Some(path) if path.exists() => contract_home(path),
+        Some(path) => tr!("{path} (not created yet)", path = contract_home(path)),
+        None => tr!("none (no home directory)").to_string(),
+    };
+    let rows = [
+        (tr!("Version"), build_info::VERSION.to_string()),
+        (tr!("Commit"), build_info::GIT_HASH.to_string()),
+        (
+            tr!("Build"),
+            format!("{} ({})", build_info::PROFILE, build_info::TARGET),
+        ),
+        (
+            tr!("Features"),
+            if features.is_empty() {
+                tr!("none").to_string()
+            } else {
+                features.join(", ")
+            },
+        ),
+        (
+            tr!("System"),
+            format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        ),
+        (tr!("Config file"), config),
+        (tr!("Theme"), current_theme().name.to_string()),
+        (tr!("Graphics"), app.thumbnails.protocol.label().to_string()),
+    ];
+    let width = rows
+        .iter()
+        .map(|(label, _)| label.chars().count())
+        .max()
+        .unwrap_or(0)
+        + 2;
+    let mut lines: Vec<Line> = rows
+        .into_iter()
+        .map(|(label, value)| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{:<width$}", label, width = width),
+                    app_theme!(accent),
+                ),
+                Span::styled(value, app_theme!(input)),
+            ])
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(tr!(
+        "Reporting a bug? Press C to copy these details, then paste them in your report."
+    )));
 
-    let help_paragraph = Paragraph::new(help_text)
+    let about = Paragraph::new(Text::from(lines))
         .style(app_theme!(normal))
-        .block(Block::default().borders(Borders::ALL).title("Instructions"))
         .wrap(Wrap { trim: false })
-        .alignment(Alignment::Left);
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .padding(Padding::horizontal(1)),
+        );
+    frame.render_widget(about, chunks[1]);
+    frame.render_widget(
+        create_footer(
+            tr!("C: Copy for a bug report • Esc: Back • ?: Keys"),
+            chunks[2].width,
+        ),
+        chunks[2],
+    );
+}
 
-    frame.render_widget(help_paragraph, chunks[1]);
-    frame.render_widget(create_footer("Press Esc to return to main menu"), chunks[2]);
+/**
+ * Draw the key cheat-sheet of the current screen on top of it.
+ * @param frame The frame to draw on.
+ * @param app The application state.
+ */
+fn draw_cheat_sheet(frame: &mut Frame, app: &App) {
+    let mut lines: Vec<Line> = screen_bindings(&app.current_screen)
+        .iter()
+        .map(binding_line)
+        .collect();
+    lines.push(Line::from(""));
+    lines.extend(GLOBAL_BINDINGS.iter().map(binding_line));
+
+    let area = centered_rect(60, 70, frame.area());
+    frame.render_widget(Clear, area);
+    let sheet = Paragraph::new(Text::from(lines))
+        .style(app_theme!(normal))
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .title(tr!(
+                    "Keys: {screen}",
+                    screen = screen_name(&app.current_screen)
+                ))
+                .title_bottom(tr!("Press any key to close"))
+                .borders(Borders::ALL),
+        );
+    frame.render_widget(sheet, area);
 }
 
 fn draw_exit_screen(frame: &mut Frame, _app: &App) {
     frame.render_widget(Clear, frame.area());
 
     let popup_block = Block::default()
-        .title("Exit Confirmation")
+        .title(tr!("Exit Confirmation"))
         .borders(Borders::ALL)
-        .style(Style::default().bg(Color::DarkGray));
+        .style(app_theme!(popup));
 
     let exit_text = Text::styled(
-        "Are you sure you want to exit PDF Cutter? (y/n)",
-        Style::default().fg(Color::Red),
+        tr!("Are you sure you want to exit PDF Cutter? (y/n)"),
+        app_theme!(error),
     );
 
     let exit_paragraph = Paragraph::new(exit_text)
@@ -569,23 +3441,154 @@ fn draw_exit_screen(frame: &mut Frame, _app: &App) {
     frame.render_widget(exit_paragraph, area);
 }
 
-fn draw_error_popup(frame: &mut Frame, message: &str) {
-    frame.render_widget(Clear, frame.area());
+/**
+ * Draw the status bar: current operation, selected files with their total pages and size,
+ * working directory and the last message, whatever the screen.
+ * @param frame The frame to draw on.
+ * @param app The application state.
+ */
+fn draw_status_bar(frame: &mut Frame, app: &App) {
+    let mut parts = vec![match app.operation_mode {
+        OperationMode::None => tr!("No operation").to_string(),
+        mode => mode_label(mode).to_string(),
+    }];
+
+    let files = app.selected_files();
+    if !files.is_empty() {
+        let infos: Vec<_> = files
+            .iter()
+            .filter_map(|file| app.inspect_cache.get(file))
+            .collect();
+        parts.push(if files.len() == 1 {
+            tr!("1 file").to_string()
+        } else {
+            tr!("{count} files", count = files.len())
+        });
+        if !infos.is_empty() {
+            let pages: usize = infos.iter().map(|info| info.page_count()).sum();
+            let size: u64 = infos.iter().map(|info| info.file_size).sum();
+            parts.push(pages_label(pages));
+            parts.push(format_size(size));
+        }
+    }
 
-    let popup_block = Block::default()
-        .title("Error")
-        .borders(Borders::ALL)
-        .style(Style::default().bg(Color::DarkGray));
+    // Relative paths and default outputs go to the working directory
+    if let Ok(dir) = std::env::current_dir() {
+        parts.push(format!("📂 {}", contract_home(&dir)));
+    }
 
-    let error_text = Text::styled(message, Style::default().fg(Color::Red));
+    let mut spans = vec![Span::raw(format!(" {}", parts.join(" • ")))];
+    if let Some(message) = &app.ui_state.last_message {
+        let (text, error, style) = match message {
+            MessageType::Error(text) => (text, true, app_theme!(status).patch(app_theme!(error))),
+            MessageType::Success(text) => (text, false, app_theme!(status)),
+        };
+        // Only the first line fits; the result screen shows the full message
+        let first_line = text.lines().next().unwrap_or_default();
+        spans.push(Span::raw(" │ "));
+        spans.push(Span::styled(prefixed_message(first_line, error), style));
+    }
 
-    let error_paragraph = Paragraph::new(error_text)
-        .block(popup_block)
+    frame.render_widget(
+        Paragraph::new(Line::from(spans)).style(app_theme!(status)),
+        status_bar_area(frame.area()),
+    );
+}
+
+/**
+ * Put "ERROR:" or "OK:" before a message when the theme asks for it, so errors and successes
+ * are told apart without their color.
+ * @param message The message.
+ * @param error Whether it reports a failure.
+ * @returns The message, prefixed or not.
+ */
+fn prefixed_message(message: &str, error: bool) -> String {
+    match (app_theme!(message_prefixes), error) {
+        (false, _) => message.to_string(),
+        (true, true) => tr!("ERROR: {message}", message = message),
+        (true, false) => tr!("OK: {message}", message = message),
+    }
+}
+
+/**
+ * Draw the current toast over the bottom of the screen, where the footer is, above the status bar.
+ * The screen underneath stays visible and keeps receiving keys.
+ * @param frame The frame to draw on.
+ * @param app The application state.
+ */
+fn draw_toast(frame: &mut Frame, app: &App) {
+    let Some(toast) = &app.ui_state.toast else {
+        return;
+    };
+    let (title, style) = match toast.kind {
+        ToastKind::Error => (tr!("Error"), app_theme!(error)),
+        ToastKind::Info => (tr!("Info"), app_theme!(success)),
+    };
+
+    let area = content_area(frame.area());
+    let text_width = area.width.saturating_sub(2).max(1) as usize;
+    let lines = toast.message.chars().count().div_ceil(text_width).max(1) as u16;
+    let height = (lines + 2).min(TOAST_MAX_HEIGHT).min(area.height);
+    let toast_area = Rect::new(area.x, area.bottom() - height, area.width, height);
+
+    frame.render_widget(Clear, toast_area);
+    let paragraph = Paragraph::new(toast.message.as_str())
+        .style(style)
         .wrap(Wrap { trim: false })
-        .alignment(Alignment::Center);
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .style(app_theme!(popup)),
+        );
+    frame.render_widget(paragraph, toast_area);
+}
 
-    let area = centered_rect(60, 25, frame.area());
-    frame.render_widget(error_paragraph, area);
+/**
+ * Draw the command palette on top of the current screen.
+ * Display the query and the matching actions, best match first.
+ * @param frame The frame to draw on.
+ * @param app The application state.
+ */
+fn draw_command_palette(frame: &mut Frame, app: &App) {
+    let area = centered_rect(60, 60, frame.area());
+    frame.render_widget(Clear, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(area);
+
+    let query = Paragraph::new(format!("> {}", app.palette.query))
+        .style(app_theme!(input))
+        .block(
+            Block::default()
+                .title(tr!("Command Palette"))
+                .title_bottom(tr!("↑↓: Select • Enter: Run • Esc: Close"))
+                .borders(Borders::ALL),
+        );
+    frame.render_widget(query, chunks[0]);
+
+    let matches = palette_matches(app);
+    let items: Vec<ListItem> = if matches.is_empty() {
+        vec![ListItem::new(tr!("No matching action")).style(app_theme!(footer))]
+    } else {
+        matches
+            .iter()
+            .map(|action| ListItem::new(action.label()))
+            .collect()
+    };
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL))
+        .style(app_theme!(normal))
+        .highlight_style(app_theme!(highlight))
+        .highlight_symbol("▶ ");
+    let selected = (!matches.is_empty()).then_some(app.palette.selected);
+    frame.render_stateful_widget(
+        list,
+        chunks[1],
+        &mut ListState::default().with_selected(selected),
+    );
 }
 
 // Helper function to create a centered rectangle
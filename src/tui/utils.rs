@@ -1,17 +1,36 @@
+use crate::i18n::tr;
+use crate::pdf::image::has_image_extension;
+use crate::pdf::inspect::{PageInfo, page_size_groups};
+use crate::pdf::operation::Operation;
+use crate::pdf::output::{contract_home, expand_home};
+use crate::pdf::page_spec::{
+    PageSegment, parse_named_segments, parse_page_segments, parse_page_sequence, parse_page_set,
+    parse_pages_to_delete,
+};
+use crate::pdf::space::SpaceWarning;
+use crate::pdf::split::{find_overlap, find_shared_output, segment_filenames};
 use crate::tui::errors::{TuiError, TuiResult};
-use lopdf::Document;
+use crate::tui::state::{BrowserEntry, BrowserState};
+use crossterm::event::KeyCode;
+use std::cmp::Ordering;
+use std::fs;
+use std::io;
+use std::iter::Peekable;
 use std::path::Path;
+use std::str::Chars;
+use std::time::Duration;
 
 /**
- * Validate if the given file path exists and is a valid PDF file.
+ * Validate if the given file path exists and has a PDF extension.
+ * The content is only checked when the file is loaded on the validation worker.
  * @param input The file path to validate.
  * @returns Ok(()) if valid, Err(TuiError) if invalid.
- * @throws TuiError if the file does not exist or is not a valid PDF.
+ * @throws TuiError if the file does not exist or is not a PDF file.
  */
 pub fn validate_file_input(input: &str) -> TuiResult<()> {
     if input.is_empty() {
         return Err(TuiError::FileNotFound {
-            path: "empty path".to_string(),
+            path: tr!("empty path").to_string(),
         });
     }
 
@@ -21,7 +40,7 @@ pub fn validate_file_input(input: &str) -> TuiResult<()> {
         });
     }
 
-    if !is_pdf_file(input) {
+    if !has_pdf_extension(input) {
         return Err(TuiError::InvalidPdf {
             path: input.to_string(),
         });
@@ -30,6 +49,29 @@ pub fn validate_file_input(input: &str) -> TuiResult<()> {
     Ok(())
 }
 
+/**
+ * Validate the image file of a watermark: it must exist and be a JPEG or PNG file.
+ * @param input The image path, `~` is expanded.
+ * @returns The expanded path.
+ * @throws TuiError if the file does not exist or is not a supported image.
+ */
+pub fn validate_image_input(input: &str) -> TuiResult<String> {
+    let path = expand_home(input).to_string_lossy().into_owned();
+    if input.is_empty() || !Path::new(&path).is_file() {
+        return Err(TuiError::FileNotFound {
+            path: if input.is_empty() {
+                tr!("empty path").to_string()
+            } else {
+                input.to_string()
+            },
+        });
+    }
+    if !has_image_extension(&path) {
+        return Err(TuiError::UnsupportedImage { path });
+    }
+    Ok(path)
+}
+
 /**
  * Check if the required number of files are provided for a merge operation (min two files).
  * @param files The list of file paths to validate.
@@ -50,13 +92,7 @@ pub fn validate_merge_requirements(files: &[String]) -> TuiResult<()> {
  * @throws TuiError if there are too many files for deletion.
  */
 pub fn validate_delete_requirements(files: &[String]) -> TuiResult<()> {
-    if files.is_empty() {
-        return Err(TuiError::NoFilesSelected);
-    }
-    if files.len() != 1 {
-        return Err(TuiError::TooManyFiles { count: files.len() });
-    }
-    Ok(())
+    validate_single_file_requirements(files)
 }
 
 /**
@@ -66,6 +102,17 @@ pub fn validate_delete_requirements(files: &[String]) -> TuiResult<()> {
  * @throws TuiError if there are no files or too many files for splitting.
  */
 pub fn validate_split_requirements(files: &[String]) -> TuiResult<()> {
+    validate_single_file_requirements(files)
+}
+
+/**
+ * Check if exactly one file is provided, for operations working on a single document
+ * (delete, split, rotate, extract, compress, watermark).
+ * @param files The list of file paths to validate.
+ * @returns Ok(()) if valid, Err(TuiError) if invalid.
+ * @throws TuiError if there are no files or too many files.
+ */
+pub fn validate_single_file_requirements(files: &[String]) -> TuiResult<()> {
     if files.is_empty() {
         return Err(TuiError::NoFilesSelected);
     }
@@ -76,195 +123,1284 @@ pub fn validate_split_requirements(files: &[String]) -> TuiResult<()> {
 }
 
 /**
- * Check if the given file path points to a valid PDF file by attempting to load it.
+ * Check if the given file path has a PDF extension, without loading it.
  * @param path The file path to check.
- * @returns true if the file is a valid PDF, false otherwise.
+ * @returns true if the file name ends with ".pdf", false otherwise.
  */
-fn is_pdf_file(path: &str) -> bool {
-    let path = Path::new(path);
-    if !path.exists() || path.extension().map(|e| e != "pdf").unwrap_or(true) {
-        return false;
-    }
-    match Document::load(path) {
-        Ok(_) => true,
-        Err(_) => false,
+fn has_pdf_extension(path: &str) -> bool {
+    Path::new(path).extension().is_some_and(|e| e == "pdf")
+}
+
+/**
+ * Validate a page specification against the number of pages of the document, see
+ * parse_page_set.
+ * @param pages_str The string representing page ranges.
+ * @param page_count The number of pages of the document, when known.
+ * @returns A vector of unique page numbers if valid, Err(TuiError) if invalid.
+ * @throws TuiError if the specification is invalid or references a page past the end.
+ */
+pub fn validate_page_ranges_in_document(
+    pages_str: &str,
+    page_count: Option<usize>,
+) -> TuiResult<Vec<u32>> {
+    Ok(parse_page_set(pages_str, page_count)?)
+}
+
+/**
+ * Validate a page specification whose order matters against the number of pages of the
+ * document, see parse_page_sequence.
+ * @param pages_str The string representing page ranges.
+ * @param page_count The number of pages of the document, when known.
+ * @returns The unique page numbers, in the order they are typed.
+ * @throws TuiError if the specification is invalid or references a page past the end.
+ */
+pub fn validate_page_order_in_document(
+    pages_str: &str,
+    page_count: Option<usize>,
+) -> TuiResult<Vec<u32>> {
+    Ok(parse_page_sequence(pages_str, page_count)?)
+}
+
+/**
+ * Validate the pages to delete from a document: they must exist and leave at least one page.
+ * @param pages_str The string representing page ranges.
+ * @param page_count The number of pages of the document, when known.
+ * @returns A vector of unique page numbers if valid, Err(TuiError) if invalid.
+ * @throws TuiError if the specification is invalid or would delete every page.
+ */
+pub fn validate_delete_pages(pages_str: &str, page_count: Option<usize>) -> TuiResult<Vec<u32>> {
+    Ok(parse_pages_to_delete(pages_str, page_count)?)
+}
+
+/**
+ * Check that split segments are written in the selected mode, before parsing them.
+ * Page segments cannot have names, and names of named segments become part of the filenames.
+ * Named mode also accepts unnamed segments, which get numbered filenames.
+ * @param segments_str The segments as typed by the user.
+ * @param use_named_segments Whether named segments are selected.
+ * @returns Ok(()) if the segments match the mode.
+ * @throws TuiError::NamedSegmentInPageMode, TuiError::UnnamedSegment or
+ * TuiError::InvalidOutputName naming the first segment that does not fit.
+ */
+pub fn validate_split_segments(segments_str: &str, use_named_segments: bool) -> TuiResult<()> {
+    for segment in segments_str.split(',').map(str::trim) {
+        let Some((name, _)) = segment.split_once(':') else {
+            continue;
+        };
+        if !use_named_segments {
+            return Err(TuiError::NamedSegmentInPageMode {
+                segment: segment.to_string(),
+            });
+        }
+        if name.trim().is_empty() {
+            return Err(TuiError::UnnamedSegment {
+                segment: segment.to_string(),
+            });
+        }
+        validate_output_name(name.trim())?;
     }
+    Ok(())
 }
 
 /**
- * Parse a single page number from a string.
- * @param page_str The string representing a page number.
- * @returns The parsed page number.
- * @throws TuiError if the page number is invalid or zero.
+ * Compute the files a split would produce, without touching the document.
+ * @param segments_str The segments as typed by the user.
+ * @param use_named_segments Whether the segments use the "name:pages" format.
+ * @param output_prefix The prefix of the generated filenames.
+ * @returns The output filename of each segment, with the segment itself.
+ * @throws TuiError if the segments do not match the mode or cannot be parsed.
  */
-fn parse_single_page(page_str: &str) -> TuiResult<u32> {
-    let page: u32 = page_str
-        .trim()
-        .parse()
-        .map_err(|_| TuiError::InvalidPageRange {
-            input: format!("Invalid page: {}", page_str),
-        })?;
+pub fn preview_split_outputs(
+    segments_str: &str,
+    use_named_segments: bool,
+    output_prefix: &str,
+) -> TuiResult<Vec<(String, PageSegment)>> {
+    validate_split_segments(segments_str, use_named_segments)?;
+    let segments = if use_named_segments {
+        parse_named_segments(segments_str)
+    } else {
+        parse_page_segments(segments_str)
+    }?;
 
-    if page == 0 {
-        return Err(TuiError::InvalidPageRange {
-            input: "Page numbers must be greater than 0".to_string(),
+    if let Some(shared) = find_shared_output(&segments, output_prefix) {
+        return Err(TuiError::SharedOutputFile {
+            first: segments[shared.first].label(),
+            second: segments[shared.second].label(),
+            file: shared.file,
         });
     }
 
-    Ok(page)
+    let filenames =
+        segment_filenames(&segments, output_prefix).map_err(|e| TuiError::InvalidPageRange {
+            input: e.to_string(),
+        })?;
+    Ok(filenames.into_iter().zip(segments).collect())
 }
 
 /**
- * Parse a page range from a string (e.g., "3-7").
- * @param range_str The string representing a page range.
- * @returns A vector of page numbers in the range.
- * @throws TuiError if the range format is invalid.
+ * Check split segments against the document before running the split: every page must exist
+ * and, unless overlaps are allowed, no page may be written to two files.
+ * @param segments_str The segments as typed by the user.
+ * @param use_named_segments Whether named segments are selected.
+ * @param page_count The number of pages of the document, when known.
+ * @param allow_overlaps Whether a page may be part of several segments.
+ * @returns Ok(()) if the segments can be split from the document.
+ * @throws TuiError if the segments are invalid, go past the end or overlap.
  */
-fn parse_page_range(range_str: &str) -> TuiResult<Vec<u32>> {
-    let range_parts: Vec<&str> = range_str.split('-').collect();
+pub fn validate_split_in_document(
+    segments_str: &str,
+    use_named_segments: bool,
+    page_count: Option<usize>,
+    allow_overlaps: bool,
+) -> TuiResult<()> {
+    let outputs = preview_split_outputs(segments_str, use_named_segments, "")?;
+    let segments: Vec<PageSegment> = outputs.into_iter().map(|(_, segment)| segment).collect();
 
-    if range_parts.len() != 2 {
-        return Err(TuiError::InvalidPageRange {
-            input: format!("Invalid range format: {}", range_str),
+    if let Some(page_count) = page_count
+        && let Some(page) = segments
+            .iter()
+            .flat_map(|segment| segment.get_pages())
+            .find(|&page| page as usize > page_count)
+    {
+        return Err(TuiError::PageOutOfRange { page, page_count });
+    }
+
+    if !allow_overlaps && let Some(overlap) = find_overlap(&segments) {
+        return Err(TuiError::OverlappingSegments {
+            first: segments[overlap.first].label(),
+            second: segments[overlap.second].label(),
+            page: overlap.pages[0],
         });
     }
 
-    let start = parse_single_page(range_parts[0])?;
-    let end = parse_single_page(range_parts[1])?;
+    Ok(())
+}
 
-    if start > end {
-        return Err(TuiError::InvalidPageRange {
-            input: format!("Invalid range: {} > {} (start must be <= end)", start, end),
-        });
+/**
+ * Validate the output directory. An empty directory means the current one, and missing folders
+ * are created when the outputs are written.
+ * @param directory The output directory as typed by the user.
+ * @returns Ok(()) if the directory can be written into, Err(TuiError) otherwise.
+ * @throws TuiError::OutputDirectoryIsFile if the path, or one of its parents, is a file.
+ */
+pub fn validate_output_directory(directory: &str) -> TuiResult<()> {
+    let directory = expand_home(directory.trim());
+    match directory.ancestors().find(|dir| dir.exists()) {
+        Some(existing) if !existing.is_dir() => Err(TuiError::OutputDirectoryIsFile {
+            path: contract_home(existing),
+        }),
+        _ => Ok(()),
     }
+}
+
+/**
+ * Validate an output filename (or split prefix) as typed in its field.
+ * Folders belong in the output directory field, so path separators are rejected.
+ * @param name The filename as typed by the user.
+ * @returns Ok(()) if a file can be created with this name, Err(TuiError) otherwise.
+ * @throws TuiError::InvalidOutputName describing what is wrong with the name.
+ */
+pub fn validate_output_name(name: &str) -> TuiResult<()> {
+    let reason = if name.contains(['/', '\\']) {
+        tr!("use the output directory for folders")
+    } else if name.chars().any(char::is_control) {
+        tr!("control characters are not allowed")
+    } else if name == "." || name == ".." {
+        tr!("not a file name")
+    } else if name.trim().is_empty() || name.eq_ignore_ascii_case(".pdf") {
+        tr!("the name is empty")
+    } else if name.len() > 250 {
+        tr!("the name is too long")
+    } else {
+        return Ok(());
+    };
 
-    Ok((start..=end).collect())
+    Err(TuiError::InvalidOutputName {
+        name: name.to_string(),
+        reason: reason.to_string(),
+    })
 }
 
 /**
- * Parse a single part of a page specification (either a single page or a range).
- * @param part The string part to parse.
- * @returns A vector of page numbers.
- * @throws TuiError if the part is invalid.
+ * List the directories matching a partially typed path, for Tab completion.
+ * Hidden directories are only offered when the typed name starts with a dot.
+ * @param input The partial path (e.g., "exp" or "~/Doc").
+ * @returns The sorted completions, each ending with a slash.
  */
-fn parse_page_part(part: &str) -> TuiResult<Vec<u32>> {
-    let part = part.trim();
+pub fn complete_directory(input: &str) -> Vec<String> {
+    let (parent, partial) = match input.rfind('/') {
+        Some(i) => input.split_at(i + 1),
+        None => ("", input),
+    };
+    let search_dir = if parent.is_empty() {
+        Path::new(".").to_path_buf()
+    } else {
+        expand_home(parent)
+    };
+
+    let Ok(entries) = fs::read_dir(search_dir) else {
+        return Vec::new();
+    };
+
+    let mut completions: Vec<String> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| {
+            name.starts_with(partial) && (!name.starts_with('.') || partial.starts_with('.'))
+        })
+        .map(|name| format!("{}{}/", parent, name))
+        .collect();
+    completions.sort();
+    completions
+}
 
-    if part.is_empty() {
-        return Ok(Vec::new());
+/**
+ * Split a line holding several paths, e.g. pasted from a file manager or `ls`.
+ * Paths are separated by whitespace; a path with spaces is written in single or double quotes,
+ * or with its spaces escaped by a backslash, like in a shell.
+ * A quote inside a name (e.g. "l'été.pdf") is kept, only a token starting with one is quoted.
+ * @param input The typed or pasted text.
+ * @returns The paths, in order.
+ */
+pub fn split_paths(input: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    let mut current = String::new();
+    let mut in_path = false;
+    let mut quote: Option<char> = None;
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => current.push(c),
+            None if (c == '"' || c == '\'') && !in_path => {
+                quote = Some(c);
+                in_path = true;
+            }
+            None if c == '\\' && chars.peek().is_some_and(|next| next.is_whitespace()) => {
+                current.extend(chars.next());
+            }
+            None if c.is_whitespace() => {
+                if in_path {
+                    paths.push(std::mem::take(&mut current));
+                    in_path = false;
+                }
+            }
+            None => {
+                current.push(c);
+                in_path = true;
+            }
+        }
+    }
+    if in_path {
+        paths.push(current);
     }
+    paths
+}
 
-    if part.contains('-') {
-        parse_page_range(part)
+/**
+ * Write a path so that split_paths reads it back whole.
+ * @param path The path.
+ * @returns The path, in quotes when it holds whitespace or starts with a quote.
+ */
+pub fn quote_path(path: &str) -> String {
+    if !path.contains(char::is_whitespace) && !path.starts_with(['"', '\'']) {
+        path.to_string()
+    } else if path.contains('"') {
+        format!("'{}'", path)
     } else {
-        let page = parse_single_page(part)?;
-        Ok(vec![page])
+        format!("\"{}\"", path)
+    }
+}
+
+/**
+ * List the PDF files of a directory, in natural order (e.g., "scan_2.pdf" before "scan_10.pdf").
+ * Hidden files and directories are skipped.
+ * @param directory The directory to search.
+ * @param recursive Whether to also search its subdirectories.
+ * @returns The paths of the PDF files found.
+ * @throws io::Error if a directory cannot be read.
+ */
+pub fn find_pdfs(directory: &str, recursive: bool) -> io::Result<Vec<String>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        let hidden = path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        let path = path.to_string_lossy().into_owned();
+        if hidden {
+            continue;
+        }
+        if Path::new(&path).is_dir() {
+            if recursive {
+                files.extend(find_pdfs(&path, true)?);
+            }
+        } else if has_pdf_extension(&path) {
+            files.push(path);
+        }
     }
+    files.sort_by(|a, b| natural_cmp(a, b));
+    Ok(files)
 }
 
 /**
- * Normalize a vector of pages by sorting and removing duplicates.
- * @param pages The vector of page numbers to normalize.
- * @returns The normalized vector.
- * @throws TuiError if no valid pages are provided.
+ * List the entries of a directory for the file browser, following its sort order and filters.
+ * @param directory The directory to list.
+ * @param browser The browser settings: sort order, hidden entries, PDFs only or all files.
+ * @returns The entries of the directory, folders first.
+ * @throws io::Error if the directory cannot be read.
  */
-fn normalize_pages(mut pages: Vec<u32>) -> TuiResult<Vec<u32>> {
-    if pages.is_empty() {
-        return Err(TuiError::InvalidPageRange {
-            input: "No valid pages specified".to_string(),
+pub fn list_directory(directory: &Path, browser: &BrowserState) -> io::Result<Vec<BrowserEntry>> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        // Follows symbolic links, so a link to a folder is listed as a folder
+        let metadata = fs::metadata(&path).ok();
+        let is_dir = metadata.as_ref().is_some_and(|m| m.is_dir());
+        if (name.starts_with('.') && !browser.show_hidden)
+            || !(is_dir || browser.show_all_files || has_pdf_extension(&name))
+        {
+            continue;
+        }
+        entries.push(BrowserEntry {
+            name,
+            path,
+            is_dir,
+            size: metadata
+                .as_ref()
+                .filter(|m| m.is_file())
+                .map_or(0, |m| m.len()),
+            modified: metadata.and_then(|m| m.modified().ok()),
         });
     }
+    entries.sort_by(|a, b| browser.sort.compare(a, b));
+    Ok(entries)
+}
 
-    pages.sort();
-    pages.dedup();
-    Ok(pages)
+/// Number of items PageUp and PageDown move through in lists and scrolled text.
+pub const PAGE_STEP: usize = 10;
+
+/**
+ * Move the selection of a list with Up/Down, PageUp/PageDown or Home/End.
+ * @param key The key pressed.
+ * @param index The selected index.
+ * @param len The number of items in the list.
+ * @returns The new selected index, or None when the key does not move through lists.
+ */
+pub fn list_navigation(key: KeyCode, index: usize, len: usize) -> Option<usize> {
+    let last = len.saturating_sub(1);
+    match key {
+        KeyCode::Up => Some(index.saturating_sub(1)),
+        KeyCode::Down => Some((index + 1).min(last)),
+        KeyCode::PageUp => Some(index.saturating_sub(PAGE_STEP)),
+        KeyCode::PageDown => Some((index + PAGE_STEP).min(last)),
+        KeyCode::Home => Some(0),
+        KeyCode::End => Some(last),
+        _ => None,
+    }
 }
 
 /**
- * Validate and parse a string representing page ranges (e.g., "1-3,5,7-9").
- * @param pages_str The string representing page ranges.
- * @returns A vector of unique page numbers if valid, Err(TuiError) if invalid.
- * @throws TuiError if the page range string is invalid.
+ * Compare two strings the way people sort file names: runs of digits are compared by their
+ * numeric value and letters regardless of case.
+ * @param a The first string.
+ * @param b The second string.
+ * @returns The ordering of a relative to b.
  */
-pub fn validate_page_ranges(pages_str: &str) -> TuiResult<Vec<u32>> {
-    let mut all_pages = Vec::new();
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut left, mut right) = (a.chars().peekable(), b.chars().peekable());
+    loop {
+        let ordering = match (left.peek().copied(), right.peek().copied()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let (x, y) = (take_number(&mut left), take_number(&mut right));
+                x.len().cmp(&y.len()).then_with(|| x.cmp(&y))
+            }
+            (Some(x), Some(y)) => {
+                left.next();
+                right.next();
+                x.to_lowercase().cmp(y.to_lowercase())
+            }
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
 
-    for part in pages_str.split(',') {
-        let part_pages = parse_page_part(part)?;
-        all_pages.extend(part_pages);
+/// Consume a run of digits, without its leading zeros.
+fn take_number(chars: &mut Peekable<Chars>) -> String {
+    let mut digits = String::new();
+    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+        digits.push(c);
     }
+    digits.trim_start_matches('0').to_string()
+}
 
-    normalize_pages(all_pages)
+/**
+ * Shorten a text to a number of characters, ending it with "…" when it is cut.
+ * @param text The text to shorten.
+ * @param max_chars The longest result, in characters.
+ * @returns The text unchanged when it fits, otherwise its beginning followed by "…".
+ */
+pub fn ellipsize(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    format!("{}…", kept.trim_end())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/**
+ * Format a size in bytes for display (e.g., "68 KB", "1.2 MB").
+ * @param bytes The size in bytes.
+ * @returns The human readable size.
+ */
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
 
-    #[test]
-    fn test_parse_single_page() {
-        assert_eq!(parse_single_page("5").unwrap(), 5);
-        assert_eq!(parse_single_page(" 10 ").unwrap(), 10);
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
 
-        // Error cases
-        assert!(parse_single_page("0").is_err());
-        assert!(parse_single_page("abc").is_err());
-        assert!(parse_single_page("").is_err());
+    if unit == 0 || size >= 10.0 {
+        format!("{:.0} {}", size, UNITS[unit])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
     }
+}
 
-    #[test]
-    fn test_parse_page_range() {
-        assert_eq!(parse_page_range("3-5").unwrap(), vec![3, 4, 5]);
-        assert_eq!(parse_page_range("1-1").unwrap(), vec![1]);
-        assert_eq!(parse_page_range(" 2 - 4 ").unwrap(), vec![2, 3, 4]);
+/**
+ * Format a duration for display, to the second (e.g., "0:07", "12:30", "1:02:03").
+ * @param duration The duration.
+ * @returns The minutes and seconds, preceded by the hours when there are some.
+ */
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+/**
+ * Pages of a document that are not in a selection, e.g. the pages a deletion keeps.
+ * @param pages The selected pages.
+ * @param page_count The number of pages of the document.
+ * @returns The other pages, sorted.
+ */
+pub fn complement_pages(pages: &[u32], page_count: usize) -> Vec<u32> {
+    (1..=page_count as u32)
+        .filter(|page| !pages.contains(page))
+        .collect()
+}
+
+/**
+ * Format a list of pages back into a compact page specification (e.g., "1,3-5").
+ * This is the inverse of validate_page_ranges_in_document, and of validate_page_order_in_document
+ * for pages that are not sorted: only runs of consecutive pages in increasing order are merged.
+ * @param pages The deduplicated page numbers.
+ * @returns The page specification string.
+ */
+pub fn format_page_ranges(pages: &[u32]) -> String {
+    let mut parts = Vec::new();
+    let mut iter = pages.iter().copied().peekable();
+
+    while let Some(start) = iter.next() {
+        let mut end = start;
+        while iter.peek() == Some(&(end + 1)) {
+            end = iter.next().unwrap_or(end);
+        }
+
+        if start == end {
+            parts.push(start.to_string());
+        } else {
+            parts.push(format!("{}-{}", start, end));
+        }
+    }
+
+    parts.join(",")
+}
+
+/**
+ * Fuzzy-match a query against a label, ignoring case.
+ * Labels with a word starting with the query rank first, then labels containing it,
+ * then labels containing its characters in order.
+ * @param query The typed query.
+ * @param label The candidate label.
+ * @returns A score (lower is better), or None if it does not match.
+ */
+pub fn fuzzy_score(query: &str, label: &str) -> Option<usize> {
+    let query = query.trim().to_lowercase();
+    let label = label.to_lowercase();
+
+    if let Some(position) = label.find(&query) {
+        let word_start = label[..position]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !c.is_alphanumeric());
+        return Some(if word_start { position } else { 100 + position });
+    }
+
+    let chars: Vec<char> = label.chars().collect();
+    let mut gaps = 0;
+    let mut position = 0;
+    for wanted in query.chars().filter(|c| !c.is_whitespace()) {
+        let offset = chars[position..].iter().position(|&c| c == wanted)?;
+        gaps += offset;
+        position += offset + 1;
+    }
+    Some(1000 + gaps)
+}
+
+/**
+ * Shorten a footer of " • " separated key hints to fit a width.
+ * Hints are kept in order while they fit, and the "?: Keys" hint is always kept last.
+ * @param text The full footer text.
+ * @param width The number of columns available.
+ * @returns The text unchanged when it fits, otherwise the hints that fit.
+ */
+pub fn fit_hints(text: &str, width: usize) -> String {
+    // Kept when the others do not fit, since the cheat sheet lists every key
+    let keys_hint = tr!("?: Keys");
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
 
-        // Error cases
-        assert!(parse_page_range("5-3").is_err()); // start > end
-        assert!(parse_page_range("1-0").is_err()); // zero page
-        assert!(parse_page_range("a-b").is_err()); // invalid numbers
-        assert!(parse_page_range("1-2-3").is_err()); // too many parts
+    let mut kept: Vec<&str> = Vec::new();
+    let mut used = keys_hint.chars().count();
+    for hint in text.split(" • ").filter(|hint| *hint != keys_hint) {
+        let needed = hint.chars().count() + " • ".chars().count();
+        if used + needed > width {
+            break;
+        }
+        used += needed;
+        kept.push(hint);
     }
+    kept.push(keys_hint);
+    kept.join(" • ")
+}
+
+/**
+ * Format an error with every cause on its own line, for the log viewer.
+ * @param error The error.
+ * @returns The outermost message followed by "caused by:" lines.
+ */
+pub fn error_chain(error: &anyhow::Error) -> String {
+    error
+        .chain()
+        .map(|cause| cause.to_string())
+        .collect::<Vec<_>>()
+        .join(&format!("\n  {} ", tr!("caused by:")))
+}
+
+/**
+ * Describe a queued operation on one line, using file names rather than full paths.
+ * @param operation The operation to describe.
+ * @returns A short description, e.g. "Rotate a.pdf pages 1-3 by 90° → b.pdf".
+ */
+pub fn describe_operation(operation: &Operation) -> String {
+    let name = |path: &str| {
+        Path::new(path)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.to_string())
+    };
+
+    match operation {
+        Operation::Merge { inputs, output } => tr!(
+            "Merge {inputs} → {output}",
+            inputs = inputs
+                .iter()
+                .map(|i| name(i))
+                .collect::<Vec<_>>()
+                .join(" + "),
+            output = name(output)
+        ),
+        Operation::Delete {
+            input,
+            output,
+            pages,
+        } => tr!(
+            "Delete pages {pages} from {input} → {output}",
+            pages = format_page_ranges(pages),
+            input = name(input),
+            output = name(output)
+        ),
+        Operation::Split {
+            input,
+            output_prefix,
+            segments,
+            ..
+        } => tr!(
+            "Split {input} into {segments} → {prefix}_*.pdf",
+            input = name(input),
+            segments = segments,
+            prefix = name(output_prefix)
+        ),
+        Operation::Rotate {
+            input,
+            output,
+            rules,
+        } => tr!(
+            "Rotate {input} {rules} → {output}",
+            input = name(input),
+            rules = rules
+                .iter()
+                .map(|rule| tr!(
+                    "{pages} by {angle}°",
+                    pages = if rule.pages.is_empty() {
+                        tr!("all pages").to_string()
+                    } else {
+                        tr!("pages {pages}", pages = format_page_ranges(&rule.pages))
+                    },
+                    angle = rule.angle
+                ))
+                .collect::<Vec<_>>()
+                .join(", "),
+            output = name(output)
+        ),
+        Operation::Extract {
+            input,
+            output,
+            pages,
+            ..
+        } => tr!(
+            "Extract pages {pages} from {input} → {output}",
+            pages = format_page_ranges(pages),
+            input = name(input),
+            output = name(output)
+        ),
+        Operation::Compress { input, output, .. } => tr!(
+            "Compress {input} → {output}",
+            input = name(input),
+            output = name(output)
+        ),
+        Operation::Watermark {
+            input,
+            output,
+            pages,
+            ..
+        } => tr!(
+            "Watermark {input} {pages} → {output}",
+            input = name(input),
+            pages = if pages.is_empty() {
+                tr!("all pages").to_string()
+            } else {
+                tr!("pages {pages}", pages = format_page_ranges(pages))
+            },
+            output = name(output)
+        ),
+        Operation::Annotate {
+            input,
+            output,
+            note,
+        } => tr!(
+            "Annotate page {page} of {input} → {output}",
+            page = note.page,
+            input = name(input),
+            output = name(output)
+        ),
+    }
+}
+
+/**
+ * Describe a warning about the outputs of an operation, with sizes in human-readable units.
+ * @param warning The warning to describe.
+ * @returns A one-line message.
+ */
+pub fn describe_space_warning(warning: &SpaceWarning) -> String {
+    match warning {
+        SpaceWarning::NotEnoughSpace {
+            directory,
+            needed,
+            available,
+        } => tr!(
+            "Not enough space in {directory}: about {needed} needed, {available} free",
+            directory = contract_home(directory),
+            needed = format_size(*needed),
+            available = format_size(*available)
+        ),
+        SpaceWarning::LargeOutput {
+            output,
+            size,
+            limit,
+        } => tr!(
+            "{output} will be about {size}, over the {limit} warning size",
+            output = Path::new(output)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| output.clone()),
+            size = format_size(*size),
+            limit = format_size(*limit)
+        ),
+    }
+}
+
+/**
+ * Describe the page sizes of a document, one line per size and orientation, the most common
+ * first. The pages of the other sizes are listed, as they are the ones to rotate or check
+ * before printing.
+ * @param pages The pages, in document order.
+ * @returns Lines such as "92 pages A4 portrait (210 × 297 mm)" and
+ * "3 pages A3 landscape (420 × 297 mm): pages 14,57,88".
+ */
+pub fn describe_page_sizes(pages: &[PageInfo]) -> Vec<String> {
+    let groups = page_size_groups(pages);
+    if let [group] = &groups[..] {
+        return vec![match group.pages.len() {
+            1 => tr!("1 page {size}", size = group.page.describe()),
+            count => tr!(
+                "All {count} pages {size}",
+                count = count,
+                size = group.page.describe()
+            ),
+        }];
+    }
+    groups
+        .iter()
+        .enumerate()
+        .map(|(index, group)| {
+            let size = group.page.describe();
+            match (index, &group.pages[..]) {
+                (0, _) => tr!(
+                    "{count} pages {size}",
+                    count = group.pages.len(),
+                    size = size
+                ),
+                (_, [page]) => tr!("1 page {size}: page {page}", size = size, page = page),
+                (_, pages) => tr!(
+                    "{count} pages {size}: pages {pages}",
+                    count = pages.len(),
+                    size = size,
+                    pages = format_page_ranges(pages)
+                ),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::RotationRule;
 
     #[test]
-    fn test_parse_page_part() {
-        assert_eq!(parse_page_part("5").unwrap(), vec![5]);
-        assert_eq!(parse_page_part("3-5").unwrap(), vec![3, 4, 5]);
-        assert_eq!(parse_page_part("").unwrap(), vec![]); // empty part
-        assert_eq!(parse_page_part("  ").unwrap(), vec![]); // whitespace only
+    fn test_describe_page_sizes() {
+        let a4 = PageInfo {
+            width: 595.0,
+            height: 842.0,
+            rotation: 0,
+        };
+        let a3_landscape = PageInfo {
+            width: 1191.0,
+            height: 842.0,
+            rotation: 0,
+        };
+        let letter = PageInfo {
+            width: 612.0,
+            height: 792.0,
+            rotation: 0,
+        };
+
+        let mut pages = vec![a4; 95];
+        for page in [14, 57, 88] {
+            pages[page - 1] = a3_landscape;
+        }
+        pages[40] = letter;
+        assert_eq!(
+            describe_page_sizes(&pages),
+            vec![
+                "91 pages A4 portrait (210 × 297 mm)",
+                "3 pages A3 landscape (420 × 297 mm): pages 14,57,88",
+                "1 page Letter portrait (216 × 279 mm): page 41",
+            ]
+        );
+        assert_eq!(
+            describe_page_sizes(&[a4; 7]),
+            vec!["All 7 pages A4 portrait (210 × 297 mm)"]
+        );
+        assert_eq!(
+            describe_page_sizes(&[letter]),
+            vec!["1 page Letter portrait (216 × 279 mm)"]
+        );
+        assert!(describe_page_sizes(&[]).is_empty());
     }
 
     #[test]
-    fn test_normalize_pages() {
-        assert_eq!(normalize_pages(vec![3, 1, 2, 1]).unwrap(), vec![1, 2, 3]);
-        assert_eq!(normalize_pages(vec![5, 5, 5]).unwrap(), vec![5]);
+    fn test_split_paths() {
+        assert_eq!(
+            split_paths("a.pdf  b.pdf\nc.pdf"),
+            vec!["a.pdf", "b.pdf", "c.pdf"]
+        );
+        assert_eq!(
+            split_paths("'my file.pdf' \"other one.pdf\" my\\ scan.pdf"),
+            vec!["my file.pdf", "other one.pdf", "my scan.pdf"]
+        );
+        assert_eq!(split_paths("l'été.pdf a.pdf"), vec!["l'été.pdf", "a.pdf"]);
+        assert_eq!(split_paths("''"), vec![""]);
+        assert!(split_paths("  \n ").is_empty());
 
-        // Error case
-        assert!(normalize_pages(vec![]).is_err());
+        for path in ["a.pdf", "my file.pdf", "say \"hi\".pdf", "'quoted'.pdf"] {
+            assert_eq!(split_paths(&quote_path(path)), vec![path]);
+        }
     }
 
     #[test]
     fn test_validate_page_ranges() {
         // Valid cases
         assert_eq!(
-            validate_page_ranges("1,3,5-7").unwrap(),
+            validate_page_ranges_in_document("1,3,5-7", None).unwrap(),
             vec![1, 3, 5, 6, 7]
         );
-        assert_eq!(validate_page_ranges("2-4,6").unwrap(), vec![2, 3, 4, 6]);
-        assert_eq!(validate_page_ranges("10").unwrap(), vec![10]);
         assert_eq!(
-            validate_page_ranges("1-3,5,7-9").unwrap(),
+            validate_page_ranges_in_document("2-4,6", None).unwrap(),
+            vec![2, 3, 4, 6]
+        );
+        assert_eq!(
+            validate_page_ranges_in_document("10", None).unwrap(),
+            vec![10]
+        );
+        assert_eq!(
+            validate_page_ranges_in_document("1-3,5,7-9", None).unwrap(),
             vec![1, 2, 3, 5, 7, 8, 9]
         );
         assert_eq!(
-            validate_page_ranges(" 1 , 2 - 3 , 5 ").unwrap(),
+            validate_page_ranges_in_document(" 1 , 2 - 3 , 5 ", None).unwrap(),
             vec![1, 2, 3, 5]
         );
 
         // Invalid cases
-        assert!(validate_page_ranges("3-1").is_err());
-        assert!(validate_page_ranges("a,b,c").is_err());
-        assert!(validate_page_ranges("0,2-4").is_err());
-        assert!(validate_page_ranges("").is_err());
-        assert!(validate_page_ranges(",,,").is_err());
+        assert!(validate_page_ranges_in_document("3-1", None).is_err());
+        assert!(validate_page_ranges_in_document("a,b,c", None).is_err());
+        assert!(validate_page_ranges_in_document("0,2-4", None).is_err());
+        assert!(validate_page_ranges_in_document("", None).is_err());
+        assert!(validate_page_ranges_in_document(",,,", None).is_err());
+    }
+
+    #[test]
+    fn test_format_page_ranges() {
+        assert_eq!(format_page_ranges(&[1, 3, 4, 5, 7]), "1,3-5,7");
+        assert_eq!(format_page_ranges(&[2]), "2");
+        assert_eq!(format_page_ranges(&[]), "");
+
+        // Round trip with the parser
+        let pages = validate_page_ranges_in_document("1-3,5,7-9", None).unwrap();
+        assert_eq!(format_page_ranges(&pages), "1-3,5,7-9");
+        let order = validate_page_order_in_document("5,1-3,9,8", None).unwrap();
+        assert_eq!(format_page_ranges(&order), "5,1-3,9,8");
+    }
+
+    #[test]
+    fn test_validate_page_order() {
+        assert_eq!(
+            validate_page_order_in_document("3,1-2", None).unwrap(),
+            vec![3, 1, 2]
+        );
+        // Repeated pages stay where they first appear
+        assert_eq!(
+            validate_page_order_in_document("2,1-3", None).unwrap(),
+            vec![2, 1, 3]
+        );
+        assert!(validate_page_order_in_document("", None).is_err());
+        assert!(validate_page_order_in_document("3-1", None).is_err());
+
+        assert_eq!(
+            validate_page_order_in_document("7,1", Some(7)).unwrap(),
+            vec![7, 1]
+        );
+        assert!(validate_page_order_in_document("8,1", Some(7)).is_err());
+    }
+
+    #[test]
+    fn test_complement_pages() {
+        let pages = validate_page_ranges_in_document("1,3-5,15", None).unwrap();
+        assert_eq!(format_page_ranges(&complement_pages(&pages, 14)), "2,6-14");
+        assert_eq!(complement_pages(&[1, 2], 2), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn test_last_page_and_steps() {
+        assert_eq!(
+            validate_page_ranges_in_document("1-9:2", None).unwrap(),
+            vec![1, 3, 5, 7, 9]
+        );
+        assert_eq!(
+            validate_page_ranges_in_document("2-last:3", Some(10)).unwrap(),
+            vec![2, 5, 8]
+        );
+        assert_eq!(
+            validate_page_order_in_document("last-1-last,1-5:4", Some(6)).unwrap(),
+            vec![5, 6, 1]
+        );
+        // Every other page deleted keeps the odd ones
+        assert_eq!(
+            validate_delete_pages("!1-last:2", Some(6)).unwrap(),
+            vec![2, 4, 6]
+        );
+        assert!(validate_page_ranges_in_document("3:2", None).is_err());
+        assert!(validate_page_ranges_in_document("1-9:0", None).is_err());
+        assert!(validate_page_ranges_in_document("1-9:x", None).is_err());
+    }
+
+    #[test]
+    fn test_page_complement() {
+        assert_eq!(
+            validate_page_ranges_in_document("!2-5", Some(7)).unwrap(),
+            vec![1, 6, 7]
+        );
+        assert_eq!(
+            validate_page_order_in_document(" ^ 7,1", Some(7)).unwrap(),
+            vec![2, 3, 4, 5, 6]
+        );
+        assert_eq!(validate_delete_pages("!1", Some(3)).unwrap(), vec![2, 3]);
+
+        assert!(matches!(
+            validate_page_ranges_in_document("!2-5", None),
+            Err(TuiError::PageCountNeeded { .. })
+        ));
+        assert!(matches!(
+            validate_page_ranges_in_document("!2-5", None),
+            Err(TuiError::PageCountNeeded { .. })
+        ));
+        assert!(matches!(
+            validate_page_ranges_in_document("!2-9", Some(7)),
+            Err(TuiError::PageOutOfRange { page: 8, .. })
+        ));
+        assert!(validate_page_ranges_in_document("!1-7", Some(7)).is_err());
+        assert!(validate_page_ranges_in_document("!", Some(7)).is_err());
+        assert!(validate_page_ranges_in_document("2,!3", Some(7)).is_err());
+    }
+
+    #[test]
+    fn test_validate_page_ranges_in_document() {
+        assert_eq!(
+            validate_page_ranges_in_document("1,3-5", Some(14)).unwrap(),
+            vec![1, 3, 4, 5]
+        );
+        assert!(validate_page_ranges_in_document("1,15", None).is_ok());
+
+        let err = validate_page_ranges_in_document("1,15", Some(14)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Page 15 is out of range (document has 14 pages)"
+        );
+    }
+
+    #[test]
+    fn test_page_spec_errors_tell_the_column() {
+        let err = validate_page_ranges_in_document("1-3,5-7-9", None).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid page range: unexpected '-' at column 8"
+        );
+        let err = preview_split_outputs("intro:1-3,body:", true, "out").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid page range: missing page number at column 16"
+        );
+        // Listing more pages than any document has is refused before the pages are listed
+        let spec = ["1-100000"; 2].join(",");
+        assert!(validate_page_order_in_document(&spec, None).is_err());
+        assert!(validate_split_in_document(&spec, false, None, true).is_err());
+    }
+
+    #[test]
+    fn test_page_specs_survive_random_input() {
+        // Keystrokes the page and segment fields can receive, including the pieces of valid
+        // specifications so that deeper paths are reached
+        const PIECES: [&str; 16] = [
+            "1", "7", "0", "9999999", "-", ",", ":", "!", "^", "(", ")", " ", "last", "intro",
+            "\u{1b}", "é",
+        ];
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        for _ in 0..5000 {
+            let length = next() % 12;
+            let spec: String = (0..length)
+                .map(|_| PIECES[(next() % PIECES.len() as u64) as usize])
+                .collect();
+            for page_count in [None, Some(7)] {
+                if let Ok(pages) = validate_page_ranges_in_document(&spec, page_count) {
+                    assert!(
+                        !pages.is_empty() && pages.iter().all(|&page| page > 0),
+                        "{spec}"
+                    );
+                }
+                if let Ok(pages) = validate_page_order_in_document(&spec, page_count) {
+                    assert!(!pages.is_empty(), "{spec}");
+                }
+                let _ = validate_delete_pages(&spec, page_count);
+                for named in [false, true] {
+                    let _ = validate_split_in_document(&spec, named, page_count, false);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_validate_delete_pages() {
+        assert!(validate_delete_pages("1-3", Some(4)).is_ok());
+        assert!(validate_delete_pages("1-4", Some(4)).is_err());
+        assert!(validate_delete_pages("5", Some(4)).is_err());
+        assert!(validate_delete_pages("1-4", None).is_ok());
+    }
+
+    #[test]
+    fn test_preview_split_outputs() {
+        let preview = preview_split_outputs("1-3,5", false, "out").unwrap();
+        assert_eq!(preview.len(), 2);
+        assert_eq!(preview[0].0, "out_001.pdf");
+        assert_eq!(preview[0].1.get_pages().len(), 3);
+        assert_eq!(preview[1].0, "out_002.pdf");
+
+        let named = preview_split_outputs("intro:1-2", true, "book").unwrap();
+        assert_eq!(named[0].0, "book_intro.pdf");
+
+        assert!(preview_split_outputs("1-x", false, "out").is_err());
+    }
+
+    #[test]
+    fn test_validate_split_in_document() {
+        assert!(validate_split_in_document("1-3,4-10", false, Some(10), false).is_ok());
+        // Without the page count, only the segments themselves are checked
+        assert!(validate_split_in_document("1-30", false, None, false).is_ok());
+        assert!(matches!(
+            validate_split_in_document("1-3,4-12", false, Some(10), false),
+            Err(TuiError::PageOutOfRange {
+                page: 11,
+                page_count: 10
+            })
+        ));
+        assert!(matches!(
+            validate_split_in_document("intro:1-3,body:3-10", true, Some(10), false),
+            Err(TuiError::OverlappingSegments { first, second, page: 3 })
+                if first == "intro:1-3" && second == "body:3-10"
+        ));
+        assert!(validate_split_in_document("intro:1-3,body:3-10", true, Some(10), true).is_ok());
+        // Allowing overlaps does not let a segment replace the file of another
+        assert!(matches!(
+            validate_split_in_document("a:1,a:2", true, Some(10), true),
+            Err(TuiError::SharedOutputFile { first, second, file })
+                if first == "a:1" && second == "a:2" && file == "_a.pdf"
+        ));
+        assert!(matches!(
+            validate_split_in_document("002:1,2", true, Some(10), true),
+            Err(TuiError::SharedOutputFile { .. })
+        ));
+        assert!(validate_split_in_document("1-3,x", false, Some(10), true).is_err());
+    }
+
+    #[test]
+    fn test_validate_split_segments() {
+        assert!(validate_split_segments("1-3,5", false).is_ok());
+        // Named mode also takes unnamed segments
+        assert!(validate_split_segments("intro:1-2, 3-5", true).is_ok());
+        assert!(matches!(
+            validate_split_segments("1-2, intro:3-5", false),
+            Err(TuiError::NamedSegmentInPageMode { segment }) if segment == "intro:3-5"
+        ));
+        assert!(matches!(
+            validate_split_segments(" :1-2", true),
+            Err(TuiError::UnnamedSegment { .. })
+        ));
+        assert!(matches!(
+            validate_split_segments("a/b:1-2", true),
+            Err(TuiError::InvalidOutputName { .. })
+        ));
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(69_286), "68 KB");
+        assert_eq!(format_size(1_258_291), "1.2 MB");
+        assert_eq!(format_size(5 * 1024 * 1024 * 1024), "5.0 GB");
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_millis(7_900)), "0:07");
+        assert_eq!(format_duration(Duration::from_secs(750)), "12:30");
+        assert_eq!(format_duration(Duration::from_secs(3723)), "1:02:03");
+    }
+
+    #[test]
+    fn test_validate_output_directory() {
+        assert!(validate_output_directory("").is_ok());
+        assert!(validate_output_directory("tests/tests_pdf").is_ok());
+        assert!(validate_output_directory("nonexistent_dir/2024").is_ok());
+        assert!(matches!(
+            validate_output_directory("tests/tests_pdf/a.pdf"),
+            Err(TuiError::OutputDirectoryIsFile { .. })
+        ));
+        assert!(validate_output_directory("tests/tests_pdf/a.pdf/out").is_err());
+    }
+
+    #[test]
+    fn test_validate_output_name() {
+        assert!(validate_output_name("merged.pdf").is_ok());
+        assert!(validate_output_name("report 2024").is_ok());
+        for name in ["out/merged.pdf", "a\\b.pdf", "..", ".pdf", "  ", "a\tb"] {
+            assert!(
+                matches!(
+                    validate_output_name(name),
+                    Err(TuiError::InvalidOutputName { .. })
+                ),
+                "{:?}",
+                name
+            );
+        }
+        assert_eq!(
+            validate_output_name("out/x.pdf").unwrap_err().to_string(),
+            "Invalid output name 'out/x.pdf': use the output directory for folders"
+        );
+    }
+
+    #[test]
+    fn test_complete_directory() {
+        assert_eq!(complete_directory("tests/tests_"), vec!["tests/tests_pdf/"]);
+        assert!(complete_directory("te").contains(&"tests/".to_string()));
+        assert!(complete_directory("tests/tests_pdf/a").is_empty());
+        assert!(complete_directory("nonexistent/").is_empty());
+    }
+
+    #[test]
+    fn test_ellipsize() {
+        assert_eq!(ellipsize("Invoice", 10), "Invoice");
+        assert_eq!(ellipsize("Quarterly report 2024", 10), "Quarterly…");
+        assert_eq!(ellipsize("Relevé de compte", 7), "Relevé…");
+    }
+
+    #[test]
+    fn test_list_navigation() {
+        assert_eq!(list_navigation(KeyCode::Down, 3, 300), Some(4));
+        assert_eq!(list_navigation(KeyCode::Up, 0, 300), Some(0));
+        assert_eq!(list_navigation(KeyCode::PageDown, 3, 300), Some(13));
+        assert_eq!(list_navigation(KeyCode::PageDown, 295, 300), Some(299));
+        assert_eq!(list_navigation(KeyCode::PageUp, 3, 300), Some(0));
+        assert_eq!(list_navigation(KeyCode::End, 3, 300), Some(299));
+        assert_eq!(list_navigation(KeyCode::Home, 150, 300), Some(0));
+        assert_eq!(list_navigation(KeyCode::Down, 0, 0), Some(0));
+        assert_eq!(list_navigation(KeyCode::Enter, 3, 300), None);
+    }
+
+    #[test]
+    fn test_natural_cmp() {
+        let mut names = vec![
+            "scan_10.pdf",
+            "Scan_2.pdf",
+            "scan_1.pdf",
+            "scan_02b.pdf",
+            "a.pdf",
+        ];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(
+            names,
+            vec![
+                "a.pdf",
+                "scan_1.pdf",
+                "Scan_2.pdf",
+                "scan_02b.pdf",
+                "scan_10.pdf"
+            ]
+        );
+        assert_eq!(natural_cmp("page007", "page7"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_find_pdfs() {
+        assert_eq!(
+            find_pdfs("tests/tests_pdf", false).unwrap(),
+            vec![
+                "tests/tests_pdf/a.pdf",
+                "tests/tests_pdf/b.pdf",
+                "tests/tests_pdf/c.pdf"
+            ]
+        );
+        assert!(find_pdfs("tests", false).unwrap().is_empty());
+        assert_eq!(find_pdfs("tests", true).unwrap().len(), 3);
+        assert!(find_pdfs("nonexistent", false).is_err());
+    }
+
+    #[test]
+    fn test_error_chain() {
+        let error = anyhow::anyhow!("No such file").context("Failed to load PDF 'a.pdf'");
+        assert_eq!(
+            error_chain(&error),
+            "Failed to load PDF 'a.pdf'\n  caused by: No such file"
+        );
+    }
+
+    #[test]
+    fn test_describe_operation() {
+        let rotate = Operation::Rotate {
+            input: "/tmp/in/a.pdf".to_string(),
+            output: "/tmp/out/b.pdf".to_string(),
+            rules: vec![
+                RotationRule {
+                    pages: vec![1, 2, 3, 7],
+                    angle: 90,
+                },
+                RotationRule {
+                    pages: Vec::new(),
+                    angle: 180,
+                },
+            ],
+        };
+        assert_eq!(
+            describe_operation(&rotate),
+            "Rotate a.pdf pages 1-3,7 by 90°, all pages by 180° → b.pdf"
+        );
+
+        let merge = Operation::Merge {
+            inputs: vec!["a.pdf".to_string(), "b.pdf".to_string()],
+            output: "ab.pdf".to_string(),
+        };
+        assert_eq!(describe_operation(&merge), "Merge a.pdf + b.pdf → ab.pdf");
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "Start merge"), Some(0));
+        assert!(fuzzy_score("smg", "Start merge").is_some());
+        assert!(fuzzy_score("xyz", "Start merge").is_none());
+        assert!(fuzzy_score("MERGE", "Start merge").is_some());
+
+        // Word prefixes rank before substrings, which rank before scattered characters
+        let word = fuzzy_score("sp", "Start: Split pages").unwrap();
+        let substring = fuzzy_score("pli", "Start: Split pages").unwrap();
+        let scattered = fuzzy_score("sp", "Show operation queue").unwrap();
+        assert!(word < substring);
+        assert!(substring < scattered);
+    }
+
+    #[test]
+    fn test_fit_hints() {
+        let footer = "Enter: Select • Tab: Next field • Esc: Back • ?: Keys";
+        assert_eq!(fit_hints(footer, 80), footer);
+        assert_eq!(fit_hints(footer, 30), "Enter: Select • ?: Keys");
+        assert_eq!(fit_hints(footer, 10), "?: Keys");
+        assert_eq!(
+            fit_hints("Enter: Select • Esc: Back", 24),
+            "Enter: Select • ?: Keys"
+        );
     }
 }